@@ -14,9 +14,12 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
+use snarkos_node_store::StorageLayout;
+
 use anyhow::{bail, Result};
 use clap::Parser;
 use colored::Colorize;
+use std::path::PathBuf;
 
 /// Cleans the snarkOS node storage.
 #[derive(Debug, Parser)]
@@ -27,20 +30,31 @@ pub struct Clean {
     /// Enables development mode, specify the unique ID of the local node to clean.
     #[clap(long)]
     pub dev: Option<u16>,
+    /// Specify the directory of the ledger database, if it was placed elsewhere.
+    #[clap(long = "ledger-dir")]
+    pub ledger_dir: Option<PathBuf>,
+    /// Specify the directory of the database write-ahead log, if it was placed elsewhere.
+    #[clap(long = "wal-dir")]
+    pub wal_dir: Option<PathBuf>,
 }
 
 impl Clean {
     /// Cleans the snarkOS node storage.
     pub fn parse(self) -> Result<String> {
+        // Construct the storage layout.
+        let layout = StorageLayout { ledger_dir: self.ledger_dir, wal_dir: self.wal_dir, state_dir: None };
+
+        // Remove the write-ahead log from storage, if it was placed apart from the ledger.
+        let mut output = String::new();
+        if layout.wal_dir.is_some() {
+            output = Self::remove_ledger(layout.wal_dir(self.network, self.dev))? + "\n";
+        }
         // Remove the specified ledger from storage.
-        Self::remove_ledger(self.network, self.dev)
+        Ok(output + &Self::remove_ledger(layout.ledger_dir(self.network, self.dev))?)
     }
 
     /// Removes the specified ledger from storage.
-    fn remove_ledger(network: u16, dev: Option<u16>) -> Result<String> {
-        // Construct the path to the ledger in storage.
-        let path = aleo_std::aleo_ledger_dir(network, dev);
-
+    fn remove_ledger(path: PathBuf) -> Result<String> {
         // Prepare the path string.
         let path_string = format!("(in \"{}\")", path.display()).dimmed();
 
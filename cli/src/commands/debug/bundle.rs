@@ -0,0 +1,237 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkos_node_store::StorageLayout;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use flate2::{write::GzEncoder, Compression};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The prefixes of secrets that are redacted from the free-form text in the bundle (i.e. the logs).
+const SECRET_PREFIXES: [&str; 3] = ["APrivateKey1", "AViewKey1", "Bearer "];
+
+/// The environment variables that are included in the bundle. Any other variable is left out.
+const ENV_ALLOW_LIST: [&str; 6] =
+    ["RUST_LOG", "RUST_BACKTRACE", "RUST_MIN_STACK", "RAYON_NUM_THREADS", "TOKIO_WORKER_THREADS", "MALLOC_CONF"];
+
+/// Gathers the version info, config, recent logs, peer table, sync status, and storage stats
+/// of the node into a single tarball, with any secrets in the logs redacted.
+#[derive(Debug, Parser)]
+pub struct Bundle {
+    /// Specify the REST endpoint of the running node
+    #[clap(default_value = "http://127.0.0.1:3030", long = "endpoint")]
+    pub endpoint: String,
+    /// Specify the path to the logfile of the node
+    #[clap(default_value_os_t = std::env::temp_dir().join("snarkos.log"), long = "logfile")]
+    pub logfile: PathBuf,
+    /// Specify the number of recent log lines to include
+    #[clap(default_value = "1000", long = "lines")]
+    pub lines: usize,
+    /// Specify the network of the node storage
+    #[clap(default_value = "3", long = "network")]
+    pub network: u16,
+    /// Enables development mode, specify the unique ID of the local node
+    #[clap(long)]
+    pub dev: Option<u16>,
+    /// Specify the directory of the ledger database, if it was placed elsewhere
+    #[clap(long = "ledger-dir")]
+    pub ledger_dir: Option<PathBuf>,
+    /// Specify the directory of the database write-ahead log, if it was placed elsewhere
+    #[clap(long = "wal-dir")]
+    pub wal_dir: Option<PathBuf>,
+    /// Specify the directory of the node state files, if it was placed elsewhere
+    #[clap(long = "state-dir")]
+    pub state_dir: Option<PathBuf>,
+    /// Specify the path of the tarball to write
+    #[clap(long = "output")]
+    pub output: Option<PathBuf>,
+}
+
+impl Bundle {
+    /// Gathers the diagnostic bundle, and returns the path of the tarball.
+    pub fn parse(self) -> Result<String> {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let output = self.output.clone().unwrap_or_else(|| PathBuf::from(format!("snarkos-debug-{timestamp}.tar.gz")));
+
+        // Gather the diagnostics.
+        let files = [
+            ("version.txt", self.version()),
+            ("config.txt", self.config()),
+            ("logs.txt", self.logs()),
+            ("peers.json", self.query(&["peers/all/metrics", "peers/messages/stats"])),
+            ("sync.json", self.query(&["latest/height", "latest/hash", "sync/forks/10", "node/address"])),
+            ("storage.txt", self.storage()),
+        ];
+
+        // Write the tarball.
+        let mut tarball = tar::Builder::new(GzEncoder::new(fs::File::create(&output)?, Compression::default()));
+        for (name, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(timestamp);
+            header.set_cksum();
+            tarball.append_data(&mut header, name, contents.as_bytes())?;
+        }
+        tarball.into_inner()?.finish()?;
+
+        Ok(format!("✅ Wrote the diagnostic bundle to '{}'", output.display().to_string().bold()))
+    }
+
+    /// Returns the version info.
+    fn version(&self) -> String {
+        format!(
+            "snarkOS {}\nos: {}\narch: {}\n",
+            env!("CARGO_PKG_VERSION"),
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        )
+    }
+
+    /// Returns the bundle config, and the allowed environment variables.
+    fn config(&self) -> String {
+        let mut config = format!("{self:#?}\n\n");
+        for key in ENV_ALLOW_LIST {
+            if let Ok(value) = std::env::var(key) {
+                config.push_str(&format!("{key}={value}\n"));
+            }
+        }
+        config
+    }
+
+    /// Returns the recent lines of the logfile, with any secrets redacted.
+    fn logs(&self) -> String {
+        match fs::read_to_string(&self.logfile) {
+            Ok(logs) => {
+                let lines = logs.lines().collect::<Vec<_>>();
+                redact(&lines[lines.len().saturating_sub(self.lines)..].join("\n"))
+            }
+            Err(error) => format!("Unable to read '{}' - {error}", self.logfile.display()),
+        }
+    }
+
+    /// Returns the responses of the given REST routes of the running node.
+    fn query(&self, routes: &[&str]) -> String {
+        let responses = routes
+            .iter()
+            .map(|route| {
+                let url = format!("{}/testnet3/{route}", self.endpoint);
+                let response = match ureq::get(&url).call().map(|response| response.into_json::<serde_json::Value>()) {
+                    Ok(Ok(response)) => response,
+                    Ok(Err(error)) => serde_json::Value::String(format!("Invalid response - {error}")),
+                    Err(error) => serde_json::Value::String(format!("Unavailable - {error}")),
+                };
+                (route.to_string(), response)
+            })
+            .collect::<serde_json::Map<_, _>>();
+        serde_json::to_string_pretty(&responses).unwrap_or_default()
+    }
+
+    /// Returns the size and number of files of each component of the node storage.
+    fn storage(&self) -> String {
+        let layout = StorageLayout {
+            ledger_dir: self.ledger_dir.clone(),
+            wal_dir: self.wal_dir.clone(),
+            state_dir: self.state_dir.clone(),
+        };
+        let components = [
+            ("ledger", layout.ledger_dir(self.network, self.dev)),
+            ("wal", layout.wal_dir(self.network, self.dev)),
+            ("state", layout.state_dir(self.network, self.dev)),
+        ];
+
+        let mut storage = String::new();
+        for (index, (component, path)) in components.iter().enumerate() {
+            storage.push_str(&format!("{component}: {}\n", path.display()));
+            // Skip the components that live in the directory of a previous component.
+            if components[..index].iter().all(|(_, previous)| previous != path) {
+                match directory_size(path) {
+                    Ok((size, num_files)) => storage.push_str(&format!("size: {size} bytes\nfiles: {num_files}\n")),
+                    Err(error) => storage.push_str(&format!("Unable to read '{}' - {error}\n", path.display())),
+                }
+            }
+            storage.push('\n');
+        }
+        storage
+    }
+}
+
+/// Returns the total size in bytes and the number of files in the given directory.
+fn directory_size(path: &Path) -> Result<(u64, u64)> {
+    let (mut size, mut num_files) = (0, 0);
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            let (dir_size, dir_files) = directory_size(&entry.path())?;
+            size += dir_size;
+            num_files += dir_files;
+        } else {
+            size += metadata.len();
+            num_files += 1;
+        }
+    }
+    Ok((size, num_files))
+}
+
+/// Redacts any secrets in the given contents.
+pub(crate) fn redact(contents: &str) -> String {
+    let mut redacted = contents.to_string();
+    for prefix in SECRET_PREFIXES {
+        let mut start = 0;
+        while let Some(index) = redacted[start..].find(prefix) {
+            let secret_start = start + index + prefix.len();
+            // The secret continues until the next character that is not alphanumeric, '-', '_', or '.'.
+            let secret_len = redacted[secret_start..]
+                .find(|c: char| !c.is_ascii_alphanumeric() && !"-_.".contains(c))
+                .unwrap_or(redacted.len() - secret_start);
+            redacted.replace_range(secret_start..secret_start + secret_len, "[REDACTED]");
+            start = secret_start + "[REDACTED]".len();
+        }
+    }
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        let contents =
+            "key APrivateKey1zkp8CZNn3yeCseEtxuVPbDCwSyhGW6yZKUYKfgXmcpoGPWH (view AViewKey1abc) Bearer ey.J9";
+        assert_eq!(redact(contents), "key APrivateKey1[REDACTED] (view AViewKey1[REDACTED]) Bearer [REDACTED]");
+        assert_eq!(redact("no secrets here"), "no secrets here");
+    }
+
+    #[test]
+    fn test_config_env_allow_list() {
+        std::env::set_var("SNARKOS_TEST_BUNDLE_SECRET", "secret");
+        std::env::set_var("RUST_BACKTRACE", "1");
+
+        // Ensure only the allowed environment variables are included.
+        let bundle = Bundle::try_parse_from(["bundle"].iter()).unwrap();
+        let config = bundle.config();
+        assert!(!config.contains("SNARKOS_TEST_BUNDLE_SECRET"));
+        assert!(config.contains("RUST_BACKTRACE=1"));
+    }
+}
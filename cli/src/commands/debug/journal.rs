@@ -0,0 +1,229 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkos_node_consensus::journal_segments;
+use snarkos_node_store::StorageLayout;
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use colored::Colorize;
+use serde_json::Value;
+use std::{collections::BTreeMap, fmt::Write, fs, path::PathBuf};
+
+/// The maximum number of rejected blocks and height gaps listed in the summary.
+const MAX_LISTED: usize = 20;
+
+/// Inspects the consensus journal of a node offline, or replays it into a summary.
+#[derive(Debug, Parser)]
+pub struct Journal {
+    /// Specify the network of the node storage
+    #[clap(default_value = "3", long = "network")]
+    pub network: u16,
+    /// Enables development mode, specify the unique ID of the local node
+    #[clap(long)]
+    pub dev: Option<u16>,
+    /// Specify the path to the journal directory (defaults to 'journal' in the state directory)
+    #[clap(long = "dir")]
+    pub dir: Option<PathBuf>,
+    /// Specify the kind of events to show (e.g. 'blockRejected')
+    #[clap(long = "kind")]
+    pub kind: Option<String>,
+    /// Specify the lowest block height of the events to show
+    #[clap(long = "from-height")]
+    pub from_height: Option<u64>,
+    /// Specify the highest block height of the events to show
+    #[clap(long = "to-height")]
+    pub to_height: Option<u64>,
+    /// If the flag is set, the events are replayed into a summary, instead of printed
+    #[clap(long)]
+    pub summary: bool,
+}
+
+impl Journal {
+    /// Reads the journal segments, and returns the matching events or their summary.
+    pub fn parse(self) -> Result<String> {
+        let directory = match &self.dir {
+            Some(dir) => dir.clone(),
+            None => StorageLayout::default().state_dir(self.network, self.dev).join("journal"),
+        };
+        let segments = match journal_segments(&directory) {
+            Ok(segments) if !segments.is_empty() => segments,
+            Ok(_) => bail!("There are no journal segments in '{}'", directory.display()),
+            Err(error) => bail!("Unable to read the journal at '{}' - {error}", directory.display()),
+        };
+
+        let mut output = String::new();
+        let mut replay = Replay::default();
+        for (_, path) in segments {
+            for line in fs::read_to_string(&path)?.lines() {
+                // A crash may leave a partial line at the end of a segment.
+                let event = match serde_json::from_str::<Value>(line) {
+                    Ok(event) => event,
+                    Err(_) => {
+                        replay.num_malformed += 1;
+                        continue;
+                    }
+                };
+                if !self.matches(&event) {
+                    continue;
+                }
+                if self.summary {
+                    replay.apply(&event);
+                } else {
+                    writeln!(output, "{line}")?;
+                }
+            }
+        }
+
+        if self.summary {
+            Ok(replay.render())
+        } else {
+            Ok(output.trim_end().to_string())
+        }
+    }
+
+    /// Returns `true` if the given event matches the filters. Events without a height are excluded by a height filter.
+    fn matches(&self, event: &Value) -> bool {
+        if let Some(kind) = &self.kind {
+            if event["kind"].as_str() != Some(kind.as_str()) {
+                return false;
+            }
+        }
+        if self.from_height.is_some() || self.to_height.is_some() {
+            let height = match event["height"].as_u64() {
+                Some(height) => height,
+                None => return false,
+            };
+            if self.from_height.map_or(false, |from| height < from) || self.to_height.map_or(false, |to| height > to) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// The summary of a replayed journal.
+#[derive(Debug, Default)]
+struct Replay {
+    /// The number of events of each kind.
+    counts: BTreeMap<String, usize>,
+    /// The timestamps of the first and last events.
+    timestamps: Option<(i64, i64)>,
+    /// The height of the last accepted block.
+    last_accepted: Option<u64>,
+    /// The ranges of heights skipped between accepted blocks.
+    gaps: Vec<(u64, u64)>,
+    /// The height, hash, and reason of each rejected block.
+    rejected: Vec<(u64, String, String)>,
+    /// The number of lines that are not valid events.
+    num_malformed: usize,
+}
+
+impl Replay {
+    /// Applies the given event to the summary.
+    fn apply(&mut self, event: &Value) {
+        let kind = event["kind"].as_str().unwrap_or("unknown");
+        *self.counts.entry(kind.to_string()).or_default() += 1;
+
+        if let Some(timestamp) = event["timestamp"].as_i64() {
+            let (first, _) = self.timestamps.unwrap_or((timestamp, timestamp));
+            self.timestamps = Some((first, timestamp));
+        }
+
+        let height = event["height"].as_u64().unwrap_or_default();
+        let hash = event["hash"].as_str().unwrap_or_default().to_string();
+        match kind {
+            "blockAccepted" => {
+                if let Some(last) = self.last_accepted {
+                    if height > last + 1 {
+                        self.gaps.push((last + 1, height - 1));
+                    }
+                }
+                self.last_accepted = Some(height);
+            }
+            "blockRejected" => {
+                self.rejected.push((height, hash, event["reason"].as_str().unwrap_or_default().to_string()));
+            }
+            _ => (),
+        }
+    }
+
+    /// Renders the summary.
+    fn render(&self) -> String {
+        let mut output = String::new();
+        let _ = writeln!(output, "{}", "Events".bold());
+        for (kind, count) in &self.counts {
+            let _ = writeln!(output, "  {kind}: {count}");
+        }
+        if let Some((first, last)) = self.timestamps {
+            let _ = writeln!(output, "  span: {first} to {last} ({} seconds)", (last - first) / 1000);
+        }
+        if self.num_malformed > 0 {
+            let _ = writeln!(output, "  malformed: {}", self.num_malformed);
+        }
+        if let Some(height) = self.last_accepted {
+            let _ = writeln!(output, "\n{} {height}", "Last accepted block:".bold());
+        }
+        if !self.gaps.is_empty() {
+            let _ = writeln!(output, "\n{} ({})", "Gaps between accepted blocks".bold(), self.gaps.len());
+            for (start, end) in self.gaps.iter().rev().take(MAX_LISTED) {
+                let _ = writeln!(output, "  {start}..={end}");
+            }
+        }
+        if !self.rejected.is_empty() {
+            let _ = writeln!(output, "\n{} ({})", "Rejected blocks".bold(), self.rejected.len());
+            for (height, hash, reason) in self.rejected.iter().rev().take(MAX_LISTED) {
+                let _ = writeln!(output, "  {height} {hash} - {reason}");
+            }
+        }
+        output.trim_end().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay() {
+        let mut replay = Replay::default();
+        let events = [
+            r#"{"timestamp":1000,"kind":"blockAccepted","height":1,"hash":"ab1a","round":1}"#,
+            r#"{"timestamp":2000,"kind":"blockRejected","height":2,"hash":"ab1b","reason":"invalid"}"#,
+            r#"{"timestamp":3000,"kind":"blockAccepted","height":2,"hash":"ab1c","round":2}"#,
+            r#"{"timestamp":9000,"kind":"blockAccepted","height":5,"hash":"ab1d","round":5}"#,
+        ];
+        for event in events {
+            replay.apply(&serde_json::from_str(event).unwrap());
+        }
+        assert_eq!(replay.counts["blockAccepted"], 3);
+        assert_eq!(replay.counts["blockRejected"], 1);
+        assert_eq!(replay.timestamps, Some((1000, 9000)));
+        assert_eq!(replay.last_accepted, Some(5));
+        assert_eq!(replay.gaps, vec![(3, 4)]);
+        assert_eq!(replay.rejected, vec![(2, "ab1b".to_string(), "invalid".to_string())]);
+    }
+
+    #[test]
+    fn test_matches() {
+        let command = Journal::parse_from(["journal", "--kind", "blockAccepted", "--from-height", "2"]);
+        let event = |line: &str| serde_json::from_str::<Value>(line).unwrap();
+        assert!(command.matches(&event(r#"{"kind":"blockAccepted","height":2}"#)));
+        assert!(!command.matches(&event(r#"{"kind":"blockAccepted","height":1}"#)));
+        assert!(!command.matches(&event(r#"{"kind":"blockRejected","height":3}"#)));
+        assert!(!command.matches(&event(r#"{"kind":"committeeChanged"}"#)));
+    }
+}
@@ -0,0 +1,44 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+mod bundle;
+pub use bundle::*;
+
+mod journal;
+pub use journal::*;
+
+use anyhow::Result;
+use clap::Parser;
+
+/// Commands to diagnose the node.
+#[derive(Debug, Parser)]
+pub enum Diagnostics {
+    /// Gather a diagnostic bundle to attach to bug reports.
+    #[clap(name = "bundle")]
+    Bundle(Bundle),
+    /// Inspect or replay the consensus journal of a node.
+    #[clap(name = "journal")]
+    Journal(Journal),
+}
+
+impl Diagnostics {
+    pub fn parse(self) -> Result<String> {
+        match self {
+            Self::Bundle(command) => command.parse(),
+            Self::Journal(command) => command.parse(),
+        }
+    }
+}
@@ -23,12 +23,18 @@ pub use deploy::*;
 mod execute;
 pub use execute::*;
 
+mod replay;
+pub use replay::*;
+
 mod scan;
 pub use scan::*;
 
 mod transfer;
 pub use transfer::*;
 
+mod vectors;
+pub use vectors::*;
+
 use snarkvm::{
     file::{AleoFile, Manifest},
     package::Package,
@@ -51,10 +57,14 @@ pub enum Developer {
     Deploy(Deploy),
     /// Execute a program function.
     Execute(Execute),
+    /// Replay blocks and print a digest of the resulting state.
+    Replay(Replay),
     /// Scan the node for records.
     Scan(Scan),
     /// Transfer credits.
     Transfer(Transfer),
+    /// Generate or check the test vectors of the transaction circuits.
+    Vectors(Vectors),
 }
 
 impl Developer {
@@ -63,8 +73,10 @@ impl Developer {
             Self::Decrypt(decrypt) => decrypt.parse(),
             Self::Deploy(deploy) => deploy.parse(),
             Self::Execute(execute) => execute.parse(),
+            Self::Replay(replay) => replay.parse(),
             Self::Scan(scan) => scan.parse(),
             Self::Transfer(transfer) => transfer.parse(),
+            Self::Vectors(vectors) => vectors.parse(),
         }
     }
 
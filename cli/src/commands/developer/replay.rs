@@ -0,0 +1,82 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::CurrentNetwork;
+
+use snarkos_node_cdn::load_blocks;
+use snarkos_node_ledger::{Ledger, StateDigest};
+use snarkvm::prelude::{Block, ConsensusMemory, FromBytes, Network};
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use parking_lot::Mutex;
+use serde_json::json;
+use std::sync::Arc;
+use tokio::runtime;
+
+/// Replays blocks from the CDN, and prints a digest of the resulting state.
+///
+/// The digest is expected to be identical across platforms; a mismatch between two
+/// machines replaying the same range indicates nondeterministic execution.
+#[derive(Debug, Parser)]
+pub struct Replay {
+    /// The block height to stop replaying at.
+    #[clap(long)]
+    pub end: Option<u32>,
+    /// The URL of the CDN to replay blocks from.
+    #[clap(default_value = "https://testnet3.blocks.aleo.org/phase3", long)]
+    pub cdn: String,
+}
+
+impl Replay {
+    pub fn parse(self) -> Result<String> {
+        // Initialize the runtime.
+        let runtime = runtime::Builder::new_current_thread().enable_all().build()?;
+        // Replay the blocks.
+        let state_digest = runtime.block_on(Self::replay(&self.cdn, self.end))?;
+
+        // Output the digest, along with the platform it was computed on.
+        Ok(serde_json::to_string_pretty(&json!({
+            "arch": std::env::consts::ARCH,
+            "os": std::env::consts::OS,
+            "height": state_digest.height(),
+            "digest": state_digest.digest().to_string(),
+        }))?)
+    }
+
+    /// Replays the blocks from the CDN into an in-memory ledger, and returns the resulting state digest.
+    async fn replay(cdn: &str, end_height: Option<u32>) -> Result<StateDigest<CurrentNetwork>> {
+        // Load the genesis block.
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes())?;
+        // Initialize an in-memory ledger.
+        let ledger = Ledger::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::load(genesis, None)?;
+        // Initialize the state digest.
+        let state_digest = Arc::new(Mutex::new(StateDigest::new(&ledger)?));
+
+        // Replay the blocks, absorbing the state after each block.
+        let state_digest_clone = state_digest.clone();
+        load_blocks(cdn, 1, end_height, move |block: Block<CurrentNetwork>| {
+            ledger.add_next_block(&block)?;
+            state_digest_clone.lock().absorb(&ledger)
+        })
+        .await
+        .map_err(|(height, error)| anyhow!("Failed to replay block {height} - {error}"))?;
+
+        // Return the state digest.
+        let state_digest = *state_digest.lock();
+        Ok(state_digest)
+    }
+}
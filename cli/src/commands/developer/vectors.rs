@@ -0,0 +1,58 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::CurrentNetwork;
+
+use snarkos_node_consensus::{TestVectors, TEST_VECTORS_VERSION};
+use snarkvm::prelude::Network;
+
+use anyhow::Result;
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Generates the test vectors of the transaction circuits, and writes them to a versioned fixture file.
+#[derive(Debug, Parser)]
+pub struct Vectors {
+    /// The seed to generate the test vectors from.
+    #[clap(default_value = "0", long)]
+    pub seed: u64,
+    /// The directory to write the fixture file to.
+    #[clap(long)]
+    pub output: PathBuf,
+    /// Checks the fixture file in the output directory against the current implementation, instead of generating it.
+    #[clap(long)]
+    pub check: bool,
+}
+
+impl Vectors {
+    pub fn parse(self) -> Result<String> {
+        match self.check {
+            true => {
+                // Read the test vectors, and check them.
+                let path = TestVectors::path(&self.output, TEST_VECTORS_VERSION, CurrentNetwork::ID);
+                let vectors = TestVectors::read(&path)?;
+                vectors.check::<CurrentNetwork>()?;
+                Ok(format!("✅ Checked {} test vectors in '{}'", vectors.vectors.len(), path.display()))
+            }
+            false => {
+                // Generate the test vectors, and write them.
+                let vectors = TestVectors::generate::<CurrentNetwork>(self.seed)?;
+                let path = vectors.write(&self.output)?;
+                Ok(format!("✅ Wrote {} test vectors to '{}'", vectors.vectors.len(), path.display()))
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::LedgerOptions;
+
+use snarkos_node_cdn::{export_era_files, BLOCKS_PER_ERA};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use std::path::PathBuf;
+
+/// Exports blocks from the ledger into fixed-size, content-addressed era files.
+#[derive(Debug, Parser)]
+pub struct ExportEra {
+    /// The directory to write the era files and index to.
+    #[clap(long)]
+    pub dir: PathBuf,
+    /// The block height to stop exporting at (exclusive), defaults to the ledger tip.
+    #[clap(long)]
+    pub end: Option<u32>,
+    #[clap(flatten)]
+    pub options: LedgerOptions,
+}
+
+impl ExportEra {
+    pub fn parse(self) -> Result<String> {
+        // Load the ledger.
+        let ledger = self.options.load()?;
+        // Determine the end height.
+        let end_height = self.end.unwrap_or_else(|| ledger.latest_height() + 1);
+
+        // Export the era files.
+        let entries = export_era_files(&ledger, &self.dir, end_height)?;
+
+        let path_string = format!("(in \"{}\")", self.dir.display()).dimmed();
+        match entries.last() {
+            Some(entry) => Ok(format!(
+                "✅ Exported {} era files of {BLOCKS_PER_ERA} blocks, up to block {} {path_string}",
+                entries.len(),
+                entry.end
+            )),
+            None => Ok(format!("✅ No complete era of {BLOCKS_PER_ERA} blocks to export {path_string}")),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::{CurrentNetwork, LedgerOptions};
+
+use snarkos_node_cdn::EraReader;
+use snarkos_node_consensus::Consensus;
+use snarkvm::prelude::Block;
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use colored::Colorize;
+use rayon::prelude::*;
+use std::{path::PathBuf, sync::mpsc, thread};
+
+/// The number of eras to buffer ahead of the ledger.
+const NUM_BUFFERED_ERAS: usize = 2;
+
+/// Imports blocks from era files into the ledger.
+#[derive(Debug, Parser)]
+pub struct ImportEra {
+    /// The directory containing the era files and index.
+    pub dir: PathBuf,
+    #[clap(flatten)]
+    pub options: LedgerOptions,
+}
+
+impl ImportEra {
+    pub fn parse(self) -> Result<String> {
+        // Load the ledger and the consensus module.
+        let ledger = self.options.load()?;
+        let consensus = Consensus::new(ledger.clone(), false)?;
+        // Open the era files.
+        let reader = EraReader::open(&self.dir)?;
+
+        // Determine the eras that are not yet in the ledger.
+        let start_height = ledger.latest_height() + 1;
+        let entries = reader.index().iter().filter(|entry| entry.end > start_height).cloned().collect::<Vec<_>>();
+        let path_string = format!("(in \"{}\")", self.dir.display()).dimmed();
+        if entries.is_empty() {
+            return Ok(format!("✅ The ledger is already past the era files {path_string}"));
+        }
+
+        // Read the eras, and verify the transaction proofs of their blocks in parallel, ahead of applying them to the ledger.
+        let (sender, receiver) = mpsc::sync_channel::<Result<Vec<(Block<CurrentNetwork>, bool)>>>(NUM_BUFFERED_ERAS);
+        let proof_consensus = consensus.clone();
+        let read_ahead = thread::spawn(move || {
+            for entry in entries {
+                let result = reader.read_era::<CurrentNetwork>(&entry).map(|blocks| {
+                    blocks
+                        .into_par_iter()
+                        .filter(|block| block.height() >= start_height)
+                        .map(|block| {
+                            // Note: The proofs of a block may not verify ahead of its predecessors, e.g. if it executes
+                            // a program that is deployed in an earlier block of the era, so a failure is deferred.
+                            let proofs_verified = match proof_consensus.check_transaction_proofs(&block) {
+                                Ok(()) => true,
+                                Err(error) => {
+                                    debug!("Deferring the proof verification of block {} - {error}", block.height());
+                                    false
+                                }
+                            };
+                            (block, proofs_verified)
+                        })
+                        .collect()
+                });
+                // Stop if the read failed, or the ledger stopped receiving.
+                let is_err = result.is_err();
+                if sender.send(result).is_err() || is_err {
+                    break;
+                }
+            }
+        });
+
+        // Verify and apply the blocks to the ledger, in order, as they would be during sync.
+        for blocks in receiver {
+            for (block, proofs_verified) in blocks? {
+                // Ensure the block is a valid next block, verifying any proofs that were deferred.
+                consensus.check_next_block_with(&block, proofs_verified)?;
+                // Add the block to the ledger.
+                consensus.advance_to_next_block(&block)?;
+            }
+            info!("Imported blocks up to {}", ledger.latest_height());
+        }
+        read_ahead.join().map_err(|_| anyhow!("The era reader panicked"))?;
+
+        Ok(format!("✅ Imported blocks {start_height} to {} {path_string}", ledger.latest_height()))
+    }
+}
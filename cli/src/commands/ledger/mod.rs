@@ -0,0 +1,91 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+mod export_era;
+pub use export_era::*;
+
+mod import_era;
+pub use import_era::*;
+
+mod rebuild_indexes;
+pub use rebuild_indexes::*;
+
+use snarkos_node_ledger::Ledger as LedgerState;
+use snarkos_node_store::{rocksdb::RocksDB, ConsensusDB, StorageLayout};
+use snarkvm::prelude::{Block, FromBytes, Network};
+
+use anyhow::Result;
+use clap::Parser;
+use std::path::PathBuf;
+
+type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+/// Commands to manage the node ledger.
+#[derive(Debug, Parser)]
+pub enum Ledger {
+    /// Export blocks from the ledger into era files.
+    #[clap(name = "export-era")]
+    ExportEra(ExportEra),
+    /// Import blocks from era files into the ledger.
+    #[clap(name = "import-era")]
+    ImportEra(ImportEra),
+    /// Rebuild the secondary indexes of the ledger.
+    #[clap(name = "rebuild-indexes")]
+    RebuildIndexes(RebuildIndexes),
+}
+
+impl Ledger {
+    pub fn parse(self) -> Result<String> {
+        match self {
+            Self::ExportEra(command) => command.parse(),
+            Self::ImportEra(command) => command.parse(),
+            Self::RebuildIndexes(command) => command.parse(),
+        }
+    }
+}
+
+/// The options shared by the commands that manage the node ledger.
+#[derive(Debug, Parser)]
+pub struct LedgerOptions {
+    /// Specify the directory of the ledger database, if it was placed elsewhere
+    #[clap(long = "ledger-dir")]
+    pub ledger_dir: Option<PathBuf>,
+    /// Specify the directory of the database write-ahead log, if it was placed elsewhere
+    #[clap(long = "wal-dir")]
+    pub wal_dir: Option<PathBuf>,
+    /// Specify the verbosity of the logs [options: 0, 1, 2, 3, 4]
+    #[clap(default_value = "0", long = "verbosity")]
+    pub verbosity: u8,
+    /// Specify the path to the file where logs will be stored
+    #[clap(default_value_os_t = std::env::temp_dir().join("snarkos-ledger.log"), long = "logfile")]
+    pub logfile: PathBuf,
+}
+
+impl LedgerOptions {
+    /// Initializes the logger, and loads the ledger of the node from storage.
+    fn load(&self) -> Result<LedgerState<CurrentNetwork, ConsensusDB<CurrentNetwork>>> {
+        // Initialize the logger, which writes to stdout as there is no display.
+        let _ = crate::helpers::initialize_logger(self.verbosity, true, &self.logfile);
+        // Open the database in the storage layout.
+        let layout =
+            StorageLayout { ledger_dir: self.ledger_dir.clone(), wal_dir: self.wal_dir.clone(), state_dir: None };
+        RocksDB::open_with_layout(CurrentNetwork::ID, None, &layout)?;
+        // Load the genesis block.
+        let genesis = Block::from_bytes_le(CurrentNetwork::genesis_bytes())?;
+        // Load the ledger.
+        LedgerState::load(genesis, None)
+    }
+}
@@ -0,0 +1,54 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::{CurrentNetwork, LedgerOptions};
+
+use snarkos_node_store::Indexes;
+
+use anyhow::Result;
+use clap::Parser;
+
+/// Rebuilds the secondary indexes of the ledger, or backfills them from the blocks they have not indexed yet.
+#[derive(Debug, Parser)]
+pub struct RebuildIndexes {
+    /// The name of the index to rebuild, defaults to every index [options: address-transactions, program-deployments, solution-provers]
+    #[clap(long)]
+    pub index: Option<String>,
+    /// If the flag is set, the indexes are only backfilled from their progress cursors, instead of rebuilt from the genesis block
+    #[clap(long)]
+    pub resume: bool,
+    #[clap(flatten)]
+    pub options: LedgerOptions,
+}
+
+impl RebuildIndexes {
+    pub fn parse(self) -> Result<String> {
+        // Load the ledger.
+        let ledger = self.options.load()?;
+        // Open the secondary indexes.
+        let indexes = Indexes::<CurrentNetwork>::open(None)?;
+
+        // Reset the indexes, unless resuming from their progress cursors.
+        if !self.resume {
+            indexes.reset(self.index.as_deref())?;
+        }
+        // Backfill the indexes up to the latest block.
+        let latest_height = ledger.latest_height();
+        let num_blocks = indexes.backfill(latest_height, |height| ledger.get_block(height))?;
+
+        Ok(format!("✅ Indexed {num_blocks} blocks, up to block {latest_height}"))
+    }
+}
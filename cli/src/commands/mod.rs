@@ -20,15 +20,27 @@ pub use account::*;
 mod clean;
 pub use clean::*;
 
+mod debug;
+pub use debug::*;
+
 mod developer;
 pub use developer::*;
 
+mod ledger;
+pub use ledger::*;
+
+mod p2p_test;
+pub use p2p_test::*;
+
 mod start;
 pub use start::*;
 
 mod update;
 pub use update::*;
 
+mod verifier;
+pub use verifier::*;
+
 use anyhow::Result;
 use clap::Parser;
 
@@ -49,12 +61,20 @@ pub enum Command {
     Account(Account),
     #[clap(name = "clean")]
     Clean(Clean),
+    #[clap(subcommand, name = "debug")]
+    Debug(Diagnostics),
     #[clap(subcommand)]
     Developer(Developer),
+    #[clap(subcommand)]
+    Ledger(Ledger),
+    #[clap(name = "p2p-test")]
+    P2pTest(P2pTest),
     #[clap(name = "start")]
     Start(Box<Start>),
     #[clap(name = "update")]
     Update(Update),
+    #[clap(name = "verifier")]
+    Verifier(Verifier),
 }
 
 impl Command {
@@ -63,9 +83,13 @@ impl Command {
         match self {
             Self::Account(command) => command.parse(),
             Self::Clean(command) => command.parse(),
+            Self::Debug(command) => command.parse(),
             Self::Developer(command) => command.parse(),
+            Self::Ledger(command) => command.parse(),
+            Self::P2pTest(command) => command.parse(),
             Self::Start(command) => command.parse(),
             Self::Update(command) => command.parse(),
+            Self::Verifier(command) => command.parse(),
         }
     }
 }
@@ -0,0 +1,286 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkos_account::Account;
+use snarkos_node_messages::{
+    BlockRequest,
+    ChallengeRequest,
+    ChallengeResponse,
+    Data,
+    Message,
+    MessageCodec,
+    NodeType,
+    Ping,
+};
+use snarkvm::prelude::{Block, FromBytes, Network, Testnet3};
+
+use anyhow::{anyhow, bail, ensure, Result};
+use clap::Parser;
+use colored::Colorize;
+use futures_util::{SinkExt, StreamExt};
+use rand::Rng;
+use std::{fmt::Write, net::SocketAddr, time::Duration};
+use tokio::{io::AsyncWriteExt, net::TcpStream, time::timeout};
+use tokio_util::codec::Framed;
+
+type CurrentNetwork = Testnet3;
+
+/// The framed connection to the remote node.
+type Connection = Framed<TcpStream, MessageCodec<CurrentNetwork>>;
+
+/// The outcome of a conformance check.
+enum Outcome {
+    Pass,
+    Fail(String),
+    Skip(String),
+}
+
+impl From<Result<()>> for Outcome {
+    fn from(result: Result<()>) -> Self {
+        match result {
+            Ok(()) => Self::Pass,
+            Err(error) => Self::Fail(error.to_string()),
+        }
+    }
+}
+
+/// Runs a P2P protocol conformance test against a remote node, and prints a conformance report.
+#[derive(Debug, Parser)]
+pub struct P2pTest {
+    /// Specify the IP address and port of the node to test
+    pub addr: SocketAddr,
+    /// Specify the number of seconds to wait for each response
+    #[clap(default_value = "10", long = "timeout")]
+    pub timeout: u64,
+}
+
+impl P2pTest {
+    /// Runs the conformance checks, and returns the conformance report.
+    pub fn parse(self) -> Result<String> {
+        // Initialize the runtime.
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+        let checks = runtime.block_on(self.run());
+
+        // Construct the report.
+        let mut report = format!("\n🔬 P2P conformance report for {}\n\n", self.addr.to_string().bold());
+        let mut num_passed = 0;
+        let mut num_failed = 0;
+        for (name, outcome) in &checks {
+            match outcome {
+                Outcome::Pass => {
+                    num_passed += 1;
+                    writeln!(report, "  {} {name}", "PASS".green().bold())?;
+                }
+                Outcome::Fail(reason) => {
+                    num_failed += 1;
+                    writeln!(report, "  {} {name} - {reason}", "FAIL".red().bold())?;
+                }
+                Outcome::Skip(reason) => writeln!(report, "  {} {name} - {reason}", "SKIP".yellow().bold())?,
+            }
+        }
+        write!(
+            report,
+            "\n{num_passed} passed, {num_failed} failed, {} skipped",
+            checks.len() - num_passed - num_failed
+        )?;
+        Ok(report)
+    }
+
+    /// Runs the conformance checks against the remote node.
+    async fn run(&self) -> Vec<(&'static str, Outcome)> {
+        let mut checks = Vec::new();
+
+        // Check the handshake.
+        let (mut connection, node_type) = match self.connect().await {
+            Ok(result) => result,
+            Err(error) => {
+                checks.push(("handshake", Outcome::Fail(error.to_string())));
+                for name in ["ping", "block request", "malformed message"] {
+                    checks.push((name, Outcome::Skip("the handshake failed".to_string())));
+                }
+                return checks;
+            }
+        };
+        checks.push(("handshake", Outcome::Pass));
+
+        // Check the ping.
+        checks.push(("ping", self.check_ping(&mut connection).await.into()));
+
+        // Check the block request.
+        match node_type.is_beacon() || node_type.is_validator() {
+            true => checks.push(("block request", self.check_block_request(&mut connection).await.into())),
+            false => checks.push(("block request", Outcome::Skip(format!("a {node_type} does not serve blocks")))),
+        }
+
+        // Check the malformed message, on a fresh connection.
+        let outcome = match self.connect().await {
+            Ok((connection, _)) => self.check_malformed_message(connection).await.into(),
+            Err(error) => Outcome::Fail(format!("unable to reconnect - {error}")),
+        };
+        checks.push(("malformed message", outcome));
+
+        checks
+    }
+
+    /// Connects to the remote node and performs the handshake, returning the connection and the remote node type.
+    async fn connect(&self) -> Result<(Connection, NodeType)> {
+        let rng = &mut rand::thread_rng();
+
+        // Sample an ephemeral account.
+        let account = Account::<CurrentNetwork>::new(rng)?;
+        // Retrieve the genesis block header.
+        let genesis_header = *Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes())?.header();
+
+        // Connect to the remote node.
+        let stream = timeout(self.timeout(), TcpStream::connect(self.addr)).await??;
+        let mut connection = Framed::new(stream, MessageCodec::default());
+
+        // Send a challenge request.
+        let our_nonce = rng.gen();
+        let our_request =
+            ChallengeRequest::new(self.addr.port(), NodeType::Client, account.address(), our_nonce, Default::default());
+        connection.send(Message::ChallengeRequest(our_request)).await?;
+
+        // Receive the challenge response, followed by the challenge request.
+        let peer_response = match self.next_message(&mut connection).await? {
+            Message::ChallengeResponse(response) => response,
+            message => bail!("expected a 'ChallengeResponse', received '{}'", message.name()),
+        };
+        let peer_request = match self.next_message(&mut connection).await? {
+            Message::ChallengeRequest(request) => request,
+            message => bail!("expected a 'ChallengeRequest', received '{}'", message.name()),
+        };
+
+        // Verify the challenge response.
+        ensure!(
+            peer_request.version >= Message::<CurrentNetwork>::VERSION,
+            "outdated version {}",
+            peer_request.version
+        );
+        ensure!(peer_response.genesis_header == genesis_header, "incorrect genesis block header");
+        let signature = peer_response.signature.deserialize().await?;
+        ensure!(signature.verify_bytes(&peer_request.address, &our_nonce.to_le_bytes()), "invalid nonce signature");
+
+        // Send the challenge response.
+        let our_signature = account.sign_bytes(&peer_request.nonce.to_le_bytes(), rng)?;
+        let our_response =
+            ChallengeResponse { genesis_header, observed_ip: self.addr.ip(), signature: Data::Object(our_signature) };
+        connection.send(Message::ChallengeResponse(our_response)).await?;
+
+        // Increase the message size limit after the handshake.
+        connection.codec_mut().update_max_message_len();
+
+        Ok((connection, peer_request.node_type))
+    }
+
+    /// Sends a `Ping`, and expects a `Pong` in return.
+    async fn check_ping(&self, connection: &mut Connection) -> Result<()> {
+        connection.send(Message::Ping(Ping::new(NodeType::Client, None))).await?;
+        self.expect(connection, |message| matches!(message, Message::Pong(_))).await.map(|_| ())
+    }
+
+    /// Sends a `BlockRequest` for the genesis block, and expects the genesis block in return.
+    async fn check_block_request(&self, connection: &mut Connection) -> Result<()> {
+        let request = BlockRequest { start_height: 0, end_height: 1 };
+        connection.send(Message::BlockRequest(request)).await?;
+
+        // Receive the block response.
+        let response = match self.expect(connection, |message| matches!(message, Message::BlockResponse(_))).await? {
+            Message::BlockResponse(response) => response,
+            _ => unreachable!(),
+        };
+        ensure!(response.request == request, "the response does not match the request ({})", response.request);
+
+        // Ensure the genesis block was returned.
+        let blocks = response.blocks.deserialize().await?;
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes())?;
+        match blocks.0.as_slice() {
+            [block] if block.hash() == genesis.hash() => Ok(()),
+            [block] => bail!("received block {} instead of the genesis block", block.hash()),
+            blocks => bail!("received {} blocks instead of 1", blocks.len()),
+        }
+    }
+
+    /// Sends a message with an unknown message ID, and expects the remote node to disconnect.
+    async fn check_malformed_message(&self, mut connection: Connection) -> Result<()> {
+        // Send a frame with a length of 2 bytes, containing an unknown message ID.
+        connection.get_mut().write_all(&[2, 0, 0, 0, 0xff, 0xff]).await?;
+
+        // Wait for the remote node to disconnect.
+        let result = timeout(self.timeout(), async {
+            loop {
+                match connection.next().await {
+                    Some(Ok(Message::Disconnect(_))) | Some(Err(_)) | None => return,
+                    Some(Ok(_)) => continue,
+                }
+            }
+        })
+        .await;
+        ensure!(result.is_ok(), "the connection remained open after a malformed message");
+        Ok(())
+    }
+
+    /// Returns the next message from the remote node.
+    async fn next_message(&self, connection: &mut Connection) -> Result<Message<CurrentNetwork>> {
+        match timeout(self.timeout(), connection.next()).await {
+            Ok(Some(Ok(Message::Disconnect(disconnect)))) => bail!("disconnected ({:?})", disconnect.reason),
+            Ok(Some(Ok(message))) => Ok(message),
+            Ok(Some(Err(error))) => bail!("{error}"),
+            Ok(None) => bail!("the connection was closed"),
+            Err(_) => bail!("timed out after {} seconds", self.timeout),
+        }
+    }
+
+    /// Returns the first message from the remote node that satisfies the given predicate.
+    async fn expect(
+        &self,
+        connection: &mut Connection,
+        predicate: impl Fn(&Message<CurrentNetwork>) -> bool,
+    ) -> Result<Message<CurrentNetwork>> {
+        timeout(self.timeout(), async {
+            loop {
+                let message = self.next_message(connection).await?;
+                if predicate(&message) {
+                    return Ok(message);
+                }
+            }
+        })
+        .await
+        .map_err(|_| anyhow!("timed out after {} seconds", self.timeout))?
+    }
+
+    /// Returns the timeout for each response.
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_p2p_test() {
+        let command = P2pTest::try_parse_from(["snarkos", "127.0.0.1:4133"].iter()).unwrap();
+        assert_eq!(command.addr, SocketAddr::from(([127, 0, 0, 1], 4133)));
+        assert_eq!(command.timeout, 10);
+
+        // Ensure an unreachable node fails the handshake, and skips the remaining checks.
+        let command = P2pTest::try_parse_from(["snarkos", "127.0.0.1:1", "--timeout", "1"].iter()).unwrap();
+        let report = command.parse().unwrap();
+        assert!(report.contains("0 passed, 1 failed, 3 skipped"));
+    }
+}
@@ -16,16 +16,51 @@
 
 use snarkos_account::Account;
 use snarkos_display::Display;
-use snarkos_node::{Node, NodeType};
-use snarkvm::prelude::{Block, ConsensusMemory, ConsensusStore, FromBytes, Network, PrivateKey, Testnet3, VM};
+use snarkos_node::{
+    BatchConfig,
+    BuilderPolicy,
+    Compression,
+    GossipConfig,
+    JournalConfig,
+    LightGatewayConfig,
+    Node,
+    NodeType,
+    PeerDiversityConfig,
+    RelayLevel,
+    SpillConfig,
+    VerifierConfig,
+    VerifierEndpoint,
+    VerifierKey,
+    WatchListConfig,
+    WorkerConfig,
+};
+use snarkos_node_cdn::{EraReader, RemoteEraArchive};
+use snarkos_node_metrics::{AlertRules, PushConfig, PushProtocol};
+use snarkos_node_rest::{
+    AuthToken,
+    CaptchaConfig,
+    FaucetConfig,
+    JsonMode,
+    ReadinessThresholds,
+    RestConfig,
+    RestLimits,
+    RestProxy,
+};
+use snarkos_node_store::StorageLayout;
+use snarkvm::prelude::{Address, Block, ConsensusMemory, ConsensusStore, FromBytes, Network, PrivateKey, Testnet3, VM};
 
-use anyhow::{bail, Result};
+use anyhow::{bail, ensure, Result};
 use clap::Parser;
 use colored::Colorize;
 use core::str::FromStr;
 use rand::SeedableRng;
 use rand_chacha::ChaChaRng;
-use std::{net::SocketAddr, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    net::{IpAddr, SocketAddr},
+    path::{Path, PathBuf},
+    time::Duration,
+};
 use tokio::runtime::{self, Runtime};
 
 /// The recommended minimum number of 'open files' limit for a beacon.
@@ -63,6 +98,18 @@ pub struct Start {
     /// Specify the IP address and port of a peer to connect to
     #[clap(default_value = "", long = "connect")]
     pub connect: String,
+    /// Specify the maximum number of peers in the same /16 subnet (/32 for IPv6) that the node dials (unlimited if unset)
+    #[clap(long = "max-peers-per-subnet")]
+    pub max_peers_per_subnet: Option<usize>,
+    /// Specify the compression that a beacon or validator offers its peers for block and certificate messages ('none' or 'snappy')
+    #[clap(default_value = "none", long = "compression")]
+    pub compression: Compression,
+    /// Specify the checks a validator performs on a beacon proposal before relaying it ('full' or 'header' to verify the block in the background)
+    #[clap(default_value = "full", long = "relay")]
+    pub relay: RelayLevel,
+    /// Specify the number of threads that verify the signatures of the inbound messages
+    #[clap(default_value = "2", long = "signature-threads")]
+    pub signature_threads: usize,
 
     /// Specify the IP address and port for the REST server
     #[clap(default_value = "0.0.0.0:3033", long = "rest")]
@@ -70,6 +117,79 @@ pub struct Start {
     /// If the flag is set, the node will not initialize the REST server
     #[clap(long)]
     pub norest: bool,
+    /// Specify the IP address and port for the gRPC server, which is served alongside the REST server (without TLS)
+    #[cfg(feature = "grpc")]
+    #[clap(long = "grpc")]
+    pub grpc: Option<SocketAddr>,
+    /// Specify the path of the PEM certificate chain, to serve the REST server over TLS (requires '--rest-tls-key')
+    #[cfg(feature = "tls")]
+    #[clap(long = "rest-tls-cert")]
+    pub rest_tls_cert: Option<PathBuf>,
+    /// Specify the path of the PEM private key of the certificate, to serve the REST server over TLS
+    #[cfg(feature = "tls")]
+    #[clap(long = "rest-tls-key")]
+    pub rest_tls_key: Option<PathBuf>,
+    /// Specify a domain to serve the REST server over TLS, with a certificate issued and renewed by an ACME server
+    #[cfg(feature = "tls")]
+    #[clap(long = "rest-tls-domain")]
+    pub rest_tls_domain: Option<String>,
+    /// Specify the contact email of the ACME account
+    #[cfg(feature = "tls")]
+    #[clap(long = "rest-tls-email")]
+    pub rest_tls_email: Option<String>,
+    /// Specify the directory URL of the ACME server
+    #[cfg(feature = "tls")]
+    #[clap(default_value = snarkos_node_rest::LETS_ENCRYPT_DIRECTORY, long = "rest-tls-acme-directory")]
+    pub rest_tls_acme_directory: String,
+    /// Specify the IP address and port on which the ACME HTTP-01 challenges are served (must be reachable on port 80)
+    #[cfg(feature = "tls")]
+    #[clap(default_value = "0.0.0.0:80", long = "rest-tls-challenge")]
+    pub rest_tls_challenge: SocketAddr,
+    /// If the flag is set, the node will serve the REST API to the local user on an IPC socket, without bearer tokens
+    #[clap(long)]
+    pub ipc: bool,
+    /// Specify the path of the IPC socket, in a directory only accessible by the user (default: 'ipc/snarkos.sock' in the state directory)
+    #[clap(long = "ipc-path")]
+    pub ipc_path: Option<PathBuf>,
+    /// Specify a bearer token for the protected REST endpoints, as 'TOKEN' or 'TOKEN:privilege,...' [privileges: broadcast, proving, peers, watchList, and 'admin', 'history', or 'builder' if listed]
+    #[clap(long = "rpc-auth-token")]
+    pub rpc_auth_token: Vec<String>,
+    /// Specify the number of REST requests per second each IP may sustain (unlimited if unset)
+    #[clap(long = "rest-rps")]
+    pub rest_rps: Option<u32>,
+    /// Specify the number of REST requests each IP may send at once, on top of the sustained rate
+    #[clap(default_value = "0", long = "rest-burst")]
+    pub rest_burst: u32,
+    /// Specify the maximum size of a REST request body, in bytes
+    #[clap(default_value = "67108864", long = "rest-max-body-bytes")]
+    pub rest_max_body_bytes: u64,
+    /// Specify an origin allowed to make cross-origin REST requests, as 'scheme://host[:port]' (any origin if unset)
+    #[clap(long = "rest-cors-origin")]
+    pub rest_cors_origin: Vec<String>,
+    /// Specify the IP of a reverse proxy trusted to report the client IP in the 'X-Forwarded-For' header
+    #[clap(long = "rest-trusted-proxy")]
+    pub rest_trusted_proxy: Vec<IpAddr>,
+    /// Specify the URL path prefix of the REST endpoints (e.g. 'api/v1')
+    #[clap(long = "rest-path-prefix")]
+    pub rest_path_prefix: Option<String>,
+    /// If the flag is set, the REST server rejects the fields of a transaction outside its canonical JSON representation
+    #[clap(long = "rest-strict-json")]
+    pub rest_strict_json: bool,
+    /// Specify the maximum number of blocks the node may be behind the median height of its peers to be reported as ready at '/ready'
+    #[clap(default_value = "10", long = "ready-max-sync-lag")]
+    pub ready_max_sync_lag: u32,
+    /// Specify the minimum number of connected peers for the node to be reported as ready at '/ready'
+    #[clap(default_value = "1", long = "ready-min-peers")]
+    pub ready_min_peers: usize,
+    /// If the flag is set, the REST server serves the memory usage, allocator statistics, and per-thread CPU profiles (also in the pprof format) of the node to admin tokens at '/debug'
+    #[clap(long = "rest-profiling")]
+    pub rest_profiling: bool,
+    /// Specify the private key of a dedicated account that proves the jobs at '/testnet3/proving/jobs', and pays their fees (disabled if unset)
+    #[clap(long = "proving-key")]
+    pub proving_key: Option<String>,
+    /// Specify the minimum total fee of a bundle from an external builder at '/testnet3/builder', for the beacon to adopt it (disabled if unset)
+    #[clap(long = "builder-min-fee")]
+    pub builder_min_fee: Option<u64>,
 
     /// If the flag is set, the node will not render the display
     #[clap(long)]
@@ -84,9 +204,140 @@ pub struct Start {
     /// Enables the node to prefetch initial blocks from a CDN
     #[clap(default_value = "https://testnet3.blocks.aleo.org/phase3", long = "cdn")]
     pub cdn: String,
+    /// Skips proof verification for the blocks that link back to a trusted block during the initial sync, specified as 'HEIGHT:HASH'
+    #[clap(long = "assume-valid")]
+    pub assume_valid: Option<String>,
+    /// Specify a trusted checkpoint as 'HEIGHT:HASH', which the node refuses to sync any conflicting chain across
+    #[clap(long = "checkpoint")]
+    pub checkpoint: Vec<String>,
+    /// Specify an address whose public balance, mapping values, and public records are tracked from startup
+    #[clap(long = "watch-address")]
+    pub watch_address: Vec<String>,
+    /// Specify the maximum number of changes of the watched addresses to retain
+    #[clap(default_value = "1024", long = "watch-list-changes")]
+    pub watch_list_changes: usize,
+    /// Specify the number of peers that receive the full payload of gossiped solutions and transactions
+    #[clap(long = "gossip-fanout")]
+    pub gossip_fanout: Option<usize>,
+    /// If the flag is set, transactions are announced to the peers beyond the gossip fanout
+    #[clap(long = "lazy-push")]
+    pub lazy_push: bool,
+    /// Specify the maximum random delay in milliseconds before relaying locally-submitted transactions to each peer
+    #[clap(default_value = "0", long = "diffusion-delay")]
+    pub diffusion_delay: u64,
+    /// Specify the path to a TOML file of alert rules to evaluate against the node metrics
+    #[clap(long = "alerts")]
+    pub alerts: Option<PathBuf>,
+    /// Specify the URL of a Prometheus push-gateway to push the node metrics to
+    #[clap(long = "metrics-push-gateway")]
+    pub metrics_push_gateway: Option<String>,
+    /// Specify the URL of a Prometheus remote-write endpoint to push the node metrics to
+    #[clap(long = "metrics-remote-write")]
+    pub metrics_remote_write: Option<String>,
+    /// Specify the interval in seconds at which the node metrics are pushed
+    #[clap(default_value = "15", long = "metrics-push-interval")]
+    pub metrics_push_interval: u64,
+    /// Specify the basic authentication credentials for pushing the node metrics, as 'username:password'
+    #[clap(long = "metrics-push-auth")]
+    pub metrics_push_auth: Option<String>,
+    /// Specify the IP address and port to serve the node metrics to Prometheus at '/metrics'
+    #[cfg(feature = "metrics")]
+    #[clap(long = "metrics")]
+    pub metrics: Option<SocketAddr>,
+    /// Specify the directory of the ledger database
+    #[clap(long = "ledger-dir")]
+    pub ledger_dir: Option<PathBuf>,
+    /// Specify the directory of the database write-ahead log, if it should live apart from the ledger
+    #[clap(long = "wal-dir")]
+    pub wal_dir: Option<PathBuf>,
+    /// Specify the directory of the node state files, if it should live apart from the ledger
+    #[clap(long = "state-dir")]
+    pub state_dir: Option<PathBuf>,
+    /// [Experimental] Specify the base URL of an S3-compatible bucket of era files to serve the historical blocks from
+    #[clap(long = "remote-ledger")]
+    pub remote_ledger: Option<String>,
+    /// Specify the maximum number of era files from the remote ledger to cache on disk
+    #[clap(default_value = "16", long = "remote-ledger-cache")]
+    pub remote_ledger_cache: usize,
+    /// Specify a directory of era files (see 'snarkos ledger export-era') to serve the blocks requested by peers from
+    #[clap(long = "era-dir")]
+    pub era_dir: Option<PathBuf>,
+    /// If the flag is set, the node will maintain the secondary indexes of the ledger, including the address index (see 'snarkos ledger rebuild-indexes')
+    #[clap(long, alias = "enable-address-index")]
+    pub indexes: bool,
+    /// If the flag is set, the client peers are served as untrusted light consumers, restricted to read-only messages
+    #[clap(long = "light-gateway")]
+    pub light_gateway: bool,
+    /// Specify the maximum number of requests from each light consumer per 10 seconds
+    #[clap(default_value = "50", long = "light-gateway-rate")]
+    pub light_gateway_rate: usize,
+    /// If the flag is set, the blocks buffered during sync beyond '--spill-max-memory' are spilled to a scratch directory
+    #[clap(long = "spill-blocks")]
+    pub spill_blocks: bool,
+    /// Specify the maximum number of blocks buffered in memory during sync, before spilling to disk
+    #[clap(default_value = "256", long = "spill-max-memory")]
+    pub spill_max_memory: usize,
+    /// Specify the maximum size in megabytes of the blocks spilled to disk during sync
+    #[clap(default_value = "4096", long = "spill-max-disk")]
+    pub spill_max_disk: u64,
+    /// If the flag is set, the consensus events are journaled to the state directory (see 'snarkos debug journal')
+    #[clap(long = "journal")]
+    pub journal: bool,
+    /// Specify the maximum size in megabytes of each segment of the consensus journal
+    #[clap(default_value = "16", long = "journal-segment-size")]
+    pub journal_segment_size: u64,
+    /// Specify the maximum number of segments of the consensus journal to retain
+    #[clap(default_value = "16", long = "journal-segments")]
+    pub journal_segments: usize,
+    /// Specify the number of workers that verify the unconfirmed solutions (beacon only)
+    #[clap(long = "solution-workers")]
+    pub solution_workers: Option<usize>,
+    /// Specify the number of workers that verify the unconfirmed execution transactions (beacon and validator only)
+    #[clap(long = "transaction-workers")]
+    pub transaction_workers: Option<usize>,
+    /// Specify the number of workers that verify the unconfirmed deployment transactions (beacon and validator only)
+    #[clap(long = "deployment-workers")]
+    pub deployment_workers: Option<usize>,
+    /// Specify the minimum number of transactions in a produced block, as the batch size adapts to the round latency (beacon only)
+    #[clap(long = "batch-min-size")]
+    pub batch_min_size: Option<usize>,
+    /// Specify the maximum number of transactions in a produced block, as the batch size adapts to the round latency (beacon only)
+    #[clap(long = "batch-max-size")]
+    pub batch_max_size: Option<usize>,
+    /// Specify the minimum time between produced blocks in seconds, as the batch timeout adapts to the round latency (beacon only)
+    #[clap(long = "batch-min-timeout")]
+    pub batch_min_timeout: Option<u64>,
+    /// Specify the maximum time between produced blocks in seconds, as the batch timeout adapts to the round latency (beacon only)
+    #[clap(long = "batch-max-timeout")]
+    pub batch_max_timeout: Option<u64>,
+    /// If the flag is set, the proof verification of blocks is offloaded to a child process (see 'snarkos verifier')
+    #[clap(long = "verifier-process")]
+    pub verifier_process: bool,
+    /// Specify the IP address and port of a remote verification service to offload the proof verification of blocks to
+    #[clap(long = "verifier-remote")]
+    pub verifier_remote: Option<SocketAddr>,
+    /// Specify the key shared with the remote verification service, which authenticates its verdicts
+    #[clap(long = "verifier-key")]
+    pub verifier_key: Option<String>,
+    /// Specify a webhook URL to deliver the crash reports to, in addition to writing them to the state directory
+    #[clap(long = "crash-webhook")]
+    pub crash_webhook: Option<String>,
+
     /// Enables development mode, specify a unique ID for this node
     #[clap(long)]
     pub dev: Option<u16>,
+    /// Enables the faucet in development mode, specify the amount dispensed per request in microcredits
+    #[clap(long = "faucet-amount")]
+    pub faucet_amount: Option<u64>,
+    /// Specify the number of seconds each IP must wait between faucet requests
+    #[clap(default_value = "3600", long = "faucet-cooldown")]
+    pub faucet_cooldown: u64,
+    /// Specify the URL of a captcha verifier ('siteverify' API) to require a solved captcha with each faucet request
+    #[clap(long = "faucet-captcha-url")]
+    pub faucet_captcha_url: Option<String>,
+    /// Specify the secret key for the captcha verifier
+    #[clap(long = "faucet-captcha-secret")]
+    pub faucet_captcha_secret: Option<String>,
 }
 
 impl Start {
@@ -159,6 +410,162 @@ impl Start {
         }
     }
 
+    /// Returns the assume-valid checkpoint, from the given configurations.
+    fn parse_assume_valid<N: Network>(&self) -> Result<Option<(u32, N::BlockHash)>> {
+        let checkpoint = match &self.assume_valid {
+            Some(checkpoint) => checkpoint,
+            None => return Ok(None),
+        };
+        let (height, hash) = match checkpoint.split_once(':') {
+            Some((height, hash)) => (height.parse::<u32>(), N::BlockHash::from_str(hash)),
+            None => bail!("The checkpoint supplied to --assume-valid ('{checkpoint}') must be 'HEIGHT:HASH'"),
+        };
+        match (height, hash) {
+            (Ok(height), Ok(hash)) => Ok(Some((height, hash))),
+            _ => bail!("The checkpoint supplied to --assume-valid ('{checkpoint}') is malformed"),
+        }
+    }
+
+    /// Returns the trusted checkpoints, from the given configurations.
+    fn parse_trusted_checkpoints<N: Network>(&self) -> Result<BTreeMap<u32, N::BlockHash>> {
+        let mut checkpoints = BTreeMap::new();
+        for checkpoint in &self.checkpoint {
+            let (height, hash) = match checkpoint.split_once(':') {
+                Some((height, hash)) => (height.parse::<u32>(), N::BlockHash::from_str(hash)),
+                None => bail!("The checkpoint supplied to --checkpoint ('{checkpoint}') must be 'HEIGHT:HASH'"),
+            };
+            match (height, hash) {
+                (Ok(height), Ok(hash)) => {
+                    if checkpoints.insert(height, hash).map_or(false, |existing| existing != hash) {
+                        bail!("The checkpoints supplied to --checkpoint conflict at block {height}");
+                    }
+                }
+                _ => bail!("The checkpoint supplied to --checkpoint ('{checkpoint}') is malformed"),
+            }
+        }
+        Ok(checkpoints)
+    }
+
+    /// Returns the configuration of the watch list, from the given configurations.
+    fn parse_watch_list<N: Network>(&self) -> Result<WatchListConfig<N>> {
+        let mut addresses = Vec::with_capacity(self.watch_address.len());
+        for address in &self.watch_address {
+            match Address::<N>::from_str(address) {
+                Ok(address) => addresses.push(address),
+                Err(error) => bail!("The address supplied to --watch-address ('{address}') is malformed: {error}"),
+            }
+        }
+        Ok(WatchListConfig { addresses, max_changes: self.watch_list_changes })
+    }
+
+    /// Returns the configurations for pushing the node metrics, from the given configurations.
+    fn parse_metrics_push(&self) -> Result<Vec<PushConfig>> {
+        // Parse the basic authentication credentials.
+        let basic_auth = self.metrics_push_auth.as_deref().map(PushConfig::parse_basic_auth).transpose()?;
+        // Ensure the push interval is nonzero.
+        ensure!(self.metrics_push_interval > 0, "The metrics push interval must be at least 1 second");
+
+        let endpoints = [
+            (&self.metrics_push_gateway, PushProtocol::Gateway),
+            (&self.metrics_remote_write, PushProtocol::RemoteWrite),
+        ];
+        Ok(endpoints
+            .into_iter()
+            .filter_map(|(url, protocol)| url.clone().map(|url| (url, protocol)))
+            .map(|(url, protocol)| PushConfig {
+                url,
+                protocol,
+                interval: Duration::from_secs(self.metrics_push_interval),
+                basic_auth: basic_auth.clone(),
+            })
+            .collect())
+    }
+
+    /// Returns the configuration of the REST server, from the given configurations.
+    fn parse_rest_config<N: Network>(&self, state_dir: &Path) -> Result<RestConfig> {
+        // Parse the bearer tokens for the protected REST endpoints.
+        let auth_tokens =
+            self.rpc_auth_token.iter().map(|token| AuthToken::from_str(token)).collect::<Result<Vec<_>>>()?;
+        // Parse the proving key, which enables the proving jobs.
+        if let Some(proving_key) = &self.proving_key {
+            if let Err(error) = PrivateKey::<N>::from_str(proving_key) {
+                bail!("The private key supplied to --proving-key is malformed: {error}");
+            }
+        }
+        // Parse the faucet, which is only served in development mode.
+        let faucet = match self.faucet_amount {
+            Some(amount) => {
+                ensure!(self.dev.is_some(), "The faucet is only available in development mode");
+                let captcha = match (&self.faucet_captcha_url, &self.faucet_captcha_secret) {
+                    (Some(verify_url), Some(secret)) => {
+                        Some(CaptchaConfig { verify_url: verify_url.clone(), secret: secret.clone() })
+                    }
+                    (None, None) => None,
+                    _ => bail!("The faucet captcha requires both '--faucet-captcha-url' and '--faucet-captcha-secret'"),
+                };
+                Some(FaucetConfig { amount, cooldown_in_secs: self.faucet_cooldown, captcha })
+            }
+            None => None,
+        };
+        // Parse the TLS configuration of the REST server, if it is enabled.
+        #[cfg(feature = "tls")]
+        let tls = {
+            use snarkos_node_rest::{AcmeConfig, TlsConfig};
+
+            match (&self.rest_tls_cert, &self.rest_tls_key, &self.rest_tls_domain) {
+                (Some(certificate), Some(key), None) => {
+                    Some(TlsConfig::Files { certificate: certificate.clone(), key: key.clone() })
+                }
+                (None, None, Some(domain)) => Some(TlsConfig::Acme(AcmeConfig {
+                    domain: domain.clone(),
+                    email: self.rest_tls_email.clone(),
+                    directory_url: self.rest_tls_acme_directory.clone(),
+                    challenge_ip: self.rest_tls_challenge,
+                    cache_dir: state_dir.join("tls"),
+                })),
+                (None, None, None) => None,
+                _ => bail!("Specify either '--rest-tls-cert' and '--rest-tls-key', or '--rest-tls-domain'"),
+            }
+        };
+        // Parse the path of the IPC socket, if it is enabled.
+        #[cfg(unix)]
+        let ipc_path = match !self.norest && self.ipc {
+            true => Some(self.ipc_path.clone().unwrap_or_else(|| state_dir.join("ipc").join("snarkos.sock"))),
+            false => None,
+        };
+
+        let config = RestConfig {
+            auth_tokens,
+            limits: RestLimits {
+                requests_per_sec: self.rest_rps,
+                burst: self.rest_burst,
+                max_body_bytes: self.rest_max_body_bytes,
+            },
+            proxy: RestProxy {
+                allowed_origins: self.rest_cors_origin.clone(),
+                trusted_proxies: self.rest_trusted_proxy.clone(),
+                path_prefix: self.rest_path_prefix.clone(),
+            },
+            json_mode: match self.rest_strict_json {
+                true => JsonMode::Strict,
+                false => JsonMode::Lenient,
+            },
+            readiness: ReadinessThresholds { max_sync_lag: self.ready_max_sync_lag, min_peers: self.ready_min_peers },
+            profiling: self.rest_profiling,
+            proving_key: self.proving_key.clone(),
+            proving_jobs_path: Some(state_dir.join("proving-jobs.json")),
+            faucet,
+            #[cfg(unix)]
+            ipc_path,
+            #[cfg(feature = "grpc")]
+            grpc_ip: self.grpc,
+            #[cfg(feature = "tls")]
+            tls,
+        };
+        config.check()?;
+        Ok(config)
+    }
+
     /// Updates the configurations if the node is in development mode, and returns the
     /// alternative genesis block if the node is in development mode. Otherwise, returns the actual genesis block.
     fn parse_development<N: Network>(&mut self, trusted_peers: &mut Vec<SocketAddr>) -> Result<Block<N>> {
@@ -245,21 +652,54 @@ impl Start {
 
         // Parse the trusted IPs to connect to.
         let mut trusted_peers = self.parse_trusted_peers()?;
+        // Ensure the peer diversity constraints permit dialing.
+        ensure!(self.max_peers_per_subnet != Some(0), "The maximum number of peers per subnet must be at least 1");
 
         // Parse the CDN.
         let cdn = self.parse_cdn();
 
+        // Parse the assume-valid checkpoint.
+        let assume_valid = self.parse_assume_valid::<N>()?;
+        // Parse the trusted checkpoints.
+        let checkpoints = self.parse_trusted_checkpoints::<N>()?;
+        // Parse the watch list.
+        let watch_list = self.parse_watch_list::<N>()?;
+
+        // Parse the alert rules.
+        let alerts = self.alerts.as_ref().map(AlertRules::load).transpose()?;
+        // Parse the metrics push configurations.
+        let metrics_push = self.parse_metrics_push()?;
+
         // Parse the development configurations, and determine the genesis block.
         let genesis = self.parse_development::<N>(&mut trusted_peers)?;
 
+        // Parse the storage layout.
+        let layout = StorageLayout {
+            ledger_dir: self.ledger_dir.clone(),
+            wal_dir: self.wal_dir.clone(),
+            state_dir: self.state_dir.clone(),
+        };
+        let state_dir = layout.state_dir(N::ID, self.dev);
+        // Install the crash handler.
+        crate::helpers::install_crash_handler(state_dir.join("crash-reports"), self.crash_webhook.clone());
+
         // Parse the REST IP.
         let rest_ip = match self.norest {
             true => None,
             false => Some(self.rest),
         };
-
+        // Parse the configuration of the REST server.
+        let rest_config = self.parse_rest_config::<N>(&state_dir)?;
         // Parse the node account and node type.
         let (account, node_type) = self.parse_account::<N>()?;
+        // Ensure the compression is only offered by beacons and validators.
+        if self.compression.is_enabled() && !(node_type.is_beacon() || node_type.is_validator()) {
+            bail!("The compression is only offered by beacons and validators");
+        }
+        // Ensure the relay level is only set on validators.
+        if self.relay.is_deferred() && !node_type.is_validator() {
+            bail!("The beacon proposals are only relayed by validators");
+        }
 
         // If the display is not enabled, render the welcome message.
         if self.nodisplay {
@@ -297,12 +737,139 @@ impl Start {
         }
 
         // Initialize the node.
-        match node_type {
-            NodeType::Beacon => Node::new_beacon(self.node, rest_ip, account, &trusted_peers, genesis, cdn, self.dev).await,
-            NodeType::Validator => Node::new_validator(self.node, rest_ip, account, &trusted_peers, genesis, cdn, self.dev).await,
-            NodeType::Prover => Node::new_prover(self.node, account, &trusted_peers, genesis, self.dev).await,
-            NodeType::Client => Node::new_client(self.node, account, &trusted_peers, genesis, self.dev).await,
+        let node = match node_type {
+            NodeType::Beacon => Node::new_beacon(self.node, rest_ip, rest_config, account, &trusted_peers, genesis, cdn, assume_valid, self.compression, layout, self.dev).await,
+            NodeType::Validator => Node::new_validator(self.node, rest_ip, rest_config, account, &trusted_peers, genesis, cdn, assume_valid, self.compression, layout, self.dev).await,
+            NodeType::Prover => Node::new_prover(self.node, account, &trusted_peers, genesis, layout, self.dev).await,
+            NodeType::Client => Node::new_client(self.node, account, &trusted_peers, genesis, layout, self.dev).await,
+        }?;
+
+        // Initialize the gossip configuration.
+        node.set_gossip_config(GossipConfig {
+            fanout: self.gossip_fanout,
+            lazy_push: self.lazy_push,
+            diffusion_delay_in_ms: self.diffusion_delay,
+        });
+        // Initialize the diversity constraints on the dialed peers.
+        node.set_peer_diversity_config(PeerDiversityConfig { max_peers_per_subnet: self.max_peers_per_subnet });
+        // Initialize the number of threads that verify signatures.
+        node.set_signature_threads(self.signature_threads)?;
+
+        // Initialize the policy for the bundles of external builders.
+        if let Some(min_total_fee) = self.builder_min_fee {
+            node.set_builder_policy(BuilderPolicy { min_total_fee })?;
+        }
+
+        // Initialize the light gateway.
+        if self.light_gateway {
+            node.set_light_gateway_config(LightGatewayConfig {
+                enabled: true,
+                max_requests_per_interval: self.light_gateway_rate,
+            })?;
+        }
+        // Initialize the relay level of the beacon proposals.
+        if self.relay.is_deferred() {
+            node.set_relay_level(self.relay)?;
+        }
+        // Initialize the spill buffer of the sync pool.
+        if self.spill_blocks {
+            node.set_spill_config(SpillConfig {
+                max_in_memory: self.spill_max_memory,
+                dir: Some(state_dir.join("sync-spill")),
+                max_disk_bytes: self.spill_max_disk * 1024 * 1024,
+            })?;
+        }
+        // Initialize the consensus journal.
+        if self.journal {
+            node.set_journal_config(JournalConfig {
+                directory: state_dir.join("journal"),
+                max_segment_bytes: self.journal_segment_size * 1024 * 1024,
+                max_segments: self.journal_segments,
+            })?;
+        }
+        // Initialize the transmission workers.
+        if self.solution_workers.is_some() || self.transaction_workers.is_some() || self.deployment_workers.is_some() {
+            let default = WorkerConfig::default();
+            node.set_worker_config(WorkerConfig {
+                solution_workers: self.solution_workers.unwrap_or(default.solution_workers),
+                transaction_workers: self.transaction_workers.unwrap_or(default.transaction_workers),
+                deployment_workers: self.deployment_workers.unwrap_or(default.deployment_workers),
+                ..default
+            })?;
+        }
+        // Initialize the bounds of the adaptive batch.
+        if self.batch_min_size.is_some()
+            || self.batch_max_size.is_some()
+            || self.batch_min_timeout.is_some()
+            || self.batch_max_timeout.is_some()
+        {
+            let default = BatchConfig::default();
+            node.set_batch_config(BatchConfig {
+                min_size: self.batch_min_size.unwrap_or(default.min_size),
+                max_size: self.batch_max_size.unwrap_or(default.max_size),
+                min_timeout_in_secs: self.batch_min_timeout.unwrap_or(default.min_timeout_in_secs),
+                max_timeout_in_secs: self.batch_max_timeout.unwrap_or(default.max_timeout_in_secs),
+            })?;
+        }
+        // Initialize the verification service.
+        if let Some(addr) = self.verifier_remote {
+            let key = match &self.verifier_key {
+                Some(key) => VerifierKey::new(key)?,
+                None => bail!("The remote verification service requires a shared '--verifier-key'"),
+            };
+            node.set_verifier_config(VerifierConfig::new(VerifierEndpoint::Remote { addr, key }))?;
+        } else if self.verifier_process {
+            node.set_verifier_config(VerifierConfig::new(VerifierEndpoint::Process {
+                program: std::env::current_exe()?,
+                args: vec!["verifier".to_string(), "--network".to_string(), self.network.to_string()],
+            }))?;
+        }
+        // Initialize the trusted checkpoints of the sync pool.
+        if !checkpoints.is_empty() {
+            node.set_trusted_checkpoints(checkpoints)?;
+        }
+        // Initialize the watch list.
+        if !watch_list.addresses.is_empty() || watch_list.max_changes != WatchListConfig::<N>::default().max_changes {
+            node.set_watch_list_config(watch_list)?;
+        }
+
+        // Initialize the alert rules.
+        if let Some(alerts) = alerts {
+            node.initialize_alerts(alerts);
+        }
+        // Initialize pushing the metrics.
+        for config in metrics_push {
+            node.initialize_metrics_push(config);
+        }
+        // Initialize the Prometheus exporter.
+        #[cfg(feature = "metrics")]
+        if let Some(metrics_ip) = self.metrics {
+            snarkos_node_metrics::initialize(metrics_ip);
+            node.initialize_metrics_export();
+        }
+        // Initialize the remote ledger.
+        if let Some(base_url) = &self.remote_ledger {
+            let cache_dir = state_dir.join("era-cache");
+            node.set_remote_archive(RemoteEraArchive::open(base_url, cache_dir, self.remote_ledger_cache).await?)?;
+        }
+        // Serve the blocks requested by peers from the era files.
+        if let Some(era_dir) = &self.era_dir {
+            node.set_era_reader(EraReader::open(era_dir)?)?;
+        }
+        // Initialize the secondary indexes.
+        if self.indexes {
+            node.initialize_indexes(self.dev)?;
         }
+        // Track the latest height for the crash reports.
+        let router = node.router().clone();
+        tokio::spawn(async move {
+            loop {
+                crate::helpers::set_crash_height(router.sync().latest_canon_height());
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+        });
+
+        Ok(node)
     }
 
     /// Returns a runtime for the node.
@@ -451,6 +1018,60 @@ mod tests {
         assert!(config.parse_cdn().is_none());
     }
 
+    #[test]
+    fn test_parse_assume_valid() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(config.parse_assume_valid::<CurrentNetwork>().unwrap().is_none());
+
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let checkpoint = format!("0:{}", genesis.hash());
+        let config = Start::try_parse_from(["snarkos", "--assume-valid", &checkpoint].iter()).unwrap();
+        assert_eq!(config.parse_assume_valid::<CurrentNetwork>().unwrap(), Some((0, genesis.hash())));
+
+        let hash = genesis.hash().to_string();
+        let config = Start::try_parse_from(["snarkos", "--assume-valid", &hash].iter()).unwrap();
+        assert!(config.parse_assume_valid::<CurrentNetwork>().is_err());
+        let config = Start::try_parse_from(["snarkos", "--assume-valid", "0:ab1xx"].iter()).unwrap();
+        assert!(config.parse_assume_valid::<CurrentNetwork>().is_err());
+    }
+
+    #[test]
+    fn test_parse_trusted_checkpoints() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(config.parse_trusted_checkpoints::<CurrentNetwork>().unwrap().is_empty());
+
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let checkpoint = format!("0:{}", genesis.hash());
+        let config = Start::try_parse_from(["snarkos", "--checkpoint", &checkpoint].iter()).unwrap();
+        let checkpoints = config.parse_trusted_checkpoints::<CurrentNetwork>().unwrap();
+        assert_eq!(checkpoints, BTreeMap::from([(0, genesis.hash())]));
+
+        let config = Start::try_parse_from(["snarkos", "--checkpoint", &genesis.hash().to_string()].iter()).unwrap();
+        assert!(config.parse_trusted_checkpoints::<CurrentNetwork>().is_err());
+        let config = Start::try_parse_from(["snarkos", "--checkpoint", "x:ab1xx"].iter()).unwrap();
+        assert!(config.parse_trusted_checkpoints::<CurrentNetwork>().is_err());
+    }
+
+    #[test]
+    fn test_parse_metrics_push() {
+        let config = Start::try_parse_from(["snarkos"].iter()).unwrap();
+        assert!(config.parse_metrics_push().unwrap().is_empty());
+
+        let config = Start::try_parse_from(
+            ["snarkos", "--metrics-remote-write", "http://localhost:9090/api/v1/write", "--metrics-push-auth", "a:b"]
+                .iter(),
+        )
+        .unwrap();
+        let configs = config.parse_metrics_push().unwrap();
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].protocol, PushProtocol::RemoteWrite);
+        assert_eq!(configs[0].interval, Duration::from_secs(15));
+        assert_eq!(configs[0].basic_auth, Some(("a".to_string(), "b".to_string())));
+
+        let config = Start::try_parse_from(["snarkos", "--metrics-push-auth", "ab"].iter()).unwrap();
+        assert!(config.parse_metrics_push().is_err());
+    }
+
     #[test]
     fn test_parse_development() {
         let prod_genesis = Block::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
@@ -0,0 +1,116 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkos_node_consensus::{serve_verifier, VerifierKey};
+use snarkvm::prelude::{ConsensusMemory, ConsensusStore, Network, Testnet3, VM};
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use std::{
+    io,
+    net::{SocketAddr, TcpListener},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+/// The maximum number of clients served concurrently, when listening.
+const MAX_VERIFIER_CLIENTS: usize = 16;
+
+/// Runs a proof verification service, to which a node offloads the proof verification of blocks.
+#[derive(Debug, Parser)]
+pub struct Verifier {
+    /// Specify the network of the verified transactions
+    #[clap(default_value = "3", long = "network")]
+    pub network: u16,
+    /// Specify the IP address and port to serve on (defaults to serving over stdin and stdout)
+    #[clap(long = "listen")]
+    pub listen: Option<SocketAddr>,
+    /// Specify the key shared with the nodes, which authenticates their requests (required with '--listen')
+    #[clap(long = "key")]
+    pub key: Option<String>,
+}
+
+impl Verifier {
+    /// Serves the verification requests, until the client disconnects (or indefinitely, if listening).
+    pub fn parse(self) -> Result<String> {
+        // Initialize the logger, on stderr, as stdout carries the verdicts when serving over stdin and stdout.
+        let _ = tracing_subscriber::fmt().with_ansi(false).with_writer(io::stderr).try_init();
+
+        match self.network {
+            3 => self.serve::<Testnet3>(),
+            _ => bail!("Invalid network ID specified"),
+        }
+    }
+
+    /// Serves the verification requests for the given network.
+    fn serve<N: Network>(&self) -> Result<String> {
+        // Initialize a stateless VM, as the proofs are verified without reference to the ledger state.
+        let vm = VM::from(ConsensusStore::<N, ConsensusMemory<N>>::open(None)?)?;
+
+        match self.listen {
+            Some(addr) => {
+                let key = match &self.key {
+                    Some(key) => VerifierKey::new(key)?,
+                    None => bail!("Serving on '--listen' requires a shared '--key'"),
+                };
+                let listener = TcpListener::bind(addr)?;
+                info!("Serving proof verification requests on {addr}");
+
+                let num_clients = Arc::new(AtomicUsize::new(0));
+                for stream in listener.incoming() {
+                    let stream = match stream {
+                        Ok(stream) => stream,
+                        Err(error) => {
+                            warn!("Failed to accept a verification client - {error}");
+                            continue;
+                        }
+                    };
+                    // Ensure the number of concurrent clients is bounded.
+                    if num_clients.fetch_add(1, Ordering::SeqCst) >= MAX_VERIFIER_CLIENTS {
+                        num_clients.fetch_sub(1, Ordering::SeqCst);
+                        warn!("Refusing a verification client, as {MAX_VERIFIER_CLIENTS} clients are connected");
+                        continue;
+                    }
+                    let (vm, key, num_clients) = (vm.clone(), key.clone(), num_clients.clone());
+                    thread::spawn(move || {
+                        match stream.try_clone() {
+                            Ok(reader) => {
+                                if let Err(error) = serve_verifier(reader, stream, Some(&key), |transaction| {
+                                    vm.check_transaction(transaction)
+                                }) {
+                                    warn!("Failed to serve a verification client - {error}");
+                                }
+                            }
+                            Err(error) => warn!("Failed to accept a verification client - {error}"),
+                        }
+                        num_clients.fetch_sub(1, Ordering::SeqCst);
+                    });
+                }
+                Ok(String::new())
+            }
+            None => {
+                // Note: The node spawns this command as a child process, and owns its stdin and stdout.
+                serve_verifier(io::stdin().lock(), io::stdout().lock(), None, |transaction| {
+                    vm.check_transaction(transaction)
+                })?;
+                Ok(String::new())
+            }
+        }
+    }
+}
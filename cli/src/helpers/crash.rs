@@ -0,0 +1,137 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::commands::redact;
+
+use parking_lot::Mutex;
+use std::{
+    backtrace::Backtrace,
+    collections::VecDeque,
+    fmt::Write as _,
+    fs,
+    io,
+    panic::PanicInfo,
+    path::PathBuf,
+    sync::atomic::{AtomicU32, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The maximum number of recent log lines included in a crash report.
+const MAX_RECENT_LOGS: usize = 256;
+/// The timeout for delivering a crash report to the webhook.
+const WEBHOOK_TIMEOUT_IN_SECS: u64 = 5;
+
+/// The most recent log lines, in order of arrival.
+static RECENT_LOGS: Mutex<VecDeque<String>> = parking_lot::const_mutex(VecDeque::new());
+/// The latest block height of the node, as of the last update.
+static LATEST_HEIGHT: AtomicU32 = AtomicU32::new(0);
+
+/// A log writer that retains the most recent log lines for the crash reports.
+pub struct RecentLogs;
+
+impl io::Write for RecentLogs {
+    /// Appends the given buffer to the recent log lines, evicting the oldest line if full.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut logs = RECENT_LOGS.lock();
+        if logs.len() >= MAX_RECENT_LOGS {
+            logs.pop_front();
+        }
+        logs.push_back(String::from_utf8_lossy(buf).trim_end().to_string());
+        Ok(buf.len())
+    }
+
+    /// Flushes the log writer (no-op).
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Sets the latest block height of the node, which is included in the crash reports.
+pub fn set_crash_height(height: u32) {
+    LATEST_HEIGHT.store(height, Ordering::Relaxed);
+}
+
+/// Installs a panic hook that restores the terminal from the display, writes a crash report into the given
+/// directory, delivers it to the given webhook (if any), and exits the process.
+pub fn install_crash_handler(report_dir: PathBuf, webhook: Option<String>) {
+    std::panic::set_hook(Box::new(move |info| {
+        // Restore the terminal, so that the crash report is readable and the shell is usable after the exit.
+        let _ = snarkos_display::restore_terminal();
+
+        let report = crash_report(info);
+
+        // Write the crash report.
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or(0);
+        let path = report_dir.join(format!("crash-{timestamp}.txt"));
+        match fs::create_dir_all(&report_dir).and_then(|_| fs::write(&path, &report)) {
+            Ok(()) => eprintln!("\n{report}\nThe crash report was written to '{}'", path.display()),
+            Err(error) => eprintln!("\n{report}\nFailed to write the crash report to '{}' - {error}", path.display()),
+        }
+
+        // Deliver the crash report to the webhook.
+        if let Some(webhook) = &webhook {
+            let payload = serde_json::json!({
+                "version": env!("CARGO_PKG_VERSION"),
+                "panic": redact(&info.to_string()),
+                "height": LATEST_HEIGHT.load(Ordering::Relaxed),
+                "report": report,
+            });
+            let request = ureq::post(webhook).timeout(Duration::from_secs(WEBHOOK_TIMEOUT_IN_SECS));
+            if let Err(error) = request.send_json(payload) {
+                eprintln!("Failed to deliver the crash report to '{webhook}' - {error}");
+            }
+        }
+
+        // Exit, instead of leaving the node running without the panicked task.
+        std::process::exit(1);
+    }));
+}
+
+/// Returns the crash report for the given panic, with any secrets redacted.
+fn crash_report(info: &PanicInfo) -> String {
+    let mut report = String::new();
+    let _ = writeln!(report, "snarkOS v{} crashed", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(report, "Thread: {}", std::thread::current().name().unwrap_or("<unnamed>"));
+    let _ = writeln!(report, "Latest height: {}", LATEST_HEIGHT.load(Ordering::Relaxed));
+    let _ = writeln!(report, "Panic: {info}");
+    let _ = writeln!(report, "\nBacktrace:\n{}", Backtrace::force_capture());
+    let _ = writeln!(report, "Recent logs:");
+    // Note: The panic may have happened while the logs were locked, so do not wait indefinitely.
+    match RECENT_LOGS.try_lock_for(Duration::from_secs(1)) {
+        Some(logs) => logs.iter().for_each(|line| {
+            let _ = writeln!(report, "{line}");
+        }),
+        None => report.push_str("<unavailable>\n"),
+    }
+    redact(&report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_recent_logs() {
+        for i in 0..MAX_RECENT_LOGS + 10 {
+            RecentLogs.write_all(format!("line {i}\n").as_bytes()).unwrap();
+        }
+        let logs = RECENT_LOGS.lock();
+        assert_eq!(logs.len(), MAX_RECENT_LOGS);
+        assert_eq!(logs.front().unwrap(), "line 10");
+        assert_eq!(logs.back().unwrap(), &format!("line {}", MAX_RECENT_LOGS + 9));
+    }
+}
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::helpers::LogWriter;
+use crate::helpers::{LogWriter, RecentLogs};
 
 use crossterm::tty::IsTty;
 use std::{fs::File, io, path::Path};
@@ -35,7 +35,7 @@ pub fn initialize_logger<P: AsRef<Path>>(verbosity: u8, nodisplay: bool, logfile
     };
 
     // Filter out undesirable logs. (unfortunately EnvFilter cannot be cloned)
-    let [filter, filter2] = std::array::from_fn(|_| {
+    let [filter, filter2, filter3] = std::array::from_fn(|_| {
         let filter = EnvFilter::from_default_env()
             .add_directive("mio=off".parse().unwrap())
             .add_directive("tokio_util=off".parse().unwrap())
@@ -88,6 +88,14 @@ pub fn initialize_logger<P: AsRef<Path>>(verbosity: u8, nodisplay: bool, logfile
                 .with_target(verbosity > 2)
                 .with_filter(filter2),
         )
+        .with(
+            // Add layer retaining the recent logs for the crash reports
+            tracing_subscriber::fmt::Layer::default()
+                .with_ansi(false)
+                .with_writer(|| RecentLogs)
+                .with_target(verbosity > 2)
+                .with_filter(filter3),
+        )
         .try_init();
 
     log_receiver
@@ -17,6 +17,9 @@
 mod bech32m;
 pub use bech32m::*;
 
+mod crash;
+pub use crash::*;
+
 mod log_writer;
 use log_writer::*;
 
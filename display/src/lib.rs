@@ -27,12 +27,14 @@ use snarkvm::prelude::Network;
 
 use anyhow::Result;
 use crossterm::{
+    cursor::Show,
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use std::{
     io,
+    sync::atomic::{AtomicBool, Ordering},
     thread,
     time::{Duration, Instant},
 };
@@ -47,6 +49,19 @@ use tui::{
     Terminal,
 };
 
+/// The flag for whether the display has taken over the terminal.
+static IS_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Restores the terminal from the display, if the display has taken it over.
+/// This is safe to call from a panic hook, as it does not depend on the state of the display.
+pub fn restore_terminal() -> Result<()> {
+    if IS_ACTIVE.swap(false, Ordering::SeqCst) {
+        disable_raw_mode()?;
+        execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, Show)?;
+    }
+    Ok(())
+}
+
 pub struct Display<N: Network> {
     /// An instance of the node.
     node: Node<N>,
@@ -62,6 +77,7 @@ impl<N: Network> Display<N> {
     /// Initializes a new display.
     pub fn start(node: Node<N>, log_receiver: Receiver<Vec<u8>>) -> Result<()> {
         // Initialize the display.
+        IS_ACTIVE.store(true, Ordering::SeqCst);
         enable_raw_mode()?;
         let mut stdout = io::stdout();
         execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
@@ -80,9 +96,7 @@ impl<N: Network> Display<N> {
         let res = display.render(&mut terminal);
 
         // Terminate the display.
-        disable_raw_mode()?;
-        execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
-        terminal.show_cursor()?;
+        restore_terminal()?;
 
         // Exit.
         if let Err(err) = res {
@@ -0,0 +1,392 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkos_node_ledger::Ledger;
+use snarkvm::prelude::{Block, ConsensusStorage, FromBytes, Network, ToBytes};
+
+use anyhow::{bail, ensure, Result};
+use core::ops::Range;
+use parking_lot::RwLock;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    fs::{self, File},
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+
+/// The number of blocks per era file.
+pub const BLOCKS_PER_ERA: u32 = 8192;
+/// The file name of the era index.
+pub const ERA_INDEX_FILE: &str = "era.index";
+
+/// An entry in the era index, describing a single era file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EraEntry {
+    /// The first block height in the era (inclusive).
+    pub start: u32,
+    /// The last block height in the era (exclusive).
+    pub end: u32,
+    /// The SHA-256 checksum of the era file, in lowercase hex.
+    pub checksum: String,
+}
+
+impl EraEntry {
+    /// Returns the content-addressed file name of the era file.
+    pub fn file_name(&self) -> String {
+        format!("{:010}-{:010}-{}.era", self.start, self.end, &self.checksum[..16])
+    }
+
+    /// Returns the range of block heights in the era.
+    pub const fn range(&self) -> Range<u32> {
+        self.start..self.end
+    }
+}
+
+impl FromStr for EraEntry {
+    type Err = anyhow::Error;
+
+    /// Parses an era entry from a line of the era index, in the form `start end checksum`.
+    fn from_str(line: &str) -> Result<Self> {
+        let entry = match line.split_whitespace().collect::<Vec<_>>()[..] {
+            [start, end, checksum] => Self { start: start.parse()?, end: end.parse()?, checksum: checksum.to_string() },
+            _ => bail!("Malformed era index entry '{line}'"),
+        };
+        ensure!(entry.start < entry.end, "Invalid era range in '{line}'");
+        // Note: The checksum must be lowercase hex, as it names the era file.
+        let is_hex = entry.checksum.bytes().all(|byte| matches!(byte, b'0'..=b'9' | b'a'..=b'f'));
+        ensure!(entry.checksum.len() == 64 && is_hex, "Invalid era checksum in '{line}'");
+        Ok(entry)
+    }
+}
+
+impl std::fmt::Display for EraEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.start, self.end, self.checksum)
+    }
+}
+
+/// Returns the hex-encoded SHA-256 checksum of the given bytes.
+pub(crate) fn checksum(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().fold(String::with_capacity(64), |mut output, byte| {
+        let _ = write!(output, "{byte:02x}");
+        output
+    })
+}
+
+/// Reads the era index from the given directory, returning an empty index if it does not exist.
+pub fn read_era_index(directory: &Path) -> Result<Vec<EraEntry>> {
+    read_era_index_with_size(directory, BLOCKS_PER_ERA)
+}
+
+/// Reads the era index of eras of the given size from the given directory.
+fn read_era_index_with_size(directory: &Path, blocks_per_era: u32) -> Result<Vec<EraEntry>> {
+    let path = directory.join(ERA_INDEX_FILE);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    parse_era_index_with_size(&fs::read_to_string(path)?, blocks_per_era)
+}
+
+/// Parses the contents of an era index.
+pub(crate) fn parse_era_index(contents: &str) -> Result<Vec<EraEntry>> {
+    parse_era_index_with_size(contents, BLOCKS_PER_ERA)
+}
+
+/// Parses the contents of an index of eras of the given size.
+fn parse_era_index_with_size(contents: &str, blocks_per_era: u32) -> Result<Vec<EraEntry>> {
+    let entries =
+        contents.lines().filter(|line| !line.trim().is_empty()).map(EraEntry::from_str).collect::<Result<Vec<_>>>()?;
+    // Ensure the eras are complete and contiguous, starting from genesis.
+    for (i, entry) in entries.iter().enumerate() {
+        let expected_start = i as u32 * blocks_per_era;
+        ensure!(entry.start == expected_start, "Era index is not contiguous at block {expected_start}");
+        ensure!(
+            entry.end == expected_start + blocks_per_era,
+            "Era index has an incomplete era at block {expected_start}"
+        );
+    }
+    Ok(entries)
+}
+
+/// Exports every complete era of blocks below the given end height into the given directory,
+/// and returns the newly-written entries. Eras that are already in the index are skipped.
+pub fn export_era_files<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    directory: &Path,
+    end_height: u32,
+) -> Result<Vec<EraEntry>> {
+    export_era_files_with_size(ledger, directory, end_height, BLOCKS_PER_ERA)
+}
+
+/// Exports every complete era of the given size below the given end height into the given directory.
+fn export_era_files_with_size<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    directory: &Path,
+    end_height: u32,
+    blocks_per_era: u32,
+) -> Result<Vec<EraEntry>> {
+    // Ensure the end height exists in the ledger.
+    ensure!(end_height <= ledger.latest_height() + 1, "The end height ({end_height}) is above the ledger tip");
+
+    // Prepare the directory and load the existing index.
+    fs::create_dir_all(directory)?;
+    let mut index = read_era_index_with_size(directory, blocks_per_era)?;
+
+    let mut exported = Vec::new();
+    let mut start = index.len() as u32 * blocks_per_era;
+    while start + blocks_per_era <= end_height {
+        let end = start + blocks_per_era;
+
+        // Serialize the blocks, each prefixed by its length.
+        let mut bytes = Vec::new();
+        for height in start..end {
+            let block_bytes = ledger.get_block(height)?.to_bytes_le()?;
+            bytes.extend_from_slice(&(block_bytes.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&block_bytes);
+        }
+
+        // Write the era file, named by its content.
+        let entry = EraEntry { start, end, checksum: checksum(&bytes) };
+        fs::write(directory.join(entry.file_name()), &bytes)?;
+        debug!("Exported blocks {start} to {end} into '{}'", entry.file_name());
+
+        // Append the entry to the index, after the era file is written.
+        index.push(entry.clone());
+        let index_string = index.iter().map(|entry| format!("{entry}\n")).collect::<String>();
+        fs::write(directory.join(ERA_INDEX_FILE), index_string)?;
+
+        exported.push(entry);
+        start = end;
+    }
+    Ok(exported)
+}
+
+/// Deserializes the blocks in the given range of heights, from the contents of an era starting at `era_start`.
+/// The contents may start at any block of the era, as long as `era_start` is the height of that block.
+pub(crate) fn read_blocks<N: Network>(bytes: &[u8], era_start: u32, heights: Range<u32>) -> Result<Vec<Block<N>>> {
+    let mut blocks = Vec::with_capacity(heights.len());
+    let mut cursor = bytes;
+    for height in era_start..heights.end {
+        ensure!(cursor.len() >= 4, "Truncated era file at block {height}");
+        let (length, rest) = cursor.split_at(4);
+        let length = u32::from_le_bytes(length.try_into()?) as usize;
+        ensure!(rest.len() >= length, "Truncated era file at block {height}");
+        let (block_bytes, rest) = rest.split_at(length);
+
+        // Only deserialize the requested blocks.
+        if heights.contains(&height) {
+            let block = Block::read_le(block_bytes)?;
+            ensure!(block.height() == height, "Unexpected block {} in the era file", block.height());
+            blocks.push(block);
+        }
+        cursor = rest;
+    }
+    Ok(blocks)
+}
+
+/// A reader that serves blocks from a directory of era files.
+#[derive(Clone, Debug)]
+pub struct EraReader {
+    /// The directory containing the era files.
+    directory: PathBuf,
+    /// The era index.
+    index: Vec<EraEntry>,
+    /// The byte offsets of the blocks in each era file that was read, followed by the length of the file,
+    /// keyed by the first height of the era. An era file is checked against its checksum once, when its
+    /// offsets are computed, after which only the requested blocks are read from it.
+    offsets: Arc<RwLock<HashMap<u32, Arc<Vec<u64>>>>>,
+}
+
+impl EraReader {
+    /// Opens the era files in the given directory.
+    pub fn open<P: AsRef<Path>>(directory: P) -> Result<Self> {
+        Self::open_with_size(directory, BLOCKS_PER_ERA)
+    }
+
+    /// Opens the era files of the given size in the given directory.
+    fn open_with_size<P: AsRef<Path>>(directory: P, blocks_per_era: u32) -> Result<Self> {
+        let directory = directory.as_ref().to_path_buf();
+        let index = read_era_index_with_size(&directory, blocks_per_era)?;
+        Ok(Self { directory, index, offsets: Default::default() })
+    }
+
+    /// Returns the era index.
+    pub fn index(&self) -> &[EraEntry] {
+        &self.index
+    }
+
+    /// Returns the block height up to which (exclusive) the era files contain blocks.
+    pub fn end_height(&self) -> u32 {
+        self.index.last().map(|entry| entry.end).unwrap_or(0)
+    }
+
+    /// Reads the raw contents of the given era, ensuring they match its checksum.
+    pub fn read_era_bytes(&self, entry: &EraEntry) -> Result<Vec<u8>> {
+        let bytes = fs::read(self.directory.join(entry.file_name()))?;
+        ensure!(checksum(&bytes) == entry.checksum, "Checksum mismatch in era file '{}'", entry.file_name());
+        Ok(bytes)
+    }
+
+    /// Reads the blocks of the given era.
+    pub fn read_era<N: Network>(&self, entry: &EraEntry) -> Result<Vec<Block<N>>> {
+        let bytes = self.read_era_bytes(entry)?;
+        let blocks = read_blocks(&bytes, entry.start, entry.range())?;
+        ensure!(blocks.len() == entry.range().len(), "Incorrect number of blocks in era file '{}'", entry.file_name());
+        Ok(blocks)
+    }
+
+    /// Returns the blocks in the given range, as requested by a peer during sync.
+    /// Only the requested blocks are read and deserialized from the era files.
+    pub fn get_blocks<N: Network>(&self, heights: Range<u32>) -> Result<Vec<Block<N>>> {
+        if heights.end > self.end_height() {
+            bail!("Blocks {} to {} are not in the era files", heights.start, heights.end);
+        }
+
+        let mut blocks = Vec::with_capacity(heights.len());
+        for entry in self.index.iter().filter(|entry| entry.start < heights.end && heights.start < entry.end) {
+            let offsets = self.block_offsets(entry)?;
+            let range = heights.start.max(entry.start)..heights.end.min(entry.end);
+            let (first, last) = ((range.start - entry.start) as usize, (range.end - entry.start) as usize);
+
+            // Read the bytes of the requested blocks from the era file.
+            let mut file = File::open(self.directory.join(entry.file_name()))?;
+            file.seek(SeekFrom::Start(offsets[first]))?;
+            let mut bytes = vec![0u8; (offsets[last] - offsets[first]) as usize];
+            file.read_exact(&mut bytes)?;
+
+            blocks.extend(read_blocks(&bytes, range.start, range)?);
+        }
+        ensure!(blocks.len() == heights.len(), "Missing blocks {} to {} in the era files", heights.start, heights.end);
+        Ok(blocks)
+    }
+
+    /// Returns the blocks in the given range, ensuring they match the block hashes in the given ledger.
+    pub fn get_checked_blocks<N: Network, C: ConsensusStorage<N>>(
+        &self,
+        ledger: &Ledger<N, C>,
+        heights: Range<u32>,
+    ) -> Result<Vec<Block<N>>> {
+        let blocks = self.get_blocks::<N>(heights)?;
+        for block in &blocks {
+            ensure!(
+                block.hash() == ledger.get_hash(block.height())?,
+                "Block {} in the era files does not match the ledger",
+                block.height()
+            );
+        }
+        Ok(blocks)
+    }
+
+    /// Returns the byte offsets of the blocks in the given era, followed by the length of the era file.
+    fn block_offsets(&self, entry: &EraEntry) -> Result<Arc<Vec<u64>>> {
+        if let Some(offsets) = self.offsets.read().get(&entry.start) {
+            return Ok(offsets.clone());
+        }
+
+        // Walk the length prefixes of the blocks, without deserializing them.
+        let bytes = self.read_era_bytes(entry)?;
+        let mut offsets = Vec::with_capacity(entry.range().len() + 1);
+        let mut offset = 0usize;
+        while offset < bytes.len() {
+            ensure!(bytes.len() - offset >= 4, "Truncated era file '{}'", entry.file_name());
+            let length = u32::from_le_bytes(bytes[offset..offset + 4].try_into()?) as usize;
+            ensure!(bytes.len() - offset - 4 >= length, "Truncated era file '{}'", entry.file_name());
+            offsets.push(offset as u64);
+            offset += 4 + length;
+        }
+        ensure!(offsets.len() == entry.range().len(), "Incorrect number of blocks in era file '{}'", entry.file_name());
+        offsets.push(bytes.len() as u64);
+
+        let offsets = Arc::new(offsets);
+        self.offsets.write().insert(entry.start, offsets.clone());
+        Ok(offsets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::{prelude::Testnet3, synthesizer::ConsensusMemory};
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_era_entry_round_trip() {
+        let entry = EraEntry { start: BLOCKS_PER_ERA, end: 2 * BLOCKS_PER_ERA, checksum: checksum(b"snarkOS") };
+        assert_eq!(entry, EraEntry::from_str(&entry.to_string()).unwrap());
+        assert_eq!(entry.file_name(), format!("0000008192-0000016384-{}.era", &entry.checksum[..16]));
+
+        // Ensure malformed entries are rejected.
+        assert!(EraEntry::from_str("0 8192").is_err());
+        assert!(EraEntry::from_str(&format!("8192 0 {}", entry.checksum)).is_err());
+        assert!(EraEntry::from_str("0 8192 deadbeef").is_err());
+        // Ensure a checksum that is not lowercase hex is rejected, including one with a multi-byte character.
+        assert!(EraEntry::from_str(&format!("0 8192 {}", entry.checksum.to_uppercase())).is_err());
+        let checksum = format!("{}é{}", &entry.checksum[..15], &entry.checksum[17..]);
+        assert_eq!(checksum.len(), 64);
+        assert!(EraEntry::from_str(&format!("0 8192 {checksum}")).is_err());
+    }
+
+    #[test]
+    fn test_parse_era_index() {
+        let line = |start: u32, end: u32| format!("{start} {end} {}\n", checksum(&start.to_le_bytes()));
+
+        // Ensure a contiguous index of complete eras is accepted.
+        let contents = line(0, BLOCKS_PER_ERA) + &line(BLOCKS_PER_ERA, 2 * BLOCKS_PER_ERA);
+        assert_eq!(parse_era_index(&contents).unwrap().len(), 2);
+
+        // Ensure a gap, an overlap, and an incomplete era are rejected.
+        assert!(parse_era_index(&(line(0, BLOCKS_PER_ERA) + &line(BLOCKS_PER_ERA + 1, 2 * BLOCKS_PER_ERA))).is_err());
+        assert!(parse_era_index(&(line(0, BLOCKS_PER_ERA) + &line(BLOCKS_PER_ERA, 3 * BLOCKS_PER_ERA))).is_err());
+        assert!(parse_era_index(&line(0, BLOCKS_PER_ERA - 1)).is_err());
+    }
+
+    #[test]
+    fn test_export_and_read_era_files() {
+        let directory = tempfile::tempdir().unwrap();
+
+        // Load a ledger with the genesis block.
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let ledger = Ledger::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::load(genesis.clone(), None).unwrap();
+
+        // Export the ledger into eras of one block.
+        let entries = export_era_files_with_size(&ledger, directory.path(), 1, 1).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(read_era_index_with_size(directory.path(), 1).unwrap(), entries);
+        // Ensure exporting again skips the eras in the index.
+        assert!(export_era_files_with_size(&ledger, directory.path(), 1, 1).unwrap().is_empty());
+
+        // Ensure the blocks are read back, in full and by range.
+        let reader = EraReader::open_with_size(directory.path(), 1).unwrap();
+        assert_eq!(reader.end_height(), 1);
+        assert_eq!(reader.read_era::<CurrentNetwork>(&entries[0]).unwrap(), vec![genesis.clone()]);
+        assert_eq!(reader.get_blocks::<CurrentNetwork>(0..1).unwrap(), vec![genesis.clone()]);
+        assert_eq!(reader.get_checked_blocks(&ledger, 0..1).unwrap(), vec![genesis]);
+        assert!(reader.get_blocks::<CurrentNetwork>(0..2).is_err());
+
+        // Ensure a tampered era file is rejected.
+        let path = directory.path().join(entries[0].file_name());
+        let mut bytes = fs::read(&path).unwrap();
+        *bytes.last_mut().unwrap() ^= 1;
+        fs::write(&path, bytes).unwrap();
+        let reader = EraReader::open_with_size(directory.path(), 1).unwrap();
+        assert!(reader.get_blocks::<CurrentNetwork>(0..1).is_err());
+    }
+}
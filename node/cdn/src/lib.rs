@@ -21,3 +21,9 @@ extern crate tracing;
 
 mod blocks;
 pub use blocks::{load_blocks, sync_ledger_with_cdn};
+
+mod era;
+pub use era::*;
+
+mod remote;
+pub use remote::*;
@@ -0,0 +1,283 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{checksum, parse_era_index, read_blocks, EraEntry, ERA_INDEX_FILE};
+use snarkvm::prelude::{Block, Network};
+
+use anyhow::{bail, ensure, Result};
+use core::ops::Range;
+use parking_lot::{Mutex, RwLock};
+use reqwest::Client;
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// An experimental read-through archive of era files, served from an S3-compatible bucket.
+/// The era files are downloaded on demand, and the most recently used eras are cached on disk.
+/// The bucket is untrusted: every block read from an era file is checked against the block hash in the local ledger.
+#[derive(Clone, Debug)]
+pub struct RemoteEraArchive {
+    /// The base URL of the bucket, in path-style (e.g. `https://s3.example.com/bucket/prefix`).
+    base_url: String,
+    /// The directory of the cached era files.
+    cache_dir: PathBuf,
+    /// The maximum number of era files cached on disk.
+    max_cached_eras: usize,
+    /// The HTTP client.
+    client: Client,
+    /// The era index of the bucket.
+    index: Arc<RwLock<Vec<EraEntry>>>,
+    /// The cached eras, from the least to the most recently used.
+    cached: Arc<Mutex<VecDeque<EraEntry>>>,
+    /// The locks held while downloading an era file, to avoid concurrent downloads of the same era.
+    download_locks: Arc<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>>,
+}
+
+impl RemoteEraArchive {
+    /// Opens the era files in the bucket at the given base URL, caching up to `max_cached_eras` eras in the given directory.
+    pub async fn open(base_url: &str, cache_dir: PathBuf, max_cached_eras: usize) -> Result<Self> {
+        ensure!(max_cached_eras > 0, "The era cache must hold at least one era");
+        fs::create_dir_all(&cache_dir)?;
+
+        let archive = Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            cache_dir,
+            max_cached_eras,
+            client: Client::builder().build()?,
+            index: Default::default(),
+            cached: Default::default(),
+            download_locks: Default::default(),
+        };
+
+        // Fetch the era index of the bucket.
+        archive.refresh_index().await?;
+
+        // Restore the era files that were cached by a previous run.
+        let index = archive.index.read().clone();
+        for entry in index.iter().filter(|entry| archive.cache_dir.join(entry.file_name()).exists()) {
+            archive.touch(entry);
+        }
+
+        info!("Serving blocks 0 to {} from the era files in '{}'", archive.end_height(), archive.base_url);
+        Ok(archive)
+    }
+
+    /// Returns the base URL of the bucket.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Returns the block height up to which (exclusive) the bucket contains blocks.
+    pub fn end_height(&self) -> u32 {
+        self.index.read().last().map(|entry| entry.end).unwrap_or(0)
+    }
+
+    /// Fetches the latest era index from the bucket.
+    pub async fn refresh_index(&self) -> Result<()> {
+        let bytes = self.fetch(ERA_INDEX_FILE).await?;
+        let index = parse_era_index(std::str::from_utf8(&bytes)?)?;
+
+        // Ensure the bucket did not rewrite the eras that were previously indexed.
+        let num_known_eras = self.index.read().len();
+        ensure!(
+            index.len() >= num_known_eras && index[..num_known_eras] == self.index.read()[..],
+            "The era index in '{}' is inconsistent with its previous contents",
+            self.base_url
+        );
+
+        *self.index.write() = index;
+        Ok(())
+    }
+
+    /// Returns the blocks in the given range, downloading the era files that are not cached.
+    /// The blocks are checked against the given block hashes from the local ledger, one per requested height.
+    pub async fn get_blocks<N: Network>(
+        &self,
+        heights: Range<u32>,
+        expected_hashes: &[N::BlockHash],
+    ) -> Result<Vec<Block<N>>> {
+        if heights.end > self.end_height() {
+            bail!("Blocks {} to {} are not in the era files", heights.start, heights.end);
+        }
+        ensure!(expected_hashes.len() == heights.len(), "Expected one block hash per requested block");
+
+        // Retrieve the eras that overlap with the requested range.
+        let entries = self
+            .index
+            .read()
+            .iter()
+            .filter(|entry| entry.start < heights.end && heights.start < entry.end)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let mut blocks = Vec::with_capacity(heights.len());
+        for entry in entries {
+            let bytes = self.read_era_bytes(&entry).await?;
+            let range = heights.start.max(entry.start)..heights.end.min(entry.end);
+            let hashes = &expected_hashes[(range.start - heights.start) as usize..(range.end - heights.start) as usize];
+            match read_blocks::<N>(&bytes, entry.start, range).and_then(|era_blocks| {
+                ensure_block_hashes(&era_blocks, hashes)?;
+                Ok(era_blocks)
+            }) {
+                Ok(era_blocks) => blocks.extend(era_blocks),
+                Err(error) => {
+                    // Evict the era file, so it is downloaded again on the next request.
+                    self.evict(&entry);
+                    bail!("Invalid era file '{}' from '{}' - {error}", entry.file_name(), self.base_url)
+                }
+            }
+        }
+        ensure!(blocks.len() == heights.len(), "Missing blocks {} to {} in the era files", heights.start, heights.end);
+        Ok(blocks)
+    }
+
+    /// Reads the raw contents of the given era, from the cache if present, or otherwise from the bucket.
+    async fn read_era_bytes(&self, entry: &EraEntry) -> Result<Vec<u8>> {
+        let file_name = entry.file_name();
+        let path = self.cache_dir.join(&file_name);
+
+        // Read the era file from the cache, waiting for any ongoing download of the same era to finish.
+        let lock = self.download_locks.lock().entry(file_name.clone()).or_default().clone();
+        let result = {
+            let _guard = lock.lock().await;
+            match fs::read(&path) {
+                Ok(bytes) => {
+                    self.touch(entry);
+                    Ok(bytes)
+                }
+                Err(_) => self.download(entry, &path).await,
+            }
+        };
+
+        // Remove the lock of the era, if no other request is waiting on it.
+        let mut download_locks = self.download_locks.lock();
+        drop(lock);
+        if download_locks.get(&file_name).map(|lock| Arc::strong_count(lock) == 1).unwrap_or(false) {
+            download_locks.remove(&file_name);
+        }
+        result
+    }
+
+    /// Downloads the given era into the cache.
+    async fn download(&self, entry: &EraEntry, path: &Path) -> Result<Vec<u8>> {
+        // Download the era file, and ensure it matches its checksum.
+        let bytes = self.fetch(&entry.file_name()).await?;
+        ensure!(checksum(&bytes) == entry.checksum, "Checksum mismatch in era file '{}'", entry.file_name());
+        debug!("Downloaded era file '{}' from '{}'", entry.file_name(), self.base_url);
+
+        // Cache the era file, writing it atomically to avoid serving a partial file.
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, &bytes)?;
+        fs::rename(&temp_path, path)?;
+        self.touch(entry);
+
+        Ok(bytes)
+    }
+
+    /// Removes the given era from the cache.
+    fn evict(&self, entry: &EraEntry) {
+        self.cached.lock().retain(|cached_entry| cached_entry != entry);
+        if let Err(error) = fs::remove_file(self.cache_dir.join(entry.file_name())) {
+            warn!("Failed to evict era file '{}' from the cache - {error}", entry.file_name());
+        }
+    }
+
+    /// Marks the given era as the most recently used, and evicts the least recently used eras beyond the cache limit.
+    fn touch(&self, entry: &EraEntry) {
+        let mut cached = self.cached.lock();
+        cached.retain(|cached_entry| cached_entry != entry);
+        cached.push_back(entry.clone());
+
+        while cached.len() > self.max_cached_eras {
+            if let Some(evicted) = cached.pop_front() {
+                if let Err(error) = fs::remove_file(self.cache_dir.join(evicted.file_name())) {
+                    warn!("Failed to evict era file '{}' from the cache - {error}", evicted.file_name());
+                }
+            }
+        }
+    }
+
+    /// Fetches the given file from the bucket.
+    async fn fetch(&self, file_name: &str) -> Result<Vec<u8>> {
+        let url = format!("{}/{file_name}", self.base_url);
+        // Fetch the bytes from the given URL.
+        let response = match self.client.get(&url).send().await.and_then(|response| response.error_for_status()) {
+            Ok(response) => response,
+            Err(error) => bail!("Failed to fetch '{url}': {error}"),
+        };
+        match response.bytes().await {
+            Ok(bytes) => Ok(bytes.to_vec()),
+            Err(error) => bail!("Failed to read '{url}': {error}"),
+        }
+    }
+}
+
+/// Ensures the given blocks match the given block hashes.
+fn ensure_block_hashes<N: Network>(blocks: &[Block<N>], hashes: &[N::BlockHash]) -> Result<()> {
+    ensure!(blocks.len() == hashes.len(), "Expected {} blocks, found {}", hashes.len(), blocks.len());
+    for (block, hash) in blocks.iter().zip(hashes) {
+        ensure!(block.hash() == *hash, "Block {} does not match the local block hash", block.height());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BLOCKS_PER_ERA;
+
+    #[test]
+    fn test_remote_era_cache_eviction() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cache_dir = temp_dir.path().to_path_buf();
+
+        let archive = RemoteEraArchive {
+            base_url: "http://localhost".to_string(),
+            cache_dir: cache_dir.clone(),
+            max_cached_eras: 2,
+            client: Client::new(),
+            index: Default::default(),
+            cached: Default::default(),
+            download_locks: Default::default(),
+        };
+
+        // Cache three eras.
+        let entries = (0..3)
+            .map(|i| EraEntry {
+                start: i * BLOCKS_PER_ERA,
+                end: (i + 1) * BLOCKS_PER_ERA,
+                checksum: checksum(&i.to_le_bytes()),
+            })
+            .collect::<Vec<_>>();
+        for entry in &entries[..2] {
+            fs::write(cache_dir.join(entry.file_name()), b"era").unwrap();
+            archive.touch(entry);
+        }
+        // Use the first era again, so the second era becomes the least recently used.
+        archive.touch(&entries[0]);
+        fs::write(cache_dir.join(entries[2].file_name()), b"era").unwrap();
+        archive.touch(&entries[2]);
+
+        // Ensure the least recently used era was evicted.
+        assert_eq!(*archive.cached.lock(), vec![entries[0].clone(), entries[2].clone()]);
+        assert!(cache_dir.join(entries[0].file_name()).exists());
+        assert!(!cache_dir.join(entries[1].file_name()).exists());
+        assert!(cache_dir.join(entries[2].file_name()).exists());
+    }
+}
@@ -0,0 +1,171 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::prelude::Network;
+
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+/// The assume-valid checkpoint, and the chain of block hashes linked back from it.
+struct Checkpoint<N: Network> {
+    /// The height of the checkpoint.
+    height: u32,
+    /// The block hash of the checkpoint.
+    hash: N::BlockHash,
+    /// The hashes of the ancestors of the checkpoint, in descending order of height from `height - 1`.
+    ancestors: Vec<N::BlockHash>,
+}
+
+impl<N: Network> Checkpoint<N> {
+    /// Returns the lowest height that is linked back from the checkpoint, and its block hash.
+    fn lowest(&self) -> (u32, N::BlockHash) {
+        match self.ancestors.last() {
+            Some(hash) => (self.height - self.ancestors.len() as u32, *hash),
+            None => (self.height, self.hash),
+        }
+    }
+
+    /// Returns the linked block hash at the given height, if it is at or below the checkpoint.
+    fn get(&self, height: u32) -> Option<N::BlockHash> {
+        match height == self.height {
+            true => Some(self.hash),
+            false => self.ancestors.get(self.height.checked_sub(height)?.checked_sub(1)? as usize).copied(),
+        }
+    }
+}
+
+/// The assume-valid checkpoint, below which the transaction proofs and coinbase solutions are not re-verified.
+/// A block is only assumed to be valid once its hash is linked back from the checkpoint hash (headers first),
+/// such that a peer cannot serve an unverified chain that does not lead to the checkpoint.
+/// Note: The linked hashes are kept in memory until their blocks are added, at 32 bytes per block.
+#[derive(Clone, Default)]
+pub struct AssumeValid<N: Network> {
+    checkpoint: Arc<RwLock<Option<Checkpoint<N>>>>,
+}
+
+impl<N: Network> AssumeValid<N> {
+    /// Returns the assume-valid checkpoint (height and hash), if it has not yet been reached.
+    pub fn get(&self) -> Option<(u32, N::BlockHash)> {
+        self.checkpoint.read().as_ref().map(|checkpoint| (checkpoint.height, checkpoint.hash))
+    }
+
+    /// Sets the assume-valid checkpoint, which has no linked ancestors yet.
+    pub fn set(&self, height: u32, hash: N::BlockHash) {
+        *self.checkpoint.write() = Some(Checkpoint { height, hash, ancestors: Vec::new() });
+    }
+
+    /// Returns the lowest block (height and hash) that is linked back from the checkpoint, if it is not yet reached.
+    pub fn lowest_linked(&self) -> Option<(u32, N::BlockHash)> {
+        self.checkpoint.read().as_ref().map(Checkpoint::lowest)
+    }
+
+    /// Returns `true` if the block with the given height and hash is the checkpoint, or is linked back from it.
+    pub fn contains(&self, height: u32, hash: &N::BlockHash) -> bool {
+        self.checkpoint.read().as_ref().and_then(|checkpoint| checkpoint.get(height)).as_ref() == Some(hash)
+    }
+
+    /// Links the given blocks (height, hash, and previous hash) back from the checkpoint, down to the block after
+    /// the given latest height, and returns the number of newly linked blocks. The blocks are given in ascending order,
+    /// and their hashes must have been recomputed from their previous hashes and headers by the caller.
+    pub fn link(&self, blocks: &[(u32, N::BlockHash, N::BlockHash)], latest_height: u32) -> usize {
+        let mut checkpoint = self.checkpoint.write();
+        let checkpoint = match checkpoint.as_mut() {
+            Some(checkpoint) => checkpoint,
+            None => return 0,
+        };
+        let mut num_linked = 0;
+        for (height, hash, previous_hash) in blocks.iter().rev() {
+            // Extend the links, if the block is the lowest linked block, and its predecessor is still needed.
+            let (lowest, lowest_hash) = checkpoint.lowest();
+            if *height == lowest && *hash == lowest_hash && *height > latest_height.saturating_add(1) {
+                checkpoint.ancestors.push(*previous_hash);
+                num_linked += 1;
+            }
+        }
+        num_linked
+    }
+
+    /// Removes the links at or below the given height, as its block was added to the ledger,
+    /// and returns `true` if the checkpoint is reached.
+    pub fn advance(&self, height: u32) -> bool {
+        let mut checkpoint = self.checkpoint.write();
+        match checkpoint.as_mut() {
+            Some(inner) if height < inner.height => {
+                // The ancestor at index `i` is at height `inner.height - 1 - i`.
+                inner.ancestors.truncate((inner.height - 1 - height) as usize);
+                false
+            }
+            Some(_) => {
+                *checkpoint = None;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{Field, TestRng, Uniform};
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+    type BlockHash = <CurrentNetwork as Network>::BlockHash;
+
+    #[test]
+    fn test_link() {
+        let rng = &mut TestRng::default();
+
+        // Sample a chain of block hashes, from the genesis block to the checkpoint at block 10.
+        let hashes = (0..=10).map(|_| Field::<CurrentNetwork>::rand(rng).into()).collect::<Vec<BlockHash>>();
+        let block = |height: u32| (height, hashes[height as usize], hashes[height as usize - 1]);
+
+        let assume_valid = AssumeValid::<CurrentNetwork>::default();
+        assert_eq!(assume_valid.lowest_linked(), None);
+        assert_eq!(assume_valid.link(&[block(10)], 0), 0);
+
+        // Set the checkpoint, and ensure only the checkpoint is assumed to be valid.
+        assume_valid.set(10, hashes[10]);
+        assert_eq!(assume_valid.get(), Some((10, hashes[10])));
+        assert_eq!(assume_valid.lowest_linked(), Some((10, hashes[10])));
+        assert!(assume_valid.contains(10, &hashes[10]));
+        assert!(!assume_valid.contains(9, &hashes[9]));
+
+        // Ensure blocks that do not link back to the checkpoint are ignored.
+        let fake = (10, Field::<CurrentNetwork>::rand(rng).into(), hashes[9]);
+        assert_eq!(assume_valid.link(&[fake], 0), 0);
+        assert_eq!(assume_valid.link(&[block(8), block(9)], 0), 0);
+        assert!(!assume_valid.contains(8, &hashes[8]));
+
+        // Link the blocks back from the checkpoint, in ascending order, down to the ledger at block 3.
+        assert_eq!(assume_valid.link(&[block(8), block(9), block(10)], 3), 3);
+        assert_eq!(assume_valid.lowest_linked(), Some((7, hashes[7])));
+        assert_eq!(assume_valid.link(&(2..=7).map(block).collect::<Vec<_>>(), 3), 3);
+        assert_eq!(assume_valid.lowest_linked(), Some((4, hashes[4])));
+        assert!((4..=10).all(|height| assume_valid.contains(height, &hashes[height as usize])));
+        assert!(!assume_valid.contains(3, &hashes[3]));
+        assert!(!assume_valid.contains(11, &hashes[10]));
+
+        // Advance the ledger, and ensure the links are removed, until the checkpoint is reached.
+        assert!(!assume_valid.advance(5));
+        assert_eq!(assume_valid.lowest_linked(), Some((6, hashes[6])));
+        assert!(!assume_valid.contains(5, &hashes[5]));
+        assert!(assume_valid.contains(6, &hashes[6]));
+        assert!(assume_valid.advance(10));
+        assert_eq!(assume_valid.get(), None);
+        assert!(!assume_valid.contains(10, &hashes[10]));
+    }
+}
@@ -0,0 +1,236 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::RoundTimings;
+
+use anyhow::{ensure, Result};
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// The default minimum number of transactions in a block.
+pub const DEFAULT_MIN_BATCH_SIZE: usize = 1;
+/// The default maximum number of transactions in a block.
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 1024;
+/// The default minimum time between blocks, in seconds.
+pub const DEFAULT_MIN_BATCH_TIMEOUT_IN_SECS: u64 = 5;
+/// The default maximum time between blocks, in seconds.
+pub const DEFAULT_MAX_BATCH_TIMEOUT_IN_SECS: u64 = 30;
+/// The initial time between blocks, in seconds.
+const INITIAL_BATCH_TIMEOUT_IN_SECS: u64 = 15;
+/// A round is considered fast if its latency is at most this fraction of the batch timeout.
+const FAST_ROUND_DIVISOR: u64 = 4;
+
+/// The bounds of the adaptive batch size and batch timeout.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BatchConfig {
+    /// The minimum number of transactions in a block.
+    pub min_size: usize,
+    /// The maximum number of transactions in a block.
+    pub max_size: usize,
+    /// The minimum time between blocks, in seconds.
+    pub min_timeout_in_secs: u64,
+    /// The maximum time between blocks, in seconds.
+    pub max_timeout_in_secs: u64,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            min_size: DEFAULT_MIN_BATCH_SIZE,
+            max_size: DEFAULT_MAX_BATCH_SIZE,
+            min_timeout_in_secs: DEFAULT_MIN_BATCH_TIMEOUT_IN_SECS,
+            max_timeout_in_secs: DEFAULT_MAX_BATCH_TIMEOUT_IN_SECS,
+        }
+    }
+}
+
+impl BatchConfig {
+    /// Ensures the bounds are non-empty.
+    pub fn check(&self) -> Result<()> {
+        ensure!(self.min_size > 0, "The minimum batch size must be greater than zero");
+        ensure!(self.min_size <= self.max_size, "The minimum batch size must not exceed the maximum batch size");
+        ensure!(self.min_timeout_in_secs > 0, "The minimum batch timeout must be greater than zero");
+        ensure!(
+            self.min_timeout_in_secs <= self.max_timeout_in_secs,
+            "The minimum batch timeout must not exceed the maximum batch timeout"
+        );
+        Ok(())
+    }
+}
+
+/// The current batch size and batch timeout.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct BatchLimits {
+    /// The maximum number of transactions in the next block.
+    pub size: usize,
+    /// The time between blocks, in seconds.
+    pub timeout_in_secs: u64,
+}
+
+impl BatchLimits {
+    /// Returns the initial limits within the given bounds.
+    fn initial(config: &BatchConfig) -> Self {
+        Self {
+            size: config.max_size,
+            timeout_in_secs: INITIAL_BATCH_TIMEOUT_IN_SECS
+                .clamp(config.min_timeout_in_secs, config.max_timeout_in_secs),
+        }
+    }
+
+    /// Returns the limits, clamped to the given bounds.
+    fn clamp(self, config: &BatchConfig) -> Self {
+        Self {
+            size: self.size.clamp(config.min_size, config.max_size),
+            timeout_in_secs: self.timeout_in_secs.clamp(config.min_timeout_in_secs, config.max_timeout_in_secs),
+        }
+    }
+}
+
+/// The adaptive batch sizes the blocks produced by this node, based on the latency of its recent rounds.
+/// If a round completes well within the batch timeout, and its block was full, the batch size grows and the timeout shrinks.
+/// If a round overruns the batch timeout, the batch size is halved and the timeout is extended.
+#[derive(Clone, Debug)]
+pub struct AdaptiveBatch {
+    /// The bounds, and the current limits.
+    state: Arc<RwLock<(BatchConfig, BatchLimits)>>,
+}
+
+impl Default for AdaptiveBatch {
+    fn default() -> Self {
+        let config = BatchConfig::default();
+        Self { state: Arc::new(RwLock::new((config, BatchLimits::initial(&config)))) }
+    }
+}
+
+impl AdaptiveBatch {
+    /// Sets the bounds of the batch size and batch timeout, clamping the current limits to them.
+    pub fn configure(&self, config: BatchConfig) -> Result<()> {
+        config.check()?;
+        let mut state = self.state.write();
+        *state = (config, state.1.clamp(&config));
+        Ok(())
+    }
+
+    /// Returns the bounds of the batch size and batch timeout.
+    pub fn config(&self) -> BatchConfig {
+        self.state.read().0
+    }
+
+    /// Returns the current batch size and batch timeout.
+    pub fn limits(&self) -> BatchLimits {
+        self.state.read().1
+    }
+
+    /// Returns the maximum number of transactions in the next block.
+    pub fn size(&self) -> usize {
+        self.state.read().1.size
+    }
+
+    /// Returns the time between blocks, in seconds.
+    pub fn timeout_in_secs(&self) -> u64 {
+        self.state.read().1.timeout_in_secs
+    }
+
+    /// Adjusts the limits to the timings of a round in which this node produced a block,
+    /// and returns the new limits if they changed.
+    pub fn observe(&self, timings: &RoundTimings) -> Option<BatchLimits> {
+        let mut state = self.state.write();
+        let (config, limits) = *state;
+        let latency_ms = timings.latency_ms();
+        let timeout_ms = limits.timeout_in_secs.saturating_mul(1000);
+        let is_fast = latency_ms.saturating_mul(FAST_ROUND_DIVISOR) <= timeout_ms;
+        let is_full = timings.num_transactions >= limits.size;
+
+        let next = if latency_ms > timeout_ms {
+            // The round overran the timeout, so back off.
+            BatchLimits {
+                size: limits.size / 2,
+                timeout_in_secs: limits.timeout_in_secs.saturating_add(limits.timeout_in_secs / 2),
+            }
+        } else if is_fast && is_full {
+            // The round was fast and the block was full, so grow the batch.
+            BatchLimits {
+                size: limits.size.saturating_add((limits.size / 4).max(1)),
+                timeout_in_secs: limits.timeout_in_secs.saturating_sub(1),
+            }
+        } else {
+            limits
+        }
+        .clamp(&config);
+
+        match next != limits {
+            true => {
+                state.1 = next;
+                Some(next)
+            }
+            false => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the timings of a round with the given latency and number of transactions.
+    fn sample_timings(latency_ms: u64, num_transactions: usize) -> RoundTimings {
+        RoundTimings {
+            round: 1,
+            height: 1,
+            num_transactions,
+            preparation_ms: 0,
+            proposal_ms: latency_ms,
+            verification_ms: 0,
+            commit_ms: 0,
+            propagation_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_adaptive_batch() {
+        let batch = AdaptiveBatch::default();
+        assert_eq!(batch.limits(), BatchLimits { size: DEFAULT_MAX_BATCH_SIZE, timeout_in_secs: 15 });
+
+        // Ensure invalid bounds are rejected.
+        assert!(batch.configure(BatchConfig { min_size: 0, ..Default::default() }).is_err());
+        assert!(batch.configure(BatchConfig { min_size: 8, max_size: 4, ..Default::default() }).is_err());
+        let inverted = BatchConfig { min_timeout_in_secs: 20, max_timeout_in_secs: 10, ..Default::default() };
+        assert!(batch.configure(inverted).is_err());
+
+        // Ensure the current limits are clamped to the new bounds.
+        let config = BatchConfig { min_size: 4, max_size: 16, min_timeout_in_secs: 10, max_timeout_in_secs: 20 };
+        batch.configure(config).unwrap();
+        assert_eq!(batch.limits(), BatchLimits { size: 16, timeout_in_secs: 15 });
+
+        // Ensure a round within the timeout, that is not fast, keeps the limits.
+        assert_eq!(batch.observe(&sample_timings(10_000, 16)), None);
+        // Ensure a fast round with a block that is not full keeps the limits.
+        assert_eq!(batch.observe(&sample_timings(100, 3)), None);
+
+        // Ensure a slow round halves the size and extends the timeout, down to the bounds.
+        assert_eq!(batch.observe(&sample_timings(16_000, 16)), Some(BatchLimits { size: 8, timeout_in_secs: 20 }));
+        assert_eq!(batch.observe(&sample_timings(21_000, 8)), Some(BatchLimits { size: 4, timeout_in_secs: 20 }));
+        assert_eq!(batch.observe(&sample_timings(21_000, 4)), None);
+
+        // Ensure fast rounds with full blocks grow the size and shorten the timeout, up to the bounds.
+        assert_eq!(batch.observe(&sample_timings(100, 4)), Some(BatchLimits { size: 5, timeout_in_secs: 19 }));
+        for _ in 0..32 {
+            batch.observe(&sample_timings(100, batch.size()));
+        }
+        assert_eq!(batch.limits(), BatchLimits { size: 16, timeout_in_secs: 10 });
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::prelude::{Network, Transaction};
+
+use anyhow::{bail, Result};
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// The maximum number of transactions in a bundle.
+pub const MAX_BUNDLE_TRANSACTIONS: usize = 64;
+/// The maximum number of builders with a pending bundle.
+const MAX_BUILDERS: usize = 64;
+
+/// The policy under which the proposer adopts the bundles of external builders.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BuilderPolicy {
+    /// The minimum total fee of a bundle for it to be adopted, in microcredits.
+    pub min_total_fee: u64,
+}
+
+/// An ordered bundle of transactions for the next block, submitted by an external builder.
+#[derive(Clone, Debug)]
+pub struct Bundle<N: Network> {
+    /// The name of the builder.
+    pub builder: String,
+    /// The height of the block the bundle is for.
+    pub height: u32,
+    /// The transactions, in the order the builder proposes them.
+    pub transactions: Vec<Transaction<N>>,
+    /// The total fee of the transactions, in microcredits.
+    pub total_fee: u64,
+}
+
+/// The receipt of an accepted bundle.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleReceipt {
+    /// The height of the block the bundle is for.
+    pub height: u32,
+    /// The number of transactions in the bundle.
+    pub num_transactions: usize,
+    /// The total fee of the transactions, in microcredits.
+    pub total_fee: u64,
+}
+
+/// The bundles submitted by external builders for the next block, with at most one bundle per builder.
+/// Bundles are only accepted once a builder policy is set.
+#[derive(Clone, Debug)]
+pub struct Bundles<N: Network> {
+    /// The builder policy, if bundles are accepted.
+    policy: Arc<RwLock<Option<BuilderPolicy>>>,
+    /// The pending bundles, by builder.
+    bundles: Arc<RwLock<IndexMap<String, Bundle<N>>>>,
+}
+
+impl<N: Network> Default for Bundles<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network> Bundles<N> {
+    /// Initializes a new set of bundles, which does not accept bundles until a builder policy is set.
+    pub fn new() -> Self {
+        Self { policy: Default::default(), bundles: Default::default() }
+    }
+
+    /// Returns the builder policy, if bundles are accepted.
+    pub fn policy(&self) -> Option<BuilderPolicy> {
+        *self.policy.read()
+    }
+
+    /// Sets the builder policy, which enables the bundles.
+    pub fn set_policy(&self, policy: BuilderPolicy) {
+        *self.policy.write() = Some(policy);
+    }
+
+    /// Returns the number of pending bundles.
+    pub fn num_bundles(&self) -> usize {
+        self.bundles.read().len()
+    }
+
+    /// Inserts the given bundle, replacing the previous bundle of its builder.
+    pub(crate) fn insert(&self, bundle: Bundle<N>) -> Result<()> {
+        if self.policy().is_none() {
+            bail!("This node does not accept bundles from builders");
+        }
+        let mut bundles = self.bundles.write();
+        if bundles.len() >= MAX_BUILDERS && !bundles.contains_key(&bundle.builder) {
+            bail!("Too many builders have a pending bundle");
+        }
+        bundles.insert(bundle.builder.clone(), bundle);
+        Ok(())
+    }
+
+    /// Removes all pending bundles, and returns the bundles for the given height that meet the builder policy,
+    /// from the highest total fee to the lowest.
+    pub(crate) fn take(&self, height: u32) -> Vec<Bundle<N>> {
+        let min_total_fee = match self.policy() {
+            Some(policy) => policy.min_total_fee,
+            None => return vec![],
+        };
+        let mut bundles: Vec<_> = self
+            .bundles
+            .write()
+            .drain(..)
+            .map(|(_, bundle)| bundle)
+            .filter(|bundle| bundle.height == height && bundle.total_fee >= min_total_fee)
+            .collect();
+        bundles.sort_by(|a, b| b.total_fee.cmp(&a.total_fee));
+        bundles
+    }
+
+    /// Removes the bundles for the given height or below.
+    pub(crate) fn prune(&self, height: u32) {
+        self.bundles.write().retain(|_, bundle| bundle.height > height);
+    }
+}
+
+/// Returns `true` if the given transactions share a transaction ID, an input ID, or an output ID.
+pub(crate) fn is_conflicting<N: Network>(a: &Transaction<N>, b: &Transaction<N>) -> bool {
+    a.id() == b.id()
+        || a.input_ids().any(|input_id| b.input_ids().any(|other| other == input_id))
+        || a.output_ids().any(|output_id| b.output_ids().any(|other| other == output_id))
+}
@@ -0,0 +1,259 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::prelude::{Address, Network};
+
+use anyhow::{ensure, Result};
+use indexmap::IndexSet;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    sync::Arc,
+};
+
+/// The kind of change to the beacon set.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitteeChangeKind {
+    /// The address was added to the beacon set.
+    Added,
+    /// The address was removed from the beacon set.
+    Removed,
+}
+
+/// A change to the beacon set.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct CommitteeChange<N: Network> {
+    /// The epoch number at which the change occurred.
+    pub epoch: u32,
+    /// The block height at which the change occurred.
+    pub height: u32,
+    /// The address of the beacon.
+    pub address: Address<N>,
+    /// The kind of change.
+    pub kind: CommitteeChangeKind,
+}
+
+/// The difference in the beacon set between two epochs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct CommitteeDiff<N: Network> {
+    /// The starting epoch number.
+    pub from_epoch: u32,
+    /// The ending epoch number.
+    pub to_epoch: u32,
+    /// The addresses in the beacon set at the ending epoch, but not at the starting epoch.
+    pub added: Vec<Address<N>>,
+    /// The addresses in the beacon set at the starting epoch, but not at the ending epoch.
+    pub removed: Vec<Address<N>>,
+}
+
+/// The history of changes to the beacon set, in order of occurrence.
+#[derive(Clone, Debug)]
+pub struct CommitteeHistory<N: Network> {
+    /// The changes to the beacon set.
+    changes: Arc<RwLock<Vec<CommitteeChange<N>>>>,
+    /// The file the changes are persisted to, if any.
+    path: Arc<Mutex<Option<PathBuf>>>,
+}
+
+impl<N: Network> Default for CommitteeHistory<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network> CommitteeHistory<N> {
+    /// Initializes a new, empty committee history.
+    pub fn new() -> Self {
+        Self { changes: Default::default(), path: Default::default() }
+    }
+
+    /// Loads the changes from the given file, followed by the changes recorded since startup that it does not
+    /// contain yet, and persists any new changes to it.
+    pub fn open(&self, path: PathBuf) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut changes = Vec::new();
+        if path.exists() {
+            for line in fs::read_to_string(&path)?.lines() {
+                match serde_json::from_str::<CommitteeChange<N>>(line) {
+                    Ok(change) => changes.push(change),
+                    Err(error) => warn!("Skipping malformed committee change in '{}' - {error}", path.display()),
+                }
+            }
+        }
+        let mut path_lock = self.path.lock();
+        let mut current = self.changes.write();
+        for change in current.iter() {
+            if !changes.contains(change) {
+                changes.push(change.clone());
+            }
+        }
+        *current = changes;
+        // Rewrite the file with the merged changes, atomically.
+        let mut contents = Vec::new();
+        for change in current.iter() {
+            serde_json::to_writer(&mut contents, change)?;
+            contents.push(b'\n');
+        }
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, contents)?;
+        fs::rename(&temp_path, &path)?;
+        *path_lock = Some(path);
+        Ok(())
+    }
+
+    /// Returns the changes to the beacon set, starting from the given index.
+    pub fn changes_since(&self, index: usize) -> Vec<CommitteeChange<N>> {
+        self.changes.read().iter().skip(index).cloned().collect()
+    }
+
+    /// Returns the number of recorded changes.
+    pub fn len(&self) -> usize {
+        self.changes.read().len()
+    }
+
+    /// Returns `true` if no changes have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.changes.read().is_empty()
+    }
+
+    /// Records the given change to the beacon set, and appends it to the file, if one is set.
+    pub(crate) fn record(&self, change: CommitteeChange<N>) {
+        info!("Beacon '{}' was {:?} at block {} (epoch {})", change.address, change.kind, change.height, change.epoch);
+        let path = self.path.lock();
+        if let Some(path) = &*path {
+            let result = serde_json::to_vec(&change).map_err(anyhow::Error::from).and_then(|mut line| {
+                line.push(b'\n');
+                OpenOptions::new().create(true).append(true).open(path)?.write_all(&line)?;
+                Ok(())
+            });
+            if let Err(error) = result {
+                warn!("Failed to persist the committee change - {error}");
+            }
+        }
+        self.changes.write().push(change);
+    }
+
+    /// Returns the beacon set as of the end of the given epoch.
+    pub fn members_at(&self, epoch: u32) -> IndexSet<Address<N>> {
+        let mut members = IndexSet::new();
+        for change in self.changes.read().iter().take_while(|change| change.epoch <= epoch) {
+            match change.kind {
+                CommitteeChangeKind::Added => members.insert(change.address),
+                CommitteeChangeKind::Removed => members.remove(&change.address),
+            };
+        }
+        members
+    }
+
+    /// Returns the difference in the beacon set between the ends of the two given epochs.
+    pub fn diff(&self, from_epoch: u32, to_epoch: u32) -> Result<CommitteeDiff<N>> {
+        ensure!(from_epoch <= to_epoch, "The starting epoch ({from_epoch}) exceeds the ending epoch ({to_epoch})");
+
+        // Retrieve the beacon sets at both epochs.
+        let before = self.members_at(from_epoch);
+        let after = self.members_at(to_epoch);
+
+        Ok(CommitteeDiff {
+            from_epoch,
+            to_epoch,
+            added: after.difference(&before).copied().collect(),
+            removed: before.difference(&after).copied().collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{PrivateKey, TestRng, Testnet3};
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_committee_diff() {
+        let rng = &mut TestRng::default();
+
+        // Sample the addresses.
+        let mut sample_address = || Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        let (a, b, c) = (sample_address(), sample_address(), sample_address());
+
+        // Record the changes.
+        let history = CommitteeHistory::<CurrentNetwork>::new();
+        let change = |epoch, address, kind| CommitteeChange { epoch, height: epoch * 10, address, kind };
+        history.record(change(0, a, CommitteeChangeKind::Added));
+        history.record(change(1, b, CommitteeChangeKind::Added));
+        history.record(change(2, c, CommitteeChangeKind::Added));
+        history.record(change(2, a, CommitteeChangeKind::Removed));
+        assert_eq!(history.len(), 4);
+        assert_eq!(history.changes_since(3).len(), 1);
+
+        // Ensure the diffs are correct.
+        let diff = history.diff(0, 1).unwrap();
+        assert_eq!(diff.added, vec![b]);
+        assert!(diff.removed.is_empty());
+
+        let diff = history.diff(1, 2).unwrap();
+        assert_eq!(diff.added, vec![c]);
+        assert_eq!(diff.removed, vec![a]);
+
+        let diff = history.diff(2, 2).unwrap();
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+
+        assert!(history.diff(2, 1).is_err());
+    }
+
+    #[test]
+    fn test_committee_history_persistence() {
+        let rng = &mut TestRng::default();
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("committee.jsonl");
+
+        // Sample the addresses.
+        let mut sample_address = || Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        let (a, b) = (sample_address(), sample_address());
+        let change = |epoch, address, kind| CommitteeChange { epoch, height: epoch * 10, address, kind };
+
+        // Record the genesis beacon before the file is opened, and a change after.
+        let history = CommitteeHistory::<CurrentNetwork>::new();
+        history.record(change(0, a, CommitteeChangeKind::Added));
+        history.open(path.clone()).unwrap();
+        history.record(change(1, b, CommitteeChangeKind::Added));
+
+        // Ensure a restarted node reloads the changes, without duplicating the genesis beacon.
+        let history = CommitteeHistory::<CurrentNetwork>::new();
+        history.record(change(0, a, CommitteeChangeKind::Added));
+        history.open(path.clone()).unwrap();
+        assert_eq!(
+            history.changes_since(0),
+            vec![change(0, a, CommitteeChangeKind::Added), change(1, b, CommitteeChangeKind::Added)]
+        );
+        assert_eq!(history.diff(0, 1).unwrap().added, vec![b]);
+
+        // Ensure a malformed line is skipped.
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"not a change\n").unwrap();
+        let history = CommitteeHistory::<CurrentNetwork>::new();
+        history.open(path).unwrap();
+        assert_eq!(history.len(), 2);
+    }
+}
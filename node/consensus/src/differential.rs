@@ -0,0 +1,292 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+use std::{
+    fmt,
+    marker::PhantomData,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// A block validator that can be driven by the differential harness.
+pub trait BlockValidator<N: Network> {
+    /// Returns the name of the validator, used when reporting divergences.
+    fn name(&self) -> String;
+
+    /// Checks the given block is a valid next block.
+    fn check_next_block(&self, block: &Block<N>) -> Result<()>;
+
+    /// Advances the validator to the next block.
+    fn advance_to_next_block(&self, block: &Block<N>) -> Result<()>;
+}
+
+impl<N: Network, C: ConsensusStorage<N>> BlockValidator<N> for Consensus<N, C> {
+    fn name(&self) -> String {
+        format!("snarkos-node-consensus v{}", env!("CARGO_PKG_VERSION"))
+    }
+
+    fn check_next_block(&self, block: &Block<N>) -> Result<()> {
+        Consensus::check_next_block(self, block)
+    }
+
+    fn advance_to_next_block(&self, block: &Block<N>) -> Result<()> {
+        Consensus::advance_to_next_block(self, block)
+    }
+}
+
+/// A set of golden fixtures, holding the expected acceptance of each block of a corpus, in corpus order.
+///
+/// The fixtures are keyed by the index of the block in the corpus, so a corpus that contains the same block
+/// more than once keeps a verdict for each copy. When replayed, the fixtures expect the blocks in corpus order.
+#[derive(Default)]
+pub struct GoldenFixtures<N: Network> {
+    /// The hash and expected acceptance of each block, indexed by its position in the corpus.
+    expectations: Vec<(N::BlockHash, bool)>,
+    /// The index of the next block to be checked against the fixtures.
+    cursor: AtomicUsize,
+}
+
+impl<N: Network> Clone for GoldenFixtures<N> {
+    fn clone(&self) -> Self {
+        Self { expectations: self.expectations.clone(), cursor: AtomicUsize::new(self.cursor.load(Ordering::SeqCst)) }
+    }
+}
+
+impl<N: Network> GoldenFixtures<N> {
+    /// Initializes a new, empty set of golden fixtures.
+    pub fn new() -> Self {
+        Self { expectations: Default::default(), cursor: Default::default() }
+    }
+
+    /// Records the expected acceptance of the next block in the corpus.
+    pub fn insert(&mut self, block_hash: N::BlockHash, is_accepted: bool) {
+        self.expectations.push((block_hash, is_accepted));
+    }
+
+    /// Returns the expected acceptance of the block at the given index in the corpus, if it exists.
+    pub fn get(&self, index: usize) -> Option<(N::BlockHash, bool)> {
+        self.expectations.get(index).copied()
+    }
+
+    /// Returns the number of fixtures.
+    pub fn len(&self) -> usize {
+        self.expectations.len()
+    }
+
+    /// Returns `true` if there are no fixtures.
+    pub fn is_empty(&self) -> bool {
+        self.expectations.is_empty()
+    }
+
+    /// Records the verdicts of the given validator on the given blocks, advancing it on every accepted block.
+    pub fn record<V: BlockValidator<N>>(validator: &V, blocks: &[Block<N>]) -> Result<Self> {
+        let mut fixtures = Self::new();
+        for block in blocks {
+            let is_accepted = validator.check_next_block(block).is_ok();
+            if is_accepted {
+                validator.advance_to_next_block(block)?;
+            }
+            fixtures.insert(block.hash(), is_accepted);
+        }
+        Ok(fixtures)
+    }
+}
+
+impl<N: Network> BlockValidator<N> for GoldenFixtures<N> {
+    fn name(&self) -> String {
+        "golden fixtures".to_string()
+    }
+
+    fn check_next_block(&self, block: &Block<N>) -> Result<()> {
+        // Retrieve the fixture of the next block in the corpus.
+        let index = self.cursor.fetch_add(1, Ordering::SeqCst);
+        match self.get(index) {
+            Some((hash, _)) if hash != block.hash() => {
+                bail!("Block {} ({}) does not match the golden fixture at index {index}", block.height(), block.hash())
+            }
+            Some((_, true)) => Ok(()),
+            Some((_, false)) => bail!("Block {} ({}) is expected to be rejected", block.height(), block.hash()),
+            None => bail!("Block {} ({}) has no golden fixture at index {index}", block.height(), block.hash()),
+        }
+    }
+
+    fn advance_to_next_block(&self, _block: &Block<N>) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The verdict of a validator on a single block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// The block was accepted.
+    Accepted,
+    /// The block was rejected, with the given reason.
+    Rejected(String),
+}
+
+impl Verdict {
+    /// Returns `true` if the block was accepted.
+    pub const fn is_accepted(&self) -> bool {
+        matches!(self, Self::Accepted)
+    }
+}
+
+impl From<Result<()>> for Verdict {
+    fn from(result: Result<()>) -> Self {
+        match result {
+            Ok(()) => Self::Accepted,
+            Err(error) => Self::Rejected(error.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Accepted => write!(f, "accepted"),
+            Self::Rejected(reason) => write!(f, "rejected ({reason})"),
+        }
+    }
+}
+
+/// A block on which the two validators disagree.
+#[derive(Clone, Debug)]
+pub struct Divergence<N: Network> {
+    /// The index of the block in the corpus.
+    pub index: usize,
+    /// The height of the block.
+    pub height: u32,
+    /// The hash of the block.
+    pub hash: N::BlockHash,
+    /// The verdict of the first validator.
+    pub left: Verdict,
+    /// The verdict of the second validator.
+    pub right: Verdict,
+}
+
+/// The report produced by a differential run.
+#[derive(Clone, Debug)]
+pub struct DifferentialReport<N: Network> {
+    /// The name of the first validator.
+    pub left: String,
+    /// The name of the second validator.
+    pub right: String,
+    /// The number of blocks checked by both validators.
+    pub num_checked: usize,
+    /// The number of blocks accepted by both validators.
+    pub num_accepted: usize,
+    /// The divergence, if the validators disagreed on a block.
+    pub divergence: Option<Divergence<N>>,
+}
+
+impl<N: Network> DifferentialReport<N> {
+    /// Returns `true` if the validators agreed on every checked block.
+    pub const fn is_consistent(&self) -> bool {
+        self.divergence.is_none()
+    }
+}
+
+impl<N: Network> fmt::Display for DifferentialReport<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.divergence {
+            None => write!(
+                f,
+                "'{}' and '{}' agree on {} blocks ({} accepted)",
+                self.left, self.right, self.num_checked, self.num_accepted
+            ),
+            Some(divergence) => write!(
+                f,
+                "'{}' and '{}' diverge on block {} ({}) at index {}: {} vs. {}",
+                self.left,
+                self.right,
+                divergence.height,
+                divergence.hash,
+                divergence.index,
+                divergence.left,
+                divergence.right
+            ),
+        }
+    }
+}
+
+/// A harness that feeds the same blocks to two validators, and reports any acceptance divergence.
+pub struct DifferentialHarness<N: Network, A: BlockValidator<N>, B: BlockValidator<N>> {
+    /// The first validator.
+    left: A,
+    /// The second validator.
+    right: B,
+    /// PhantomData.
+    _phantom: PhantomData<N>,
+}
+
+impl<N: Network, A: BlockValidator<N>, B: BlockValidator<N>> DifferentialHarness<N, A, B> {
+    /// Initializes a new differential harness for the given validators.
+    pub fn new(left: A, right: B) -> Self {
+        Self { left, right, _phantom: PhantomData }
+    }
+
+    /// Deserializes the given blocks, and runs them through both validators.
+    pub fn run_serialized<T: AsRef<[u8]>>(&self, corpus: &[T]) -> Result<DifferentialReport<N>> {
+        let blocks = corpus.iter().map(|bytes| Block::from_bytes_le(bytes.as_ref())).collect::<Result<Vec<_>>>()?;
+        self.run(&blocks)
+    }
+
+    /// Runs the given blocks through both validators, in order.
+    ///
+    /// Blocks accepted by both validators are applied to both, so that subsequent blocks
+    /// are checked against the same state. The run halts at the first divergence,
+    /// as the validators no longer share a common state past that point.
+    pub fn run(&self, blocks: &[Block<N>]) -> Result<DifferentialReport<N>> {
+        let mut report = DifferentialReport {
+            left: self.left.name(),
+            right: self.right.name(),
+            num_checked: 0,
+            num_accepted: 0,
+            divergence: None,
+        };
+
+        for (index, block) in blocks.iter().enumerate() {
+            // Check the block with both validators.
+            let left = Verdict::from(self.left.check_next_block(block));
+            let right = Verdict::from(self.right.check_next_block(block));
+            report.num_checked += 1;
+
+            match (left.is_accepted(), right.is_accepted()) {
+                // Advance both validators to the next block.
+                (true, true) => {
+                    self.left.advance_to_next_block(block)?;
+                    self.right.advance_to_next_block(block)?;
+                    report.num_accepted += 1;
+                }
+                // Both validators rejected the block, so there is nothing to apply.
+                (false, false) => trace!("Block {} ({}) was rejected by both validators", block.height(), block.hash()),
+                // The validators disagree on the block.
+                _ => {
+                    let divergence = Divergence { index, height: block.height(), hash: block.hash(), left, right };
+                    report.divergence = Some(divergence);
+                    break;
+                }
+            }
+        }
+
+        match report.is_consistent() {
+            true => debug!("{report}"),
+            false => warn!("{report}"),
+        }
+        Ok(report)
+    }
+}
@@ -0,0 +1,227 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::prelude::{Address, Block, Network, Signature};
+
+use ::time::OffsetDateTime;
+use anyhow::Result;
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    sync::Arc,
+};
+
+/// The maximum number of signed blocks retained to detect equivocations against.
+const MAX_OBSERVED_BLOCKS: usize = 4096;
+/// The maximum number of equivocations retained, after which the oldest evidence is removed.
+const MAX_EVIDENCE: usize = 4096;
+
+/// A block hash, as signed by the block signer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedBlockHash<N: Network> {
+    /// The block height.
+    pub height: u32,
+    /// The block hash.
+    pub hash: N::BlockHash,
+    /// The signature of the signer on the block hash.
+    pub signature: Signature<N>,
+}
+
+impl<N: Network> SignedBlockHash<N> {
+    /// Returns the signed block hash of the given block.
+    pub fn from_block(block: &Block<N>) -> Self {
+        Self { height: block.height(), hash: block.hash(), signature: *block.signature() }
+    }
+
+    /// Returns `true` if the signature is valid for the given signer.
+    pub fn verify(&self, signer: &Address<N>) -> bool {
+        self.signature.verify(signer, &[*self.hash])
+    }
+}
+
+/// The evidence that a signer signed two distinct blocks in the same round.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EquivocationEvidence<N: Network> {
+    /// The address of the equivocating signer.
+    pub signer: Address<N>,
+    /// The round in which the blocks were signed.
+    pub round: u64,
+    /// The block that was observed first.
+    pub first: SignedBlockHash<N>,
+    /// The conflicting block that was observed second.
+    pub second: SignedBlockHash<N>,
+    /// The UNIX timestamp (in seconds) at which the equivocation was detected.
+    pub detected_at: i64,
+}
+
+impl<N: Network> EquivocationEvidence<N> {
+    /// Returns `true` if the evidence is self-contained, i.e. the two block hashes are distinct,
+    /// and both signatures are valid for the signer. Note: The rounds are as reported in the block headers.
+    pub fn verify(&self) -> bool {
+        self.first.hash != self.second.hash && self.first.verify(&self.signer) && self.second.verify(&self.signer)
+    }
+}
+
+/// A detector of conflicting blocks signed by the same signer in the same round,
+/// which persists the evidence of each equivocation.
+#[derive(Clone, Debug)]
+#[allow(clippy::type_complexity)]
+pub struct Equivocations<N: Network> {
+    /// The recently-observed signed blocks, for each signer and round.
+    observed: Arc<RwLock<IndexMap<(Address<N>, u64), SignedBlockHash<N>>>>,
+    /// The evidence of the detected equivocations, for each signer and round, in order of detection.
+    evidence: Arc<RwLock<IndexMap<(Address<N>, u64), EquivocationEvidence<N>>>>,
+    /// The path of the file the evidence is persisted to, if any.
+    path: Arc<RwLock<Option<PathBuf>>>,
+}
+
+impl<N: Network> Default for Equivocations<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network> Equivocations<N> {
+    /// Initializes a new equivocation detector, without persistence.
+    pub fn new() -> Self {
+        Self { observed: Default::default(), evidence: Default::default(), path: Default::default() }
+    }
+
+    /// Loads the persisted evidence from the given file, and persists any new evidence to it.
+    pub fn open(&self, path: PathBuf) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if path.exists() {
+            let mut evidence = IndexMap::new();
+            for line in fs::read_to_string(&path)?.lines() {
+                match serde_json::from_str::<EquivocationEvidence<N>>(line) {
+                    Ok(entry) => {
+                        evidence.entry((entry.signer, entry.round)).or_insert(entry);
+                    }
+                    Err(error) => warn!("Skipping malformed equivocation evidence in '{}' - {error}", path.display()),
+                }
+            }
+            // Retain the latest evidence, up to the maximum.
+            let num_excess = evidence.len().saturating_sub(MAX_EVIDENCE);
+            evidence.drain(..num_excess);
+            // Rewrite the file with the retained evidence, so that it does not grow without bound.
+            let mut contents = Vec::new();
+            for entry in evidence.values() {
+                serde_json::to_writer(&mut contents, entry)?;
+                contents.push(b'\n');
+            }
+            fs::write(&path, contents)?;
+            *self.evidence.write() = evidence;
+        }
+        *self.path.write() = Some(path);
+        Ok(())
+    }
+
+    /// Returns the number of detected equivocations.
+    pub fn len(&self) -> usize {
+        self.evidence.read().len()
+    }
+
+    /// Returns `true` if no equivocations were detected.
+    pub fn is_empty(&self) -> bool {
+        self.evidence.read().is_empty()
+    }
+
+    /// Returns up to `limit` of the detected equivocations after skipping `offset`, in order of detection,
+    /// optionally for the given signer.
+    pub fn evidence(&self, signer: Option<Address<N>>, offset: usize, limit: usize) -> Vec<EquivocationEvidence<N>> {
+        let evidence = self.evidence.read();
+        evidence
+            .values()
+            .filter(|entry| signer.map_or(true, |signer| entry.signer == signer))
+            .skip(offset)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// Observes the given block, which must be signed by a member of the committee. If the signer has already
+    /// signed a distinct block in the same round, the evidence is recorded and returned.
+    /// Blocks with an invalid signature are ignored.
+    pub fn observe(&self, block: &Block<N>) -> Option<EquivocationEvidence<N>> {
+        let signer = block.signature().to_address();
+        let key = (signer, block.round());
+        let signed = SignedBlockHash::from_block(block);
+
+        // Ensure the block is attributable to the signer, before it is observed.
+        if !signed.verify(&signer) {
+            return None;
+        }
+
+        let first = {
+            let mut observed = self.observed.write();
+            match observed.get(&key) {
+                Some(first) if first.hash == signed.hash => return None,
+                Some(first) => first.clone(),
+                None => {
+                    observed.insert(key, signed);
+                    // Remove the oldest observations, beyond the maximum.
+                    let num_excess = observed.len().saturating_sub(MAX_OBSERVED_BLOCKS);
+                    observed.drain(..num_excess);
+                    return None;
+                }
+            }
+        };
+
+        let evidence = EquivocationEvidence {
+            signer,
+            round: key.1,
+            first,
+            second: signed,
+            detected_at: OffsetDateTime::now_utc().unix_timestamp(),
+        };
+        {
+            let mut entries = self.evidence.write();
+            // Ensure the equivocation has not been reported yet.
+            if entries.contains_key(&key) {
+                return None;
+            }
+            entries.insert(key, evidence.clone());
+            // Remove the oldest evidence, beyond the maximum.
+            let num_excess = entries.len().saturating_sub(MAX_EVIDENCE);
+            entries.drain(..num_excess);
+        }
+        warn!(
+            "Detected an equivocation by '{signer}' in round {} (blocks '{}' and '{}')",
+            evidence.round, evidence.first.hash, evidence.second.hash
+        );
+        if let Err(error) = self.persist(&evidence) {
+            error!("Failed to persist the equivocation evidence - {error}");
+        }
+        Some(evidence)
+    }
+
+    /// Appends the given evidence to the evidence file, if one is set.
+    fn persist(&self, evidence: &EquivocationEvidence<N>) -> Result<()> {
+        if let Some(path) = &*self.path.read() {
+            let mut line = serde_json::to_vec(evidence)?;
+            line.push(b'\n');
+            OpenOptions::new().create(true).append(true).open(path)?.write_all(&line)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::DEPLOYMENT_FEE_FACTOR;
+use snarkvm::prelude::*;
+
+use anyhow::Result;
+use serde::Serialize;
+
+/// The number of recent blocks whose transactions are sampled to estimate the priority fee.
+pub const FEE_ESTIMATE_WINDOW: u32 = 20;
+
+/// The breakdown of the fee paid by a transaction, in microcredits.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FeeBreakdown {
+    /// The minimum fee required by the node, i.e. the sum of the finalize cost and storage cost.
+    pub base: u64,
+    /// The fee paid in excess of the base fee.
+    pub priority: u64,
+    /// The cost of finalizing the transaction.
+    pub finalize_cost: u64,
+    /// The cost of storing the transaction, in proportion to its size in bytes.
+    pub storage_cost: u64,
+}
+
+impl FeeBreakdown {
+    /// Computes the fee breakdown of the given transaction.
+    pub fn new<N: Network>(transaction: &Transaction<N>) -> Result<Self> {
+        // Retrieve the total fee.
+        let total = *transaction.fee()?;
+
+        // Compute the storage cost. Currently, only deployments pay for their storage in bytes.
+        let storage_cost = match transaction {
+            Transaction::Deploy(..) => {
+                u64::try_from(transaction.to_bytes_le()?.len())?.saturating_mul(DEPLOYMENT_FEE_FACTOR)
+            }
+            _ => 0,
+        };
+        // Finalize is not priced by the node yet.
+        let finalize_cost = 0;
+
+        // Compute the base fee, and the priority fee in excess of it.
+        let base = storage_cost.saturating_add(finalize_cost);
+        let priority = total.saturating_sub(base);
+
+        Ok(Self { base, priority, finalize_cost, storage_cost })
+    }
+}
+
+/// A suggested priority fee for a transaction to be included within a target number of blocks, in microcredits.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FeeEstimate {
+    /// The number of blocks within which the transaction should be included.
+    pub target_blocks: u32,
+    /// The suggested priority fee, to be paid on top of the base fee of the transaction.
+    pub priority_fee: u64,
+    /// The number of recently confirmed transactions that were sampled.
+    pub num_confirmed: usize,
+    /// The number of pending transactions that were sampled.
+    pub num_pending: usize,
+}
+
+impl FeeEstimate {
+    /// Suggests a priority fee, given the priority fees of the recently confirmed and pending transactions,
+    /// and the maximum number of transactions per block.
+    ///
+    /// The tighter the target, the higher the percentile of the sampled fees that is suggested. If the pending
+    /// transactions would fill every block up to the target, the suggestion is raised to outbid those that do not fit.
+    pub fn new(target_blocks: u32, confirmed: &[u64], pending: &[u64], block_capacity: usize) -> Self {
+        let target_blocks = target_blocks.max(1);
+
+        // Select the percentile of the sampled fees for the target.
+        let percentile = match target_blocks {
+            1 => 90,
+            2..=3 => 75,
+            4..=10 => 50,
+            _ => 25,
+        };
+        let mut samples = confirmed.iter().chain(pending).copied().collect::<Vec<_>>();
+        samples.sort_unstable();
+        let sampled_fee = match samples.is_empty() {
+            true => 0,
+            false => samples[(samples.len() - 1) * percentile / 100],
+        };
+
+        // Outbid the pending transactions that do not fit in the blocks up to the target.
+        let capacity = block_capacity.saturating_mul(target_blocks as usize);
+        let mut pending_fees = pending.to_vec();
+        pending_fees.sort_unstable_by(|a, b| b.cmp(a));
+        let congestion_fee = match capacity {
+            0 => 0,
+            capacity => pending_fees.get(capacity - 1).map_or(0, |fee| fee.saturating_add(1)),
+        };
+
+        Self {
+            target_blocks,
+            priority_fee: sampled_fee.max(congestion_fee),
+            num_confirmed: confirmed.len(),
+            num_pending: pending.len(),
+        }
+    }
+}
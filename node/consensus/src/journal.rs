@@ -0,0 +1,265 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::CommitteeChangeKind;
+use snarkvm::prelude::{Address, Network};
+
+use ::time::OffsetDateTime;
+use anyhow::{ensure, Result};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// The default maximum size of a journal segment (in bytes).
+pub const DEFAULT_JOURNAL_SEGMENT_BYTES: u64 = 16 * 1024 * 1024;
+/// The default maximum number of journal segments that are retained.
+pub const DEFAULT_JOURNAL_SEGMENTS: usize = 16;
+
+/// The file name prefix of a journal segment.
+const SEGMENT_PREFIX: &str = "journal-";
+/// The file name extension of a journal segment.
+const SEGMENT_EXTENSION: &str = ".jsonl";
+
+/// A consensus-relevant event, as recorded in the journal.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum JournalEvent<N: Network> {
+    /// A block was proposed by this node.
+    #[serde(rename_all = "camelCase")]
+    BlockProposed { height: u32, hash: N::BlockHash, round: u64, num_transactions: usize },
+    /// A block was added to the ledger.
+    BlockAccepted { height: u32, hash: N::BlockHash, round: u64 },
+    /// A block was rejected as the next block.
+    BlockRejected { height: u32, hash: N::BlockHash, reason: String },
+    /// The beacon set was changed.
+    CommitteeChanged { address: Address<N>, change: CommitteeChangeKind },
+    /// A signer was found to sign two distinct blocks in the same round.
+    EquivocationDetected { signer: Address<N>, round: u64 },
+}
+
+/// A journal entry, as written to a segment.
+#[derive(Serialize)]
+struct JournalEntry<'a, N: Network> {
+    /// The UNIX timestamp (in milliseconds) at which the event was recorded.
+    timestamp: i64,
+    /// The event.
+    #[serde(flatten)]
+    event: &'a JournalEvent<N>,
+}
+
+/// A failure to read the ledger while checking a block, which is not recorded as a rejection of the block.
+#[derive(Debug)]
+pub(crate) struct LedgerReadError(pub(crate) anyhow::Error);
+
+impl std::fmt::Display for LedgerReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to read the ledger - {}", self.0)
+    }
+}
+
+impl std::error::Error for LedgerReadError {}
+
+/// The configuration of the consensus journal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JournalConfig {
+    /// The directory of the journal segments.
+    pub directory: PathBuf,
+    /// The maximum size of a segment (in bytes), after which a new segment is started.
+    pub max_segment_bytes: u64,
+    /// The maximum number of segments retained, after which the oldest segments are removed.
+    pub max_segments: usize,
+}
+
+impl JournalConfig {
+    /// Initializes a new journal configuration with the default retention, in the given directory.
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory, max_segment_bytes: DEFAULT_JOURNAL_SEGMENT_BYTES, max_segments: DEFAULT_JOURNAL_SEGMENTS }
+    }
+}
+
+/// An append-only journal of consensus-relevant events, written as JSON lines to a set of rotating segments.
+/// The journal is disabled until it is opened, in which case recording an event is a no-op.
+#[derive(Clone, Default)]
+pub struct Journal {
+    /// The writer of the current segment, if the journal is enabled.
+    writer: Arc<Mutex<Option<SegmentWriter>>>,
+}
+
+impl Journal {
+    /// Opens the journal with the given configuration. Each time the journal is opened, a new segment is started.
+    pub fn open(&self, config: JournalConfig) -> Result<()> {
+        ensure!(config.max_segment_bytes > 0, "The journal segment size must be greater than zero");
+        ensure!(config.max_segments > 0, "The journal must retain at least one segment");
+        fs::create_dir_all(&config.directory)?;
+        // Start a new segment after the latest existing one.
+        let index = journal_segments(&config.directory)?.last().map_or(0, |(index, _)| index + 1);
+        let writer = SegmentWriter::create(config, index)?;
+        writer.prune()?;
+        info!("Journaling consensus events to '{}'", writer.config.directory.display());
+        *self.writer.lock() = Some(writer);
+        Ok(())
+    }
+
+    /// Returns `true` if the journal is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.writer.lock().is_some()
+    }
+
+    /// Records the given event. A failure to write is logged, and does not interrupt consensus.
+    pub fn record<N: Network>(&self, event: JournalEvent<N>) {
+        let mut writer = self.writer.lock();
+        if let Some(writer) = writer.as_mut() {
+            let timestamp = (OffsetDateTime::now_utc().unix_timestamp_nanos() / 1_000_000) as i64;
+            let entry = JournalEntry { timestamp, event: &event };
+            if let Err(error) = writer.append(&entry) {
+                warn!("Failed to record a consensus event in the journal - {error}");
+            }
+        }
+    }
+}
+
+/// The writer of the current journal segment.
+struct SegmentWriter {
+    /// The journal configuration.
+    config: JournalConfig,
+    /// The index of the current segment.
+    index: u64,
+    /// The file of the current segment.
+    file: File,
+    /// The number of bytes written to the current segment.
+    size: u64,
+}
+
+impl SegmentWriter {
+    /// Creates the segment with the given index.
+    fn create(config: JournalConfig, index: u64) -> Result<Self> {
+        let path = segment_path(&config.directory, index);
+        let file = OpenOptions::new().create_new(true).append(true).open(path)?;
+        Ok(Self { config, index, file, size: 0 })
+    }
+
+    /// Appends the given entry to the current segment, starting a new segment if the current one is full.
+    fn append<N: Network>(&mut self, entry: &JournalEntry<N>) -> Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        // Rotate the segment, if the entry would exceed its maximum size.
+        if self.size > 0 && self.size + line.len() as u64 > self.config.max_segment_bytes {
+            *self = Self::create(self.config.clone(), self.index + 1)?;
+            self.prune()?;
+        }
+        // Each entry is written in a single call, so a crash leaves at most one partial line.
+        self.file.write_all(&line)?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// Removes the oldest segments, beyond the maximum number of segments.
+    fn prune(&self) -> Result<()> {
+        let segments = journal_segments(&self.config.directory)?;
+        let num_excess = segments.len().saturating_sub(self.config.max_segments);
+        for (_, path) in segments.into_iter().take(num_excess) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the path of the journal segment with the given index.
+fn segment_path(directory: &Path, index: u64) -> PathBuf {
+    directory.join(format!("{SEGMENT_PREFIX}{index:010}{SEGMENT_EXTENSION}"))
+}
+
+/// Returns the indices and paths of the journal segments in the given directory, from oldest to newest.
+pub fn journal_segments(directory: &Path) -> Result<Vec<(u64, PathBuf)>> {
+    let mut segments = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        let index = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix(SEGMENT_PREFIX))
+            .and_then(|name| name.strip_suffix(SEGMENT_EXTENSION))
+            .and_then(|index| index.parse::<u64>().ok());
+        if let Some(index) = index {
+            segments.push((index, path));
+        }
+    }
+    segments.sort_unstable();
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{Field, TestRng, Testnet3, Uniform};
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_journal_rotation() {
+        let rng = &mut TestRng::default();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let directory = temp_dir.path().join("journal");
+
+        // A disabled journal ignores the events.
+        let journal = Journal::default();
+        let event = |height| JournalEvent::<CurrentNetwork>::BlockAccepted {
+            height,
+            hash: Field::rand(rng).into(),
+            round: height as u64,
+        };
+        journal.record(event(0));
+        assert!(!journal.is_enabled());
+        assert!(!directory.exists());
+
+        // Open the journal with small segments.
+        let config = JournalConfig { directory: directory.clone(), max_segment_bytes: 512, max_segments: 3 };
+        journal.open(config.clone()).unwrap();
+        assert!(journal.is_enabled());
+        for height in 0..50 {
+            journal.record(event(height));
+        }
+
+        // Ensure the oldest segments were removed, and the retained segments are within bounds.
+        let segments = journal_segments(&directory).unwrap();
+        assert_eq!(segments.len(), 3);
+        assert!(segments[0].0 > 0);
+        let mut heights = Vec::new();
+        for (_, path) in &segments {
+            let contents = fs::read_to_string(path).unwrap();
+            assert!(contents.len() as u64 <= config.max_segment_bytes);
+            for line in contents.lines() {
+                let entry: serde_json::Value = serde_json::from_str(line).unwrap();
+                assert_eq!(entry["kind"], "blockAccepted");
+                heights.push(entry["height"].as_u64().unwrap());
+            }
+        }
+        // Ensure the retained events are the latest, in order.
+        assert_eq!(heights.last(), Some(&49));
+        assert!(heights.windows(2).all(|pair| pair[0] + 1 == pair[1]));
+
+        // Reopening the journal starts a new segment.
+        let latest = segments.last().unwrap().0;
+        journal.open(config).unwrap();
+        assert_eq!(journal_segments(&directory).unwrap().last().unwrap().0, latest + 1);
+    }
+}
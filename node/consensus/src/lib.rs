@@ -19,16 +19,65 @@
 #[macro_use]
 extern crate tracing;
 
+mod assume_valid;
+pub use assume_valid::*;
+
+mod batching;
+pub use batching::*;
+
+mod builder;
+pub use builder::*;
+
+mod committee;
+pub use committee::*;
+
+mod differential;
+pub use differential::*;
+
+mod equivocation;
+pub use equivocation::*;
+
+mod fees;
+pub use fees::*;
+
 mod helpers;
 pub use helpers::*;
 
+mod journal;
+pub use journal::*;
+
 mod memory_pool;
 pub use memory_pool::*;
 
+mod pipeline;
+pub use pipeline::*;
+
+mod proposals;
+pub use proposals::*;
+
+mod subscriptions;
+pub use subscriptions::*;
+
+mod transmissions;
+pub use transmissions::*;
+
+mod vectors;
+pub use vectors::*;
+
+mod verifier;
+pub use verifier::*;
+
+mod watch_list;
+pub use watch_list::*;
+
+mod workers;
+pub use workers::*;
+
 #[cfg(test)]
 mod tests;
 
 use snarkos_node_ledger::Ledger;
+use snarkos_node_messages::{SOLUTION_TRANSMISSION_KIND, TRANSACTION_TRANSMISSION_KIND};
 use snarkvm::prelude::*;
 
 use ::time::OffsetDateTime;
@@ -52,9 +101,35 @@ pub struct Consensus<N: Network, C: ConsensusStorage<N>> {
     coinbase_puzzle: CoinbasePuzzle<N>,
     /// The memory pool.
     memory_pool: MemoryPool<N>,
+    /// The bundles submitted by external builders for the next block.
+    bundles: Bundles<N>,
+    /// The registered transmission kinds, and their unconfirmed transmissions.
+    transmissions: Transmissions<N, C>,
     /// The beacons.
     // TODO (howardwu): Update this to retrieve from a beacons store.
     beacons: Arc<RwLock<IndexMap<Address<N>, ()>>>,
+    /// The history of changes to the beacon set.
+    committee_history: CommitteeHistory<N>,
+    /// The watch list.
+    watch_list: WatchList<N>,
+    /// The detector of equivocating block signers.
+    equivocations: Equivocations<N>,
+    /// The cache of the blocks proposed by this node, by round.
+    proposals: ProposalCache<N>,
+    /// The subscription channels.
+    subscriptions: Subscriptions<N>,
+    /// The metrics of the block pipeline.
+    pipeline_metrics: PipelineMetrics,
+    /// The workers that process the unconfirmed transmissions.
+    workers: TransmissionWorkers,
+    /// The adaptive size of the blocks produced by this node, and the time between them.
+    batch: AdaptiveBatch,
+    /// The verifier that offloads the proof verification to a verification service.
+    verifier: ProofVerifier,
+    /// The journal of consensus events.
+    journal: Journal,
+    /// The assume-valid checkpoint, and the block hashes linked back from it, whose proofs are not re-verified.
+    assume_valid: AssumeValid<N>,
     /// The boolean flag for the development mode.
     #[allow(dead_code)]
     is_dev: bool,
@@ -67,19 +142,33 @@ impl<N: Network, C: ConsensusStorage<N>> Consensus<N, C> {
         let coinbase_puzzle = CoinbasePuzzle::<N>::load()?;
 
         // Initialize consensus.
-        let mut consensus = Self {
+        let consensus = Self {
             ledger,
             coinbase_puzzle,
             memory_pool: Default::default(),
+            bundles: Default::default(),
+            transmissions: Default::default(),
             // TODO (howardwu): Update this to retrieve from a validators store.
             beacons: Default::default(),
+            committee_history: Default::default(),
+            watch_list: Default::default(),
+            equivocations: Default::default(),
+            proposals: Default::default(),
+            subscriptions: Default::default(),
+            pipeline_metrics: Default::default(),
+            workers: Default::default(),
+            batch: Default::default(),
+            verifier: Default::default(),
+            journal: Default::default(),
+            assume_valid: Default::default(),
             is_dev,
         };
 
-        // Add the genesis beacon.
+        // Add the genesis beacon, which joined the beacon set at the genesis block.
         let genesis_beacon = consensus.ledger.get_block(0)?.signature().to_address();
-        if !consensus.beacons.read().contains_key(&genesis_beacon) {
-            consensus.add_beacon(genesis_beacon)?;
+        if consensus.beacons.write().insert(genesis_beacon, ()).is_none() {
+            let kind = CommitteeChangeKind::Added;
+            consensus.committee_history.record(CommitteeChange { epoch: 0, height: 0, address: genesis_beacon, kind });
         }
 
         Ok(consensus)
@@ -95,6 +184,7 @@ impl<N: Network, C: ConsensusStorage<N>> Consensus<N, C> {
         if self.beacons.write().insert(address, ()).is_some() {
             bail!("'{address}' is already in the beacon set.")
         } else {
+            self.record_committee_change(address, CommitteeChangeKind::Added);
             Ok(())
         }
     }
@@ -104,25 +194,261 @@ impl<N: Network, C: ConsensusStorage<N>> Consensus<N, C> {
         if self.beacons.write().remove(&address).is_none() {
             bail!("'{address}' is not in the beacon set.")
         } else {
+            self.record_committee_change(address, CommitteeChangeKind::Removed);
             Ok(())
         }
     }
 
+    /// Returns the history of changes to the beacon set.
+    pub const fn committee_history(&self) -> &CommitteeHistory<N> {
+        &self.committee_history
+    }
+
+    /// Returns the difference in the beacon set between the ends of the two given epochs.
+    pub fn get_committee_diff(&self, from_epoch: u32, to_epoch: u32) -> Result<CommitteeDiff<N>> {
+        // Ensure the ending epoch is not in the future.
+        let latest_epoch = self.ledger.latest_epoch_number();
+        ensure!(to_epoch <= latest_epoch, "The ending epoch ({to_epoch}) exceeds the latest epoch ({latest_epoch})");
+        self.committee_history.diff(from_epoch, to_epoch)
+    }
+
+    /// Records a change to the beacon set at the latest block, and notifies the subscribers.
+    fn record_committee_change(&self, address: Address<N>, kind: CommitteeChangeKind) {
+        let change = CommitteeChange {
+            epoch: self.ledger.latest_epoch_number(),
+            height: self.ledger.latest_height(),
+            address,
+            kind,
+        };
+        self.committee_history.record(change.clone());
+        self.subscriptions.notify_committee_change(&change);
+        self.journal.record(JournalEvent::CommitteeChanged { address, change: kind });
+    }
+
+    /// Returns the detector of equivocating block signers.
+    pub const fn equivocations(&self) -> &Equivocations<N> {
+        &self.equivocations
+    }
+
+    /// Checks the given block for an equivocation by its signer, and records any evidence.
+    /// Blocks that are not signed by a beacon are ignored.
+    pub fn check_equivocation(&self, block: &Block<N>) -> Option<EquivocationEvidence<N>> {
+        if !self.beacons.read().contains_key(&block.signature().to_address()) {
+            return None;
+        }
+        let evidence = self.equivocations.observe(block)?;
+        self.journal.record(JournalEvent::EquivocationDetected { signer: evidence.signer, round: evidence.round });
+        Some(evidence)
+    }
+
+    /// Returns the cache of the blocks proposed by this node.
+    pub const fn proposals(&self) -> &ProposalCache<N> {
+        &self.proposals
+    }
+
+    /// Returns the journal of consensus events.
+    pub const fn journal(&self) -> &Journal {
+        &self.journal
+    }
+
+    /// Enables the journal of consensus events, with the given configuration.
+    pub fn set_journal(&self, config: JournalConfig) -> Result<()> {
+        self.journal.open(config)
+    }
+
+    /// Returns the assume-valid checkpoint (height and hash), if it has not yet been reached.
+    pub fn assume_valid(&self) -> Option<(u32, N::BlockHash)> {
+        self.assume_valid.get()
+    }
+
+    /// Returns `true` if the transaction proofs and coinbase solution of the block with the given height and hash
+    /// are assumed to be valid, i.e. the block is the assume-valid checkpoint, or is linked back from it.
+    pub fn is_assumed_valid(&self, height: u32, hash: &N::BlockHash) -> bool {
+        self.assume_valid.contains(height, hash)
+    }
+
+    /// Returns the lowest block (height and hash) that is linked back from the assume-valid checkpoint,
+    /// if the checkpoint has not yet been reached.
+    pub fn lowest_assume_valid_link(&self) -> Option<(u32, N::BlockHash)> {
+        self.assume_valid.lowest_linked()
+    }
+
+    /// Links the given blocks (height, hash, and previous hash) back from the assume-valid checkpoint,
+    /// and returns the number of newly linked blocks. The blocks are given in ascending order, and their
+    /// hashes must have been recomputed from their previous hashes and headers, e.g. by verifying their certificates.
+    pub fn link_assume_valid(&self, blocks: &[(u32, N::BlockHash, N::BlockHash)]) -> usize {
+        self.assume_valid.link(blocks, self.ledger.latest_height())
+    }
+
+    /// Sets the assume-valid checkpoint. The transaction proofs and coinbase solutions of the block at the given
+    /// height, and of each block linked back from it (see [`Self::link_assume_valid`]), are not re-verified,
+    /// and the block at the given height must have the given hash.
+    /// Note: The block hash, signature, transactions root, linkage, and uniqueness checks are still enforced.
+    pub fn set_assume_valid(&self, height: u32, hash: N::BlockHash) -> Result<()> {
+        // If the ledger already reached the checkpoint, ensure it is on the canonical chain.
+        if height <= self.ledger.latest_height() {
+            ensure!(
+                self.ledger.get_hash(height)? == hash,
+                "The assume-valid checkpoint '{hash}' is not the canonical block {height}"
+            );
+            info!("The assume-valid checkpoint '{hash}' is already in the ledger");
+            return Ok(());
+        }
+        info!("Skipping proof verification for blocks linked back from the assume-valid checkpoint '{hash}' (block {height})");
+        self.assume_valid.set(height, hash);
+        Ok(())
+    }
+
+    /// Returns the watch list.
+    pub const fn watch_list(&self) -> &WatchList<N> {
+        &self.watch_list
+    }
+
+    /// Adds the given address to the watch list.
+    pub fn watch_address(&self, address: Address<N>) -> Result<()> {
+        self.watch_list.watch(&self.ledger, address)
+    }
+
+    /// Sets the configuration of the watch list, watching its addresses.
+    pub fn set_watch_list_config(&self, config: WatchListConfig<N>) -> Result<()> {
+        self.watch_list.configure(&self.ledger, config)
+    }
+
+    /// Returns the subscription channels.
+    pub const fn subscriptions(&self) -> &Subscriptions<N> {
+        &self.subscriptions
+    }
+
+    /// Returns the metrics of the block pipeline.
+    pub const fn pipeline_metrics(&self) -> &PipelineMetrics {
+        &self.pipeline_metrics
+    }
+
+    /// Returns the workers that process the unconfirmed transmissions.
+    pub const fn workers(&self) -> &TransmissionWorkers {
+        &self.workers
+    }
+
+    /// Starts the workers that process the unconfirmed transmissions, with the given configuration.
+    pub fn set_worker_config(&self, config: WorkerConfig) -> Result<()> {
+        self.workers.start(config)
+    }
+
+    /// Returns the adaptive size of the blocks produced by this node, and the time between them.
+    pub const fn batch(&self) -> &AdaptiveBatch {
+        &self.batch
+    }
+
+    /// Sets the bounds of the adaptive batch size and batch timeout.
+    pub fn set_batch_config(&self, config: BatchConfig) -> Result<()> {
+        ensure!(
+            config.max_size <= Transactions::<N>::MAX_TRANSACTIONS,
+            "The maximum batch size must not exceed {} transactions",
+            Transactions::<N>::MAX_TRANSACTIONS
+        );
+        self.batch.configure(config)
+    }
+
+    /// Returns the verifier that offloads the proof verification to a verification service.
+    pub const fn verifier(&self) -> &ProofVerifier {
+        &self.verifier
+    }
+
+    /// Offloads the proof verification of the blocks to the verification service with the given configuration.
+    pub fn set_verifier_config(&self, config: VerifierConfig) -> Result<()> {
+        self.verifier.configure(config)
+    }
+
     /// Returns the memory pool.
     pub const fn memory_pool(&self) -> &MemoryPool<N> {
         &self.memory_pool
     }
 
-    /// Adds the given unconfirmed transaction to the memory pool.
-    pub fn add_unconfirmed_transaction(&self, transaction: Transaction<N>) -> Result<()> {
+    /// Returns the registered transmission kinds, and their unconfirmed transmissions.
+    pub const fn transmissions(&self) -> &Transmissions<N, C> {
+        &self.transmissions
+    }
+
+    /// Returns the bundles submitted by external builders.
+    pub const fn bundles(&self) -> &Bundles<N> {
+        &self.bundles
+    }
+
+    /// Checks the given bundle of transactions from an external builder, and holds it for the next block.
+    /// The bundle replaces the previous bundle of the builder, and is adopted at the discretion of the proposer.
+    pub fn submit_bundle(
+        &self,
+        builder: String,
+        height: u32,
+        transactions: Vec<Transaction<N>>,
+    ) -> Result<BundleReceipt> {
+        // Ensure the bundle is for the next block.
+        let next_height = self.ledger.latest_height() + 1;
+        ensure!(height == next_height, "The bundle is for block {height}, but the next block is {next_height}");
+        // Ensure the number of transactions is within bounds.
+        let num_transactions = transactions.len();
+        ensure!(
+            (1..=MAX_BUNDLE_TRANSACTIONS).contains(&num_transactions),
+            "A bundle must contain between 1 and {MAX_BUNDLE_TRANSACTIONS} transactions (found {num_transactions})"
+        );
+        // Ensure the transactions do not conflict with each other.
+        for (i, transaction) in transactions.iter().enumerate() {
+            if transactions[i + 1..].iter().any(|other| is_conflicting(transaction, other)) {
+                bail!("Transaction '{}' conflicts with another transaction in the bundle", transaction.id());
+            }
+        }
+        // Ensure the transactions are well-formed and unique, and compute their total fee.
+        let mut total_fee = 0u64;
+        for transaction in &transactions {
+            self.check_transaction_basic(transaction)?;
+            total_fee = total_fee.saturating_add(*transaction.fee()?);
+        }
+
+        self.bundles.insert(Bundle { builder, height, transactions, total_fee })?;
+        Ok(BundleReceipt { height, num_transactions, total_fee })
+    }
+
+    /// Estimates the priority fee for a transaction to be included within the given number of blocks,
+    /// from the fees of the transactions in the recent blocks and in the memory pool.
+    pub fn estimate_priority_fee(&self, target_blocks: u32) -> Result<FeeEstimate> {
+        // Retrieve the priority fees of the transactions in the recent blocks, excluding the coinbase.
+        let latest_height = self.ledger.latest_height();
+        let start_height = latest_height.saturating_sub(FEE_ESTIMATE_WINDOW - 1);
+        let mut confirmed = Vec::new();
+        for block in self.ledger.get_blocks(start_height..latest_height + 1)? {
+            for transaction in block.transactions().iter().filter(|transaction| !transaction.is_coinbase()) {
+                confirmed.push(FeeBreakdown::new(transaction)?.priority);
+            }
+        }
+        // Retrieve the priority fees of the transactions in the memory pool.
+        let pending = self
+            .memory_pool
+            .unconfirmed_transactions()
+            .iter()
+            .map(|transaction| Ok(FeeBreakdown::new(transaction)?.priority))
+            .collect::<Result<Vec<_>>>()?;
+        // Estimate the priority fee.
+        Ok(FeeEstimate::new(target_blocks, &confirmed, &pending, Transactions::<N>::MAX_TRANSACTIONS))
+    }
+
+    /// Checks the given unconfirmed transaction may be added to the memory pool, without adding it.
+    pub fn check_unconfirmed_transaction(&self, transaction: &Transaction<N>) -> Result<()> {
         // Ensure the transaction is not already in the memory pool.
         if self.memory_pool.contains_unconfirmed_transaction(transaction.id()) {
             bail!("Transaction is already in the memory pool.");
         }
         // Check that the transaction is well-formed and unique.
-        self.check_transaction_basic(&transaction)?;
+        self.check_transaction_basic(transaction)
+    }
+
+    /// Adds the given unconfirmed transaction to the memory pool.
+    pub fn add_unconfirmed_transaction(&self, transaction: Transaction<N>) -> Result<()> {
+        // Check that the transaction may be added to the memory pool.
+        self.check_unconfirmed_transaction(&transaction)?;
         // Insert the transaction to the memory pool.
         self.memory_pool.add_unconfirmed_transaction(&transaction);
+        // Notify the subscribers of the new transaction.
+        self.subscriptions.notify_transaction(&transaction);
 
         Ok(())
     }
@@ -154,6 +480,59 @@ impl<N: Network, C: ConsensusStorage<N>> Consensus<N, C> {
         Ok(())
     }
 
+    /// Registers the given kind of transmission, to be validated, gossiped, and held in the memory pool.
+    pub fn register_transmission_kind(&self, kind: impl TransmissionKind<N, C> + 'static) -> Result<()> {
+        self.transmissions.register(kind)
+    }
+
+    /// Adds the given unconfirmed transmission of the given kind to the memory pool. The solutions and transactions
+    /// are added to the memory pool natively, and the transmissions of the registered kinds to the registry.
+    /// Returns `false` if the transmission is already in the memory pool.
+    pub fn add_unconfirmed_transmission(&self, kind: &str, payload: Vec<u8>) -> Result<bool> {
+        match kind {
+            SOLUTION_TRANSMISSION_KIND => {
+                let solution = ProverSolution::<N>::read_le(&payload[..])?;
+                if self.memory_pool.contains_unconfirmed_solution(solution.commitment()) {
+                    return Ok(false);
+                }
+                self.add_unconfirmed_solution(&solution)?;
+                Ok(true)
+            }
+            TRANSACTION_TRANSMISSION_KIND => {
+                let transaction = Transaction::<N>::read_le(&payload[..])?;
+                if self.memory_pool.contains_unconfirmed_transaction(transaction.id()) {
+                    return Ok(false);
+                }
+                self.add_unconfirmed_transaction(transaction)?;
+                Ok(true)
+            }
+            _ => self.transmissions.add(&self.ledger, kind, payload),
+        }
+    }
+
+    /// Returns the unconfirmed transmissions of the given kind and their fees, as their payloads.
+    /// Note: The prover solutions do not pay a fee.
+    pub fn unconfirmed_transmissions(&self, kind: &str) -> Result<Vec<(Vec<u8>, u64)>> {
+        match kind {
+            SOLUTION_TRANSMISSION_KIND => self
+                .memory_pool
+                .unconfirmed_solutions()
+                .into_iter()
+                .map(|(solution, _)| Ok((solution.to_bytes_le()?, 0)))
+                .collect(),
+            TRANSACTION_TRANSMISSION_KIND => self
+                .memory_pool
+                .unconfirmed_transactions()
+                .into_iter()
+                .map(|transaction| Ok((transaction.to_bytes_le()?, *transaction.fee()?)))
+                .collect(),
+            _ => {
+                ensure!(self.transmissions.is_registered(kind), "The '{kind}' transmission kind is not registered");
+                Ok(self.transmissions.unconfirmed(kind))
+            }
+        }
+    }
+
     /// Returns `true` if the coinbase target is met.
     pub fn is_coinbase_target_met(&self) -> Result<bool> {
         // Retrieve the latest proof target.
@@ -167,11 +546,19 @@ impl<N: Network, C: ConsensusStorage<N>> Consensus<N, C> {
     }
 
     /// Returns a candidate for the next block in the ledger.
+    /// If this node already proposed a block in the next round, the same block is returned.
     pub fn propose_next_block<R: Rng + CryptoRng>(&self, private_key: &PrivateKey<N>, rng: &mut R) -> Result<Block<N>> {
         // Retrieve the latest state root.
         let latest_state_root = *self.ledger.latest_state_root();
         // Retrieve the latest block.
         let latest_block = self.ledger.latest_block();
+
+        // If a block was already proposed in the next round, re-propose it, as signing another would equivocate.
+        if let Some(block) = self.proposals.get(latest_block.round().saturating_add(1), latest_block.hash())? {
+            debug!("Re-proposing block {} ('{}') from the proposal cache", block.height(), block.hash());
+            return Ok(block);
+        }
+
         // Retrieve the latest height.
         let latest_height = latest_block.height();
         // Retrieve the latest total supply in microcredits.
@@ -187,8 +574,8 @@ impl<N: Network, C: ConsensusStorage<N>> Consensus<N, C> {
         // Initialize the new finalize root.
         let finalize_root = Field::zero();
 
-        // Select the transactions from the memory pool.
-        let transactions = self.memory_pool.candidate_transactions(self).into_iter().collect::<Transactions<N>>();
+        // Select the transactions from the builder bundles and the memory pool.
+        let transactions = self.select_transactions(latest_height + 1).into_iter().collect::<Transactions<N>>();
         // Select the prover solutions from the memory pool.
         let prover_solutions =
             self.memory_pool.candidate_solutions(self, latest_height, latest_proof_target, latest_coinbase_target)?;
@@ -337,7 +724,63 @@ impl<N: Network, C: ConsensusStorage<N>> Consensus<N, C> {
         )?;
 
         // Construct the new block.
-        Block::new(private_key, latest_block.hash(), header, transactions, coinbase, rng)
+        let block = Block::new(private_key, latest_block.hash(), header, transactions, coinbase, rng)?;
+
+        // Record the proposed block in the journal.
+        self.journal.record(JournalEvent::BlockProposed {
+            height: block.height(),
+            hash: block.hash(),
+            round: block.round(),
+            num_transactions: block.transactions().len(),
+        });
+
+        Ok(block)
+    }
+
+    /// Returns the transactions for the next block at the given height. If a builder bundle is adopted,
+    /// its transactions lead in the order of the builder, followed by the memory pool candidates that do not conflict with it.
+    /// The memory pool candidates, followed by the transactions that carry the unconfirmed transmissions,
+    /// are limited to the current batch size.
+    fn select_transactions(&self, height: u32) -> Vec<Transaction<N>> {
+        let batch_size = self.batch.size();
+        // Select the candidate transactions from the memory pool.
+        let mut candidates = self.memory_pool.candidate_transactions(self);
+        // Select the transactions that carry the unconfirmed transmissions of the registered kinds.
+        for transaction in self.transmissions.candidate_transactions() {
+            if candidates.iter().any(|candidate| is_conflicting(candidate, &transaction)) {
+                continue;
+            }
+            if self.check_transaction_basic(&transaction).is_ok() {
+                candidates.push(transaction);
+            }
+        }
+        // Adopt the bundle with the highest total fee, that is still valid.
+        for bundle in self.bundles.take(height) {
+            if let Some(transaction) = bundle.transactions.iter().find(|tx| self.check_transaction_basic(tx).is_err()) {
+                debug!(
+                    "Skipping the bundle from builder '{}' (transaction '{}' is invalid)",
+                    bundle.builder,
+                    transaction.id()
+                );
+                continue;
+            }
+            info!(
+                "Adopting the bundle of {} transactions from builder '{}' for block {height}",
+                bundle.transactions.len(),
+                bundle.builder
+            );
+            let mut transactions = bundle.transactions;
+            let num_bundled = transactions.len();
+            for candidate in candidates {
+                if !transactions[..num_bundled].iter().any(|transaction| is_conflicting(transaction, &candidate)) {
+                    transactions.push(candidate);
+                }
+            }
+            transactions.truncate(num_bundled.max(batch_size));
+            return transactions;
+        }
+        candidates.truncate(batch_size);
+        candidates
     }
 
     /// Advances the ledger to the next block.
@@ -357,6 +800,33 @@ impl<N: Network, C: ConsensusStorage<N>> Consensus<N, C> {
             self.memory_pool.clear_invalid_solutions(self);
         }
 
+        // Clear the memory pool of unconfirmed transmissions that are now invalid.
+        self.transmissions.clear_invalid(&self.ledger);
+
+        // Refresh the public balances and mapping values of the watched addresses.
+        self.watch_list.refresh(&self.ledger, block);
+
+        // Remove the builder bundles for this block or below.
+        self.bundles.prune(block.height());
+
+        // Remove the links to this block, and if this block is the assume-valid checkpoint, resume full verification.
+        if self.assume_valid.advance(block.height()) {
+            info!("Reached the assume-valid checkpoint at block {}, resuming full verification", block.height());
+        }
+
+        // Observe the signer of the block, to detect any conflicting block in the same round.
+        self.check_equivocation(block);
+
+        // Record the block in the journal.
+        self.journal.record(JournalEvent::BlockAccepted {
+            height: block.height(),
+            hash: block.hash(),
+            round: block.round(),
+        });
+
+        // Notify the subscribers of the new block.
+        self.subscriptions.notify_block(block);
+
         info!("Advanced to block {}", block.height());
 
         Ok(())
@@ -368,6 +838,8 @@ impl<N: Network, C: ConsensusStorage<N>> Consensus<N, C> {
         self.memory_pool.clear_invalid_solutions(self);
         // Clear the memory pool of unconfirmed transactions that are now invalid.
         self.memory_pool.clear_invalid_transactions(self);
+        // Clear the memory pool of unconfirmed transmissions that are now invalid.
+        self.transmissions.clear_invalid(&self.ledger);
         Ok(())
     }
 
@@ -377,11 +849,37 @@ impl<N: Network, C: ConsensusStorage<N>> Consensus<N, C> {
         self.memory_pool.clear_all_unconfirmed_solutions();
         // Clear the memory pool of unconfirmed transactions that are now invalid.
         self.memory_pool.clear_unconfirmed_transactions();
+        // Clear the memory pool of all unconfirmed transmissions.
+        self.transmissions.clear();
         Ok(())
     }
 
     /// Checks the given block is valid next block.
     pub fn check_next_block(&self, block: &Block<N>) -> Result<()> {
+        self.check_next_block_with(block, false)
+    }
+
+    /// Checks the given block is valid next block, skipping the transaction proofs if `proofs_verified` is set.
+    /// Note: `proofs_verified` must only be set once [`Self::check_transaction_proofs`] has succeeded for the block.
+    pub fn check_next_block_with(&self, block: &Block<N>, proofs_verified: bool) -> Result<()> {
+        // Ensure the block extends the latest block. A stale or duplicate block is not a rejection.
+        self.check_next_block_position(block)?;
+        let result = self.check_next_block_inner(block, proofs_verified);
+        // Record the rejected block in the journal, unless the ledger could not be read.
+        if let Err(error) = &result {
+            if !error.is::<LedgerReadError>() {
+                self.journal.record(JournalEvent::BlockRejected {
+                    height: block.height(),
+                    hash: block.hash(),
+                    reason: error.to_string(),
+                });
+            }
+        }
+        result
+    }
+
+    /// Checks the given block extends the latest block in the ledger.
+    fn check_next_block_position(&self, block: &Block<N>) -> Result<()> {
         // Ensure the previous block hash is correct.
         if self.ledger.latest_hash() != block.previous_hash() {
             bail!("The next block has an incorrect previous block hash")
@@ -392,15 +890,20 @@ impl<N: Network, C: ConsensusStorage<N>> Consensus<N, C> {
             bail!("Block hash '{}' already exists in the ledger", block.hash())
         }
 
-        // Ensure the next block height is correct.
-        if self.ledger.latest_height() > 0 && self.ledger.latest_height() + 1 != block.height() {
-            bail!("The next block has an incorrect block height")
-        }
-
         // Ensure the block height does not already exist.
         if self.ledger.contains_block_height(block.height())? {
             bail!("Block height '{}' already exists in the ledger", block.height())
         }
+        Ok(())
+    }
+
+    /// Checks the given block is valid next block, skipping the transaction proofs if `proofs_verified` is set.
+    /// A failure to read the ledger is returned as a [`LedgerReadError`].
+    fn check_next_block_inner(&self, block: &Block<N>, proofs_verified: bool) -> Result<()> {
+        // Ensure the next block height is correct.
+        if self.ledger.latest_height() > 0 && self.ledger.latest_height() + 1 != block.height() {
+            bail!("The next block has an incorrect block height")
+        }
 
         // TODO (raychu86): Ensure the next round number includes timeouts.
         // Ensure the next round is correct.
@@ -422,16 +925,21 @@ impl<N: Network, C: ConsensusStorage<N>> Consensus<N, C> {
 
         for transaction_id in block.transaction_ids() {
             // Ensure the transaction in the block do not already exist.
-            if self.ledger.contains_transaction_id(transaction_id)? {
+            if self.ledger.contains_transaction_id(transaction_id).map_err(LedgerReadError)? {
                 bail!("Transaction '{transaction_id}' already exists in the ledger")
             }
         }
 
         /* Input */
 
+        // Ensure the block does not spend the same serial number twice.
+        if has_duplicates(block.serial_numbers()) {
+            bail!("Found a duplicate serial number in block {}", block.height())
+        }
+
         // Ensure the ledger does not already contain a given serial numbers.
         for serial_number in block.serial_numbers() {
-            if self.ledger.contains_serial_number(serial_number)? {
+            if self.ledger.contains_serial_number(serial_number).map_err(LedgerReadError)? {
                 bail!("Serial number '{serial_number}' already exists in the ledger")
             }
         }
@@ -440,14 +948,14 @@ impl<N: Network, C: ConsensusStorage<N>> Consensus<N, C> {
 
         // Ensure the ledger does not already contain a given commitments.
         for commitment in block.commitments() {
-            if self.ledger.contains_commitment(commitment)? {
+            if self.ledger.contains_commitment(commitment).map_err(LedgerReadError)? {
                 bail!("Commitment '{commitment}' already exists in the ledger")
             }
         }
 
         // Ensure the ledger does not already contain a given nonces.
         for nonce in block.nonces() {
-            if self.ledger.contains_nonce(nonce)? {
+            if self.ledger.contains_nonce(nonce).map_err(LedgerReadError)? {
                 bail!("Nonce '{nonce}' already exists in the ledger")
             }
         }
@@ -456,7 +964,7 @@ impl<N: Network, C: ConsensusStorage<N>> Consensus<N, C> {
 
         // Ensure the ledger does not already contain a given transition public keys.
         for tpk in block.transition_public_keys() {
-            if self.ledger.contains_tpk(tpk)? {
+            if self.ledger.contains_tpk(tpk).map_err(LedgerReadError)? {
                 bail!("Transition public key '{tpk}' already exists in the ledger")
             }
         }
@@ -568,27 +1076,6 @@ impl<N: Network, C: ConsensusStorage<N>> Consensus<N, C> {
             bail!("Invalid proof target: expected {}, got {}", expected_proof_target, block.proof_target())
         }
 
-        /* Block Hash */
-
-        // Compute the Merkle root of the block header.
-        let header_root = match block.header().to_root() {
-            Ok(root) => root,
-            Err(error) => bail!("Failed to compute the Merkle root of the block header: {error}"),
-        };
-
-        // Check the block hash.
-        match N::hash_bhp1024(&[block.previous_hash().to_bits_le(), header_root.to_bits_le()].concat()) {
-            Ok(candidate_hash) => {
-                // Ensure the block hash matches the one in the block.
-                if candidate_hash != *block.hash() {
-                    bail!("Block {} ({}) has an incorrect block hash.", block.height(), block.hash());
-                }
-            }
-            Err(error) => {
-                bail!("Unable to compute block hash for block {} ({}): {error}", block.height(), block.hash())
-            }
-        };
-
         /* Signature */
 
         // Ensure the block is signed by an authorized beacon.
@@ -597,28 +1084,21 @@ impl<N: Network, C: ConsensusStorage<N>> Consensus<N, C> {
             bail!("Block {} ({}) is signed by an unauthorized beacon ({})", block.height(), block.hash(), signer);
         }
 
-        // Check the signature.
-        if !block.signature().verify(&signer, &[*block.hash()]) {
-            bail!("Invalid signature for block {} ({})", block.height(), block.hash());
-        }
+        /* Integrity */
 
-        /* Transactions */
+        // Ensure the block hash, signature, and transactions root are valid.
+        Self::check_block_integrity(block)?;
 
-        // Compute the transactions root.
-        match block.transactions().to_root() {
-            // Ensure the transactions root matches the one in the block header.
-            Ok(root) => {
-                if root != block.header().transactions_root() {
-                    bail!(
-                        "Block {} ({}) has an incorrect transactions root: expected {}",
-                        block.height(),
-                        block.hash(),
-                        block.header().transactions_root()
-                    );
-                }
+        // Determine if the proofs in this block are assumed to be valid, as it is linked back from the checkpoint.
+        let is_assumed_valid = self.is_assumed_valid(block.height(), &block.hash());
+        // Ensure the block at the assume-valid checkpoint is the trusted one.
+        if let Some((height, hash)) = self.assume_valid.get() {
+            if block.height() == height && block.hash() != hash {
+                bail!("Block {} ({}) conflicts with the assume-valid checkpoint '{hash}'", block.height(), block.hash())
             }
-            Err(error) => bail!("Failed to compute the Merkle root of the block transactions: {error}"),
-        };
+        }
+
+        /* Transactions */
 
         // Ensure the transactions list is not empty.
         if block.transactions().is_empty() {
@@ -632,7 +1112,7 @@ impl<N: Network, C: ConsensusStorage<N>> Consensus<N, C> {
 
         // Ensure each transaction is well-formed and unique.
         cfg_iter!(block.transactions()).try_for_each(|(_, transaction)| {
-            self.check_transaction_basic(transaction)
+            self.check_transaction(transaction, !is_assumed_valid && !proofs_verified)
                 .map_err(|e| anyhow!("Invalid transaction found in the transactions list: {e}"))
         })?;
 
@@ -667,17 +1147,19 @@ impl<N: Network, C: ConsensusStorage<N>> Consensus<N, C> {
             }
             // Ensure the puzzle commitments are new.
             for puzzle_commitment in coinbase.puzzle_commitments() {
-                if self.ledger.contains_puzzle_commitment(&puzzle_commitment)? {
+                if self.ledger.contains_puzzle_commitment(&puzzle_commitment).map_err(LedgerReadError)? {
                     bail!("Puzzle commitment {puzzle_commitment} already exists in the ledger");
                 }
             }
-            // Ensure the coinbase solution is valid.
-            if !self.coinbase_puzzle.verify(
-                coinbase,
-                &self.ledger.latest_epoch_challenge()?,
-                self.ledger.latest_coinbase_target(),
-                self.ledger.latest_proof_target(),
-            )? {
+            // Ensure the coinbase solution is valid, unless it is assumed to be valid.
+            if !is_assumed_valid
+                && !self.coinbase_puzzle.verify(
+                    coinbase,
+                    &self.ledger.latest_epoch_challenge().map_err(LedgerReadError)?,
+                    self.ledger.latest_coinbase_target(),
+                    self.ledger.latest_proof_target(),
+                )?
+            {
                 bail!("Invalid coinbase solution: {:?}", coinbase);
             }
         } else {
@@ -690,8 +1172,93 @@ impl<N: Network, C: ConsensusStorage<N>> Consensus<N, C> {
         Ok(())
     }
 
+    /// Checks the given block is internally consistent, without reference to the ledger state.
+    /// This checks the block hash, the block signature, and the transactions root.
+    pub fn check_block_integrity(block: &Block<N>) -> Result<()> {
+        /* Block Hash */
+
+        // Compute the Merkle root of the block header.
+        let header_root = match block.header().to_root() {
+            Ok(root) => root,
+            Err(error) => bail!("Failed to compute the Merkle root of the block header: {error}"),
+        };
+
+        // Check the block hash.
+        match N::hash_bhp1024(&[block.previous_hash().to_bits_le(), header_root.to_bits_le()].concat()) {
+            Ok(candidate_hash) => {
+                // Ensure the block hash matches the one in the block.
+                if candidate_hash != *block.hash() {
+                    bail!("Block {} ({}) has an incorrect block hash.", block.height(), block.hash());
+                }
+            }
+            Err(error) => {
+                bail!("Unable to compute block hash for block {} ({}): {error}", block.height(), block.hash())
+            }
+        };
+
+        /* Signature */
+
+        // Check the signature.
+        let signer = block.signature().to_address();
+        if !block.signature().verify(&signer, &[*block.hash()]) {
+            bail!("Invalid signature for block {} ({})", block.height(), block.hash());
+        }
+
+        /* Transactions */
+
+        // Compute the transactions root.
+        match block.transactions().to_root() {
+            // Ensure the transactions root matches the one in the block header.
+            Ok(root) => {
+                if root != block.header().transactions_root() {
+                    bail!(
+                        "Block {} ({}) has an incorrect transactions root: expected {}",
+                        block.height(),
+                        block.hash(),
+                        block.header().transactions_root()
+                    );
+                }
+            }
+            Err(error) => bail!("Failed to compute the Merkle root of the block transactions: {error}"),
+        };
+
+        Ok(())
+    }
+
+    /// Checks the transaction proofs in the given block, without reference to the ledger state.
+    pub fn check_transaction_proofs(&self, block: &Block<N>) -> Result<()> {
+        // Offload the transactions that a stateless verification service can check, if one is configured.
+        let (offloaded, local): (Vec<_>, Vec<_>) = match self.verifier.is_enabled() {
+            true => block.transactions().iter().partition(|transaction| is_offloadable(transaction)),
+            false => (Vec::new(), block.transactions().iter().collect()),
+        };
+        // Verify the offloaded transactions with the service, while verifying the others locally.
+        let check_locally = |transactions: &[&Transaction<N>]| {
+            cfg_iter!(transactions).try_for_each(|transaction| self.ledger.vm().check_transaction(transaction))
+        };
+        let verify_offloaded = || match offloaded.is_empty() {
+            true => Some(Ok(())),
+            false => self.verifier.verify(offloaded.iter().copied()),
+        };
+        #[cfg(feature = "parallel")]
+        let (verdict, result) = rayon::join(verify_offloaded, || check_locally(&local));
+        #[cfg(not(feature = "parallel"))]
+        let (verdict, result) = (verify_offloaded(), check_locally(&local));
+        result?;
+        match verdict {
+            Some(verdict) => verdict,
+            // Verify the offloaded transactions locally, if the service failed to respond.
+            None => check_locally(&offloaded),
+        }
+    }
+
     /// Checks the given transaction is well-formed and unique.
     pub fn check_transaction_basic(&self, transaction: &Transaction<N>) -> Result<()> {
+        self.check_transaction(transaction, true)
+    }
+
+    /// Checks the given transaction is well-formed and unique, verifying its proofs if `verify_proofs` is set.
+    fn check_transaction(&self, transaction: &Transaction<N>, verify_proofs: bool) -> Result<()> {
         let transaction_id = transaction.id();
 
         // Ensure the ledger does not already contain the given transaction ID.
@@ -708,17 +1275,16 @@ impl<N: Network, C: ConsensusStorage<N>> Consensus<N, C> {
 
         // TODO (raychu86): Currently ignoring this rule for executions. Revisit this in phase 3.
         // Ensure transactions with a positive balance must pay for its storage in bytes.
-        let fee = transaction.fee()?;
-        if matches!(transaction, Transaction::Deploy(..))
-            && u64::try_from(transaction.to_bytes_le()?.len())?.saturating_mul(DEPLOYMENT_FEE_FACTOR) > *fee
-        {
+        if FeeBreakdown::new(transaction)?.base > *transaction.fee()? {
             bail!("Transaction '{transaction_id}' has insufficient fee to cover its storage in bytes")
         }
 
         /* Proof(s) */
 
         // Ensure the transaction is valid.
-        self.ledger.vm().check_transaction(transaction)?;
+        if verify_proofs {
+            self.ledger.vm().check_transaction(transaction)?;
+        }
 
         /* Input */
 
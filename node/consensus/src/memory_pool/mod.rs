@@ -18,17 +18,34 @@ mod solutions;
 mod transactions;
 
 use crate::{anchor_block_height, Consensus};
-use snarkvm::prelude::{ConsensusStorage, Itertools, Network, ProverSolution, PuzzleCommitment, Transaction};
+use snarkvm::prelude::{ConsensusStorage, Itertools, Network, ProverSolution, PuzzleCommitment, ToBytes, Transaction};
 
 use anyhow::{anyhow, Result};
+use indexmap::IndexMap;
 use parking_lot::RwLock;
+use serde::Serialize;
 use std::{collections::HashMap, sync::Arc};
 
+/// A summary of the unconfirmed transactions in the memory pool.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct MemoryPoolInfo {
+    /// The number of unconfirmed transactions.
+    pub num_transactions: usize,
+    /// The total size of the unconfirmed transactions, in bytes.
+    pub total_bytes: usize,
+    /// The lowest fee paid by an unconfirmed transaction, in microcredits.
+    pub min_fee: Option<u64>,
+    /// The median fee paid by the unconfirmed transactions, in microcredits.
+    pub median_fee: Option<u64>,
+    /// The highest fee paid by an unconfirmed transaction, in microcredits.
+    pub max_fee: Option<u64>,
+}
+
 #[derive(Clone, Debug)]
 #[allow(clippy::type_complexity)]
 pub struct MemoryPool<N: Network> {
-    /// The pool of unconfirmed transactions.
-    unconfirmed_transactions: Arc<RwLock<HashMap<N::TransactionID, Transaction<N>>>>,
+    /// The pool of unconfirmed transactions, in order of arrival.
+    unconfirmed_transactions: Arc<RwLock<IndexMap<N::TransactionID, Transaction<N>>>>,
     /// The pool of unconfirmed solutions and their proof targets.
     unconfirmed_solutions: Arc<RwLock<HashMap<PuzzleCommitment<N>, (ProverSolution<N>, u64)>>>,
 }
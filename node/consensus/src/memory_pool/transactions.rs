@@ -32,6 +32,33 @@ impl<N: Network> MemoryPool<N> {
         self.unconfirmed_transactions.read().values().cloned().collect::<Vec<_>>()
     }
 
+    /// Returns up to `limit` unconfirmed transaction IDs, in order of arrival, starting from `offset`.
+    pub fn unconfirmed_transaction_ids(&self, offset: usize, limit: usize) -> Vec<N::TransactionID> {
+        self.unconfirmed_transactions.read().keys().skip(offset).take(limit).copied().collect()
+    }
+
+    /// Returns a summary of the unconfirmed transactions in the memory pool.
+    pub fn unconfirmed_transactions_info(&self) -> Result<MemoryPoolInfo> {
+        let unconfirmed_transactions = self.unconfirmed_transactions.read();
+
+        // Compute the total size and the fees of the unconfirmed transactions.
+        let mut total_bytes = 0;
+        let mut fees = Vec::with_capacity(unconfirmed_transactions.len());
+        for transaction in unconfirmed_transactions.values() {
+            total_bytes += transaction.to_bytes_le()?.len();
+            fees.push(*transaction.fee()?);
+        }
+        fees.sort_unstable();
+
+        Ok(MemoryPoolInfo {
+            num_transactions: unconfirmed_transactions.len(),
+            total_bytes,
+            min_fee: fees.first().copied(),
+            median_fee: fees.get(fees.len() / 2).copied(),
+            max_fee: fees.last().copied(),
+        })
+    }
+
     /// Returns a candidate set of unconfirmed transactions for inclusion in a block.
     pub fn candidate_transactions<C: ConsensusStorage<N>>(&self, consensus: &Consensus<N, C>) -> Vec<Transaction<N>> {
         // TODO (raychu86): Add more sophisticated logic for transaction selection.
@@ -0,0 +1,393 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::Consensus;
+use snarkvm::prelude::{Block, ConsensusStorage, FromBytes, Network};
+
+use anyhow::{ensure, Result};
+use serde::Serialize;
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError, SyncSender},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// The maximum number of blocks queued in front of each stage of the block pipeline.
+pub const PIPELINE_QUEUE_CAPACITY: usize = 8;
+
+/// The interval at which the block pipeline polls its source, if it is not woken in the meantime.
+const PIPELINE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A stage of the block pipeline.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    /// Deserializes the block, and checks its height.
+    Decode,
+    /// Checks the block hash, signature, and transactions root.
+    HeaderCheck,
+    /// Verifies the transaction proofs.
+    ProofVerification,
+    /// Checks the block against the ledger state.
+    Execute,
+    /// Adds the block to the ledger.
+    /// Note: This stage runs on the thread of the execute stage, as each block is executed against its committed predecessor.
+    Commit,
+}
+
+impl PipelineStage {
+    /// The stages of the block pipeline, in order.
+    pub const ALL: [Self; 5] = [Self::Decode, Self::HeaderCheck, Self::ProofVerification, Self::Execute, Self::Commit];
+
+    /// Returns the name of the stage.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Decode => "decode",
+            Self::HeaderCheck => "header_check",
+            Self::ProofVerification => "proof_verification",
+            Self::Execute => "execute",
+            Self::Commit => "commit",
+        }
+    }
+}
+
+impl fmt::Display for PipelineStage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The counters of a single stage of the block pipeline.
+#[derive(Debug, Default)]
+struct StageCounters {
+    /// The number of blocks waiting in front of the stage.
+    queued: AtomicUsize,
+    /// The number of blocks that passed the stage.
+    processed: AtomicU64,
+    /// The number of blocks that failed the stage.
+    failed: AtomicU64,
+    /// The total time spent in the stage, in microseconds.
+    busy_micros: AtomicU64,
+}
+
+impl StageCounters {
+    /// Records a block leaving the stage, which entered it at the given instant.
+    fn record(&self, start: Instant, is_success: bool) {
+        self.busy_micros.fetch_add(start.elapsed().as_micros() as u64, Ordering::Relaxed);
+        match is_success {
+            true => self.processed.fetch_add(1, Ordering::Relaxed),
+            false => self.failed.fetch_add(1, Ordering::Relaxed),
+        };
+    }
+}
+
+/// A snapshot of the metrics of a single stage of the block pipeline.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct StageMetrics {
+    /// The stage.
+    pub stage: PipelineStage,
+    /// The number of blocks waiting in front of the stage.
+    pub queued: usize,
+    /// The number of blocks that passed the stage.
+    pub processed: u64,
+    /// The number of blocks that failed the stage.
+    pub failed: u64,
+    /// The total time spent in the stage, in microseconds.
+    pub busy_micros: u64,
+}
+
+/// The metrics of the block pipeline.
+#[derive(Clone, Debug, Default)]
+pub struct PipelineMetrics {
+    /// The counters of each stage, in pipeline order.
+    stages: Arc<[StageCounters; 5]>,
+}
+
+impl PipelineMetrics {
+    /// Returns the counters of the given stage.
+    fn stage(&self, stage: PipelineStage) -> &StageCounters {
+        &self.stages[stage as usize]
+    }
+
+    /// Returns a snapshot of the metrics of each stage, in pipeline order.
+    pub fn snapshot(&self) -> Vec<StageMetrics> {
+        PipelineStage::ALL
+            .iter()
+            .map(|stage| {
+                let counters = self.stage(*stage);
+                StageMetrics {
+                    stage: *stage,
+                    queued: counters.queued.load(Ordering::Relaxed),
+                    processed: counters.processed.load(Ordering::Relaxed),
+                    failed: counters.failed.load(Ordering::Relaxed),
+                    busy_micros: counters.busy_micros.load(Ordering::Relaxed),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the stage where the backpressure originates, if any.
+    /// This is the last stage with a full queue, as every stage in front of it is blocked on it.
+    pub fn backpressure(&self) -> Option<PipelineStage> {
+        PipelineStage::ALL
+            .iter()
+            .rev()
+            .find(|stage| self.stage(**stage).queued.load(Ordering::Relaxed) >= PIPELINE_QUEUE_CAPACITY)
+            .copied()
+    }
+}
+
+/// A block submitted to the block pipeline.
+pub enum PipelineInput<N: Network> {
+    /// A serialized block.
+    Encoded(Vec<u8>),
+    /// A deserialized block.
+    Decoded(Block<N>),
+}
+
+/// The block pipeline pulls the next blocks from a source, and verifies and commits them in height order.
+/// Each stage runs on a dedicated thread, and is connected to the next stage by a bounded queue,
+/// such that a slow stage applies backpressure to the stages in front of it, and ultimately to the source.
+pub struct BlockPipeline {
+    /// The sender that wakes the feeder of the pipeline.
+    wake: SyncSender<()>,
+    /// The shutdown flag.
+    shutdown: Arc<AtomicBool>,
+}
+
+impl BlockPipeline {
+    /// Initializes a new block pipeline, which pulls the block at a given height from the `source`,
+    /// and calls `on_commit` with each block that is added to the ledger.
+    pub fn new<N: Network, C: ConsensusStorage<N>>(
+        consensus: Consensus<N, C>,
+        source: impl Fn(u32) -> Option<PipelineInput<N>> + Send + 'static,
+        on_commit: impl Fn(&Block<N>) + Send + 'static,
+    ) -> Result<Self> {
+        let metrics = consensus.pipeline_metrics().clone();
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        // Initialize the queue in front of each stage.
+        let (wake, wake_receiver) = mpsc::sync_channel(1);
+        let (decode_sender, decode_receiver) = mpsc::sync_channel(PIPELINE_QUEUE_CAPACITY);
+        let (header_sender, header_receiver) = mpsc::sync_channel(PIPELINE_QUEUE_CAPACITY);
+        let (proof_sender, proof_receiver) = mpsc::sync_channel(PIPELINE_QUEUE_CAPACITY);
+        let (execute_sender, execute_receiver) = mpsc::sync_channel(PIPELINE_QUEUE_CAPACITY);
+
+        // Spawn the feeder.
+        let feeder = Feeder {
+            consensus: consensus.clone(),
+            metrics: metrics.clone(),
+            in_flight: in_flight.clone(),
+            shutdown: shutdown.clone(),
+        };
+        thread::Builder::new()
+            .name("block-pipeline-feeder".to_string())
+            .spawn(move || feeder.run(source, decode_sender, wake_receiver))?;
+
+        // Spawn the decode stage.
+        let stage = Stage::new(PipelineStage::Decode, &metrics, &in_flight);
+        stage.spawn(decode_receiver, header_sender, |(height, input): (u32, PipelineInput<N>)| {
+            let block = match input {
+                PipelineInput::Encoded(bytes) => Block::from_bytes_le(&bytes)?,
+                PipelineInput::Decoded(block) => block,
+            };
+            ensure!(block.height() == height, "Expected block {height}, found block {}", block.height());
+            Ok(block)
+        })?;
+
+        // Spawn the header check stage.
+        let stage = Stage::new(PipelineStage::HeaderCheck, &metrics, &in_flight);
+        stage.spawn(header_receiver, proof_sender, |block: Block<N>| {
+            Consensus::<N, C>::check_block_integrity(&block)?;
+            Ok(block)
+        })?;
+
+        // Spawn the proof verification stage.
+        let stage = Stage::new(PipelineStage::ProofVerification, &metrics, &in_flight);
+        let proof_consensus = consensus.clone();
+        stage.spawn(proof_receiver, execute_sender, move |block: Block<N>| {
+            // Note: The proofs of a block may not verify ahead of its predecessors, e.g. if it executes
+            // a program that is deployed in a block still in flight, so a failure is deferred to the execute stage.
+            let proofs_verified = match proof_consensus.is_assumed_valid(block.height(), &block.hash()) {
+                true => false,
+                false => match proof_consensus.check_transaction_proofs(&block) {
+                    Ok(()) => true,
+                    Err(error) => {
+                        debug!("Deferring the proof verification of block {} - {error}", block.height());
+                        false
+                    }
+                },
+            };
+            Ok((block, proofs_verified))
+        })?;
+
+        // Spawn the execute and commit stages.
+        let commit_wake = wake.clone();
+        thread::Builder::new().name("block-pipeline-execute".to_string()).spawn(move || {
+            for (block, proofs_verified) in execute_receiver {
+                metrics.stage(PipelineStage::Execute).queued.fetch_sub(1, Ordering::Relaxed);
+
+                // Check the block against the ledger state.
+                let start = Instant::now();
+                let result = consensus.check_next_block_with(&block, proofs_verified);
+                metrics.stage(PipelineStage::Execute).record(start, result.is_ok());
+
+                match result {
+                    Ok(()) => {
+                        // Add the block to the ledger.
+                        let start = Instant::now();
+                        let result = consensus.advance_to_next_block(&block);
+                        metrics.stage(PipelineStage::Commit).record(start, result.is_ok());
+
+                        match result {
+                            Ok(()) => on_commit(&block),
+                            Err(error) => warn!("{error}"),
+                        }
+                    }
+                    Err(error) => warn!("The next block ({}) is invalid - {error}", block.height()),
+                }
+
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                // Wake the feeder, as the source may hold the next block already.
+                let _ = commit_wake.try_send(());
+            }
+        })?;
+
+        Ok(Self { wake, shutdown })
+    }
+
+    /// Wakes the pipeline, to pull the next blocks from the source.
+    pub fn notify(&self) {
+        // Note: This only fails if a wake-up is already pending.
+        let _ = self.wake.try_send(());
+    }
+
+    /// Shuts down the pipeline. The blocks in flight are still committed.
+    pub fn shut_down(&self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+        self.notify();
+    }
+}
+
+/// The feeder pulls the next blocks from the source into the decode stage, in height order.
+struct Feeder<N: Network, C: ConsensusStorage<N>> {
+    /// The consensus module.
+    consensus: Consensus<N, C>,
+    /// The metrics of the pipeline.
+    metrics: PipelineMetrics,
+    /// The number of blocks in the pipeline.
+    in_flight: Arc<AtomicUsize>,
+    /// The shutdown flag.
+    shutdown: Arc<AtomicBool>,
+}
+
+impl<N: Network, C: ConsensusStorage<N>> Feeder<N, C> {
+    /// Runs the feeder until the pipeline is shut down.
+    fn run(
+        self,
+        source: impl Fn(u32) -> Option<PipelineInput<N>>,
+        sender: SyncSender<(u32, PipelineInput<N>)>,
+        wake: Receiver<()>,
+    ) {
+        // The height of the next block to submit.
+        let mut next_height = 0;
+
+        while !self.shutdown.load(Ordering::Relaxed) {
+            // If the pipeline is empty, resume from the latest block in the ledger.
+            // Note: This discards the heights that were submitted after a block that failed.
+            if self.in_flight.load(Ordering::SeqCst) == 0 {
+                next_height = self.consensus.ledger.latest_height() + 1;
+            }
+
+            // Submit the next blocks from the source.
+            while let Some(input) = source(next_height) {
+                self.in_flight.fetch_add(1, Ordering::SeqCst);
+                self.metrics.stage(PipelineStage::Decode).queued.fetch_add(1, Ordering::Relaxed);
+                // Note: This blocks while the decode stage is full.
+                if sender.send((next_height, input)).is_err() {
+                    return;
+                }
+                next_height += 1;
+            }
+
+            // Wait until the pipeline is woken, or the poll interval elapses.
+            if let Err(RecvTimeoutError::Disconnected) = wake.recv_timeout(PIPELINE_POLL_INTERVAL) {
+                return;
+            }
+        }
+    }
+}
+
+/// A stage of the block pipeline that runs on a dedicated thread.
+struct Stage {
+    /// The stage.
+    stage: PipelineStage,
+    /// The next stage.
+    next: PipelineStage,
+    /// The metrics of the pipeline.
+    metrics: PipelineMetrics,
+    /// The number of blocks in the pipeline.
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl Stage {
+    /// Initializes a new stage.
+    fn new(stage: PipelineStage, metrics: &PipelineMetrics, in_flight: &Arc<AtomicUsize>) -> Self {
+        let next = PipelineStage::ALL[stage as usize + 1];
+        Self { stage, next, metrics: metrics.clone(), in_flight: in_flight.clone() }
+    }
+
+    /// Spawns the stage, which applies `process` to each received item, and forwards the output to the next stage.
+    fn spawn<I: Send + 'static, O: Send + 'static>(
+        self,
+        receiver: Receiver<I>,
+        sender: SyncSender<O>,
+        process: impl Fn(I) -> Result<O> + Send + 'static,
+    ) -> Result<()> {
+        thread::Builder::new().name(format!("block-pipeline-{}", self.stage)).spawn(move || {
+            for item in receiver {
+                let counters = self.metrics.stage(self.stage);
+                counters.queued.fetch_sub(1, Ordering::Relaxed);
+
+                // Process the item.
+                let start = Instant::now();
+                let result = process(item);
+                counters.record(start, result.is_ok());
+
+                match result {
+                    Ok(output) => {
+                        self.metrics.stage(self.next).queued.fetch_add(1, Ordering::Relaxed);
+                        // Note: This blocks while the next stage is full.
+                        if sender.send(output).is_err() {
+                            break;
+                        }
+                    }
+                    Err(error) => {
+                        warn!("Block pipeline failed at the {} stage - {error}", self.stage);
+                        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+                    }
+                }
+            }
+        })?;
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::prelude::{Block, FromBytes, Network, ToBytes};
+
+use anyhow::{bail, Result};
+use parking_lot::RwLock;
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+/// The maximum number of rounds for which the signed proposals are retained.
+const MAX_CACHED_PROPOSALS: usize = 16;
+/// The maximum number of attempts to persist a proposal.
+const MAX_PERSIST_ATTEMPTS: usize = 3;
+/// The interval between the attempts to persist a proposal.
+const PERSIST_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The file name prefix of a cached proposal.
+const PROPOSAL_PREFIX: &str = "proposal-";
+/// The file name extension of a cached proposal.
+const PROPOSAL_EXTENSION: &str = ".block";
+
+/// The proposal cache stores the block this node signed in each recent round, and persists it before the block
+/// leaves the node, such that a restarted beacon re-proposes the same block, instead of signing a different block
+/// in the same round (which its peers would report as an equivocation).
+#[derive(Clone, Debug)]
+#[allow(clippy::type_complexity)]
+pub struct ProposalCache<N: Network> {
+    /// The signed proposals, by round.
+    proposals: Arc<RwLock<BTreeMap<u64, Block<N>>>>,
+    /// The directory the proposals are persisted to, if any.
+    directory: Arc<RwLock<Option<PathBuf>>>,
+}
+
+impl<N: Network> Default for ProposalCache<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network> ProposalCache<N> {
+    /// Initializes a new, in-memory proposal cache.
+    pub fn new() -> Self {
+        Self { proposals: Default::default(), directory: Default::default() }
+    }
+
+    /// Loads the proposals persisted in the given directory, and persists any new proposals to it.
+    pub fn open(&self, directory: PathBuf) -> Result<()> {
+        fs::create_dir_all(&directory)?;
+        let mut proposals = BTreeMap::new();
+        for entry in fs::read_dir(&directory)? {
+            let path = entry?.path();
+            let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+            if !name.starts_with(PROPOSAL_PREFIX) || !name.ends_with(PROPOSAL_EXTENSION) {
+                continue;
+            }
+            match Block::<N>::from_bytes_le(&fs::read(&path)?) {
+                Ok(block) => {
+                    proposals.insert(block.round(), block);
+                }
+                Err(error) => warn!("Skipping the malformed proposal in '{}' - {error}", path.display()),
+            }
+        }
+        *self.proposals.write() = proposals;
+        *self.directory.write() = Some(directory);
+        Ok(())
+    }
+
+    /// Returns the number of cached proposals.
+    pub fn len(&self) -> usize {
+        self.proposals.read().len()
+    }
+
+    /// Returns `true` if there are no cached proposals.
+    pub fn is_empty(&self) -> bool {
+        self.proposals.read().is_empty()
+    }
+
+    /// Returns the proposal this node signed in the given round, if any.
+    /// Errors if the proposal extends a block other than the given previous block, as signing another
+    /// proposal in the round would equivocate.
+    pub fn get(&self, round: u64, previous_hash: N::BlockHash) -> Result<Option<Block<N>>> {
+        match self.proposals.read().get(&round) {
+            Some(block) if block.previous_hash() == previous_hash => Ok(Some(block.clone())),
+            Some(block) => bail!(
+                "Refusing to sign a second proposal in round {round} (already signed block '{}' on '{}')",
+                block.hash(),
+                block.previous_hash()
+            ),
+            None => Ok(None),
+        }
+    }
+
+    /// Stores the given signed proposal, and persists it before returning.
+    /// Errors if a different proposal is already stored for the round of the block.
+    pub fn insert(&self, block: &Block<N>) -> Result<()> {
+        let round = block.round();
+        let mut proposals = self.proposals.write();
+        match proposals.get(&round) {
+            Some(existing) if existing.hash() == block.hash() => return Ok(()),
+            Some(existing) => bail!("Already signed block '{}' in round {round}", existing.hash()),
+            None => (),
+        }
+
+        if let Some(directory) = &*self.directory.read() {
+            let bytes = block.to_bytes_le()?;
+            // Retry a failed write, as the block must not leave the node before it is persisted.
+            let mut attempt = 1;
+            while let Err(error) = Self::persist(directory, round, &bytes) {
+                if attempt >= MAX_PERSIST_ATTEMPTS {
+                    bail!("Failed to persist the proposal of round {round} - {error}");
+                }
+                warn!("Failed to persist the proposal of round {round} (attempt {attempt}) - {error}");
+                attempt += 1;
+                std::thread::sleep(PERSIST_RETRY_INTERVAL);
+            }
+        }
+        proposals.insert(round, block.clone());
+
+        // Remove the proposals of the oldest rounds, beyond the maximum.
+        while proposals.len() > MAX_CACHED_PROPOSALS {
+            let oldest = match proposals.keys().next() {
+                Some(oldest) => *oldest,
+                None => break,
+            };
+            proposals.remove(&oldest);
+            if let Some(directory) = &*self.directory.read() {
+                let _ = fs::remove_file(Self::path(directory, oldest));
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the given proposal, if it is cached, e.g. as it failed to verify and thus never left the node.
+    pub fn remove(&self, block: &Block<N>) {
+        let round = block.round();
+        let mut proposals = self.proposals.write();
+        if proposals.get(&round).map(|cached| cached.hash()) != Some(block.hash()) {
+            return;
+        }
+        proposals.remove(&round);
+        if let Some(directory) = &*self.directory.read() {
+            if let Err(error) = fs::remove_file(Self::path(directory, round)) {
+                warn!("Failed to remove the proposal of round {round} - {error}");
+            }
+        }
+    }
+
+    /// Writes the given proposal bytes durably, such that a crash never leaves a partial or lost proposal.
+    fn persist(directory: &Path, round: u64, bytes: &[u8]) -> Result<()> {
+        // Write the proposal to a temporary file first, and flush it to the disk.
+        let path = Self::path(directory, round);
+        let staging = path.with_extension("tmp");
+        let mut file = File::create(&staging)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        // Move the proposal into place, and flush the directory entry to the disk.
+        fs::rename(&staging, &path)?;
+        #[cfg(unix)]
+        File::open(directory)?.sync_all()?;
+        Ok(())
+    }
+
+    /// Returns the path of the proposal for the given round.
+    fn path(directory: &Path, round: u64) -> PathBuf {
+        directory.join(format!("{PROPOSAL_PREFIX}{round:020}{PROPOSAL_EXTENSION}"))
+    }
+}
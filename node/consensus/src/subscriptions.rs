@@ -0,0 +1,161 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::CommitteeChange;
+use snarkvm::prelude::{Block, Header, Network, Transaction};
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+/// The maximum number of notifications buffered for a lagging subscriber.
+const SUBSCRIPTION_CAPACITY: usize = 64;
+
+/// A notification of a newly accepted block.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct BlockNotification<N: Network> {
+    /// The block hash.
+    pub hash: N::BlockHash,
+    /// The block height.
+    pub height: u32,
+    /// The block header.
+    pub header: Header<N>,
+}
+
+impl<N: Network> From<&Block<N>> for BlockNotification<N> {
+    fn from(block: &Block<N>) -> Self {
+        Self { hash: block.hash(), height: block.height(), header: *block.header() }
+    }
+}
+
+/// The timings of a round in which this node produced a block, in milliseconds.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RoundTimings {
+    /// The round of the block.
+    pub round: u64,
+    /// The height of the block.
+    pub height: u32,
+    /// The number of transactions in the block.
+    pub num_transactions: usize,
+    /// The time to prepare the memory pool for the block, including creating the transaction of the node itself.
+    pub preparation_ms: u64,
+    /// The time to propose the block.
+    pub proposal_ms: u64,
+    /// The time to check the proposed block.
+    pub verification_ms: u64,
+    /// The time to add the block to the ledger.
+    pub commit_ms: u64,
+    /// The time to serialize the block and write it to the connected beacons, until every write completed
+    /// or the propagation timeout elapsed.
+    pub propagation_ms: u64,
+}
+
+impl RoundTimings {
+    /// Returns the total time of the round, in milliseconds.
+    pub fn latency_ms(&self) -> u64 {
+        self.preparation_ms
+            .saturating_add(self.proposal_ms)
+            .saturating_add(self.verification_ms)
+            .saturating_add(self.commit_ms)
+            .saturating_add(self.propagation_ms)
+    }
+}
+
+/// The channels that notify subscribers of consensus events.
+#[derive(Clone, Debug)]
+pub struct Subscriptions<N: Network> {
+    /// The sender of newly accepted blocks.
+    blocks: broadcast::Sender<BlockNotification<N>>,
+    /// The sender of transactions newly added to the memory pool.
+    transactions: broadcast::Sender<Transaction<N>>,
+    /// The sender of the timings of the rounds in which this node produced a block.
+    rounds: broadcast::Sender<RoundTimings>,
+    /// The sender of the changes to the beacon set.
+    committee: broadcast::Sender<CommitteeChange<N>>,
+}
+
+impl<N: Network> Default for Subscriptions<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network> Subscriptions<N> {
+    /// Initializes a new set of subscription channels.
+    pub fn new() -> Self {
+        Self {
+            blocks: broadcast::channel(SUBSCRIPTION_CAPACITY).0,
+            transactions: broadcast::channel(SUBSCRIPTION_CAPACITY).0,
+            rounds: broadcast::channel(SUBSCRIPTION_CAPACITY).0,
+            committee: broadcast::channel(SUBSCRIPTION_CAPACITY).0,
+        }
+    }
+
+    /// Returns a new subscriber to the newly accepted blocks.
+    pub fn subscribe_blocks(&self) -> broadcast::Receiver<BlockNotification<N>> {
+        self.blocks.subscribe()
+    }
+
+    /// Returns a new subscriber to the transactions newly added to the memory pool.
+    pub fn subscribe_transactions(&self) -> broadcast::Receiver<Transaction<N>> {
+        self.transactions.subscribe()
+    }
+
+    /// Returns a new subscriber to the timings of the rounds in which this node produced a block.
+    pub fn subscribe_rounds(&self) -> broadcast::Receiver<RoundTimings> {
+        self.rounds.subscribe()
+    }
+
+    /// Returns a new subscriber to the changes to the beacon set.
+    pub fn subscribe_committee(&self) -> broadcast::Receiver<CommitteeChange<N>> {
+        self.committee.subscribe()
+    }
+
+    /// Notifies the subscribers of a newly accepted block.
+    pub fn notify_block(&self, block: &Block<N>) {
+        // Skip the notification if there are no subscribers.
+        if self.blocks.receiver_count() > 0 {
+            // Note: This only fails if every subscriber was dropped in the meantime.
+            let _ = self.blocks.send(block.into());
+        }
+    }
+
+    /// Notifies the subscribers of a transaction newly added to the memory pool.
+    pub fn notify_transaction(&self, transaction: &Transaction<N>) {
+        // Skip the notification if there are no subscribers.
+        if self.transactions.receiver_count() > 0 {
+            // Note: This only fails if every subscriber was dropped in the meantime.
+            let _ = self.transactions.send(transaction.clone());
+        }
+    }
+
+    /// Notifies the subscribers of the timings of a round in which this node produced a block.
+    pub fn notify_round(&self, timings: RoundTimings) {
+        // Skip the notification if there are no subscribers.
+        if self.rounds.receiver_count() > 0 {
+            // Note: This only fails if every subscriber was dropped in the meantime.
+            let _ = self.rounds.send(timings);
+        }
+    }
+
+    /// Notifies the subscribers of a change to the beacon set.
+    pub fn notify_committee_change(&self, change: &CommitteeChange<N>) {
+        // Skip the notification if there are no subscribers.
+        if self.committee.receiver_count() > 0 {
+            // Note: This only fails if every subscriber was dropped in the meantime.
+            let _ = self.committee.send(change.clone());
+        }
+    }
+}
@@ -20,10 +20,11 @@ use snarkvm::{
         account::{Address, PrivateKey, ViewKey},
         network::{prelude::*, Testnet3},
         program::{Entry, Identifier, Literal, Plaintext, Value},
+        types::Field,
     },
     prelude::TestRng,
     synthesizer::{
-        block::{Block, Transaction, Transactions},
+        block::{Block, Header, Transaction, Transactions},
         program::Program,
         store::ConsensusStore,
         vm::VM,
@@ -196,59 +197,136 @@ function compute:
 
     pub(crate) fn sample_execution_transaction(rng: &mut TestRng) -> Transaction<CurrentNetwork> {
         static INSTANCE: OnceCell<Transaction<CurrentNetwork>> = OnceCell::new();
-        INSTANCE
-            .get_or_init(|| {
-                // Initialize a new caller.
-                let caller_private_key = crate::tests::test_helpers::sample_genesis_private_key(rng);
-                let caller_view_key = ViewKey::try_from(&caller_private_key).unwrap();
-                let address = Address::try_from(&caller_private_key).unwrap();
+        INSTANCE.get_or_init(|| build_execution_transaction(rng)).clone()
+    }
 
-                // Initialize the consensus.
-                let consensus = crate::tests::test_helpers::sample_genesis_consensus(rng);
+    /// Returns a new execution transaction, which spends the same fee record on every call.
+    pub(crate) fn build_execution_transaction(rng: &mut TestRng) -> Transaction<CurrentNetwork> {
+        // Initialize a new caller.
+        let caller_private_key = crate::tests::test_helpers::sample_genesis_private_key(rng);
+        let caller_view_key = ViewKey::try_from(&caller_private_key).unwrap();
+        let address = Address::try_from(&caller_private_key).unwrap();
 
-                // Fetch the unspent records.
-                let microcredits = Identifier::from_str("microcredits").unwrap();
-                let records = consensus
-                    .ledger
-                    .find_records(&caller_view_key, RecordsFilter::SlowUnspent(caller_private_key))
-                    .unwrap()
-                    .filter(|(_, record)| {
-                        // TODO (raychu86): Find cleaner approach and check that the record is associated with the `credits.aleo` program
-                        match record.data().get(&microcredits) {
-                            Some(Entry::Private(Plaintext::Literal(Literal::U64(amount), _))) => !amount.is_zero(),
-                            _ => false,
-                        }
-                    })
-                    .collect::<indexmap::IndexMap<_, _>>();
-                trace!("Unspent Records:\n{:#?}", records);
-                // Select a record to spend.
-                let record = records.values().next().unwrap().clone();
+        // Initialize the consensus.
+        let consensus = crate::tests::test_helpers::sample_genesis_consensus(rng);
 
-                // Retrieve the VM.
-                let vm = consensus.ledger.vm();
+        // Fetch the unspent records.
+        let microcredits = Identifier::from_str("microcredits").unwrap();
+        let records = consensus
+            .ledger
+            .find_records(&caller_view_key, RecordsFilter::SlowUnspent(caller_private_key))
+            .unwrap()
+            .filter(|(_, record)| {
+                // TODO (raychu86): Find cleaner approach and check that the record is associated with the `credits.aleo` program
+                match record.data().get(&microcredits) {
+                    Some(Entry::Private(Plaintext::Literal(Literal::U64(amount), _))) => !amount.is_zero(),
+                    _ => false,
+                }
+            })
+            .collect::<indexmap::IndexMap<_, _>>();
+        trace!("Unspent Records:\n{:#?}", records);
+        // Select a record to spend.
+        let record = records.values().next().unwrap().clone();
+
+        // Retrieve the VM.
+        let vm = consensus.ledger.vm();
+
+        // Prepare the inputs.
+        let inputs = [
+            Value::<CurrentNetwork>::from_str(&address.to_string()).unwrap(),
+            Value::<CurrentNetwork>::from_str("1u64").unwrap(),
+        ]
+        .into_iter();
+
+        // Authorize.
+        let authorization = vm.authorize(&caller_private_key, "credits.aleo", "mint", inputs, rng).unwrap();
+        assert_eq!(authorization.len(), 1);
+
+        // Execute the fee.
+        let fee = Transaction::execute_fee(vm, &caller_private_key, record, 100, None, rng).unwrap();
+
+        // Execute.
+        let transaction = Transaction::execute_authorization(vm, authorization, Some(fee), None, rng).unwrap();
+        // Verify.
+        assert!(vm.verify_transaction(&transaction));
+        // Return the transaction.
+        transaction
+    }
 
-                // Prepare the inputs.
-                let inputs = [
-                    Value::<CurrentNetwork>::from_str(&address.to_string()).unwrap(),
-                    Value::<CurrentNetwork>::from_str("1u64").unwrap(),
-                ]
-                .into_iter();
+    /// A mutation that makes a block invalid, while keeping it well-formed.
+    #[derive(Copy, Clone, Debug)]
+    pub(crate) enum BlockMutation {
+        /// Replaces the transactions root in the header with a random value.
+        BadTransactionsRoot,
+        /// Appends two transactions that spend the same record.
+        DuplicateSerialNumber,
+        /// Signs the block with a key that does not belong to a beacon.
+        WrongSigner,
+    }
 
-                // Authorize.
-                let authorization = vm.authorize(&caller_private_key, "credits.aleo", "mint", inputs, rng).unwrap();
-                assert_eq!(authorization.len(), 1);
+    /// Builds the next block for the given consensus, with an optional mutation.
+    pub(crate) struct BlockBuilder<'a> {
+        /// The consensus to build the next block for.
+        consensus: &'a CurrentConsensus,
+        /// The private key of the beacon that proposes the block.
+        private_key: PrivateKey<CurrentNetwork>,
+        /// The mutation to apply to the block, if any.
+        mutation: Option<BlockMutation>,
+    }
 
-                // Execute the fee.
-                let fee = Transaction::execute_fee(vm, &caller_private_key, record, 100, None, rng).unwrap();
+    impl<'a> BlockBuilder<'a> {
+        /// Initializes a new block builder, proposing with the genesis private key.
+        pub(crate) fn new(consensus: &'a CurrentConsensus, rng: &mut TestRng) -> Self {
+            Self { consensus, private_key: sample_genesis_private_key(rng), mutation: None }
+        }
 
-                // Execute.
-                let transaction = Transaction::execute_authorization(vm, authorization, Some(fee), None, rng).unwrap();
-                // Verify.
-                assert!(vm.verify_transaction(&transaction));
-                // Return the transaction.
-                transaction
-            })
-            .clone()
+        /// Applies the given mutation to the block.
+        pub(crate) fn mutate(mut self, mutation: BlockMutation) -> Self {
+            self.mutation = Some(mutation);
+            self
+        }
+
+        /// Returns the next block.
+        pub(crate) fn build(self, rng: &mut TestRng) -> Block<CurrentNetwork> {
+            // Propose a valid next block.
+            let block = self.consensus.propose_next_block(&self.private_key, rng).unwrap();
+            let header = *block.header();
+            let transactions = block.transactions().clone();
+
+            // Apply the mutation, if any.
+            let (private_key, header, transactions) = match self.mutation {
+                None => return block,
+                Some(BlockMutation::BadTransactionsRoot) => {
+                    let header = Header::from(
+                        header.previous_state_root(),
+                        Field::rand(rng),
+                        header.finalize_root(),
+                        header.coinbase_accumulator_point(),
+                        *header.metadata(),
+                    )
+                    .unwrap();
+                    (self.private_key, header, transactions)
+                }
+                Some(BlockMutation::DuplicateSerialNumber) => {
+                    let mut transactions: Vec<_> = transactions.values().cloned().collect();
+                    transactions.push(build_execution_transaction(rng));
+                    transactions.push(build_execution_transaction(rng));
+                    let transactions = Transactions::from(&transactions);
+                    let header = Header::from(
+                        header.previous_state_root(),
+                        transactions.to_root().unwrap(),
+                        header.finalize_root(),
+                        header.coinbase_accumulator_point(),
+                        *header.metadata(),
+                    )
+                    .unwrap();
+                    (self.private_key, header, transactions)
+                }
+                Some(BlockMutation::WrongSigner) => (PrivateKey::new(rng).unwrap(), header, transactions),
+            };
+            Block::new(&private_key, block.previous_hash(), header, transactions, block.coinbase().cloned(), rng)
+                .unwrap()
+        }
     }
 }
 
@@ -423,6 +501,381 @@ fn test_ledger_execute_many() {
     }
 }
 
+#[test]
+#[traced_test]
+fn test_differential_harness() {
+    let rng = &mut TestRng::default();
+
+    // Sample the genesis private key.
+    let private_key = crate::tests::test_helpers::sample_genesis_private_key(rng);
+    // Sample two independent instances of the genesis consensus.
+    let consensus = crate::tests::test_helpers::sample_genesis_consensus(rng);
+    let other = crate::tests::test_helpers::sample_genesis_consensus(rng);
+
+    // Propose the next block, and serialize it.
+    let next_block = consensus.propose_next_block(&private_key, rng).unwrap();
+    let corpus = [next_block.to_bytes_le().unwrap(), next_block.to_bytes_le().unwrap()];
+
+    // Ensure both instances accept the next block, and both reject the duplicate.
+    let harness = crate::DifferentialHarness::new(consensus, other);
+    let report = harness.run_serialized(&corpus).unwrap();
+    assert!(report.is_consistent());
+    assert_eq!(report.num_checked, 2);
+    assert_eq!(report.num_accepted, 1);
+
+    // Record the golden fixtures of the corpus, and ensure each copy of the block keeps its own verdict.
+    let validator = crate::tests::test_helpers::sample_genesis_consensus(rng);
+    let recorded = crate::GoldenFixtures::record(&validator, &[next_block.clone(), next_block.clone()]).unwrap();
+    assert_eq!(recorded.len(), 2);
+    assert_eq!(recorded.get(0), Some((next_block.hash(), true)));
+    assert_eq!(recorded.get(1), Some((next_block.hash(), false)));
+
+    // Ensure replaying the corpus against the recorded fixtures reports no divergence.
+    let harness = crate::DifferentialHarness::new(crate::tests::test_helpers::sample_genesis_consensus(rng), recorded);
+    let report = harness.run_serialized(&corpus).unwrap();
+    assert!(report.is_consistent());
+    assert_eq!(report.num_accepted, 1);
+
+    // Ensure a divergence is reported against golden fixtures that expect the block to be rejected.
+    let mut fixtures = crate::GoldenFixtures::new();
+    fixtures.insert(next_block.hash(), false);
+    let harness = crate::DifferentialHarness::new(crate::tests::test_helpers::sample_genesis_consensus(rng), fixtures);
+    let report = harness.run(&[next_block.clone()]).unwrap();
+    let divergence = report.divergence.unwrap();
+    assert_eq!(divergence.index, 0);
+    assert_eq!(divergence.hash, next_block.hash());
+    assert!(divergence.left.is_accepted());
+    assert!(!divergence.right.is_accepted());
+}
+
+#[test]
+fn test_watch_list() {
+    use crate::{WatchListConfig, WatchedChange};
+
+    let rng = &mut TestRng::default();
+
+    // Sample the genesis private key and address.
+    let private_key = crate::tests::test_helpers::sample_genesis_private_key(rng);
+    let address = Address::try_from(&private_key).unwrap();
+    // Sample the genesis consensus.
+    let consensus = crate::tests::test_helpers::sample_genesis_consensus(rng);
+
+    // Watch the address.
+    consensus.watch_address(address).unwrap();
+    assert!(consensus.watch_address(address).is_err());
+    assert_eq!(consensus.watch_list().balances().get(&address), Some(&0));
+
+    // Advance to a block that executes a transaction of the watched address.
+    let transaction = crate::tests::test_helpers::sample_execution_transaction(rng);
+    consensus.add_unconfirmed_transaction(transaction).unwrap();
+    let next_block = consensus.propose_next_block(&private_key, rng).unwrap();
+    consensus.advance_to_next_block(&next_block).unwrap();
+
+    // Ensure the balance is up to date, and every observed change is of the watched address at this block.
+    let balance = consensus.ledger.get_public_balance(&address).unwrap();
+    assert_eq!(consensus.watch_list().balances().get(&address), Some(&balance));
+    let changes = consensus.watch_list().changes();
+    assert!(changes.iter().all(|change| *change.address() == address));
+    assert!(changes.iter().all(|change| match change {
+        WatchedChange::Balance { height, .. }
+        | WatchedChange::Mapping { height, .. }
+        | WatchedChange::Record { height, .. } => *height == next_block.height(),
+    }));
+    assert_eq!(consensus.watch_list().num_addresses(), 1);
+    assert_eq!(consensus.watch_list().num_changes(), changes.len() as u64);
+
+    // Advance to an empty block, and ensure no further changes were observed.
+    let next_block = consensus.propose_next_block(&private_key, rng).unwrap();
+    consensus.advance_to_next_block(&next_block).unwrap();
+    assert_eq!(consensus.watch_list().changes(), changes);
+
+    // Unwatch the address.
+    consensus.watch_list().unwatch(&address).unwrap();
+    assert!(consensus.watch_list().unwatch(&address).is_err());
+    assert!(consensus.watch_list().balances().is_empty());
+
+    // Ensure the configured addresses are watched, and the bound on the changes is checked.
+    assert!(consensus.set_watch_list_config(WatchListConfig { addresses: vec![], max_changes: 0 }).is_err());
+    consensus.set_watch_list_config(WatchListConfig { addresses: vec![address], max_changes: 16 }).unwrap();
+    assert_eq!(consensus.watch_list().num_addresses(), 1);
+}
+
+#[test]
+fn test_subscribe_blocks() {
+    let rng = &mut TestRng::default();
+
+    // Sample the genesis private key.
+    let private_key = crate::tests::test_helpers::sample_genesis_private_key(rng);
+    // Sample the genesis consensus.
+    let consensus = crate::tests::test_helpers::sample_genesis_consensus(rng);
+
+    // Subscribe to the new blocks.
+    let mut subscriber = consensus.subscriptions().subscribe_blocks();
+    assert!(subscriber.try_recv().is_err());
+
+    // Advance to the next block, and ensure the subscriber is notified.
+    let next_block = consensus.propose_next_block(&private_key, rng).unwrap();
+    consensus.advance_to_next_block(&next_block).unwrap();
+    let notification = subscriber.try_recv().unwrap();
+    assert_eq!(notification.hash, next_block.hash());
+    assert_eq!(notification.height, next_block.height());
+    assert_eq!(&notification.header, next_block.header());
+    assert!(subscriber.try_recv().is_err());
+}
+
+#[test]
+fn test_subscribe_transactions() {
+    let rng = &mut TestRng::default();
+
+    // Sample the genesis consensus.
+    let consensus = crate::tests::test_helpers::sample_genesis_consensus(rng);
+
+    // Subscribe to the pending transactions.
+    let mut subscriber = consensus.subscriptions().subscribe_transactions();
+
+    // Add a transaction to the memory pool, and ensure the subscriber is notified.
+    let transaction = crate::tests::test_helpers::sample_execution_transaction(rng);
+    consensus.add_unconfirmed_transaction(transaction.clone()).unwrap();
+    assert_eq!(subscriber.try_recv().unwrap().id(), transaction.id());
+
+    // Ensure a rejected transaction is not notified.
+    assert!(consensus.add_unconfirmed_transaction(transaction).is_err());
+    assert!(subscriber.try_recv().is_err());
+}
+
+#[test]
+fn test_subscribe_committee() {
+    use crate::{CommitteeChange, CommitteeChangeKind};
+
+    let rng = &mut TestRng::default();
+
+    // Sample the genesis private key.
+    let private_key = crate::tests::test_helpers::sample_genesis_private_key(rng);
+    // Sample the genesis consensus.
+    let mut consensus = crate::tests::test_helpers::sample_genesis_consensus(rng);
+
+    // Ensure the genesis beacon is recorded as added at the genesis block.
+    let genesis_beacon = Address::try_from(&private_key).unwrap();
+    let change = |address, kind| CommitteeChange { epoch: 0, height: 0, address, kind };
+    assert_eq!(
+        consensus.committee_history().changes_since(0),
+        vec![change(genesis_beacon, CommitteeChangeKind::Added)]
+    );
+
+    // Subscribe to the changes to the beacon set.
+    let mut subscriber = consensus.subscriptions().subscribe_committee();
+    assert!(subscriber.try_recv().is_err());
+
+    // Add and remove a beacon, and ensure the subscriber is notified of each change.
+    let address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+    consensus.add_beacon(address).unwrap();
+    assert_eq!(subscriber.try_recv().unwrap(), change(address, CommitteeChangeKind::Added));
+    consensus.remove_beacon(address).unwrap();
+    assert_eq!(subscriber.try_recv().unwrap(), change(address, CommitteeChangeKind::Removed));
+
+    // Ensure a rejected change is not notified.
+    assert!(consensus.remove_beacon(address).is_err());
+    assert!(subscriber.try_recv().is_err());
+    assert_eq!(consensus.committee_history().len(), 3);
+}
+
+#[test]
+fn test_block_pipeline() {
+    let rng = &mut TestRng::default();
+
+    // Sample the genesis private key.
+    let private_key = crate::tests::test_helpers::sample_genesis_private_key(rng);
+    // Sample the genesis consensus.
+    let consensus = crate::tests::test_helpers::sample_genesis_consensus(rng);
+
+    // Propose the next block, and serve it from the source of the pipeline.
+    let next_block = consensus.propose_next_block(&private_key, rng).unwrap();
+    let bytes = next_block.to_bytes_le().unwrap();
+    let source = move |height: u32| match height {
+        1 => Some(crate::PipelineInput::Encoded(bytes.clone())),
+        _ => None,
+    };
+
+    // Start the pipeline, and ensure it commits the block.
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let pipeline = crate::BlockPipeline::new(consensus.clone(), source, move |block: &Block<CurrentNetwork>| {
+        sender.send(block.hash()).unwrap();
+    })
+    .unwrap();
+    let hash = receiver.recv_timeout(std::time::Duration::from_secs(60)).unwrap();
+    assert_eq!(hash, next_block.hash());
+    assert_eq!(consensus.ledger.latest_hash(), next_block.hash());
+
+    // Ensure the block passed through every stage.
+    let metrics = consensus.pipeline_metrics().snapshot();
+    assert_eq!(metrics.len(), crate::PipelineStage::ALL.len());
+    assert!(metrics.iter().all(|stage| stage.processed == 1 && stage.failed == 0 && stage.queued == 0));
+    assert!(consensus.pipeline_metrics().backpressure().is_none());
+
+    pipeline.shut_down();
+}
+
+#[test]
+fn test_check_unconfirmed_transaction() {
+    let rng = &mut TestRng::default();
+
+    // Sample the genesis consensus.
+    let consensus = crate::tests::test_helpers::sample_genesis_consensus(rng);
+
+    // Ensure the check does not add the transaction to the memory pool.
+    let transaction = crate::tests::test_helpers::sample_execution_transaction(rng);
+    consensus.check_unconfirmed_transaction(&transaction).unwrap();
+    assert_eq!(consensus.memory_pool().num_unconfirmed_transactions(), 0);
+
+    // Ensure the check fails once the transaction is in the memory pool.
+    consensus.add_unconfirmed_transaction(transaction.clone()).unwrap();
+    assert!(consensus.check_unconfirmed_transaction(&transaction).is_err());
+}
+
+#[test]
+fn test_builder_bundles() {
+    let rng = &mut TestRng::default();
+
+    // Sample the genesis consensus.
+    let consensus = crate::tests::test_helpers::sample_genesis_consensus(rng);
+    let private_key = crate::tests::test_helpers::sample_genesis_private_key(rng);
+    let transaction = crate::tests::test_helpers::sample_execution_transaction(rng);
+    let builder = || "builder".to_string();
+
+    // Ensure a bundle is rejected until a builder policy is set.
+    assert!(consensus.submit_bundle(builder(), 1, vec![transaction.clone()]).is_err());
+    consensus.bundles().set_policy(crate::BuilderPolicy::default());
+
+    // Ensure a bundle for another block, or with conflicting transactions, is rejected.
+    assert!(consensus.submit_bundle(builder(), 2, vec![transaction.clone()]).is_err());
+    assert!(consensus.submit_bundle(builder(), 1, vec![transaction.clone(), transaction.clone()]).is_err());
+
+    // Submit a bundle for the next block.
+    let receipt = consensus.submit_bundle(builder(), 1, vec![transaction.clone()]).unwrap();
+    let total_fee = *transaction.fee().unwrap();
+    assert_eq!(receipt, crate::BundleReceipt { height: 1, num_transactions: 1, total_fee });
+    assert_eq!(consensus.bundles().num_bundles(), 1);
+
+    // Ensure the proposer adopts the bundle, although its transaction is not in the memory pool.
+    assert_eq!(consensus.memory_pool().num_unconfirmed_transactions(), 0);
+    let block = consensus.propose_next_block(&private_key, rng).unwrap();
+    assert!(block.transaction_ids().any(|id| *id == transaction.id()));
+    consensus.check_next_block(&block).unwrap();
+    assert_eq!(consensus.bundles().num_bundles(), 0);
+}
+
+#[test]
+fn test_memory_pool_info() {
+    let rng = &mut TestRng::default();
+
+    // Sample the genesis consensus.
+    let consensus = crate::tests::test_helpers::sample_genesis_consensus(rng);
+
+    // Ensure the summary of an empty memory pool has no fee statistics.
+    let info = consensus.memory_pool().unconfirmed_transactions_info().unwrap();
+    assert_eq!(info, crate::MemoryPoolInfo::default());
+
+    // Add a transaction to the memory pool.
+    let transaction = crate::tests::test_helpers::sample_execution_transaction(rng);
+    consensus.add_unconfirmed_transaction(transaction.clone()).unwrap();
+
+    // Ensure the summary reflects the transaction.
+    let fee = *transaction.fee().unwrap();
+    let info = consensus.memory_pool().unconfirmed_transactions_info().unwrap();
+    assert_eq!(info.num_transactions, 1);
+    assert_eq!(info.total_bytes, transaction.to_bytes_le().unwrap().len());
+    assert_eq!((info.min_fee, info.median_fee, info.max_fee), (Some(fee), Some(fee), Some(fee)));
+
+    // Ensure the transaction IDs are paginated.
+    assert_eq!(consensus.memory_pool().unconfirmed_transaction_ids(0, 10), vec![transaction.id()]);
+    assert!(consensus.memory_pool().unconfirmed_transaction_ids(1, 10).is_empty());
+}
+
+#[test]
+fn test_assume_valid() {
+    let rng = &mut TestRng::default();
+
+    // Sample the genesis private key.
+    let private_key = crate::tests::test_helpers::sample_genesis_private_key(rng);
+    // Sample the genesis consensus.
+    let consensus = crate::tests::test_helpers::sample_genesis_consensus(rng);
+
+    // Ensure a checkpoint that is already in the ledger is ignored, if it is on the canonical chain.
+    consensus.set_assume_valid(0, consensus.ledger.latest_hash()).unwrap();
+    assert!(consensus.assume_valid().is_none());
+
+    // Propose the next block, and ensure a checkpoint with another hash at its height rejects it.
+    let next_block = consensus.propose_next_block(&private_key, rng).unwrap();
+    consensus.set_assume_valid(1, consensus.ledger.latest_hash()).unwrap();
+    assert!(!consensus.is_assumed_valid(1, &next_block.hash()));
+    assert!(consensus.check_next_block(&next_block).is_err());
+
+    // Set the next block as the checkpoint, and ensure only the checkpoint is assumed to be valid.
+    consensus.set_assume_valid(1, next_block.hash()).unwrap();
+    assert_eq!(consensus.assume_valid(), Some((1, next_block.hash())));
+    assert!(consensus.is_assumed_valid(1, &next_block.hash()));
+    assert!(!consensus.is_assumed_valid(2, &next_block.hash()));
+
+    // Ensure the checkpoint is not linked below the next block, as its predecessor is in the ledger.
+    let links = [(1, next_block.hash(), next_block.previous_hash())];
+    assert_eq!(consensus.link_assume_valid(&links), 0);
+    assert_eq!(consensus.lowest_assume_valid_link(), Some((1, next_block.hash())));
+
+    // Ensure the block is still a valid next block.
+    consensus.check_next_block(&next_block).unwrap();
+
+    // Advance to the checkpoint, and ensure full verification resumes.
+    consensus.advance_to_next_block(&next_block).unwrap();
+    assert!(consensus.assume_valid().is_none());
+    assert!(!consensus.is_assumed_valid(1, &next_block.hash()));
+
+    // Ensure a checkpoint that conflicts with the canonical chain is rejected.
+    assert!(consensus.set_assume_valid(0, next_block.hash()).is_err());
+}
+
+#[test]
+fn test_fee_breakdown() {
+    let rng = &mut TestRng::default();
+
+    // Ensure a deployment pays for its storage in bytes, and the remainder is the priority fee.
+    let transaction = crate::tests::test_helpers::sample_deployment_transaction(rng);
+    let breakdown = crate::FeeBreakdown::new(&transaction).unwrap();
+    assert_eq!(breakdown.storage_cost, transaction.to_bytes_le().unwrap().len() as u64 * 1000);
+    assert_eq!(breakdown.finalize_cost, 0);
+    assert_eq!(breakdown.base, breakdown.storage_cost);
+    assert_eq!(breakdown.base + breakdown.priority, *transaction.fee().unwrap());
+
+    // Ensure an execution pays no base fee.
+    let transaction = crate::tests::test_helpers::sample_execution_transaction(rng);
+    let breakdown = crate::FeeBreakdown::new(&transaction).unwrap();
+    assert_eq!(breakdown.base, 0);
+    assert_eq!(breakdown.priority, *transaction.fee().unwrap());
+}
+
+#[test]
+fn test_fee_estimate() {
+    // Ensure an idle network suggests no priority fee.
+    let estimate = crate::FeeEstimate::new(1, &[], &[], 10);
+    assert_eq!(estimate.priority_fee, 0);
+
+    // Ensure a tighter target suggests a higher percentile of the sampled fees.
+    let confirmed = (1..=100).collect::<Vec<u64>>();
+    assert_eq!(crate::FeeEstimate::new(1, &confirmed, &[], 10).priority_fee, 90);
+    assert_eq!(crate::FeeEstimate::new(3, &confirmed, &[], 10).priority_fee, 75);
+    assert_eq!(crate::FeeEstimate::new(10, &confirmed, &[], 10).priority_fee, 50);
+    assert_eq!(crate::FeeEstimate::new(100, &confirmed, &[], 10).priority_fee, 25);
+    // Ensure a target of zero blocks is treated as the next block.
+    assert_eq!(crate::FeeEstimate::new(0, &confirmed, &[], 10), crate::FeeEstimate::new(1, &confirmed, &[], 10));
+
+    // Ensure a congested memory pool raises the suggestion to outbid the pending transactions that do not fit.
+    let pending = vec![500; 30];
+    let estimate = crate::FeeEstimate::new(2, &confirmed, &pending, 10);
+    assert_eq!(estimate.priority_fee, 501);
+    assert_eq!((estimate.num_confirmed, estimate.num_pending), (100, 30));
+    // Ensure the pending transactions that fit in the blocks only count as samples.
+    assert_eq!(crate::FeeEstimate::new(4, &confirmed, &pending, 10).priority_fee, 65);
+}
+
 #[test]
 #[traced_test]
 fn test_proof_target() {
@@ -510,3 +963,356 @@ fn test_coinbase_target() {
     // Ensure the block contains a coinbase solution.
     assert!(proposed_block.coinbase().is_some());
 }
+
+#[test]
+fn test_block_builder() {
+    let rng = &mut TestRng::default();
+
+    // Sample the genesis consensus.
+    let consensus = crate::tests::test_helpers::sample_genesis_consensus(rng);
+
+    // Ensure each mutation is rejected, with the error of the check it breaks.
+    for (mutation, expected_error) in [
+        (test_helpers::BlockMutation::BadTransactionsRoot, "has an incorrect transactions root"),
+        (test_helpers::BlockMutation::DuplicateSerialNumber, "Found a duplicate serial number"),
+        (test_helpers::BlockMutation::WrongSigner, "is signed by an unauthorized beacon"),
+    ] {
+        let block = test_helpers::BlockBuilder::new(&consensus, rng).mutate(mutation).build(rng);
+        let error = consensus.check_next_block(&block).unwrap_err().to_string();
+        assert!(error.contains(expected_error), "{mutation:?} was rejected with an unexpected error: {error}");
+    }
+
+    // Ensure the unmutated block is a valid next block.
+    let next_block = test_helpers::BlockBuilder::new(&consensus, rng).build(rng);
+    consensus.check_next_block(&next_block).unwrap();
+
+    // Ensure the unmutated block is added to the ledger.
+    consensus.advance_to_next_block(&next_block).unwrap();
+    assert_eq!(consensus.ledger.latest_height(), 1);
+    assert_eq!(consensus.ledger.latest_hash(), next_block.hash());
+
+    // Ensure the builder proposes on top of the new tip.
+    let next_block = test_helpers::BlockBuilder::new(&consensus, rng).build(rng);
+    assert_eq!(next_block.height(), 2);
+    consensus.check_next_block(&next_block).unwrap();
+}
+
+#[test]
+fn test_equivocation_detection() {
+    let rng = &mut TestRng::default();
+
+    // Sample the genesis consensus.
+    let consensus = crate::tests::test_helpers::sample_genesis_consensus(rng);
+
+    // Observe the next block, and ensure observing it again is not an equivocation.
+    let next_block = test_helpers::BlockBuilder::new(&consensus, rng).build(rng);
+    assert!(consensus.check_equivocation(&next_block).is_none());
+    assert!(consensus.check_equivocation(&next_block).is_none());
+
+    // Ensure a distinct block in the same round by a signer outside the committee is not observed.
+    let block =
+        test_helpers::BlockBuilder::new(&consensus, rng).mutate(test_helpers::BlockMutation::WrongSigner).build(rng);
+    assert!(consensus.check_equivocation(&block).is_none());
+
+    // Ensure a distinct block in the same round by the same signer is an equivocation.
+    let conflicting_block = test_helpers::BlockBuilder::new(&consensus, rng)
+        .mutate(test_helpers::BlockMutation::BadTransactionsRoot)
+        .build(rng);
+    let evidence = consensus.check_equivocation(&conflicting_block).unwrap();
+    assert_eq!(evidence.signer, next_block.signature().to_address());
+    assert_eq!(evidence.round, next_block.round());
+    assert_eq!(evidence.first.hash, next_block.hash());
+    assert_eq!(evidence.second.hash, conflicting_block.hash());
+    assert!(evidence.verify());
+
+    // Ensure the evidence is recorded once.
+    assert!(consensus.check_equivocation(&conflicting_block).is_none());
+    assert_eq!(consensus.equivocations().evidence(None, 0, 10), vec![evidence.clone()]);
+    assert_eq!(consensus.equivocations().evidence(Some(evidence.signer), 1, 10), vec![]);
+    assert_eq!(consensus.equivocations().evidence(Some(block.signature().to_address()), 0, 10), vec![]);
+
+    // Ensure evidence with a forged signature is rejected.
+    let mut forged = evidence;
+    forged.second.signature = *block.signature();
+    assert!(!forged.verify());
+}
+
+#[test]
+fn test_journal_rejected_blocks() {
+    let rng = &mut TestRng::default();
+
+    // Sample the genesis consensus, and enable the journal.
+    let consensus = crate::tests::test_helpers::sample_genesis_consensus(rng);
+    let temp_dir = tempfile::tempdir().unwrap();
+    let directory = temp_dir.path().join("journal");
+    consensus.set_journal(crate::JournalConfig::new(directory.clone())).unwrap();
+
+    // Ensure a duplicate block is not recorded as a rejection.
+    let genesis = consensus.ledger.get_block(0).unwrap();
+    assert!(consensus.check_next_block(&genesis).is_err());
+
+    // Ensure an invalid next block is recorded as a rejection.
+    let invalid_block = test_helpers::BlockBuilder::new(&consensus, rng)
+        .mutate(test_helpers::BlockMutation::BadTransactionsRoot)
+        .build(rng);
+    assert!(consensus.check_next_block(&invalid_block).is_err());
+
+    let (_, path) = crate::journal_segments(&directory).unwrap().pop().unwrap();
+    let contents = std::fs::read_to_string(path).unwrap();
+    let entries =
+        contents.lines().map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()).collect::<Vec<_>>();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["kind"], "blockRejected");
+    assert_eq!(entries[0]["hash"], invalid_block.hash().to_string());
+}
+
+#[test]
+fn test_proposal_cache() {
+    use crate::ProposalCache;
+
+    let rng = &mut TestRng::default();
+
+    // Sample the genesis private key.
+    let private_key = crate::tests::test_helpers::sample_genesis_private_key(rng);
+    // Sample the genesis consensus.
+    let consensus = crate::tests::test_helpers::sample_genesis_consensus(rng);
+    let genesis_hash = consensus.ledger.latest_hash();
+
+    // Persist the proposals to a temporary directory.
+    let temp_dir = tempfile::tempdir().unwrap();
+    let directory = temp_dir.path().join("proposals");
+    consensus.proposals().open(directory.clone()).unwrap();
+    assert!(consensus.proposals().is_empty());
+
+    // Cache the next block, and ensure it is re-proposed in its round.
+    let next_block = test_helpers::BlockBuilder::new(&consensus, rng).build(rng);
+    consensus.proposals().insert(&next_block).unwrap();
+    consensus.proposals().insert(&next_block).unwrap();
+    assert_eq!(consensus.proposals().len(), 1);
+    assert_eq!(consensus.propose_next_block(&private_key, rng).unwrap().hash(), next_block.hash());
+
+    // Ensure a different block in the same round is never cached.
+    let conflicting_block = test_helpers::BlockBuilder::new(&consensus, rng)
+        .mutate(test_helpers::BlockMutation::BadTransactionsRoot)
+        .build(rng);
+    assert!(consensus.proposals().insert(&conflicting_block).is_err());
+
+    // Ensure a proposal on a different previous block is refused.
+    assert!(consensus.proposals().get(next_block.round(), next_block.hash()).is_err());
+
+    // Ensure only the cached proposal is removed.
+    consensus.proposals().remove(&conflicting_block);
+    assert_eq!(consensus.proposals().len(), 1);
+    consensus.proposals().remove(&next_block);
+    assert!(consensus.proposals().is_empty());
+    assert!(consensus.proposals().get(next_block.round(), next_block.hash()).unwrap().is_none());
+    consensus.proposals().insert(&next_block).unwrap();
+
+    // Ensure the proposal is restored from the directory, after a restart.
+    let proposals = ProposalCache::<CurrentNetwork>::new();
+    proposals.open(directory.clone()).unwrap();
+    assert_eq!(
+        proposals.get(next_block.round(), genesis_hash).unwrap().map(|block| block.hash()),
+        Some(next_block.hash())
+    );
+    assert!(proposals.get(next_block.round() + 1, next_block.hash()).unwrap().is_none());
+}
+
+#[test]
+fn test_test_vectors() {
+    use crate::{TestVectors, TEST_VECTORS_VERSION};
+
+    // Ensure the generated test vectors match the committed fixture.
+    // Note: Set `UPDATE_TEST_VECTORS` to regenerate the fixture, e.g. after bumping `TEST_VECTORS_VERSION`.
+    let fixtures_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("vectors");
+    let vectors = TestVectors::generate::<CurrentNetwork>(0).unwrap();
+    if std::env::var_os("UPDATE_TEST_VECTORS").is_some() {
+        vectors.write(&fixtures_dir).unwrap();
+    }
+    let path = TestVectors::path(&fixtures_dir, TEST_VECTORS_VERSION, CurrentNetwork::ID);
+    let fixtures = TestVectors::read(&path).unwrap();
+    assert_eq!(fixtures, vectors);
+    assert_eq!(
+        fixtures.vectors.iter().map(|vector| vector.name.as_str()).collect::<Vec<_>>(),
+        vec!["mint", "transfer", "transfer-tampered"]
+    );
+
+    // Ensure every vector meets its expectations.
+    fixtures.check::<CurrentNetwork>().unwrap();
+
+    // Ensure the tampered transaction still deserializes, so it is rejected by the proof verification.
+    let tampered = &fixtures.vectors[2];
+    assert!(!tampered.is_valid);
+    assert_ne!(tampered.transaction, fixtures.vectors[1].transaction);
+    assert!(Transaction::<CurrentNetwork>::from_bytes_le(&hex::decode(&tampered.transaction).unwrap()).is_ok());
+
+    // Ensure a wrong expectation is reported.
+    let mut wrong = fixtures.clone();
+    wrong.vectors[0].is_valid = false;
+    assert!(wrong.check::<CurrentNetwork>().is_err());
+    let mut wrong = fixtures.clone();
+    wrong.vectors[1].commitments.reverse();
+    assert!(wrong.check::<CurrentNetwork>().is_err());
+    let mut wrong = fixtures.clone();
+    wrong.vectors[2].transaction.truncate(64);
+    assert!(wrong.check::<CurrentNetwork>().is_err());
+    let mut wrong = fixtures;
+    wrong.version += 1;
+    assert!(wrong.check::<CurrentNetwork>().is_err());
+
+    // Ensure the fixture file round-trips.
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = vectors.write(temp_dir.path()).unwrap();
+    assert_eq!(path, TestVectors::path(temp_dir.path(), TEST_VECTORS_VERSION, CurrentNetwork::ID));
+    assert_eq!(TestVectors::read(&path).unwrap(), vectors);
+}
+
+#[test]
+fn test_transmission_kinds() {
+    use crate::TransmissionKind;
+    use snarkvm::synthesizer::ConsensusMemory;
+
+    /// A sample transmission kind, which is valid while the first byte is at least the latest height,
+    /// and is carried into a block by the given transaction.
+    struct Blob(&'static str, Transaction<CurrentNetwork>);
+
+    impl TransmissionKind<CurrentNetwork, ConsensusMemory<CurrentNetwork>> for Blob {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn max_size(&self) -> usize {
+            4
+        }
+
+        fn check(&self, ledger: &test_helpers::CurrentLedger, payload: &[u8]) -> anyhow::Result<()> {
+            match payload.first() {
+                Some(byte) if *byte as u32 >= ledger.latest_height() => Ok(()),
+                _ => anyhow::bail!("The blob is stale"),
+            }
+        }
+
+        fn fee(&self, payload: &[u8]) -> anyhow::Result<u64> {
+            Ok(payload.len() as u64)
+        }
+
+        fn to_transaction(&self, _payload: &[u8]) -> anyhow::Result<Transaction<CurrentNetwork>> {
+            Ok(self.1.clone())
+        }
+    }
+
+    let rng = &mut TestRng::default();
+
+    // Sample the genesis private key.
+    let private_key = crate::tests::test_helpers::sample_genesis_private_key(rng);
+    // Sample the genesis consensus.
+    let consensus = crate::tests::test_helpers::sample_genesis_consensus(rng);
+    // Sample the transactions that carry the transmissions.
+    let execution = test_helpers::sample_execution_transaction(rng);
+    let deployment = test_helpers::sample_deployment_transaction(rng);
+
+    // Ensure the unregistered and native kinds are rejected.
+    assert!(consensus.add_unconfirmed_transmission("blob", vec![1]).is_err());
+    assert!(consensus.register_transmission_kind(Blob("solution", execution.clone())).is_err());
+    assert!(consensus.register_transmission_kind(Blob("", execution.clone())).is_err());
+
+    // Register the kind, and ensure it is registered once.
+    consensus.register_transmission_kind(Blob("blob", execution.clone())).unwrap();
+    assert!(consensus.register_transmission_kind(Blob("blob", execution.clone())).is_err());
+    assert_eq!(consensus.transmissions().kinds(), vec!["solution", "transaction", "blob"]);
+
+    // Ensure the transmissions are checked against the rules of the kind.
+    assert!(consensus.add_unconfirmed_transmission("blob", vec![]).is_err());
+    assert!(consensus.add_unconfirmed_transmission("blob", vec![1; 5]).is_err());
+    assert!(consensus.add_unconfirmed_transmission("blob", vec![1, 2, 3]).unwrap());
+    assert!(!consensus.add_unconfirmed_transmission("blob", vec![1, 2, 3]).unwrap());
+    assert_eq!(consensus.transmissions().unconfirmed("blob"), vec![(vec![1, 2, 3], 3)]);
+
+    // Register a kind that becomes stale in the next block, and is outbid by the blob.
+    consensus.register_transmission_kind(Blob("stale", deployment)).unwrap();
+    assert!(consensus.add_unconfirmed_transmission("stale", vec![0]).unwrap());
+    assert_eq!(consensus.unconfirmed_transmissions("stale").unwrap(), vec![(vec![0], 1)]);
+
+    // Ensure the native kinds are routed to the memory pool.
+    assert!(consensus.add_unconfirmed_transmission("solution", vec![1, 2, 3]).is_err());
+    assert!(consensus.unconfirmed_transmissions("transaction").unwrap().is_empty());
+
+    // Ensure the next block includes the transaction that carries the blob.
+    let next_block = consensus.propose_next_block(&private_key, rng).unwrap();
+    assert!(next_block.transaction_ids().any(|transaction_id| *transaction_id == execution.id()));
+
+    // Advance to the next block, and ensure the included and stale transmissions are removed.
+    consensus.advance_to_next_block(&next_block).unwrap();
+    assert!(consensus.transmissions().unconfirmed("blob").is_empty());
+    assert!(consensus.transmissions().unconfirmed("stale").is_empty());
+    assert_eq!(consensus.transmissions().num_unconfirmed(), 0);
+
+    // Ensure a transmission that is carried by a transaction in the ledger is rejected.
+    assert!(consensus.add_unconfirmed_transmission("blob", vec![5]).is_err());
+    assert!(consensus.add_unconfirmed_transmission("transaction", execution.to_bytes_le().unwrap()).is_err());
+}
+
+#[test]
+fn test_proof_verifier() {
+    use crate::{serve_verifier, ProofVerifier, VerifierConfig, VerifierEndpoint, VerifierKey};
+    use std::net::{Shutdown, TcpListener};
+
+    let rng = &mut TestRng::default();
+
+    // Sample the genesis block, and reject its first transaction on the verification service.
+    let genesis = test_helpers::sample_genesis_block(rng);
+    let rejected_id = genesis.transactions().iter().next().unwrap().id();
+    let key = VerifierKey::new("a shared verifier key").unwrap();
+    assert!(VerifierKey::new("too short").is_err());
+
+    // Ensure an unreachable verification service is rejected.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let verifier = ProofVerifier::default();
+    drop(listener);
+    assert!(verifier.configure(VerifierConfig::new(VerifierEndpoint::Remote { addr, key: key.clone() })).is_err());
+    assert!(!verifier.is_enabled());
+    assert!(verifier.verify::<CurrentNetwork>([]).is_none());
+
+    // Serves a single connection to the verification service with the given key, and returns a handle to its stream.
+    let serve = move |listener: TcpListener, key: VerifierKey| {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let service = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            sender.send(stream.try_clone().unwrap()).unwrap();
+            serve_verifier::<CurrentNetwork>(stream.try_clone().unwrap(), stream, Some(&key), |transaction| {
+                anyhow::ensure!(transaction.id() != rejected_id, "Invalid proof");
+                Ok(())
+            })
+        });
+        (service, receiver)
+    };
+
+    // Ensure a client with another key is refused.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (service, _) = serve(listener, key.clone());
+    let other_key = VerifierKey::new("another shared verifier key").unwrap();
+    assert!(verifier.configure(VerifierConfig::new(VerifierEndpoint::Remote { addr, key: other_key })).is_err());
+    assert!(service.join().unwrap().is_err());
+    assert!(!verifier.is_enabled());
+
+    // Serve a single connection to the verification service.
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (service, streams) = serve(listener, key.clone());
+    verifier.configure(VerifierConfig::new(VerifierEndpoint::Remote { addr, key })).unwrap();
+    assert!(verifier.is_enabled());
+
+    // Ensure the verdicts of the service are returned.
+    assert!(verifier.verify::<CurrentNetwork>([]).unwrap().is_ok());
+    let error = verifier.verify(genesis.transactions().iter()).unwrap().unwrap_err();
+    assert!(error.to_string().contains("Invalid proof"));
+    assert!(verifier.verify(genesis.transactions().iter().skip(1)).unwrap().is_ok());
+
+    // Ensure the caller falls back to local verification, if the service fails to respond.
+    streams.recv().unwrap().shutdown(Shutdown::Both).unwrap();
+    service.join().unwrap().unwrap();
+    assert!(verifier.verify(genesis.transactions().iter()).is_none());
+    assert!(verifier.is_enabled());
+}
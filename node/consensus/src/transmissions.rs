@@ -0,0 +1,253 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkos_node_ledger::Ledger;
+use snarkos_node_messages::{MAX_TRANSMISSION_KIND_LEN, SOLUTION_TRANSMISSION_KIND, TRANSACTION_TRANSMISSION_KIND};
+use snarkvm::prelude::{ConsensusStorage, Network, Transaction};
+
+use anyhow::{anyhow, bail, ensure, Result};
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+/// The maximum number of unconfirmed transmissions of each kind in the memory pool.
+const MAX_TRANSMISSIONS_PER_KIND: usize = 1024;
+/// The names of the transmission kinds that are handled natively.
+const NATIVE_KINDS: [&str; 2] = [SOLUTION_TRANSMISSION_KIND, TRANSACTION_TRANSMISSION_KIND];
+
+/// The rules of a kind of transmission beyond the solutions and transactions (e.g. data blobs or attestations),
+/// which is gossiped between the nodes and held in the memory pool. Each transmission is an opaque payload,
+/// which is interpreted by its kind, and is included in a block by the transaction that carries it.
+pub trait TransmissionKind<N: Network, C: ConsensusStorage<N>>: Send + Sync {
+    /// Returns the name of the kind, which identifies its transmissions on the network.
+    fn name(&self) -> &'static str;
+
+    /// Returns the maximum size of a transmission of this kind, in bytes.
+    fn max_size(&self) -> usize;
+
+    /// Checks the given transmission against the ledger.
+    /// Note: The unconfirmed transmissions are re-checked after each block, and removed once they are invalid.
+    fn check(&self, ledger: &Ledger<N, C>, payload: &[u8]) -> Result<()>;
+
+    /// Returns the fee paid by the given transmission (in microcredits), by which it is ranked in the memory pool.
+    fn fee(&self, payload: &[u8]) -> Result<u64>;
+
+    /// Returns the transaction that carries the given transmission into a block.
+    /// Once the transaction is in the ledger, the transmission is removed from the memory pool.
+    fn to_transaction(&self, payload: &[u8]) -> Result<Transaction<N>>;
+}
+
+/// An unconfirmed transmission in the memory pool.
+#[derive(Clone)]
+struct Unconfirmed<N: Network> {
+    /// The fee paid by the transmission (in microcredits).
+    fee: u64,
+    /// The transaction that carries the transmission into a block.
+    transaction: Transaction<N>,
+}
+
+/// The registry of the transmission kinds, and the pool of their unconfirmed transmissions.
+#[derive(Clone)]
+#[allow(clippy::type_complexity)]
+pub struct Transmissions<N: Network, C: ConsensusStorage<N>> {
+    /// The registered transmission kinds, by name.
+    kinds: Arc<RwLock<IndexMap<&'static str, Arc<dyn TransmissionKind<N, C>>>>>,
+    /// The unconfirmed transmissions, for each kind, in order of arrival.
+    pool: Arc<RwLock<IndexMap<&'static str, IndexMap<Vec<u8>, Unconfirmed<N>>>>>,
+}
+
+impl<N: Network, C: ConsensusStorage<N>> Default for Transmissions<N, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>> Transmissions<N, C> {
+    /// Initializes a new, empty registry.
+    pub fn new() -> Self {
+        Self { kinds: Default::default(), pool: Default::default() }
+    }
+
+    /// Registers the given transmission kind.
+    pub fn register(&self, kind: impl TransmissionKind<N, C> + 'static) -> Result<()> {
+        let name = kind.name();
+        ensure!(!name.is_empty() && name.len() <= MAX_TRANSMISSION_KIND_LEN, "Invalid transmission kind '{name}'");
+        ensure!(!NATIVE_KINDS.contains(&name), "The '{name}' transmissions are handled natively");
+
+        let mut kinds = self.kinds.write();
+        ensure!(!kinds.contains_key(name), "The '{name}' transmission kind is already registered");
+        kinds.insert(name, Arc::new(kind));
+        info!("Registered the '{name}' transmission kind");
+        Ok(())
+    }
+
+    /// Returns the names of the transmission kinds, starting with the native kinds.
+    pub fn kinds(&self) -> Vec<&'static str> {
+        NATIVE_KINDS.into_iter().chain(self.kinds.read().keys().copied()).collect()
+    }
+
+    /// Returns `true` if the given transmission kind is native or registered.
+    pub fn is_supported(&self, name: &str) -> bool {
+        NATIVE_KINDS.contains(&name) || self.is_registered(name)
+    }
+
+    /// Returns `true` if the given transmission kind is registered.
+    pub fn is_registered(&self, name: &str) -> bool {
+        self.kinds.read().contains_key(name)
+    }
+
+    /// Returns the registered transmission kind with the given name.
+    fn get(&self, name: &str) -> Result<(&'static str, Arc<dyn TransmissionKind<N, C>>)> {
+        self.kinds
+            .read()
+            .get_key_value(name)
+            .map(|(name, kind)| (*name, kind.clone()))
+            .ok_or_else(|| anyhow!("The '{name}' transmission kind is not registered"))
+    }
+
+    /// Checks the given transmission against the rules of its kind, and returns its fee.
+    pub fn check(&self, ledger: &Ledger<N, C>, name: &str, payload: &[u8]) -> Result<u64> {
+        let (name, kind) = self.get(name)?;
+        ensure!(
+            payload.len() <= kind.max_size(),
+            "The '{name}' transmission exceeds the maximum size ({} > {} bytes)",
+            payload.len(),
+            kind.max_size()
+        );
+        kind.check(ledger, payload)?;
+        kind.fee(payload)
+    }
+
+    /// Adds the given transmission to the memory pool, after checking it against the rules of its kind.
+    /// Returns `false` if the transmission is already in the memory pool. If the pool of the kind is full,
+    /// the transmission with the lowest fee is evicted, if it pays less than the given transmission.
+    /// Note: The checks are performed before the memory pool is locked, and may be expensive.
+    pub fn add(&self, ledger: &Ledger<N, C>, name: &str, payload: Vec<u8>) -> Result<bool> {
+        let (name, kind) = self.get(name)?;
+        // Skip the checks, if the transmission is already in the memory pool.
+        if self.pool.read().get(name).map_or(false, |transmissions| transmissions.contains_key(&payload)) {
+            trace!("The '{name}' transmission already exists in the memory pool");
+            return Ok(false);
+        }
+        let fee = self.check(ledger, name, &payload)?;
+        let transaction = kind.to_transaction(&payload)?;
+        ensure!(
+            !ledger.contains_transaction_id(&transaction.id())?,
+            "The '{name}' transmission is already in the ledger (transaction '{}')",
+            transaction.id()
+        );
+
+        let mut pool = self.pool.write();
+        let transmissions = pool.entry(name).or_default();
+        if transmissions.contains_key(&payload) {
+            trace!("The '{name}' transmission already exists in the memory pool");
+            return Ok(false);
+        }
+        if transmissions.len() >= MAX_TRANSMISSIONS_PER_KIND {
+            // Find the transmission with the lowest fee.
+            let lowest =
+                transmissions.values().map(|unconfirmed| unconfirmed.fee).enumerate().min_by_key(|(_, fee)| *fee);
+            match lowest {
+                Some((index, lowest_fee)) if lowest_fee < fee => {
+                    transmissions.shift_remove_index(index);
+                }
+                _ => bail!("The memory pool is full of '{name}' transmissions that pay a higher fee"),
+            }
+        }
+        transmissions.insert(payload, Unconfirmed { fee, transaction });
+        debug!("✉️  Added a '{name}' transmission with fee '{fee}' to the memory pool");
+        Ok(true)
+    }
+
+    /// Returns the unconfirmed transmissions of the given kind and their fees, in order of arrival.
+    pub fn unconfirmed(&self, name: &str) -> Vec<(Vec<u8>, u64)> {
+        match self.pool.read().get(name) {
+            Some(transmissions) => {
+                transmissions.iter().map(|(payload, unconfirmed)| (payload.clone(), unconfirmed.fee)).collect()
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the transactions that carry the unconfirmed transmissions, from the highest fee to the lowest.
+    pub fn candidate_transactions(&self) -> Vec<Transaction<N>> {
+        let mut candidates =
+            self.pool.read().values().flat_map(|transmissions| transmissions.values().cloned()).collect::<Vec<_>>();
+        candidates.sort_by(|a, b| b.fee.cmp(&a.fee));
+        candidates.into_iter().map(|unconfirmed| unconfirmed.transaction).collect()
+    }
+
+    /// Returns the number of unconfirmed transmissions of all kinds.
+    pub fn num_unconfirmed(&self) -> usize {
+        self.pool.read().values().map(|transmissions| transmissions.len()).sum()
+    }
+
+    /// Clears the memory pool of the unconfirmed transmissions that were included in a block, or are now invalid.
+    /// Note: The transmissions are checked before the memory pool is locked.
+    pub fn clear_invalid(&self, ledger: &Ledger<N, C>) {
+        let kinds = self.kinds.read().clone();
+        // Retrieve the unconfirmed transmissions, and the IDs of the transactions that carry them.
+        let pool = self
+            .pool
+            .read()
+            .iter()
+            .map(|(name, transmissions)| {
+                let transmissions = transmissions
+                    .iter()
+                    .map(|(payload, unconfirmed)| (payload.clone(), unconfirmed.transaction.id()))
+                    .collect::<Vec<_>>();
+                (*name, transmissions)
+            })
+            .collect::<Vec<_>>();
+
+        // Find the transmissions that were included in the ledger, or are now invalid.
+        let mut invalid = HashMap::<&'static str, HashSet<Vec<u8>>>::new();
+        for (name, transmissions) in pool {
+            let kind = match kinds.get(name) {
+                Some(kind) => kind,
+                None => continue,
+            };
+            for (payload, transaction_id) in transmissions {
+                let result = match ledger.contains_transaction_id(&transaction_id) {
+                    Ok(true) => Err(anyhow!("The transaction '{transaction_id}' is in the ledger")),
+                    Ok(false) => kind.check(ledger, &payload),
+                    Err(error) => Err(error),
+                };
+                if let Err(error) = result {
+                    trace!("Removed a '{name}' transmission from the memory pool - {error}");
+                    invalid.entry(name).or_default().insert(payload);
+                }
+            }
+        }
+
+        // Remove the transmissions from the memory pool.
+        if !invalid.is_empty() {
+            for (name, transmissions) in self.pool.write().iter_mut() {
+                if let Some(invalid) = invalid.get(name) {
+                    transmissions.retain(|payload, _| !invalid.contains(payload));
+                }
+            }
+        }
+    }
+
+    /// Clears the memory pool of all unconfirmed transmissions.
+    pub fn clear(&self) {
+        self.pool.write().clear();
+    }
+}
@@ -0,0 +1,249 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+use anyhow::{bail, Context};
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// The version of the test vector format.
+/// Note: Bump the version on any change to the fields, or to the transactions that are generated.
+pub const TEST_VECTORS_VERSION: u16 = 1;
+
+/// The fee (in microcredits) paid by the fee transition of the test vectors.
+const TEST_VECTOR_FEE: u64 = 100;
+
+/// A single test vector, for the execution and fee circuits of one transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestVector {
+    /// The name of the test vector.
+    pub name: String,
+    /// The program ID of the executed function.
+    pub program_id: String,
+    /// The name of the executed function.
+    pub function_name: String,
+    /// The inputs of the executed function.
+    pub inputs: Vec<String>,
+    /// The record spent on the fee, and the fee amount, if the transaction pays a fee.
+    pub fee: Option<(String, u64)>,
+    /// The transaction, as little-endian bytes in hex.
+    pub transaction: String,
+    /// The expected transaction ID.
+    pub transaction_id: String,
+    /// The expected transition IDs, in order.
+    pub transition_ids: Vec<String>,
+    /// The expected serial numbers, in order.
+    pub serial_numbers: Vec<String>,
+    /// The expected commitments, in order.
+    pub commitments: Vec<String>,
+    /// The expected verification result of the transaction.
+    pub is_valid: bool,
+}
+
+impl TestVector {
+    /// Initializes a new test vector, with the expectations derived from the given transaction.
+    fn new<N: Network>(
+        name: &str,
+        (program_id, function_name, inputs): (&str, &str, &[Value<N>]),
+        fee: Option<&(Record<N, Plaintext<N>>, u64)>,
+        transaction: &Transaction<N>,
+    ) -> Result<Self> {
+        Ok(Self {
+            name: name.to_string(),
+            program_id: program_id.to_string(),
+            function_name: function_name.to_string(),
+            inputs: inputs.iter().map(|input| input.to_string()).collect(),
+            fee: fee.map(|(record, amount)| (record.to_string(), *amount)),
+            transaction: hex::encode(transaction.to_bytes_le()?),
+            transaction_id: transaction.id().to_string(),
+            transition_ids: transaction.transitions().map(|transition| transition.id().to_string()).collect(),
+            serial_numbers: transaction.serial_numbers().map(|serial_number| serial_number.to_string()).collect(),
+            commitments: transaction.commitments().map(|commitment| commitment.to_string()).collect(),
+            is_valid: true,
+        })
+    }
+
+    /// Checks the transaction of the test vector against its expectations, with the given VM.
+    pub fn check<N: Network, C: ConsensusStorage<N>>(&self, vm: &VM<N, C>) -> Result<()> {
+        let transaction = hex::decode(&self.transaction)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| Transaction::<N>::from_bytes_le(&bytes));
+        match (transaction, self.is_valid) {
+            (Ok(transaction), true) => {
+                let transition_ids = transaction.transitions().map(|transition| transition.id().to_string());
+                let serial_numbers = transaction.serial_numbers().map(|serial_number| serial_number.to_string());
+                let commitments = transaction.commitments().map(|commitment| commitment.to_string());
+                ensure!(transaction.id().to_string() == self.transaction_id, "The transaction ID does not match");
+                ensure!(transition_ids.eq(self.transition_ids.iter().cloned()), "The transition IDs do not match");
+                ensure!(serial_numbers.eq(self.serial_numbers.iter().cloned()), "The serial numbers do not match");
+                ensure!(commitments.eq(self.commitments.iter().cloned()), "The commitments do not match");
+                ensure!(vm.verify_transaction(&transaction), "The transaction is expected to be valid");
+            }
+            (Ok(transaction), false) => {
+                ensure!(!vm.verify_transaction(&transaction), "The transaction is expected to be invalid")
+            }
+            // Note: An invalid transaction must still deserialize, so that it is rejected by the verification.
+            (Err(error), _) => bail!("The transaction is malformed - {error}"),
+        }
+        Ok(())
+    }
+}
+
+/// A versioned set of test vectors for the transaction circuits, generated from a fixed seed.
+///
+/// The vectors record the inputs and private records of each transaction, alongside the expected
+/// IDs, serial numbers, commitments, and verification result, such that alternative implementations
+/// and audits can check their transaction verification against the same fixtures.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestVectors {
+    /// The version of the test vector format.
+    pub version: u16,
+    /// The network ID.
+    pub network: u16,
+    /// The seed the test vectors were generated from.
+    pub seed: u64,
+    /// The private key of the caller.
+    pub private_key: String,
+    /// The genesis block the transactions are built on, as little-endian bytes in hex.
+    pub genesis: String,
+    /// The test vectors.
+    pub vectors: Vec<TestVector>,
+}
+
+impl TestVectors {
+    /// Generates the test vectors from the given seed.
+    pub fn generate<N: Network>(seed: u64) -> Result<Self> {
+        let rng = &mut ChaChaRng::seed_from_u64(seed);
+
+        // Sample the caller, and the genesis block.
+        let private_key = PrivateKey::<N>::new(rng)?;
+        let address = Address::try_from(&private_key)?;
+        let vm = VM::from(ConsensusStore::<N, ConsensusMemory<N>>::open(None)?)?;
+        let genesis = Block::genesis(&vm, &private_key, rng)?;
+
+        // Initialize the ledger, and select the record to spend.
+        let ledger = Ledger::<N, ConsensusMemory<N>>::load(genesis.clone(), None)?;
+        let record = match ledger.find_unspent_records(&ViewKey::try_from(&private_key)?)?.values().next() {
+            Some(record) => record.clone(),
+            None => bail!("The genesis block has no records to spend"),
+        };
+
+        let mut vectors = Vec::new();
+
+        // Generate a mint, with a fee.
+        let inputs = [Value::<N>::from_str(&address.to_string())?, Value::from_str("1u64")?];
+        let fee = (record.clone(), TEST_VECTOR_FEE);
+        let call = ("credits.aleo", "mint");
+        let transaction =
+            Transaction::execute(ledger.vm(), &private_key, call, inputs.iter(), Some(fee.clone()), None, rng)?;
+        vectors.push(TestVector::new("mint", (call.0, call.1, &inputs), Some(&fee), &transaction)?);
+
+        // Generate a transfer, without a fee.
+        let inputs = [Value::Record(record), Value::from_str(&address.to_string())?, Value::from_str("1u64")?];
+        let call = ("credits.aleo", "transfer");
+        let transaction = Transaction::execute(ledger.vm(), &private_key, call, inputs.iter(), None, None, rng)?;
+        let transfer = TestVector::new("transfer", (call.0, call.1, &inputs), None, &transaction)?;
+
+        // Derive an invalid transfer, by tampering with a public input of its proof.
+        let tampered = TestVector {
+            name: "transfer-tampered".to_string(),
+            transaction: hex::encode(tamper(ledger.vm(), &transaction)?),
+            transaction_id: String::new(),
+            transition_ids: vec![],
+            serial_numbers: vec![],
+            commitments: vec![],
+            is_valid: false,
+            ..transfer.clone()
+        };
+        vectors.extend([transfer, tampered]);
+
+        Ok(Self {
+            version: TEST_VECTORS_VERSION,
+            network: N::ID,
+            seed,
+            private_key: private_key.to_string(),
+            genesis: hex::encode(genesis.to_bytes_le()?),
+            vectors,
+        })
+    }
+
+    /// Checks every test vector against its expectations, with a ledger initialized from the genesis block.
+    pub fn check<N: Network>(&self) -> Result<()> {
+        ensure!(self.version == TEST_VECTORS_VERSION, "Unsupported test vector version ({})", self.version);
+        ensure!(self.network == N::ID, "The test vectors are for network {}, not {}", self.network, N::ID);
+
+        // Initialize the ledger from the genesis block.
+        let genesis = Block::<N>::from_bytes_le(&hex::decode(&self.genesis)?)?;
+        let ledger = Ledger::<N, ConsensusMemory<N>>::load(genesis, None)?;
+
+        // Check each test vector.
+        for vector in &self.vectors {
+            vector.check(ledger.vm()).with_context(|| format!("Test vector '{}' failed", vector.name))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the path of the fixture file for the given version and network, in the given directory.
+    pub fn path(directory: &Path, version: u16, network: u16) -> PathBuf {
+        directory.join(format!("v{version}")).join(format!("network-{network}.json"))
+    }
+
+    /// Writes the test vectors to their fixture file in the given directory, and returns its path.
+    pub fn write(&self, directory: &Path) -> Result<PathBuf> {
+        let path = Self::path(directory, self.version, self.network);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+
+    /// Reads the test vectors from the given fixture file.
+    pub fn read(path: &Path) -> Result<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+}
+
+/// Returns the bytes of the given transaction, with a public input of its proof tampered with, such that
+/// the transaction still deserializes, but fails to verify.
+fn tamper<N: Network, C: ConsensusStorage<N>>(vm: &VM<N, C>, transaction: &Transaction<N>) -> Result<Vec<u8>> {
+    let bytes = transaction.to_bytes_le()?;
+    // Flip the least significant bit of a public field element, i.e. a transition or an output commitment.
+    let candidates =
+        transaction.transitions().map(|transition| *transition.tcm()).chain(transaction.commitments().copied());
+    for candidate in candidates {
+        let candidate = candidate.to_bytes_le()?;
+        let index = match bytes.windows(candidate.len()).position(|window| window == candidate.as_slice()) {
+            Some(index) => index,
+            None => continue,
+        };
+        let mut tampered = bytes.clone();
+        tampered[index] ^= 1;
+        if let Ok(tampered_transaction) = Transaction::<N>::from_bytes_le(&tampered) {
+            if !vm.verify_transaction(&tampered_transaction) {
+                return Ok(tampered);
+            }
+        }
+    }
+    bail!("Failed to tamper with a public input of transaction '{}'", transaction.id())
+}
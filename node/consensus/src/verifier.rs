@@ -0,0 +1,469 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::prelude::*;
+
+use anyhow::{anyhow, bail, ensure, Result};
+use parking_lot::{Mutex, RwLock};
+use rand::{rngs::OsRng, RngCore};
+use ring::{constant_time, hmac};
+use std::{
+    fmt,
+    io::{self, BufReader, BufWriter, Read, Write},
+    net::{SocketAddr, TcpStream},
+    path::PathBuf,
+    process::{Child, ChildStdin, Command, Stdio},
+    str::FromStr,
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The default timeout of a verification request to the verification service.
+pub const DEFAULT_VERIFIER_TIMEOUT: Duration = Duration::from_secs(60);
+/// The minimum length of the key shared with a remote verification service (in bytes).
+pub const MIN_VERIFIER_KEY_LEN: usize = 16;
+/// The maximum size of a frame exchanged with the verification service (in bytes).
+/// Note: Larger sets of transactions are split into several batches.
+const MAX_VERIFIER_FRAME_BYTES: u32 = 16 * 1024 * 1024;
+/// The maximum number of idle connections retained to the verification service.
+const MAX_IDLE_CONNECTIONS: usize = 4;
+/// The size of the challenge a remote verification service opens a session with (in bytes).
+const CHALLENGE_BYTES: usize = 32;
+/// The size of a message authentication tag (in bytes).
+const TAG_BYTES: usize = 32;
+
+/// The status byte of a response, if every transaction in the batch verified.
+const STATUS_VERIFIED: u8 = 0;
+/// The status byte of a response, if a transaction in the batch failed to verify.
+const STATUS_REJECTED: u8 = 1;
+
+/// The key shared with a remote verification service, which authenticates the requests and verdicts.
+#[derive(Clone, PartialEq, Eq)]
+pub struct VerifierKey(Vec<u8>);
+
+impl VerifierKey {
+    /// Initializes the key from the given shared secret.
+    pub fn new(secret: &str) -> Result<Self> {
+        ensure!(
+            secret.len() >= MIN_VERIFIER_KEY_LEN,
+            "The verifier key must be at least {MIN_VERIFIER_KEY_LEN} bytes long"
+        );
+        Ok(Self(secret.as_bytes().to_vec()))
+    }
+}
+
+impl fmt::Debug for VerifierKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Note: The secret is never logged.
+        write!(f, "VerifierKey(..)")
+    }
+}
+
+/// The endpoint of a verification service.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerifierEndpoint {
+    /// A child process, which is spawned with the given program and arguments, and served over its stdin and stdout.
+    Process { program: PathBuf, args: Vec<String> },
+    /// A remote service, which is served over TCP at the given address, and authenticated with the shared key.
+    Remote { addr: SocketAddr, key: VerifierKey },
+}
+
+/// The configuration of the verification service.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifierConfig {
+    /// The endpoint of the verification service.
+    pub endpoint: VerifierEndpoint,
+    /// The timeout of a verification request to the service.
+    pub timeout: Duration,
+}
+
+impl VerifierConfig {
+    /// Initializes a new verifier configuration for the given endpoint.
+    pub const fn new(endpoint: VerifierEndpoint) -> Self {
+        Self { endpoint, timeout: DEFAULT_VERIFIER_TIMEOUT }
+    }
+}
+
+/// An authenticated session with a remote verification service. Each message is authenticated with the shared key,
+/// the challenge of the service, and the number of the exchange, so that it can be neither forged nor replayed.
+struct Session {
+    /// The key shared with the service.
+    key: hmac::Key,
+    /// The challenge the service opened the session with.
+    challenge: [u8; CHALLENGE_BYTES],
+    /// The number of exchanges in the session.
+    counter: u64,
+}
+
+impl Session {
+    /// Initializes a session with the given key and challenge.
+    fn new(key: &VerifierKey, challenge: [u8; CHALLENGE_BYTES]) -> Self {
+        Self { key: hmac::Key::new(hmac::HMAC_SHA256, &key.0), challenge, counter: 0 }
+    }
+
+    /// Returns the authentication tag of the given message parts, under the given label.
+    fn tag(&self, label: &[u8], parts: &[&[u8]]) -> [u8; TAG_BYTES] {
+        let mut context = hmac::Context::with_key(&self.key);
+        context.update(label);
+        context.update(&self.challenge);
+        context.update(&self.counter.to_le_bytes());
+        for part in parts {
+            context.update(&(part.len() as u64).to_le_bytes());
+            context.update(part);
+        }
+        let mut tag = [0u8; TAG_BYTES];
+        tag.copy_from_slice(context.sign().as_ref());
+        tag
+    }
+
+    /// Ensures the given tag is the authentication tag of the given message parts, under the given label.
+    fn verify(&self, label: &[u8], parts: &[&[u8]], tag: &[u8; TAG_BYTES]) -> io::Result<()> {
+        constant_time::verify_slices_are_equal(&self.tag(label, parts), tag).map_err(|_| {
+            io::Error::new(io::ErrorKind::PermissionDenied, "Failed to authenticate the verification peer")
+        })
+    }
+}
+
+/// A verdict of the verification service, as its status and message.
+type Verdict = (u8, Vec<u8>);
+
+/// A connection to the verification service.
+enum Connection {
+    /// The pipes of a child process, whose verdicts are read on a dedicated thread, so that they can time out.
+    Process { child: Child, stdin: BufWriter<ChildStdin>, verdicts: Receiver<io::Result<Verdict>>, timeout: Duration },
+    /// An authenticated TCP stream to a remote service.
+    Remote { reader: BufReader<TcpStream>, writer: BufWriter<TcpStream>, session: Session },
+}
+
+impl Connection {
+    /// Connects to the given verification service.
+    fn open(config: &VerifierConfig) -> Result<Self> {
+        match &config.endpoint {
+            VerifierEndpoint::Process { program, args } => {
+                let mut child = Command::new(program)
+                    .args(args)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::inherit())
+                    .spawn()?;
+                let (stdin, stdout) = match (child.stdin.take(), child.stdout.take()) {
+                    (Some(stdin), Some(stdout)) => (stdin, stdout),
+                    _ => bail!("Failed to open the pipes of the verification process"),
+                };
+                // Read the verdicts, until the process exits or the connection is dropped.
+                let (sender, verdicts) = mpsc::sync_channel(1);
+                thread::Builder::new().name("proof-verifier-reader".to_string()).spawn(move || {
+                    let mut stdout = BufReader::new(stdout);
+                    loop {
+                        let verdict = read_verdict(&mut stdout);
+                        let is_err = verdict.is_err();
+                        if sender.send(verdict).is_err() || is_err {
+                            break;
+                        }
+                    }
+                })?;
+                Ok(Self::Process { child, stdin: BufWriter::new(stdin), verdicts, timeout: config.timeout })
+            }
+            VerifierEndpoint::Remote { addr, key } => {
+                let stream = TcpStream::connect_timeout(addr, config.timeout)?;
+                stream.set_read_timeout(Some(config.timeout))?;
+                stream.set_write_timeout(Some(config.timeout))?;
+                stream.set_nodelay(true)?;
+                let (mut reader, mut writer) = (BufReader::new(stream.try_clone()?), BufWriter::new(stream));
+
+                // Answer the challenge of the service, and ensure it answers with the same key.
+                let mut challenge = [0u8; CHALLENGE_BYTES];
+                reader.read_exact(&mut challenge)?;
+                let session = Session::new(key, challenge);
+                writer.write_all(&session.tag(b"client-hello", &[]))?;
+                writer.flush()?;
+                let mut tag = [0u8; TAG_BYTES];
+                reader.read_exact(&mut tag)?;
+                session.verify(b"server-hello", &[], &tag)?;
+
+                Ok(Self::Remote { reader, writer, session })
+            }
+        }
+    }
+
+    /// Sends the given batch to the verification service, and returns its verdict.
+    fn exchange(&mut self, batch: &[u8]) -> io::Result<Result<()>> {
+        let (status, message) = match self {
+            Self::Process { stdin, verdicts, timeout, .. } => {
+                write_frame(stdin, batch)?;
+                stdin.flush()?;
+                match verdicts.recv_timeout(*timeout) {
+                    Ok(verdict) => verdict?,
+                    Err(RecvTimeoutError::Timeout) => {
+                        return Err(io::Error::new(io::ErrorKind::TimedOut, "The verification process timed out"));
+                    }
+                    Err(RecvTimeoutError::Disconnected) => {
+                        return Err(io::Error::new(io::ErrorKind::BrokenPipe, "The verification process exited"));
+                    }
+                }
+            }
+            Self::Remote { reader, writer, session } => {
+                let request_tag = session.tag(b"request", &[batch]);
+                write_frame(writer, batch)?;
+                writer.write_all(&request_tag)?;
+                writer.flush()?;
+
+                let (status, message) = read_verdict(reader)?;
+                let mut tag = [0u8; TAG_BYTES];
+                reader.read_exact(&mut tag)?;
+                session.verify(b"response", &[&request_tag, &[status], &message], &tag)?;
+                session.counter += 1;
+                (status, message)
+            }
+        };
+        match status {
+            STATUS_VERIFIED => Ok(Ok(())),
+            STATUS_REJECTED => Ok(Err(anyhow!("{}", String::from_utf8_lossy(&message)))),
+            status => Err(io::Error::new(io::ErrorKind::InvalidData, format!("Invalid verifier status '{status}'"))),
+        }
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        // Ensure the verification process does not outlive its connection.
+        if let Self::Process { child, .. } = self {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// The proof verifier offloads the proof verification of transaction batches to a verification service,
+/// isolating the memory-heavy verification from the consensus process. Each request is served on its own
+/// connection, so concurrent requests are verified concurrently.
+/// If the service is not configured or unreachable, the caller verifies the proofs itself.
+#[derive(Clone, Default)]
+pub struct ProofVerifier {
+    /// The configuration of the service, if one is configured.
+    config: Arc<RwLock<Option<VerifierConfig>>>,
+    /// The idle connections to the service.
+    idle: Arc<Mutex<Vec<Connection>>>,
+}
+
+impl ProofVerifier {
+    /// Configures the verification service, and ensures it is reachable.
+    pub fn configure(&self, config: VerifierConfig) -> Result<()> {
+        let connection = Connection::open(&config)?;
+        match &config.endpoint {
+            VerifierEndpoint::Process { program, .. } => {
+                info!("Offloading the proof verification to the process '{}'", program.display())
+            }
+            VerifierEndpoint::Remote { addr, .. } => info!("Offloading the proof verification to '{addr}'"),
+        }
+        *self.config.write() = Some(config);
+        *self.idle.lock() = vec![connection];
+        Ok(())
+    }
+
+    /// Returns `true` if a verification service is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.config.read().is_some()
+    }
+
+    /// Verifies the proofs of the given transactions with the verification service, in parallel batches.
+    /// Returns `None` if the service is not configured, or fails to respond, in which case the proofs are not verified.
+    pub fn verify<'a, N: Network>(
+        &self,
+        transactions: impl IntoIterator<Item = &'a Transaction<N>>,
+    ) -> Option<Result<()>> {
+        let config = self.config.read().clone()?;
+        let batches = match encode_batches(transactions) {
+            Ok(batches) => batches,
+            Err(error) => {
+                warn!("Failed to encode a batch for the verification service - {error}");
+                return None;
+            }
+        };
+        let verdicts = cfg_iter!(batches).map(|batch| self.exchange(&config, batch)).collect::<Vec<_>>();
+        // Return the first rejection, unless a batch was not verified.
+        let mut result = Ok(());
+        for verdict in verdicts {
+            match verdict? {
+                Ok(()) => (),
+                Err(error) => result = result.and(Err(error)),
+            }
+        }
+        Some(result)
+    }
+
+    /// Sends the given batch on an idle connection (or a new one), and returns the verdict of the service.
+    fn exchange(&self, config: &VerifierConfig, batch: &[u8]) -> Option<Result<()>> {
+        let connection = self.idle.lock().pop();
+        let mut connection = match connection {
+            Some(connection) => connection,
+            None => match Connection::open(config) {
+                Ok(connection) => connection,
+                Err(error) => {
+                    warn!("Failed to connect to the verification service - {error}");
+                    return None;
+                }
+            },
+        };
+        match connection.exchange(batch) {
+            Ok(result) => {
+                // Retain the connection for the next request.
+                let mut idle = self.idle.lock();
+                if idle.len() < MAX_IDLE_CONNECTIONS {
+                    idle.push(connection);
+                }
+                Some(result)
+            }
+            Err(error) => {
+                warn!("Failed to verify a batch with the verification service - {error}");
+                None
+            }
+        }
+    }
+}
+
+/// Returns `true` if the given transaction can be fully checked by a stateless verification service,
+/// i.e. if it only calls `credits.aleo`, which is the only program such a service knows.
+pub(crate) fn is_offloadable<N: Network>(transaction: &Transaction<N>) -> bool {
+    let credits = match ProgramID::<N>::from_str("credits.aleo") {
+        Ok(credits) => credits,
+        Err(_) => return false,
+    };
+    matches!(transaction, Transaction::Execute(..))
+        && transaction.transitions().all(|transition| *transition.program_id() == credits)
+}
+
+/// Serves verification requests from the given reader, until it is closed.
+/// If a key is given, the client must authenticate with it before its requests are read,
+/// and the verdicts are authenticated with it. Each batch is verified with `check`,
+/// and the verdict is written to the given writer.
+pub fn serve_verifier<N: Network>(
+    reader: impl Read,
+    writer: impl Write,
+    key: Option<&VerifierKey>,
+    check: impl Fn(&Transaction<N>) -> Result<()>,
+) -> Result<()> {
+    let (mut reader, mut writer) = (BufReader::new(reader), BufWriter::new(writer));
+
+    // Challenge the client to authenticate with the shared key.
+    let mut session = match key {
+        Some(key) => {
+            let mut challenge = [0u8; CHALLENGE_BYTES];
+            OsRng.fill_bytes(&mut challenge);
+            writer.write_all(&challenge)?;
+            writer.flush()?;
+            let session = Session::new(key, challenge);
+            let mut tag = [0u8; TAG_BYTES];
+            reader.read_exact(&mut tag)?;
+            session.verify(b"client-hello", &[], &tag)?;
+            writer.write_all(&session.tag(b"server-hello", &[]))?;
+            writer.flush()?;
+            Some(session)
+        }
+        None => None,
+    };
+
+    loop {
+        let batch = match read_frame(&mut reader) {
+            Ok(batch) => batch,
+            // The client closed the connection.
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(error) => return Err(error.into()),
+        };
+        // Ensure the request is authenticated, before it is verified.
+        let request_tag = match &session {
+            Some(session) => {
+                let mut tag = [0u8; TAG_BYTES];
+                reader.read_exact(&mut tag)?;
+                session.verify(b"request", &[&batch], &tag)?;
+                Some(tag)
+            }
+            None => None,
+        };
+
+        let result = decode_batch::<N>(&batch).and_then(|transactions| transactions.iter().try_for_each(&check));
+        let (status, message) = match result {
+            Ok(()) => (STATUS_VERIFIED, Vec::new()),
+            Err(error) => (STATUS_REJECTED, error.to_string().into_bytes()),
+        };
+        writer.write_all(&[status])?;
+        write_frame(&mut writer, &message)?;
+        if let (Some(session), Some(request_tag)) = (&mut session, request_tag) {
+            writer.write_all(&session.tag(b"response", &[&request_tag, &[status], &message]))?;
+            session.counter += 1;
+        }
+        writer.flush()?;
+    }
+}
+
+/// Writes the given bytes as a length-prefixed frame.
+fn write_frame(writer: &mut (impl Write + ?Sized), bytes: &[u8]) -> io::Result<()> {
+    writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    writer.write_all(bytes)
+}
+
+/// Reads a length-prefixed frame.
+fn read_frame(reader: &mut (impl Read + ?Sized)) -> io::Result<Vec<u8>> {
+    let mut length = [0u8; 4];
+    reader.read_exact(&mut length)?;
+    let length = u32::from_le_bytes(length);
+    if length > MAX_VERIFIER_FRAME_BYTES {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("The frame is too large ({length} bytes)")));
+    }
+    let mut bytes = vec![0u8; length as usize];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Reads the status and the message of a verdict.
+fn read_verdict(reader: &mut (impl Read + ?Sized)) -> io::Result<Verdict> {
+    let mut status = [0u8; 1];
+    reader.read_exact(&mut status)?;
+    Ok((status[0], read_frame(reader)?))
+}
+
+/// Encodes the given transactions as batches, which are sequences of length-prefixed transactions,
+/// each within the maximum frame size.
+fn encode_batches<'a, N: Network>(transactions: impl IntoIterator<Item = &'a Transaction<N>>) -> Result<Vec<Vec<u8>>> {
+    let mut batches = vec![Vec::new()];
+    for transaction in transactions {
+        let mut frame = Vec::new();
+        write_frame(&mut frame, &transaction.to_bytes_le()?)?;
+        ensure!(frame.len() <= MAX_VERIFIER_FRAME_BYTES as usize, "Transaction '{}' is too large", transaction.id());
+        // Start a new batch, if the transaction does not fit in the current one.
+        match batches.last_mut() {
+            Some(batch) if batch.len() + frame.len() <= MAX_VERIFIER_FRAME_BYTES as usize => batch.extend(frame),
+            _ => batches.push(frame),
+        }
+    }
+    Ok(batches)
+}
+
+/// Decodes the transactions of the given batch.
+fn decode_batch<N: Network>(mut batch: &[u8]) -> Result<Vec<Transaction<N>>> {
+    let mut transactions = Vec::new();
+    while !batch.is_empty() {
+        transactions.push(Transaction::from_bytes_le(&read_frame(&mut batch)?)?);
+    }
+    Ok(transactions)
+}
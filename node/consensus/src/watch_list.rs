@@ -0,0 +1,340 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkos_node_ledger::Ledger;
+use snarkvm::prelude::{
+    Address,
+    Block,
+    ConsensusStorage,
+    Field,
+    Identifier,
+    Literal,
+    Network,
+    Output,
+    Owner,
+    Plaintext,
+    ProgramID,
+    Value,
+};
+
+use anyhow::{bail, ensure, Result};
+use indexmap::{IndexMap, IndexSet};
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::{
+    collections::VecDeque,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+/// The configuration of the watch list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WatchListConfig<N: Network> {
+    /// The addresses to watch from startup.
+    pub addresses: Vec<Address<N>>,
+    /// The maximum number of changes retained by the watch list.
+    pub max_changes: usize,
+}
+
+impl<N: Network> Default for WatchListConfig<N> {
+    fn default() -> Self {
+        Self { addresses: Vec::new(), max_changes: 1024 }
+    }
+}
+
+/// A change observed for a watched address.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum WatchedChange<N: Network> {
+    /// A change in the public balance of the address.
+    #[serde(rename_all = "camelCase")]
+    Balance {
+        /// The watched address.
+        address: Address<N>,
+        /// The block height at which the change was observed.
+        height: u32,
+        /// The previous public balance (in microcredits).
+        previous: u64,
+        /// The current public balance (in microcredits).
+        current: u64,
+    },
+    /// A change in the value keyed by the address in a program mapping, other than the public balance.
+    #[serde(rename_all = "camelCase")]
+    Mapping {
+        /// The watched address.
+        address: Address<N>,
+        /// The block height at which the change was observed.
+        height: u32,
+        /// The program of the mapping.
+        program_id: ProgramID<N>,
+        /// The name of the mapping.
+        mapping_name: Identifier<N>,
+        /// The previous value, or `None` if it did not exist or was not tracked yet.
+        previous: Option<Value<N>>,
+        /// The current value, if any.
+        current: Option<Value<N>>,
+    },
+    /// A record output to the address as its public owner.
+    #[serde(rename_all = "camelCase")]
+    Record {
+        /// The watched address.
+        address: Address<N>,
+        /// The height of the block that contains the record.
+        height: u32,
+        /// The ID of the transaction that output the record.
+        transaction_id: N::TransactionID,
+        /// The commitment of the record.
+        commitment: Field<N>,
+    },
+}
+
+impl<N: Network> WatchedChange<N> {
+    /// Returns the watched address of the change.
+    pub const fn address(&self) -> &Address<N> {
+        match self {
+            Self::Balance { address, .. } | Self::Mapping { address, .. } | Self::Record { address, .. } => address,
+        }
+    }
+}
+
+/// The key of a value tracked in a program mapping, i.e. the watched address, the program, and the mapping name.
+type MappingKey<N> = (Address<N>, ProgramID<N>, Identifier<N>);
+
+/// A list of watched addresses, and their public balances and mapping values as of the latest block.
+///
+/// Records are attributed to a watched address only if it is their public owner,
+/// as a privately-owned record can only be recognized with the view key of its owner.
+#[derive(Clone, Debug)]
+#[allow(clippy::type_complexity)]
+pub struct WatchList<N: Network> {
+    /// The watched addresses, and their latest public balances.
+    balances: Arc<RwLock<IndexMap<Address<N>, u64>>>,
+    /// The latest values keyed by the watched addresses in the program mappings updated since they were watched.
+    mappings: Arc<RwLock<IndexMap<MappingKey<N>, Value<N>>>>,
+    /// The most recent changes, in order of observation.
+    changes: Arc<RwLock<VecDeque<WatchedChange<N>>>>,
+    /// The maximum number of changes retained.
+    max_changes: Arc<AtomicUsize>,
+    /// The total number of changes observed.
+    num_changes: Arc<AtomicU64>,
+}
+
+impl<N: Network> Default for WatchList<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network> WatchList<N> {
+    /// Initializes a new, empty watch list.
+    pub fn new() -> Self {
+        Self {
+            balances: Default::default(),
+            mappings: Default::default(),
+            changes: Default::default(),
+            max_changes: Arc::new(AtomicUsize::new(WatchListConfig::<N>::default().max_changes)),
+            num_changes: Default::default(),
+        }
+    }
+
+    /// Applies the given configuration, watching its addresses.
+    pub fn configure<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>, config: WatchListConfig<N>) -> Result<()> {
+        ensure!(config.max_changes > 0, "The watch list must retain at least one change");
+        self.max_changes.store(config.max_changes, Ordering::SeqCst);
+        // Drop the oldest changes beyond the new bound.
+        let mut changes = self.changes.write();
+        while changes.len() > config.max_changes {
+            changes.pop_front();
+        }
+        drop(changes);
+
+        for address in config.addresses {
+            self.watch(ledger, address)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of watched addresses.
+    pub fn num_addresses(&self) -> usize {
+        self.balances.read().len()
+    }
+
+    /// Returns the total number of changes observed.
+    pub fn num_changes(&self) -> u64 {
+        self.num_changes.load(Ordering::SeqCst)
+    }
+
+    /// Returns the watched addresses, and their latest public balances.
+    pub fn balances(&self) -> IndexMap<Address<N>, u64> {
+        self.balances.read().clone()
+    }
+
+    /// Returns the most recent changes, in order of observation.
+    pub fn changes(&self) -> Vec<WatchedChange<N>> {
+        self.changes.read().iter().cloned().collect()
+    }
+
+    /// Adds the given address to the watch list.
+    pub fn watch<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>, address: Address<N>) -> Result<()> {
+        // Retrieve the current public balance.
+        let balance = ledger.get_public_balance(&address)?;
+        if self.balances.write().insert(address, balance).is_some() {
+            bail!("'{address}' is already in the watch list.")
+        }
+        Ok(())
+    }
+
+    /// Removes the given address from the watch list.
+    pub fn unwatch(&self, address: &Address<N>) -> Result<()> {
+        if self.balances.write().remove(address).is_none() {
+            bail!("'{address}' is not in the watch list.")
+        }
+        self.mappings.write().retain(|(watched, ..), _| watched != address);
+        Ok(())
+    }
+
+    /// Refreshes the public balances and mapping values of the watched addresses after the given block,
+    /// and records any changes, along with the records output by the block to a watched address.
+    pub(crate) fn refresh<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>, block: &Block<N>) {
+        // Snapshot the watched addresses, so the ledger is read without holding the locks.
+        let addresses: Vec<Address<N>> = self.balances.read().keys().copied().collect();
+        if addresses.is_empty() {
+            return;
+        }
+        let height = block.height();
+
+        // Collect the records output to a watched address, and the programs whose mappings the block may have updated.
+        let mut records = Vec::new();
+        let mut program_ids = IndexSet::new();
+        for transaction in block.transactions().iter() {
+            for transition in transaction.transitions() {
+                if transition.finalize().is_some() {
+                    program_ids.insert(*transition.program_id());
+                }
+                for output in transition.outputs() {
+                    if let Output::Record(commitment, _, Some(record)) = output {
+                        if let Owner::Public(owner) = record.owner() {
+                            if addresses.contains(owner) {
+                                records.push(WatchedChange::Record {
+                                    address: *owner,
+                                    height,
+                                    transaction_id: transaction.id(),
+                                    commitment: *commitment,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Retrieve the current public balances.
+        let balances: Vec<(Address<N>, u64)> = addresses
+            .iter()
+            .filter_map(|address| match ledger.get_public_balance(address) {
+                Ok(balance) => Some((*address, balance)),
+                Err(error) => {
+                    warn!("Failed to retrieve the public balance of watched address '{address}' - {error}");
+                    None
+                }
+            })
+            .collect();
+
+        // Retrieve the current values keyed by the watched addresses in the mappings of the updated programs.
+        // Note: The public balance, which is the `account` mapping of `credits.aleo`, is tracked separately.
+        let credits = ProgramID::<N>::from_str("credits.aleo").ok();
+        let mut values = Vec::new();
+        for program_id in program_ids.into_iter().filter(|program_id| Some(*program_id) != credits) {
+            let program = match ledger.get_program(program_id) {
+                Ok(program) => program,
+                Err(error) => {
+                    warn!("Failed to retrieve program '{program_id}' for the watch list - {error}");
+                    continue;
+                }
+            };
+            for mapping_name in program.mappings().keys() {
+                for address in &addresses {
+                    let key = Plaintext::from(Literal::Address(*address));
+                    match ledger.get_mapping_value(&program_id, mapping_name, &key) {
+                        Ok(value) => values.push(((*address, program_id, *mapping_name), value)),
+                        Err(error) => {
+                            warn!("Failed to retrieve '{program_id}/{mapping_name}' of '{address}' - {error}")
+                        }
+                    }
+                }
+            }
+        }
+
+        // Compare the current balances and values against the latest ones, and update them.
+        let mut changes = Vec::new();
+        {
+            let mut latest_balances = self.balances.write();
+            for (address, current) in balances {
+                // Skip the address if it was unwatched in the meantime.
+                let previous = match latest_balances.get_mut(&address) {
+                    Some(previous) => previous,
+                    None => continue,
+                };
+                if current != *previous {
+                    info!("Watched address '{address}' changed balance at block {height} ({} -> {current})", *previous);
+                    changes.push(WatchedChange::Balance { address, height, previous: *previous, current });
+                    *previous = current;
+                }
+            }
+
+            let mut latest_values = self.mappings.write();
+            for (key, current) in values {
+                // Skip the address if it was unwatched in the meantime.
+                if !latest_balances.contains_key(&key.0) {
+                    continue;
+                }
+                let previous = match &current {
+                    Some(current) => latest_values.insert(key, current.clone()),
+                    None => latest_values.swap_remove(&key),
+                };
+                if previous != current {
+                    let (address, program_id, mapping_name) = key;
+                    info!("Watched address '{address}' changed '{program_id}/{mapping_name}' at block {height}");
+                    changes.push(WatchedChange::Mapping {
+                        address,
+                        height,
+                        program_id,
+                        mapping_name,
+                        previous,
+                        current,
+                    });
+                }
+            }
+        }
+        changes.extend(records);
+
+        // Record the changes.
+        if !changes.is_empty() {
+            let max_changes = self.max_changes.load(Ordering::SeqCst);
+            let mut latest_changes = self.changes.write();
+            for change in changes {
+                if latest_changes.len() >= max_changes {
+                    latest_changes.pop_front();
+                }
+                latest_changes.push_back(change);
+                self.num_changes.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    }
+}
@@ -0,0 +1,392 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::prelude::{Network, Transaction};
+
+use anyhow::{ensure, Result};
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        mpsc::{self, SyncSender, TrySendError},
+        Arc,
+    },
+    thread,
+    time::Instant,
+};
+
+/// The default number of workers for each class of transmissions.
+pub const DEFAULT_WORKERS_PER_CLASS: usize = 1;
+/// The maximum number of workers for each class of transmissions.
+pub const MAX_WORKERS_PER_CLASS: usize = 16;
+/// The default maximum number of transmissions queued in front of each worker.
+pub const DEFAULT_WORKER_QUEUE_CAPACITY: usize = 1024;
+
+/// A class of transmissions, which is processed by a dedicated set of workers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransmissionClass {
+    /// A prover solution.
+    Solution,
+    /// An execution transaction.
+    Transaction,
+    /// A deployment transaction.
+    Deployment,
+}
+
+impl TransmissionClass {
+    /// The classes of transmissions, in the order of their workers.
+    pub const ALL: [Self; 3] = [Self::Solution, Self::Transaction, Self::Deployment];
+
+    /// Returns the class of the given transaction.
+    pub fn of_transaction<N: Network>(transaction: &Transaction<N>) -> Self {
+        match transaction.is_deploy() {
+            true => Self::Deployment,
+            false => Self::Transaction,
+        }
+    }
+
+    /// Returns the name of the class.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Solution => "solution",
+            Self::Transaction => "transaction",
+            Self::Deployment => "deployment",
+        }
+    }
+}
+
+impl fmt::Display for TransmissionClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The configuration of the transmission workers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct WorkerConfig {
+    /// The number of workers that process solutions.
+    pub solution_workers: usize,
+    /// The number of workers that process execution transactions.
+    pub transaction_workers: usize,
+    /// The number of workers that process deployment transactions.
+    pub deployment_workers: usize,
+    /// The maximum number of transmissions queued in front of each worker.
+    pub queue_capacity: usize,
+}
+
+impl Default for WorkerConfig {
+    /// Initializes the configuration with one worker for each class of transmissions.
+    fn default() -> Self {
+        Self {
+            solution_workers: DEFAULT_WORKERS_PER_CLASS,
+            transaction_workers: DEFAULT_WORKERS_PER_CLASS,
+            deployment_workers: DEFAULT_WORKERS_PER_CLASS,
+            queue_capacity: DEFAULT_WORKER_QUEUE_CAPACITY,
+        }
+    }
+}
+
+impl WorkerConfig {
+    /// Returns the number of workers for the given class of transmissions.
+    pub const fn num_workers(&self, class: TransmissionClass) -> usize {
+        match class {
+            TransmissionClass::Solution => self.solution_workers,
+            TransmissionClass::Transaction => self.transaction_workers,
+            TransmissionClass::Deployment => self.deployment_workers,
+        }
+    }
+}
+
+/// A unit of work, which returns `true` if the transmission was processed successfully.
+type Job = Box<dyn FnOnce() -> bool + Send>;
+
+/// The counters of a single worker.
+#[derive(Debug, Default)]
+struct WorkerCounters {
+    /// The number of transmissions waiting in front of the worker.
+    queued: AtomicUsize,
+    /// The number of transmissions that were processed successfully.
+    processed: AtomicU64,
+    /// The number of transmissions that failed to process.
+    failed: AtomicU64,
+    /// The number of transmissions that were dropped, as the queue was full.
+    dropped: AtomicU64,
+    /// The total time spent processing transmissions, in microseconds.
+    busy_micros: AtomicU64,
+}
+
+/// A snapshot of the metrics of a single worker.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct WorkerMetrics {
+    /// The class of transmissions of the worker.
+    pub class: TransmissionClass,
+    /// The index of the worker, within its class.
+    pub index: usize,
+    /// The number of transmissions waiting in front of the worker.
+    pub queued: usize,
+    /// The maximum number of transmissions queued in front of the worker.
+    pub capacity: usize,
+    /// The number of transmissions that were processed successfully.
+    pub processed: u64,
+    /// The number of transmissions that failed to process.
+    pub failed: u64,
+    /// The number of transmissions that were dropped, as the queue was full.
+    pub dropped: u64,
+    /// The total time spent processing transmissions, in microseconds.
+    pub busy_micros: u64,
+}
+
+/// A worker, which processes the transmissions of one class on a dedicated thread.
+struct Worker {
+    /// The class of transmissions of the worker.
+    class: TransmissionClass,
+    /// The index of the worker, within its class.
+    index: usize,
+    /// The sender to the queue of the worker.
+    sender: SyncSender<Job>,
+    /// The maximum number of transmissions queued in front of the worker.
+    capacity: usize,
+    /// The counters of the worker.
+    counters: Arc<WorkerCounters>,
+}
+
+impl Worker {
+    /// Spawns a new worker, which processes jobs until its sender is dropped.
+    fn spawn(class: TransmissionClass, index: usize, queue_capacity: usize) -> Result<Self> {
+        let (sender, receiver) = mpsc::sync_channel::<Job>(queue_capacity);
+        let counters = Arc::new(WorkerCounters::default());
+
+        let worker_counters = counters.clone();
+        thread::Builder::new().name(format!("{class}-worker-{index}")).spawn(move || {
+            while let Ok(job) = receiver.recv() {
+                worker_counters.queued.fetch_sub(1, Ordering::Relaxed);
+                let start = Instant::now();
+                let is_success = job();
+                worker_counters.busy_micros.fetch_add(start.elapsed().as_micros() as u64, Ordering::Relaxed);
+                match is_success {
+                    true => worker_counters.processed.fetch_add(1, Ordering::Relaxed),
+                    false => worker_counters.failed.fetch_add(1, Ordering::Relaxed),
+                };
+            }
+        })?;
+
+        Ok(Self { class, index, sender, capacity: queue_capacity, counters })
+    }
+
+    /// Returns a snapshot of the metrics of the worker.
+    fn metrics(&self) -> WorkerMetrics {
+        WorkerMetrics {
+            class: self.class,
+            index: self.index,
+            queued: self.counters.queued.load(Ordering::Relaxed),
+            capacity: self.capacity,
+            processed: self.counters.processed.load(Ordering::Relaxed),
+            failed: self.counters.failed.load(Ordering::Relaxed),
+            dropped: self.counters.dropped.load(Ordering::Relaxed),
+            busy_micros: self.counters.busy_micros.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// The transmission workers process the unconfirmed transmissions of each class on a dedicated set of threads,
+/// such that heavy deployment traffic does not delay the propagation of solutions.
+/// Within a class, each transmission is assigned to a worker deterministically by its ID.
+#[derive(Clone, Default)]
+pub struct TransmissionWorkers {
+    /// The workers, grouped by class in the order of `TransmissionClass::ALL`.
+    workers: Arc<RwLock<Vec<Worker>>>,
+}
+
+impl TransmissionWorkers {
+    /// Starts the workers with the given configuration, replacing any running workers.
+    /// The replaced workers exit once they have drained their queues.
+    pub fn start(&self, config: WorkerConfig) -> Result<()> {
+        ensure!(config.queue_capacity > 0, "The worker queue capacity must be greater than zero");
+        let mut workers = Vec::new();
+        for class in TransmissionClass::ALL {
+            let num_workers = config.num_workers(class);
+            ensure!(
+                (1..=MAX_WORKERS_PER_CLASS).contains(&num_workers),
+                "The number of {class} workers must be between 1 and {MAX_WORKERS_PER_CLASS}"
+            );
+            for index in 0..num_workers {
+                workers.push(Worker::spawn(class, index, config.queue_capacity)?);
+            }
+        }
+        *self.workers.write() = workers;
+        Ok(())
+    }
+
+    /// Returns `true` if the workers are running.
+    pub fn is_running(&self) -> bool {
+        !self.workers.read().is_empty()
+    }
+
+    /// Returns the number of running workers for the given class of transmissions.
+    pub fn num_workers(&self, class: TransmissionClass) -> usize {
+        self.workers.read().iter().filter(|worker| worker.class == class).count()
+    }
+
+    /// Returns the index of the worker (within the given class) that processes the transmission with the given ID.
+    pub fn shard(&self, class: TransmissionClass, id: &[u8]) -> Option<usize> {
+        match self.num_workers(class) {
+            0 => None,
+            num_workers => Some((fnv1a(id) % num_workers as u64) as usize),
+        }
+    }
+
+    /// Queues the given job on the worker of the given class that is assigned to the transmission ID,
+    /// and returns `false` if the queue of the worker is full.
+    /// If the workers are not running, the job is processed on the calling thread.
+    pub fn dispatch(&self, class: TransmissionClass, id: &[u8], job: impl FnOnce() -> bool + Send + 'static) -> bool {
+        let index = match self.shard(class, id) {
+            Some(index) => index,
+            None => {
+                job();
+                return true;
+            }
+        };
+        let workers = self.workers.read();
+        let worker = match workers.iter().filter(|worker| worker.class == class).nth(index) {
+            Some(worker) => worker,
+            None => return false,
+        };
+        // Increment the queue length first, as the worker may dequeue the job immediately.
+        worker.counters.queued.fetch_add(1, Ordering::Relaxed);
+        match worker.sender.try_send(Box::new(job)) {
+            Ok(()) => true,
+            Err(error) => {
+                worker.counters.queued.fetch_sub(1, Ordering::Relaxed);
+                if let TrySendError::Full(_) = error {
+                    worker.counters.dropped.fetch_add(1, Ordering::Relaxed);
+                    trace!("Dropped a {class} transmission, as the queue of {class} worker {index} is full");
+                }
+                false
+            }
+        }
+    }
+
+    /// Returns a snapshot of the metrics of each worker.
+    pub fn metrics(&self) -> Vec<WorkerMetrics> {
+        self.workers.read().iter().map(Worker::metrics).collect()
+    }
+
+    /// Returns the saturation of the fullest worker queue, from 0 (empty) to 1 (full).
+    pub fn saturation(&self) -> f64 {
+        self.workers
+            .read()
+            .iter()
+            .map(|worker| worker.counters.queued.load(Ordering::Relaxed) as f64 / worker.capacity as f64)
+            .fold(0.0, f64::max)
+    }
+
+    /// Returns the total number of transmissions that were dropped, as the queue of their worker was full.
+    pub fn num_dropped(&self) -> u64 {
+        self.workers.read().iter().map(|worker| worker.counters.dropped.load(Ordering::Relaxed)).sum()
+    }
+}
+
+/// Returns the 64-bit FNV-1a hash of the given bytes, which is stable across platforms and releases.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn test_worker_sharding() {
+        let workers = TransmissionWorkers::default();
+        // Ensure the jobs are processed inline, if the workers are not running.
+        assert!(!workers.is_running());
+        assert_eq!(workers.shard(TransmissionClass::Solution, b"id"), None);
+        assert!(workers.dispatch(TransmissionClass::Solution, b"id", || true));
+
+        // Ensure an invalid configuration is rejected.
+        assert!(workers.start(WorkerConfig { deployment_workers: 0, ..Default::default() }).is_err());
+        assert!(workers.start(WorkerConfig { queue_capacity: 0, ..Default::default() }).is_err());
+
+        let config = WorkerConfig { transaction_workers: 4, ..Default::default() };
+        workers.start(config).unwrap();
+        assert_eq!(workers.num_workers(TransmissionClass::Transaction), 4);
+        assert_eq!(workers.metrics().len(), 6);
+
+        // Ensure the sharding is deterministic.
+        for id in [&b"a"[..], b"b", b"c", b"d"] {
+            let index = workers.shard(TransmissionClass::Transaction, id).unwrap();
+            assert!(index < 4);
+            assert_eq!(workers.shard(TransmissionClass::Transaction, id), Some(index));
+            assert_eq!(workers.shard(TransmissionClass::Solution, id), Some(0));
+        }
+
+        // Process a transmission of each class, and ensure it is recorded by the assigned worker.
+        let (sender, receiver) = channel();
+        for class in TransmissionClass::ALL {
+            let sender = sender.clone();
+            assert!(workers.dispatch(class, b"id", move || sender.send(class).is_ok()));
+        }
+        let mut processed = receiver.iter().take(3).collect::<Vec<_>>();
+        processed.sort_by_key(|class| *class as u8);
+        assert_eq!(processed, TransmissionClass::ALL);
+
+        // Wait for the workers to record the jobs.
+        while workers.metrics().iter().map(|metrics| metrics.processed).sum::<u64>() < 3 {
+            thread::yield_now();
+        }
+        let index = workers.shard(TransmissionClass::Transaction, b"id").unwrap();
+        for metrics in workers.metrics() {
+            let expected = metrics.class != TransmissionClass::Transaction || metrics.index == index;
+            assert_eq!(metrics.processed, expected as u64, "{metrics:?}");
+            assert_eq!(metrics.queued, 0);
+        }
+    }
+
+    #[test]
+    fn test_worker_backpressure() {
+        let workers = TransmissionWorkers::default();
+        workers.start(WorkerConfig { queue_capacity: 1, ..Default::default() }).unwrap();
+        assert_eq!(workers.saturation(), 0.0);
+
+        // Block the solution worker, and wait for it to dequeue the job.
+        let (started, started_receiver) = channel();
+        let (release, release_receiver) = channel::<()>();
+        assert!(workers.dispatch(TransmissionClass::Solution, b"a", move || {
+            started.send(()).unwrap();
+            release_receiver.recv().is_ok()
+        }));
+        started_receiver.recv().unwrap();
+
+        // Fill the queue of the worker, and ensure the next transmission is dropped.
+        assert!(workers.dispatch(TransmissionClass::Solution, b"b", || true));
+        assert_eq!(workers.saturation(), 1.0);
+        assert!(!workers.dispatch(TransmissionClass::Solution, b"c", || true));
+        assert_eq!(workers.num_dropped(), 1);
+
+        // Ensure the other classes are unaffected.
+        assert!(workers.dispatch(TransmissionClass::Transaction, b"c", || true));
+
+        // Release the worker, and ensure its queue drains.
+        release.send(()).unwrap();
+        while workers.saturation() > 0.0 {
+            thread::yield_now();
+        }
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+/// A chained digest over the ledger state, used to detect nondeterministic execution across platforms.
+///
+/// After each block, the digest absorbs the block hash, the state root, and the consensus-critical
+/// values derived by the node (total supply, cumulative proof target, coinbase and proof targets).
+/// Two honest nodes that replay the same blocks must arrive at the same digest, regardless of the
+/// architecture or operating system they run on.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StateDigest<N: Network> {
+    /// The height of the last absorbed block.
+    height: u32,
+    /// The chained digest.
+    digest: Field<N>,
+}
+
+impl<N: Network> StateDigest<N> {
+    /// Initializes the state digest from the latest state of the given ledger.
+    pub fn new<C: ConsensusStorage<N>>(ledger: &Ledger<N, C>) -> Result<Self> {
+        let mut state_digest = Self { height: 0, digest: Field::zero() };
+        state_digest.absorb(ledger)?;
+        Ok(state_digest)
+    }
+
+    /// Returns the height of the last absorbed block.
+    pub const fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the chained digest.
+    pub const fn digest(&self) -> Field<N> {
+        self.digest
+    }
+
+    /// Absorbs the latest state of the given ledger into the digest.
+    pub fn absorb<C: ConsensusStorage<N>>(&mut self, ledger: &Ledger<N, C>) -> Result<()> {
+        // Retrieve the latest block.
+        let block = ledger.latest_block();
+
+        // Prepare the preimage.
+        let preimage = [
+            self.digest,
+            *block.hash(),
+            *ledger.latest_state_root(),
+            Field::from_u64(ledger.latest_total_supply_in_microcredits()),
+            Field::from_u128(ledger.latest_cumulative_proof_target()),
+            Field::from_u64(ledger.latest_coinbase_target()),
+            Field::from_u64(ledger.latest_proof_target()),
+        ];
+
+        // Update the digest.
+        self.height = block.height();
+        self.digest = N::hash_psd8(&preimage)?;
+        Ok(())
+    }
+}
+
+impl<N: Network> Display for StateDigest<N> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.height, self.digest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_helpers::CurrentLedger;
+    use snarkvm::console::network::Testnet3;
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_state_digest_is_deterministic() {
+        // Load the genesis block.
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+
+        // Initialize two independent ledgers.
+        let ledger = CurrentLedger::load(genesis.clone(), None).unwrap();
+        let other = CurrentLedger::load(genesis, None).unwrap();
+
+        // Ensure the digests match.
+        let state_digest = StateDigest::new(&ledger).unwrap();
+        assert_eq!(state_digest, StateDigest::new(&other).unwrap());
+        assert_eq!(state_digest.height(), 0);
+
+        // Ensure absorbing the same state again changes the digest.
+        let mut next_digest = state_digest;
+        next_digest.absorb(&ledger).unwrap();
+        assert_ne!(state_digest.digest(), next_digest.digest());
+    }
+}
@@ -30,6 +30,22 @@ impl<N: Network, C: ConsensusStorage<N>> Ledger<N, C> {
         self.vm.block_store().find_block_hash(transaction_id)
     }
 
+    /// Returns the block height and the index within the block of the given `transaction ID`.
+    pub fn find_transaction_location(&self, transaction_id: &N::TransactionID) -> Result<Option<(u32, usize)>> {
+        // Retrieve the block hash.
+        let block_hash = match self.find_block_hash(transaction_id)? {
+            Some(block_hash) => block_hash,
+            None => return Ok(None),
+        };
+        // Retrieve the block height.
+        let height = self.get_height(&block_hash)?;
+        // Retrieve the index of the transaction in the block.
+        match self.get_transactions(height)?.transaction_ids().position(|id| id == transaction_id) {
+            Some(index) => Ok(Some((height, index))),
+            None => bail!("Transaction '{transaction_id}' is missing from block {height}"),
+        }
+    }
+
     /// Returns the block hash that contains the given `puzzle commitment`.
     pub fn find_block_hash_from_puzzle_commitment(
         &self,
@@ -65,6 +65,10 @@ impl<N: Network, C: ConsensusStorage<N>> Ledger<N, C> {
 
     /// Returns the block for the given block hash.
     pub fn get_block_by_hash(&self, block_hash: &N::BlockHash) -> Result<Block<N>> {
+        // If the hash is the genesis block hash, return the genesis block.
+        if *block_hash == self.genesis.hash() {
+            return Ok(self.genesis.clone());
+        }
         // Retrieve the block.
         match self.vm.block_store().get_block(block_hash)? {
             Some(block) => Ok(block),
@@ -72,8 +76,12 @@ impl<N: Network, C: ConsensusStorage<N>> Ledger<N, C> {
         }
     }
 
-    /// Returns the block height for the given block hash.
+    /// Returns the block height for the given block hash, from the hash-to-height index in storage.
     pub fn get_height(&self, block_hash: &N::BlockHash) -> Result<u32> {
+        // If the hash is the genesis block hash, return the genesis height.
+        if *block_hash == self.genesis.hash() {
+            return Ok(0);
+        }
         match self.vm.block_store().get_block_height(block_hash)? {
             Some(height) => Ok(height),
             None => bail!("Missing block height for block '{block_hash}'"),
@@ -104,6 +112,27 @@ impl<N: Network, C: ConsensusStorage<N>> Ledger<N, C> {
         }
     }
 
+    /// Returns the range of block heights whose rounds are within the given rounds (inclusive).
+    /// The range is inclusive of the start and exclusive of the end.
+    pub fn get_heights_for_rounds(&self, start_round: u64, end_round: u64) -> Result<Range<u32>> {
+        // Returns the lowest block height with a round of at least the given round.
+        // Note: The block rounds strictly increase with the block height.
+        let lower_bound = |round: u64| -> Result<u32> {
+            let (mut low, mut high) = (0, self.latest_height() + 1);
+            while low < high {
+                let middle = low + (high - low) / 2;
+                match self.get_header(middle)?.round() < round {
+                    true => low = middle + 1,
+                    false => high = middle,
+                }
+            }
+            Ok(low)
+        };
+        let start_height = lower_bound(start_round)?;
+        let end_height = lower_bound(end_round.saturating_add(1))?;
+        Ok(start_height..end_height.max(start_height))
+    }
+
     /// Returns the block header for the given block height.
     pub fn get_header(&self, height: u32) -> Result<Header<N>> {
         // If the height is 0, return the genesis block header.
@@ -157,6 +186,31 @@ impl<N: Network, C: ConsensusStorage<N>> Ledger<N, C> {
         }
     }
 
+    /// Returns the value of the given key in the given mapping, if it exists.
+    pub fn get_mapping_value(
+        &self,
+        program_id: &ProgramID<N>,
+        mapping_name: &Identifier<N>,
+        key: &Plaintext<N>,
+    ) -> Result<Option<Value<N>>> {
+        self.vm.program_store().get_value_speculative(program_id, mapping_name, key)
+    }
+
+    /// Returns the public balance (in microcredits) of the given address.
+    pub fn get_public_balance(&self, address: &Address<N>) -> Result<u64> {
+        // Prepare the program ID, mapping name, and key.
+        let program_id = ProgramID::from_str("credits.aleo")?;
+        let mapping_name = Identifier::from_str("account")?;
+        let key = Plaintext::from(Literal::Address(*address));
+
+        // Retrieve the balance from the `account` mapping.
+        match self.get_mapping_value(&program_id, &mapping_name, &key)? {
+            Some(Value::Plaintext(Plaintext::Literal(Literal::U64(balance), _))) => Ok(*balance),
+            Some(value) => bail!("Malformed public balance for '{address}' - {value}"),
+            None => Ok(0),
+        }
+    }
+
     /// Returns the block coinbase solution for the given block height.
     pub fn get_coinbase(&self, height: u32) -> Result<Option<CoinbaseSolution<N>>> {
         // If the height is 0, return the genesis block coinbase.
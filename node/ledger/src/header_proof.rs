@@ -0,0 +1,80 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+/// A block header, with a proof of its inclusion in the block tree of a state root.
+/// This lets a light client verify the header against a trusted state root, without the blocks in between.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeaderProof<N: Network> {
+    /// The state root that the proof is against.
+    pub state_root: N::StateRoot,
+    /// The block height, which is the index of the block in the block tree.
+    pub block_height: u32,
+    /// The block hash, which is the leaf of the block tree.
+    pub block_hash: N::BlockHash,
+    /// The previous block hash.
+    pub previous_hash: N::BlockHash,
+    /// The block header.
+    pub header: Header<N>,
+    /// The Merkle path of the block hash in the block tree.
+    pub block_path: BlockPath<N>,
+}
+
+impl<N: Network> HeaderProof<N> {
+    /// Returns `true` if the header hashes to the block hash, and the block hash is in the block tree of the state root.
+    pub fn verify(&self) -> bool {
+        // Compute the Merkle root of the block header.
+        let header_root = match self.header.to_root() {
+            Ok(root) => root,
+            Err(_) => return false,
+        };
+        // Ensure the block hash commits to the previous block hash and the header.
+        match N::hash_bhp1024(&[self.previous_hash.to_bits_le(), header_root.to_bits_le()].concat()) {
+            Ok(candidate_hash) if candidate_hash == *self.block_hash => (),
+            _ => return false,
+        }
+        // Ensure the block path is at the block height, and leads to the state root.
+        *self.block_path.leaf_index() == self.block_height as u64
+            && N::verify_merkle_path_bhp(&self.block_path, &*self.state_root, &self.block_hash.to_bits_le())
+    }
+}
+
+impl<N: Network, C: ConsensusStorage<N>> Ledger<N, C> {
+    /// Returns the header at the given block height, with a proof of its inclusion in the latest state root.
+    /// The proof is taken from the block tree that the block store maintains, through the state path
+    /// of a commitment in the block.
+    pub fn get_header_proof(&self, height: u32) -> Result<HeaderProof<N>> {
+        // Retrieve a commitment in the block.
+        let transactions = self.get_transactions(height)?;
+        let commitment = match transactions.commitments().next() {
+            Some(commitment) => *commitment,
+            None => bail!("Block {height} does not contain a commitment to prove"),
+        };
+        // Construct the state path of the commitment, which includes the path of the block hash in the block tree.
+        let state_path = self.get_state_path_for_commitment(&commitment)?;
+        ensure!(*state_path.block_path().leaf_index() == height as u64, "Mismatching block path for block {height}");
+
+        Ok(HeaderProof {
+            state_root: state_path.global_state_root(),
+            block_height: height,
+            block_hash: state_path.block_hash(),
+            previous_hash: state_path.previous_block_hash(),
+            header: self.get_header(height)?,
+            block_path: state_path.block_path().clone(),
+        })
+    }
+}
@@ -0,0 +1,237 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+use snarkvm::prelude::{Input, Output};
+
+use indexmap::IndexSet;
+use parking_lot::Mutex;
+
+/// The direction of a transaction, from the perspective of an account.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// The transaction does not spend any record of the account.
+    Incoming,
+    /// The transaction spends a record of the account, and creates records or public values for other accounts.
+    Outgoing,
+    /// The transaction spends a record of the account, and only creates records for the account.
+    SelfTransfer,
+}
+
+/// A transaction that is relevant to an account, with its metadata decrypted with the view key of the account.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistoryEntry<N: Network> {
+    /// The transaction ID.
+    pub transaction_id: N::TransactionID,
+    /// The height of the block that includes the transaction.
+    pub block_height: u32,
+    /// The hash of the block that includes the transaction.
+    pub block_hash: N::BlockHash,
+    /// The timestamp of the block that includes the transaction.
+    pub timestamp: i64,
+    /// The direction of the transaction.
+    pub direction: Direction,
+    /// The amount (in microcredits) in the records of the account that the transaction creates.
+    pub received: u64,
+    /// The amount (in microcredits) in the records of the account that the transaction spends.
+    pub spent: u64,
+    /// The other addresses in the public inputs and outputs of the transaction.
+    /// The recipients of private records are not derivable, and are omitted.
+    pub counterparties: Vec<Address<N>>,
+}
+
+/// A page of the transaction history of an account, from newest to oldest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionHistory<N: Network> {
+    /// The total number of transactions in the history.
+    pub total: usize,
+    /// The transactions in the page.
+    pub entries: Vec<HistoryEntry<N>>,
+}
+
+/// The maximum number of accounts whose transaction history is indexed at once.
+/// Beyond this bound, the index of the least recently read account is dropped.
+pub const MAX_INDEXED_ACCOUNTS: usize = 64;
+
+/// The index of the transaction history of an account, which is extended with the blocks added since the last read.
+#[derive(Clone, Debug)]
+pub(crate) struct HistoryIndex<N: Network> {
+    /// The height of the next block to scan.
+    next_height: u32,
+    /// The hash of the last scanned block, to detect a reorganization of the scanned blocks.
+    last_hash: Option<N::BlockHash>,
+    /// The mapping of `tag` to the amount of each unspent record of the account.
+    unspent: IndexMap<Field<N>, u64>,
+    /// The relevant transactions, from oldest to newest.
+    entries: Vec<HistoryEntry<N>>,
+}
+
+impl<N: Network> Default for HistoryIndex<N> {
+    fn default() -> Self {
+        Self { next_height: 0, last_hash: None, unspent: Default::default(), entries: Default::default() }
+    }
+}
+
+/// The indexes of the transaction history of the recently read accounts, from least to most recently read.
+pub(crate) type HistoryIndexes<N> = Arc<RwLock<IndexMap<Address<N>, Arc<Mutex<HistoryIndex<N>>>>>>;
+
+impl<N: Network, C: ConsensusStorage<N>> Ledger<N, C> {
+    /// Returns the transactions that are relevant to the given view key, from newest to oldest, skipping the first
+    /// `offset` transactions and returning up to `limit` of them. A transaction is relevant if it spends or creates
+    /// a record of the account, or has the address of the account in a public input or output.
+    ///
+    /// Note: The spent records are only recognizable from the tags of the records created earlier, so the history
+    /// of an account is indexed by scanning every block on its first read. The index is then kept for later reads,
+    /// which only scan the blocks added since.
+    pub fn get_transaction_history(
+        &self,
+        view_key: &ViewKey<N>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<TransactionHistory<N>> {
+        let address = view_key.to_address();
+
+        // Retrieve the index of the account, marking it as the most recently read.
+        let index = {
+            let mut indexes = self.history.write();
+            let index = indexes.shift_remove(&address).unwrap_or_default();
+            // Drop the index of the least recently read account, if the bound is reached.
+            if indexes.len() >= MAX_INDEXED_ACCOUNTS {
+                indexes.shift_remove_index(0);
+            }
+            indexes.insert(address, index.clone());
+            index
+        };
+
+        // Note: The index is locked while it is extended, so concurrent reads of an account scan each block once.
+        let mut index = index.lock();
+        self.extend_history_index(view_key, &mut index)?;
+
+        Ok(TransactionHistory {
+            total: index.entries.len(),
+            entries: index.entries.iter().rev().skip(offset).take(limit).cloned().collect(),
+        })
+    }
+
+    /// Extends the given history index with the blocks added since it was last extended.
+    /// If the blocks it scanned were reorganized, the index is rebuilt from the genesis block.
+    fn extend_history_index(&self, view_key: &ViewKey<N>, index: &mut HistoryIndex<N>) -> Result<()> {
+        let latest_height = self.latest_height();
+
+        // Rebuild the index, if the last scanned block is no longer in the canonical chain.
+        if let Some(last_hash) = index.last_hash {
+            let last_height = index.next_height.saturating_sub(1);
+            if last_height > latest_height || self.get_hash(last_height)? != last_hash {
+                *index = HistoryIndex::default();
+            }
+        }
+
+        // Derive the address corresponding to the given view key.
+        let address = view_key.to_address();
+        let address_x_coordinate = address.to_x_coordinate();
+        // Derive the `sk_tag` from the graph key.
+        let sk_tag = match GraphKey::try_from(view_key) {
+            Ok(graph_key) => graph_key.sk_tag(),
+            Err(e) => bail!("Failed to derive the graph key from the view key: {e}"),
+        };
+        let microcredits = Identifier::from_str("microcredits")?;
+
+        for height in index.next_height..=latest_height {
+            let block = self.get_block(height)?;
+            for transaction in block.transactions().iter() {
+                let (mut received, mut spent, mut is_relevant) = (0u64, 0u64, false);
+                let mut has_foreign_records = false;
+                let mut counterparties = IndexSet::new();
+
+                for transition in transaction.transitions() {
+                    // Collect the spent records and the public addresses.
+                    for input in transition.inputs() {
+                        match input {
+                            Input::Record(_, tag) => {
+                                if let Some(amount) = index.unspent.remove(tag) {
+                                    spent = spent.saturating_add(amount);
+                                    is_relevant = true;
+                                }
+                            }
+                            Input::Public(_, Some(Plaintext::Literal(Literal::Address(other), _))) => {
+                                match *other == address {
+                                    true => is_relevant = true,
+                                    false => {
+                                        counterparties.insert(*other);
+                                    }
+                                }
+                            }
+                            _ => (),
+                        }
+                    }
+                    // Collect the created records and the public addresses.
+                    for output in transition.outputs() {
+                        match output {
+                            Output::Record(commitment, _, Some(record)) => {
+                                if !record.is_owner_with_address_x_coordinate(view_key, &address_x_coordinate) {
+                                    has_foreign_records = true;
+                                    continue;
+                                }
+                                // Decrypt the amount of the record.
+                                let amount = match record.decrypt(view_key)?.data().get(&microcredits) {
+                                    Some(Entry::Private(Plaintext::Literal(Literal::U64(amount), _))) => **amount,
+                                    _ => 0,
+                                };
+                                // Track the record by its tag, to recognize it once it is spent.
+                                index.unspent.insert(Record::<N, Plaintext<N>>::tag(sk_tag, *commitment)?, amount);
+                                received = received.saturating_add(amount);
+                                is_relevant = true;
+                            }
+                            Output::Public(_, Some(Plaintext::Literal(Literal::Address(other), _))) => {
+                                match *other == address {
+                                    true => is_relevant = true,
+                                    false => {
+                                        counterparties.insert(*other);
+                                    }
+                                }
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+
+                if !is_relevant {
+                    continue;
+                }
+                let direction = match spent > 0 {
+                    false => Direction::Incoming,
+                    true if !has_foreign_records && counterparties.is_empty() => Direction::SelfTransfer,
+                    true => Direction::Outgoing,
+                };
+                index.entries.push(HistoryEntry {
+                    transaction_id: transaction.id(),
+                    block_height: height,
+                    block_hash: block.hash(),
+                    timestamp: block.timestamp(),
+                    direction,
+                    received,
+                    spent,
+                    counterparties: counterparties.into_iter().collect(),
+                });
+            }
+            // Advance the index past the block.
+            index.next_height = height + 1;
+            index.last_hash = Some(block.hash());
+        }
+        Ok(())
+    }
+}
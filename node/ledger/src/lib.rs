@@ -24,6 +24,15 @@ mod find;
 mod get;
 mod iterators;
 
+mod header_proof;
+pub use header_proof::*;
+
+mod history;
+pub use history::*;
+
+mod determinism;
+pub use determinism::*;
+
 #[cfg(test)]
 mod tests;
 
@@ -31,7 +40,7 @@ use snarkvm::{
     console::{
         account::{Address, GraphKey, PrivateKey, Signature, ViewKey},
         network::prelude::*,
-        program::{Ciphertext, Entry, Identifier, Literal, Plaintext, ProgramID, Record, StatePath, Value},
+        program::{BlockPath, Ciphertext, Entry, Identifier, Literal, Plaintext, ProgramID, Record, StatePath, Value},
         types::{Field, Group},
     },
     synthesizer::{
@@ -56,6 +65,9 @@ use rayon::prelude::*;
 
 pub type RecordMap<N> = IndexMap<Field<N>, Record<N, Plaintext<N>>>;
 
+/// The number of blocks on top of a block, after which the block is considered finalized.
+pub const FINALITY_DEPTH: u32 = 100;
+
 #[derive(Copy, Clone, Debug)]
 pub enum RecordsFilter<N: Network> {
     /// Returns all records associated with the account.
@@ -80,6 +92,8 @@ pub struct Ledger<N: Network, C: ConsensusStorage<N>> {
     current_block: Arc<RwLock<Block<N>>>,
     /// The current epoch challenge.
     current_epoch_challenge: Arc<RwLock<Option<EpochChallenge<N>>>>,
+    /// The indexes of the transaction history of the recently read accounts.
+    history: HistoryIndexes<N>,
 }
 
 impl<N: Network, C: ConsensusStorage<N>> Ledger<N, C> {
@@ -136,6 +150,7 @@ impl<N: Network, C: ConsensusStorage<N>> Ledger<N, C> {
             genesis: genesis.clone(),
             current_block: Arc::new(RwLock::new(genesis.clone())),
             current_epoch_challenge: Default::default(),
+            history: Default::default(),
         };
 
         // If the block store is empty, initialize the genesis block.
@@ -188,6 +203,11 @@ impl<N: Network, C: ConsensusStorage<N>> Ledger<N, C> {
         self.current_block.read().height()
     }
 
+    /// Returns the latest finalized block height, which is `FINALITY_DEPTH` blocks below the latest block.
+    pub fn finalized_height(&self) -> u32 {
+        self.latest_height().saturating_sub(FINALITY_DEPTH)
+    }
+
     /// Returns the latest block hash.
     pub fn latest_hash(&self) -> N::BlockHash {
         self.current_block.read().hash()
@@ -16,7 +16,10 @@
 
 use crate::{tests::test_helpers::CurrentLedger, Ledger};
 use snarkvm::{
-    console::network::{prelude::*, Testnet3},
+    console::{
+        account::{PrivateKey, ViewKey},
+        network::{prelude::*, Testnet3},
+    },
     prelude::TestRng,
     synthesizer::{block::Block, store::ConsensusStore, vm::VM, ConsensusMemory},
 };
@@ -67,6 +70,51 @@ fn test_load() {
     assert_eq!(ledger.latest_height(), genesis.height());
     assert_eq!(ledger.latest_round(), genesis.round());
     assert_eq!(ledger.latest_block(), genesis);
+    assert_eq!(ledger.finalized_height(), genesis.height());
+}
+
+#[test]
+fn test_find_transaction_location() {
+    // Load the genesis block.
+    let genesis = sample_genesis_block();
+
+    // Initialize the ledger with the genesis block.
+    let ledger = CurrentLedger::load(genesis.clone(), None).unwrap();
+
+    // Ensure each genesis transaction is located in the genesis block, in order.
+    for (index, transaction_id) in genesis.transaction_ids().enumerate() {
+        assert_eq!(ledger.find_transaction_location(transaction_id).unwrap(), Some((0, index)));
+    }
+}
+
+#[test]
+fn test_get_heights_for_rounds() {
+    // Load the genesis block.
+    let genesis = sample_genesis_block();
+    let round = genesis.round();
+
+    // Initialize the ledger with the genesis block.
+    let ledger = CurrentLedger::load(genesis, None).unwrap();
+    assert_eq!(ledger.get_heights_for_rounds(0, round).unwrap(), 0..1);
+    assert_eq!(ledger.get_heights_for_rounds(round, round + 10).unwrap(), 0..1);
+    assert_eq!(ledger.get_heights_for_rounds(round + 1, round + 10).unwrap(), 1..1);
+    assert!(ledger.get_heights_for_rounds(round + 1, round).unwrap().is_empty());
+}
+
+#[test]
+fn test_get_block_by_hash() {
+    // Load the genesis block.
+    let genesis = sample_genesis_block();
+
+    // Initialize the ledger with the genesis block.
+    let ledger = CurrentLedger::load(genesis.clone(), None).unwrap();
+    assert_eq!(ledger.get_block_by_hash(&genesis.hash()).unwrap(), genesis);
+    assert_eq!(ledger.get_height(&genesis.hash()).unwrap(), genesis.height());
+
+    // Ensure an unknown block hash is rejected.
+    let unknown_hash = genesis.previous_hash();
+    assert!(ledger.get_block_by_hash(&unknown_hash).is_err());
+    assert!(ledger.get_height(&unknown_hash).is_err());
 }
 
 #[test]
@@ -105,3 +153,76 @@ fn test_state_path() {
 
     let _state_path = ledger.get_state_path_for_commitment(commitment).unwrap();
 }
+
+#[test]
+fn test_get_header_proof() {
+    // Load the genesis block.
+    let genesis = sample_genesis_block();
+
+    // Initialize the ledger with the genesis block.
+    let ledger = CurrentLedger::load(genesis.clone(), None).unwrap();
+
+    // Ensure the header proof verifies against the latest state root.
+    let proof = ledger.get_header_proof(0).unwrap();
+    assert_eq!(proof.header, *genesis.header());
+    assert_eq!(proof.block_hash, genesis.hash());
+    assert_eq!(proof.state_root, ledger.latest_state_root());
+    assert!(proof.verify());
+
+    // Ensure a proof for another block height is rejected.
+    let mut tampered = proof;
+    tampered.block_height = 1;
+    assert!(!tampered.verify());
+
+    // Ensure a header proof for a missing block is rejected.
+    assert!(ledger.get_header_proof(1).is_err());
+}
+
+#[test]
+fn test_get_transaction_history() {
+    let rng = &mut TestRng::default();
+
+    // Sample the genesis private key.
+    let private_key = crate::tests::test_helpers::sample_genesis_private_key(rng);
+    let view_key = ViewKey::try_from(private_key).unwrap();
+    // Initialize the store.
+    let store = ConsensusStore::<_, ConsensusMemory<_>>::open(None).unwrap();
+    // Create a genesis block.
+    let genesis = Block::genesis(&VM::from(store).unwrap(), &private_key, rng).unwrap();
+
+    // Initialize the ledger with the genesis block.
+    let ledger = CurrentLedger::load(genesis.clone(), None).unwrap();
+
+    // Ensure every genesis transaction is in the history of the genesis account, from newest to oldest.
+    let history = ledger.get_transaction_history(&view_key, 0, 100).unwrap();
+    let expected_ids = genesis.transaction_ids().rev().copied().collect::<Vec<_>>();
+    assert_eq!(history.total, expected_ids.len());
+    assert_eq!(history.entries.iter().map(|entry| entry.transaction_id).collect::<Vec<_>>(), expected_ids);
+    for entry in &history.entries {
+        assert_eq!(entry.block_height, 0);
+        assert_eq!(entry.block_hash, genesis.hash());
+        assert_eq!(entry.direction, crate::Direction::Incoming);
+        assert!(entry.received > 0);
+        assert_eq!(entry.spent, 0);
+    }
+
+    // Ensure the history is paged.
+    let page = ledger.get_transaction_history(&view_key, 1, 1).unwrap();
+    assert_eq!(page.total, history.total);
+    assert_eq!(page.entries, history.entries.into_iter().skip(1).take(1).collect::<Vec<_>>());
+
+    // Ensure another account has an empty history.
+    let other_view_key = ViewKey::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+    let history = ledger.get_transaction_history(&other_view_key, 0, 100).unwrap();
+    assert_eq!(history.total, 0);
+    assert!(history.entries.is_empty());
+
+    // Ensure the history of both accounts is indexed, from the least to the most recently read.
+    let indexed = ledger.history.read().keys().copied().collect::<Vec<_>>();
+    assert_eq!(indexed, vec![view_key.to_address(), other_view_key.to_address()]);
+    // Ensure a later read is served from the index.
+    let page = ledger.get_transaction_history(&view_key, 1, 1).unwrap();
+    assert_eq!(page.total, expected_ids.len());
+    assert_eq!(page.entries[0].transaction_id, expected_ids[1]);
+    assert_eq!(ledger.history.read().keys().last(), Some(&view_key.to_address()));
+}
@@ -0,0 +1,90 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+/// A request for the block hashes at exponentially spaced heights within a range,
+/// to narrow down the common ancestor with a peer on a fork.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AncestorRequest {
+    /// The highest block height known to be common with the peer (inclusive).
+    pub start_height: u32,
+    /// The lowest block height known to diverge from the peer (inclusive).
+    pub end_height: u32,
+}
+
+impl AncestorRequest {
+    /// Returns the requested heights, in descending order: `end_height`, `end_height - 1`, `end_height - 3`,
+    /// `end_height - 7`, ..., down to `start_height`. Each round narrows the range by at least half,
+    /// so two peers find their common ancestor in O(log n) messages, with O(log n) hashes per message.
+    pub fn heights(&self) -> Vec<u32> {
+        let mut heights = Vec::new();
+        let (mut height, mut step) = (self.end_height, 1u32);
+        while height > self.start_height {
+            heights.push(height);
+            height = height.saturating_sub(step).max(self.start_height);
+            step = step.saturating_mul(2);
+        }
+        heights.push(self.start_height);
+        heights
+    }
+}
+
+impl MessageTrait for AncestorRequest {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> String {
+        format!("AncestorRequest {}..={}", self.start_height, self.end_height)
+    }
+
+    /// Serializes the message into the buffer.
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        Ok(bincode::serialize_into(writer, &(self.start_height, self.end_height))?)
+    }
+
+    /// Deserializes the given buffer into a message.
+    #[inline]
+    fn deserialize(bytes: BytesMut) -> Result<Self> {
+        let mut reader = bytes.reader();
+        Ok(Self {
+            start_height: bincode::deserialize_from(&mut reader)?,
+            end_height: bincode::deserialize_from(&mut reader)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_heights() {
+        // Ensure the heights are exponentially spaced, and include both ends of the range.
+        let request = AncestorRequest { start_height: 100, end_height: 200 };
+        assert_eq!(request.heights(), vec![200, 199, 197, 193, 185, 169, 137, 100]);
+
+        // Ensure adjacent heights are both included.
+        let request = AncestorRequest { start_height: 5, end_height: 6 };
+        assert_eq!(request.heights(), vec![6, 5]);
+
+        // Ensure the number of heights is logarithmic in the range.
+        let request = AncestorRequest { start_height: 0, end_height: u32::MAX };
+        assert!(request.heights().len() <= AncestorResponse::<CurrentNetwork>::MAXIMUM_NUMBER_OF_HASHES as usize);
+    }
+}
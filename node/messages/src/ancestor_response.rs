@@ -0,0 +1,78 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AncestorResponse<N: Network> {
+    /// The original ancestor request.
+    pub request: AncestorRequest,
+    /// The block hashes at the requested heights, in the order of the request.
+    pub hashes: Vec<(u32, N::BlockHash)>,
+}
+
+impl<N: Network> AncestorResponse<N> {
+    /// The maximum number of block hashes that can be sent in a single message.
+    pub const MAXIMUM_NUMBER_OF_HASHES: u16 = 64;
+}
+
+impl<N: Network> MessageTrait for AncestorResponse<N> {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> String {
+        format!("AncestorResponse {}..={}", self.request.start_height, self.request.end_height)
+    }
+
+    /// Serializes the message into the buffer.
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        // Ensure that the number of hashes is within the allowed range.
+        if self.hashes.len() > Self::MAXIMUM_NUMBER_OF_HASHES as usize {
+            bail!("Ancestor response exceeds maximum number of hashes");
+        }
+        self.request.serialize(writer)?;
+        (self.hashes.len() as u16).write_le(&mut *writer)?;
+        for (height, hash) in &self.hashes {
+            height.write_le(&mut *writer)?;
+            hash.write_le(&mut *writer)?;
+        }
+        Ok(())
+    }
+
+    /// Deserializes the given buffer into a message.
+    #[inline]
+    fn deserialize(bytes: BytesMut) -> Result<Self> {
+        let mut reader = bytes.reader();
+        let request = AncestorRequest {
+            start_height: bincode::deserialize_from(&mut reader)?,
+            end_height: bincode::deserialize_from(&mut reader)?,
+        };
+        // Read the number of hashes.
+        let num_hashes = u16::read_le(&mut reader)?;
+        // Ensure that the number of hashes is within the allowed range.
+        if num_hashes > Self::MAXIMUM_NUMBER_OF_HASHES {
+            bail!("Ancestor response exceeds maximum number of hashes");
+        }
+        // Read the hashes.
+        let hashes = (0..num_hashes)
+            .map(|_| Ok((u32::read_le(&mut reader)?, N::BlockHash::read_le(&mut reader)?)))
+            .collect::<Result<Vec<_>>>()?;
+        match reader.into_inner().remaining() == 0 {
+            true => Ok(Self { request, hashes }),
+            false => bail!("Invalid 'AncestorResponse' message"),
+        }
+    }
+}
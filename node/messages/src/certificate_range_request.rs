@@ -0,0 +1,58 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+/// A request for the block certificates of every round in a range, so a lagging node can learn
+/// the signed chain of block hashes ahead of it in a single exchange.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CertificateRangeRequest {
+    /// The starting round (inclusive).
+    pub start_round: u64,
+    /// The ending round (inclusive).
+    pub end_round: u64,
+}
+
+impl CertificateRangeRequest {
+    /// Returns `true` if the given round is within the requested range.
+    pub const fn contains(&self, round: u64) -> bool {
+        self.start_round <= round && round <= self.end_round
+    }
+}
+
+impl MessageTrait for CertificateRangeRequest {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> String {
+        format!("CertificateRangeRequest {}..={}", self.start_round, self.end_round)
+    }
+
+    /// Serializes the message into the buffer.
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        Ok(bincode::serialize_into(writer, &(self.start_round, self.end_round))?)
+    }
+
+    /// Deserializes the given buffer into a message.
+    #[inline]
+    fn deserialize(bytes: BytesMut) -> Result<Self> {
+        let mut reader = bytes.reader();
+        Ok(Self {
+            start_round: bincode::deserialize_from(&mut reader)?,
+            end_round: bincode::deserialize_from(&mut reader)?,
+        })
+    }
+}
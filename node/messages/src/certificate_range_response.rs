@@ -0,0 +1,226 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+use snarkvm::prelude::ToBits;
+
+/// The certificate of a block, i.e. the block hash and its linkage, as signed by the beacon of the round.
+/// The certificate carries the block header, so the receiver recomputes the block hash, instead of trusting it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockCertificate<N: Network> {
+    /// The round of the block.
+    pub round: u64,
+    /// The height of the block.
+    pub height: u32,
+    /// The previous block hash.
+    pub previous_hash: N::BlockHash,
+    /// The block header.
+    pub header: Header<N>,
+    /// The block hash.
+    pub block_hash: N::BlockHash,
+    /// The signature of the beacon on the block hash.
+    pub signature: Signature<N>,
+}
+
+impl<N: Network> BlockCertificate<N> {
+    /// Returns the certificate of the given block.
+    pub fn from_block(block: &Block<N>) -> Self {
+        Self {
+            round: block.round(),
+            height: block.height(),
+            previous_hash: block.previous_hash(),
+            header: *block.header(),
+            block_hash: block.hash(),
+            signature: *block.signature(),
+        }
+    }
+
+    /// Returns the address of the signer of the certificate.
+    pub fn signer(&self) -> Address<N> {
+        self.signature.to_address()
+    }
+
+    /// Returns `true` if the block hash is the hash of the previous block hash and the header, which matches
+    /// the round and height of the certificate, and the signature on the block hash is valid for the signer.
+    pub fn verify(&self) -> bool {
+        if self.header.round() != self.round || self.header.height() != self.height {
+            return false;
+        }
+        // Recompute the block hash, so the signature binds the linkage and the header of the block.
+        let block_hash = self
+            .header
+            .to_root()
+            .and_then(|root| N::hash_bhp1024(&[self.previous_hash.to_bits_le(), root.to_bits_le()].concat()));
+        match block_hash {
+            Ok(block_hash) if block_hash == *self.block_hash => {
+                self.signature.verify(&self.signer(), &[*self.block_hash])
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<N: Network> ToBytes for BlockCertificate<N> {
+    fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.round.write_le(&mut writer)?;
+        self.height.write_le(&mut writer)?;
+        self.previous_hash.write_le(&mut writer)?;
+        self.header.write_le(&mut writer)?;
+        self.block_hash.write_le(&mut writer)?;
+        self.signature.write_le(&mut writer)
+    }
+}
+
+impl<N: Network> FromBytes for BlockCertificate<N> {
+    fn read_le<R: Read>(mut reader: R) -> IoResult<Self> {
+        Ok(Self {
+            round: u64::read_le(&mut reader)?,
+            height: u32::read_le(&mut reader)?,
+            previous_hash: N::BlockHash::read_le(&mut reader)?,
+            header: Header::read_le(&mut reader)?,
+            block_hash: N::BlockHash::read_le(&mut reader)?,
+            signature: Signature::read_le(&mut reader)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CertificateRangeResponse<N: Network> {
+    /// The original certificate range request.
+    pub request: CertificateRangeRequest,
+    /// The block certificates within the requested rounds, in ascending order of round.
+    pub certificates: Vec<BlockCertificate<N>>,
+}
+
+impl<N: Network> CertificateRangeResponse<N> {
+    /// The maximum number of block certificates that can be sent in a single message.
+    pub const MAXIMUM_NUMBER_OF_CERTIFICATES: u16 = 256;
+
+    /// Checks that the certificates are within the requested rounds, and form a chain of consecutive blocks.
+    /// Note: This does not check the signatures, or that the signers are beacons.
+    pub fn check(&self) -> Result<()> {
+        for certificate in &self.certificates {
+            if !self.request.contains(certificate.round) {
+                bail!("Certificate for round {} is outside of the requested rounds", certificate.round);
+            }
+        }
+        for pair in self.certificates.windows(2) {
+            if pair[0].round >= pair[1].round
+                || pair[0].height + 1 != pair[1].height
+                || pair[0].block_hash != pair[1].previous_hash
+            {
+                bail!("Certificates for blocks {} and {} are not linked", pair[0].height, pair[1].height);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<N: Network> MessageTrait for CertificateRangeResponse<N> {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> String {
+        format!("CertificateRangeResponse {}..={}", self.request.start_round, self.request.end_round)
+    }
+
+    /// Serializes the message into the buffer.
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        // Ensure that the number of certificates is within the allowed range.
+        if self.certificates.len() > Self::MAXIMUM_NUMBER_OF_CERTIFICATES as usize {
+            bail!("Certificate range response exceeds maximum number of certificates");
+        }
+        self.request.serialize(writer)?;
+        (self.certificates.len() as u16).write_le(&mut *writer)?;
+        self.certificates.iter().try_for_each(|certificate| certificate.write_le(&mut *writer))?;
+        Ok(())
+    }
+
+    /// Deserializes the given buffer into a message.
+    #[inline]
+    fn deserialize(bytes: BytesMut) -> Result<Self> {
+        let mut reader = bytes.reader();
+        let request = CertificateRangeRequest {
+            start_round: bincode::deserialize_from(&mut reader)?,
+            end_round: bincode::deserialize_from(&mut reader)?,
+        };
+        // Read the number of certificates.
+        let num_certificates = u16::read_le(&mut reader)?;
+        // Ensure that the number of certificates is within the allowed range.
+        if num_certificates > Self::MAXIMUM_NUMBER_OF_CERTIFICATES {
+            bail!("Certificate range response exceeds maximum number of certificates");
+        }
+        // Read the certificates.
+        let certificates =
+            (0..num_certificates).map(|_| BlockCertificate::read_le(&mut reader)).collect::<Result<Vec<_>, _>>()?;
+        match reader.into_inner().remaining() == 0 {
+            true => Ok(Self { request, certificates }),
+            false => bail!("Invalid 'CertificateRangeResponse' message"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{Field, TestRng, Uniform};
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_check() {
+        let rng = &mut TestRng::default();
+
+        // Sample the certificate of the genesis block, and ensure it is valid.
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let genesis = BlockCertificate::from_block(&genesis);
+        assert!(genesis.verify());
+
+        // Sample the certificate of the next block.
+        let next = BlockCertificate {
+            round: 1,
+            height: 1,
+            previous_hash: genesis.block_hash,
+            block_hash: Field::<CurrentNetwork>::rand(rng).into(),
+            ..genesis.clone()
+        };
+        assert!(!next.verify());
+
+        // Ensure a certificate is rejected, if its block hash does not match its linkage or header.
+        let relinked = BlockCertificate { previous_hash: next.block_hash, ..genesis.clone() };
+        assert!(!relinked.verify());
+        let mismatched = BlockCertificate { round: genesis.round + 1, ..genesis.clone() };
+        assert!(!mismatched.verify());
+
+        // Ensure linked certificates within the requested rounds are accepted.
+        let request = CertificateRangeRequest { start_round: 0, end_round: 1 };
+        let response = CertificateRangeResponse { request, certificates: vec![genesis.clone(), next.clone()] };
+        assert!(response.check().is_ok());
+        assert!(CertificateRangeResponse::<CurrentNetwork> { request, certificates: vec![] }.check().is_ok());
+
+        // Ensure certificates outside of the requested rounds are rejected.
+        let request = CertificateRangeRequest { start_round: 1, end_round: 1 };
+        let response = CertificateRangeResponse { request, certificates: vec![genesis.clone(), next.clone()] };
+        assert!(response.check().is_err());
+
+        // Ensure unlinked or unordered certificates are rejected.
+        let request = CertificateRangeRequest { start_round: 0, end_round: 1 };
+        let unlinked = BlockCertificate { previous_hash: next.block_hash, ..next.clone() };
+        assert!(CertificateRangeResponse { request, certificates: vec![genesis.clone(), unlinked] }.check().is_err());
+        assert!(CertificateRangeResponse { request, certificates: vec![next, genesis] }.check().is_err());
+    }
+}
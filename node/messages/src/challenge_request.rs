@@ -23,6 +23,8 @@ pub struct ChallengeRequest<N: Network> {
     pub node_type: NodeType,
     pub address: Address<N>,
     pub nonce: u64,
+    /// The compression that the node offers for the connection.
+    pub compression: Compression,
 }
 
 impl<N: Network> MessageTrait for ChallengeRequest<N> {
@@ -37,20 +39,27 @@ impl<N: Network> MessageTrait for ChallengeRequest<N> {
     fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
         Ok(bincode::serialize_into(
             writer,
-            &(self.version, self.listener_port, self.node_type, self.address, self.nonce),
+            &(self.version, self.listener_port, self.node_type, self.address, self.nonce, self.compression),
         )?)
     }
 
     /// Deserializes the given buffer into a message.
     #[inline]
     fn deserialize(bytes: BytesMut) -> Result<Self> {
-        let (version, listener_port, node_type, address, nonce) = bincode::deserialize_from(&mut bytes.reader())?;
-        Ok(Self { version, listener_port, node_type, address, nonce })
+        let (version, listener_port, node_type, address, nonce, compression) =
+            bincode::deserialize_from(&mut bytes.reader())?;
+        Ok(Self { version, listener_port, node_type, address, nonce, compression })
     }
 }
 
 impl<N: Network> ChallengeRequest<N> {
-    pub fn new(listener_port: u16, node_type: NodeType, address: Address<N>, nonce: u64) -> Self {
-        Self { version: Message::<N>::VERSION, listener_port, node_type, address, nonce }
+    pub fn new(
+        listener_port: u16,
+        node_type: NodeType,
+        address: Address<N>,
+        nonce: u64,
+        compression: Compression,
+    ) -> Self {
+        Self { version: Message::<N>::VERSION, listener_port, node_type, address, nonce, compression }
     }
 }
@@ -19,6 +19,7 @@ use super::*;
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ChallengeResponse<N: Network> {
     pub genesis_header: Header<N>,
+    pub observed_ip: IpAddr,
     pub signature: Data<Signature<N>>,
 }
 
@@ -33,6 +34,7 @@ impl<N: Network> MessageTrait for ChallengeResponse<N> {
     #[inline]
     fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
         writer.write_all(&self.genesis_header.to_bytes_le()?)?;
+        bincode::serialize_into(&mut *writer, &self.observed_ip)?;
         self.signature.serialize_blocking_into(writer)
     }
 
@@ -42,6 +44,7 @@ impl<N: Network> MessageTrait for ChallengeResponse<N> {
         let mut reader = bytes.reader();
         Ok(Self {
             genesis_header: Header::read_le(&mut reader)?,
+            observed_ip: bincode::deserialize_from(&mut reader)?,
             signature: Data::Buffer(reader.into_inner().freeze()),
         })
     }
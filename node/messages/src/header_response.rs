@@ -0,0 +1,78 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HeaderResponse<N: Network> {
+    /// The original header request.
+    pub request: HeaderRequest,
+    /// The block headers.
+    pub headers: Vec<Header<N>>,
+}
+
+impl<N: Network> HeaderResponse<N> {
+    /// The maximum number of block headers that can be sent in a single message.
+    pub const MAXIMUM_NUMBER_OF_HEADERS: u16 = 256;
+}
+
+impl<N: Network> MessageTrait for HeaderResponse<N> {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> String {
+        let start = self.request.start_height;
+        let end = self.request.end_height;
+        match start + 1 == end {
+            true => format!("HeaderResponse {start}"),
+            false => format!("HeaderResponse {start}..{end}"),
+        }
+    }
+
+    /// Serializes the message into the buffer.
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        // Ensure that the number of headers is within the allowed range.
+        if self.headers.len() > Self::MAXIMUM_NUMBER_OF_HEADERS as usize {
+            bail!("Header response exceeds maximum number of headers");
+        }
+        self.request.serialize(writer)?;
+        (self.headers.len() as u16).write_le(&mut *writer)?;
+        self.headers.iter().try_for_each(|header| header.write_le(&mut *writer))?;
+        Ok(())
+    }
+
+    /// Deserializes the given buffer into a message.
+    #[inline]
+    fn deserialize(bytes: BytesMut) -> Result<Self> {
+        let mut reader = bytes.reader();
+        let request = HeaderRequest {
+            start_height: bincode::deserialize_from(&mut reader)?,
+            end_height: bincode::deserialize_from(&mut reader)?,
+        };
+        // Read the number of headers.
+        let num_headers = u16::read_le(&mut reader)?;
+        // Ensure that the number of headers is within the allowed range.
+        if num_headers > Self::MAXIMUM_NUMBER_OF_HEADERS {
+            bail!("Header response exceeds maximum number of headers");
+        }
+        // Read the headers.
+        let headers = (0..num_headers).map(|_| Header::read_le(&mut reader)).collect::<Result<Vec<_>, _>>()?;
+        match reader.into_inner().remaining() == 0 {
+            true => Ok(Self { request, headers }),
+            false => bail!("Invalid 'HeaderResponse' message"),
+        }
+    }
+}
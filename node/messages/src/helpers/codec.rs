@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::Message;
+use crate::{Compression, Message};
 use snarkvm::prelude::Network;
 
 use ::bytes::{BufMut, BytesMut};
@@ -27,13 +27,23 @@ const MAXIMUM_HANDSHAKE_MESSAGE_SIZE: usize = 1024 * 1024; // 1 MiB
 /// The maximum size of a message that can be transmitted in the network.
 const MAXIMUM_MESSAGE_SIZE: usize = 128 * 1024 * 1024; // 128 MiB
 
+/// The bit of the message ID that marks a compressed message.
+const COMPRESSED_FLAG: u16 = 0x8000;
+
 /// The codec used to decode and encode network `Message`s.
 pub struct MessageCodec<N: Network> {
     codec: LengthDelimitedCodec,
+    /// The compression negotiated on the connection.
+    compression: Compression,
     _phantom: PhantomData<N>,
 }
 
 impl<N: Network> MessageCodec<N> {
+    /// Initializes a codec that compresses the block and certificate messages with the given compression.
+    pub fn with_compression(compression: Compression) -> Self {
+        Self { compression, ..Default::default() }
+    }
+
     /// Increases the maximum permitted message size post-handshake.
     pub fn update_max_message_len(&mut self) {
         self.codec = LengthDelimitedCodec::builder().max_frame_length(MAXIMUM_MESSAGE_SIZE).little_endian().new_codec();
@@ -47,11 +57,34 @@ impl<N: Network> Default for MessageCodec<N> {
                 .max_frame_length(MAXIMUM_HANDSHAKE_MESSAGE_SIZE)
                 .little_endian()
                 .new_codec(),
+            compression: Default::default(),
             _phantom: Default::default(),
         }
     }
 }
 
+impl<N: Network> MessageCodec<N> {
+    /// Returns the message bytes of the given compressed payload, with the compression flag cleared from its ID.
+    fn decompress(&self, id: u16, bytes: &[u8]) -> Result<BytesMut, std::io::Error> {
+        // Ensure compression is negotiated on the connection.
+        if !self.compression.is_enabled() {
+            error!("Received a compressed message on an uncompressed connection");
+            return Err(std::io::ErrorKind::InvalidData.into());
+        }
+        let payload = match self.compression.decompress(&bytes[2..], MAXIMUM_MESSAGE_SIZE) {
+            Ok(payload) => payload,
+            Err(error) => {
+                error!("Failed to decompress a message: {error}");
+                return Err(std::io::ErrorKind::InvalidData.into());
+            }
+        };
+        let mut buffer = BytesMut::with_capacity(payload.len() + 2);
+        buffer.put_u16_le(id);
+        buffer.put_slice(&payload);
+        Ok(buffer)
+    }
+}
+
 impl<N: Network> Encoder<Message<N>> for MessageCodec<N> {
     type Error = std::io::Error;
 
@@ -62,7 +95,22 @@ impl<N: Network> Encoder<Message<N>> for MessageCodec<N> {
             // This error should never happen, the conversion is for greater compatibility.
             .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "serialization error"))?;
 
-        let serialized_message = dst.split_to(dst.len()).freeze();
+        let mut serialized_message = dst.split_to(dst.len()).freeze();
+
+        // Compress the payload of a block or certificate message, if compression is negotiated on the connection.
+        if self.compression.is_enabled() && message.is_compressible() {
+            let compressed = self
+                .compression
+                .compress(&serialized_message[2..])
+                .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "compression error"))?;
+            // Send the message uncompressed, if compression does not reduce its size.
+            if compressed.len() + 2 < serialized_message.len() {
+                let mut buffer = BytesMut::with_capacity(compressed.len() + 2);
+                buffer.put_u16_le(message.id() | COMPRESSED_FLAG);
+                buffer.put_slice(&compressed);
+                serialized_message = buffer.freeze();
+            }
+        }
 
         self.codec.encode(serialized_message, dst)
     }
@@ -79,6 +127,12 @@ impl<N: Network> Decoder for MessageCodec<N> {
             None => return Ok(None),
         };
 
+        // Decompress the payload, if the message is marked as compressed.
+        let bytes = match bytes.get(..2).map(|id| u16::from_le_bytes([id[0], id[1]])) {
+            Some(id) if id & COMPRESSED_FLAG != 0 => self.decompress(id & !COMPRESSED_FLAG, &bytes)?,
+            _ => bytes,
+        };
+
         // Convert the bytes to a message, or fail if it is not valid.
         match Message::deserialize(bytes) {
             Ok(message) => Ok(Some(message)),
@@ -89,3 +143,39 @@ impl<N: Network> Decoder for MessageCodec<N> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BlockRequest, BlockResponse, Data};
+
+    use ::bytes::Bytes;
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_compression() {
+        let message = Message::<CurrentNetwork>::BlockResponse(BlockResponse {
+            request: BlockRequest { start_height: 0, end_height: 1 },
+            blocks: Data::Buffer(Bytes::from(vec![0u8; 4096])),
+        });
+
+        // Ensure the message is compressed, and marked as such, on a compressed connection.
+        let mut codec = MessageCodec::<CurrentNetwork>::with_compression(Compression::Snappy);
+        let mut frame = BytesMut::new();
+        codec.encode(message.clone(), &mut frame).unwrap();
+        assert!(frame.len() < 4096);
+        assert_ne!(u16::from_le_bytes([frame[4], frame[5]]) & COMPRESSED_FLAG, 0);
+        assert_eq!(codec.decode(&mut frame.clone()).unwrap(), Some(message.clone()));
+
+        // Ensure a compressed message is rejected on an uncompressed connection.
+        let mut uncompressed = MessageCodec::<CurrentNetwork>::default();
+        assert!(uncompressed.decode(&mut frame).is_err());
+
+        // Ensure the message is not compressed on an uncompressed connection.
+        let mut frame = BytesMut::new();
+        uncompressed.encode(message.clone(), &mut frame).unwrap();
+        assert_eq!(u16::from_le_bytes([frame[4], frame[5]]), message.id());
+        assert_eq!(codec.decode(&mut frame).unwrap(), Some(message));
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// The compression of the block and certificate messages on a connection.
+/// Each node offers its compression in the handshake, and a connection is compressed only if both nodes offer it.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, Hash)]
+#[repr(u8)]
+pub enum Compression {
+    /// The messages are not compressed.
+    #[default]
+    None = 0,
+    /// The messages are compressed with Snappy.
+    Snappy,
+}
+
+impl Compression {
+    /// Returns the compression of a connection, where this node offers `self` and the peer offers `other`.
+    pub fn negotiate(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Snappy, Self::Snappy) => Self::Snappy,
+            _ => Self::None,
+        }
+    }
+
+    /// Returns `true` if the messages are compressed.
+    pub const fn is_enabled(&self) -> bool {
+        !matches!(self, Self::None)
+    }
+
+    /// Returns the compressed bytes.
+    pub fn compress(&self, bytes: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Self::None => Ok(bytes.to_vec()),
+            Self::Snappy => Ok(snap::raw::Encoder::new().compress_vec(bytes)?),
+        }
+    }
+
+    /// Returns the decompressed bytes, or an error if they would exceed the given maximum length.
+    pub fn decompress(&self, bytes: &[u8], max_len: usize) -> Result<Vec<u8>> {
+        match self {
+            Self::None => Ok(bytes.to_vec()),
+            Self::Snappy => {
+                // Check the decompressed length in the header, before allocating for it.
+                let len = snap::raw::decompress_len(bytes)?;
+                if len > max_len {
+                    bail!("The decompressed message is too large ({len} bytes)")
+                }
+                Ok(snap::raw::Decoder::new().decompress_vec(bytes)?)
+            }
+        }
+    }
+}
+
+impl core::str::FromStr for Compression {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "none" => Ok(Self::None),
+            "snappy" => Ok(Self::Snappy),
+            _ => bail!("Unknown compression '{s}' (expected 'none' or 'snappy')"),
+        }
+    }
+}
+
+impl core::fmt::Display for Compression {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", match self {
+            Self::None => "none",
+            Self::Snappy => "snappy",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(Compression::Snappy.negotiate(Compression::Snappy), Compression::Snappy);
+        assert_eq!(Compression::Snappy.negotiate(Compression::None), Compression::None);
+        assert_eq!(Compression::None.negotiate(Compression::Snappy), Compression::None);
+        assert_eq!(Compression::None.negotiate(Compression::None), Compression::None);
+    }
+
+    #[test]
+    fn test_decompress_bounds() {
+        let bytes = vec![7u8; 4096];
+        let compressed = Compression::Snappy.compress(&bytes).unwrap();
+        assert!(compressed.len() < bytes.len());
+        assert_eq!(Compression::Snappy.decompress(&compressed, bytes.len()).unwrap(), bytes);
+        // Ensure a message that would decompress beyond the maximum length is rejected.
+        assert!(Compression::Snappy.decompress(&compressed, bytes.len() - 1).is_err());
+        // Ensure malformed input is rejected.
+        assert!(Compression::Snappy.decompress(&[0xff, 0xff, 0xff], bytes.len()).is_err());
+    }
+}
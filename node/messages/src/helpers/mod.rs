@@ -20,6 +20,9 @@ pub use block_locators::*;
 mod codec;
 pub use codec::MessageCodec;
 
+mod compression;
+pub use compression::Compression;
+
 #[allow(unused)]
 mod noise_codec;
 
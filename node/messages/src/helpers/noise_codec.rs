@@ -276,19 +276,33 @@ impl Decoder for NoiseCodec {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use snarkvm::prelude::{Address, Group, TestRng, Uniform};
+    use snarkvm::prelude::{Address, Block, Field, FromBytes, Group, Network, PrivateKey, Signature, TestRng, Uniform};
 
     use crate::{
+        AncestorRequest,
+        AncestorResponse,
+        BlockCertificate,
         BlockRequest,
+        CertificateRangeRequest,
+        CertificateRangeResponse,
         ChallengeRequest,
+        Compression,
         Disconnect,
         DisconnectReason,
+        HeaderRequest,
+        HeaderResponse,
+        MemoryPoolRequest,
+        MemoryPoolResponse,
         NodeType,
+        PeerAnnouncement,
         PeerRequest,
         PeerResponse,
         Ping,
         Pong,
         PuzzleRequest,
+        TransactionAnnouncement,
+        TransactionRequest,
+        UnconfirmedTransmission,
     };
     use snow::{params::NoiseParams, Builder};
 
@@ -358,6 +372,7 @@ mod tests {
             node_type: NodeType::Client,
             address: Address::new(Group::rand(rng)),
             nonce: 0,
+            compression: Compression::Snappy,
         })));
 
         assert_roundtrip(challenge_request);
@@ -372,6 +387,31 @@ mod tests {
         assert_roundtrip(disconnect);
     }
 
+    fn sample_peer_announcement(rng: &mut TestRng) -> PeerAnnouncement<CurrentNetwork> {
+        let private_key = PrivateKey::new(rng).unwrap();
+        let (listener, node_type, address, timestamp) =
+            ("1.2.3.4:4133".parse().unwrap(), NodeType::Client, Address::try_from(&private_key).unwrap(), 1);
+        let bytes = PeerAnnouncement::signed_bytes(listener, node_type, address, timestamp).unwrap();
+        let signature = Signature::sign_bytes(&private_key, &bytes, rng).unwrap();
+        PeerAnnouncement { listener, node_type, address, timestamp, signature }
+    }
+
+    #[test]
+    fn peer_announcement_roundtrip() {
+        let rng = &mut TestRng::default();
+
+        let announcement = sample_peer_announcement(rng);
+        assert!(announcement.verify_signature());
+
+        // Ensure the signature does not cover another listening address.
+        let mut spoofed = announcement.clone();
+        spoofed.listener = "5.6.7.8:4133".parse().unwrap();
+        assert!(!spoofed.verify_signature());
+
+        let peer_announcement = MessageOrBytes::Message(Box::new(Message::PeerAnnouncement(announcement)));
+        assert_roundtrip(peer_announcement);
+    }
+
     #[test]
     fn peer_request_roundtrip() {
         let peer_request = MessageOrBytes::Message(Box::new(Message::PeerRequest(PeerRequest)));
@@ -380,8 +420,14 @@ mod tests {
 
     #[test]
     fn peer_response_roundtrip() {
+        let rng = &mut TestRng::default();
+
         let peer_response = MessageOrBytes::Message(Box::new(Message::PeerResponse(PeerResponse { peers: vec![] })));
         assert_roundtrip(peer_response);
+
+        let peers = vec![sample_peer_announcement(rng), sample_peer_announcement(rng)];
+        let peer_response = MessageOrBytes::Message(Box::new(Message::PeerResponse(PeerResponse { peers })));
+        assert_roundtrip(peer_response);
     }
 
     #[test]
@@ -403,4 +449,116 @@ mod tests {
         let puzzle_request = MessageOrBytes::Message(Box::new(Message::PuzzleRequest(PuzzleRequest)));
         assert_roundtrip(puzzle_request);
     }
+
+    #[test]
+    fn transaction_announcement_roundtrip() {
+        let rng = &mut TestRng::default();
+
+        let transaction_announcement =
+            MessageOrBytes::Message(Box::new(Message::TransactionAnnouncement(TransactionAnnouncement {
+                transaction_id: Field::<CurrentNetwork>::rand(rng).into(),
+            })));
+        assert_roundtrip(transaction_announcement);
+    }
+
+    #[test]
+    fn transaction_request_roundtrip() {
+        let rng = &mut TestRng::default();
+
+        let transaction_request = MessageOrBytes::Message(Box::new(Message::TransactionRequest(TransactionRequest {
+            transaction_id: Field::<CurrentNetwork>::rand(rng).into(),
+        })));
+        assert_roundtrip(transaction_request);
+    }
+
+    #[test]
+    fn unconfirmed_transmission_roundtrip() {
+        for payload in [::bytes::Bytes::from_static(b"payload"), Default::default()] {
+            let transmission = UnconfirmedTransmission { kind: "blob".to_string(), payload };
+            let unconfirmed_transmission =
+                MessageOrBytes::Message(Box::new(Message::<CurrentNetwork>::UnconfirmedTransmission(transmission)));
+            assert_roundtrip(unconfirmed_transmission);
+        }
+    }
+
+    #[test]
+    fn header_request_roundtrip() {
+        let header_request = MessageOrBytes::Message(Box::new(Message::HeaderRequest(HeaderRequest {
+            start_height: 0,
+            end_height: 100,
+        })));
+
+        assert_roundtrip(header_request);
+    }
+
+    #[test]
+    fn header_response_roundtrip() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+
+        let header_response = MessageOrBytes::Message(Box::new(Message::HeaderResponse(HeaderResponse {
+            request: HeaderRequest { start_height: 0, end_height: 1 },
+            headers: vec![*genesis.header()],
+        })));
+        assert_roundtrip(header_response);
+    }
+
+    #[test]
+    fn memory_pool_request_roundtrip() {
+        let memory_pool_request = MessageOrBytes::Message(Box::new(Message::MemoryPoolRequest(MemoryPoolRequest)));
+        assert_roundtrip(memory_pool_request);
+    }
+
+    #[test]
+    fn memory_pool_response_roundtrip() {
+        let rng = &mut TestRng::default();
+
+        let memory_pool_response = MessageOrBytes::Message(Box::new(Message::MemoryPoolResponse(MemoryPoolResponse {
+            num_transactions: 2,
+            num_solutions: 1,
+            transaction_ids: vec![Field::<CurrentNetwork>::rand(rng).into(), Field::<CurrentNetwork>::rand(rng).into()],
+        })));
+        assert_roundtrip(memory_pool_response);
+    }
+
+    #[test]
+    fn ancestor_request_roundtrip() {
+        let ancestor_request = MessageOrBytes::Message(Box::new(Message::AncestorRequest(AncestorRequest {
+            start_height: 0,
+            end_height: 100,
+        })));
+        assert_roundtrip(ancestor_request);
+    }
+
+    #[test]
+    fn ancestor_response_roundtrip() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+
+        let ancestor_response = MessageOrBytes::Message(Box::new(Message::AncestorResponse(AncestorResponse {
+            request: AncestorRequest { start_height: 0, end_height: 1 },
+            hashes: vec![(1, genesis.hash()), (0, genesis.hash())],
+        })));
+        assert_roundtrip(ancestor_response);
+    }
+
+    #[test]
+    fn certificate_range_request_roundtrip() {
+        let certificate_range_request =
+            MessageOrBytes::Message(Box::new(Message::CertificateRangeRequest(CertificateRangeRequest {
+                start_round: 0,
+                end_round: 100,
+            })));
+        assert_roundtrip(certificate_range_request);
+    }
+
+    #[test]
+    fn certificate_range_response_roundtrip() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+
+        let certificate_range_response =
+            MessageOrBytes::Message(Box::new(Message::CertificateRangeResponse(CertificateRangeResponse {
+                request: CertificateRangeRequest { start_round: 0, end_round: 1 },
+                certificates: vec![BlockCertificate::from_block(&genesis)],
+            })));
+        assert_roundtrip(certificate_range_response);
+    }
 }
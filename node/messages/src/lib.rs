@@ -22,6 +22,12 @@ extern crate tracing;
 pub mod helpers;
 pub use helpers::*;
 
+mod ancestor_request;
+pub use ancestor_request::AncestorRequest;
+
+mod ancestor_response;
+pub use ancestor_response::AncestorResponse;
+
 mod beacon_propose;
 pub use beacon_propose::BeaconPropose;
 
@@ -37,6 +43,12 @@ pub use block_request::BlockRequest;
 mod block_response;
 pub use block_response::{BlockResponse, DataBlocks};
 
+mod certificate_range_request;
+pub use certificate_range_request::CertificateRangeRequest;
+
+mod certificate_range_response;
+pub use certificate_range_response::{BlockCertificate, CertificateRangeResponse};
+
 mod challenge_request;
 pub use challenge_request::ChallengeRequest;
 
@@ -46,6 +58,21 @@ pub use challenge_response::ChallengeResponse;
 mod disconnect;
 pub use disconnect::Disconnect;
 
+mod header_request;
+pub use header_request::HeaderRequest;
+
+mod header_response;
+pub use header_response::HeaderResponse;
+
+mod memory_pool_request;
+pub use memory_pool_request::MemoryPoolRequest;
+
+mod memory_pool_response;
+pub use memory_pool_response::MemoryPoolResponse;
+
+mod peer_announcement;
+pub use peer_announcement::PeerAnnouncement;
+
 mod peer_request;
 pub use peer_request::PeerRequest;
 
@@ -64,12 +91,26 @@ pub use puzzle_request::PuzzleRequest;
 mod puzzle_response;
 pub use puzzle_response::PuzzleResponse;
 
+mod transaction_announcement;
+pub use transaction_announcement::TransactionAnnouncement;
+
+mod transaction_request;
+pub use transaction_request::TransactionRequest;
+
 mod unconfirmed_solution;
 pub use unconfirmed_solution::UnconfirmedSolution;
 
 mod unconfirmed_transaction;
 pub use unconfirmed_transaction::UnconfirmedTransaction;
 
+mod unconfirmed_transmission;
+pub use unconfirmed_transmission::{
+    UnconfirmedTransmission,
+    MAX_TRANSMISSION_KIND_LEN,
+    SOLUTION_TRANSMISSION_KIND,
+    TRANSACTION_TRANSMISSION_KIND,
+};
+
 use snarkvm::prelude::{
     error,
     Address,
@@ -91,7 +132,7 @@ use std::{
     fmt,
     fmt::{Display, Formatter},
     io::{Read, Result as IoResult, Write},
-    net::SocketAddr,
+    net::{IpAddr, SocketAddr},
     ops::Deref,
 };
 
@@ -108,48 +149,72 @@ pub trait MessageTrait {
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Message<N: Network> {
+    AncestorRequest(AncestorRequest),
+    AncestorResponse(AncestorResponse<N>),
     BeaconPropose(BeaconPropose<N>),
     BeaconTimeout(BeaconTimeout<N>),
     BeaconVote(BeaconVote<N>),
     BlockRequest(BlockRequest),
     BlockResponse(BlockResponse<N>),
+    CertificateRangeRequest(CertificateRangeRequest),
+    CertificateRangeResponse(CertificateRangeResponse<N>),
     ChallengeRequest(ChallengeRequest<N>),
     ChallengeResponse(ChallengeResponse<N>),
     Disconnect(Disconnect),
+    HeaderRequest(HeaderRequest),
+    HeaderResponse(HeaderResponse<N>),
+    MemoryPoolRequest(MemoryPoolRequest),
+    MemoryPoolResponse(MemoryPoolResponse<N>),
+    PeerAnnouncement(PeerAnnouncement<N>),
     PeerRequest(PeerRequest),
-    PeerResponse(PeerResponse),
+    PeerResponse(PeerResponse<N>),
     Ping(Ping<N>),
     Pong(Pong),
     PuzzleRequest(PuzzleRequest),
     PuzzleResponse(PuzzleResponse<N>),
+    TransactionAnnouncement(TransactionAnnouncement<N>),
+    TransactionRequest(TransactionRequest<N>),
     UnconfirmedSolution(UnconfirmedSolution<N>),
     UnconfirmedTransaction(UnconfirmedTransaction<N>),
+    UnconfirmedTransmission(UnconfirmedTransmission),
 }
 
 impl<N: Network> Message<N> {
     /// The version of the network protocol; it can be incremented in order to force users to update.
-    pub const VERSION: u32 = 5;
+    pub const VERSION: u32 = 7;
 
     /// Returns the message name.
     #[inline]
     pub fn name(&self) -> String {
         match self {
+            Self::AncestorRequest(message) => message.name(),
+            Self::AncestorResponse(message) => message.name(),
             Self::BeaconPropose(message) => message.name(),
             Self::BeaconTimeout(message) => message.name(),
             Self::BeaconVote(message) => message.name(),
             Self::BlockRequest(message) => message.name(),
             Self::BlockResponse(message) => message.name(),
+            Self::CertificateRangeRequest(message) => message.name(),
+            Self::CertificateRangeResponse(message) => message.name(),
             Self::ChallengeRequest(message) => message.name(),
             Self::ChallengeResponse(message) => message.name(),
             Self::Disconnect(message) => message.name(),
+            Self::HeaderRequest(message) => message.name(),
+            Self::HeaderResponse(message) => message.name(),
+            Self::MemoryPoolRequest(message) => message.name(),
+            Self::MemoryPoolResponse(message) => message.name(),
+            Self::PeerAnnouncement(message) => message.name(),
             Self::PeerRequest(message) => message.name(),
             Self::PeerResponse(message) => message.name(),
             Self::Ping(message) => message.name(),
             Self::Pong(message) => message.name(),
             Self::PuzzleRequest(message) => message.name(),
             Self::PuzzleResponse(message) => message.name(),
+            Self::TransactionAnnouncement(message) => message.name(),
+            Self::TransactionRequest(message) => message.name(),
             Self::UnconfirmedSolution(message) => message.name(),
             Self::UnconfirmedTransaction(message) => message.name(),
+            Self::UnconfirmedTransmission(message) => message.name(),
         }
     }
 
@@ -173,31 +238,72 @@ impl<N: Network> Message<N> {
             Self::PuzzleResponse(..) => 13,
             Self::UnconfirmedSolution(..) => 14,
             Self::UnconfirmedTransaction(..) => 15,
+            Self::TransactionAnnouncement(..) => 16,
+            Self::TransactionRequest(..) => 17,
+            Self::HeaderRequest(..) => 18,
+            Self::HeaderResponse(..) => 19,
+            Self::MemoryPoolRequest(..) => 20,
+            Self::MemoryPoolResponse(..) => 21,
+            Self::AncestorRequest(..) => 22,
+            Self::AncestorResponse(..) => 23,
+            Self::PeerAnnouncement(..) => 24,
+            Self::UnconfirmedTransmission(..) => 25,
+            Self::CertificateRangeRequest(..) => 26,
+            Self::CertificateRangeResponse(..) => 27,
         }
     }
 
+    /// Returns `true` if the message carries blocks or block certificates,
+    /// which are compressed on the connections that negotiated compression.
+    #[inline]
+    pub fn is_compressible(&self) -> bool {
+        matches!(self, Self::BeaconPropose(..) | Self::BlockResponse(..) | Self::CertificateRangeResponse(..))
+    }
+
+    /// Returns `true` if the message carries an unconfirmed transmission, which is gossiped by many peers,
+    /// and is the first to be dropped when the node is saturated.
+    #[inline]
+    pub fn is_transmission(&self) -> bool {
+        matches!(
+            self,
+            Self::UnconfirmedSolution(..) | Self::UnconfirmedTransaction(..) | Self::UnconfirmedTransmission(..)
+        )
+    }
+
     /// Serializes the message into the buffer.
     #[inline]
     pub fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
         writer.write_all(&self.id().to_le_bytes()[..])?;
 
         match self {
+            Self::AncestorRequest(message) => message.serialize(writer),
+            Self::AncestorResponse(message) => message.serialize(writer),
             Self::BeaconPropose(message) => message.serialize(writer),
             Self::BeaconTimeout(message) => message.serialize(writer),
             Self::BeaconVote(message) => message.serialize(writer),
             Self::BlockRequest(message) => message.serialize(writer),
             Self::BlockResponse(message) => message.serialize(writer),
+            Self::CertificateRangeRequest(message) => message.serialize(writer),
+            Self::CertificateRangeResponse(message) => message.serialize(writer),
             Self::ChallengeRequest(message) => message.serialize(writer),
             Self::ChallengeResponse(message) => message.serialize(writer),
             Self::Disconnect(message) => message.serialize(writer),
+            Self::HeaderRequest(message) => message.serialize(writer),
+            Self::HeaderResponse(message) => message.serialize(writer),
+            Self::MemoryPoolRequest(message) => message.serialize(writer),
+            Self::MemoryPoolResponse(message) => message.serialize(writer),
+            Self::PeerAnnouncement(message) => message.serialize(writer),
             Self::PeerRequest(message) => message.serialize(writer),
             Self::PeerResponse(message) => message.serialize(writer),
             Self::Ping(message) => message.serialize(writer),
             Self::Pong(message) => message.serialize(writer),
             Self::PuzzleRequest(message) => message.serialize(writer),
             Self::PuzzleResponse(message) => message.serialize(writer),
+            Self::TransactionAnnouncement(message) => message.serialize(writer),
+            Self::TransactionRequest(message) => message.serialize(writer),
             Self::UnconfirmedSolution(message) => message.serialize(writer),
             Self::UnconfirmedTransaction(message) => message.serialize(writer),
+            Self::UnconfirmedTransmission(message) => message.serialize(writer),
         }
     }
 
@@ -230,6 +336,18 @@ impl<N: Network> Message<N> {
             13 => Self::PuzzleResponse(MessageTrait::deserialize(bytes)?),
             14 => Self::UnconfirmedSolution(MessageTrait::deserialize(bytes)?),
             15 => Self::UnconfirmedTransaction(MessageTrait::deserialize(bytes)?),
+            16 => Self::TransactionAnnouncement(MessageTrait::deserialize(bytes)?),
+            17 => Self::TransactionRequest(MessageTrait::deserialize(bytes)?),
+            18 => Self::HeaderRequest(MessageTrait::deserialize(bytes)?),
+            19 => Self::HeaderResponse(MessageTrait::deserialize(bytes)?),
+            20 => Self::MemoryPoolRequest(MessageTrait::deserialize(bytes)?),
+            21 => Self::MemoryPoolResponse(MessageTrait::deserialize(bytes)?),
+            22 => Self::AncestorRequest(MessageTrait::deserialize(bytes)?),
+            23 => Self::AncestorResponse(MessageTrait::deserialize(bytes)?),
+            24 => Self::PeerAnnouncement(MessageTrait::deserialize(bytes)?),
+            25 => Self::UnconfirmedTransmission(MessageTrait::deserialize(bytes)?),
+            26 => Self::CertificateRangeRequest(MessageTrait::deserialize(bytes)?),
+            27 => Self::CertificateRangeResponse(MessageTrait::deserialize(bytes)?),
             _ => bail!("Unknown message ID {id}"),
         };
 
@@ -0,0 +1,76 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+/// A summary of the memory pool.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoryPoolResponse<N: Network> {
+    /// The number of unconfirmed transactions.
+    pub num_transactions: u32,
+    /// The number of unconfirmed solutions.
+    pub num_solutions: u32,
+    /// The IDs of the oldest unconfirmed transactions, up to the maximum number of transaction IDs.
+    pub transaction_ids: Vec<N::TransactionID>,
+}
+
+impl<N: Network> MemoryPoolResponse<N> {
+    /// The maximum number of transaction IDs that can be sent in a single message.
+    pub const MAXIMUM_NUMBER_OF_TRANSACTION_IDS: u16 = 256;
+}
+
+impl<N: Network> MessageTrait for MemoryPoolResponse<N> {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> String {
+        "MemoryPoolResponse".to_string()
+    }
+
+    /// Serializes the message into the buffer.
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        // Ensure that the number of transaction IDs is within the allowed range.
+        if self.transaction_ids.len() > Self::MAXIMUM_NUMBER_OF_TRANSACTION_IDS as usize {
+            bail!("Memory pool response exceeds maximum number of transaction IDs");
+        }
+        self.num_transactions.write_le(&mut *writer)?;
+        self.num_solutions.write_le(&mut *writer)?;
+        (self.transaction_ids.len() as u16).write_le(&mut *writer)?;
+        self.transaction_ids.iter().try_for_each(|transaction_id| transaction_id.write_le(&mut *writer))?;
+        Ok(())
+    }
+
+    /// Deserializes the given buffer into a message.
+    #[inline]
+    fn deserialize(bytes: BytesMut) -> Result<Self> {
+        let mut reader = bytes.reader();
+        let num_transactions = u32::read_le(&mut reader)?;
+        let num_solutions = u32::read_le(&mut reader)?;
+        // Read the number of transaction IDs.
+        let num_transaction_ids = u16::read_le(&mut reader)?;
+        // Ensure that the number of transaction IDs is within the allowed range.
+        if num_transaction_ids > Self::MAXIMUM_NUMBER_OF_TRANSACTION_IDS {
+            bail!("Memory pool response exceeds maximum number of transaction IDs");
+        }
+        // Read the transaction IDs.
+        let transaction_ids =
+            (0..num_transaction_ids).map(|_| N::TransactionID::read_le(&mut reader)).collect::<Result<Vec<_>, _>>()?;
+        match reader.into_inner().remaining() == 0 {
+            true => Ok(Self { num_transactions, num_solutions, transaction_ids }),
+            false => bail!("Invalid 'MemoryPoolResponse' message"),
+        }
+    }
+}
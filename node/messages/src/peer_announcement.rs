@@ -0,0 +1,82 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+use serde::{Deserialize, Serialize};
+
+/// The domain separator of the bytes signed in a peer announcement.
+const ANNOUNCEMENT_DOMAIN: &[u8] = b"snarkos-peer-announcement";
+
+/// The listening address and node type of a node, signed with its account key. A node sends its own
+/// announcement to each peer once the IP the peer observed for it is corroborated, and again periodically,
+/// and the peers that dialed its address relay it in their `PeerResponse` messages.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct PeerAnnouncement<N: Network> {
+    /// The listening address of the node.
+    pub listener: SocketAddr,
+    /// The node type of the node.
+    pub node_type: NodeType,
+    /// The account address of the node.
+    pub address: Address<N>,
+    /// The UNIX timestamp at which the announcement was signed.
+    pub timestamp: i64,
+    /// The signature of the announcement, by the account of the node.
+    pub signature: Signature<N>,
+}
+
+impl<N: Network> MessageTrait for PeerAnnouncement<N> {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> String {
+        "PeerAnnouncement".to_string()
+    }
+
+    /// Serializes the message into the buffer.
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        Ok(bincode::serialize_into(writer, self)?)
+    }
+
+    /// Deserializes the given buffer into a message.
+    #[inline]
+    fn deserialize(bytes: BytesMut) -> Result<Self> {
+        Ok(bincode::deserialize_from(&mut bytes.reader())?)
+    }
+}
+
+impl<N: Network> PeerAnnouncement<N> {
+    /// Returns the bytes that a node signs to announce the given listening address and node type.
+    pub fn signed_bytes(
+        listener: SocketAddr,
+        node_type: NodeType,
+        address: Address<N>,
+        timestamp: i64,
+    ) -> Result<Vec<u8>> {
+        let mut bytes = ANNOUNCEMENT_DOMAIN.to_vec();
+        bincode::serialize_into(&mut bytes, &(listener, node_type, address, timestamp))?;
+        Ok(bytes)
+    }
+
+    /// Returns `true` if the announcement is signed by the account of its address.
+    pub fn verify_signature(&self) -> bool {
+        match Self::signed_bytes(self.listener, self.node_type, self.address, self.timestamp) {
+            Ok(bytes) => self.signature.verify_bytes(&self.address, &bytes),
+            Err(_) => false,
+        }
+    }
+}
@@ -17,11 +17,16 @@
 use super::*;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct PeerResponse {
-    pub peers: Vec<SocketAddr>,
+pub struct PeerResponse<N: Network> {
+    pub peers: Vec<PeerAnnouncement<N>>,
 }
 
-impl MessageTrait for PeerResponse {
+impl<N: Network> PeerResponse<N> {
+    /// The maximum number of peer announcements that can be sent in a single message.
+    pub const MAXIMUM_NUMBER_OF_PEERS: u16 = 128;
+}
+
+impl<N: Network> MessageTrait for PeerResponse<N> {
     /// Returns the message name.
     #[inline]
     fn name(&self) -> String {
@@ -31,12 +36,78 @@ impl MessageTrait for PeerResponse {
     /// Serializes the message into the buffer.
     #[inline]
     fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
-        Ok(bincode::serialize_into(writer, &self.peers)?)
+        // Ensure that the number of peers is within the allowed range.
+        if self.peers.len() > Self::MAXIMUM_NUMBER_OF_PEERS as usize {
+            bail!("Peer response exceeds maximum number of peers");
+        }
+        (self.peers.len() as u16).write_le(&mut *writer)?;
+        self.peers.iter().try_for_each(|peer| Ok(bincode::serialize_into(&mut *writer, peer)?))
     }
 
     /// Deserializes the given buffer into a message.
     #[inline]
     fn deserialize(bytes: BytesMut) -> Result<Self> {
-        Ok(Self { peers: bincode::deserialize_from(&mut bytes.reader())? })
+        let mut reader = bytes.reader();
+        // Read the number of peers.
+        let num_peers = u16::read_le(&mut reader)?;
+        // Ensure that the number of peers is within the allowed range.
+        if num_peers > Self::MAXIMUM_NUMBER_OF_PEERS {
+            bail!("Peer response exceeds maximum number of peers");
+        }
+        // Read the peer announcements.
+        let peers = (0..num_peers)
+            .map(|_| bincode::deserialize_from(&mut reader))
+            .collect::<Result<Vec<PeerAnnouncement<N>>, _>>()?;
+        match reader.into_inner().remaining() == 0 {
+            true => Ok(Self { peers }),
+            false => bail!("Invalid 'PeerResponse' message"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{PrivateKey, TestRng, Testnet3};
+
+    use std::str::FromStr;
+
+    type CurrentNetwork = Testnet3;
+
+    /// Returns a signed announcement of the given listening address.
+    fn sample_announcement(rng: &mut TestRng, listener: &str) -> PeerAnnouncement<CurrentNetwork> {
+        let private_key = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let address = Address::try_from(private_key).unwrap();
+        let listener = SocketAddr::from_str(listener).unwrap();
+        let bytes = PeerAnnouncement::signed_bytes(listener, NodeType::Client, address, 0).unwrap();
+        let signature = Signature::sign_bytes(&private_key, &bytes, rng).unwrap();
+        PeerAnnouncement { listener, node_type: NodeType::Client, address, timestamp: 0, signature }
+    }
+
+    #[test]
+    fn test_peer_response() {
+        let rng = &mut TestRng::default();
+
+        // Ensure the announcements round-trip.
+        let peers = vec![sample_announcement(rng, "1.2.3.4:4133"), sample_announcement(rng, "5.6.7.8:4133")];
+        let response = PeerResponse { peers };
+        let mut buffer = Vec::new();
+        response.serialize(&mut buffer).unwrap();
+        let bytes = BytesMut::from(&buffer[..]);
+        assert_eq!(PeerResponse::deserialize(bytes.clone()).unwrap(), response);
+        assert!(response.peers.iter().all(PeerAnnouncement::verify_signature));
+
+        // Ensure trailing bytes are rejected.
+        let mut trailing = bytes.clone();
+        trailing.extend_from_slice(&[0]);
+        assert!(PeerResponse::<CurrentNetwork>::deserialize(trailing).is_err());
+
+        // Ensure a count above the maximum is rejected, before any announcement is read.
+        let maximum = PeerResponse::<CurrentNetwork>::MAXIMUM_NUMBER_OF_PEERS;
+        let mut oversized = BytesMut::from(&(maximum + 1).to_le_bytes()[..]);
+        oversized.extend_from_slice(&bytes[2..]);
+        assert!(PeerResponse::<CurrentNetwork>::deserialize(oversized).is_err());
+        let peers = vec![response.peers[0].clone(); maximum as usize + 1];
+        assert!(PeerResponse { peers }.serialize(&mut Vec::new()).is_err());
     }
 }
@@ -0,0 +1,68 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+use ::bytes::Bytes;
+
+/// The maximum length of the name of a transmission kind, in bytes.
+pub const MAX_TRANSMISSION_KIND_LEN: usize = 32;
+/// The kind of the transmissions that carry a prover solution, which are handled natively.
+pub const SOLUTION_TRANSMISSION_KIND: &str = "solution";
+/// The kind of the transmissions that carry a transaction, which are handled natively.
+pub const TRANSACTION_TRANSMISSION_KIND: &str = "transaction";
+
+/// An unconfirmed transmission, as an opaque payload that is interpreted by its kind.
+/// The solutions and transactions are carried as their little-endian bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnconfirmedTransmission {
+    pub kind: String,
+    pub payload: Bytes,
+}
+
+impl MessageTrait for UnconfirmedTransmission {
+    /// Returns the message name.
+    #[inline]
+    fn name(&self) -> String {
+        "UnconfirmedTransmission".to_string()
+    }
+
+    /// Serializes the message into the buffer.
+    #[inline]
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        if self.kind.is_empty() || self.kind.len() > MAX_TRANSMISSION_KIND_LEN {
+            bail!("Invalid transmission kind '{}'", self.kind)
+        }
+        writer.write_all(&[self.kind.len() as u8])?;
+        writer.write_all(self.kind.as_bytes())?;
+        writer.write_all(&self.payload)?;
+        Ok(())
+    }
+
+    /// Deserializes the given buffer into a message.
+    #[inline]
+    fn deserialize(mut bytes: BytesMut) -> Result<Self> {
+        if bytes.remaining() == 0 {
+            bail!("Missing the transmission kind in an 'UnconfirmedTransmission'")
+        }
+        let kind_len = bytes.get_u8() as usize;
+        if kind_len == 0 || kind_len > MAX_TRANSMISSION_KIND_LEN || bytes.remaining() < kind_len {
+            bail!("Invalid transmission kind in an 'UnconfirmedTransmission'")
+        }
+        let kind = String::from_utf8(bytes.split_to(kind_len).to_vec())?;
+        Ok(Self { kind, payload: bytes.freeze() })
+    }
+}
@@ -0,0 +1,261 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::GAUGE_NAMES;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+/// The comparison between a metric value and the threshold of an alert rule.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Comparison {
+    #[serde(rename = "<")]
+    LessThan,
+    #[serde(rename = "<=")]
+    LessThanOrEqual,
+    #[serde(rename = ">")]
+    GreaterThan,
+    #[serde(rename = ">=")]
+    GreaterThanOrEqual,
+    #[serde(rename = "==")]
+    Equal,
+}
+
+impl Comparison {
+    /// Returns `true` if the given value satisfies the comparison against the given threshold.
+    pub fn holds(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Self::LessThan => value < threshold,
+            Self::LessThanOrEqual => value <= threshold,
+            Self::GreaterThan => value > threshold,
+            Self::GreaterThanOrEqual => value >= threshold,
+            Self::Equal => value == threshold,
+        }
+    }
+}
+
+/// The action to take when an alert rule fires.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum AlertAction {
+    /// Logs the alert.
+    Log,
+    /// Logs the alert, and posts it as JSON to the given URL.
+    Webhook { url: String },
+    /// Logs the alert, and shuts down the node.
+    Shutdown,
+}
+
+/// A rule that fires when a metric satisfies a condition for a given duration.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AlertRule {
+    /// The name of the rule.
+    pub name: String,
+    /// The name of the metric.
+    pub metric: String,
+    /// The comparison between the metric value and the threshold.
+    pub condition: Comparison,
+    /// The threshold of the metric value.
+    pub threshold: f64,
+    /// The number of seconds the condition must hold before the rule fires.
+    #[serde(default)]
+    pub duration: u64,
+    /// The action to take when the rule fires.
+    #[serde(flatten)]
+    pub action: AlertAction,
+}
+
+/// The set of alert rules, as configured in TOML.
+///
+/// ```toml
+/// [[rule]]
+/// name = "low-peer-count"
+/// metric = "snarkos_peers_connected_total"
+/// condition = "<"
+/// threshold = 3
+/// duration = 300
+/// action = "webhook"
+/// url = "http://localhost:8080/alerts"
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AlertRules {
+    /// The alert rules.
+    #[serde(rename = "rule", default)]
+    pub rules: Vec<AlertRule>,
+}
+
+impl AlertRules {
+    /// Parses the alert rules from the given TOML string.
+    pub fn from_toml(string: &str) -> Result<Self> {
+        let rules: Self = toml::from_str(string)?;
+        // Ensure each rule refers to an exported metric.
+        for rule in &rules.rules {
+            if !GAUGE_NAMES.contains(&rule.metric.as_str()) {
+                bail!("Alert rule '{}' refers to an unknown metric '{}'", rule.name, rule.metric)
+            }
+        }
+        Ok(rules)
+    }
+
+    /// Loads the alert rules from the TOML file at the given path.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+}
+
+/// An alert raised by a rule.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Alert {
+    /// The name of the rule.
+    pub rule: String,
+    /// The name of the metric.
+    pub metric: String,
+    /// The value of the metric when the rule fired.
+    pub value: f64,
+    /// The action to take.
+    #[serde(skip)]
+    pub action: AlertAction,
+}
+
+/// The evaluation state of an alert rule.
+#[derive(Clone, Debug, Default)]
+struct RuleState {
+    /// The instant at which the condition started to hold, if it currently holds.
+    since: Option<Instant>,
+    /// Whether the rule has fired since the condition started to hold.
+    fired: bool,
+}
+
+/// An engine that evaluates alert rules against snapshots of the metrics.
+#[derive(Clone, Debug)]
+pub struct AlertEngine {
+    /// The alert rules, and their evaluation states.
+    rules: Vec<(AlertRule, RuleState)>,
+}
+
+impl AlertEngine {
+    /// Initializes a new alert engine with the given rules.
+    pub fn new(rules: AlertRules) -> Self {
+        Self { rules: rules.rules.into_iter().map(|rule| (rule, RuleState::default())).collect() }
+    }
+
+    /// Evaluates the rules against the given metrics, and returns the alerts that fired.
+    /// A rule fires once each time its condition holds for longer than its duration.
+    pub fn evaluate(&mut self, metrics: &HashMap<&str, f64>, now: Instant) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        for (rule, state) in self.rules.iter_mut() {
+            // Retrieve the metric value, skipping the rule if it is not available.
+            let value = match metrics.get(rule.metric.as_str()) {
+                Some(value) => *value,
+                None => continue,
+            };
+
+            // If the condition does not hold, reset the rule.
+            if !rule.condition.holds(value, rule.threshold) {
+                if state.fired {
+                    info!("Alert '{}' resolved ({} = {value})", rule.name, rule.metric);
+                }
+                *state = RuleState::default();
+                continue;
+            }
+
+            // Fire the rule if the condition has held for the configured duration.
+            let since = *state.since.get_or_insert(now);
+            if !state.fired && now.saturating_duration_since(since) >= Duration::from_secs(rule.duration) {
+                state.fired = true;
+                alerts.push(Alert {
+                    rule: rule.name.clone(),
+                    metric: rule.metric.clone(),
+                    value,
+                    action: rule.action.clone(),
+                });
+            }
+        }
+        alerts
+    }
+}
+
+/// Posts the given alert as JSON to the given URL.
+pub async fn post_alert(url: &str, alert: &Alert) -> Result<()> {
+    reqwest::Client::new().post(url).json(alert).send().await?.error_for_status()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::peers;
+
+    const RULES: &str = r#"
+[[rule]]
+name = "low-peer-count"
+metric = "snarkos_peers_connected_total"
+condition = "<"
+threshold = 3
+duration = 300
+action = "log"
+
+[[rule]]
+name = "too-many-restricted"
+metric = "snarkos_peers_restricted_total"
+condition = ">="
+threshold = 10
+action = "webhook"
+url = "http://localhost:8080/alerts"
+"#;
+
+    #[test]
+    fn test_parse_rules() {
+        let rules = AlertRules::from_toml(RULES).unwrap();
+        assert_eq!(rules.rules.len(), 2);
+        assert_eq!(rules.rules[0].condition, Comparison::LessThan);
+        assert_eq!(rules.rules[0].action, AlertAction::Log);
+        assert_eq!(rules.rules[1].duration, 0);
+        assert_eq!(rules.rules[1].action, AlertAction::Webhook { url: "http://localhost:8080/alerts".to_string() });
+
+        // Ensure unknown metrics are rejected.
+        assert!(AlertRules::from_toml(&RULES.replace(peers::RESTRICTED, "snarkos_unknown")).is_err());
+    }
+
+    #[test]
+    fn test_evaluate_rules() {
+        let mut engine = AlertEngine::new(AlertRules::from_toml(RULES).unwrap());
+        let start = Instant::now();
+
+        // The peer count is low, but not for long enough.
+        let metrics = HashMap::from([(peers::CONNECTED, 1.0), (peers::RESTRICTED, 0.0)]);
+        assert!(engine.evaluate(&metrics, start).is_empty());
+        assert!(engine.evaluate(&metrics, start + Duration::from_secs(299)).is_empty());
+
+        // The peer count has been low for 5 minutes.
+        let alerts = engine.evaluate(&metrics, start + Duration::from_secs(300));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].rule, "low-peer-count");
+        assert_eq!(alerts[0].value, 1.0);
+
+        // The rule does not fire again until the condition is reset.
+        assert!(engine.evaluate(&metrics, start + Duration::from_secs(600)).is_empty());
+        let metrics = HashMap::from([(peers::CONNECTED, 5.0), (peers::RESTRICTED, 10.0)]);
+        let alerts = engine.evaluate(&metrics, start + Duration::from_secs(601));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].rule, "too-many-restricted");
+    }
+}
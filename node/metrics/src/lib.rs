@@ -14,19 +14,31 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
+#[macro_use]
+extern crate tracing;
+
+mod alerts;
+pub use alerts::*;
+
 mod names;
 
+mod push;
+pub use push::*;
+
 // Re-export the metrics macros.
 pub use metrics::*;
 // Expose the names at the crate level for easy access.
 pub use names::*;
 
-/// Initialises the metrics and returns a handle to the task running the metrics exporter.
-pub fn initialize() -> tokio::task::JoinHandle<()> {
+/// Initialises the metrics, with an HTTP listener serving them to Prometheus at `/metrics` on the given IP address,
+/// and returns a handle to the task running the metrics exporter.
+#[cfg(feature = "prometheus")]
+pub fn initialize(listen_ip: std::net::SocketAddr) -> tokio::task::JoinHandle<()> {
     use metrics_exporter_prometheus::PrometheusBuilder;
 
     // Build the recorder and set as global.
-    let (recorder, exporter) = PrometheusBuilder::new().build().expect("can't build the prometheus exporter");
+    let (recorder, exporter) =
+        PrometheusBuilder::new().with_http_listener(listen_ip).build().expect("can't build the prometheus exporter");
     metrics::set_boxed_recorder(Box::new(recorder)).expect("can't set the prometheus exporter");
 
     // Spawn a dedicated task for the exporter on the runtime.
@@ -41,8 +53,16 @@ pub fn initialize() -> tokio::task::JoinHandle<()> {
     metrics_exporter_task
 }
 
+#[cfg(feature = "prometheus")]
 fn register_metrics() {
     for name in GAUGE_NAMES {
         register_gauge!(name);
     }
 }
+
+/// Sets the gauges to the given snapshot of the metrics.
+pub fn update_gauges(snapshot: &std::collections::HashMap<&'static str, f64>) {
+    for (name, value) in snapshot {
+        gauge!(*name, *value);
+    }
+}
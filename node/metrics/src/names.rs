@@ -14,14 +14,69 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-pub const GAUGE_NAMES: [&str; 4] = [blocks::HEIGHT, peers::CONNECTED, peers::CANDIDATE, peers::RESTRICTED];
+pub const GAUGE_NAMES: [&str; 20] = [
+    blocks::HEIGHT,
+    blocks::SYNC_LAG,
+    blocks::FORKS,
+    peers::CONNECTED,
+    peers::CANDIDATE,
+    peers::RESTRICTED,
+    peers::THROTTLED,
+    memory_pool::TRANSACTIONS,
+    memory_pool::SOLUTIONS,
+    storage::LIVE_DATA_BYTES,
+    storage::SST_FILES_BYTES,
+    storage::MEMTABLE_BYTES,
+    storage::NUM_KEYS,
+    node::STANDBY,
+    watch_list::ADDRESSES,
+    watch_list::CHANGES,
+    rest::RATE_LIMITED,
+    rest::OVERSIZED,
+    transmissions::QUEUE_SATURATION,
+    transmissions::DROPPED,
+];
 
 pub mod blocks {
     pub const HEIGHT: &str = "snarkos_blocks_height_total";
+    pub const SYNC_LAG: &str = "snarkos_blocks_sync_lag";
+    pub const FORKS: &str = "snarkos_blocks_forks_total";
 }
 
 pub mod peers {
     pub const CONNECTED: &str = "snarkos_peers_connected_total";
     pub const CANDIDATE: &str = "snarkos_peers_candidate_total";
     pub const RESTRICTED: &str = "snarkos_peers_restricted_total";
+    pub const THROTTLED: &str = "snarkos_peers_throttled_messages_total";
+}
+
+pub mod memory_pool {
+    pub const TRANSACTIONS: &str = "snarkos_memory_pool_transactions_total";
+    pub const SOLUTIONS: &str = "snarkos_memory_pool_solutions_total";
+}
+
+pub mod storage {
+    pub const LIVE_DATA_BYTES: &str = "snarkos_storage_live_data_bytes";
+    pub const SST_FILES_BYTES: &str = "snarkos_storage_sst_files_bytes";
+    pub const MEMTABLE_BYTES: &str = "snarkos_storage_memtable_bytes";
+    pub const NUM_KEYS: &str = "snarkos_storage_keys_total";
+}
+
+pub mod node {
+    pub const STANDBY: &str = "snarkos_node_standby";
+}
+
+pub mod watch_list {
+    pub const ADDRESSES: &str = "snarkos_watch_list_addresses_total";
+    pub const CHANGES: &str = "snarkos_watch_list_changes_total";
+}
+
+pub mod rest {
+    pub const RATE_LIMITED: &str = "snarkos_rest_rate_limited_total";
+    pub const OVERSIZED: &str = "snarkos_rest_oversized_total";
+}
+
+pub mod transmissions {
+    pub const QUEUE_SATURATION: &str = "snarkos_transmissions_queue_saturation";
+    pub const DROPPED: &str = "snarkos_transmissions_dropped_total";
 }
@@ -0,0 +1,169 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{anyhow, Result};
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The job label attached to the pushed metrics.
+const JOB: &str = "snarkos";
+
+/// The protocol used to push metrics.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PushProtocol {
+    /// Pushes the metrics in the text exposition format to a Prometheus push-gateway.
+    Gateway,
+    /// Pushes the metrics as a Prometheus remote-write request.
+    RemoteWrite,
+}
+
+/// The configuration for pushing metrics to a remote endpoint.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PushConfig {
+    /// The URL to push the metrics to.
+    pub url: String,
+    /// The protocol used to push the metrics.
+    pub protocol: PushProtocol,
+    /// The interval at which the metrics are pushed.
+    pub interval: Duration,
+    /// The username and password for basic authentication, if any.
+    pub basic_auth: Option<(String, String)>,
+}
+
+impl PushConfig {
+    /// Parses the given `username:password` string into basic authentication credentials.
+    pub fn parse_basic_auth(credentials: &str) -> Result<(String, String)> {
+        let (username, password) =
+            credentials.split_once(':').ok_or_else(|| anyhow!("Expected credentials as 'username:password'"))?;
+        Ok((username.to_string(), password.to_string()))
+    }
+}
+
+/// Pushes the given metrics to the configured endpoint.
+pub async fn push_metrics(config: &PushConfig, metrics: &HashMap<&str, f64>) -> Result<()> {
+    let client = reqwest::Client::new();
+    let request = match config.protocol {
+        PushProtocol::Gateway => {
+            client.put(&config.url).header("Content-Type", "text/plain; version=0.0.4").body(render_text(metrics))
+        }
+        PushProtocol::RemoteWrite => {
+            let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as i64;
+            let body = snap::raw::Encoder::new().compress_vec(&encode_remote_write(metrics, timestamp))?;
+            client
+                .post(&config.url)
+                .header("Content-Type", "application/x-protobuf")
+                .header("Content-Encoding", "snappy")
+                .header("X-Prometheus-Remote-Write-Version", "0.1.0")
+                .body(body)
+        }
+    };
+    let request = match &config.basic_auth {
+        Some((username, password)) => request.basic_auth(username, Some(password)),
+        None => request,
+    };
+    request.send().await?.error_for_status()?;
+    Ok(())
+}
+
+/// Renders the given metrics as gauges in the Prometheus text exposition format.
+pub fn render_text(metrics: &HashMap<&str, f64>) -> String {
+    let mut names = metrics.keys().collect::<Vec<_>>();
+    names.sort();
+
+    let mut output = String::new();
+    for name in names {
+        // Writing to a `String` is infallible.
+        let _ = writeln!(output, "# TYPE {name} gauge\n{name} {}", metrics[name]);
+    }
+    output
+}
+
+/// Encodes the given metrics as an (uncompressed) Prometheus remote-write request, at the given timestamp in milliseconds.
+pub fn encode_remote_write(metrics: &HashMap<&str, f64>, timestamp: i64) -> Vec<u8> {
+    let mut names = metrics.keys().collect::<Vec<_>>();
+    names.sort();
+
+    let mut request = Vec::new();
+    for name in names {
+        // Encode the labels, in lexicographic order of the label names.
+        let mut series = Vec::new();
+        for (label, value) in [("__name__", *name), ("job", JOB)] {
+            let mut message = Vec::new();
+            encode_bytes(&mut message, 1, label.as_bytes());
+            encode_bytes(&mut message, 2, value.as_bytes());
+            encode_bytes(&mut series, 1, &message);
+        }
+        // Encode the sample.
+        let mut sample = vec![0x09];
+        sample.extend_from_slice(&metrics[name].to_le_bytes());
+        sample.push(0x10);
+        encode_varint(&mut sample, timestamp as u64);
+        encode_bytes(&mut series, 2, &sample);
+        // Append the time series to the request.
+        encode_bytes(&mut request, 1, &series);
+    }
+    request
+}
+
+/// Appends the given length-delimited protobuf field to the buffer.
+fn encode_bytes(buffer: &mut Vec<u8>, field: u8, bytes: &[u8]) {
+    buffer.push(field << 3 | 2);
+    encode_varint(buffer, bytes.len() as u64);
+    buffer.extend_from_slice(bytes);
+}
+
+/// Appends the given protobuf varint to the buffer.
+fn encode_varint(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_text() {
+        let metrics = HashMap::from([("b", 2.0), ("a", 1.5)]);
+        assert_eq!(render_text(&metrics), "# TYPE a gauge\na 1.5\n# TYPE b gauge\nb 2\n");
+    }
+
+    #[test]
+    fn test_encode_remote_write() {
+        let metrics = HashMap::from([("a", 1.0)]);
+
+        // Construct the expected request by hand.
+        let name_label = [&[0x0a, 8][..], b"__name__", &[0x12, 1], b"a"].concat();
+        let job_label = [&[0x0a, 3][..], b"job", &[0x12, 7], b"snarkos"].concat();
+        let sample = [&[0x09][..], &1.0f64.to_le_bytes(), &[0x10, 0xac, 0x02]].concat();
+        let series = [&[0x0a, 13][..], &name_label, &[0x0a, 14], &job_label, &[0x12, 12], &sample].concat();
+        let expected = [&[0x0a, 45][..], &series].concat();
+
+        assert_eq!(encode_remote_write(&metrics, 300), expected);
+    }
+
+    #[test]
+    fn test_parse_basic_auth() {
+        assert_eq!(PushConfig::parse_basic_auth("user:pass:word").unwrap(), ("user".into(), "pass:word".into()));
+        assert!(PushConfig::parse_basic_auth("user").is_err());
+    }
+}
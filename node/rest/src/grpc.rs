@@ -0,0 +1,433 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+/// The types and services generated from `proto/snarkos.proto`.
+pub mod proto {
+    tonic::include_proto!("snarkos.v1");
+}
+
+use crate::{authorize, block_stream, AuthToken, Privilege, RateLimiter, RequestCounter};
+use proto::node_server::{Node, NodeServer};
+use snarkos_node_consensus::{Consensus, SignedBlockHash};
+use snarkos_node_ledger::Ledger;
+use snarkos_node_router::{ConnectionDirection, Router};
+use snarkvm::{
+    prelude::{Address, Network, ToBytes},
+    synthesizer::{Block, ConsensusStorage},
+};
+
+use anyhow::Result;
+use futures_util::{Stream, StreamExt};
+use std::{net::SocketAddr, pin::Pin, str::FromStr, sync::Arc};
+use tonic::{transport::Server, Request, Response, Status};
+
+/// A stream of blocks, as gRPC messages.
+type BlockStream = Pin<Box<dyn Stream<Item = Result<proto::Block, Status>> + Send>>;
+
+/// A gRPC server for the ledger, mirroring the block, transaction, and peer queries of the REST server.
+/// The requests are subject to the rate limits of the REST server, and share its per-IP budget,
+/// and the block streams are counted as requests being served by the REST server while they are open.
+pub struct GrpcServer<N: Network, C: ConsensusStorage<N>> {
+    /// The consensus module.
+    consensus: Option<Consensus<N, C>>,
+    /// The ledger.
+    ledger: Ledger<N, C>,
+    /// The router of the node.
+    router: Router<N>,
+    /// The rate limiter of the REST server.
+    limiter: RateLimiter,
+    /// The requests being served by the REST server.
+    requests: RequestCounter,
+    /// The static bearer tokens accepted by the REST server.
+    auth_tokens: Arc<Vec<AuthToken>>,
+}
+
+impl<N: Network, C: 'static + ConsensusStorage<N>> GrpcServer<N, C> {
+    /// Initializes a new instance of the gRPC server.
+    pub fn new(
+        consensus: Option<Consensus<N, C>>,
+        ledger: Ledger<N, C>,
+        router: Router<N>,
+        limiter: RateLimiter,
+        requests: RequestCounter,
+        auth_tokens: Arc<Vec<AuthToken>>,
+    ) -> Self {
+        Self { consensus, ledger, router, limiter, requests, auth_tokens }
+    }
+
+    /// Serves the gRPC requests on the given IP address, until the server fails.
+    pub async fn serve(self, grpc_ip: SocketAddr) {
+        let limiter = self.limiter.clone();
+        let service = NodeServer::with_interceptor(self, move |request: Request<()>| {
+            check_rate(&limiter, request.remote_addr())?;
+            Ok(request)
+        });
+        if let Err(error) = Server::builder().add_service(service).serve(grpc_ip).await {
+            error!("The gRPC server failed - {error}");
+        }
+    }
+
+    /// Streams the blocks from the given height (or the next block), as they are added to the ledger,
+    /// or as they are finalized if `finalized` is set.
+    fn subscribe(&self, start_height: Option<u32>, finalized: bool) -> Result<Response<BlockStream>, Status> {
+        let consensus = match &self.consensus {
+            Some(consensus) => consensus,
+            None => return Err(Status::unimplemented("Block subscriptions require the consensus module")),
+        };
+        // Subscribe before choosing the start height, so that no block is missed in between.
+        let subscriber = consensus.subscriptions().subscribe_blocks();
+        let tip_height = match finalized {
+            true => self.ledger.finalized_height(),
+            false => self.ledger.latest_height(),
+        };
+        let start_height = start_height.unwrap_or(tip_height + 1);
+
+        // Count the stream as a request being served, until it is dropped.
+        let request = self.requests.enter();
+        let blocks = block_stream(self.ledger.clone(), subscriber, start_height, finalized).map(move |block| {
+            let _request = &request;
+            match block {
+                Ok(block) => block_message(block),
+                Err(error) => Err(Status::internal(error.to_string())),
+            }
+        });
+        Ok(Response::new(Box::pin(blocks)))
+    }
+}
+
+/// Takes a token from the rate limiter for the given remote address, as the REST server does for each request.
+fn check_rate(limiter: &RateLimiter, remote: Option<SocketAddr>) -> Result<(), Status> {
+    match remote {
+        Some(remote) if !limiter.check_rate(remote.ip()) => Err(Status::resource_exhausted("Too many requests")),
+        _ => Ok(()),
+    }
+}
+
+/// Ensures the authorization metadata of the given request grants the given privilege, given the static bearer tokens,
+/// and records each privileged call in the audit log, as the REST server does for its protected endpoints.
+fn ensure_privilege<T>(
+    auth_tokens: &[AuthToken],
+    request: &Request<T>,
+    method: &str,
+    privilege: Privilege,
+) -> Result<(), Status> {
+    let header = request.metadata().get("authorization").and_then(|value| value.to_str().ok());
+    match authorize(auth_tokens, header, privilege) {
+        Ok(caller) => {
+            info!("[audit] '{caller}' called gRPC {method} ({privilege})");
+            Ok(())
+        }
+        Err(error) => {
+            warn!("[audit] Denied gRPC {method} ({privilege}) - {error}");
+            Err(Status::unauthenticated(error.to_string()))
+        }
+    }
+}
+
+/// Converts the given signed block hash to its gRPC message.
+fn signed_block_hash_message<N: Network>(signed: SignedBlockHash<N>) -> proto::SignedBlockHash {
+    proto::SignedBlockHash {
+        height: signed.height,
+        hash: signed.hash.to_string(),
+        signature: signed.signature.to_string(),
+    }
+}
+
+/// Converts the given block to its gRPC message.
+fn block_message<N: Network>(block: Block<N>) -> Result<proto::Block, Status> {
+    Ok(proto::Block {
+        height: block.height(),
+        hash: block.hash().to_string(),
+        block: block.to_bytes_le().map_err(|error| Status::internal(error.to_string()))?,
+    })
+}
+
+#[tonic::async_trait]
+impl<N: Network, C: 'static + ConsensusStorage<N>> Node for GrpcServer<N, C> {
+    type SubscribeBlocksStream = BlockStream;
+    type SubscribeFinalizedBlocksStream = BlockStream;
+
+    /// Returns the height of the latest block.
+    async fn get_latest_height(&self, _: Request<proto::Empty>) -> Result<Response<proto::Height>, Status> {
+        Ok(Response::new(proto::Height { height: self.ledger.latest_height() }))
+    }
+
+    /// Returns the block at the given height.
+    async fn get_block(&self, request: Request<proto::GetBlockRequest>) -> Result<Response<proto::Block>, Status> {
+        let height = request.into_inner().height;
+        let block = self.ledger.get_block(height).map_err(|_| Status::not_found(format!("Missing block {height}")))?;
+        Ok(Response::new(block_message(block)?))
+    }
+
+    /// Returns the block with the given block hash.
+    async fn get_block_by_hash(
+        &self,
+        request: Request<proto::GetBlockByHashRequest>,
+    ) -> Result<Response<proto::Block>, Status> {
+        let block_hash = N::BlockHash::from_str(&request.into_inner().block_hash)
+            .map_err(|_| Status::invalid_argument("Invalid block hash"))?;
+        let block = self
+            .ledger
+            .get_block_by_hash(&block_hash)
+            .map_err(|_| Status::not_found(format!("Missing block '{block_hash}'")))?;
+        Ok(Response::new(block_message(block)?))
+    }
+
+    /// Returns the block header at the given height, with a proof of its inclusion in the latest state root.
+    async fn get_header_proof(
+        &self,
+        request: Request<proto::GetBlockRequest>,
+    ) -> Result<Response<proto::HeaderProof>, Status> {
+        let height = request.into_inner().height;
+        // Construct the proof on a blocking thread, as it reads the block from storage.
+        let ledger = self.ledger.clone();
+        let proof = tokio::task::spawn_blocking(move || ledger.get_header_proof(height))
+            .await
+            .map_err(|error| Status::internal(error.to_string()))?
+            .map_err(|_| Status::not_found(format!("Missing block {height}")))?;
+        Ok(Response::new(proto::HeaderProof {
+            state_root: proof.state_root.to_string(),
+            height: proof.block_height,
+            block_hash: proof.block_hash.to_string(),
+            previous_hash: proof.previous_hash.to_string(),
+            header: proof.header.to_bytes_le().map_err(|error| Status::internal(error.to_string()))?,
+            block_path: proof.block_path.to_bytes_le().map_err(|error| Status::internal(error.to_string()))?,
+        }))
+    }
+
+    /// Returns the transaction with the given transaction ID.
+    async fn get_transaction(
+        &self,
+        request: Request<proto::GetTransactionRequest>,
+    ) -> Result<Response<proto::Transaction>, Status> {
+        let transaction_id = N::TransactionID::from_str(&request.into_inner().transaction_id)
+            .map_err(|_| Status::invalid_argument("Invalid transaction ID"))?;
+        let transaction = self
+            .ledger
+            .get_transaction(transaction_id)
+            .map_err(|_| Status::not_found(format!("Missing transaction '{transaction_id}'")))?;
+        Ok(Response::new(proto::Transaction {
+            transaction_id: transaction_id.to_string(),
+            transaction: transaction.to_bytes_le().map_err(|error| Status::internal(error.to_string()))?,
+        }))
+    }
+
+    /// Returns the connected peers, to callers with the `peers` privilege.
+    async fn get_peers(&self, request: Request<proto::Empty>) -> Result<Response<proto::Peers>, Status> {
+        ensure_privilege(&self.auth_tokens, &request, "GetPeers", Privilege::Peers)?;
+        let peers = self
+            .router
+            .connected_peer_info()
+            .into_iter()
+            .map(|peer| proto::Peer {
+                ip: peer.ip.to_string(),
+                direction: match peer.direction {
+                    ConnectionDirection::Inbound => "inbound".to_string(),
+                    ConnectionDirection::Outbound => "outbound".to_string(),
+                },
+                node_type: peer.node_type.to_string(),
+                version: peer.version,
+                last_seen_secs: peer.last_seen_secs,
+            })
+            .collect();
+        Ok(Response::new(proto::Peers { peers }))
+    }
+
+    /// Returns a page of the evidence of the detected equivocations, optionally for the given signer.
+    async fn get_equivocation_evidence(
+        &self,
+        request: Request<proto::GetEquivocationEvidenceRequest>,
+    ) -> Result<Response<proto::EquivocationEvidenceList>, Status> {
+        const MAX_PAGE_LIMIT: usize = 1000;
+
+        let consensus = match &self.consensus {
+            Some(consensus) => consensus,
+            None => return Err(Status::unimplemented("Equivocation evidence requires the consensus module")),
+        };
+        let request = request.into_inner();
+        let signer = match request.signer {
+            Some(signer) => {
+                Some(Address::<N>::from_str(&signer).map_err(|_| Status::invalid_argument("Invalid signer"))?)
+            }
+            None => None,
+        };
+        let limit = request.limit.unwrap_or(100) as usize;
+        if limit > MAX_PAGE_LIMIT {
+            return Err(Status::invalid_argument(format!(
+                "Cannot request more than {MAX_PAGE_LIMIT} equivocations per call (requested {limit})"
+            )));
+        }
+        let evidence = consensus
+            .equivocations()
+            .evidence(signer, request.offset as usize, limit)
+            .into_iter()
+            .map(|evidence| proto::EquivocationEvidence {
+                signer: evidence.signer.to_string(),
+                round: evidence.round,
+                first: Some(signed_block_hash_message(evidence.first)),
+                second: Some(signed_block_hash_message(evidence.second)),
+                detected_at: evidence.detected_at,
+            })
+            .collect();
+        Ok(Response::new(proto::EquivocationEvidenceList { evidence }))
+    }
+
+    /// Streams the blocks from the given height (or the next block), as they are added to the ledger.
+    async fn subscribe_blocks(
+        &self,
+        request: Request<proto::SubscribeBlocksRequest>,
+    ) -> Result<Response<Self::SubscribeBlocksStream>, Status> {
+        self.subscribe(request.into_inner().start_height, false)
+    }
+
+    /// Streams the finalized blocks from the given height (or the next finalized block), in order and without gaps.
+    async fn subscribe_finalized_blocks(
+        &self,
+        request: Request<proto::SubscribeBlocksRequest>,
+    ) -> Result<Response<Self::SubscribeFinalizedBlocksStream>, Status> {
+        self.subscribe(request.into_inner().start_height, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Claims, RestLimits};
+    use snarkos_account::Account;
+    use snarkos_node_messages::NodeType;
+    use snarkvm::{
+        prelude::{FromBytes, TestRng, Testnet3},
+        synthesizer::ConsensusMemory,
+    };
+
+    use std::net::{IpAddr, Ipv4Addr};
+
+    type CurrentNetwork = Testnet3;
+    type CurrentServer = GrpcServer<CurrentNetwork, ConsensusMemory<CurrentNetwork>>;
+
+    /// Initializes a gRPC server over a ledger with the genesis block, with the given rate limits.
+    async fn sample_server(rng: &mut TestRng, limits: RestLimits) -> CurrentServer {
+        let genesis = Block::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let ledger = Ledger::load(genesis, None).unwrap();
+        let consensus = Consensus::new(ledger.clone(), true).unwrap();
+        let node_ip = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
+        let router = Router::new(node_ip, NodeType::Client, Account::new(rng).unwrap(), &[], 10, true).await.unwrap();
+        GrpcServer::new(
+            Some(consensus),
+            ledger,
+            router,
+            RateLimiter::new(limits),
+            Default::default(),
+            Default::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_grpc_queries() {
+        let rng = &mut TestRng::default();
+        let server = sample_server(rng, RestLimits::default()).await;
+        let genesis = server.ledger.get_block(0).unwrap();
+
+        // Ensure the latest height and the genesis block are served.
+        let height = server.get_latest_height(Request::new(proto::Empty {})).await.unwrap().into_inner();
+        assert_eq!(height.height, 0);
+        let block = server.get_block(Request::new(proto::GetBlockRequest { height: 0 })).await.unwrap().into_inner();
+        assert_eq!(block.hash, genesis.hash().to_string());
+        assert_eq!(Block::<CurrentNetwork>::from_bytes_le(&block.block).unwrap(), genesis);
+
+        // Ensure the block is served by its hash.
+        let request = proto::GetBlockByHashRequest { block_hash: genesis.hash().to_string() };
+        let block = server.get_block_by_hash(Request::new(request)).await.unwrap().into_inner();
+        assert_eq!(block.height, 0);
+
+        // Ensure missing blocks and malformed hashes are rejected with the matching status.
+        let error = server.get_block(Request::new(proto::GetBlockRequest { height: 1 })).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::NotFound);
+        let request = proto::GetBlockByHashRequest { block_hash: "invalid".to_string() };
+        let error = server.get_block_by_hash(Request::new(request)).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_grpc_auth() {
+        let rng = &mut TestRng::default();
+        let server = sample_server(rng, RestLimits::default()).await;
+
+        // Ensure the peers are not served without a token.
+        let error = server.get_peers(Request::new(proto::Empty {})).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::Unauthenticated);
+
+        // Ensure the peers are served with the JSON web token of the node.
+        let address = server.router.address();
+        let jwt = Claims::new(address).to_jwt_string().unwrap();
+        let mut request = Request::new(proto::Empty {});
+        request.metadata_mut().insert("authorization", format!("Bearer {jwt}").parse().unwrap());
+        assert!(server.get_peers(request).await.unwrap().into_inner().peers.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_grpc_block_streams() {
+        let rng = &mut TestRng::default();
+        let server = sample_server(rng, RestLimits::default()).await;
+        let genesis = server.ledger.get_block(0).unwrap();
+
+        // Ensure both streams start from the requested height, and are counted as requests being served.
+        let request = proto::SubscribeBlocksRequest { start_height: Some(0) };
+        let mut blocks = server.subscribe_blocks(Request::new(request)).await.unwrap().into_inner();
+        let request = proto::SubscribeBlocksRequest { start_height: Some(0) };
+        let mut finalized_blocks = server.subscribe_finalized_blocks(Request::new(request)).await.unwrap().into_inner();
+        assert_eq!(server.requests.in_flight(), 2);
+        assert_eq!(blocks.next().await.unwrap().unwrap().hash, genesis.hash().to_string());
+        assert_eq!(finalized_blocks.next().await.unwrap().unwrap().hash, genesis.hash().to_string());
+
+        // Ensure the streams wait for the next block, instead of ending.
+        let next = tokio::time::timeout(std::time::Duration::from_millis(100), blocks.next()).await;
+        assert!(next.is_err());
+
+        // Ensure a dropped stream is no longer counted.
+        drop(blocks);
+        drop(finalized_blocks);
+        assert_eq!(server.requests.in_flight(), 0);
+        assert_eq!(server.requests.served(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_grpc_block_streams_without_consensus() {
+        let rng = &mut TestRng::default();
+        let mut server = sample_server(rng, RestLimits::default()).await;
+        server.consensus = None;
+
+        // Ensure the streams are unavailable without the consensus module.
+        let request = proto::SubscribeBlocksRequest { start_height: None };
+        let error = server.subscribe_finalized_blocks(Request::new(request)).await.err().unwrap();
+        assert_eq!(error.code(), tonic::Code::Unimplemented);
+        assert_eq!(server.requests.in_flight(), 0);
+    }
+
+    #[test]
+    fn test_grpc_rate_limit() {
+        let limiter = RateLimiter::new(RestLimits { requests_per_sec: Some(1), burst: 1, max_body_bytes: 1024 });
+        let remote = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 4130);
+
+        // Ensure the remote address may burst, and is then rate limited.
+        assert!(check_rate(&limiter, Some(remote)).is_ok());
+        assert!(check_rate(&limiter, Some(remote)).is_ok());
+        assert_eq!(check_rate(&limiter, Some(remote)).unwrap_err().code(), tonic::Code::ResourceExhausted);
+        // Ensure the budget is shared with the REST requests from the same IP.
+        assert!(!limiter.check_rate(remote.ip()));
+        assert_eq!(limiter.num_rate_limited(), 2);
+    }
+}
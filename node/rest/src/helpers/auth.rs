@@ -19,11 +19,12 @@ use crate::RestError;
 use snarkvm::prelude::*;
 
 use ::time::OffsetDateTime;
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, ensure, Result};
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
-use warp::{reject, Filter, Rejection};
+use std::sync::Arc;
+use warp::{http::Method, path::FullPath, reject, Filter, Rejection};
 
 /// The time a jwt token is valid for.
 pub const EXPIRATION: i64 = 10 * 365 * 24 * 60 * 60; // 10 years.
@@ -37,6 +38,94 @@ fn jwt_secret() -> &'static Vec<u8> {
     })
 }
 
+/// A privilege required to call a protected endpoint.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Privilege {
+    /// Broadcasting transactions with an inclusion deadline.
+    Broadcast,
+    /// Submitting and managing proving jobs.
+    Proving,
+    /// Connecting to and disconnecting from peers.
+    Peers,
+    /// Managing the watch list.
+    WatchList,
+    /// Changing the operating mode of the node.
+    Admin,
+    /// Reading the transaction history of a view key, or querying the secondary indexes of the ledger.
+    History,
+    /// Submitting transaction bundles as an external block builder.
+    Builder,
+}
+
+impl Privilege {
+    /// The privileges granted to the JSON web token of the node.
+    /// The `history` privilege, which exposes the view keys of its callers, is only granted to a token that lists it.
+    pub const ALL: [Self; 6] =
+        [Self::Broadcast, Self::Proving, Self::Peers, Self::WatchList, Self::Admin, Self::Builder];
+    /// The privileges granted to a token without an explicit list.
+    /// The `admin`, `history`, and `builder` privileges are never granted by default, and must be listed explicitly.
+    pub const DEFAULT: [Self; 4] = [Self::Broadcast, Self::Proving, Self::Peers, Self::WatchList];
+}
+
+impl FromStr for Privilege {
+    type Err = anyhow::Error;
+
+    fn from_str(privilege: &str) -> Result<Self> {
+        match privilege {
+            "broadcast" => Ok(Self::Broadcast),
+            "proving" => Ok(Self::Proving),
+            "peers" => Ok(Self::Peers),
+            "watchList" => Ok(Self::WatchList),
+            "admin" => Ok(Self::Admin),
+            "history" => Ok(Self::History),
+            "builder" => Ok(Self::Builder),
+            _ => bail!(
+                "Unknown privilege '{privilege}' (expected 'broadcast', 'proving', 'peers', 'watchList', 'admin', 'history', or 'builder')"
+            ),
+        }
+    }
+}
+
+impl Display for Privilege {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Broadcast => write!(f, "broadcast"),
+            Self::Proving => write!(f, "proving"),
+            Self::Peers => write!(f, "peers"),
+            Self::WatchList => write!(f, "watchList"),
+            Self::Admin => write!(f, "admin"),
+            Self::History => write!(f, "history"),
+            Self::Builder => write!(f, "builder"),
+        }
+    }
+}
+
+/// A static bearer token, with the privileges it grants.
+#[derive(Clone)]
+pub struct AuthToken {
+    /// The token.
+    token: String,
+    /// The privileges granted by the token.
+    privileges: Vec<Privilege>,
+}
+
+impl FromStr for AuthToken {
+    type Err = anyhow::Error;
+
+    /// Parses a token of the form `TOKEN`, which grants the default privileges,
+    /// or `TOKEN:privilege,...`, which grants the listed privileges.
+    fn from_str(token: &str) -> Result<Self> {
+        let (token, privileges) = match token.split_once(':') {
+            Some((token, privileges)) => {
+                (token, privileges.split(',').map(Privilege::from_str).collect::<Result<Vec<_>>>()?)
+            }
+            None => (token, Privilege::DEFAULT.to_vec()),
+        };
+        ensure!(token.len() >= 16, "The REST authentication token must be at least 16 characters long");
+        Ok(Self { token: token.to_string(), privileges })
+    }
+}
+
 /// The Json web token claims.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Claims {
@@ -67,28 +156,176 @@ impl Claims {
     }
 }
 
-/// Checks the authorization header for a valid token.
-pub fn with_auth() -> impl Filter<Extract = ((),), Error = Rejection> + Clone {
-    warp::header::<String>("authorization").and_then(|token: String| async move {
-        if !token.starts_with("Bearer ") {
-            return Err(reject::custom(RestError::Request("Invalid authorization header.".to_string())));
-        }
+/// The marker of the requests received over the IPC socket, which only the IPC server inserts into a request.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct IpcConnection;
 
-        // Decode the claims from the token.
-        match decode::<Claims>(
-            token.trim_start_matches("Bearer "),
-            &DecodingKey::from_secret(jwt_secret()),
-            &Validation::new(Algorithm::HS256),
-        ) {
-            Ok(decoded) => {
-                let claims = decoded.claims;
-                if claims.is_expired() {
-                    return Err(reject::custom(RestError::Request("Expired JSON Web Token.".to_string())));
+/// Checks the authorization header for the JSON web token of the node, or one of the given static tokens,
+/// that grants the given privilege, and records each privileged call in the audit log.
+///
+/// Requests over the IPC socket are authorized by its file permissions, and are recognized by the marker
+/// that the IPC server inserts. Every other request requires a token, even if it has no remote address.
+pub fn with_auth(
+    auth_tokens: Arc<Vec<AuthToken>>,
+    privilege: Privilege,
+) -> impl Filter<Extract = ((),), Error = Rejection> + Clone {
+    with_caller(auth_tokens, privilege).map(|_| ())
+}
+
+/// Checks the authorization header for a token that grants the given privilege, as `with_auth` does,
+/// and returns the caller: the address of the JSON web token, the index of the static token, or `ipc`.
+pub fn with_caller(
+    auth_tokens: Arc<Vec<AuthToken>>,
+    privilege: Privilege,
+) -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    warp::method()
+        .and(warp::path::full())
+        .and(warp::ext::optional::<IpcConnection>())
+        .and(warp::header::optional::<String>("authorization"))
+        .and_then(move |method: Method, path: FullPath, ipc: Option<IpcConnection>, header: Option<String>| {
+            let caller = match ipc {
+                Some(IpcConnection) => Ok("ipc".to_string()),
+                None => authorize(&auth_tokens, header.as_deref(), privilege),
+            };
+            async move {
+                match caller {
+                    Ok(caller) => {
+                        info!("[audit] '{caller}' called {method} {} ({privilege})", path.as_str());
+                        Ok(caller)
+                    }
+                    Err(error) => {
+                        warn!("[audit] Denied {method} {} ({privilege}) - {error}", path.as_str());
+                        Err(reject::custom(RestError::Unauthorized(error.to_string())))
+                    }
                 }
+            }
+        })
+}
+
+/// Returns the caller of the given authorization header, if it grants the given privilege,
+/// given the static bearer tokens.
+pub(crate) fn authorize(auth_tokens: &[AuthToken], header: Option<&str>, privilege: Privilege) -> Result<String> {
+    let token = match header.and_then(|header| header.strip_prefix("Bearer ")) {
+        Some(token) => token,
+        None => bail!("Invalid authorization header."),
+    };
+
+    // Check the static bearer tokens.
+    for (index, auth_token) in auth_tokens.iter().enumerate() {
+        if constant_time_eq(token.as_bytes(), auth_token.token.as_bytes()) {
+            ensure!(
+                auth_token.privileges.contains(&privilege),
+                "The token does not grant the '{privilege}' privilege."
+            );
+            return Ok(format!("token #{index}"));
+        }
+    }
 
-                Ok(())
+    // Decode the claims from the JSON web token, which grants the privileges in `Privilege::ALL`.
+    ensure!(Privilege::ALL.contains(&privilege), "The JSON web token does not grant the '{privilege}' privilege.");
+    match decode::<Claims>(token, &DecodingKey::from_secret(jwt_secret()), &Validation::new(Algorithm::HS256)) {
+        Ok(decoded) => {
+            let claims = decoded.claims;
+            if claims.is_expired() {
+                bail!("Expired JSON Web Token.");
             }
-            Err(_) => Err(reject::custom(RestError::Request("Unauthorized caller.".to_string()))),
+            Ok(claims.sub)
         }
-    })
+        Err(_) => bail!("Unauthorized caller."),
+    }
+}
+
+/// Returns `true` if the given byte strings are equal, in time independent of their contents.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+
+    #[test]
+    fn test_auth_token_privileges() {
+        // Ensure a token without a list grants the default privileges, which exclude the explicit privileges.
+        let token = AuthToken::from_str("0123456789abcdef").unwrap();
+        assert_eq!(token.privileges, Privilege::DEFAULT.to_vec());
+        for privilege in [Privilege::Admin, Privilege::History, Privilege::Builder] {
+            assert!(!token.privileges.contains(&privilege));
+        }
+
+        // Ensure a token with a list grants only the listed privileges.
+        let token = AuthToken::from_str("0123456789abcdef:peers,watchList").unwrap();
+        assert_eq!(token.token, "0123456789abcdef");
+        assert_eq!(token.privileges, vec![Privilege::Peers, Privilege::WatchList]);
+
+        // Ensure the admin privilege is granted when listed explicitly.
+        let token = AuthToken::from_str("0123456789abcdef:admin").unwrap();
+        assert_eq!(token.privileges, vec![Privilege::Admin]);
+
+        // Ensure short tokens and unknown privileges are rejected.
+        assert!(AuthToken::from_str("short").is_err());
+        assert!(AuthToken::from_str("0123456789abcdef:root").is_err());
+    }
+
+    #[test]
+    fn test_authorize_tokens() {
+        let tokens =
+            [AuthToken::from_str("0123456789abcdef").unwrap(), AuthToken::from_str("fedcba9876543210:admin").unwrap()];
+
+        // Ensure the default token is denied the admin privilege, and the explicit token is granted it.
+        let default = Some("Bearer 0123456789abcdef");
+        assert_eq!(authorize(&tokens, default, Privilege::Peers).unwrap(), "token #0");
+        assert!(authorize(&tokens, default, Privilege::Admin).is_err());
+        let admin = Some("Bearer fedcba9876543210");
+        assert_eq!(authorize(&tokens, admin, Privilege::Admin).unwrap(), "token #1");
+        assert!(authorize(&tokens, admin, Privilege::Peers).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_with_auth() {
+        let filter = with_auth(Default::default(), Privilege::Admin);
+        let remote: SocketAddr = "203.0.113.1:4130".parse().unwrap();
+
+        // Ensure requests over TCP are rejected without a token.
+        assert!(!warp::test::request().remote_addr(remote).matches(&filter).await);
+        let request = warp::test::request().remote_addr(remote).header("authorization", "Bearer 0123456789abcdef");
+        assert!(!request.matches(&filter).await);
+
+        // Ensure requests over TCP are accepted with the JSON web token of the node.
+        let rng = &mut TestRng::default();
+        let address = Address::<Testnet3>::try_from(PrivateKey::<Testnet3>::new(rng).unwrap()).unwrap();
+        let jwt = Claims::new(address).to_jwt_string().unwrap();
+        let request = warp::test::request().remote_addr(remote).header("authorization", format!("Bearer {jwt}"));
+        assert!(request.matches(&filter).await);
+
+        // Ensure requests without a remote address are rejected, unless they are marked by the IPC server.
+        assert!(!warp::test::request().matches(&filter).await);
+        assert!(warp::test::request().extension(IpcConnection).matches(&filter).await);
+    }
+
+    #[test]
+    fn test_authorize_jwt() {
+        let rng = &mut TestRng::default();
+        let address = Address::<Testnet3>::try_from(PrivateKey::<Testnet3>::new(rng).unwrap()).unwrap();
+        let jwt = Claims::new(address).to_jwt_string().unwrap();
+
+        // Ensure the JSON web token grants its privileges, except the history privilege.
+        for privilege in Privilege::ALL {
+            assert_eq!(authorize(&[], Some(&format!("Bearer {jwt}")), privilege).unwrap(), address.to_string());
+        }
+        assert!(authorize(&[], Some(&format!("Bearer {jwt}")), Privilege::History).is_err());
+
+        // Ensure malformed and unknown tokens are rejected.
+        assert!(authorize(&[], None, Privilege::Peers).is_err());
+        assert!(authorize(&[], Some(&jwt), Privilege::Peers).is_err());
+        assert!(authorize(&[], Some("Bearer 0123456789abcdef"), Privilege::Peers).is_err());
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"token", b"token"));
+        assert!(!constant_time_eq(b"token", b"tokem"));
+        assert!(!constant_time_eq(b"token", b"token2"));
+    }
 }
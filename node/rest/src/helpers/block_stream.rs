@@ -0,0 +1,62 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkos_node_consensus::BlockNotification;
+use snarkos_node_ledger::Ledger;
+use snarkvm::prelude::{Block, ConsensusStorage, Network};
+
+use anyhow::Result;
+use futures_util::{stream, Stream};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// Streams the blocks of the ledger from the given height, in order and without gaps, as they are added to the ledger,
+/// or as they are finalized if `finalized` is set. Blocks missed by a lagging subscriber are read back from the ledger,
+/// so a client can resume the stream exactly where it left off by restarting it from the height after its last block.
+///
+/// The subscriber must be created before the start height is chosen, so that no block is missed in between.
+pub fn block_stream<N: Network, C: ConsensusStorage<N>>(
+    ledger: Ledger<N, C>,
+    subscriber: broadcast::Receiver<BlockNotification<N>>,
+    start_height: u32,
+    finalized: bool,
+) -> impl Stream<Item = Result<Block<N>>> {
+    stream::unfold((subscriber, Some(start_height)), move |(mut subscriber, next_height)| {
+        let ledger = ledger.clone();
+        async move {
+            // Stop after the stream failed.
+            let height = next_height?;
+            loop {
+                // Retrieve the tip of the ledger.
+                let tip_height = match finalized {
+                    true => ledger.finalized_height(),
+                    false => ledger.latest_height(),
+                };
+                // Send the next block, once the tip has reached it.
+                if height <= tip_height {
+                    return match ledger.get_block(height) {
+                        Ok(block) => Some((Ok(block), (subscriber, Some(height + 1)))),
+                        Err(error) => Some((Err(error), (subscriber, None))),
+                    };
+                }
+                // Wait for the next block to be added to the ledger.
+                match subscriber.recv().await {
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,157 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::RateLimiter;
+use snarkos_node_consensus::{PipelineMetrics, PipelineStage, PIPELINE_QUEUE_CAPACITY};
+
+use serde::Serialize;
+use std::sync::{
+    atomic::{AtomicU64, AtomicUsize, Ordering},
+    Arc,
+};
+
+/// The number of concurrent requests at which the server is considered fully loaded.
+const NOMINAL_IN_FLIGHT_REQUESTS: usize = 256;
+/// The number of queued proving jobs at which the prover is considered fully loaded.
+const NOMINAL_QUEUED_PROVING_JOBS: usize = 16;
+
+/// Tracks the requests being served by the REST server.
+#[derive(Clone, Debug, Default)]
+pub struct RequestCounter {
+    /// The number of requests being served.
+    in_flight: Arc<AtomicUsize>,
+    /// The number of requests served since startup.
+    served: Arc<AtomicU64>,
+}
+
+impl RequestCounter {
+    /// Records the start of a request, which ends when the returned guard is dropped.
+    pub fn enter(&self) -> InFlightRequest {
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        InFlightRequest(self.clone())
+    }
+
+    /// Returns the number of requests being served.
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of requests served since startup.
+    pub fn served(&self) -> u64 {
+        self.served.load(Ordering::Relaxed)
+    }
+}
+
+/// A request being served by the REST server.
+pub struct InFlightRequest(RequestCounter);
+
+impl Drop for InFlightRequest {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::Relaxed);
+        self.0.served.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// A report of the current load on the node, for load balancers fronting several nodes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct NodeCapacity {
+    /// The number of requests being served.
+    pub in_flight_requests: usize,
+    /// The number of requests served since startup.
+    pub served_requests: u64,
+    /// The number of requests rejected for exceeding the rate limit.
+    pub rate_limited_requests: u64,
+    /// The number of requests rejected for exceeding the maximum body size.
+    pub oversized_requests: u64,
+    /// The number of proving jobs waiting for the prover.
+    pub queued_proving_jobs: usize,
+    /// The number of blocks waiting in the block pipeline.
+    pub verification_backlog: usize,
+    /// The pipeline stage where the backpressure originates, if any.
+    pub backpressure: Option<PipelineStage>,
+    /// The number of unconfirmed transactions in the memory pool.
+    pub unconfirmed_transactions: usize,
+    /// The overall load, from 0 (idle) to 100 (saturated).
+    pub load_score: u8,
+}
+
+impl NodeCapacity {
+    /// Initializes the capacity report. The load score is the utilization of the busiest resource,
+    /// and is saturated whenever the block pipeline applies backpressure.
+    pub fn new(
+        requests: &RequestCounter,
+        limiter: &RateLimiter,
+        queued_proving_jobs: usize,
+        pipeline: Option<&PipelineMetrics>,
+        unconfirmed_transactions: usize,
+    ) -> Self {
+        let in_flight_requests = requests.in_flight();
+        let verification_backlog =
+            pipeline.map_or(0, |pipeline| pipeline.snapshot().iter().map(|stage| stage.queued).sum());
+        let backpressure = pipeline.and_then(|pipeline| pipeline.backpressure());
+
+        // Compute the utilization of each resource, as a percentage.
+        let utilization = [
+            in_flight_requests * 100 / NOMINAL_IN_FLIGHT_REQUESTS,
+            queued_proving_jobs * 100 / NOMINAL_QUEUED_PROVING_JOBS,
+            verification_backlog * 100 / (PIPELINE_QUEUE_CAPACITY * PipelineStage::ALL.len()),
+        ];
+        let load_score = match backpressure {
+            Some(_) => 100,
+            None => utilization.into_iter().max().unwrap_or(0).min(100) as u8,
+        };
+
+        Self {
+            in_flight_requests,
+            served_requests: requests.served(),
+            rate_limited_requests: limiter.num_rate_limited(),
+            oversized_requests: limiter.num_oversized(),
+            queued_proving_jobs,
+            verification_backlog,
+            backpressure,
+            unconfirmed_transactions,
+            load_score,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_node_capacity() {
+        let requests = RequestCounter::default();
+        let limiter = RateLimiter::default();
+
+        // Ensure an idle node reports no load.
+        let capacity = NodeCapacity::new(&requests, &limiter, 0, None, 0);
+        assert_eq!(capacity.load_score, 0);
+
+        // Ensure in-flight requests are counted until they finish.
+        let guards: Vec<_> = (0..NOMINAL_IN_FLIGHT_REQUESTS / 2).map(|_| requests.enter()).collect();
+        let capacity = NodeCapacity::new(&requests, &limiter, 0, None, 0);
+        assert_eq!(capacity.in_flight_requests, NOMINAL_IN_FLIGHT_REQUESTS / 2);
+        assert_eq!(capacity.load_score, 50);
+        drop(guards);
+        assert_eq!(requests.in_flight(), 0);
+        assert_eq!(requests.served(), NOMINAL_IN_FLIGHT_REQUESTS as u64 / 2);
+
+        // Ensure the load score reflects the busiest resource, and is capped.
+        let capacity = NodeCapacity::new(&requests, &limiter, NOMINAL_QUEUED_PROVING_JOBS * 2, None, 0);
+        assert_eq!(capacity.load_score, 100);
+    }
+}
@@ -0,0 +1,79 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+#[cfg(feature = "tls")]
+use crate::TlsConfig;
+use crate::{AuthToken, FaucetConfig, JsonMode, ReadinessThresholds, RestLimits, RestProxy};
+
+use anyhow::{bail, Result};
+#[cfg(feature = "grpc")]
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+/// The configuration of the REST server, which is given to `Rest::start`.
+#[derive(Clone, Default)]
+pub struct RestConfig {
+    /// The static bearer tokens accepted in addition to the JSON web token of the node.
+    pub auth_tokens: Vec<AuthToken>,
+    /// The limits applied to every request.
+    pub limits: RestLimits,
+    /// The CORS and reverse-proxy configuration.
+    pub proxy: RestProxy,
+    /// The handling of the fields outside the canonical representation of a JSON request body.
+    pub json_mode: JsonMode,
+    /// The thresholds that the node must meet to be ready to serve traffic.
+    pub readiness: ReadinessThresholds,
+    /// Whether the profiling endpoints are enabled.
+    pub profiling: bool,
+    /// The private key of the account that proves the jobs and pays their fees, which enables the proving jobs.
+    /// The account should be dedicated to proving, and hold only the funds for the fees of the jobs.
+    pub proving_key: Option<String>,
+    /// The path of the file the proving jobs are persisted to, or `None` to keep them in memory only.
+    pub proving_jobs_path: Option<PathBuf>,
+    /// The configuration of the faucet, which is only served on development networks.
+    pub faucet: Option<FaucetConfig>,
+    /// The path of the Unix domain socket on which the REST server is also served, if any.
+    #[cfg(unix)]
+    pub ipc_path: Option<PathBuf>,
+    /// The IP address of the gRPC server, which is served alongside the REST server, if any.
+    #[cfg(feature = "grpc")]
+    pub grpc_ip: Option<SocketAddr>,
+    /// The TLS configuration, which serves the REST server over HTTPS, if any.
+    #[cfg(feature = "tls")]
+    pub tls: Option<TlsConfig>,
+}
+
+impl RestConfig {
+    /// Ensures the configuration is well-formed.
+    pub fn check(&self) -> Result<()> {
+        self.proxy.check()?;
+        if let Some(faucet) = &self.faucet {
+            if faucet.amount == 0 {
+                bail!("The faucet amount must be greater than zero");
+            }
+        }
+        #[cfg(feature = "tls")]
+        if let Some(tls) = &self.tls {
+            tls.check()?;
+        }
+        // The gRPC server does not terminate TLS, so it would serve the same data in plaintext.
+        #[cfg(all(feature = "grpc", feature = "tls"))]
+        if self.grpc_ip.is_some() && self.tls.is_some() {
+            bail!("The gRPC server does not support TLS, and cannot be enabled together with TLS");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,263 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkos_node_ledger::Ledger;
+use snarkvm::prelude::{ConsensusStorage, Network, Transaction};
+
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// The maximum number of submissions tracked at once.
+const MAX_DEADLINE_SUBMISSIONS: usize = 4096;
+/// The maximum number of deadline events buffered for a lagging subscriber.
+const DEADLINE_EVENT_CAPACITY: usize = 64;
+/// The number of blocks before the deadline, within which a pending transaction is rebroadcast on every check.
+const URGENT_WINDOW_IN_BLOCKS: u32 = 5;
+
+/// The status of a transaction submitted with an inclusion deadline.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeadlineStatus {
+    /// The transaction is awaiting inclusion.
+    Pending,
+    /// The transaction was included at the given block height.
+    Included(u32),
+    /// The deadline passed before the transaction was included.
+    Expired,
+}
+
+impl DeadlineStatus {
+    /// Returns the status of a submission with the given deadline, from the height of the block
+    /// that included the transaction (if any), and the latest height of the ledger.
+    fn resolve(max_height: u32, included_height: Option<u32>, latest_height: u32) -> Self {
+        match included_height {
+            // The transaction was included by its deadline.
+            Some(height) if height <= max_height => Self::Included(height),
+            // The transaction was included after its deadline, which counts as a miss.
+            Some(_) => Self::Expired,
+            // The block at the deadline was produced without the transaction.
+            None if latest_height >= max_height => Self::Expired,
+            None => Self::Pending,
+        }
+    }
+}
+
+/// Returns `true` if a pending transaction is due to be rebroadcast at the given latest height.
+fn is_rebroadcast_due(max_height: u32, last_broadcast_height: u32, latest_height: u32) -> bool {
+    let is_urgent = max_height.saturating_sub(latest_height) <= URGENT_WINDOW_IN_BLOCKS;
+    is_urgent || latest_height > last_broadcast_height
+}
+
+/// A transaction submitted with an inclusion deadline.
+#[derive(Clone, Debug, Serialize)]
+pub struct DeadlineSubmission<N: Network> {
+    /// The transaction.
+    #[serde(skip)]
+    pub transaction: Transaction<N>,
+    /// The maximum block height at which the transaction may be included.
+    pub max_height: u32,
+    /// The status of the submission.
+    pub status: DeadlineStatus,
+    /// The number of times the transaction has been broadcast.
+    pub num_broadcasts: u32,
+    /// The block height at which the transaction was last broadcast.
+    pub last_broadcast_height: u32,
+}
+
+/// A notification that a submission was resolved as included or expired.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadlineEvent<N: Network> {
+    /// The transaction ID.
+    pub transaction_id: N::TransactionID,
+    /// The maximum block height at which the transaction may be included.
+    pub max_height: u32,
+    /// The resolved status of the submission.
+    pub status: DeadlineStatus,
+}
+
+/// The set of transactions submitted with an inclusion deadline.
+#[derive(Clone, Debug)]
+#[allow(clippy::type_complexity)]
+pub struct Deadlines<N: Network> {
+    /// The map of transaction IDs to their submissions.
+    submissions: Arc<RwLock<IndexMap<N::TransactionID, DeadlineSubmission<N>>>>,
+    /// The sender of the submissions resolved as included or expired.
+    events: broadcast::Sender<DeadlineEvent<N>>,
+}
+
+impl<N: Network> Default for Deadlines<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network> Deadlines<N> {
+    /// Initializes a new set of deadline submissions.
+    pub fn new() -> Self {
+        Self { submissions: Default::default(), events: broadcast::channel(DEADLINE_EVENT_CAPACITY).0 }
+    }
+
+    /// Returns a new subscriber to the submissions resolved as included or expired.
+    pub fn subscribe(&self) -> broadcast::Receiver<DeadlineEvent<N>> {
+        self.events.subscribe()
+    }
+
+    /// Returns the submission for the given transaction ID, if it exists.
+    pub fn get(&self, transaction_id: &N::TransactionID) -> Option<DeadlineSubmission<N>> {
+        self.submissions.read().get(transaction_id).cloned()
+    }
+
+    /// Tracks the given transaction, which was broadcast at the given height, until the given deadline.
+    pub fn insert(&self, transaction: Transaction<N>, max_height: u32, latest_height: u32) {
+        let mut submissions = self.submissions.write();
+        // If the limit is reached, evict the oldest completed submission.
+        if submissions.len() >= MAX_DEADLINE_SUBMISSIONS {
+            if let Some(index) = submissions.values().position(|s| s.status != DeadlineStatus::Pending) {
+                submissions.shift_remove_index(index);
+            }
+        }
+        submissions.insert(transaction.id(), DeadlineSubmission {
+            transaction,
+            max_height,
+            status: DeadlineStatus::Pending,
+            num_broadcasts: 1,
+            last_broadcast_height: latest_height,
+        });
+    }
+
+    /// Returns `true` if the limit of tracked submissions has been reached.
+    pub fn is_full(&self) -> bool {
+        let submissions = self.submissions.read();
+        submissions.len() >= MAX_DEADLINE_SUBMISSIONS
+            && submissions.values().all(|submission| submission.status == DeadlineStatus::Pending)
+    }
+
+    /// Updates the status of every pending submission against the ledger,
+    /// and returns the transactions that are due to be rebroadcast.
+    ///
+    /// A pending transaction is rebroadcast once per new block, and on every check
+    /// once the deadline is within `URGENT_WINDOW_IN_BLOCKS` blocks.
+    /// The submissions resolved as included or expired are sent to the subscribers.
+    pub fn refresh<C: ConsensusStorage<N>>(&self, ledger: &Ledger<N, C>) -> Vec<Transaction<N>> {
+        // Retrieve the latest height.
+        let latest_height = ledger.latest_height();
+
+        // Snapshot the pending submissions, so the ledger is read without holding the lock.
+        let pending: Vec<(N::TransactionID, u32)> = self
+            .submissions
+            .read()
+            .iter()
+            .filter(|(_, submission)| submission.status == DeadlineStatus::Pending)
+            .map(|(transaction_id, submission)| (*transaction_id, submission.max_height))
+            .collect();
+
+        // Resolve the status of each pending submission against the ledger.
+        let statuses: Vec<(N::TransactionID, DeadlineStatus)> = pending
+            .into_iter()
+            .map(|(transaction_id, max_height)| {
+                // Check if the transaction was included.
+                let block_height = match ledger.find_block_hash(&transaction_id) {
+                    Ok(Some(block_hash)) => ledger.get_height(&block_hash).ok(),
+                    _ => None,
+                };
+                (transaction_id, DeadlineStatus::resolve(max_height, block_height, latest_height))
+            })
+            .collect();
+
+        let mut transactions = Vec::new();
+        let mut events = Vec::new();
+        let mut submissions = self.submissions.write();
+        for (transaction_id, status) in statuses {
+            // Skip submissions that were evicted in the meantime.
+            let submission = match submissions.get_mut(&transaction_id) {
+                Some(submission) if submission.status == DeadlineStatus::Pending => submission,
+                _ => continue,
+            };
+
+            match status {
+                // The transaction was included.
+                DeadlineStatus::Included(height) => {
+                    info!(
+                        "Transaction '{transaction_id}' was included at block {height} (deadline {})",
+                        submission.max_height
+                    );
+                }
+                // The deadline has passed.
+                DeadlineStatus::Expired => {
+                    warn!(
+                        "Transaction '{transaction_id}' expired at block {latest_height} (deadline {})",
+                        submission.max_height
+                    );
+                }
+                // The transaction is still pending.
+                DeadlineStatus::Pending => {
+                    if is_rebroadcast_due(submission.max_height, submission.last_broadcast_height, latest_height) {
+                        submission.num_broadcasts += 1;
+                        submission.last_broadcast_height = latest_height;
+                        transactions.push(submission.transaction.clone());
+                    }
+                    continue;
+                }
+            }
+            submission.status = status;
+            events.push(DeadlineEvent { transaction_id, max_height: submission.max_height, status });
+        }
+        drop(submissions);
+
+        // Notify the subscribers of the resolved submissions, if there are any.
+        if self.events.receiver_count() > 0 {
+            for event in events {
+                // Note: This only fails if every subscriber was dropped in the meantime.
+                let _ = self.events.send(event);
+            }
+        }
+        transactions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_status() {
+        // The transaction is pending until the block at its deadline.
+        assert_eq!(DeadlineStatus::resolve(10, None, 5), DeadlineStatus::Pending);
+        assert_eq!(DeadlineStatus::resolve(10, None, 9), DeadlineStatus::Pending);
+        assert_eq!(DeadlineStatus::resolve(10, None, 10), DeadlineStatus::Expired);
+        assert_eq!(DeadlineStatus::resolve(10, None, 11), DeadlineStatus::Expired);
+
+        // The transaction is included only at or below its deadline.
+        assert_eq!(DeadlineStatus::resolve(10, Some(7), 8), DeadlineStatus::Included(7));
+        assert_eq!(DeadlineStatus::resolve(10, Some(10), 12), DeadlineStatus::Included(10));
+        assert_eq!(DeadlineStatus::resolve(10, Some(11), 11), DeadlineStatus::Expired);
+    }
+
+    #[test]
+    fn test_is_rebroadcast_due() {
+        // Far from the deadline, the transaction is rebroadcast once per new block.
+        assert!(!is_rebroadcast_due(100, 20, 20));
+        assert!(is_rebroadcast_due(100, 20, 21));
+
+        // Close to the deadline, the transaction is rebroadcast on every check.
+        assert!(is_rebroadcast_due(100, 95, 95));
+        assert!(is_rebroadcast_due(100, 99, 99));
+    }
+}
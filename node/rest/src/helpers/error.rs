@@ -14,10 +14,116 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
+use serde_json::{json, Value};
+use warp::{http::StatusCode, reply, Rejection, Reply};
+
 /// An enum of error handlers for the REST API server.
 #[derive(Debug)]
 pub enum RestError {
+    /// The request is invalid.
     Request(String),
+    /// The endpoint is not available on this node type.
+    InvalidEndpoint,
+    /// The caller is not authorized to call the endpoint.
+    Unauthorized(String),
+    /// The given block does not exist in the ledger.
+    BlockNotFound(String),
+    /// The given transaction was rejected.
+    InvalidTransaction(String),
+    /// The ledger has not reached the given block height (at the requested finality) yet.
+    NotSynced { height: u32, tip_height: u32 },
+    /// The caller exceeded the rate limit.
+    RateLimited,
+    /// The request body exceeds the given maximum size, in bytes.
+    PayloadTooLarge(u64),
 }
 
 impl warp::reject::Reject for RestError {}
+
+impl RestError {
+    /// Returns the stable error code, following the JSON-RPC 2.0 conventions.
+    pub const fn code(&self) -> i32 {
+        match self {
+            Self::Request(..) => -32600,
+            Self::InvalidEndpoint => -32601,
+            Self::BlockNotFound(..) => -32001,
+            Self::InvalidTransaction(..) => -32002,
+            Self::NotSynced { .. } => -32003,
+            Self::Unauthorized(..) => -32004,
+            Self::RateLimited => -32005,
+            Self::PayloadTooLarge(..) => -32006,
+        }
+    }
+
+    /// Returns the HTTP status code.
+    pub const fn status(&self) -> StatusCode {
+        match self {
+            Self::Request(..) | Self::InvalidTransaction(..) => StatusCode::BAD_REQUEST,
+            Self::InvalidEndpoint | Self::BlockNotFound(..) => StatusCode::NOT_FOUND,
+            Self::NotSynced { .. } => StatusCode::SERVICE_UNAVAILABLE,
+            Self::Unauthorized(..) => StatusCode::UNAUTHORIZED,
+            Self::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            Self::PayloadTooLarge(..) => StatusCode::PAYLOAD_TOO_LARGE,
+        }
+    }
+
+    /// Returns the human-readable error message.
+    pub fn message(&self) -> String {
+        match self {
+            Self::Request(message) | Self::Unauthorized(message) => message.clone(),
+            Self::InvalidEndpoint => "Invalid endpoint".to_string(),
+            Self::BlockNotFound(block) => format!("Block '{block}' does not exist"),
+            Self::InvalidTransaction(reason) => format!("Invalid transaction - {reason}"),
+            Self::NotSynced { height, tip_height } => {
+                format!("Block {height} is beyond the tip of the ledger (at height {tip_height})")
+            }
+            Self::RateLimited => "Too many requests".to_string(),
+            Self::PayloadTooLarge(max_bytes) => format!("The request body exceeds {max_bytes} bytes"),
+        }
+    }
+
+    /// Returns the machine-readable error data.
+    pub fn data(&self) -> Value {
+        match self {
+            Self::Request(..) | Self::InvalidEndpoint | Self::Unauthorized(..) | Self::RateLimited => Value::Null,
+            Self::BlockNotFound(block) => json!({ "block": block }),
+            Self::InvalidTransaction(reason) => json!({ "reason": reason }),
+            Self::NotSynced { height, tip_height } => json!({ "height": height, "tip_height": tip_height }),
+            Self::PayloadTooLarge(max_bytes) => json!({ "max_bytes": max_bytes }),
+        }
+    }
+}
+
+/// Replies to a `RestError` rejection with its code, message, and data.
+/// Any other rejection is passed through to the default handler.
+pub async fn handle_rejection(rejection: Rejection) -> Result<impl Reply, Rejection> {
+    match rejection.find::<RestError>() {
+        Some(error) => {
+            let body = json!({ "code": error.code(), "message": error.message(), "data": error.data() });
+            Ok(reply::with_status(reply::json(&body), error.status()))
+        }
+        None => Err(rejection),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rest_error() {
+        let error = RestError::NotSynced { height: 10, tip_height: 5 };
+        assert_eq!(error.code(), -32003);
+        assert_eq!(error.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(error.data(), json!({ "height": 10, "tip_height": 5 }));
+
+        let error = RestError::BlockNotFound("ab1".to_string());
+        assert_eq!(error.code(), -32001);
+        assert_eq!(error.status(), StatusCode::NOT_FOUND);
+        assert_eq!(error.data(), json!({ "block": "ab1" }));
+
+        let error = RestError::InvalidEndpoint;
+        assert_eq!(error.code(), -32601);
+        assert_eq!(error.data(), Value::Null);
+    }
+}
@@ -0,0 +1,249 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::{
+    console::{
+        account::Address,
+        program::{Entry, Identifier, Literal, Plaintext, Record},
+        types::Field,
+    },
+    prelude::Network,
+};
+
+use anyhow::Result;
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The maximum number of client IPs whose last request is tracked at once.
+const MAX_TRACKED_IPS: usize = 1 << 16;
+/// The duration after which a record reserved by a broadcast faucet transaction is released,
+/// in case the transaction is never included in a block.
+const RESERVATION_TIMEOUT: Duration = Duration::from_secs(600);
+/// The timeout of a request to the captcha verifier.
+const CAPTCHA_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The configuration of the faucet, which is only served on development networks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FaucetConfig {
+    /// The amount dispensed per request, in microcredits.
+    pub amount: u64,
+    /// The number of seconds each client IP must wait between requests.
+    pub cooldown_in_secs: u64,
+    /// The captcha verifier, if a solved captcha is required with each request.
+    pub captcha: Option<CaptchaConfig>,
+}
+
+/// The configuration of a captcha verifier compatible with the reCAPTCHA and hCaptcha `siteverify` APIs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CaptchaConfig {
+    /// The URL of the verification endpoint.
+    pub verify_url: String,
+    /// The secret key of the site.
+    pub secret: String,
+}
+
+/// A request for funds from the faucet.
+#[derive(Clone, Debug, Deserialize)]
+pub struct FaucetRequest<N: Network> {
+    /// The address to fund.
+    pub address: Address<N>,
+    /// The solved captcha, if one is required.
+    #[serde(default)]
+    pub captcha: Option<String>,
+}
+
+/// The response of a captcha verifier.
+#[derive(Deserialize)]
+struct CaptchaVerdict {
+    /// Whether the captcha was solved.
+    success: bool,
+}
+
+/// The faucet, which dispenses funds from the node account on development networks.
+#[derive(Clone)]
+pub struct Faucet<N: Network> {
+    /// The configuration.
+    config: FaucetConfig,
+    /// The HTTP client of the captcha verifier.
+    client: reqwest::Client,
+    /// The map of client IPs to the time of their last request.
+    last_requests: Arc<Mutex<HashMap<IpAddr, Instant>>>,
+    /// The map of the commitments of the records spent by pending faucet transactions, to the time they were reserved.
+    reserved: Arc<Mutex<HashMap<Field<N>, Instant>>>,
+}
+
+/// A record reserved for a faucet transaction, which is released when dropped unless it is kept.
+pub struct Reservation<N: Network> {
+    /// The faucet.
+    faucet: Faucet<N>,
+    /// The commitment of the reserved record.
+    commitment: Field<N>,
+    /// Whether the reservation is kept, as the transaction spending the record was broadcast.
+    kept: bool,
+}
+
+impl<N: Network> Reservation<N> {
+    /// Keeps the record reserved until it is spent, or until the reservation times out.
+    pub fn keep(mut self) {
+        self.kept = true;
+    }
+}
+
+impl<N: Network> Drop for Reservation<N> {
+    /// Releases the record, unless the reservation is kept.
+    fn drop(&mut self) {
+        if !self.kept {
+            self.faucet.reserved.lock().remove(&self.commitment);
+        }
+    }
+}
+
+impl<N: Network> Faucet<N> {
+    /// Initializes the faucet with the given configuration.
+    pub fn new(config: FaucetConfig) -> Result<Self> {
+        let client = reqwest::Client::builder().timeout(CAPTCHA_TIMEOUT).build()?;
+        Ok(Self { config, client, last_requests: Default::default(), reserved: Default::default() })
+    }
+
+    /// Returns the amount dispensed per request, in microcredits.
+    pub const fn amount(&self) -> u64 {
+        self.config.amount
+    }
+
+    /// Returns `true` if the given captcha is solved, or if no captcha is required.
+    pub async fn verify_captcha(&self, captcha: Option<&str>, client_ip: Option<IpAddr>) -> Result<bool> {
+        let config = match &self.config.captcha {
+            Some(config) => config,
+            None => return Ok(true),
+        };
+        let captcha = match captcha {
+            Some(captcha) => captcha,
+            None => return Ok(false),
+        };
+
+        // Submit the captcha to the verifier.
+        let mut form = vec![("secret", config.secret.clone()), ("response", captcha.to_string())];
+        if let Some(client_ip) = client_ip {
+            form.push(("remoteip", client_ip.to_string()));
+        }
+        let response = self.client.post(&config.verify_url).form(&form).send().await?;
+        let verdict: CaptchaVerdict = serde_json::from_str(&response.text().await?)?;
+        Ok(verdict.success)
+    }
+
+    /// Records a request from the given client IP, returning `false` if the client is still cooling down.
+    pub fn check_cooldown(&self, client_ip: IpAddr) -> bool {
+        let cooldown = Duration::from_secs(self.config.cooldown_in_secs);
+        let now = Instant::now();
+
+        let mut last_requests = self.last_requests.lock();
+        // If too many IPs are tracked, forget the IPs that have cooled down.
+        if last_requests.len() >= MAX_TRACKED_IPS {
+            last_requests.retain(|_, last| now.duration_since(*last) < cooldown);
+        }
+        match last_requests.get(&client_ip) {
+            Some(last) if now.duration_since(*last) < cooldown => false,
+            _ => {
+                last_requests.insert(client_ip, now);
+                true
+            }
+        }
+    }
+
+    /// Reserves an unspent record that covers the faucet amount, given the unspent records of the node account.
+    pub fn reserve_record(
+        &self,
+        unspent_records: IndexMap<Field<N>, Record<N, Plaintext<N>>>,
+    ) -> Option<(Reservation<N>, Record<N, Plaintext<N>>)> {
+        let microcredits = Identifier::from_str("microcredits").ok()?;
+
+        let mut reserved = self.reserved.lock();
+        // Release the records that have since been spent, or whose reservation timed out.
+        reserved.retain(|commitment, reserved_at| {
+            unspent_records.contains_key(commitment) && reserved_at.elapsed() < RESERVATION_TIMEOUT
+        });
+        // Reserve the first unreserved record that covers the faucet amount.
+        let (commitment, record) = unspent_records.into_iter().find(|(commitment, record)| {
+            let balance = match record.data().get(&microcredits) {
+                Some(Entry::Private(Plaintext::Literal(Literal::U64(amount), _))) => **amount,
+                _ => 0,
+            };
+            balance >= self.config.amount && !reserved.contains_key(commitment)
+        })?;
+        reserved.insert(commitment, Instant::now());
+        Some((Reservation { faucet: self.clone(), commitment, kept: false }, record))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{PrivateKey, TestRng, Testnet3};
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_faucet_cooldown() {
+        let config = FaucetConfig { amount: 1, cooldown_in_secs: 60, captcha: None };
+        let faucet = Faucet::<CurrentNetwork>::new(config).unwrap();
+        let ip = IpAddr::from([127, 0, 0, 1]);
+
+        // Ensure each IP must wait for the cooldown between requests.
+        assert!(faucet.check_cooldown(ip));
+        assert!(!faucet.check_cooldown(ip));
+        assert!(faucet.check_cooldown(IpAddr::from([127, 0, 0, 2])));
+
+        // Ensure an empty ledger has no records to reserve.
+        assert!(faucet.reserve_record(Default::default()).is_none());
+    }
+
+    #[test]
+    fn test_faucet_reservation() {
+        let rng = &mut TestRng::default();
+        let config = FaucetConfig { amount: 100, cooldown_in_secs: 60, captcha: None };
+        let faucet = Faucet::<CurrentNetwork>::new(config).unwrap();
+
+        // Sample an unspent record that covers the faucet amount.
+        let address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        let record = Record::from_str(&format!(
+            "{{ owner: {address}.private, microcredits: 100u64.private, _nonce: 0group.public }}"
+        ))
+        .unwrap();
+        let unspent_records = IndexMap::from([(Field::from_u64(1), record)]);
+
+        // Ensure the record is released when the reservation is dropped.
+        let (reservation, _) = faucet.reserve_record(unspent_records.clone()).unwrap();
+        assert!(faucet.reserve_record(unspent_records.clone()).is_none());
+        drop(reservation);
+
+        // Ensure the record remains reserved when the reservation is kept.
+        let (reservation, _) = faucet.reserve_record(unspent_records.clone()).unwrap();
+        reservation.keep();
+        assert!(faucet.reserve_record(unspent_records.clone()).is_none());
+
+        // Ensure the record is released once it is no longer unspent.
+        assert!(faucet.reserve_record(Default::default()).is_none());
+        assert!(faucet.reserve_record(unspent_records).is_some());
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::IpcConnection;
+
+use anyhow::{bail, Result};
+use futures_util::{stream, Stream};
+use hyper::{
+    server::accept,
+    service::{make_service_fn, service_fn, Service},
+    Body,
+    Request,
+    Response,
+    Server,
+};
+use std::{
+    convert::Infallible,
+    fs,
+    io,
+    os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt},
+    path::Path,
+};
+use tokio::net::{UnixListener, UnixStream};
+
+/// The file permissions of the directory of the IPC socket, which grant access to the owner of the node process only.
+const IPC_DIRECTORY_MODE: u32 = 0o700;
+/// The file permissions of the IPC socket, which grant access to the owner of the node process only.
+const IPC_SOCKET_MODE: u32 = 0o600;
+
+/// Binds the IPC socket at the given path, replacing a stale socket from a previous run, and restricts it
+/// to the owner of the node process. Access is governed by the file permissions, so the protected endpoints
+/// require no bearer token over IPC.
+///
+/// The socket is bound in a directory that only the owner can access, so it is never reachable by other users,
+/// including between its creation and the change of its own permissions.
+pub(crate) fn bind_ipc_socket(path: &Path) -> Result<UnixListener> {
+    // Ensure the directory of the socket exists, and is only accessible by the owner.
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    fs::DirBuilder::new().recursive(true).mode(IPC_DIRECTORY_MODE).create(directory)?;
+    let mode = fs::metadata(directory)?.permissions().mode();
+    if mode & 0o077 != 0 {
+        bail!(
+            "Cannot bind the IPC socket in '{}' (the directory is accessible by other users, mode {:o})",
+            directory.display(),
+            mode & 0o777
+        )
+    }
+
+    // Remove the stale socket, without removing any other file at the path.
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => fs::remove_file(path)?,
+        Ok(_) => bail!("Cannot bind the IPC socket at '{}' (the path exists and is not a socket)", path.display()),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => (),
+        Err(error) => return Err(error.into()),
+    }
+    let listener = UnixListener::bind(path)?;
+    fs::set_permissions(path, fs::Permissions::from_mode(IPC_SOCKET_MODE))?;
+    Ok(listener)
+}
+
+/// Returns the stream of the connections accepted on the given IPC socket.
+fn ipc_incoming(listener: UnixListener) -> impl Stream<Item = io::Result<UnixStream>> + Send {
+    stream::unfold(listener, |listener| async move {
+        let connection = listener.accept().await.map(|(stream, _)| stream);
+        Some((connection, listener))
+    })
+}
+
+/// Serves the given service on the IPC socket, marking each request with `IpcConnection`,
+/// which authorizes it to call the protected endpoints.
+pub(crate) async fn serve_ipc<S>(listener: UnixListener, service: S)
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    let make_service = make_service_fn(move |_| {
+        let service = service.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |mut request: Request<Body>| {
+                request.extensions_mut().insert(IpcConnection);
+                service.clone().call(request)
+            }))
+        }
+    });
+    if let Err(error) = Server::builder(accept::from_stream(ipc_incoming(listener))).serve(make_service).await {
+        error!("The IPC server failed - {error}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bind_ipc_socket() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let directory = temp_dir.path().join("ipc");
+        let path = directory.join("node.sock");
+
+        // Ensure the socket and its directory are only accessible by the owner.
+        let listener = bind_ipc_socket(&path).unwrap();
+        assert_eq!(fs::metadata(&directory).unwrap().permissions().mode() & 0o777, IPC_DIRECTORY_MODE);
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, IPC_SOCKET_MODE);
+
+        // Ensure a stale socket is replaced.
+        drop(listener);
+        let _listener = bind_ipc_socket(&path).unwrap();
+
+        // Ensure any other file is not replaced.
+        let file_path = directory.join("node.txt");
+        fs::write(&file_path, b"data").unwrap();
+        assert!(bind_ipc_socket(&file_path).is_err());
+        assert_eq!(fs::read(&file_path).unwrap(), b"data");
+
+        // Ensure the socket is not bound in a directory that other users can access.
+        fs::set_permissions(&directory, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(bind_ipc_socket(&directory.join("other.sock")).is_err());
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{with_client_ip, RestError, RestProxy};
+
+use linked_hash_map::LinkedHashMap;
+use parking_lot::Mutex;
+use std::{
+    net::IpAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+use warp::{reject, Filter, Rejection};
+
+/// The maximum number of IPs whose request rate is tracked at once.
+const MAX_TRACKED_IPS: usize = 1 << 16;
+
+/// The limits applied to every request to the REST server.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RestLimits {
+    /// The number of requests per second each IP may sustain, or `None` to disable rate limiting.
+    pub requests_per_sec: Option<u32>,
+    /// The number of requests each IP may send at once, on top of the sustained rate.
+    pub burst: u32,
+    /// The maximum size of a request body, in bytes.
+    pub max_body_bytes: u64,
+}
+
+impl Default for RestLimits {
+    /// Returns the default limits, which disable rate limiting and admit the largest body of any endpoint.
+    fn default() -> Self {
+        Self { requests_per_sec: None, burst: 0, max_body_bytes: 64 * 1024 * 1024 }
+    }
+}
+
+/// Enforces the REST limits with a token bucket per IP, and counts the rejected requests.
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    /// The limits.
+    limits: RestLimits,
+    /// The map of IPs to their remaining tokens, and the time the tokens were last refilled,
+    /// from the least to the most recently seen IP.
+    buckets: Arc<Mutex<LinkedHashMap<IpAddr, (f64, Instant)>>>,
+    /// The number of requests rejected for exceeding the rate limit.
+    num_rate_limited: Arc<AtomicU64>,
+    /// The number of requests rejected for exceeding the maximum body size.
+    num_oversized: Arc<AtomicU64>,
+}
+
+impl Default for RateLimiter {
+    /// Initializes a new rate limiter with the default limits.
+    fn default() -> Self {
+        Self::new(RestLimits::default())
+    }
+}
+
+impl RateLimiter {
+    /// Initializes a new rate limiter with the given limits.
+    pub fn new(limits: RestLimits) -> Self {
+        Self {
+            limits,
+            buckets: Default::default(),
+            num_rate_limited: Default::default(),
+            num_oversized: Default::default(),
+        }
+    }
+
+    /// Returns the limits.
+    pub const fn limits(&self) -> RestLimits {
+        self.limits
+    }
+
+    /// Returns the number of requests rejected for exceeding the rate limit.
+    pub fn num_rate_limited(&self) -> u64 {
+        self.num_rate_limited.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of requests rejected for exceeding the maximum body size.
+    pub fn num_oversized(&self) -> u64 {
+        self.num_oversized.load(Ordering::Relaxed)
+    }
+
+    /// Takes a token from the bucket of the given IP, returning `false` if the bucket is empty.
+    pub fn check_rate(&self, ip: IpAddr) -> bool {
+        let requests_per_sec = match self.limits.requests_per_sec {
+            Some(requests_per_sec) => requests_per_sec as f64,
+            None => return true,
+        };
+        let capacity = requests_per_sec + self.limits.burst as f64;
+        let now = Instant::now();
+
+        let mut buckets = self.buckets.lock();
+        // Mark the IP as the most recently seen, or start tracking it with a full bucket,
+        // forgetting the least recently seen IPs if too many IPs are tracked.
+        if buckets.get_refresh(&ip).is_none() {
+            buckets.insert(ip, (capacity, now));
+            while buckets.len() > MAX_TRACKED_IPS {
+                buckets.pop_front();
+            }
+        }
+        // Refill the bucket in proportion to the elapsed time, and take a token.
+        let (tokens, last) = match buckets.get_mut(&ip) {
+            Some(bucket) => bucket,
+            None => return true,
+        };
+        *tokens = (*tokens + now.duration_since(*last).as_secs_f64() * requests_per_sec).min(capacity);
+        *last = now;
+        match *tokens >= 1.0 {
+            true => {
+                *tokens -= 1.0;
+                true
+            }
+            false => {
+                self.num_rate_limited.fetch_add(1, Ordering::Relaxed);
+                false
+            }
+        }
+    }
+
+    /// Returns `false` if the given content length exceeds the maximum body size.
+    pub fn check_body(&self, content_length: Option<u64>) -> bool {
+        match content_length.map_or(true, |length| length <= self.limits.max_body_bytes) {
+            true => true,
+            false => {
+                self.num_oversized.fetch_add(1, Ordering::Relaxed);
+                false
+            }
+        }
+    }
+}
+
+/// Rejects the requests that exceed the maximum body size, or the rate limit of their client IP.
+pub fn with_limits(
+    limiter: RateLimiter,
+    proxy: Arc<RestProxy>,
+) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    with_client_ip(proxy)
+        .and(warp::header::optional::<u64>("content-length"))
+        .and_then(move |client_ip: Option<IpAddr>, content_length: Option<u64>| {
+            let limiter = limiter.clone();
+            async move {
+                if !limiter.check_body(content_length) {
+                    return Err(reject::custom(RestError::PayloadTooLarge(limiter.limits().max_body_bytes)));
+                }
+                if let Some(client_ip) = client_ip {
+                    if !limiter.check_rate(client_ip) {
+                        return Err(reject::custom(RestError::RateLimited));
+                    }
+                }
+                Ok(())
+            }
+        })
+        .untuple_one()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter() {
+        let limiter = RateLimiter::new(RestLimits { requests_per_sec: Some(1), burst: 2, max_body_bytes: 1024 });
+        let ip = IpAddr::from([127, 0, 0, 1]);
+
+        // Ensure the IP may burst, and is then rate limited.
+        assert!((0..3).all(|_| limiter.check_rate(ip)));
+        assert!(!limiter.check_rate(ip));
+        assert_eq!(limiter.num_rate_limited(), 1);
+
+        // Ensure other IPs are not affected.
+        assert!(limiter.check_rate(IpAddr::from([127, 0, 0, 2])));
+        assert_eq!(limiter.buckets.lock().len(), 2);
+
+        // Ensure the body size is enforced.
+        assert!(limiter.check_body(None));
+        assert!(limiter.check_body(Some(1024)));
+        assert!(!limiter.check_body(Some(1025)));
+        assert_eq!(limiter.num_oversized(), 1);
+    }
+
+    #[test]
+    fn test_rate_limiter_eviction() {
+        let limiter = RateLimiter::new(RestLimits { requests_per_sec: Some(1), burst: 0, max_body_bytes: 1024 });
+        let ip = IpAddr::from([10, 0, 0, 1]);
+
+        // Exhaust the bucket of the IP.
+        assert!(limiter.check_rate(ip));
+        assert!(!limiter.check_rate(ip));
+
+        // Ensure the number of tracked IPs is bounded, by evicting the least recently seen IPs.
+        for i in 0..MAX_TRACKED_IPS as u32 {
+            assert!(limiter.check_rate(IpAddr::from((0xAC10_0000 + i).to_be_bytes())));
+        }
+        assert_eq!(limiter.buckets.lock().len(), MAX_TRACKED_IPS);
+        assert!(!limiter.buckets.lock().contains_key(&ip));
+    }
+}
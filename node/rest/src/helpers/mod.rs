@@ -17,11 +17,61 @@
 mod auth;
 pub use auth::*;
 
+mod block_stream;
+pub use block_stream::*;
+
+mod capacity;
+pub use capacity::*;
+
+mod config;
+pub use config::*;
+
+mod deadlines;
+pub use deadlines::*;
+
 mod error;
 pub use error::*;
 
+mod faucet;
+pub use faucet::*;
+
+#[cfg(unix)]
+mod ipc;
+#[cfg(unix)]
+pub use ipc::*;
+
+mod limits;
+pub use limits::*;
+
 mod middleware;
 pub use middleware::*;
 
+mod openapi;
+pub use openapi::*;
+
 mod or_reject;
 pub use or_reject::*;
+
+mod probes;
+pub use probes::*;
+
+mod profiling;
+pub use profiling::*;
+
+mod proving_jobs;
+pub use proving_jobs::*;
+
+mod proxy;
+pub use proxy::*;
+
+mod query;
+pub use query::*;
+
+mod raw_transaction;
+pub use raw_transaction::*;
+
+mod schema;
+pub use schema::*;
+
+mod websocket;
+pub use websocket::*;
@@ -0,0 +1,158 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use once_cell::sync::Lazy;
+use serde_json::{json, Value};
+
+/// The OpenAPI 3 description of the REST server, which is regenerated whenever the routes change.
+const OPENAPI_SOURCE: &str = include_str!("../../openapi.json");
+
+/// The OpenAPI 3 description of the REST server.
+static OPENAPI_SPEC: Lazy<Value> =
+    Lazy::new(|| serde_json::from_str(OPENAPI_SOURCE).expect("The OpenAPI description is malformed"));
+
+/// Returns the OpenAPI 3 description of the REST server, served under the given path prefix.
+pub fn openapi_spec(path_prefix: Option<&str>) -> Value {
+    let server = match path_prefix {
+        Some(prefix) => format!("/{}", prefix.trim_matches('/')),
+        None => "/".to_string(),
+    };
+    let mut spec = OPENAPI_SPEC.clone();
+    spec["info"]["version"] = json!(env!("CARGO_PKG_VERSION"));
+    spec["servers"] = json!([{ "url": server }]);
+    spec
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The source of the REST routes, which the OpenAPI description must match.
+    const ROUTES_SOURCE: &str = include_str!("../routes.rs");
+
+    /// An endpoint of the REST server, as declared in the routes.
+    #[derive(Debug, Default)]
+    struct Endpoint {
+        /// The HTTP method, in lowercase.
+        method: String,
+        /// The path template, e.g. `/testnet3/block/{height}`.
+        path: String,
+        /// The names of the query parameters.
+        query: Vec<String>,
+        /// The name of the handler.
+        handler: String,
+        /// Whether the endpoint requires a bearer token.
+        requires_auth: bool,
+        /// Whether the endpoint accepts a request body.
+        has_body: bool,
+    }
+
+    /// Returns the endpoints declared in the routes, in order. Each route is declared
+    /// with a `// METHOD /path?query` comment, followed by its filter chain up to its handler.
+    fn routes_endpoints() -> Vec<Endpoint> {
+        let lines: Vec<&str> = ROUTES_SOURCE.lines().map(str::trim).collect();
+
+        let mut endpoints = Vec::new();
+        for (index, line) in lines.iter().enumerate() {
+            // Parse the route declaration.
+            let (method, target) = match line.strip_prefix("// ").and_then(|line| line.split_once(' ')) {
+                Some((method, target))
+                    if ["GET", "POST", "PUT", "DELETE"].contains(&method) && target.starts_with('/') =>
+                {
+                    (method, target)
+                }
+                _ => continue,
+            };
+            let (path, query) = match target.split_once('?') {
+                Some((path, query)) => {
+                    (path, query.split('&').filter_map(|pair| pair.split_once('=')).map(|(k, _)| k).collect())
+                }
+                None => (target, Vec::new()),
+            };
+
+            // Retrieve the filter chain of the route, up to its handler.
+            let chain: Vec<&str> = lines[index + 1..].iter().copied().take_while(|line| !line.is_empty()).collect();
+            let handler = chain
+                .iter()
+                .find_map(|line| line.split_once(".and_then(Self::"))
+                .and_then(|(_, handler)| handler.split(|c: char| !c.is_alphanumeric() && c != '_').next())
+                .unwrap_or_default();
+
+            endpoints.push(Endpoint {
+                method: method.to_lowercase(),
+                path: path.to_string(),
+                query: query.into_iter().map(str::to_string).collect(),
+                handler: handler.to_string(),
+                requires_auth: chain.iter().any(|line| line.contains("with_auth(") || line.contains("with_caller(")),
+                has_body: chain.iter().any(|line| {
+                    ["warp::body::json()", "warp::body::bytes()", "json_body("].iter().any(|body| line.contains(body))
+                }),
+            });
+        }
+        endpoints
+    }
+
+    #[test]
+    fn test_openapi_matches_routes() {
+        let endpoints = routes_endpoints();
+        let spec = openapi_spec(None);
+
+        // Ensure every handler is declared as a route.
+        let num_handlers = ROUTES_SOURCE.matches(".and_then(Self::").count();
+        assert_eq!(endpoints.len(), num_handlers);
+
+        // Ensure every route is described, as it is declared.
+        for endpoint in &endpoints {
+            let operation = &spec["paths"][&endpoint.path][&endpoint.method];
+            assert!(operation.is_object(), "Missing description of '{} {}'", endpoint.method, endpoint.path);
+            assert_eq!(operation["operationId"], endpoint.handler.as_str());
+            assert!(!operation["summary"].as_str().unwrap().is_empty(), "Missing summary of '{}'", endpoint.handler);
+            let query: Vec<&str> = operation["parameters"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|parameter| parameter["in"] == "query")
+                .map(|parameter| parameter["name"].as_str().unwrap())
+                .collect();
+            assert_eq!(query, endpoint.query, "Mismatched query of '{}'", endpoint.handler);
+            assert_eq!(operation.get("security").is_some(), endpoint.requires_auth, "{}", endpoint.handler);
+            assert_eq!(operation.get("requestBody").is_some(), endpoint.has_body, "{}", endpoint.handler);
+            // Ensure every response body is described by a schema, which is checked against the response types
+            // of the handlers in the tests of the routes.
+            let handler = &endpoint.handler;
+            for (status, response) in operation["responses"].as_object().unwrap() {
+                for media in response["content"].as_object().into_iter().flat_map(|content| content.values()) {
+                    assert!(media["schema"].is_object(), "Missing schema of the {status} response of '{handler}'");
+                }
+            }
+        }
+
+        // Ensure no removed route is still described.
+        let num_operations: usize =
+            spec["paths"].as_object().unwrap().values().map(|path| path.as_object().unwrap().len()).sum();
+        assert_eq!(num_operations, endpoints.len());
+    }
+
+    #[test]
+    fn test_openapi_spec() {
+        // Ensure the spec serves the endpoints under the path prefix, with the version of the server.
+        let spec = openapi_spec(Some("api/v1/"));
+        assert_eq!(spec["servers"][0]["url"], "/api/v1");
+        assert_eq!(spec["info"]["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(spec["paths"]["/testnet3/block/{height}"]["get"]["parameters"][0]["name"], "height");
+        assert_eq!(openapi_spec(None)["servers"][0]["url"], "/");
+    }
+}
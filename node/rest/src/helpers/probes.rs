@@ -0,0 +1,104 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use serde::Serialize;
+
+/// The thresholds that the node must meet to be ready to serve traffic.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ReadinessThresholds {
+    /// The maximum number of blocks the node may be behind the median height of its peers.
+    pub max_sync_lag: u32,
+    /// The minimum number of connected peers.
+    pub min_peers: usize,
+}
+
+impl Default for ReadinessThresholds {
+    /// Returns the default thresholds, which admit a lag of a few blocks behind at least one peer.
+    fn default() -> Self {
+        Self { max_sync_lag: 10, min_peers: 1 }
+    }
+}
+
+/// The readiness of the node to serve traffic.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Readiness {
+    /// Whether the node is ready.
+    pub ready: bool,
+    /// The latest block height of the node.
+    pub height: u32,
+    /// The median block height reported by the peers, if any.
+    pub peer_height: Option<u32>,
+    /// The number of connected peers.
+    pub num_peers: usize,
+    /// The reasons the node is not ready, if any.
+    pub reasons: Vec<String>,
+}
+
+impl Readiness {
+    /// Evaluates the readiness of the node against the given thresholds.
+    pub fn evaluate_with(
+        thresholds: ReadinessThresholds,
+        height: u32,
+        peer_height: Option<u32>,
+        num_peers: usize,
+    ) -> Self {
+        let mut reasons = Vec::new();
+        // Ensure the node is connected to enough peers.
+        if num_peers < thresholds.min_peers {
+            reasons.push(format!("Connected to {num_peers} peers, below the minimum of {}", thresholds.min_peers));
+        }
+        // Ensure the node is not too far behind its peers.
+        let sync_lag = peer_height.unwrap_or_default().saturating_sub(height);
+        if sync_lag > thresholds.max_sync_lag {
+            reasons.push(format!(
+                "Behind the peers by {sync_lag} blocks, above the maximum of {}",
+                thresholds.max_sync_lag
+            ));
+        }
+        Self { ready: reasons.is_empty(), height, peer_height, num_peers, reasons }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readiness() {
+        let thresholds = ReadinessThresholds { max_sync_lag: 5, min_peers: 2 };
+
+        // A node that is synced with enough peers is ready.
+        let readiness = Readiness::evaluate_with(thresholds, 100, Some(105), 2);
+        assert!(readiness.ready);
+        assert!(readiness.reasons.is_empty());
+
+        // A node that is ahead of its peers is ready.
+        assert!(Readiness::evaluate_with(thresholds, 100, Some(90), 3).ready);
+
+        // A node that is too far behind its peers is not ready.
+        let readiness = Readiness::evaluate_with(thresholds, 100, Some(106), 2);
+        assert!(!readiness.ready);
+        assert_eq!(readiness.reasons.len(), 1);
+
+        // A node without enough peers is not ready, even if none report a height.
+        let readiness = Readiness::evaluate_with(thresholds, 0, None, 1);
+        assert!(!readiness.ready);
+        assert_eq!(readiness.reasons.len(), 1);
+
+        // Both reasons are reported.
+        assert_eq!(Readiness::evaluate_with(thresholds, 0, Some(10), 0).reasons.len(), 2);
+    }
+}
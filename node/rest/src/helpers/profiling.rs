@@ -0,0 +1,351 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{anyhow, bail, Result};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::{collections::BTreeMap, fs, time::Duration};
+
+/// The number of clock ticks per second in the CPU times under `/proc`, as configured for the system.
+static CLOCK_TICKS_PER_SEC: Lazy<u64> = Lazy::new(|| {
+    // Safety: `sysconf` has no preconditions, and returns -1 if the variable is not supported.
+    match unsafe { libc::sysconf(libc::_SC_CLK_TCK) } {
+        ticks if ticks > 0 => ticks as u64,
+        _ => 100,
+    }
+});
+/// The maximum number of seconds a CPU profile may sample for.
+pub const MAX_CPU_PROFILE_IN_SECS: u64 = 60;
+
+/// The memory usage of the node process, as reported by the kernel.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct MemoryStats {
+    /// The resident set size, in bytes.
+    pub resident_bytes: u64,
+    /// The peak resident set size, in bytes.
+    pub peak_resident_bytes: u64,
+    /// The virtual memory size, in bytes.
+    pub virtual_bytes: u64,
+    /// The size of the data segment, which includes the heap, in bytes.
+    pub data_bytes: u64,
+    /// The number of threads.
+    pub num_threads: u64,
+}
+
+impl MemoryStats {
+    /// Returns the memory usage of the node process.
+    pub fn current() -> Result<Self> {
+        Self::parse(&read_proc("/proc/self/status")?)
+    }
+
+    /// Parses the memory usage from the contents of `/proc/self/status`.
+    fn parse(status: &str) -> Result<Self> {
+        let mut stats = Self::default();
+        for line in status.lines() {
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key, value.trim()),
+                None => continue,
+            };
+            let field = match key {
+                "VmRSS" => &mut stats.resident_bytes,
+                "VmHWM" => &mut stats.peak_resident_bytes,
+                "VmSize" => &mut stats.virtual_bytes,
+                "VmData" => &mut stats.data_bytes,
+                "Threads" => {
+                    stats.num_threads = value.parse()?;
+                    continue;
+                }
+                _ => continue,
+            };
+            // The sizes are reported in kibibytes.
+            *field = value.trim_end_matches("kB").trim().parse::<u64>()? * 1024;
+        }
+        Ok(stats)
+    }
+}
+
+/// The CPU time spent by a thread of the node process during a profile.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ThreadProfile {
+    /// The thread ID.
+    pub tid: u32,
+    /// The thread name.
+    pub name: String,
+    /// The CPU time spent in user and kernel mode, in milliseconds.
+    pub cpu_ms: u64,
+    /// The share of a single core used by the thread, from 0.0 to 1.0.
+    pub utilization: f64,
+}
+
+/// Samples the CPU time of each thread of the node process over the given number of seconds,
+/// and returns the threads from the busiest to the idlest.
+pub async fn cpu_profile(seconds: u64) -> Result<Vec<ThreadProfile>> {
+    if seconds == 0 || seconds > MAX_CPU_PROFILE_IN_SECS {
+        bail!("The profile duration must be between 1 and {MAX_CPU_PROFILE_IN_SECS} seconds (requested {seconds})");
+    }
+    let start = thread_cpu_ticks()?;
+    tokio::time::sleep(Duration::from_secs(seconds)).await;
+    let end = thread_cpu_ticks()?;
+
+    // Note: The threads that exited during the profile are omitted, and the threads that started are counted in full.
+    let mut profile: Vec<_> = end
+        .into_iter()
+        .map(|(tid, (name, ticks))| {
+            let ticks = ticks.saturating_sub(start.get(&tid).map_or(0, |(_, ticks)| *ticks));
+            let cpu_ms = ticks * 1000 / *CLOCK_TICKS_PER_SEC;
+            let utilization = cpu_ms as f64 / (seconds * 1000) as f64;
+            ThreadProfile { tid, name, cpu_ms, utilization }
+        })
+        .collect();
+    profile.sort_by(|a, b| b.cpu_ms.cmp(&a.cpu_ms));
+    Ok(profile)
+}
+
+/// Samples the CPU time of each thread of the node process over the given number of seconds, and returns the profile
+/// in the pprof protobuf format, for `go tool pprof`. The samples are attributed to the thread names, one frame each,
+/// as the CPU times are read from the kernel rather than by unwinding the stacks of the threads.
+pub async fn cpu_pprof(seconds: u64) -> Result<Vec<u8>> {
+    let profile = cpu_profile(seconds).await?;
+    Ok(encode_pprof(&profile, seconds))
+}
+
+/// Encodes the given thread profiles in the pprof protobuf format, with the CPU time of each thread in nanoseconds.
+fn encode_pprof(profile: &[ThreadProfile], seconds: u64) -> Vec<u8> {
+    // The string table, which must start with the empty string.
+    let strings = ["", "cpu", "nanoseconds"];
+    let mut message = Vec::new();
+    // The sample type (field 1) and the period type (field 11): CPU time in nanoseconds.
+    let value_type = [encode_uint(1, 1), encode_uint(2, 2)].concat();
+    encode_bytes(&mut message, 1, &value_type);
+    for (index, thread) in profile.iter().enumerate() {
+        let id = index as u64 + 1;
+        // The sample (field 2), with the location of the thread and its CPU time.
+        let sample = [encode_packed(1, id), encode_packed(2, thread.cpu_ms * 1_000_000)].concat();
+        encode_bytes(&mut message, 2, &sample);
+        // The location (field 4), with a line in the function of the thread.
+        let line = encode_uint(1, id);
+        let mut location = encode_uint(1, id);
+        encode_bytes(&mut location, 4, &line);
+        encode_bytes(&mut message, 4, &location);
+        // The function (field 5), named after the thread, with its name in the string table.
+        let name = (strings.len() + index) as u64;
+        let function = [encode_uint(1, id), encode_uint(2, name), encode_uint(3, name)].concat();
+        encode_bytes(&mut message, 5, &function);
+    }
+    // The string table (field 6).
+    for string in strings.iter().copied().chain(profile.iter().map(|thread| thread.name.as_str())) {
+        encode_bytes(&mut message, 6, string.as_bytes());
+    }
+    // The duration (field 10), the period type (field 11), and the period (field 12) of the profile.
+    message.extend(encode_uint(10, seconds * 1_000_000_000));
+    encode_bytes(&mut message, 11, &value_type);
+    message.extend(encode_uint(12, 1_000_000_000 / *CLOCK_TICKS_PER_SEC));
+    message
+}
+
+/// Encodes the given value as a protobuf varint.
+fn encode_varint(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+/// Encodes the given unsigned integer field.
+fn encode_uint(field: u64, value: u64) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    encode_varint(&mut buffer, field << 3);
+    encode_varint(&mut buffer, value);
+    buffer
+}
+
+/// Encodes the given repeated unsigned integer field, with a single value, in the packed encoding.
+fn encode_packed(field: u64, value: u64) -> Vec<u8> {
+    let mut packed = Vec::new();
+    encode_varint(&mut packed, value);
+    let mut buffer = Vec::new();
+    encode_bytes(&mut buffer, field, &packed);
+    buffer
+}
+
+/// Appends the given length-delimited field to the buffer.
+fn encode_bytes(buffer: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    encode_varint(buffer, (field << 3) | 2);
+    encode_varint(buffer, bytes.len() as u64);
+    buffer.extend_from_slice(bytes);
+}
+
+/// The statistics of the memory allocator of the node process.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct AllocatorStats {
+    /// The number of bytes allocated by the node.
+    pub allocated_bytes: u64,
+    /// The number of bytes in the pages that hold the allocations.
+    pub active_bytes: u64,
+    /// The number of bytes in the pages mapped by the allocator, including its metadata.
+    pub resident_bytes: u64,
+    /// The number of bytes in the chunks mapped by the allocator.
+    pub mapped_bytes: u64,
+    /// The number of bytes retained by the allocator, which are not returned to the operating system.
+    pub retained_bytes: u64,
+    /// The number of bytes used by the metadata of the allocator.
+    pub metadata_bytes: u64,
+}
+
+impl AllocatorStats {
+    /// Returns the statistics of the jemalloc allocator, which are refreshed on each call.
+    #[cfg(feature = "jemalloc")]
+    pub fn current() -> Result<Self> {
+        // Advance the epoch of the allocator, which refreshes its cached statistics.
+        let mut epoch = 1u64;
+        let mut len = core::mem::size_of::<u64>();
+        let epoch_ptr = &mut epoch as *mut u64 as *mut libc::c_void;
+        // Safety: The name is null-terminated, and the pointers are valid for the size of the epoch.
+        let result =
+            unsafe { tikv_jemalloc_sys::mallctl(b"epoch\0".as_ptr() as _, epoch_ptr, &mut len, epoch_ptr, len) };
+        if result != 0 {
+            bail!("Failed to refresh the allocator statistics (error {result})")
+        }
+
+        Ok(Self {
+            allocated_bytes: Self::read(b"stats.allocated\0")?,
+            active_bytes: Self::read(b"stats.active\0")?,
+            resident_bytes: Self::read(b"stats.resident\0")?,
+            mapped_bytes: Self::read(b"stats.mapped\0")?,
+            retained_bytes: Self::read(b"stats.retained\0")?,
+            metadata_bytes: Self::read(b"stats.metadata\0")?,
+        })
+    }
+
+    /// Returns the statistics of the allocator, which are only available with the jemalloc allocator.
+    #[cfg(not(feature = "jemalloc"))]
+    pub fn current() -> Result<Self> {
+        bail!("The allocator statistics require the node to be built with the jemalloc allocator")
+    }
+
+    /// Reads the given statistic of the jemalloc allocator, by its null-terminated name.
+    #[cfg(feature = "jemalloc")]
+    fn read(name: &[u8]) -> Result<u64> {
+        let mut value = 0usize;
+        let mut len = core::mem::size_of::<usize>();
+        let value_ptr = &mut value as *mut usize as *mut libc::c_void;
+        // Safety: The name is null-terminated, and the pointer is valid for the size of the statistic.
+        let result =
+            unsafe { tikv_jemalloc_sys::mallctl(name.as_ptr() as _, value_ptr, &mut len, core::ptr::null_mut(), 0) };
+        match result {
+            0 => Ok(value as u64),
+            _ => bail!("Failed to read the allocator statistic '{}' (error {result})", String::from_utf8_lossy(name)),
+        }
+    }
+}
+
+/// Returns the name and the CPU time in clock ticks of each thread of the node process, by thread ID.
+fn thread_cpu_ticks() -> Result<BTreeMap<u32, (String, u64)>> {
+    let mut threads = BTreeMap::new();
+    for entry in fs::read_dir("/proc/self/task").map_err(|error| anyhow!("Profiling requires Linux - {error}"))? {
+        let entry = entry?;
+        let tid = match entry.file_name().to_str().and_then(|tid| tid.parse::<u32>().ok()) {
+            Some(tid) => tid,
+            None => continue,
+        };
+        // Skip the threads that exited while reading.
+        let path = entry.path();
+        let (name, stat) = match (fs::read_to_string(path.join("comm")), fs::read_to_string(path.join("stat"))) {
+            (Ok(name), Ok(stat)) => (name.trim().to_string(), stat),
+            _ => continue,
+        };
+        threads.insert(tid, (name, parse_cpu_ticks(&stat)?));
+    }
+    Ok(threads)
+}
+
+/// Parses the CPU time in clock ticks, spent in user and kernel mode, from the contents of a `stat` file.
+fn parse_cpu_ticks(stat: &str) -> Result<u64> {
+    // The thread name may contain spaces and parentheses, so the fields are counted after its closing parenthesis.
+    let fields: Vec<_> = match stat.rsplit_once(')') {
+        Some((_, fields)) => fields.split_whitespace().collect(),
+        None => bail!("Malformed stat file"),
+    };
+    // The user and kernel times are the 14th and 15th fields, i.e. the 12th and 13th after the thread name.
+    match (fields.get(11), fields.get(12)) {
+        (Some(utime), Some(stime)) => Ok(utime.parse::<u64>()? + stime.parse::<u64>()?),
+        _ => bail!("Malformed stat file"),
+    }
+}
+
+/// Reads the given file under `/proc`.
+fn read_proc(path: &str) -> Result<String> {
+    fs::read_to_string(path).map_err(|error| anyhow!("Profiling requires Linux - failed to read '{path}': {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_memory_stats() {
+        let status = "Name:\tsnarkos\nVmHWM:\t  204800 kB\nVmRSS:\t  102400 kB\nVmSize:\t 4096000 kB\nVmData:\t  512000 kB\nThreads:\t42\n";
+        let stats = MemoryStats::parse(status).unwrap();
+        assert_eq!(stats.resident_bytes, 102400 * 1024);
+        assert_eq!(stats.peak_resident_bytes, 204800 * 1024);
+        assert_eq!(stats.virtual_bytes, 4096000 * 1024);
+        assert_eq!(stats.data_bytes, 512000 * 1024);
+        assert_eq!(stats.num_threads, 42);
+    }
+
+    #[test]
+    fn test_clock_ticks_per_sec() {
+        assert!(*CLOCK_TICKS_PER_SEC > 0);
+    }
+
+    #[test]
+    fn test_encode_pprof() {
+        // Ensure the varints are encoded in groups of 7 bits, from the least significant group.
+        let mut buffer = Vec::new();
+        encode_varint(&mut buffer, 300);
+        assert_eq!(buffer, [0xac, 0x02]);
+
+        // Ensure each thread has a sample, a location, and a function, named in the string table.
+        let profile = vec![ThreadProfile { tid: 1, name: "tokio-runtime".to_string(), cpu_ms: 250, utilization: 0.25 }];
+        let pprof = encode_pprof(&profile, 1);
+        // The sample type is CPU time in nanoseconds.
+        assert!(pprof.starts_with(&[0x0a, 0x04, 0x08, 0x01, 0x10, 0x02]));
+        // The sample has the location of the thread, and its CPU time in nanoseconds.
+        let mut sample = vec![0x12];
+        let mut value = Vec::new();
+        encode_varint(&mut value, 250_000_000);
+        sample.push(5 + value.len() as u8);
+        sample.extend([0x0a, 0x01, 0x01, 0x12, value.len() as u8]);
+        sample.extend(value);
+        assert!(pprof.windows(sample.len()).any(|window| window == sample.as_slice()));
+        assert!(pprof.windows(b"tokio-runtime".len()).any(|window| window == b"tokio-runtime"));
+    }
+
+    #[cfg(feature = "jemalloc")]
+    #[test]
+    fn test_allocator_stats() {
+        let stats = AllocatorStats::current().unwrap();
+        assert!(stats.mapped_bytes >= stats.active_bytes);
+    }
+
+    #[test]
+    fn test_parse_cpu_ticks() {
+        let stat = "1234 (tokio-runtime) worker) S 1 1234 1234 0 -1 4194368 100 0 0 0 250 50 0 0 20 0 8 0 100 0 0";
+        assert_eq!(parse_cpu_ticks(stat).unwrap(), 300);
+        assert!(parse_cpu_ticks("1234 (snarkos").is_err());
+    }
+}
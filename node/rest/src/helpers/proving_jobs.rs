@@ -0,0 +1,304 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::prelude::{Identifier, Network, Plaintext, PrivateKey, ProgramID, Record, Value};
+
+use anyhow::{bail, Result};
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf, sync::Arc};
+use time::OffsetDateTime;
+
+/// The maximum number of proving jobs retained at once.
+const MAX_PROVING_JOBS: usize = 1024;
+
+/// A request to generate an execution transaction with the proving account.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ProvingJobRequest<N: Network> {
+    /// The program ID.
+    pub program_id: ProgramID<N>,
+    /// The function name.
+    pub function: Identifier<N>,
+    /// The function inputs.
+    pub inputs: Vec<Value<N>>,
+    /// The record to spend the fee from, if any.
+    #[serde(default)]
+    pub fee_record: Option<Record<N, Plaintext<N>>>,
+    /// The fee in microcredits.
+    #[serde(default)]
+    pub fee: u64,
+}
+
+/// The status of a proving job.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProvingJobStatus<N: Network> {
+    /// The job is awaiting a prover.
+    Queued,
+    /// The job is being proven.
+    Proving,
+    /// The job was proven and broadcast as the given transaction.
+    Completed(N::TransactionID),
+    /// The job failed with the given reason.
+    Failed(String),
+    /// The job was cancelled.
+    Cancelled,
+}
+
+impl<N: Network> ProvingJobStatus<N> {
+    /// Returns `true` if the job is queued or being proven.
+    pub const fn is_active(&self) -> bool {
+        matches!(self, Self::Queued | Self::Proving)
+    }
+}
+
+/// A proving job.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ProvingJob<N: Network> {
+    /// The job ID.
+    pub id: u64,
+    /// The request.
+    pub request: ProvingJobRequest<N>,
+    /// The status of the job.
+    pub status: ProvingJobStatus<N>,
+    /// The UNIX timestamp at which the job was submitted.
+    pub submitted_at: i64,
+    /// The UNIX timestamp at which the status of the job last changed.
+    pub updated_at: i64,
+}
+
+/// The queue of proving jobs, which is persisted to disk on every change, if a path is given.
+#[derive(Clone)]
+pub struct ProvingJobs<N: Network> {
+    /// The private key of the proving account, if one is configured.
+    private_key: Option<PrivateKey<N>>,
+    /// The path of the file the jobs are persisted to.
+    path: Option<PathBuf>,
+    /// The map of job IDs to their jobs.
+    jobs: Arc<RwLock<IndexMap<u64, ProvingJob<N>>>>,
+}
+
+impl<N: Network> ProvingJobs<N> {
+    /// Initializes the proving jobs, loading any jobs persisted at the given path.
+    /// Jobs that were being proven when the node stopped are queued again.
+    /// If the persisted jobs are corrupt, they are moved aside, and the queue starts empty.
+    pub fn load(private_key: Option<PrivateKey<N>>, path: Option<PathBuf>) -> Result<Self> {
+        let mut jobs = IndexMap::new();
+        if let Some(path) = path.as_ref().filter(|path| path.exists()) {
+            match serde_json::from_slice::<Vec<ProvingJob<N>>>(&fs::read(path)?) {
+                Ok(persisted) => {
+                    for mut job in persisted {
+                        if job.status == ProvingJobStatus::Proving {
+                            job.status = ProvingJobStatus::Queued;
+                        }
+                        jobs.insert(job.id, job);
+                    }
+                    info!("Loaded {} proving jobs from '{}'", jobs.len(), path.display());
+                }
+                Err(error) => {
+                    let corrupt_path = path.with_extension("corrupt");
+                    warn!(
+                        "The proving jobs in '{}' are corrupt, moving them to '{}' - {error}",
+                        path.display(),
+                        corrupt_path.display()
+                    );
+                    fs::rename(path, corrupt_path)?;
+                }
+            }
+        }
+        Ok(Self { private_key, path, jobs: Arc::new(RwLock::new(jobs)) })
+    }
+
+    /// Returns the private key of the proving account, if one is configured.
+    pub const fn private_key(&self) -> Option<&PrivateKey<N>> {
+        self.private_key.as_ref()
+    }
+
+    /// Returns the job for the given job ID, if it exists.
+    pub fn get(&self, id: u64) -> Option<ProvingJob<N>> {
+        self.jobs.read().get(&id).cloned()
+    }
+
+    /// Returns the number of jobs awaiting a prover.
+    pub fn num_queued(&self) -> usize {
+        self.jobs.read().values().filter(|job| job.status == ProvingJobStatus::Queued).count()
+    }
+
+    /// Queues the given request, and returns the job ID.
+    pub fn submit(&self, request: ProvingJobRequest<N>) -> Result<u64> {
+        if self.private_key.is_none() {
+            bail!("The proving jobs are disabled, as no proving key is configured")
+        }
+        let mut jobs = self.jobs.write();
+        // If the limit is reached, evict the oldest finished job.
+        if jobs.len() >= MAX_PROVING_JOBS {
+            match jobs.values().position(|job| !job.status.is_active()) {
+                Some(index) => {
+                    jobs.shift_remove_index(index);
+                }
+                None => bail!("Cannot queue more than {MAX_PROVING_JOBS} proving jobs"),
+            }
+        }
+        // Queue the job.
+        let id = jobs.keys().max().map_or(0, |id| id + 1);
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        jobs.insert(
+            id,
+            ProvingJob { id, request, status: ProvingJobStatus::Queued, submitted_at: now, updated_at: now },
+        );
+        self.persist(&jobs);
+        Ok(id)
+    }
+
+    /// Cancels the job for the given job ID, if it is queued or being proven.
+    pub fn cancel(&self, id: u64) -> Result<()> {
+        match self.jobs.read().get(&id) {
+            Some(job) if job.status.is_active() => (),
+            Some(_) => bail!("Proving job {id} has already finished"),
+            None => bail!("Missing proving job {id}"),
+        }
+        self.set_status(id, ProvingJobStatus::Cancelled);
+        Ok(())
+    }
+
+    /// Marks the oldest queued job as being proven, and returns its ID and request.
+    pub fn start_next(&self) -> Option<(u64, ProvingJobRequest<N>)> {
+        let (id, request) = self
+            .jobs
+            .read()
+            .values()
+            .find(|job| job.status == ProvingJobStatus::Queued)
+            .map(|job| (job.id, job.request.clone()))?;
+        self.set_status(id, ProvingJobStatus::Proving);
+        Some((id, request))
+    }
+
+    /// Records the outcome of the job for the given job ID, unless it was cancelled in the meantime.
+    /// Returns `true` if the outcome was recorded.
+    pub fn finish(&self, id: u64, outcome: Result<N::TransactionID>) -> bool {
+        if self.jobs.read().get(&id).map(|job| &job.status) != Some(&ProvingJobStatus::Proving) {
+            return false;
+        }
+        match outcome {
+            Ok(transaction_id) => self.set_status(id, ProvingJobStatus::Completed(transaction_id)),
+            Err(error) => self.set_status(id, ProvingJobStatus::Failed(error.to_string())),
+        }
+        true
+    }
+
+    /// Sets the status of the job for the given job ID.
+    fn set_status(&self, id: u64, status: ProvingJobStatus<N>) {
+        let mut jobs = self.jobs.write();
+        if let Some(job) = jobs.get_mut(&id) {
+            job.status = status;
+            job.updated_at = OffsetDateTime::now_utc().unix_timestamp();
+        }
+        self.persist(&jobs);
+    }
+
+    /// Writes the given jobs to disk, if a path is given.
+    fn persist(&self, jobs: &IndexMap<u64, ProvingJob<N>>) {
+        if let Some(path) = &self.path {
+            let write = || -> Result<()> {
+                // Write to a temporary file first, so the jobs are never left half-written.
+                let temp_path = path.with_extension("tmp");
+                fs::write(&temp_path, serde_json::to_vec(&jobs.values().collect::<Vec<_>>())?)?;
+                fs::rename(temp_path, path)?;
+                Ok(())
+            };
+            if let Err(error) = write() {
+                warn!("Failed to persist the proving jobs to '{}' - {error}", path.display());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use core::str::FromStr;
+    use snarkvm::prelude::{TestRng, Testnet3};
+
+    type CurrentNetwork = Testnet3;
+
+    /// Returns a sample request to execute 'credits.aleo/mint'.
+    fn sample_request() -> ProvingJobRequest<CurrentNetwork> {
+        ProvingJobRequest {
+            program_id: ProgramID::from_str("credits.aleo").unwrap(),
+            function: Identifier::from_str("mint").unwrap(),
+            inputs: vec![],
+            fee_record: None,
+            fee: 0,
+        }
+    }
+
+    #[test]
+    fn test_proving_jobs() {
+        let rng = &mut TestRng::default();
+
+        // Ensure the jobs are rejected without a proving key.
+        let jobs = ProvingJobs::<CurrentNetwork>::load(None, None).unwrap();
+        assert!(jobs.submit(sample_request()).is_err());
+
+        // Submit two jobs.
+        let jobs = ProvingJobs::<CurrentNetwork>::load(Some(PrivateKey::new(rng).unwrap()), None).unwrap();
+        assert_eq!(jobs.submit(sample_request()).unwrap(), 0);
+        assert_eq!(jobs.submit(sample_request()).unwrap(), 1);
+        assert_eq!(jobs.num_queued(), 2);
+
+        // Cancel the second job, and ensure it cannot be cancelled again.
+        jobs.cancel(1).unwrap();
+        assert_eq!(jobs.get(1).unwrap().status, ProvingJobStatus::Cancelled);
+        assert!(jobs.cancel(1).is_err());
+        assert!(jobs.cancel(2).is_err());
+
+        // Start the first job, and ensure the cancelled job is not started.
+        assert_eq!(jobs.start_next().unwrap().0, 0);
+        assert_eq!(jobs.get(0).unwrap().status, ProvingJobStatus::Proving);
+        assert!(jobs.start_next().is_none());
+
+        // Fail the first job, and ensure its outcome is recorded once.
+        assert!(jobs.finish(0, Err(anyhow!("failed"))));
+        assert_eq!(jobs.get(0).unwrap().status, ProvingJobStatus::Failed("failed".to_string()));
+        assert!(!jobs.finish(0, Err(anyhow!("failed again"))));
+    }
+
+    #[test]
+    fn test_proving_jobs_persistence() {
+        let rng = &mut TestRng::default();
+        let private_key = PrivateKey::<CurrentNetwork>::new(rng).unwrap();
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("proving-jobs.json");
+
+        // Submit and start a job.
+        let jobs = ProvingJobs::load(Some(private_key), Some(path.clone())).unwrap();
+        jobs.submit(sample_request()).unwrap();
+        assert_eq!(jobs.start_next().unwrap().0, 0);
+
+        // Reload the jobs, and ensure the job being proven is queued again.
+        let jobs = ProvingJobs::<CurrentNetwork>::load(Some(private_key), Some(path.clone())).unwrap();
+        assert_eq!(jobs.get(0).unwrap().status, ProvingJobStatus::Queued);
+
+        // Corrupt the jobs, and ensure they are moved aside on reload.
+        fs::write(&path, b"{ not json").unwrap();
+        let jobs = ProvingJobs::<CurrentNetwork>::load(Some(private_key), Some(path.clone())).unwrap();
+        assert!(jobs.get(0).is_none());
+        assert!(!path.exists());
+        assert_eq!(fs::read(path.with_extension("corrupt")).unwrap(), b"{ not json");
+    }
+}
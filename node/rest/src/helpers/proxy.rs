@@ -0,0 +1,129 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{bail, ensure, Result};
+use http::{header::HeaderName, Uri};
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+use warp::{cors::Builder, filters::BoxedFilter, Filter};
+
+/// The CORS and reverse-proxy configuration of the REST server.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RestProxy {
+    /// The origins allowed to make cross-origin requests, or none to allow any origin.
+    pub allowed_origins: Vec<String>,
+    /// The proxies trusted to report the client IP in the `X-Forwarded-For` header.
+    pub trusted_proxies: Vec<IpAddr>,
+    /// The URL path prefix of the endpoints (e.g. `api/v1`), if any.
+    pub path_prefix: Option<String>,
+}
+
+impl RestProxy {
+    /// Ensures the allowed origins are of the form `scheme://host[:port]`, and the path prefix is not empty.
+    pub fn check(&self) -> Result<()> {
+        // Ensure the origins are of the form `scheme://host[:port]`.
+        for origin in &self.allowed_origins {
+            let uri = origin.parse::<Uri>()?;
+            if uri.scheme().is_none() || uri.authority().is_none() || origin.ends_with('/') {
+                bail!("Invalid CORS origin '{origin}' (expected 'scheme://host[:port]')");
+            }
+        }
+        // Ensure the path prefix is not empty.
+        if let Some(prefix) = &self.path_prefix {
+            ensure!(prefix.split('/').any(|segment| !segment.is_empty()), "Invalid REST path prefix '{prefix}'");
+        }
+        Ok(())
+    }
+
+    /// Returns the IP of the client, given the IP of the remote peer and its `X-Forwarded-For` header.
+    /// The header is only honored when sent by a trusted proxy, and is read from the nearest hop,
+    /// so that clients cannot spoof their IP by prepending to it.
+    pub fn client_ip(&self, remote_ip: IpAddr, forwarded_for: Option<&str>) -> IpAddr {
+        let mut client_ip = remote_ip;
+        if let Some(forwarded_for) = forwarded_for {
+            for hop in forwarded_for.rsplit(',') {
+                // Stop once the hop was reported by an untrusted peer.
+                if !self.trusted_proxies.contains(&client_ip) {
+                    break;
+                }
+                match hop.trim().parse() {
+                    Ok(ip) => client_ip = ip,
+                    Err(_) => break,
+                }
+            }
+        }
+        client_ip
+    }
+
+    /// Returns the CORS policy of the REST server.
+    pub fn cors(&self) -> Builder {
+        let cors = warp::cors()
+            .allow_header(HeaderName::from_static("content-type"))
+            .allow_methods(vec!["GET", "POST", "OPTIONS"]);
+        match self.allowed_origins.is_empty() {
+            true => cors.allow_any_origin(),
+            false => cors.allow_origins(self.allowed_origins.iter().map(String::as_str)),
+        }
+    }
+
+    /// Returns a filter that matches the URL path prefix, if any.
+    pub fn path_prefix(&self) -> BoxedFilter<()> {
+        let segments = self.path_prefix.iter().flat_map(|prefix| prefix.split('/')).filter(|s| !s.is_empty());
+        segments.fold(warp::any().boxed(), |filter, segment| filter.and(warp::path(segment.to_string())).boxed())
+    }
+}
+
+/// A middleware to include the client IP in the handler, as reported by the trusted proxies.
+pub fn with_client_ip(proxy: Arc<RestProxy>) -> impl Filter<Extract = (Option<IpAddr>,), Error = Infallible> + Clone {
+    warp::addr::remote().and(warp::header::optional::<String>("x-forwarded-for")).map(
+        move |addr: Option<SocketAddr>, forwarded_for: Option<String>| {
+            addr.map(|addr| proxy.client_ip(addr.ip(), forwarded_for.as_deref()))
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_ip() {
+        let proxy = RestProxy { trusted_proxies: vec![IpAddr::from([10, 0, 0, 1])], ..Default::default() };
+        let (client, spoofed) = (IpAddr::from([1, 2, 3, 4]), "5.6.7.8");
+
+        // Ensure the header is ignored when sent by an untrusted peer.
+        assert_eq!(proxy.client_ip(client, Some(spoofed)), client);
+        // Ensure the header is honored when sent by a trusted proxy.
+        assert_eq!(proxy.client_ip(IpAddr::from([10, 0, 0, 1]), Some("1.2.3.4")), client);
+        assert_eq!(proxy.client_ip(IpAddr::from([10, 0, 0, 1]), None), IpAddr::from([10, 0, 0, 1]));
+        // Ensure the entries prepended by the client are ignored.
+        assert_eq!(proxy.client_ip(IpAddr::from([10, 0, 0, 1]), Some("5.6.7.8, 1.2.3.4")), client);
+        // Ensure chains of trusted proxies are followed.
+        assert_eq!(proxy.client_ip(IpAddr::from([10, 0, 0, 1]), Some("5.6.7.8, 1.2.3.4, 10.0.0.1")), client);
+    }
+
+    #[tokio::test]
+    async fn test_path_prefix() {
+        let proxy = RestProxy { path_prefix: Some("/api/v1/".to_string()), ..Default::default() };
+        let filter = proxy.path_prefix().and(warp::path!("testnet3" / "latest" / "height")).map(warp::reply);
+
+        assert!(warp::test::request().path("/api/v1/testnet3/latest/height").matches(&filter).await);
+        assert!(!warp::test::request().path("/testnet3/latest/height").matches(&filter).await);
+    }
+}
@@ -0,0 +1,267 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkos_node_ledger::Ledger;
+use snarkos_node_store::Indexes;
+use snarkvm::prelude::{Address, ConsensusStorage, Network, ProgramID};
+
+use anyhow::{bail, ensure, Result};
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of rows a ledger query may return.
+pub const MAX_QUERY_LIMIT: usize = 1000;
+/// The maximum number of index entries a ledger query may examine, which bounds its cost regardless of its result.
+pub const MAX_QUERY_SCANNED: usize = 10_000;
+
+/// The kind of rows selected by a ledger query.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryTarget {
+    /// The transactions with the filtered address in a public input or output.
+    Transactions,
+    /// The transactions that deployed the filtered program.
+    Deployments,
+}
+
+/// A constrained query of the ledger, which is answered from the secondary indexes.
+/// A query must filter by the key of the index of its target: `address` for transactions,
+/// and `program` for deployments.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(bound = "", deny_unknown_fields)]
+pub struct LedgerQuery<N: Network> {
+    /// The kind of rows to select.
+    select: QueryTarget,
+    /// The filter of the rows.
+    #[serde(default, rename = "where")]
+    filter: QueryFilter<N>,
+    /// The maximum number of rows to return.
+    #[serde(default = "default_query_limit")]
+    limit: usize,
+}
+
+/// Returns the default number of rows of a ledger query.
+const fn default_query_limit() -> usize {
+    100
+}
+
+/// The filter of a ledger query, which matches a row if it meets every criterion that is set.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(bound = "", deny_unknown_fields)]
+pub struct QueryFilter<N: Network> {
+    /// The address in a public input or output of the transaction.
+    address: Option<Address<N>>,
+    /// The program that a transition of the transaction calls, or that the transaction deploys.
+    program: Option<ProgramID<N>>,
+    /// The range of the height of the block that contains the transaction.
+    #[serde(default)]
+    height: HeightRange,
+}
+
+impl<N: Network> Default for QueryFilter<N> {
+    /// Returns a filter that matches every row.
+    fn default() -> Self {
+        Self { address: None, program: None, height: Default::default() }
+    }
+}
+
+/// An inclusive range of block heights, which is unbounded on each side that is not set.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HeightRange {
+    /// The minimum height.
+    gte: Option<u32>,
+    /// The maximum height.
+    lte: Option<u32>,
+}
+
+/// A row of the result of a ledger query.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(bound = "", rename_all = "camelCase")]
+pub struct QueryRow<N: Network> {
+    /// The transaction ID.
+    pub transaction_id: N::TransactionID,
+    /// The height of the block that contains the transaction.
+    pub height: u32,
+}
+
+/// The result of a ledger query.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct QueryResult<N: Network> {
+    /// The matching rows, in the order they were indexed, which is the order of the ledger.
+    pub rows: Vec<QueryRow<N>>,
+    /// The number of index entries the query examined.
+    pub scanned: usize,
+}
+
+impl<N: Network> LedgerQuery<N> {
+    /// Executes the query against the given secondary indexes and ledger. The query fails if it would examine
+    /// more than `MAX_QUERY_SCANNED` index entries, instead of returning a partial result.
+    pub fn execute<C: ConsensusStorage<N>>(
+        &self,
+        indexes: &Indexes<N>,
+        ledger: &Ledger<N, C>,
+    ) -> Result<QueryResult<N>> {
+        ensure!(self.limit <= MAX_QUERY_LIMIT, "A query cannot return more than {MAX_QUERY_LIMIT} rows");
+
+        // Retrieve the candidate transactions from the index of the target, from the minimum height.
+        // The index is ordered by height, so the candidates below the minimum height are never read.
+        let from_height = self.filter.height.gte.unwrap_or(0);
+        let candidates: Box<dyn Iterator<Item = (u32, N::TransactionID)>> =
+            match (self.select, self.filter.address, self.filter.program) {
+                (QueryTarget::Transactions, Some(address), _) => {
+                    Box::new(indexes.transactions_by_address().iter_from_height(&address, from_height)?)
+                }
+                (QueryTarget::Deployments, _, Some(program)) => {
+                    Box::new(indexes.deployments_by_program().iter_from_height(&program, from_height)?)
+                }
+                (QueryTarget::Transactions, None, _) => bail!("A query of transactions must filter by 'address'"),
+                (QueryTarget::Deployments, _, None) => bail!("A query of deployments must filter by 'program'"),
+            };
+
+        let mut rows = Vec::new();
+        let mut scanned = 0;
+        for (height, transaction_id) in candidates {
+            if rows.len() >= self.limit {
+                break;
+            }
+            // Stop at the first candidate above the maximum height.
+            if self.filter.height.lte.map_or(false, |lte| height > lte) {
+                break;
+            }
+            ensure!(
+                scanned < MAX_QUERY_SCANNED,
+                "The query examined {MAX_QUERY_SCANNED} index entries without completing, narrow its filter"
+            );
+            scanned += 1;
+            // Ensure a transition of the transaction calls the program.
+            if let (QueryTarget::Transactions, Some(program)) = (self.select, self.filter.program) {
+                let transaction = ledger.get_transaction(transaction_id)?;
+                if !transaction.transitions().any(|transition| *transition.program_id() == program) {
+                    continue;
+                }
+            }
+            rows.push(QueryRow { transaction_id, height });
+        }
+
+        Ok(QueryResult { rows, scanned })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_store::{rocksdb::RocksDB, AddressTransactions, IndexDefinition, StorageLayout};
+    use snarkvm::{
+        prelude::{Block, FromBytes, Testnet3},
+        synthesizer::ConsensusMemory,
+    };
+
+    use std::str::FromStr;
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_ledger_query() {
+        // The filter and limit have defaults.
+        let query: LedgerQuery<CurrentNetwork> = serde_json::from_str(r#"{ "select": "deployments" }"#).unwrap();
+        assert_eq!(query.select, QueryTarget::Deployments);
+        assert_eq!(query.filter, QueryFilter::default());
+        assert_eq!(query.limit, 100);
+
+        // The filter criteria are parsed under 'where'.
+        let query: LedgerQuery<CurrentNetwork> = serde_json::from_str(
+            r#"{
+                "select": "transactions",
+                "where": { "program": "credits.aleo", "height": { "gte": 10 } },
+                "limit": 5
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(query.select, QueryTarget::Transactions);
+        assert_eq!(query.filter.address, None);
+        assert_eq!(query.filter.program, Some(ProgramID::from_str("credits.aleo").unwrap()));
+        assert_eq!(query.filter.height, HeightRange { gte: Some(10), lte: None });
+        assert_eq!(query.limit, 5);
+
+        // An unknown target or criterion is rejected.
+        assert!(serde_json::from_str::<LedgerQuery<CurrentNetwork>>(r#"{ "select": "blocks" }"#).is_err());
+        let result = serde_json::from_str::<LedgerQuery<CurrentNetwork>>(
+            r#"{ "select": "transactions", "where": { "function": "transfer" } }"#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_execute_ledger_query() {
+        // Open the secondary indexes in a temporary directory, and index the genesis block.
+        let directory = tempfile::tempdir().unwrap();
+        let layout = StorageLayout { ledger_dir: Some(directory.path().to_path_buf()), ..Default::default() };
+        RocksDB::open_with_layout(CurrentNetwork::ID, None, &layout).unwrap();
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let ledger = Ledger::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::load(genesis.clone(), None).unwrap();
+        let indexes = Indexes::<CurrentNetwork>::open(None).unwrap();
+        indexes.index_block(&genesis).unwrap();
+
+        // Select the genesis transactions of the first address they mint to.
+        let (address, _) = <AddressTransactions as IndexDefinition<CurrentNetwork>>::entries(&genesis)[0];
+        let expected = indexes.transactions_by_address().get(&address).unwrap();
+        assert!(!expected.is_empty());
+        let query = |json: serde_json::Value| serde_json::from_value::<LedgerQuery<CurrentNetwork>>(json).unwrap();
+
+        // Ensure every transaction of the address is returned, along with the height of its block.
+        let result = query(serde_json::json!({ "select": "transactions", "where": { "address": address } }))
+            .execute(&indexes, &ledger)
+            .unwrap();
+        let rows: Vec<_> = result.rows.iter().map(|row| (row.transaction_id, row.height)).collect();
+        assert_eq!(rows, expected.iter().map(|transaction_id| (*transaction_id, 0)).collect::<Vec<_>>());
+        assert_eq!(result.scanned, expected.len());
+
+        // Ensure the program filter keeps the transactions that call the program.
+        let result = query(serde_json::json!({
+            "select": "transactions",
+            "where": { "address": address, "program": "credits.aleo" },
+            "limit": 1
+        }))
+        .execute(&indexes, &ledger)
+        .unwrap();
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.scanned, 1);
+
+        // Ensure the height range bounds the scan, without examining the entries below it.
+        let result = query(serde_json::json!({
+            "select": "transactions",
+            "where": { "address": address, "height": { "gte": 1 } }
+        }))
+        .execute(&indexes, &ledger)
+        .unwrap();
+        assert!(result.rows.is_empty());
+        assert_eq!(result.scanned, 0);
+
+        // Ensure the genesis block has no deployments, and a query must filter by the key of its index.
+        let result = query(serde_json::json!({ "select": "deployments", "where": { "program": "credits.aleo" } }))
+            .execute(&indexes, &ledger)
+            .unwrap();
+        assert!(result.rows.is_empty());
+        assert!(query(serde_json::json!({ "select": "deployments" })).execute(&indexes, &ledger).is_err());
+        let query = query(serde_json::json!({
+            "select": "transactions",
+            "where": { "address": address },
+            "limit": MAX_QUERY_LIMIT + 1
+        }));
+        assert!(query.execute(&indexes, &ledger).is_err());
+    }
+}
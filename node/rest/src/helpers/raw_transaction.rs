@@ -0,0 +1,120 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::{
+    prelude::{FromBytes, Network},
+    synthesizer::Transaction,
+};
+
+use bech32::FromBase32;
+use serde::Serialize;
+
+/// The human-readable part of a bech32-encoded raw transaction.
+pub const RAW_TRANSACTION_HRP: &str = "tx";
+
+/// The reason a raw transaction was rejected.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "reason", content = "message", rename_all = "lowercase")]
+pub enum RawTransactionError {
+    /// The transaction is not valid hex or bech32.
+    Encoding(String),
+    /// The transaction bytes are not a valid transaction.
+    Deserialization(String),
+    /// The transaction was rejected by the memory pool.
+    Validation(String),
+}
+
+/// Decodes the bytes of the given raw transaction, which is either hex-encoded (optionally with a `0x` prefix),
+/// or bech32-encoded with the `tx` human-readable part.
+pub fn decode_raw_bytes(raw: &str) -> Result<Vec<u8>, RawTransactionError> {
+    let raw = raw.trim();
+
+    // Decode the bech32-encoded transaction.
+    let prefix = format!("{RAW_TRANSACTION_HRP}1");
+    if raw.get(..prefix.len()).map(|start| start.eq_ignore_ascii_case(&prefix)).unwrap_or(false) {
+        let (hrp, data, _) = bech32::decode(raw).map_err(|error| RawTransactionError::Encoding(error.to_string()))?;
+        if hrp != RAW_TRANSACTION_HRP {
+            return Err(RawTransactionError::Encoding(format!("Invalid human-readable part '{hrp}'")));
+        }
+        return Vec::<u8>::from_base32(&data).map_err(|error| RawTransactionError::Encoding(error.to_string()));
+    }
+
+    // Decode the hex-encoded transaction.
+    hex::decode(raw.trim_start_matches("0x")).map_err(|error| RawTransactionError::Encoding(error.to_string()))
+}
+
+/// Decodes the given raw transaction, which is either hex-encoded or bech32-encoded.
+/// As this deserializes the whole transaction, it should be called from a blocking thread.
+pub fn decode_raw_transaction<N: Network>(raw: &str) -> Result<Transaction<N>, RawTransactionError> {
+    let bytes = decode_raw_bytes(raw)?;
+    Transaction::from_bytes_le(&bytes).map_err(|error| RawTransactionError::Deserialization(error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::Testnet3;
+
+    use bech32::{ToBase32, Variant};
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_decode_raw_bytes() {
+        let bytes = vec![0u8, 1, 2, 3, 254, 255];
+        let bech32m = bech32::encode(RAW_TRANSACTION_HRP, bytes.to_base32(), Variant::Bech32m).unwrap();
+        let bech32 = bech32::encode(RAW_TRANSACTION_HRP, bytes.to_base32(), Variant::Bech32).unwrap();
+
+        // Ensure the hex and bech32 encodings decode to the same bytes.
+        assert_eq!(decode_raw_bytes("00010203feff").unwrap(), bytes);
+        assert_eq!(decode_raw_bytes(" 0x00010203FEFF\n").unwrap(), bytes);
+        assert_eq!(decode_raw_bytes(&bech32m).unwrap(), bytes);
+        assert_eq!(decode_raw_bytes(&bech32).unwrap(), bytes);
+        assert_eq!(decode_raw_bytes(&bech32m.to_uppercase()).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_decode_raw_bytes_invalid() {
+        let bytes = vec![0u8, 1, 2, 3];
+
+        // Ensure invalid hex is rejected.
+        assert!(matches!(decode_raw_bytes("0x0g"), Err(RawTransactionError::Encoding(_))));
+        assert!(matches!(decode_raw_bytes("abc"), Err(RawTransactionError::Encoding(_))));
+
+        // Ensure a bech32 string with an invalid checksum is rejected.
+        let mut encoded = bech32::encode(RAW_TRANSACTION_HRP, bytes.to_base32(), Variant::Bech32m).unwrap();
+        let last = encoded.pop().unwrap();
+        encoded.push(if last == 'q' { 'p' } else { 'q' });
+        assert!(matches!(decode_raw_bytes(&encoded), Err(RawTransactionError::Encoding(_))));
+
+        // Ensure a bech32 string with another human-readable part is treated as hex, and rejected.
+        let encoded = bech32::encode("at", bytes.to_base32(), Variant::Bech32m).unwrap();
+        assert!(matches!(decode_raw_bytes(&encoded), Err(RawTransactionError::Encoding(_))));
+    }
+
+    #[test]
+    fn test_decode_raw_transaction_invalid() {
+        let bytes = vec![0u8; 32];
+        let hex = hex::encode(&bytes);
+        let bech32 = bech32::encode(RAW_TRANSACTION_HRP, bytes.to_base32(), Variant::Bech32m).unwrap();
+
+        // Ensure well-encoded bytes which are not a transaction are rejected at deserialization.
+        for raw in [hex, bech32] {
+            let result = decode_raw_transaction::<CurrentNetwork>(&raw);
+            assert!(matches!(result, Err(RawTransactionError::Deserialization(_))));
+        }
+    }
+}
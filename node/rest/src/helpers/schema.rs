@@ -0,0 +1,154 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::RestError;
+
+use anyhow::{bail, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use warp::{reject, Filter, Rejection};
+
+/// The top-level fields of the canonical JSON representation of a block. These fields are stable:
+/// new fields may be added, but these are never renamed or removed. The `coinbase` field is omitted
+/// from blocks without a coinbase solution.
+pub const BLOCK_FIELDS: [&str; 6] = ["block_hash", "previous_hash", "header", "transactions", "coinbase", "signature"];
+
+/// The top-level fields of the canonical JSON representation of a transaction. These fields are stable:
+/// new fields may be added, but these are never renamed or removed. A `deploy` transaction has the `owner`
+/// and `deployment` fields, and an `execute` transaction has the `execution` field.
+pub const TRANSACTION_FIELDS: [&str; 6] = ["type", "id", "owner", "deployment", "execution", "fee"];
+
+/// The handling of the fields outside the canonical representation of a JSON request body.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum JsonMode {
+    /// The unknown fields are ignored.
+    #[default]
+    Lenient,
+    /// The unknown fields are rejected.
+    Strict,
+}
+
+/// Deserializes the given JSON value. In strict mode, the value is rejected if it has any field
+/// that is absent from its canonical representation, i.e. that the deserializer would silently drop.
+pub fn from_json<T: DeserializeOwned + Serialize>(mode: JsonMode, value: Value) -> Result<T> {
+    match mode {
+        JsonMode::Lenient => Ok(serde_json::from_value(value)?),
+        JsonMode::Strict => {
+            let object: T = serde_json::from_value(value.clone())?;
+            let unknown_fields = unknown_fields(&value, &serde_json::to_value(&object)?);
+            match unknown_fields.is_empty() {
+                true => Ok(object),
+                false => bail!("Unknown fields '{}'", unknown_fields.join("', '")),
+            }
+        }
+    }
+}
+
+/// Returns a filter that deserializes the JSON request body with the given JSON mode.
+pub fn json_body<T: 'static + DeserializeOwned + Serialize + Send>(
+    mode: JsonMode,
+) -> impl Filter<Extract = (T,), Error = Rejection> + Clone {
+    warp::body::json::<Value>().and_then(move |value: Value| async move {
+        from_json::<T>(mode, value)
+            .map_err(|error| reject::custom(RestError::Request(format!("Invalid request body - {error}"))))
+    })
+}
+
+/// Returns the paths of the fields in the given JSON input that are absent from the given canonical representation.
+/// Fields with a `null` value are treated as absent from the input.
+pub fn unknown_fields(input: &Value, canonical: &Value) -> Vec<String> {
+    let mut unknown_fields = Vec::new();
+    collect_unknown_fields(input, canonical, "", &mut unknown_fields);
+    unknown_fields
+}
+
+/// Appends the paths of the fields in the given JSON input that are absent from the given canonical representation.
+fn collect_unknown_fields(input: &Value, canonical: &Value, path: &str, unknown_fields: &mut Vec<String>) {
+    match (input, canonical) {
+        (Value::Object(input), Value::Object(canonical)) => {
+            for (key, value) in input.iter().filter(|(_, value)| !value.is_null()) {
+                let field_path = match path.is_empty() {
+                    true => key.to_string(),
+                    false => format!("{path}.{key}"),
+                };
+                match canonical.get(key) {
+                    Some(canonical) => collect_unknown_fields(value, canonical, &field_path, unknown_fields),
+                    None => unknown_fields.push(field_path),
+                }
+            }
+        }
+        (Value::Array(input), Value::Array(canonical)) => {
+            for (index, (value, canonical)) in input.iter().zip(canonical).enumerate() {
+                collect_unknown_fields(value, canonical, &format!("{path}[{index}]"), unknown_fields);
+            }
+        }
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{Block, FromBytes, Network, Testnet3};
+
+    use serde_json::json;
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_unknown_fields() {
+        let canonical = json!({ "id": 1, "fee": { "amount": 2 }, "transitions": [{ "program": "credits.aleo" }] });
+
+        // Ensure the canonical representation has no unknown fields, nor do its subsets.
+        assert!(unknown_fields(&canonical, &canonical).is_empty());
+        assert!(unknown_fields(&json!({ "id": 1 }), &canonical).is_empty());
+        // Ensure the fields with a `null` value are ignored.
+        assert!(unknown_fields(&json!({ "id": 1, "extra": null }), &canonical).is_empty());
+
+        // Ensure the unknown fields are reported with their paths.
+        let input = json!({
+            "id": 1,
+            "extra": true,
+            "fee": { "amount": 2, "priority": 3 },
+            "transitions": [{ "program": "credits.aleo", "function": "transfer" }]
+        });
+        assert_eq!(unknown_fields(&input, &canonical), ["extra", "fee.priority", "transitions[0].function"]);
+    }
+
+    #[test]
+    fn test_canonical_fields() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+
+        // Ensure the block serializes with its stable fields, except the coinbase of the genesis block.
+        let block = serde_json::to_value(&genesis).unwrap();
+        for field in BLOCK_FIELDS.iter().filter(|field| **field != "coinbase") {
+            assert!(block.get(field).is_some(), "Missing block field '{field}'");
+        }
+        // Ensure the block has no fields outside of its stable fields.
+        assert!(block.as_object().unwrap().keys().all(|key| BLOCK_FIELDS.contains(&key.as_str())));
+
+        // Ensure the transactions serialize with their stable fields only.
+        for transaction in genesis.transactions().values() {
+            let transaction = serde_json::to_value(transaction).unwrap();
+            assert!(transaction.as_object().unwrap().keys().all(|key| TRANSACTION_FIELDS.contains(&key.as_str())));
+            assert!(transaction.get("id").is_some() && transaction.get("type").is_some());
+        }
+
+        // Ensure a canonical block round trips without unknown fields.
+        let round_trip = from_json::<Block<CurrentNetwork>>(JsonMode::Strict, block.clone()).unwrap();
+        assert!(unknown_fields(&block, &serde_json::to_value(round_trip).unwrap()).is_empty());
+    }
+}
@@ -0,0 +1,566 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::{DeadlineEvent, Deadlines};
+use snarkos_node_consensus::{BlockNotification, CommitteeChange, Consensus, RoundTimings};
+use snarkvm::prelude::{
+    Address,
+    Block,
+    ConsensusStorage,
+    Identifier,
+    Input,
+    Literal,
+    Network,
+    Output,
+    Plaintext,
+    ProgramID,
+    Transaction,
+};
+
+use anyhow::Result;
+use futures_util::{SinkExt, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::broadcast::{self, error::RecvError};
+use warp::ws::{Message, WebSocket};
+
+/// A subscription request from a WebSocket client.
+#[derive(Deserialize)]
+#[serde(bound = "")]
+struct SubscriptionRequest<N: Network> {
+    /// The request ID, which is echoed in the response.
+    #[serde(default)]
+    id: Value,
+    /// The requested method.
+    method: String,
+    /// The parameters of the request.
+    #[serde(default)]
+    params: SubscriptionParams<N>,
+}
+
+/// The parameters of a subscription request.
+#[derive(Default, Deserialize)]
+#[serde(bound = "")]
+struct SubscriptionParams<N: Network> {
+    /// If `true`, the full transactions are sent, instead of the transaction IDs.
+    #[serde(default)]
+    full: bool,
+    /// The filter of the pending transactions.
+    #[serde(flatten)]
+    filter: TransactionFilter<N>,
+}
+
+/// A filter of the pending transactions sent to a WebSocket client, which matches a transaction
+/// if it meets every criterion that is set.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(bound = "", rename_all = "camelCase")]
+struct TransactionFilter<N: Network> {
+    /// The program that a transition of the transaction must call.
+    program_id: Option<ProgramID<N>>,
+    /// The function that a transition of the transaction must call.
+    function_name: Option<Identifier<N>>,
+    /// The address that must appear in a public input or output of the transaction.
+    address: Option<Address<N>>,
+    /// The minimum fee of the transaction, in microcredits.
+    min_fee: Option<u64>,
+}
+
+impl<N: Network> Default for TransactionFilter<N> {
+    /// Returns a filter that matches every transaction.
+    fn default() -> Self {
+        Self { program_id: None, function_name: None, address: None, min_fee: None }
+    }
+}
+
+impl<N: Network> TransactionFilter<N> {
+    /// Returns `true` if the given transaction meets every criterion of the filter.
+    fn matches(&self, transaction: &Transaction<N>) -> bool {
+        // Ensure a transition calls the requested program and function.
+        if self.program_id.is_some() || self.function_name.is_some() {
+            let is_called = transaction.transitions().any(|transition| {
+                self.program_id.map_or(true, |program_id| *transition.program_id() == program_id)
+                    && self.function_name.map_or(true, |function_name| *transition.function_name() == function_name)
+            });
+            if !is_called {
+                return false;
+            }
+        }
+        // Ensure the address appears in a public input or output.
+        if let Some(address) = self.address {
+            let is_address = |plaintext: &Plaintext<N>| match plaintext {
+                Plaintext::Literal(Literal::Address(candidate), _) => *candidate == address,
+                _ => false,
+            };
+            let is_involved = transaction.transitions().any(|transition| {
+                transition.inputs().iter().any(|input| match input {
+                    Input::Public(_, Some(plaintext)) => is_address(plaintext),
+                    _ => false,
+                }) || transition.outputs().iter().any(|output| match output {
+                    Output::Public(_, Some(plaintext)) => is_address(plaintext),
+                    _ => false,
+                })
+            });
+            if !is_involved {
+                return false;
+            }
+        }
+        // Ensure the fee meets the minimum.
+        match self.min_fee {
+            Some(min_fee) => transaction.fee().map_or(false, |fee| *fee >= min_fee),
+            None => true,
+        }
+    }
+}
+
+/// A pending transaction, as sent to a WebSocket client.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum PendingTransaction<N: Network> {
+    /// The full transaction.
+    Full(Transaction<N>),
+    /// The transaction ID.
+    Id(N::TransactionID),
+}
+
+/// The subscribers of a WebSocket client.
+struct Subscribers<N: Network> {
+    /// The subscriber to the newly accepted blocks.
+    blocks: Option<broadcast::Receiver<BlockNotification<N>>>,
+    /// The subscriber to the pending transactions.
+    transactions: Option<TransactionSubscriber<N>>,
+    /// The subscriber to the timings of the rounds in which this node produced a block.
+    rounds: Option<broadcast::Receiver<RoundTimings>>,
+    /// The subscriber to the deadline submissions resolved as included or expired.
+    deadlines: Option<broadcast::Receiver<DeadlineEvent<N>>>,
+    /// The subscriber to the changes to the beacon set.
+    committee: Option<broadcast::Receiver<CommitteeChange<N>>>,
+}
+
+/// A subscriber to the pending transactions.
+struct TransactionSubscriber<N: Network> {
+    /// The receiver of the pending transactions.
+    receiver: broadcast::Receiver<Transaction<N>>,
+    /// Whether to send the full transactions, instead of the transaction IDs.
+    full: bool,
+    /// The filter of the pending transactions.
+    filter: TransactionFilter<N>,
+}
+
+/// Serves the subscription requests of a WebSocket client, until the connection is closed.
+pub async fn serve_subscriptions<N: Network, C: ConsensusStorage<N>>(
+    socket: WebSocket,
+    consensus: Consensus<N, C>,
+    deadlines: Deadlines<N>,
+) {
+    let (mut sender, mut receiver) = socket.split();
+    let mut subscribers =
+        Subscribers::<N> { blocks: None, transactions: None, rounds: None, deadlines: None, committee: None };
+
+    loop {
+        let reply = tokio::select! {
+            // Handle the next request from the client.
+            message = receiver.next() => match message {
+                Some(Ok(message)) if message.is_text() => {
+                    handle_request(message.to_str().unwrap_or_default(), &consensus, &deadlines, &mut subscribers)
+                }
+                Some(Ok(message)) if message.is_close() => break,
+                Some(Ok(_)) => continue,
+                Some(Err(error)) => {
+                    debug!("WebSocket connection error - {error}");
+                    break;
+                }
+                None => break,
+            },
+            // Forward the next block to the client.
+            notification = recv(&mut subscribers.blocks) => match notification {
+                Ok(block) => json!({ "subscription": "newBlocks", "result": block }),
+                Err(RecvError::Lagged(num_skipped)) => {
+                    json!({ "subscription": "newBlocks", "error": format!("Skipped {num_skipped} blocks") })
+                }
+                Err(RecvError::Closed) => break,
+            },
+            // Forward the next pending transaction to the client.
+            notification = recv_transaction(&mut subscribers.transactions) => match notification {
+                Ok(transaction) => json!({ "subscription": "pendingTransactions", "result": transaction }),
+                Err(RecvError::Lagged(num_skipped)) => {
+                    json!({ "subscription": "pendingTransactions", "error": format!("Skipped {num_skipped} transactions") })
+                }
+                Err(RecvError::Closed) => break,
+            },
+            // Forward the timings of the next round to the client.
+            notification = recv(&mut subscribers.rounds) => match notification {
+                Ok(timings) => json!({ "subscription": "rounds", "result": timings }),
+                Err(RecvError::Lagged(num_skipped)) => {
+                    json!({ "subscription": "rounds", "error": format!("Skipped {num_skipped} rounds") })
+                }
+                Err(RecvError::Closed) => break,
+            },
+            // Forward the next resolved deadline submission to the client.
+            notification = recv(&mut subscribers.deadlines) => match notification {
+                Ok(event) => json!({ "subscription": "deadlines", "result": event }),
+                Err(RecvError::Lagged(num_skipped)) => {
+                    json!({ "subscription": "deadlines", "error": format!("Skipped {num_skipped} deadline events") })
+                }
+                Err(RecvError::Closed) => break,
+            },
+            // Forward the next change to the beacon set to the client.
+            notification = recv(&mut subscribers.committee) => match notification {
+                Ok(change) => json!({ "subscription": "committee", "result": change }),
+                Err(RecvError::Lagged(num_skipped)) => {
+                    json!({ "subscription": "committee", "error": format!("Skipped {num_skipped} committee changes") })
+                }
+                Err(RecvError::Closed) => break,
+            },
+        };
+
+        // Send the reply to the client.
+        if sender.send(Message::text(reply.to_string())).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Sends the blocks of the given stream to a WebSocket client as `{ "height", "block" }` messages,
+/// until the stream ends or the connection is closed.
+pub async fn serve_block_stream<N: Network>(socket: WebSocket, blocks: impl Stream<Item = Result<Block<N>>>) {
+    let (mut sender, mut receiver) = socket.split();
+    futures_util::pin_mut!(blocks);
+
+    loop {
+        let reply = tokio::select! {
+            // Ignore the messages from the client, until the connection is closed.
+            message = receiver.next() => match message {
+                Some(Ok(message)) if !message.is_close() => continue,
+                _ => break,
+            },
+            // Forward the next block to the client.
+            block = blocks.next() => match block {
+                Some(Ok(block)) => json!({ "height": block.height(), "block": block }),
+                Some(Err(error)) => {
+                    let _ = sender.send(Message::text(json!({ "error": error.to_string() }).to_string())).await;
+                    break;
+                }
+                None => break,
+            },
+        };
+
+        // Send the block to the client.
+        if sender.send(Message::text(reply.to_string())).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// A template of the next block, as sent to an external builder.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "", rename_all = "camelCase")]
+pub struct BlockTemplate<N: Network> {
+    /// The height of the next block.
+    pub height: u32,
+    /// The hash of the block that the next block extends.
+    pub previous_hash: N::BlockHash,
+}
+
+/// A bundle of transactions from an external builder, in the order it proposes for the next block.
+#[derive(Deserialize)]
+#[serde(bound = "")]
+struct BundleRequest<N: Network> {
+    /// The request ID, which is echoed in the response.
+    #[serde(default)]
+    id: Value,
+    /// The height of the block that the bundle is for.
+    height: u32,
+    /// The transactions of the bundle.
+    transactions: Vec<Transaction<N>>,
+}
+
+/// Serves an external builder, until the connection is closed. The builder is sent a template of the next block
+/// whenever a block is accepted, starting with the given one, and may submit one bundle per block.
+pub async fn serve_builder<N: Network, C: ConsensusStorage<N>>(
+    socket: WebSocket,
+    builder: String,
+    consensus: Consensus<N, C>,
+    mut subscriber: broadcast::Receiver<BlockNotification<N>>,
+    template: BlockTemplate<N>,
+) {
+    let (mut sender, mut receiver) = socket.split();
+
+    // Send the template of the next block.
+    if sender.send(Message::text(json!({ "template": template }).to_string())).await.is_err() {
+        return;
+    }
+
+    loop {
+        let reply = tokio::select! {
+            // Handle the next bundle from the builder.
+            message = receiver.next() => match message {
+                Some(Ok(message)) if message.is_text() => {
+                    handle_bundle(message.to_str().unwrap_or_default(), &builder, &consensus).await
+                }
+                Some(Ok(message)) if message.is_close() => break,
+                Some(Ok(_)) => continue,
+                Some(Err(error)) => {
+                    debug!("WebSocket connection error - {error}");
+                    break;
+                }
+                None => break,
+            },
+            // Send the template of the block after the next accepted block.
+            notification = subscriber.recv() => match notification {
+                Ok(block) => {
+                    let template = BlockTemplate::<N> { height: block.height + 1, previous_hash: block.hash };
+                    json!({ "template": template })
+                }
+                // Skip ahead to the latest block, as the missed templates are stale.
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            },
+        };
+
+        // Send the reply to the builder.
+        if sender.send(Message::text(reply.to_string())).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Handles a bundle from an external builder, and returns the response to the builder.
+async fn handle_bundle<N: Network, C: ConsensusStorage<N>>(
+    request: &str,
+    builder: &str,
+    consensus: &Consensus<N, C>,
+) -> Value {
+    // Parse the bundle.
+    let request: BundleRequest<N> = match serde_json::from_str(request) {
+        Ok(request) => request,
+        Err(error) => return json!({ "id": Value::Null, "error": format!("Invalid bundle - {error}") }),
+    };
+
+    // Check the bundle on a blocking thread, as its transactions are verified.
+    let (consensus, builder) = (consensus.clone(), builder.to_string());
+    let (height, transactions) = (request.height, request.transactions);
+    match tokio::task::spawn_blocking(move || consensus.submit_bundle(builder, height, transactions)).await {
+        Ok(Ok(receipt)) => json!({ "id": request.id, "result": receipt }),
+        Ok(Err(error)) => json!({ "id": request.id, "error": error.to_string() }),
+        Err(error) => json!({ "id": request.id, "error": format!("Failed to check the bundle - {error}") }),
+    }
+}
+
+/// Handles a subscription request, and returns the response to the client.
+fn handle_request<N: Network, C: ConsensusStorage<N>>(
+    request: &str,
+    consensus: &Consensus<N, C>,
+    deadlines: &Deadlines<N>,
+    subscribers: &mut Subscribers<N>,
+) -> Value {
+    // Parse the request.
+    let request: SubscriptionRequest<N> = match serde_json::from_str(request) {
+        Ok(request) => request,
+        Err(error) => return json!({ "id": Value::Null, "error": format!("Invalid request - {error}") }),
+    };
+
+    match request.method.as_str() {
+        "subscribeNewBlocks" => {
+            subscribers.blocks = Some(consensus.subscriptions().subscribe_blocks());
+            json!({ "id": request.id, "result": "newBlocks" })
+        }
+        "unsubscribeNewBlocks" => {
+            subscribers.blocks = None;
+            json!({ "id": request.id, "result": true })
+        }
+        "subscribePendingTransactions" => {
+            subscribers.transactions = Some(TransactionSubscriber {
+                receiver: consensus.subscriptions().subscribe_transactions(),
+                full: request.params.full,
+                filter: request.params.filter,
+            });
+            json!({ "id": request.id, "result": "pendingTransactions" })
+        }
+        "unsubscribePendingTransactions" => {
+            subscribers.transactions = None;
+            json!({ "id": request.id, "result": true })
+        }
+        "subscribeRounds" => {
+            subscribers.rounds = Some(consensus.subscriptions().subscribe_rounds());
+            json!({ "id": request.id, "result": "rounds" })
+        }
+        "unsubscribeRounds" => {
+            subscribers.rounds = None;
+            json!({ "id": request.id, "result": true })
+        }
+        "subscribeDeadlines" => {
+            subscribers.deadlines = Some(deadlines.subscribe());
+            json!({ "id": request.id, "result": "deadlines" })
+        }
+        "unsubscribeDeadlines" => {
+            subscribers.deadlines = None;
+            json!({ "id": request.id, "result": true })
+        }
+        "subscribeCommittee" => {
+            subscribers.committee = Some(consensus.subscriptions().subscribe_committee());
+            json!({ "id": request.id, "result": "committee" })
+        }
+        "unsubscribeCommittee" => {
+            subscribers.committee = None;
+            json!({ "id": request.id, "result": true })
+        }
+        method => json!({ "id": request.id, "error": format!("Unknown method '{method}'") }),
+    }
+}
+
+/// Returns the next notification of the given subscriber, or never returns if there is no subscriber.
+async fn recv<T: Clone>(subscriber: &mut Option<broadcast::Receiver<T>>) -> Result<T, RecvError> {
+    match subscriber {
+        Some(subscriber) => subscriber.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Returns the next pending transaction of the given subscriber that matches its filter, as the full transaction
+/// if requested, or otherwise as its transaction ID. If there is no subscriber, this never returns.
+async fn recv_transaction<N: Network>(
+    subscriber: &mut Option<TransactionSubscriber<N>>,
+) -> Result<PendingTransaction<N>, RecvError> {
+    let subscriber = match subscriber {
+        Some(subscriber) => subscriber,
+        None => return std::future::pending().await,
+    };
+    loop {
+        let transaction = subscriber.receiver.recv().await?;
+        if subscriber.filter.matches(&transaction) {
+            return Ok(match subscriber.full {
+                true => PendingTransaction::Full(transaction),
+                false => PendingTransaction::Id(transaction.id()),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{FromBytes, PrivateKey, TestRng, Testnet3};
+
+    use std::{str::FromStr, time::Duration};
+
+    type CurrentNetwork = Testnet3;
+
+    /// Returns a transaction of the genesis block, which calls `credits.aleo/mint` with a public address.
+    fn sample_transaction() -> Transaction<CurrentNetwork> {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        genesis.transactions().values().next().unwrap().clone()
+    }
+
+    /// Returns the first public address input of the given transaction.
+    fn sample_address(transaction: &Transaction<CurrentNetwork>) -> Address<CurrentNetwork> {
+        transaction
+            .transitions()
+            .flat_map(|transition| transition.inputs())
+            .find_map(|input| match input {
+                Input::Public(_, Some(Plaintext::Literal(Literal::Address(address), _))) => Some(*address),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_subscription_params() {
+        // The filter defaults to matching every transaction.
+        let request: SubscriptionRequest<CurrentNetwork> =
+            serde_json::from_str(r#"{ "method": "subscribePendingTransactions" }"#).unwrap();
+        assert!(!request.params.full);
+        assert_eq!(request.params.filter, TransactionFilter::default());
+
+        // The filter criteria are parsed alongside the other parameters.
+        let request: SubscriptionRequest<CurrentNetwork> = serde_json::from_str(
+            r#"{
+                "id": 1,
+                "method": "subscribePendingTransactions",
+                "params": { "full": true, "programId": "credits.aleo", "functionName": "transfer", "minFee": 1000 }
+            }"#,
+        )
+        .unwrap();
+        assert!(request.params.full);
+        assert_eq!(request.params.filter.program_id, Some(ProgramID::from_str("credits.aleo").unwrap()));
+        assert_eq!(request.params.filter.function_name, Some(Identifier::from_str("transfer").unwrap()));
+        assert_eq!(request.params.filter.address, None);
+        assert_eq!(request.params.filter.min_fee, Some(1000));
+
+        // An invalid criterion is rejected.
+        let result = serde_json::from_str::<SubscriptionRequest<CurrentNetwork>>(
+            r#"{ "method": "subscribePendingTransactions", "params": { "programId": "credits" } }"#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_transaction_filter() {
+        let rng = &mut TestRng::default();
+        let transaction = sample_transaction();
+        let address = sample_address(&transaction);
+        let other_address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        let filter = |json: &str| serde_json::from_str::<TransactionFilter<CurrentNetwork>>(json).unwrap();
+
+        // Ensure the default filter matches every transaction.
+        assert!(TransactionFilter::default().matches(&transaction));
+
+        // Ensure the program and function must both be called by the same transition.
+        assert!(filter(r#"{ "programId": "credits.aleo" }"#).matches(&transaction));
+        assert!(filter(r#"{ "programId": "credits.aleo", "functionName": "mint" }"#).matches(&transaction));
+        assert!(!filter(r#"{ "programId": "credits.aleo", "functionName": "transfer" }"#).matches(&transaction));
+        assert!(!filter(r#"{ "programId": "token.aleo" }"#).matches(&transaction));
+
+        // Ensure the address must appear in a public input or output.
+        assert!(filter(&format!(r#"{{ "address": "{address}" }}"#)).matches(&transaction));
+        assert!(!filter(&format!(r#"{{ "address": "{other_address}" }}"#)).matches(&transaction));
+
+        // Ensure the fee must meet the minimum.
+        assert!(!filter(&format!(r#"{{ "minFee": {} }}"#, u64::MAX)).matches(&transaction));
+
+        // Ensure every criterion must be met.
+        let json = format!(r#"{{ "programId": "credits.aleo", "address": "{other_address}" }}"#);
+        assert!(!filter(&json).matches(&transaction));
+    }
+
+    #[tokio::test]
+    async fn test_recv_transaction() {
+        let transaction = sample_transaction();
+        let (sender, receiver) = broadcast::channel(16);
+
+        // Ensure a transaction that does not match the filter is not sent.
+        let filter = serde_json::from_str(r#"{ "functionName": "transfer" }"#).unwrap();
+        let mut subscriber = Some(TransactionSubscriber { receiver, full: false, filter });
+        sender.send(transaction.clone()).unwrap();
+        let result = tokio::time::timeout(Duration::from_millis(100), recv_transaction(&mut subscriber)).await;
+        assert!(result.is_err());
+
+        // Ensure a transaction that matches the filter is sent, as its transaction ID.
+        subscriber.as_mut().unwrap().filter = serde_json::from_str(r#"{ "functionName": "mint" }"#).unwrap();
+        sender.send(transaction.clone()).unwrap();
+        match recv_transaction(&mut subscriber).await.unwrap() {
+            PendingTransaction::Id(id) => assert_eq!(id, transaction.id()),
+            PendingTransaction::Full(..) => panic!("Expected the transaction ID"),
+        }
+
+        // Ensure the full transaction is sent, if requested.
+        subscriber.as_mut().unwrap().full = true;
+        sender.send(transaction.clone()).unwrap();
+        match recv_transaction(&mut subscriber).await.unwrap() {
+            PendingTransaction::Full(full) => assert_eq!(full, transaction),
+            PendingTransaction::Id(..) => panic!("Expected the full transaction"),
+        }
+    }
+}
@@ -19,30 +19,53 @@
 #[macro_use]
 extern crate tracing;
 
+#[cfg(feature = "grpc")]
+mod grpc;
+#[cfg(feature = "grpc")]
+pub use grpc::*;
+
 mod helpers;
 pub use helpers::*;
 
 mod routes;
 pub use routes::*;
 
-use snarkos_node_consensus::Consensus;
-use snarkos_node_ledger::Ledger;
-use snarkos_node_messages::{Data, Message, UnconfirmedTransaction};
+#[cfg(feature = "tls")]
+mod tls;
+#[cfg(feature = "tls")]
+pub use tls::*;
+
+use snarkos_node_cdn::RemoteEraArchive;
+use snarkos_node_consensus::{Consensus, FeeBreakdown};
+use snarkos_node_ledger::{Direction, HistoryEntry, Ledger};
+use snarkos_node_messages::{Data, Message, NodeType, UnconfirmedTransaction, UnconfirmedTransmission};
 use snarkos_node_router::{Router, Routing};
+use snarkos_node_store::Indexes;
 use snarkvm::{
-    console::{account::Address, program::ProgramID, types::Field},
-    prelude::{cfg_into_iter, Network},
-    synthesizer::{ConsensusStorage, Program, Transaction},
+    console::{
+        account::{Address, ViewKey},
+        program::{ProgramID, Value},
+        types::Field,
+    },
+    prelude::{cfg_into_iter, Block, FromBytes, Network, PrivateKey, Query, ToBytes},
+    synthesizer::{ConsensusStorage, Header, Program, Transaction},
 };
 
 use anyhow::Result;
-use http::header::HeaderName;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use serde::{Deserialize, Serialize};
-use std::{net::SocketAddr, str::FromStr, sync::Arc};
-use tokio::task::JoinHandle;
+use std::{
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{sync::Semaphore, task::JoinHandle};
 use warp::{reject, reply, Filter, Rejection, Reply};
 
+/// The maximum number of transaction history scans served concurrently.
+const MAX_CONCURRENT_HISTORY_SCANS: usize = 4;
+
 /// A REST API server for the ledger.
 #[derive(Clone)]
 pub struct Rest<N: Network, C: ConsensusStorage<N>, R: Routing<N>> {
@@ -52,6 +75,32 @@ pub struct Rest<N: Network, C: ConsensusStorage<N>, R: Routing<N>> {
     ledger: Ledger<N, C>,
     /// The node (routing).
     routing: Arc<R>,
+    /// The transactions submitted with an inclusion deadline.
+    deadlines: Deadlines<N>,
+    /// The queue of proving jobs.
+    proving_jobs: ProvingJobs<N>,
+    /// The remote archive of era files, which serves the historical blocks (experimental).
+    archive: Arc<RwLock<Option<RemoteEraArchive>>>,
+    /// The secondary indexes of the ledger, if they are maintained.
+    indexes: Arc<RwLock<Option<Arc<Indexes<N>>>>>,
+    /// The requests being served.
+    requests: RequestCounter,
+    /// The permits of the concurrent transaction history scans.
+    history_scans: Arc<Semaphore>,
+    /// The rate limiter of the requests.
+    limiter: RateLimiter,
+    /// The CORS and reverse-proxy configuration.
+    proxy: Arc<RestProxy>,
+    /// The static bearer tokens accepted in addition to the JSON web token of the node.
+    auth_tokens: Arc<Vec<AuthToken>>,
+    /// The handling of the fields outside the canonical representation of a JSON request body.
+    json_mode: JsonMode,
+    /// The thresholds that the node must meet to be ready to serve traffic.
+    readiness: ReadinessThresholds,
+    /// Whether the profiling endpoints are enabled.
+    profiling: bool,
+    /// The faucet, which is only served on development networks.
+    faucet: Option<Faucet<N>>,
     /// The server handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
 }
@@ -60,14 +109,53 @@ impl<N: Network, C: 'static + ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R>
     /// Initializes a new instance of the server.
     pub fn start(
         rest_ip: SocketAddr,
+        config: RestConfig,
         consensus: Option<Consensus<N, C>>,
         ledger: Ledger<N, C>,
         routing: Arc<R>,
+        dev: Option<u16>,
     ) -> Result<Self> {
+        // Ensure the configuration is well-formed.
+        config.check()?;
+        // Load the proving jobs, with the proving key if one is configured.
+        let proving_key = config.proving_key.as_deref().map(PrivateKey::from_str).transpose()?;
+        let proving_jobs = ProvingJobs::load(proving_key, config.proving_jobs_path.clone())?;
+        // Initialize the faucet, if the node is on a development network.
+        let faucet = match (dev, &config.faucet) {
+            (Some(_), Some(faucet)) => Some(Faucet::new(faucet.clone())?),
+            _ => None,
+        };
         // Initialize the server.
-        let mut server = Self { consensus, ledger, routing, handles: Default::default() };
+        let mut server = Self {
+            consensus,
+            ledger,
+            routing,
+            deadlines: Default::default(),
+            proving_jobs,
+            archive: Default::default(),
+            indexes: Default::default(),
+            requests: Default::default(),
+            history_scans: Arc::new(Semaphore::new(MAX_CONCURRENT_HISTORY_SCANS)),
+            limiter: RateLimiter::new(config.limits),
+            proxy: Arc::new(config.proxy.clone()),
+            auth_tokens: Arc::new(config.auth_tokens.clone()),
+            json_mode: config.json_mode,
+            readiness: config.readiness,
+            profiling: config.profiling,
+            faucet,
+            handles: Default::default(),
+        };
         // Spawn the server.
-        server.spawn_server(rest_ip);
+        server.spawn_server(rest_ip, &config);
+        // Spawn the gRPC server, if it is enabled.
+        #[cfg(feature = "grpc")]
+        if let Some(grpc_ip) = config.grpc_ip {
+            server.spawn_grpc_server(grpc_ip);
+        }
+        // Spawn the deadline tracker.
+        server.spawn_deadline_tracker();
+        // Spawn the proving worker.
+        server.spawn_proving_worker();
         // Return the server.
         Ok(server)
     }
@@ -79,33 +167,271 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         &self.ledger
     }
 
+    /// Returns the rate limiter of the requests.
+    pub const fn limiter(&self) -> &RateLimiter {
+        &self.limiter
+    }
+
     /// Returns the handles.
     pub const fn handles(&self) -> &Arc<Mutex<Vec<JoinHandle<()>>>> {
         &self.handles
     }
+
+    /// Sets the remote archive of era files, which serves the historical blocks (experimental),
+    /// and periodically refreshes its era index.
+    pub fn set_archive(&self, archive: RemoteEraArchive) {
+        // The interval (in seconds) at which the era index is refreshed.
+        const ARCHIVE_REFRESH_IN_SECS: u64 = 600;
+
+        *self.archive.write() = Some(archive.clone());
+
+        self.handles.lock().push(tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(ARCHIVE_REFRESH_IN_SECS)).await;
+
+                if let Err(error) = archive.refresh_index().await {
+                    warn!("Failed to refresh the era index from '{}' - {error}", archive.base_url());
+                }
+            }
+        }));
+    }
+
+    /// Sets the secondary indexes of the ledger, which serve the address activity queries.
+    pub fn set_indexes(&self, indexes: Arc<Indexes<N>>) {
+        *self.indexes.write() = Some(indexes);
+    }
 }
 
 impl<N: Network, C: 'static + ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
-    /// Initializes the server.
-    fn spawn_server(&mut self, rest_ip: SocketAddr) {
-        let cors = warp::cors()
-            .allow_any_origin()
-            .allow_header(HeaderName::from_static("content-type"))
-            .allow_methods(vec!["GET", "POST", "OPTIONS"]);
-
-        // Initialize the routes.
-        let routes = self.routes();
-
-        // Add custom logging for each request.
-        let custom_log = warp::log::custom(|info| match info.remote_addr() {
-            Some(addr) => debug!("Received '{} {}' from '{addr}' ({})", info.method(), info.path(), info.status()),
-            None => debug!("Received '{} {}' ({})", info.method(), info.path(), info.status()),
+    /// Initializes the server, on the IPC socket and over TLS if they are configured.
+    #[cfg_attr(not(any(unix, feature = "tls")), allow(unused_variables))]
+    fn spawn_server(&mut self, rest_ip: SocketAddr, config: &RestConfig) {
+        // Retrieve the CORS and reverse-proxy configuration.
+        let proxy = self.proxy.clone();
+        let cors = proxy.cors();
+
+        // Initialize the routes under the path prefix, track the requests, and enforce the request limits.
+        let requests = self.requests.clone();
+        let routes = warp::any()
+            .map(move || requests.enter())
+            .and(with_limits(self.limiter.clone(), proxy.clone()))
+            .and(proxy.path_prefix())
+            .and(self.routes())
+            .map(|_, reply| reply)
+            .recover(handle_rejection);
+
+        // Add custom logging for each request, with the client IP reported by the trusted proxies.
+        let custom_log = warp::log::custom(move |info| {
+            let forwarded_for = info.request_headers().get("x-forwarded-for").and_then(|value| value.to_str().ok());
+            match info.remote_addr().map(|addr| proxy.client_ip(addr.ip(), forwarded_for)) {
+                Some(ip) => debug!("Received '{} {}' from '{ip}' ({})", info.method(), info.path(), info.status()),
+                None => debug!("Received '{} {}' ({})", info.method(), info.path(), info.status()),
+            }
         });
 
+        let routes = routes.with(cors).with(custom_log);
+
+        // Spawn the server on the IPC socket, if it is enabled.
+        #[cfg(unix)]
+        if let Some(path) = &config.ipc_path {
+            match bind_ipc_socket(path) {
+                Ok(listener) => {
+                    let service = warp::service(routes.clone());
+                    self.handles.lock().push(tokio::spawn(serve_ipc(listener, service)));
+                }
+                Err(error) => warn!("Failed to start the IPC server at '{}' - {error}", path.display()),
+            }
+        }
+
+        // Spawn the server over TLS, if it is enabled.
+        #[cfg(feature = "tls")]
+        if let Some(config) = config.tls.clone() {
+            // The interval (in seconds) after which a failure to load or serve the certificate is retried.
+            const TLS_RETRY_IN_SECS: u64 = 600;
+            // The number of attempts to bind the server, while the previous server releases the address.
+            const TLS_BIND_ATTEMPTS: usize = 50;
+            // The interval (in milliseconds) between the attempts to bind the server.
+            const TLS_BIND_RETRY_IN_MILLIS: u64 = 100;
+
+            self.handles.lock().push(tokio::spawn(async move {
+                // Load the certificate, issuing it if necessary.
+                let mut certificate = loop {
+                    match config.load().await {
+                        Ok(certificate) => break certificate,
+                        Err(error) => {
+                            error!("Failed to load the TLS certificate of the REST server - {error}");
+                            tokio::time::sleep(Duration::from_secs(TLS_RETRY_IN_SECS)).await;
+                        }
+                    }
+                };
+                // The certificate served before the last renewal, to fall back to if the renewed one is rejected.
+                let mut previous = None;
+                loop {
+                    // Bind the server with the certificate, retrying while the previous server releases the address.
+                    let mut attempts = 1;
+                    let result = loop {
+                        let (shutdown, signal) = tokio::sync::oneshot::channel::<()>();
+                        let server = warp::serve(routes.clone()).tls().cert(&certificate.0).key(&certificate.1);
+                        let signal = async move {
+                            signal.await.ok();
+                        };
+                        match server.try_bind_with_graceful_shutdown(rest_ip, signal) {
+                            Ok((_, server)) => break Ok((shutdown, server)),
+                            Err(error) if attempts >= TLS_BIND_ATTEMPTS => break Err(error),
+                            Err(_) => {
+                                attempts += 1;
+                                tokio::time::sleep(Duration::from_millis(TLS_BIND_RETRY_IN_MILLIS)).await;
+                            }
+                        }
+                    };
+                    let shutdown = match result {
+                        Ok((shutdown, server)) => {
+                            tokio::spawn(server);
+                            shutdown
+                        }
+                        Err(error) => {
+                            error!("Failed to start the REST server over TLS - {error}");
+                            match previous.take() {
+                                // Fall back to the previous certificate, if the renewed one is rejected.
+                                Some(previous) => certificate = previous,
+                                None => tokio::time::sleep(Duration::from_secs(TLS_RETRY_IN_SECS)).await,
+                            }
+                            continue;
+                        }
+                    };
+                    // Serve the certificate, until a renewed certificate is obtained.
+                    let renewed = config.renewed().await;
+                    previous = Some(std::mem::replace(&mut certificate, renewed));
+                    // Shut down the server, which finishes serving its open connections in the background.
+                    info!("Reloading the TLS certificate of the REST server");
+                    let _ = shutdown.send(());
+                }
+            }));
+            return;
+        }
+
         // Spawn the server.
         self.handles.lock().push(tokio::spawn(async move {
             // Start the server.
-            warp::serve(routes.with(cors).with(custom_log)).run(rest_ip).await
+            warp::serve(routes).run(rest_ip).await
         }))
     }
+
+    /// Initializes the gRPC server on the given IP address.
+    #[cfg(feature = "grpc")]
+    fn spawn_grpc_server(&mut self, grpc_ip: SocketAddr) {
+        let server = GrpcServer::new(
+            self.consensus.clone(),
+            self.ledger.clone(),
+            self.routing.router().clone(),
+            self.limiter.clone(),
+            self.requests.clone(),
+            self.auth_tokens.clone(),
+        );
+        self.handles.lock().push(tokio::spawn(server.serve(grpc_ip)));
+    }
+
+    /// Initializes the deadline tracker, which rebroadcasts pending deadline submissions
+    /// and resolves them as included or expired.
+    fn spawn_deadline_tracker(&mut self) {
+        const DEADLINE_CHECK_IN_SECS: u64 = 5;
+
+        let ledger = self.ledger.clone();
+        let routing = self.routing.clone();
+        let deadlines = self.deadlines.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(DEADLINE_CHECK_IN_SECS)).await;
+
+                // Rebroadcast the transactions that are due.
+                for transaction in deadlines.refresh(&ledger) {
+                    let message = Message::UnconfirmedTransaction(UnconfirmedTransaction {
+                        transaction_id: transaction.id(),
+                        transaction: Data::Object(transaction),
+                    });
+                    routing.propagate_local(message, &[]);
+                }
+            }
+        }))
+    }
+
+    /// Initializes the proving worker, which proves the queued proving jobs one at a time with the proving account,
+    /// and broadcasts the resulting transactions. The worker is only spawned if a proving key is configured.
+    fn spawn_proving_worker(&mut self) {
+        const PROVING_CHECK_IN_SECS: u64 = 1;
+
+        // Retrieve the private key of the proving account.
+        let private_key = match self.proving_jobs.private_key() {
+            Some(private_key) => *private_key,
+            None => return,
+        };
+
+        let consensus = self.consensus.clone();
+        let ledger = self.ledger.clone();
+        let routing = self.routing.clone();
+        let proving_jobs = self.proving_jobs.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(PROVING_CHECK_IN_SECS)).await;
+
+                // Retrieve the next queued job.
+                let (id, request) = match proving_jobs.start_next() {
+                    Some(job) => job,
+                    None => continue,
+                };
+                info!("Proving job {id} ('{}/{}')", request.program_id, request.function);
+
+                // Prove the execution.
+                let vm = ledger.vm().clone();
+                let result = tokio::task::spawn_blocking(move || {
+                    Transaction::execute(
+                        &vm,
+                        &private_key,
+                        (request.program_id, request.function),
+                        request.inputs.iter(),
+                        request.fee_record.map(|record| (record, request.fee)),
+                        None::<Query<N, C::BlockStorage>>,
+                        &mut rand::thread_rng(),
+                    )
+                })
+                .await;
+
+                // Broadcast the transaction, unless the job was cancelled in the meantime.
+                let outcome = match result {
+                    Ok(Ok(transaction)) if proving_jobs.get(id).map_or(false, |job| job.status.is_active()) => {
+                        Self::broadcast_transaction(consensus.clone(), &routing, transaction).await
+                    }
+                    Ok(Ok(transaction)) => Ok(transaction.id()),
+                    Ok(Err(error)) => Err(error),
+                    Err(error) => Err(error.into()),
+                };
+                if proving_jobs.finish(id, outcome) {
+                    info!("Finished proving job {id}");
+                }
+            }
+        }))
+    }
+
+    /// Adds the given transaction to the memory pool, and broadcasts it.
+    /// Returns the transaction ID, or the reason the transaction was rejected.
+    async fn broadcast_transaction(
+        consensus: Option<Consensus<N, C>>,
+        routing: &R,
+        transaction: Transaction<N>,
+    ) -> Result<N::TransactionID> {
+        // If the consensus module is enabled, add the unconfirmed transaction to the memory pool,
+        // on a blocking thread, as its proofs are verified.
+        if let Some(consensus) = consensus {
+            let candidate = transaction.clone();
+            tokio::task::spawn_blocking(move || consensus.add_unconfirmed_transaction(candidate)).await??;
+        }
+        // Broadcast the transaction.
+        let transaction_id = transaction.id();
+        let message = Message::UnconfirmedTransaction(UnconfirmedTransaction {
+            transaction_id,
+            transaction: Data::Object(transaction),
+        });
+        routing.propagate_local(message, &[]);
+        Ok(transaction_id)
+    }
 }
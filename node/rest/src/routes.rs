@@ -16,7 +16,150 @@
 
 use super::*;
 
+use core::ops::Range;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use tokio::sync::broadcast::error::RecvError;
+use warp::{http::StatusCode, ws::Ws};
+
+/// The result of a single transaction in a batch broadcast.
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum BroadcastResult {
+    /// The transaction was accepted, with the given transaction ID.
+    Accepted(String),
+    /// The transaction was rejected, with the given reason.
+    Rejected(String),
+}
+
+/// The outcome of a transaction broadcast that waits for confirmation.
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+enum WaitOutcome<N: Network> {
+    /// The transaction was included in the block with the given height and hash.
+    Confirmed { height: u32, block_hash: N::BlockHash },
+    /// The transaction was dropped from the memory pool without being included in a block.
+    Rejected,
+    /// The timeout passed before the transaction was included or dropped.
+    Pending,
+}
+
+/// The `transaction_broadcast_and_wait` query object.
+#[derive(Deserialize, Serialize)]
+struct WaitTimeout {
+    /// The maximum number of seconds to wait for the transaction to be confirmed.
+    #[serde(default = "WaitTimeout::default_timeout")]
+    timeout: u64,
+}
+
+impl WaitTimeout {
+    /// The maximum number of seconds a request may wait.
+    const MAX_TIMEOUT_IN_SECS: u64 = 300;
+
+    /// Returns the default timeout in seconds.
+    const fn default_timeout() -> u64 {
+        60
+    }
+}
+
+/// The `get_cpu_profile` query object.
+#[derive(Deserialize, Serialize)]
+struct ProfileQuery {
+    /// The number of seconds to sample the CPU time for.
+    #[serde(default = "ProfileQuery::default_seconds")]
+    seconds: u64,
+}
+
+impl ProfileQuery {
+    /// Returns the default number of seconds.
+    const fn default_seconds() -> u64 {
+        10
+    }
+}
+
+/// The verdict of a raw transaction dry run.
+#[derive(Debug, Serialize)]
+struct TransactionVerdict {
+    /// Whether the transaction would be admitted to the memory pool.
+    valid: bool,
+    /// The transaction ID, if the transaction could be decoded.
+    transaction_id: Option<String>,
+    /// The reason the transaction would be rejected, if any.
+    error: Option<RawTransactionError>,
+}
+
+/// A transaction, with the breakdown of its fee and its confirmation metadata.
+#[derive(Serialize)]
+struct TransactionInfo<N: Network> {
+    /// The transaction.
+    #[serde(flatten)]
+    transaction: Transaction<N>,
+    /// The breakdown of the transaction fee.
+    fee_breakdown: FeeBreakdown,
+    /// The block that includes the transaction.
+    confirmation: Confirmation<N>,
+}
+
+/// A deployed program, with the transaction that deployed it.
+#[derive(Serialize)]
+struct ProgramDeployment<N: Network> {
+    /// The program source.
+    program: Program<N>,
+    /// The deployment transaction.
+    transaction: Transaction<N>,
+    /// The height of the block that includes the deployment transaction.
+    block_height: u32,
+}
+
+/// A block header, with a proof of its inclusion in the latest state root.
+#[derive(Serialize)]
+struct HeaderProofInfo<N: Network> {
+    /// The state root that the proof is against.
+    state_root: N::StateRoot,
+    /// The block height, which is the index of the block in the block tree.
+    block_height: u32,
+    /// The block hash, which is the leaf of the block tree.
+    block_hash: N::BlockHash,
+    /// The previous block hash.
+    previous_hash: N::BlockHash,
+    /// The block header.
+    header: Header<N>,
+    /// The Merkle path of the block hash in the block tree, in its hex-encoded little-endian byte encoding.
+    block_path: String,
+}
+
+/// The location and depth of a confirmed transaction.
+#[derive(Serialize)]
+struct Confirmation<N: Network> {
+    /// The height of the block that includes the transaction.
+    block_height: u32,
+    /// The hash of the block that includes the transaction.
+    block_hash: N::BlockHash,
+    /// The index of the transaction within the block.
+    index: usize,
+    /// The number of blocks built on top of the including block.
+    depth: u32,
+    /// Whether the including block is finalized.
+    finalized: bool,
+}
+
+/// The tip of the ledger that a query is answered against.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Finality {
+    /// The latest block, which may still be reorganized.
+    #[default]
+    Latest,
+    /// The latest finalized block, which is irreversible.
+    Finalized,
+}
+
+/// The `finality` query object.
+#[derive(Deserialize, Serialize)]
+struct FinalityQuery {
+    /// The tip of the ledger to answer against.
+    #[serde(default)]
+    finality: Finality,
+}
 
 /// The `get_blocks` query object.
 #[derive(Deserialize, Serialize)]
@@ -25,68 +168,214 @@ struct BlockRange {
     start: u32,
     /// The ending block height (exclusive).
     end: u32,
+    /// The tip of the ledger to answer against.
+    #[serde(default)]
+    finality: Finality,
+}
+
+/// The role of the node.
+#[derive(Serialize)]
+struct NodeRole {
+    /// The node type.
+    node_type: NodeType,
+    /// Whether the node stays in sync without producing blocks or solutions.
+    standby: bool,
+}
+
+/// The `stream_finalized_blocks` query object.
+#[derive(Deserialize, Serialize)]
+struct BlockCursor {
+    /// The height of the first block to stream, or unset to stream from the next finalized block.
+    from: Option<u32>,
+}
+
+/// The `estimate_fee` query object.
+#[derive(Deserialize, Serialize)]
+struct FeeTarget {
+    /// The number of blocks within which the transaction should be included.
+    #[serde(default = "FeeTarget::default_target")]
+    target: u32,
+}
+
+impl FeeTarget {
+    /// Returns the default target, which is the next block.
+    const fn default_target() -> u32 {
+        1
+    }
+}
+
+/// A page of the transactions with an address in a public input or output.
+#[derive(Serialize)]
+struct AddressTransactionsPage<N: Network> {
+    /// The total number of transactions indexed for the address.
+    total: u64,
+    /// The IDs of the transactions in the page, in the order they were indexed.
+    transaction_ids: Vec<N::TransactionID>,
+}
+
+/// A page of the transaction history of a view key, from newest to oldest.
+#[derive(Serialize)]
+struct TransactionHistoryPage<N: Network> {
+    /// The total number of transactions in the history.
+    total: usize,
+    /// The transactions in the page.
+    transactions: Vec<HistoryEntryInfo<N>>,
+}
+
+/// A transaction in the history of a view key, with its decrypted metadata.
+#[derive(Serialize)]
+struct HistoryEntryInfo<N: Network> {
+    /// The transaction ID.
+    transaction_id: N::TransactionID,
+    /// The height of the block that includes the transaction.
+    block_height: u32,
+    /// The hash of the block that includes the transaction.
+    block_hash: N::BlockHash,
+    /// The timestamp of the block that includes the transaction.
+    timestamp: i64,
+    /// The direction of the transaction, as `incoming`, `outgoing`, or `self`.
+    direction: &'static str,
+    /// The amount (in microcredits) in the records of the account that the transaction creates.
+    received: u64,
+    /// The amount (in microcredits) in the records of the account that the transaction spends.
+    spent: u64,
+    /// The other addresses in the public inputs and outputs of the transaction.
+    counterparties: Vec<Address<N>>,
+}
+
+impl<N: Network> From<HistoryEntry<N>> for HistoryEntryInfo<N> {
+    fn from(entry: HistoryEntry<N>) -> Self {
+        Self {
+            transaction_id: entry.transaction_id,
+            block_height: entry.block_height,
+            block_hash: entry.block_hash,
+            timestamp: entry.timestamp,
+            direction: match entry.direction {
+                Direction::Incoming => "incoming",
+                Direction::Outgoing => "outgoing",
+                Direction::SelfTransfer => "self",
+            },
+            received: entry.received,
+            spent: entry.spent,
+            counterparties: entry.counterparties,
+        }
+    }
+}
+
+/// The `get_memory_pool_transaction_ids`, `get_transactions_for_address`, and `get_transaction_history` query object.
+#[derive(Deserialize, Serialize)]
+struct Page {
+    /// The number of entries to skip.
+    #[serde(default)]
+    offset: usize,
+    /// The maximum number of entries to return.
+    #[serde(default = "Page::default_limit")]
+    limit: usize,
+}
+
+impl Page {
+    /// Returns the default number of entries per page.
+    const fn default_limit() -> usize {
+        100
+    }
+}
+
+/// The `get_equivocation_evidence` query object.
+#[derive(Deserialize, Serialize)]
+#[serde(bound = "")]
+struct EvidenceQuery<N: Network> {
+    /// The equivocating signer to return the evidence of, or unset for all signers.
+    signer: Option<Address<N>>,
+    /// The number of entries to skip.
+    #[serde(default)]
+    offset: usize,
+    /// The maximum number of entries to return.
+    #[serde(default = "Page::default_limit")]
+    limit: usize,
 }
 
 impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
     /// Initializes the routes, given the ledger and ledger sender.
     pub fn routes(&self) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
-        // GET /testnet3/latest/height
+        // GET /testnet3/latest/height?finality={latest|finalized}
         let latest_height = warp::get()
             .and(warp::path!("testnet3" / "latest" / "height"))
+            .and(warp::query::<FinalityQuery>())
             .and(with(self.ledger.clone()))
             .and_then(Self::latest_height);
 
-        // GET /testnet3/latest/hash
+        // GET /testnet3/latest/hash?finality={latest|finalized}
         let latest_hash = warp::get()
             .and(warp::path!("testnet3" / "latest" / "hash"))
+            .and(warp::query::<FinalityQuery>())
             .and(with(self.ledger.clone()))
             .and_then(Self::latest_hash);
 
-        // GET /testnet3/latest/block
+        // GET /testnet3/latest/block?finality={latest|finalized}
         let latest_block = warp::get()
             .and(warp::path!("testnet3" / "latest" / "block"))
+            .and(warp::query::<FinalityQuery>())
             .and(with(self.ledger.clone()))
             .and_then(Self::latest_block);
 
-        // GET /testnet3/latest/stateRoot
+        // GET /testnet3/latest/stateRoot?finality={latest|finalized}
         let latest_state_root = warp::get()
             .and(warp::path!("testnet3" / "latest" / "stateRoot"))
+            .and(warp::query::<FinalityQuery>())
             .and(with(self.ledger.clone()))
             .and_then(Self::latest_state_root);
 
-        // GET /testnet3/block/{height}
+        // GET /testnet3/finalized/height
+        let get_finalized_height = warp::get()
+            .and(warp::path!("testnet3" / "finalized" / "height"))
+            .and(with(self.ledger.clone()))
+            .and_then(Self::get_finalized_height);
+
+        // GET /testnet3/block/{height}?finality={latest|finalized}
         let get_block = warp::get()
             .and(warp::path!("testnet3" / "block" / u32))
+            .and(warp::query::<FinalityQuery>())
             .and(with(self.ledger.clone()))
+            .and(with(self.archive.clone()))
             .and_then(Self::get_block);
 
-        // GET /testnet3/blocks?start={start_height}&end={end_height}
+        // GET /testnet3/blocks?start={start_height}&end={end_height}&finality={latest|finalized}
         let get_blocks = warp::get()
             .and(warp::path!("testnet3" / "blocks"))
             .and(warp::query::<BlockRange>())
             .and(with(self.ledger.clone()))
+            .and(with(self.archive.clone()))
             .and_then(Self::get_blocks);
 
-        // GET /testnet3/block/{blockHash}
+        // GET /testnet3/block/{blockHash}?finality={latest|finalized}
         let get_block_by_hash = warp::get()
             .and(warp::path!("testnet3" / "block" / ..))
             .and(warp::path::param::<N::BlockHash>())
+            .and(warp::query::<FinalityQuery>())
             .and(with(self.ledger.clone()))
             .and_then(Self::get_block_by_hash);
 
-        // GET /testnet3/height/{blockHash}
+        // GET /testnet3/height/{blockHash}?finality={latest|finalized}
         let get_block_height_by_hash = warp::get()
             .and(warp::path!("testnet3" / "height" / ..))
             .and(warp::path::param::<N::BlockHash>())
+            .and(warp::query::<FinalityQuery>())
             .and(with(self.ledger.clone()))
             .and_then(Self::get_block_height_by_hash);
 
-        // GET /testnet3/block/{height}/transactions
+        // GET /testnet3/block/{height}/transactions?finality={latest|finalized}
         let get_block_transactions = warp::get()
             .and(warp::path!("testnet3" / "block" / u32 / "transactions"))
+            .and(warp::query::<FinalityQuery>())
             .and(with(self.ledger.clone()))
             .and_then(Self::get_block_transactions);
 
+        // GET /testnet3/block/{height}/header/proof
+        let get_header_proof = warp::get()
+            .and(warp::path!("testnet3" / "block" / u32 / "header" / "proof"))
+            .and(with(self.ledger.clone()))
+            .and_then(Self::get_header_proof);
+
         // GET /testnet3/transaction/{transactionID}
         let get_transaction = warp::get()
             .and(warp::path!("testnet3" / "transaction" / ..))
@@ -101,6 +390,47 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             .and(with(self.consensus.clone()))
             .and_then(Self::get_memory_pool_transactions);
 
+        // GET /testnet3/memoryPool/transactionIDs?offset={offset}&limit={limit}
+        let get_memory_pool_transaction_ids = warp::get()
+            .and(warp::path!("testnet3" / "memoryPool" / "transactionIDs"))
+            .and(warp::query::<Page>())
+            .and(with(self.consensus.clone()))
+            .and_then(Self::get_memory_pool_transaction_ids);
+
+        // GET /testnet3/memoryPool/info
+        let get_memory_pool_info = warp::get()
+            .and(warp::path!("testnet3" / "memoryPool" / "info"))
+            .and(with(self.consensus.clone()))
+            .and_then(Self::get_memory_pool_info);
+
+        // GET /testnet3/memoryPool/transmissions/{kind}
+        let get_memory_pool_transmissions = warp::get()
+            .and(warp::path!("testnet3" / "memoryPool" / "transmissions" / String))
+            .and(with(self.consensus.clone()))
+            .and_then(Self::get_memory_pool_transmissions);
+
+        // GET /testnet3/fee/estimate?target={blocks}
+        let estimate_fee = warp::get()
+            .and(warp::path!("testnet3" / "fee" / "estimate"))
+            .and(warp::query::<FeeTarget>())
+            .and(with(self.consensus.clone()))
+            .and_then(Self::estimate_fee);
+
+        // GET /testnet3/programs?offset={offset}&limit={limit}
+        let list_programs = warp::get()
+            .and(warp::path!("testnet3" / "programs"))
+            .and(warp::query::<Page>())
+            .and(with(self.ledger.clone()))
+            .and_then(Self::list_programs);
+
+        // GET /testnet3/program/{programID}/deployment
+        let get_program_deployment = warp::get()
+            .and(warp::path!("testnet3" / "program" / ..))
+            .and(warp::path::param::<ProgramID<N>>())
+            .and(warp::path!("deployment"))
+            .and(with(self.ledger.clone()))
+            .and_then(Self::get_program_deployment);
+
         // GET /testnet3/program/{programID}
         let get_program = warp::get()
             .and(warp::path!("testnet3" / "program" / ..))
@@ -123,6 +453,74 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             .and(with(self.consensus.clone()))
             .and_then(Self::get_beacons);
 
+        // GET /testnet3/committee/changes/{index}
+        let get_committee_changes = warp::get()
+            .and(warp::path!("testnet3" / "committee" / "changes" / usize))
+            .and(with(self.consensus.clone()))
+            .and_then(Self::get_committee_changes);
+
+        // GET /testnet3/committee/diff/{fromEpoch}/{toEpoch}
+        let get_committee_diff = warp::get()
+            .and(warp::path!("testnet3" / "committee" / "diff" / u32 / u32))
+            .and(with(self.consensus.clone()))
+            .and_then(Self::get_committee_diff);
+
+        // GET /testnet3/equivocations?signer={address}&offset={offset}&limit={limit}
+        let get_equivocation_evidence = warp::get()
+            .and(warp::path!("testnet3" / "equivocations"))
+            .and(warp::query::<EvidenceQuery<N>>())
+            .and(with(self.consensus.clone()))
+            .and_then(Self::get_equivocation_evidence);
+
+        // GET /testnet3/watchList
+        let get_watch_list = warp::get()
+            .and(warp::path!("testnet3" / "watchList"))
+            .and(with(self.consensus.clone()))
+            .and_then(Self::get_watch_list);
+
+        // GET /testnet3/watchList/changes
+        let get_watch_list_changes = warp::get()
+            .and(warp::path!("testnet3" / "watchList" / "changes"))
+            .and(with(self.consensus.clone()))
+            .and_then(Self::get_watch_list_changes);
+
+        // POST /testnet3/watchList/add
+        let watch_list_add = warp::post()
+            .and(warp::path!("testnet3" / "watchList" / "add"))
+            .and(with_auth(self.auth_tokens.clone(), Privilege::WatchList))
+            .and(warp::body::json())
+            .and(with(self.consensus.clone()))
+            .and_then(Self::watch_list_add);
+
+        // POST /testnet3/watchList/remove
+        let watch_list_remove = warp::post()
+            .and(warp::path!("testnet3" / "watchList" / "remove"))
+            .and(with_auth(self.auth_tokens.clone(), Privilege::WatchList))
+            .and(warp::body::json())
+            .and(with(self.consensus.clone()))
+            .and_then(Self::watch_list_remove);
+
+        // POST /testnet3/faucet
+        let faucet_request = warp::post()
+            .and(warp::path!("testnet3" / "faucet"))
+            .and(with_client_ip(self.proxy.clone()))
+            .and(warp::body::content_length_limit(16 * 1024))
+            .and(warp::body::json())
+            .and(with(self.faucet.clone()))
+            .and(with(self.consensus.clone()))
+            .and(with(self.ledger.clone()))
+            .and(with(self.routing.clone()))
+            .and_then(Self::faucet_request);
+
+        // GET /testnet3/node/capacity
+        let get_node_capacity = warp::get()
+            .and(warp::path!("testnet3" / "node" / "capacity"))
+            .and(with(self.consensus.clone()))
+            .and(with(self.proving_jobs.clone()))
+            .and(with(self.requests.clone()))
+            .and(with(self.limiter.clone()))
+            .and_then(Self::get_node_capacity);
+
         // GET /testnet3/peers/count
         let get_peers_count = warp::get()
             .and(warp::path!("testnet3" / "peers" / "count"))
@@ -141,11 +539,107 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             .and(with(self.routing.router().clone()))
             .and_then(Self::get_peers_all_metrics);
 
+        // GET /testnet3/peers/info
+        let get_peers_info = warp::get()
+            .and(warp::path!("testnet3" / "peers" / "info"))
+            .and(with(self.routing.router().clone()))
+            .and_then(Self::get_peers_info);
+
+        // GET /testnet3/peers/connections
+        let get_peers_connections = warp::get()
+            .and(warp::path!("testnet3" / "peers" / "connections"))
+            .and(with(self.routing.router().clone()))
+            .and_then(Self::get_peers_connections);
+
+        // POST /testnet3/peers/connect
+        let connect_peer = warp::post()
+            .and(warp::path!("testnet3" / "peers" / "connect"))
+            .and(with_auth(self.auth_tokens.clone(), Privilege::Peers))
+            .and(warp::body::json())
+            .and(with(self.routing.router().clone()))
+            .and_then(Self::connect_peer);
+
+        // POST /testnet3/peers/disconnect
+        let disconnect_peer = warp::post()
+            .and(warp::path!("testnet3" / "peers" / "disconnect"))
+            .and(with_auth(self.auth_tokens.clone(), Privilege::Peers))
+            .and(warp::body::json())
+            .and(with(self.routing.router().clone()))
+            .and_then(Self::disconnect_peer);
+
+        // GET /testnet3/peers/messages/stats
+        let get_message_stats = warp::get()
+            .and(warp::path!("testnet3" / "peers" / "messages" / "stats"))
+            .and(with(self.routing.router().clone()))
+            .and_then(Self::get_message_stats);
+
+        // GET /testnet3/sync/forks/{limit}
+        let get_fork_history = warp::get()
+            .and(warp::path!("testnet3" / "sync" / "forks" / usize))
+            .and(with(self.routing.router().clone()))
+            .and_then(Self::get_fork_history);
+
+        // GET /testnet3/sync/pipeline
+        let get_pipeline_metrics = warp::get()
+            .and(warp::path!("testnet3" / "sync" / "pipeline"))
+            .and(with(self.consensus.clone()))
+            .and_then(Self::get_pipeline_metrics);
+
+        // GET /testnet3/memoryPool/workers
+        let get_worker_metrics = warp::get()
+            .and(warp::path!("testnet3" / "memoryPool" / "workers"))
+            .and(with(self.consensus.clone()))
+            .and_then(Self::get_worker_metrics);
+
+        // GET /testnet3/debug/memory
+        let get_memory_stats = warp::get()
+            .and(warp::path!("testnet3" / "debug" / "memory"))
+            .and(with_auth(self.auth_tokens.clone(), Privilege::Admin))
+            .and(with(self.profiling))
+            .and_then(Self::get_memory_stats);
+
+        // GET /testnet3/debug/cpu?seconds={seconds}
+        let get_cpu_profile = warp::get()
+            .and(warp::path!("testnet3" / "debug" / "cpu"))
+            .and(with_auth(self.auth_tokens.clone(), Privilege::Admin))
+            .and(warp::query::<ProfileQuery>())
+            .and(with(self.profiling))
+            .and_then(Self::get_cpu_profile);
+
+        // GET /testnet3/debug/pprof?seconds={seconds}
+        let get_cpu_pprof = warp::get()
+            .and(warp::path!("testnet3" / "debug" / "pprof"))
+            .and(with_auth(self.auth_tokens.clone(), Privilege::Admin))
+            .and(warp::query::<ProfileQuery>())
+            .and(with(self.profiling))
+            .and_then(Self::get_cpu_pprof);
+
+        // GET /testnet3/debug/allocator
+        let get_allocator_stats = warp::get()
+            .and(warp::path!("testnet3" / "debug" / "allocator"))
+            .and(with_auth(self.auth_tokens.clone(), Privilege::Admin))
+            .and(with(self.profiling))
+            .and_then(Self::get_allocator_stats);
+
         // GET /testnet3/node/address
         let get_node_address = warp::get()
             .and(warp::path!("testnet3" / "node" / "address"))
             .and(with(self.routing.router().address()))
-            .and_then(|address: Address<N>| async move { Ok::<_, Rejection>(reply::json(&address.to_string())) });
+            .and_then(Self::get_node_address);
+
+        // GET /testnet3/node/role
+        let get_node_role = warp::get()
+            .and(warp::path!("testnet3" / "node" / "role"))
+            .and(with(self.routing.router().clone()))
+            .and_then(Self::get_node_role);
+
+        // POST /testnet3/node/standby
+        let set_node_standby = warp::post()
+            .and(warp::path!("testnet3" / "node" / "standby"))
+            .and(with_auth(self.auth_tokens.clone(), Privilege::Admin))
+            .and(warp::body::json())
+            .and(with(self.routing.router().clone()))
+            .and_then(Self::set_node_standby);
 
         // GET /testnet3/find/blockHash/{transactionID}
         let find_block_hash = warp::get()
@@ -155,6 +649,35 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
             .and(with(self.ledger.clone()))
             .and_then(Self::find_block_hash);
 
+        // GET /testnet3/address/{address}/transactions?offset={offset}&limit={limit}
+        let get_transactions_for_address = warp::get()
+            .and(warp::path!("testnet3" / "address" / ..))
+            .and(warp::path::param::<Address<N>>())
+            .and(warp::path!("transactions"))
+            .and(warp::query::<Page>())
+            .and(with(self.indexes.clone()))
+            .and_then(Self::get_transactions_for_address);
+
+        // POST /testnet3/query
+        let query_ledger = warp::post()
+            .and(warp::path!("testnet3" / "query"))
+            .and(with_auth(self.auth_tokens.clone(), Privilege::History))
+            .and(warp::body::content_length_limit(16 * 1024))
+            .and(warp::body::json())
+            .and(with(self.indexes.clone()))
+            .and(with(self.ledger.clone()))
+            .and_then(Self::query_ledger);
+
+        // POST /testnet3/history?offset={offset}&limit={limit}
+        let get_transaction_history = warp::post()
+            .and(warp::path!("testnet3" / "history"))
+            .and(with_auth(self.auth_tokens.clone(), Privilege::History))
+            .and(warp::query::<Page>())
+            .and(warp::body::json())
+            .and(with(self.history_scans.clone()))
+            .and(with(self.ledger.clone()))
+            .and_then(Self::get_transaction_history);
+
         // GET /testnet3/find/transactionID/deployment/{programID}
         let find_transaction_id_from_program_id = warp::get()
             .and(warp::path!("testnet3" / "find" / "transactionID" / "deployment" / ..))
@@ -183,66 +706,323 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         let transaction_broadcast = warp::post()
             .and(warp::path!("testnet3" / "transaction" / "broadcast"))
             .and(warp::body::content_length_limit(16 * 1024 * 1024))
-            .and(warp::body::json())
+            .and(json_body(self.json_mode))
             .and(with(self.consensus.clone()))
             .and(with(self.routing.clone()))
             .and_then(Self::transaction_broadcast);
 
+        // POST /testnet3/transaction/broadcast/raw
+        let transaction_broadcast_raw = warp::post()
+            .and(warp::path!("testnet3" / "transaction" / "broadcast" / "raw"))
+            .and(warp::body::content_length_limit(32 * 1024 * 1024))
+            .and(warp::body::json())
+            .and(with(self.consensus.clone()))
+            .and(with(self.routing.clone()))
+            .and_then(Self::transaction_broadcast_raw);
+
+        // POST /testnet3/transaction/validate/raw
+        let transaction_validate_raw = warp::post()
+            .and(warp::path!("testnet3" / "transaction" / "validate" / "raw"))
+            .and(warp::body::content_length_limit(32 * 1024 * 1024))
+            .and(warp::body::json())
+            .and(with(self.consensus.clone()))
+            .and_then(Self::transaction_validate_raw);
+
+        // POST /testnet3/transmission/broadcast/{kind}
+        let transmission_broadcast = warp::post()
+            .and(warp::path!("testnet3" / "transmission" / "broadcast" / String))
+            .and(warp::body::content_length_limit(32 * 1024 * 1024))
+            .and(warp::body::json())
+            .and(with(self.consensus.clone()))
+            .and(with(self.routing.clone()))
+            .and_then(Self::transmission_broadcast);
+
+        // POST /testnet3/transactions/broadcast
+        let transactions_broadcast = warp::post()
+            .and(warp::path!("testnet3" / "transactions" / "broadcast"))
+            .and(warp::body::content_length_limit(64 * 1024 * 1024))
+            .and(warp::body::json())
+            .and(with(self.json_mode))
+            .and(with(self.consensus.clone()))
+            .and(with(self.routing.clone()))
+            .and_then(Self::transactions_broadcast);
+
+        // POST /testnet3/transaction/broadcast/wait?timeout={seconds}
+        let transaction_broadcast_and_wait = warp::post()
+            .and(warp::path!("testnet3" / "transaction" / "broadcast" / "wait"))
+            .and(warp::query::<WaitTimeout>())
+            .and(warp::body::content_length_limit(16 * 1024 * 1024))
+            .and(json_body(self.json_mode))
+            .and(with(self.consensus.clone()))
+            .and(with(self.ledger.clone()))
+            .and(with(self.routing.clone()))
+            .and_then(Self::transaction_broadcast_and_wait);
+
+        // POST /testnet3/transaction/broadcast/deadline/{maxHeight}
+        let transaction_broadcast_with_deadline = warp::post()
+            .and(warp::path!("testnet3" / "transaction" / "broadcast" / "deadline" / u32))
+            .and(with_auth(self.auth_tokens.clone(), Privilege::Broadcast))
+            .and(warp::body::content_length_limit(16 * 1024 * 1024))
+            .and(json_body(self.json_mode))
+            .and(with(self.consensus.clone()))
+            .and(with(self.ledger.clone()))
+            .and(with(self.routing.clone()))
+            .and(with(self.deadlines.clone()))
+            .and_then(Self::transaction_broadcast_with_deadline);
+
+        // GET /testnet3/transaction/deadline/{transactionID}
+        let get_transaction_deadline = warp::get()
+            .and(warp::path!("testnet3" / "transaction" / "deadline" / ..))
+            .and(warp::path::param::<N::TransactionID>())
+            .and(warp::path::end())
+            .and(with(self.deadlines.clone()))
+            .and_then(Self::get_transaction_deadline);
+
+        // POST /testnet3/proving/jobs
+        let submit_proving_job = warp::post()
+            .and(warp::path!("testnet3" / "proving" / "jobs"))
+            .and(with_auth(self.auth_tokens.clone(), Privilege::Proving))
+            .and(warp::body::content_length_limit(16 * 1024 * 1024))
+            .and(warp::body::json())
+            .and(with(self.proving_jobs.clone()))
+            .and_then(Self::submit_proving_job);
+
+        // GET /testnet3/proving/jobs/{id}
+        let get_proving_job = warp::get()
+            .and(warp::path!("testnet3" / "proving" / "jobs" / u64))
+            .and(with_auth(self.auth_tokens.clone(), Privilege::Proving))
+            .and(with(self.proving_jobs.clone()))
+            .and_then(Self::get_proving_job);
+
+        // POST /testnet3/proving/jobs/{id}/cancel
+        let cancel_proving_job = warp::post()
+            .and(warp::path!("testnet3" / "proving" / "jobs" / u64 / "cancel"))
+            .and(with_auth(self.auth_tokens.clone(), Privilege::Proving))
+            .and(with(self.proving_jobs.clone()))
+            .and_then(Self::cancel_proving_job);
+
+        // GET /testnet3/subscribe
+        let subscribe = warp::path!("testnet3" / "subscribe")
+            .and(warp::ws())
+            .and(with(self.consensus.clone()))
+            .and(with(self.deadlines.clone()))
+            .and_then(Self::subscribe);
+
+        // GET /testnet3/builder
+        let subscribe_builder = warp::path!("testnet3" / "builder")
+            .and(warp::ws())
+            .and(with_caller(self.auth_tokens.clone(), Privilege::Builder))
+            .and(with(self.consensus.clone()))
+            .and(with(self.ledger.clone()))
+            .and_then(Self::subscribe_builder);
+
+        // GET /testnet3/stream/finalizedBlocks?from={height}
+        let stream_finalized_blocks = warp::path!("testnet3" / "stream" / "finalizedBlocks")
+            .and(warp::ws())
+            .and(warp::query::<BlockCursor>())
+            .and(with(self.consensus.clone()))
+            .and(with(self.ledger.clone()))
+            .and_then(Self::stream_finalized_blocks);
+
+        // GET /health
+        let get_health = warp::get().and(warp::path!("health")).and_then(Self::get_health);
+
+        // GET /ready
+        let get_readiness = warp::get()
+            .and(warp::path!("ready"))
+            .and(with(self.ledger.clone()))
+            .and(with(self.routing.router().clone()))
+            .and(with(self.readiness))
+            .and_then(Self::get_readiness);
+
+        // GET /spec.json
+        let get_spec = warp::get().and(warp::path!("spec.json")).and(with(self.proxy.clone())).and_then(Self::get_spec);
+
         // Return the list of routes.
         latest_height
             .or(latest_hash)
             .or(latest_block)
             .or(latest_state_root)
+            .or(get_finalized_height)
             .or(get_block)
             .or(get_blocks)
             .or(get_block_by_hash)
             .or(get_block_height_by_hash)
             .or(get_block_transactions)
+            .or(get_header_proof)
             .or(get_transaction)
             .or(get_memory_pool_transactions)
+            .or(get_memory_pool_transaction_ids)
+            .or(get_memory_pool_info)
+            .or(get_memory_pool_transmissions)
+            .or(estimate_fee)
             .or(get_program)
+            .or(get_program_deployment)
+            .or(list_programs)
             .or(get_state_path_for_commitment)
             .or(get_beacons)
+            .or(get_committee_changes)
+            .or(get_committee_diff)
+            .or(get_equivocation_evidence)
+            .or(get_watch_list)
+            .or(get_watch_list_changes)
+            .or(watch_list_add)
+            .or(watch_list_remove)
+            .or(get_node_capacity)
             .or(get_peers_count)
             .or(get_peers_all)
             .or(get_peers_all_metrics)
+            .or(get_peers_info)
+            .or(get_peers_connections)
+            .or(connect_peer)
+            .or(disconnect_peer)
+            .or(get_message_stats)
+            .or(get_fork_history)
+            .or(get_pipeline_metrics)
+            .or(get_worker_metrics)
+            .or(get_memory_stats)
+            .or(get_cpu_profile)
+            .or(get_cpu_pprof)
+            .or(get_allocator_stats)
             .or(get_node_address)
+            .or(get_node_role)
+            .or(set_node_standby)
             .or(find_block_hash)
+            .or(get_transactions_for_address)
+            .or(query_ledger)
+            .or(get_transaction_history)
             .or(find_transaction_id_from_program_id)
             .or(find_transaction_id_from_transition_id)
             .or(find_transition_id)
             .or(transaction_broadcast)
+            .or(transaction_broadcast_raw)
+            .or(transaction_validate_raw)
+            .or(transmission_broadcast)
+            .or(transactions_broadcast)
+            .or(transaction_broadcast_and_wait)
+            .or(transaction_broadcast_with_deadline)
+            .or(get_transaction_deadline)
+            .or(submit_proving_job)
+            .or(get_proving_job)
+            .or(cancel_proving_job)
+            .or(faucet_request)
+            .or(subscribe)
+            .or(subscribe_builder)
+            .or(stream_finalized_blocks)
+            .or(get_health)
+            .or(get_readiness)
+            .or(get_spec)
     }
 }
 
 impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
+    /// Returns the remote archive, if it is configured and contains the given block heights.
+    fn archive_for(archive: &Arc<RwLock<Option<RemoteEraArchive>>>, heights: &Range<u32>) -> Option<RemoteEraArchive> {
+        archive.read().clone().filter(|archive| heights.end <= archive.end_height())
+    }
+
+    /// Returns the given blocks from the remote archive, checked against the block hashes in the ledger.
+    async fn get_archived_blocks(
+        ledger: &Ledger<N, C>,
+        archive: &RemoteEraArchive,
+        heights: Range<u32>,
+    ) -> Result<Vec<Block<N>>, Rejection> {
+        let hashes =
+            heights.clone().map(|height| ledger.get_hash(height)).collect::<Result<Vec<_>, _>>().or_reject()?;
+        archive.get_blocks::<N>(heights, &hashes).await.or_reject()
+    }
+
+    /// Returns the tip height of the ledger for the given finality.
+    fn tip_height(ledger: &Ledger<N, C>, finality: Finality) -> u32 {
+        match finality {
+            Finality::Latest => ledger.latest_height(),
+            Finality::Finalized => ledger.finalized_height(),
+        }
+    }
+
+    /// Ensures the given block height is at or below the tip of the ledger for the given finality.
+    fn ensure_within_tip(ledger: &Ledger<N, C>, finality: Finality, height: u32) -> Result<(), Rejection> {
+        let tip_height = Self::tip_height(ledger, finality);
+        match height > tip_height {
+            true => Err(reject::custom(RestError::NotSynced { height, tip_height })),
+            false => Ok(()),
+        }
+    }
+
+    /// Ensures the given block hash exists in the ledger.
+    fn ensure_block_hash_exists(ledger: &Ledger<N, C>, hash: &N::BlockHash) -> Result<(), Rejection> {
+        match ledger.contains_block_hash(hash).or_reject()? {
+            true => Ok(()),
+            false => Err(reject::custom(RestError::BlockNotFound(hash.to_string()))),
+        }
+    }
+
     /// Returns the latest block height.
-    async fn latest_height(ledger: Ledger<N, C>) -> Result<impl Reply, Rejection> {
-        Ok(reply::json(&ledger.latest_height()))
+    async fn latest_height(query: FinalityQuery, ledger: Ledger<N, C>) -> Result<impl Reply, Rejection> {
+        Ok(reply::json(&Self::tip_height(&ledger, query.finality)))
+    }
+
+    /// Returns the latest finalized block height.
+    async fn get_finalized_height(ledger: Ledger<N, C>) -> Result<impl Reply, Rejection> {
+        Ok(reply::json(&ledger.finalized_height()))
     }
 
     /// Returns the latest block hash.
-    async fn latest_hash(ledger: Ledger<N, C>) -> Result<impl Reply, Rejection> {
-        Ok(reply::json(&ledger.latest_hash()))
+    async fn latest_hash(query: FinalityQuery, ledger: Ledger<N, C>) -> Result<impl Reply, Rejection> {
+        match query.finality {
+            Finality::Latest => Ok(reply::json(&ledger.latest_hash())),
+            Finality::Finalized => Ok(reply::json(&ledger.get_hash(ledger.finalized_height()).or_reject()?)),
+        }
     }
 
     /// Returns the latest block.
-    async fn latest_block(ledger: Ledger<N, C>) -> Result<impl Reply, Rejection> {
-        Ok(reply::json(&ledger.latest_block()))
+    async fn latest_block(query: FinalityQuery, ledger: Ledger<N, C>) -> Result<impl Reply, Rejection> {
+        match query.finality {
+            Finality::Latest => Ok(reply::json(&ledger.latest_block())),
+            Finality::Finalized => Ok(reply::json(&ledger.get_block(ledger.finalized_height()).or_reject()?)),
+        }
     }
 
     /// Returns the latest state root.
-    async fn latest_state_root(ledger: Ledger<N, C>) -> Result<impl Reply, Rejection> {
-        Ok(reply::json(&ledger.latest_state_root()))
+    async fn latest_state_root(query: FinalityQuery, ledger: Ledger<N, C>) -> Result<impl Reply, Rejection> {
+        match query.finality {
+            Finality::Latest => Ok(reply::json(&ledger.latest_state_root())),
+            Finality::Finalized => Ok(reply::json(&ledger.get_state_root(ledger.finalized_height()).or_reject()?)),
+        }
     }
 
     /// Returns the block for the given block height.
-    async fn get_block(height: u32, ledger: Ledger<N, C>) -> Result<impl Reply, Rejection> {
-        Ok(reply::json(&ledger.get_block(height).or_reject()?))
+    async fn get_block(
+        height: u32,
+        query: FinalityQuery,
+        ledger: Ledger<N, C>,
+        archive: Arc<RwLock<Option<RemoteEraArchive>>>,
+    ) -> Result<impl Reply, Rejection> {
+        // Ensure the block is within the requested tip.
+        Self::ensure_within_tip(&ledger, query.finality, height)?;
+
+        // Serve the block from the ledger, falling back to the remote archive if the block is not stored locally.
+        let block = match ledger.get_block(height) {
+            Ok(block) => block,
+            Err(error) => {
+                let heights = height..height.saturating_add(1);
+                match Self::archive_for(&archive, &heights) {
+                    Some(archive) => Self::get_archived_blocks(&ledger, &archive, heights).await?.pop(),
+                    None => None,
+                }
+                .ok_or(error)
+                .or_reject()?
+            }
+        };
+        Ok(reply::json(&block))
     }
 
     /// Returns the blocks for the given block range.
-    async fn get_blocks(block_range: BlockRange, ledger: Ledger<N, C>) -> Result<impl Reply, Rejection> {
+    async fn get_blocks(
+        block_range: BlockRange,
+        ledger: Ledger<N, C>,
+        archive: Arc<RwLock<Option<RemoteEraArchive>>>,
+    ) -> Result<impl Reply, Rejection> {
         let start_height = block_range.start;
         let end_height = block_range.end;
 
@@ -259,39 +1039,177 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
                 end_height - start_height
             ))));
         }
+        // Ensure the blocks are within the requested tip.
+        Self::ensure_within_tip(&ledger, block_range.finality, end_height.saturating_sub(1))?;
 
-        let blocks = cfg_into_iter!((start_height..end_height))
-            .map(|height| ledger.get_block(height).or_reject())
-            .collect::<Result<Vec<_>, _>>()?;
+        // Serve the blocks from the ledger, falling back to the remote archive if the blocks are not stored locally.
+        let blocks = match cfg_into_iter!((start_height..end_height))
+            .map(|height| ledger.get_block(height))
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(blocks) => blocks,
+            Err(error) => match Self::archive_for(&archive, &(start_height..end_height)) {
+                Some(archive) => Self::get_archived_blocks(&ledger, &archive, start_height..end_height).await?,
+                None => Err(error).or_reject()?,
+            },
+        };
 
         Ok(reply::json(&blocks))
     }
 
     /// Returns the block for the given block hash.
-    async fn get_block_by_hash(hash: N::BlockHash, ledger: Ledger<N, C>) -> Result<impl Reply, Rejection> {
-        Ok(reply::json(&ledger.get_block_by_hash(&hash).or_reject()?))
+    async fn get_block_by_hash(
+        hash: N::BlockHash,
+        query: FinalityQuery,
+        ledger: Ledger<N, C>,
+    ) -> Result<impl Reply, Rejection> {
+        // Ensure the block exists.
+        Self::ensure_block_hash_exists(&ledger, &hash)?;
+        let block = ledger.get_block_by_hash(&hash).or_reject()?;
+        // Ensure the block is within the requested tip.
+        Self::ensure_within_tip(&ledger, query.finality, block.height())?;
+        Ok(reply::json(&block))
     }
 
     /// Returns the block height for the given block hash.
-    async fn get_block_height_by_hash(hash: N::BlockHash, ledger: Ledger<N, C>) -> Result<impl Reply, Rejection> {
-        Ok(reply::json(&ledger.get_height(&hash).or_reject()?))
+    async fn get_block_height_by_hash(
+        hash: N::BlockHash,
+        query: FinalityQuery,
+        ledger: Ledger<N, C>,
+    ) -> Result<impl Reply, Rejection> {
+        // Ensure the block exists.
+        Self::ensure_block_hash_exists(&ledger, &hash)?;
+        let height = ledger.get_height(&hash).or_reject()?;
+        // Ensure the block is within the requested tip.
+        Self::ensure_within_tip(&ledger, query.finality, height)?;
+        Ok(reply::json(&height))
     }
 
     /// Returns the transactions for the given block height.
-    async fn get_block_transactions(height: u32, ledger: Ledger<N, C>) -> Result<impl Reply, Rejection> {
+    async fn get_block_transactions(
+        height: u32,
+        query: FinalityQuery,
+        ledger: Ledger<N, C>,
+    ) -> Result<impl Reply, Rejection> {
+        // Ensure the block is within the requested tip.
+        Self::ensure_within_tip(&ledger, query.finality, height)?;
         Ok(reply::json(&ledger.get_transactions(height).or_reject()?))
     }
 
+    /// Returns the block header for the given block height, with a proof of its inclusion in the latest state root.
+    async fn get_header_proof(height: u32, ledger: Ledger<N, C>) -> Result<impl Reply, Rejection> {
+        // Ensure the block is within the latest tip.
+        Self::ensure_within_tip(&ledger, Finality::Latest, height)?;
+        // Construct the proof on a blocking thread, as it reads the block from storage.
+        let proof = match tokio::task::spawn_blocking(move || ledger.get_header_proof(height)).await {
+            Ok(proof) => proof.or_reject()?,
+            Err(error) => {
+                return Err(reject::custom(RestError::Request(format!("Failed to prove the header - {error}"))))
+            }
+        };
+        Ok(reply::json(&HeaderProofInfo {
+            state_root: proof.state_root,
+            block_height: proof.block_height,
+            block_hash: proof.block_hash,
+            previous_hash: proof.previous_hash,
+            header: proof.header,
+            block_path: hex::encode(proof.block_path.to_bytes_le().or_reject()?),
+        }))
+    }
+
     /// Returns the transaction for the given transaction ID.
     async fn get_transaction(transaction_id: N::TransactionID, ledger: Ledger<N, C>) -> Result<impl Reply, Rejection> {
-        Ok(reply::json(&ledger.get_transaction(transaction_id).or_reject()?))
+        // Retrieve the transaction.
+        let transaction = ledger.get_transaction(transaction_id).or_reject()?;
+        // Compute the fee breakdown.
+        let fee_breakdown = FeeBreakdown::new(&transaction).or_reject()?;
+        // Locate the transaction in the ledger.
+        let (block_height, index) = match ledger.find_transaction_location(&transaction_id).or_reject()? {
+            Some(location) => location,
+            None => {
+                return Err(reject::custom(RestError::Request(format!(
+                    "Missing block for transaction '{transaction_id}'"
+                ))));
+            }
+        };
+        let confirmation = Confirmation {
+            block_height,
+            block_hash: ledger.get_hash(block_height).or_reject()?,
+            index,
+            depth: ledger.latest_height().saturating_sub(block_height),
+            finalized: block_height <= ledger.finalized_height(),
+        };
+
+        Ok(reply::json(&TransactionInfo { transaction, fee_breakdown, confirmation }))
     }
 
     /// Returns the transactions in the memory pool.
     async fn get_memory_pool_transactions(consensus: Option<Consensus<N, C>>) -> Result<impl Reply, Rejection> {
         match consensus {
             Some(consensus) => Ok(reply::json(&consensus.memory_pool().unconfirmed_transactions())),
-            None => Err(reject::custom(RestError::Request("Invalid endpoint".to_string()))),
+            None => Err(reject::custom(RestError::InvalidEndpoint)),
+        }
+    }
+
+    /// Returns a page of the transaction IDs in the memory pool, in order of arrival.
+    async fn get_memory_pool_transaction_ids(
+        page: Page,
+        consensus: Option<Consensus<N, C>>,
+    ) -> Result<impl Reply, Rejection> {
+        const MAX_PAGE_LIMIT: usize = 1000;
+
+        // Ensure the limit is bounded.
+        if page.limit > MAX_PAGE_LIMIT {
+            return Err(reject::custom(RestError::Request(format!(
+                "Cannot request more than {MAX_PAGE_LIMIT} transaction IDs per call (requested {})",
+                page.limit
+            ))));
+        }
+
+        match consensus {
+            Some(consensus) => {
+                Ok(reply::json(&consensus.memory_pool().unconfirmed_transaction_ids(page.offset, page.limit)))
+            }
+            None => Err(reject::custom(RestError::InvalidEndpoint)),
+        }
+    }
+
+    /// Returns the number, total size, and fee statistics of the transactions in the memory pool.
+    async fn get_memory_pool_info(consensus: Option<Consensus<N, C>>) -> Result<impl Reply, Rejection> {
+        match consensus {
+            Some(consensus) => Ok(reply::json(&consensus.memory_pool().unconfirmed_transactions_info().or_reject()?)),
+            None => Err(reject::custom(RestError::InvalidEndpoint)),
+        }
+    }
+
+    /// Returns the unconfirmed transmissions of the given kind, as hex-encoded payloads with their fees.
+    async fn get_memory_pool_transmissions(
+        kind: String,
+        consensus: Option<Consensus<N, C>>,
+    ) -> Result<impl Reply, Rejection> {
+        let consensus = match consensus {
+            Some(consensus) => consensus,
+            None => return Err(reject::custom(RestError::InvalidEndpoint)),
+        };
+        if !consensus.transmissions().is_supported(&kind) {
+            let error = format!("The '{kind}' transmission kind is not registered");
+            return Err(reject::custom(RestError::Request(error)));
+        }
+        let transmissions = consensus
+            .unconfirmed_transmissions(&kind)
+            .or_reject()?
+            .into_iter()
+            .map(|(payload, fee)| serde_json::json!({ "payload": hex::encode(payload), "fee": fee }))
+            .collect::<Vec<_>>();
+        Ok(reply::json(&transmissions))
+    }
+
+    /// Returns the suggested priority fee, in microcredits, for a transaction to be included within the target
+    /// number of blocks, from the fees of the recent blocks and the memory pool.
+    async fn estimate_fee(fee_target: FeeTarget, consensus: Option<Consensus<N, C>>) -> Result<impl Reply, Rejection> {
+        match consensus {
+            Some(consensus) => Ok(reply::json(&consensus.estimate_priority_fee(fee_target.target).or_reject()?)),
+            None => Err(reject::custom(RestError::InvalidEndpoint)),
         }
     }
 
@@ -306,22 +1224,187 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(reply::json(&program))
     }
 
-    /// Returns the state path for the given commitment.
-    async fn get_state_path_for_commitment(
-        commitment: Field<N>,
-        ledger: Ledger<N, C>,
-    ) -> Result<impl Reply, Rejection> {
-        Ok(reply::json(&ledger.get_state_path_for_commitment(&commitment).or_reject()?))
+    /// Returns the given program, with the transaction that deployed it.
+    async fn get_program_deployment(program_id: ProgramID<N>, ledger: Ledger<N, C>) -> Result<impl Reply, Rejection> {
+        // Retrieve the deployment transaction.
+        let transaction_id = match ledger.find_transaction_id_from_program_id(&program_id).or_reject()? {
+            Some(transaction_id) => transaction_id,
+            None => return Err(reject::custom(RestError::Request(format!("Missing deployment for '{program_id}'")))),
+        };
+        let transaction = ledger.get_transaction(transaction_id).or_reject()?;
+        // Locate the deployment transaction in the ledger.
+        let block_height = match ledger.find_transaction_location(&transaction_id).or_reject()? {
+            Some((block_height, _)) => block_height,
+            None => {
+                return Err(reject::custom(RestError::Request(format!(
+                    "Missing block for transaction '{transaction_id}'"
+                ))));
+            }
+        };
+        let program = ledger.get_program(program_id).or_reject()?;
+
+        Ok(reply::json(&ProgramDeployment { program, transaction, block_height }))
     }
 
-    /// Returns the list of current beacons.
+    /// Returns a page of the IDs of the deployed programs, in storage order.
+    async fn list_programs(page: Page, ledger: Ledger<N, C>) -> Result<impl Reply, Rejection> {
+        const MAX_PAGE_LIMIT: usize = 1000;
+
+        // Ensure the limit is bounded.
+        if page.limit > MAX_PAGE_LIMIT {
+            return Err(reject::custom(RestError::Request(format!(
+                "Cannot request more than {MAX_PAGE_LIMIT} program IDs per call (requested {})",
+                page.limit
+            ))));
+        }
+
+        let program_ids = ledger
+            .program_ids()
+            .skip(page.offset)
+            .take(page.limit)
+            .map(|program_id| program_id.into_owned())
+            .collect::<Vec<_>>();
+        Ok(reply::json(&program_ids))
+    }
+
+    /// Returns the state path for the given commitment.
+    async fn get_state_path_for_commitment(
+        commitment: Field<N>,
+        ledger: Ledger<N, C>,
+    ) -> Result<impl Reply, Rejection> {
+        Ok(reply::json(&ledger.get_state_path_for_commitment(&commitment).or_reject()?))
+    }
+
+    /// Returns the list of current beacons.
     async fn get_beacons(consensus: Option<Consensus<N, C>>) -> Result<impl Reply, Rejection> {
         match consensus {
             Some(consensus) => Ok(reply::json(&consensus.beacons().keys().collect::<Vec<&Address<N>>>())),
-            None => Err(reject::custom(RestError::Request("Invalid endpoint".to_string()))),
+            None => Err(reject::custom(RestError::InvalidEndpoint)),
+        }
+    }
+
+    /// Returns the changes to the beacon set, starting from the given index.
+    async fn get_committee_changes(index: usize, consensus: Option<Consensus<N, C>>) -> Result<impl Reply, Rejection> {
+        match consensus {
+            Some(consensus) => Ok(reply::json(&consensus.committee_history().changes_since(index))),
+            None => Err(reject::custom(RestError::InvalidEndpoint)),
+        }
+    }
+
+    /// Returns the difference in the beacon set between the two given epochs.
+    async fn get_committee_diff(
+        from_epoch: u32,
+        to_epoch: u32,
+        consensus: Option<Consensus<N, C>>,
+    ) -> Result<impl Reply, Rejection> {
+        match consensus {
+            Some(consensus) => Ok(reply::json(&consensus.get_committee_diff(from_epoch, to_epoch).or_reject()?)),
+            None => Err(reject::custom(RestError::InvalidEndpoint)),
+        }
+    }
+
+    /// Returns a page of the evidence of the detected equivocations, optionally for the given signer.
+    async fn get_equivocation_evidence(
+        query: EvidenceQuery<N>,
+        consensus: Option<Consensus<N, C>>,
+    ) -> Result<impl Reply, Rejection> {
+        const MAX_PAGE_LIMIT: usize = 1000;
+
+        // Ensure the limit is bounded.
+        if query.limit > MAX_PAGE_LIMIT {
+            return Err(reject::custom(RestError::Request(format!(
+                "Cannot request more than {MAX_PAGE_LIMIT} equivocations per call (requested {})",
+                query.limit
+            ))));
+        }
+
+        match consensus {
+            Some(consensus) => {
+                Ok(reply::json(&consensus.equivocations().evidence(query.signer, query.offset, query.limit)))
+            }
+            None => Err(reject::custom(RestError::InvalidEndpoint)),
         }
     }
 
+    /// Returns the watched addresses, and their latest public balances.
+    async fn get_watch_list(consensus: Option<Consensus<N, C>>) -> Result<impl Reply, Rejection> {
+        match consensus {
+            Some(consensus) => Ok(reply::json(&consensus.watch_list().balances())),
+            None => Err(reject::custom(RestError::InvalidEndpoint)),
+        }
+    }
+
+    /// Returns the most recent changes of the watched addresses.
+    async fn get_watch_list_changes(consensus: Option<Consensus<N, C>>) -> Result<impl Reply, Rejection> {
+        match consensus {
+            Some(consensus) => Ok(reply::json(&consensus.watch_list().changes())),
+            None => Err(reject::custom(RestError::InvalidEndpoint)),
+        }
+    }
+
+    /// Adds the given address to the watch list.
+    async fn watch_list_add(
+        _: (),
+        address: Address<N>,
+        consensus: Option<Consensus<N, C>>,
+    ) -> Result<impl Reply, Rejection> {
+        match consensus {
+            Some(consensus) => {
+                consensus.watch_address(address).or_reject()?;
+                Ok(reply::json(&address))
+            }
+            None => Err(reject::custom(RestError::InvalidEndpoint)),
+        }
+    }
+
+    /// Removes the given address from the watch list.
+    async fn watch_list_remove(
+        _: (),
+        address: Address<N>,
+        consensus: Option<Consensus<N, C>>,
+    ) -> Result<impl Reply, Rejection> {
+        match consensus {
+            Some(consensus) => {
+                consensus.watch_list().unwatch(&address).or_reject()?;
+                Ok(reply::json(&address))
+            }
+            None => Err(reject::custom(RestError::InvalidEndpoint)),
+        }
+    }
+
+    /// Returns the address of the node account.
+    async fn get_node_address(address: Address<N>) -> Result<impl Reply, Rejection> {
+        Ok(reply::json(&address.to_string()))
+    }
+
+    /// Returns the node type, and whether the node is in standby mode.
+    async fn get_node_role(router: Router<N>) -> Result<impl Reply, Rejection> {
+        Ok(reply::json(&NodeRole { node_type: router.node_type(), standby: router.is_standby() }))
+    }
+
+    /// Enters or leaves the standby mode, in which the node stays in sync without producing blocks or solutions.
+    async fn set_node_standby(_: (), standby: bool, router: Router<N>) -> Result<impl Reply, Rejection> {
+        router.set_standby(standby);
+        Ok(reply::json(&NodeRole { node_type: router.node_type(), standby: router.is_standby() }))
+    }
+
+    /// Returns the current load on the node.
+    async fn get_node_capacity(
+        consensus: Option<Consensus<N, C>>,
+        proving_jobs: ProvingJobs<N>,
+        requests: RequestCounter,
+        limiter: RateLimiter,
+    ) -> Result<impl Reply, Rejection> {
+        let capacity = NodeCapacity::new(
+            &requests,
+            &limiter,
+            proving_jobs.num_queued(),
+            consensus.as_ref().map(|consensus| consensus.pipeline_metrics()),
+            consensus.as_ref().map_or(0, |consensus| consensus.memory_pool().num_unconfirmed_transactions()),
+        );
+        Ok(reply::json(&capacity))
+    }
+
     /// Returns the number of peers connected to the node.
     async fn get_peers_count(router: Router<N>) -> Result<impl Reply, Rejection> {
         Ok(reply::json(&router.number_of_connected_peers()))
@@ -337,11 +1420,211 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         Ok(reply::json(&router.connected_metrics()))
     }
 
+    /// Returns the direction, node type, version, and last seen time of each connected peer.
+    async fn get_peers_info(router: Router<N>) -> Result<impl Reply, Rejection> {
+        Ok(reply::json(&router.connected_peer_info()))
+    }
+
+    /// Returns the number of connected peers, by connection direction.
+    async fn get_peers_connections(router: Router<N>) -> Result<impl Reply, Rejection> {
+        Ok(reply::json(&router.connection_count()))
+    }
+
+    /// Attempts to connect to the given peer IP.
+    async fn connect_peer(_: (), peer_ip: SocketAddr, router: Router<N>) -> Result<impl Reply, Rejection> {
+        router.connect(peer_ip);
+        Ok(reply::json(&peer_ip))
+    }
+
+    /// Disconnects from the given peer IP, if the peer is connected.
+    async fn disconnect_peer(_: (), peer_ip: SocketAddr, router: Router<N>) -> Result<impl Reply, Rejection> {
+        // Ensure the peer is connected.
+        if !router.is_connected(&peer_ip) {
+            return Err(reject::custom(RestError::Request(format!("Peer '{peer_ip}' is not connected"))));
+        }
+        router.disconnect(peer_ip);
+        Ok(reply::json(&peer_ip))
+    }
+
+    /// Returns the inbound message statistics of the connected peers, by message type.
+    async fn get_message_stats(router: Router<N>) -> Result<impl Reply, Rejection> {
+        Ok(reply::json(&router.message_stats().get_all()))
+    }
+
+    /// Returns up to `limit` of the most recently observed forks, from newest to oldest.
+    async fn get_fork_history(limit: usize, router: Router<N>) -> Result<impl Reply, Rejection> {
+        const MAX_FORK_HISTORY_LIMIT: usize = 100;
+
+        // Ensure the limit is bounded.
+        if limit > MAX_FORK_HISTORY_LIMIT {
+            return Err(reject::custom(RestError::Request(format!(
+                "Cannot request more than {MAX_FORK_HISTORY_LIMIT} forks per call (requested {limit})"
+            ))));
+        }
+
+        Ok(reply::json(&router.sync().get_fork_history(limit)))
+    }
+
+    /// Returns the metrics of each stage of the block pipeline, in pipeline order.
+    async fn get_pipeline_metrics(consensus: Option<Consensus<N, C>>) -> Result<impl Reply, Rejection> {
+        match consensus {
+            Some(consensus) => Ok(reply::json(&consensus.pipeline_metrics().snapshot())),
+            None => Err(reject::custom(RestError::InvalidEndpoint)),
+        }
+    }
+
+    /// Returns the queue metrics of each transmission worker, grouped by class.
+    async fn get_worker_metrics(consensus: Option<Consensus<N, C>>) -> Result<impl Reply, Rejection> {
+        match consensus {
+            Some(consensus) => Ok(reply::json(&consensus.workers().metrics())),
+            None => Err(reject::custom(RestError::InvalidEndpoint)),
+        }
+    }
+
+    /// Returns the memory usage of the node process, if the profiling endpoints are enabled.
+    async fn get_memory_stats(_: (), profiling: bool) -> Result<impl Reply, Rejection> {
+        if !profiling {
+            return Err(reject::custom(RestError::InvalidEndpoint));
+        }
+        Ok(reply::json(&MemoryStats::current().or_reject()?))
+    }
+
+    /// Samples the CPU time of each thread of the node process for the given number of seconds,
+    /// if the profiling endpoints are enabled.
+    async fn get_cpu_profile(_: (), query: ProfileQuery, profiling: bool) -> Result<impl Reply, Rejection> {
+        if !profiling {
+            return Err(reject::custom(RestError::InvalidEndpoint));
+        }
+        Ok(reply::json(&cpu_profile(query.seconds).await.or_reject()?))
+    }
+
+    /// Samples the CPU time of each thread of the node process for the given number of seconds, in the pprof format,
+    /// if the profiling endpoints are enabled.
+    async fn get_cpu_pprof(_: (), query: ProfileQuery, profiling: bool) -> Result<impl Reply, Rejection> {
+        if !profiling {
+            return Err(reject::custom(RestError::InvalidEndpoint));
+        }
+        let pprof = cpu_pprof(query.seconds).await.or_reject()?;
+        Ok(reply::with_header(pprof, "content-type", "application/octet-stream"))
+    }
+
+    /// Returns the statistics of the memory allocator, if the profiling endpoints are enabled.
+    async fn get_allocator_stats(_: (), profiling: bool) -> Result<impl Reply, Rejection> {
+        if !profiling {
+            return Err(reject::custom(RestError::InvalidEndpoint));
+        }
+        Ok(reply::json(&AllocatorStats::current().or_reject()?))
+    }
+
     /// Returns the block hash that contains the given `transaction ID`.
     async fn find_block_hash(transaction_id: N::TransactionID, ledger: Ledger<N, C>) -> Result<impl Reply, Rejection> {
         Ok(reply::json(&ledger.find_block_hash(&transaction_id).or_reject()?))
     }
 
+    /// Returns a page of the IDs of the transactions with the given address in a public input or output,
+    /// in the order they were indexed. This requires the secondary indexes to be maintained.
+    async fn get_transactions_for_address(
+        address: Address<N>,
+        page: Page,
+        indexes: Arc<RwLock<Option<Arc<Indexes<N>>>>>,
+    ) -> Result<impl Reply, Rejection> {
+        const MAX_PAGE_LIMIT: usize = 1000;
+
+        // Ensure the limit is bounded.
+        if page.limit > MAX_PAGE_LIMIT {
+            return Err(reject::custom(RestError::Request(format!(
+                "Cannot request more than {MAX_PAGE_LIMIT} transaction IDs per call (requested {})",
+                page.limit
+            ))));
+        }
+
+        // Ensure the secondary indexes are maintained.
+        let indexes = match indexes.read().clone() {
+            Some(indexes) => indexes,
+            None => return Err(reject::custom(RestError::InvalidEndpoint)),
+        };
+
+        // Look up the count and the page of the address on a blocking thread, as they are read from storage.
+        let lookup = tokio::task::spawn_blocking(move || {
+            let index = indexes.transactions_by_address();
+            Ok::<_, anyhow::Error>(AddressTransactionsPage::<N> {
+                total: index.count(&address)?,
+                transaction_ids: index.get_page(&address, page.offset, page.limit)?,
+            })
+        });
+        match lookup.await {
+            Ok(result) => Ok(reply::json(&result.or_reject()?)),
+            Err(error) => Err(reject::custom(RestError::Request(format!("Failed to look up the address - {error}")))),
+        }
+    }
+
+    /// Returns the rows of the given constrained ledger query, which is answered from the secondary indexes
+    /// within strict cost limits. This requires the secondary indexes to be maintained.
+    async fn query_ledger(
+        _: (),
+        query: LedgerQuery<N>,
+        indexes: Arc<RwLock<Option<Arc<Indexes<N>>>>>,
+        ledger: Ledger<N, C>,
+    ) -> Result<impl Reply, Rejection> {
+        // Ensure the secondary indexes are maintained.
+        let indexes = match indexes.read().clone() {
+            Some(indexes) => indexes,
+            None => return Err(reject::custom(RestError::InvalidEndpoint)),
+        };
+
+        // Execute the query on a blocking thread, as it reads from storage.
+        let execution = tokio::task::spawn_blocking(move || query.execute(&indexes, &ledger));
+        match execution.await {
+            Ok(result) => Ok(reply::json(&result.or_reject()?)),
+            Err(error) => Err(reject::custom(RestError::Request(format!("Failed to execute the query - {error}")))),
+        }
+    }
+
+    /// Returns the transactions relevant to the given view key, from newest to oldest, with their decrypted metadata.
+    async fn get_transaction_history(
+        _: (),
+        page: Page,
+        view_key: ViewKey<N>,
+        history_scans: Arc<Semaphore>,
+        ledger: Ledger<N, C>,
+    ) -> Result<impl Reply, Rejection> {
+        const MAX_PAGE_LIMIT: usize = 100;
+
+        // Ensure the limit is bounded.
+        if page.limit > MAX_PAGE_LIMIT {
+            return Err(reject::custom(RestError::Request(format!(
+                "Cannot request more than {MAX_PAGE_LIMIT} transactions per call (requested {})",
+                page.limit
+            ))));
+        }
+
+        // Reject the request, if the maximum number of scans are in progress.
+        let permit = match history_scans.try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => return Err(reject::custom(RestError::RateLimited)),
+        };
+
+        // Scan the ledger on a blocking thread, as the first read of an account indexes its history from every block.
+        // Note: The permit is held by the scan, so it is only released once the scan ends.
+        let (offset, limit) = (page.offset, page.limit);
+        let scan = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            ledger.get_transaction_history(&view_key, offset, limit)
+        });
+        let history = match scan.await {
+            Ok(history) => history.or_reject()?,
+            Err(error) => {
+                return Err(reject::custom(RestError::Request(format!(
+                    "Failed to scan the transaction history - {error}"
+                ))))
+            }
+        };
+        Ok(reply::json(&TransactionHistoryPage {
+            total: history.total,
+            transactions: history.entries.into_iter().map(HistoryEntryInfo::from).collect(),
+        }))
+    }
+
     /// Returns the transaction ID that contains the given `program ID`.
     async fn find_transaction_id_from_program_id(
         program_id: ProgramID<N>,
@@ -369,22 +1652,793 @@ impl<N: Network, C: ConsensusStorage<N>, R: Routing<N>> Rest<N, C, R> {
         consensus: Option<Consensus<N, C>>,
         routing: Arc<R>,
     ) -> Result<impl Reply, Rejection> {
-        // If the consensus module is enabled, add the unconfirmed transaction to the memory pool.
-        if let Some(consensus) = consensus {
-            // Add the unconfirmed transaction to the memory pool.
-            consensus.add_unconfirmed_transaction(transaction.clone()).or_reject()?;
+        // Add the transaction to the memory pool, and broadcast it.
+        match Self::broadcast_transaction(consensus, &routing, transaction).await {
+            Ok(transaction_id) => Ok(transaction_id.to_string()),
+            Err(error) => Err(reject::custom(RestError::InvalidTransaction(error.to_string()))),
+        }
+    }
+
+    /// Decodes the given hex-encoded or bech32-encoded transaction on a blocking thread.
+    async fn decode_raw(raw: String) -> Result<Result<Transaction<N>, RawTransactionError>, Rejection> {
+        tokio::task::spawn_blocking(move || decode_raw_transaction::<N>(&raw))
+            .await
+            .map_err(|error| reject::custom(RestError::Request(format!("Failed to decode the transaction - {error}"))))
+    }
+
+    /// Decodes the given hex-encoded or bech32-encoded transaction, adds it to the memory pool, and broadcasts it.
+    /// Returns the transaction ID, or the reason the transaction was rejected.
+    async fn transaction_broadcast_raw(
+        raw: String,
+        consensus: Option<Consensus<N, C>>,
+        routing: Arc<R>,
+    ) -> Result<impl Reply, Rejection> {
+        // Decode the transaction.
+        let transaction = match Self::decode_raw(raw).await? {
+            Ok(transaction) => transaction,
+            Err(error) => return Ok(reply::with_status(reply::json(&error), StatusCode::BAD_REQUEST)),
+        };
+
+        // Add the transaction to the memory pool, and broadcast it.
+        match Self::broadcast_transaction(consensus, &routing, transaction).await {
+            Ok(transaction_id) => Ok(reply::with_status(reply::json(&transaction_id.to_string()), StatusCode::OK)),
+            Err(error) => {
+                let error = RawTransactionError::Validation(error.to_string());
+                Ok(reply::with_status(reply::json(&error), StatusCode::BAD_REQUEST))
+            }
         }
+    }
 
-        // Prepare the unconfirmed transaction message.
+    /// Checks the given hex-encoded or bech32-encoded transaction against the memory pool and the current ledger state,
+    /// without adding it to the memory pool or broadcasting it.
+    async fn transaction_validate_raw(
+        raw: String,
+        consensus: Option<Consensus<N, C>>,
+    ) -> Result<impl Reply, Rejection> {
+        // Ensure the consensus module is enabled.
+        let consensus = match consensus {
+            Some(consensus) => consensus,
+            None => return Err(reject::custom(RestError::InvalidEndpoint)),
+        };
+
+        // Decode the transaction.
+        let transaction = match Self::decode_raw(raw).await? {
+            Ok(transaction) => transaction,
+            Err(error) => {
+                return Ok(reply::json(&TransactionVerdict { valid: false, transaction_id: None, error: Some(error) }));
+            }
+        };
+        let transaction_id = Some(transaction.id().to_string());
+
+        // Check the transaction, including its proofs, on a blocking thread.
+        let verdict = tokio::task::spawn_blocking(move || consensus.check_unconfirmed_transaction(&transaction)).await;
+        match verdict {
+            Ok(Ok(())) => Ok(reply::json(&TransactionVerdict { valid: true, transaction_id, error: None })),
+            Ok(Err(error)) => Ok(reply::json(&TransactionVerdict {
+                valid: false,
+                transaction_id,
+                error: Some(RawTransactionError::Validation(error.to_string())),
+            })),
+            Err(error) => Err(reject::custom(RestError::Request(format!("Failed to check the transaction - {error}")))),
+        }
+    }
+
+    /// Decodes the given hex-encoded transmission of the given kind, adds it to the memory pool, and broadcasts it.
+    /// Returns `false` if the transmission is already in the memory pool.
+    async fn transmission_broadcast(
+        kind: String,
+        raw: String,
+        consensus: Option<Consensus<N, C>>,
+        routing: Arc<R>,
+    ) -> Result<impl Reply, Rejection> {
+        // Ensure the consensus module is enabled.
+        let consensus = match consensus {
+            Some(consensus) => consensus,
+            None => return Err(reject::custom(RestError::InvalidEndpoint)),
+        };
+
+        // Decode the transmission.
+        let payload = hex::decode(raw.trim().trim_start_matches("0x"))
+            .map_err(|error| reject::custom(RestError::Request(format!("Invalid transmission encoding - {error}"))))?;
+
+        // Add the unconfirmed transmission to the memory pool, checking it against the rules of its kind.
+        let is_new = consensus
+            .add_unconfirmed_transmission(&kind, payload.clone())
+            .map_err(|error| reject::custom(RestError::Request(error.to_string())))?;
+
+        // Broadcast the transmission, if it is new.
+        if is_new {
+            let message = Message::UnconfirmedTransmission(UnconfirmedTransmission { kind, payload: payload.into() });
+            routing.propagate_local(message, &[]);
+        }
+        Ok(reply::json(&is_new))
+    }
+
+    /// Broadcasts the given transactions to the ledger, admitting each one independently.
+    async fn transactions_broadcast(
+        transactions: Vec<serde_json::Value>,
+        json_mode: JsonMode,
+        consensus: Option<Consensus<N, C>>,
+        routing: Arc<R>,
+    ) -> Result<impl Reply, Rejection> {
+        const MAX_BATCH_SIZE: usize = 100;
+
+        // Ensure the batch is bounded.
+        if transactions.len() > MAX_BATCH_SIZE {
+            return Err(reject::custom(RestError::Request(format!(
+                "Cannot broadcast more than {MAX_BATCH_SIZE} transactions per call (requested {})",
+                transactions.len()
+            ))));
+        }
+
+        let mut results = Vec::with_capacity(transactions.len());
+        for transaction in transactions {
+            // Deserialize the transaction.
+            let transaction = match from_json::<Transaction<N>>(json_mode, transaction) {
+                Ok(transaction) => transaction,
+                Err(error) => {
+                    results.push(BroadcastResult::Rejected(format!("Invalid transaction - {error}")));
+                    continue;
+                }
+            };
+
+            // Add the transaction to the memory pool, and broadcast it.
+            match Self::broadcast_transaction(consensus.clone(), &routing, transaction).await {
+                Ok(transaction_id) => results.push(BroadcastResult::Accepted(transaction_id.to_string())),
+                Err(error) => results.push(BroadcastResult::Rejected(error.to_string())),
+            }
+        }
+
+        Ok(reply::json(&results))
+    }
+
+    /// Broadcasts the transaction to the ledger, and waits until it is confirmed in a block,
+    /// dropped from the memory pool, or the given timeout passes.
+    async fn transaction_broadcast_and_wait(
+        wait_timeout: WaitTimeout,
+        transaction: Transaction<N>,
+        consensus: Option<Consensus<N, C>>,
+        ledger: Ledger<N, C>,
+        routing: Arc<R>,
+    ) -> Result<impl Reply, Rejection> {
+        // Ensure the consensus module is enabled.
+        let consensus = match consensus {
+            Some(consensus) => consensus,
+            None => return Err(reject::custom(RestError::InvalidEndpoint)),
+        };
+        let timeout = Duration::from_secs(wait_timeout.timeout.min(WaitTimeout::MAX_TIMEOUT_IN_SECS));
+
+        // Subscribe before broadcasting, so that no block is missed in between.
+        let mut subscriber = consensus.subscriptions().subscribe_blocks();
         let transaction_id = transaction.id();
-        let message = Message::UnconfirmedTransaction(UnconfirmedTransaction {
-            transaction_id,
-            transaction: Data::Object(transaction),
-        });
+        Self::transaction_broadcast(transaction, Some(consensus.clone()), routing).await?;
+
+        // Check the transaction after each new block, until the timeout passes.
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            // Note: The memory pool is checked first, as it drops included transactions after the block is added.
+            let is_pending = consensus.memory_pool().contains_unconfirmed_transaction(transaction_id);
+            // Check if the transaction was included in a block.
+            if let Some(block_hash) = ledger.find_block_hash(&transaction_id).or_reject()? {
+                let height = ledger.get_height(&block_hash).or_reject()?;
+                return Ok(reply::json(&WaitOutcome::<N>::Confirmed { height, block_hash }));
+            }
+            // Check if the transaction was dropped from the memory pool.
+            if !is_pending {
+                return Ok(reply::json(&WaitOutcome::<N>::Rejected));
+            }
+            // Wait for the next block.
+            match tokio::time::timeout_at(deadline, subscriber.recv()).await {
+                Ok(Ok(_)) | Ok(Err(RecvError::Lagged(_))) => continue,
+                Ok(Err(RecvError::Closed)) | Err(_) => return Ok(reply::json(&WaitOutcome::<N>::Pending)),
+            }
+        }
+    }
+
+    /// Broadcasts the transaction to the ledger, and tracks it until it is included or the given deadline passes.
+    async fn transaction_broadcast_with_deadline(
+        max_height: u32,
+        _: (),
+        transaction: Transaction<N>,
+        consensus: Option<Consensus<N, C>>,
+        ledger: Ledger<N, C>,
+        routing: Arc<R>,
+        deadlines: Deadlines<N>,
+    ) -> Result<impl Reply, Rejection> {
+        // Ensure the deadline has not already passed.
+        let latest_height = ledger.latest_height();
+        if max_height <= latest_height {
+            return Err(reject::custom(RestError::Request(format!(
+                "The deadline ({max_height}) must be greater than the latest height ({latest_height})"
+            ))));
+        }
+        // Ensure the deadline submissions are not at capacity.
+        if deadlines.is_full() {
+            return Err(reject::custom(RestError::Request("Too many pending deadline submissions".to_string())));
+        }
 
         // Broadcast the transaction.
-        routing.propagate(message, &[]);
+        let transaction_id = Self::transaction_broadcast(transaction.clone(), consensus, routing).await?;
+        // Track the transaction until the deadline.
+        deadlines.insert(transaction, max_height, latest_height);
+
+        Ok(transaction_id)
+    }
 
-        Ok(transaction_id.to_string())
+    /// Returns the deadline submission for the given transaction ID.
+    async fn get_transaction_deadline(
+        transaction_id: N::TransactionID,
+        deadlines: Deadlines<N>,
+    ) -> Result<impl Reply, Rejection> {
+        match deadlines.get(&transaction_id) {
+            Some(submission) => Ok(reply::json(&submission)),
+            None => {
+                Err(reject::custom(RestError::Request(format!("Missing deadline submission for '{transaction_id}'"))))
+            }
+        }
+    }
+
+    /// Queues the given proving job, and returns the job ID.
+    async fn submit_proving_job(
+        _: (),
+        request: ProvingJobRequest<N>,
+        proving_jobs: ProvingJobs<N>,
+    ) -> Result<impl Reply, Rejection> {
+        Ok(reply::json(&proving_jobs.submit(request).or_reject()?))
+    }
+
+    /// Returns the proving job for the given job ID.
+    async fn get_proving_job(id: u64, _: (), proving_jobs: ProvingJobs<N>) -> Result<impl Reply, Rejection> {
+        match proving_jobs.get(id) {
+            Some(job) => Ok(reply::json(&job)),
+            None => Err(reject::custom(RestError::Request(format!("Missing proving job {id}")))),
+        }
+    }
+
+    /// Cancels the proving job for the given job ID.
+    async fn cancel_proving_job(id: u64, _: (), proving_jobs: ProvingJobs<N>) -> Result<impl Reply, Rejection> {
+        proving_jobs.cancel(id).or_reject()?;
+        Ok(reply::json(&id))
+    }
+
+    /// Transfers the faucet amount from the node account to the requested address, and broadcasts the transaction.
+    async fn faucet_request(
+        client_ip: Option<IpAddr>,
+        request: FaucetRequest<N>,
+        faucet: Option<Faucet<N>>,
+        consensus: Option<Consensus<N, C>>,
+        ledger: Ledger<N, C>,
+        routing: Arc<R>,
+    ) -> Result<impl Reply, Rejection> {
+        // Ensure the faucet is enabled.
+        let faucet = match faucet {
+            Some(faucet) => faucet,
+            None => return Err(reject::custom(RestError::InvalidEndpoint)),
+        };
+        // Ensure the client IP is known, as the cooldown is enforced per client IP.
+        let client_ip = match client_ip {
+            Some(client_ip) => client_ip,
+            None => return Err(reject::custom(RestError::Unauthorized("Unknown client IP".to_string()))),
+        };
+        // Ensure the captcha is solved, if one is required.
+        if !faucet.verify_captcha(request.captcha.as_deref(), Some(client_ip)).await.or_reject()? {
+            return Err(reject::custom(RestError::Unauthorized("Invalid or missing captcha".to_string())));
+        }
+        // Ensure the client has waited for the cooldown since its last request.
+        if !faucet.check_cooldown(client_ip) {
+            return Err(reject::custom(RestError::RateLimited));
+        }
+
+        // Retrieve the unspent records of the node account, on a blocking thread.
+        let private_key = *routing.router().private_key();
+        let view_key = ViewKey::try_from(private_key).or_reject()?;
+        let scan_ledger = ledger.clone();
+        let scan = tokio::task::spawn_blocking(move || scan_ledger.find_unspent_records(&view_key)).await;
+        let unspent_records = match scan {
+            Ok(unspent_records) => unspent_records.or_reject()?,
+            Err(error) => return Err(reject::custom(RestError::Request(format!("Failed to find records - {error}")))),
+        };
+        // Reserve a record of the node account that covers the faucet amount.
+        // The record is released if the transaction is not broadcast, including if this request is dropped.
+        let (reservation, record) = match faucet.reserve_record(unspent_records) {
+            Some(reserved) => reserved,
+            None => return Err(reject::custom(RestError::Request("The faucet has no funds available".to_string()))),
+        };
+
+        // Create the transfer transaction.
+        let vm = ledger.vm().clone();
+        let (to, amount) = (request.address, faucet.amount());
+        let result = tokio::task::spawn_blocking(move || {
+            let inputs =
+                [Value::Record(record), Value::from_str(&format!("{to}"))?, Value::from_str(&format!("{amount}u64"))?];
+            Transaction::execute(
+                &vm,
+                &private_key,
+                ("credits.aleo", "transfer"),
+                inputs.iter(),
+                None,
+                None::<Query<N, C::BlockStorage>>,
+                &mut rand::thread_rng(),
+            )
+        })
+        .await;
+
+        // Broadcast the transaction, and keep the record reserved if it was broadcast.
+        let outcome = match result {
+            Ok(Ok(transaction)) => Self::broadcast_transaction(consensus, &routing, transaction).await,
+            Ok(Err(error)) => Err(error),
+            Err(error) => Err(error.into()),
+        };
+        if outcome.is_ok() {
+            reservation.keep();
+        }
+        Ok(reply::json(&outcome.or_reject()?))
+    }
+
+    /// Upgrades the connection to a WebSocket, and serves the subscription requests of the client.
+    async fn subscribe(
+        ws: Ws,
+        consensus: Option<Consensus<N, C>>,
+        deadlines: Deadlines<N>,
+    ) -> Result<impl Reply, Rejection> {
+        match consensus {
+            Some(consensus) => Ok(ws.on_upgrade(move |socket| serve_subscriptions(socket, consensus, deadlines))),
+            None => Err(reject::custom(RestError::InvalidEndpoint)),
+        }
+    }
+
+    /// Upgrades the connection to a WebSocket, and serves an external builder, which is sent a template of each
+    /// next block and may submit a bundle of transactions for it.
+    async fn subscribe_builder(
+        ws: Ws,
+        builder: String,
+        consensus: Option<Consensus<N, C>>,
+        ledger: Ledger<N, C>,
+    ) -> Result<impl Reply, Rejection> {
+        let consensus = match consensus {
+            Some(consensus) => consensus,
+            None => return Err(reject::custom(RestError::InvalidEndpoint)),
+        };
+        // Note: A builder is identified by its token, so that its bundle is replaced by its own later bundles,
+        // including across reconnections.
+        // Subscribe before reading the latest block, so that no block is missed in between.
+        let subscriber = consensus.subscriptions().subscribe_blocks();
+        let template = BlockTemplate { height: ledger.latest_height() + 1, previous_hash: ledger.latest_hash() };
+        Ok(ws.on_upgrade(move |socket| serve_builder(socket, builder, consensus, subscriber, template)))
+    }
+
+    /// Upgrades the connection to a WebSocket, and streams the finalized blocks from the requested height, in order.
+    async fn stream_finalized_blocks(
+        ws: Ws,
+        cursor: BlockCursor,
+        consensus: Option<Consensus<N, C>>,
+        ledger: Ledger<N, C>,
+    ) -> Result<impl Reply, Rejection> {
+        let consensus = match consensus {
+            Some(consensus) => consensus,
+            None => return Err(reject::custom(RestError::InvalidEndpoint)),
+        };
+        // Subscribe before choosing the start height, so that no block is missed in between.
+        let subscriber = consensus.subscriptions().subscribe_blocks();
+        let start_height = cursor.from.unwrap_or_else(|| ledger.finalized_height() + 1);
+        let blocks = block_stream(ledger, subscriber, start_height, true);
+        Ok(ws.on_upgrade(move |socket| serve_block_stream(socket, blocks)))
+    }
+
+    /// Returns OK while the process is alive.
+    async fn get_health() -> Result<impl Reply, Rejection> {
+        Ok(reply::json(&"ok"))
+    }
+
+    /// Returns whether the node is synced with its peers and connected to enough of them, with a 503 if it is not.
+    async fn get_readiness(
+        ledger: Ledger<N, C>,
+        router: Router<N>,
+        thresholds: ReadinessThresholds,
+    ) -> Result<impl Reply, Rejection> {
+        // Retrieve the median block height reported by the peers.
+        let peer_height = router.sync().median_peer_height();
+        // Evaluate the readiness of the node against the thresholds.
+        let num_peers = router.number_of_connected_peers();
+        let readiness = Readiness::evaluate_with(thresholds, ledger.latest_height(), peer_height, num_peers);
+        let status = match readiness.ready {
+            true => StatusCode::OK,
+            false => StatusCode::SERVICE_UNAVAILABLE,
+        };
+        Ok(reply::with_status(reply::json(&readiness), status))
+    }
+
+    /// Returns the OpenAPI description of the REST server.
+    async fn get_spec(proxy: Arc<RestProxy>) -> Result<impl Reply, Rejection> {
+        Ok(reply::json(&openapi_spec(proxy.path_prefix.as_deref())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_consensus::{
+        CommitteeChange,
+        CommitteeChangeKind,
+        CommitteeDiff,
+        EquivocationEvidence,
+        FeeEstimate,
+        MemoryPoolInfo,
+        PipelineStage,
+        SignedBlockHash,
+        StageMetrics,
+        TransmissionClass,
+        WatchedChange,
+        WorkerMetrics,
+    };
+    use snarkos_node_router::{ConnectionCount, ConnectionDirection, Fork, MessageCounter, PeerInfo};
+    use snarkvm::prelude::{ConsensusMemory, Identifier, TestRng, Testnet3};
+
+    use anyhow::{bail, ensure};
+    use indexmap::IndexMap;
+    use serde_json::{json, Value as Json};
+
+    type CurrentNetwork = Testnet3;
+
+    /// Returns the JSON representation of the given response.
+    fn to_json<T: Serialize>(response: T) -> Json {
+        serde_json::to_value(response).unwrap()
+    }
+
+    /// Ensures the given value matches the given schema of the OpenAPI description.
+    /// An object with `additionalProperties: false` must not have any field beyond its `properties`.
+    fn check_schema(spec: &Json, schema: &Json, value: &Json, location: &str) -> Result<()> {
+        if value.is_null() && schema["nullable"] == true {
+            return Ok(());
+        }
+        // Resolve the reference to a component schema.
+        if let Some(reference) = schema["$ref"].as_str() {
+            let name = reference.trim_start_matches("#/components/schemas/");
+            let component = &spec["components"]["schemas"][name];
+            ensure!(component.is_object(), "{location}: unknown schema '{reference}'");
+            return check_schema(spec, component, value, &format!("{location} ({name})"));
+        }
+        if let Some(schemas) = schema["allOf"].as_array() {
+            return schemas.iter().try_for_each(|schema| check_schema(spec, schema, value, location));
+        }
+        if let Some(schemas) = schema["oneOf"].as_array() {
+            let num_matches =
+                schemas.iter().filter(|schema| check_schema(spec, schema, value, location).is_ok()).count();
+            ensure!(num_matches == 1, "{location}: {value} matches {num_matches} of the 'oneOf' schemas");
+            return Ok(());
+        }
+        match schema["type"].as_str() {
+            Some("object") => {
+                let object = match value.as_object() {
+                    Some(object) => object,
+                    None => bail!("{location}: {value} is not an object"),
+                };
+                for field in schema["required"].as_array().into_iter().flatten() {
+                    let field = field.as_str().unwrap();
+                    ensure!(object.contains_key(field), "{location}: missing field '{field}'");
+                }
+                for (field, value) in object {
+                    let location = format!("{location}.{field}");
+                    match (&schema["properties"][field], &schema["additionalProperties"]) {
+                        (Json::Null, Json::Bool(false)) => bail!("{location}: the field is not described"),
+                        (Json::Null, Json::Object(_)) => {
+                            check_schema(spec, &schema["additionalProperties"], value, &location)?
+                        }
+                        (Json::Null, _) => (),
+                        (field_schema, _) => check_schema(spec, field_schema, value, &location)?,
+                    }
+                }
+            }
+            Some("array") => {
+                let array = match value.as_array() {
+                    Some(array) => array,
+                    None => bail!("{location}: {value} is not an array"),
+                };
+                let (min_items, max_items) = (schema["minItems"].as_u64(), schema["maxItems"].as_u64());
+                ensure!(array.len() as u64 >= min_items.unwrap_or(0), "{location}: too few items");
+                ensure!(array.len() as u64 <= max_items.unwrap_or(u64::MAX), "{location}: too many items");
+                for (index, item) in array.iter().enumerate() {
+                    check_schema(spec, &schema["items"], item, &format!("{location}[{index}]"))?;
+                }
+            }
+            Some("string") => {
+                ensure!(value.is_string(), "{location}: {value} is not a string");
+                if let Some(variants) = schema["enum"].as_array() {
+                    ensure!(variants.contains(value), "{location}: {value} is not one of {variants:?}");
+                }
+            }
+            Some("integer") => ensure!(value.is_i64() || value.is_u64(), "{location}: {value} is not an integer"),
+            Some("number") => ensure!(value.is_number(), "{location}: {value} is not a number"),
+            Some("boolean") => ensure!(value.is_boolean(), "{location}: {value} is not a boolean"),
+            Some(kind) => bail!("{location}: unknown type '{kind}'"),
+            // The schema accepts any value.
+            None => (),
+        }
+        Ok(())
+    }
+
+    /// Returns a sample response of each operation with a JSON response, constructed from the response types
+    /// of the handlers. An operation has several samples, if its response has several shapes.
+    fn sample_responses() -> Vec<(&'static str, Json)> {
+        let rng = &mut TestRng::default();
+
+        // Load a ledger with the genesis block.
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let ledger = Ledger::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::load(genesis.clone(), None).unwrap();
+        let transaction = genesis.transactions().iter().next().unwrap().clone();
+        let transaction_id = transaction.id();
+        let transition_id = *transaction.transition_ids().next().unwrap();
+        let commitment = *genesis.transactions().commitments().next().unwrap();
+        let block_hash = genesis.hash();
+        let address = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        let program = Program::<CurrentNetwork>::credits().unwrap();
+        let peer_ip = SocketAddr::from(([127, 0, 0, 1], 4130));
+
+        let proof = ledger.get_header_proof(0).unwrap();
+        let header_proof = HeaderProofInfo {
+            state_root: proof.state_root,
+            block_height: proof.block_height,
+            block_hash: proof.block_hash,
+            previous_hash: proof.previous_hash,
+            header: proof.header,
+            block_path: hex::encode(proof.block_path.to_bytes_le().unwrap()),
+        };
+        let transaction_info = TransactionInfo {
+            transaction: transaction.clone(),
+            fee_breakdown: FeeBreakdown { base: 1, priority: 2, finalize_cost: 0, storage_cost: 1 },
+            confirmation: Confirmation { block_height: 0, block_hash, index: 0, depth: 0, finalized: true },
+        };
+        let memory_pool_info = MemoryPoolInfo {
+            num_transactions: 1,
+            total_bytes: 1024,
+            min_fee: Some(1),
+            median_fee: Some(2),
+            max_fee: None,
+        };
+        let fee_estimate = FeeEstimate { target_blocks: 1, priority_fee: 2, num_confirmed: 3, num_pending: 4 };
+        let deployment =
+            ProgramDeployment { program: program.clone(), transaction: transaction.clone(), block_height: 0 };
+        let committee_change = CommitteeChange { epoch: 0, height: 0, address, kind: CommitteeChangeKind::Added };
+        let committee_diff = CommitteeDiff { from_epoch: 0, to_epoch: 1, added: vec![address], removed: vec![] };
+        let evidence = EquivocationEvidence {
+            signer: address,
+            round: 1,
+            first: SignedBlockHash::from_block(&genesis),
+            second: SignedBlockHash::from_block(&genesis),
+            detected_at: 0,
+        };
+        let watched_changes = vec![
+            WatchedChange::Balance { address, height: 0, previous: 0, current: 1 },
+            WatchedChange::Mapping {
+                address,
+                height: 0,
+                program_id: *program.id(),
+                mapping_name: Identifier::from_str("account").unwrap(),
+                previous: None,
+                current: Some(Value::from_str("1u64").unwrap()),
+            },
+            WatchedChange::Record { address, height: 0, transaction_id, commitment },
+        ];
+        let capacity = NodeCapacity {
+            in_flight_requests: 1,
+            served_requests: 2,
+            rate_limited_requests: 0,
+            oversized_requests: 0,
+            queued_proving_jobs: 0,
+            verification_backlog: 3,
+            backpressure: Some(PipelineStage::ProofVerification),
+            unconfirmed_transactions: 1,
+            load_score: 50,
+        };
+        let peer_info = PeerInfo {
+            ip: peer_ip,
+            direction: ConnectionDirection::Inbound,
+            node_type: NodeType::Validator,
+            version: 1,
+            last_seen_secs: 0,
+        };
+        let message_stats: IndexMap<SocketAddr, IndexMap<u16, MessageCounter>> =
+            [(peer_ip, [(0, MessageCounter::default())].into_iter().collect())].into_iter().collect();
+        let fork = Fork::<CurrentNetwork> {
+            peer_ip,
+            ancestor: 0,
+            height: 1,
+            canon_hash: block_hash,
+            peer_hash: block_hash,
+            peer_height: 1,
+            depth: 1,
+            replaced: vec![block_hash],
+            timestamp: 0,
+        };
+        let stage_metrics = PipelineStage::ALL
+            .map(|stage| StageMetrics { stage, queued: 0, processed: 1, failed: 0, busy_micros: 2 })
+            .to_vec();
+        let worker_metrics = TransmissionClass::ALL
+            .map(|class| WorkerMetrics {
+                class,
+                index: 0,
+                queued: 0,
+                capacity: 1,
+                processed: 1,
+                failed: 0,
+                dropped: 0,
+                busy_micros: 2,
+            })
+            .to_vec();
+        let thread_profile = ThreadProfile { tid: 1, name: "main".to_string(), cpu_ms: 1, utilization: 0.5 };
+        let node_role = NodeRole { node_type: NodeType::Client, standby: false };
+        let address_page = AddressTransactionsPage { total: 1, transaction_ids: vec![transaction_id] };
+        let query_result = QueryResult { rows: vec![QueryRow { transaction_id, height: 0 }], scanned: 1 };
+        let history_page = TransactionHistoryPage {
+            total: 1,
+            transactions: vec![HistoryEntryInfo {
+                transaction_id,
+                block_height: 0,
+                block_hash,
+                timestamp: 0,
+                direction: "self",
+                received: 1,
+                spent: 0,
+                counterparties: vec![address],
+            }],
+        };
+        let verdict = TransactionVerdict {
+            valid: false,
+            transaction_id: Some(transaction_id.to_string()),
+            error: Some(RawTransactionError::Validation("Invalid fee".to_string())),
+        };
+        let broadcast_results = vec![
+            BroadcastResult::Accepted(transaction_id.to_string()),
+            BroadcastResult::Rejected("Invalid transaction".to_string()),
+        ];
+        let deadline = |status| DeadlineSubmission {
+            transaction: transaction.clone(),
+            max_height: 10,
+            status,
+            num_broadcasts: 1,
+            last_broadcast_height: 0,
+        };
+        let proving_job = ProvingJob {
+            id: 1,
+            request: ProvingJobRequest {
+                program_id: *program.id(),
+                function: Identifier::from_str("transfer_public").unwrap(),
+                inputs: vec![Value::from_str(&address.to_string()).unwrap(), Value::from_str("1u64").unwrap()],
+                fee_record: None,
+                fee: 0,
+            },
+            status: ProvingJobStatus::Completed(transaction_id),
+            submitted_at: 0,
+            updated_at: 0,
+        };
+        let readiness = Readiness { ready: true, height: 0, peer_height: None, num_peers: 0, reasons: vec![] };
+
+        vec![
+            ("latest_height", to_json(ledger.latest_height())),
+            ("latest_hash", to_json(ledger.latest_hash())),
+            ("latest_block", to_json(ledger.latest_block())),
+            ("latest_state_root", to_json(ledger.latest_state_root())),
+            ("get_finalized_height", to_json(ledger.finalized_height())),
+            ("get_block", to_json(&genesis)),
+            ("get_blocks", to_json([&genesis])),
+            ("get_block_by_hash", to_json(&genesis)),
+            ("get_block_height_by_hash", to_json(ledger.get_height(&block_hash).unwrap())),
+            ("get_block_transactions", to_json(ledger.get_transactions(0).unwrap())),
+            ("get_header_proof", to_json(header_proof)),
+            ("get_transaction", to_json(transaction_info)),
+            ("get_memory_pool_transactions", to_json([&transaction])),
+            ("get_memory_pool_transaction_ids", to_json([transaction_id])),
+            ("get_memory_pool_info", to_json(memory_pool_info)),
+            ("get_memory_pool_transmissions", json!([{ "payload": hex::encode([1u8; 4]), "fee": 1 }])),
+            ("estimate_fee", to_json(fee_estimate)),
+            ("list_programs", to_json([program.id()])),
+            ("get_program_deployment", to_json(deployment)),
+            ("get_program", to_json(&program)),
+            ("get_state_path_for_commitment", to_json(ledger.get_state_path_for_commitment(&commitment).unwrap())),
+            ("get_beacons", to_json([address])),
+            ("get_committee_changes", to_json([committee_change])),
+            ("get_committee_diff", to_json(committee_diff)),
+            ("get_equivocation_evidence", to_json([evidence])),
+            ("get_watch_list", to_json([(address, 1u64)].into_iter().collect::<IndexMap<_, _>>())),
+            ("get_watch_list_changes", to_json(watched_changes)),
+            ("watch_list_add", to_json(address)),
+            ("watch_list_remove", to_json(address)),
+            ("faucet_request", to_json(transaction_id)),
+            ("get_node_capacity", to_json(capacity)),
+            ("get_peers_count", to_json(1usize)),
+            ("get_peers_all", to_json([peer_ip])),
+            ("get_peers_all_metrics", to_json([(peer_ip, NodeType::Validator)])),
+            ("get_peers_info", to_json([peer_info])),
+            ("get_peers_connections", to_json(ConnectionCount { total: 1, inbound: 1, outbound: 0 })),
+            ("connect_peer", to_json(peer_ip)),
+            ("disconnect_peer", to_json(peer_ip)),
+            ("get_message_stats", to_json(message_stats)),
+            ("get_fork_history", to_json([fork])),
+            ("get_pipeline_metrics", to_json(stage_metrics)),
+            ("get_worker_metrics", to_json(worker_metrics)),
+            ("get_memory_stats", to_json(MemoryStats::default())),
+            ("get_cpu_profile", to_json([thread_profile])),
+            ("get_allocator_stats", to_json(AllocatorStats::default())),
+            ("get_node_address", to_json(address.to_string())),
+            ("get_node_role", to_json(&node_role)),
+            ("set_node_standby", to_json(&node_role)),
+            ("find_block_hash", to_json(Some(block_hash))),
+            ("find_block_hash", to_json(None::<<CurrentNetwork as Network>::BlockHash>)),
+            ("get_transactions_for_address", to_json(address_page)),
+            ("query_ledger", to_json(query_result)),
+            ("get_transaction_history", to_json(history_page)),
+            ("find_transaction_id_from_program_id", to_json(Some(transaction_id))),
+            ("find_transaction_id_from_transition_id", to_json(Some(transaction_id))),
+            ("find_transition_id", to_json(transition_id)),
+            ("transaction_broadcast_raw", to_json(transaction_id.to_string())),
+            ("transaction_validate_raw", to_json(verdict)),
+            ("transmission_broadcast", to_json(true)),
+            ("transactions_broadcast", to_json(broadcast_results)),
+            ("transaction_broadcast_and_wait", to_json(WaitOutcome::Confirmed { height: 0, block_hash })),
+            ("transaction_broadcast_and_wait", to_json(WaitOutcome::<CurrentNetwork>::Rejected)),
+            ("transaction_broadcast_and_wait", to_json(WaitOutcome::<CurrentNetwork>::Pending)),
+            ("get_transaction_deadline", to_json(deadline(DeadlineStatus::Pending))),
+            ("get_transaction_deadline", to_json(deadline(DeadlineStatus::Included(5)))),
+            ("submit_proving_job", to_json(1u64)),
+            ("get_proving_job", to_json(&proving_job)),
+            ("get_proving_job", to_json(ProvingJob { status: ProvingJobStatus::Queued, ..proving_job })),
+            ("cancel_proving_job", to_json(1u64)),
+            ("get_health", to_json("ok")),
+            ("get_readiness", to_json(readiness)),
+            ("get_spec", openapi_spec(None)),
+        ]
+    }
+
+    #[test]
+    fn test_openapi_response_schemas() {
+        let spec = openapi_spec(None);
+        let samples = sample_responses();
+
+        // Ensure every operation with a JSON response is sampled, and every sample matches the described response.
+        let mut num_operations = 0;
+        let paths = spec["paths"].as_object().unwrap();
+        for operation in paths.values().flat_map(|path| path.as_object().unwrap().values()) {
+            let operation_id = operation["operationId"].as_str().unwrap();
+            let schema = &operation["responses"]["200"]["content"]["application/json"]["schema"];
+            if schema.is_null() {
+                continue;
+            }
+            num_operations += 1;
+            let mut responses = samples.iter().filter(|(id, _)| *id == operation_id).peekable();
+            assert!(responses.peek().is_some(), "Missing sample response of '{operation_id}'");
+            for (_, response) in responses {
+                check_schema(&spec, schema, response, operation_id).unwrap();
+            }
+        }
+        assert_eq!(num_operations, samples.iter().map(|(id, _)| id).collect::<std::collections::HashSet<_>>().len());
+
+        // Ensure the error responses match the described errors.
+        let error = RestError::NotSynced { height: 10, tip_height: 5 };
+        let body = json!({ "code": error.code(), "message": error.message(), "data": error.data() });
+        check_schema(&spec, &json!({ "$ref": "#/components/schemas/Error" }), &body, "error").unwrap();
+        let error = to_json(RawTransactionError::Encoding("Invalid hex".to_string()));
+        check_schema(&spec, &json!({ "$ref": "#/components/schemas/RawTransactionError" }), &error, "raw").unwrap();
+    }
+
+    #[test]
+    fn test_check_schema() {
+        let spec = openapi_spec(None);
+        let check = |schema: &str, value: Json| {
+            check_schema(&spec, &json!({ "$ref": format!("#/components/schemas/{schema}") }), &value, schema)
+        };
+        let role = json!({ "node_type": "Client", "standby": false });
+        assert!(check("NodeRole", role).is_ok());
+        // Ensure a missing, renamed, or mistyped field is rejected.
+        assert!(check("NodeRole", json!({ "node_type": "Client" })).is_err());
+        assert!(check("NodeRole", json!({ "nodeType": "Client", "standby": false })).is_err());
+        assert!(check("NodeRole", json!({ "node_type": "Client", "standby": 1 })).is_err());
+        assert!(check("NodeRole", json!({ "node_type": "Miner", "standby": false })).is_err());
+        // Ensure an undescribed field is rejected.
+        assert!(check("NodeRole", json!({ "node_type": "Client", "standby": false, "extra": 1 })).is_err());
+        // Ensure a nullable field accepts null, and each variant of a tagged enum is distinguished.
+        let readiness = json!({ "ready": true, "height": 0, "peer_height": null, "num_peers": 0, "reasons": [] });
+        assert!(check("Readiness", readiness).is_ok());
+        assert!(check("WaitOutcome", json!({ "status": "pending" })).is_ok());
+        assert!(check("WaitOutcome", json!({ "status": "confirmed" })).is_err());
     }
 }
@@ -0,0 +1,278 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{anyhow, bail, Result};
+use parking_lot::RwLock;
+use rcgen::{Certificate, CertificateParams, DistinguishedName, DnType, KeyPair, PKCS_ECDSA_P256_SHA256};
+use reqwest::{header::LOCATION, Client, Response};
+use ring::{
+    digest::{digest, SHA256},
+    rand::SystemRandom,
+    signature::{EcdsaKeyPair, KeyPair as _, ECDSA_P256_SHA256_FIXED_SIGNING},
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+/// The interval at which the status of a pending authorization or order is polled.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// The maximum number of times the status of a pending authorization or order is polled.
+const MAX_POLLS: usize = 60;
+
+/// The key authorizations of the pending HTTP-01 challenges, by token.
+pub(crate) type Challenges = Arc<RwLock<HashMap<String, String>>>;
+
+/// The endpoints of an ACME server.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Directory {
+    new_nonce: String,
+    new_account: String,
+    new_order: String,
+}
+
+/// An ACME order, for a certificate of the given identifiers.
+#[derive(Debug, Deserialize)]
+struct Order {
+    status: String,
+    authorizations: Vec<String>,
+    finalize: String,
+    certificate: Option<String>,
+}
+
+/// An ACME authorization, to prove control of an identifier.
+#[derive(Debug, Deserialize)]
+struct Authorization {
+    status: String,
+    challenges: Vec<Challenge>,
+}
+
+/// An ACME challenge of an authorization.
+#[derive(Debug, Deserialize)]
+struct Challenge {
+    #[serde(rename = "type")]
+    kind: String,
+    url: String,
+    token: String,
+}
+
+/// A minimal ACME (RFC 8555) client, which issues a certificate for a single domain with an HTTP-01 challenge.
+pub(crate) struct AcmeClient {
+    /// The HTTP client.
+    client: Client,
+    /// The endpoints of the ACME server.
+    directory: Directory,
+    /// The account key.
+    key: EcdsaKeyPair,
+    /// The source of randomness for the signatures.
+    rng: SystemRandom,
+    /// The account URL, once the account is registered.
+    kid: Option<String>,
+    /// The replay nonce for the next request, if one was returned by the server.
+    nonce: Option<String>,
+}
+
+impl AcmeClient {
+    /// Initializes a new ACME client for the server with the given directory URL, and the given PKCS#8 account key.
+    pub(crate) async fn new(directory_url: &str, account_key: &[u8]) -> Result<Self> {
+        let client = Client::new();
+        let directory = client.get(directory_url).send().await?.error_for_status()?.json().await?;
+        let key = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, account_key)
+            .map_err(|error| anyhow!("Invalid ACME account key - {error}"))?;
+        Ok(Self { client, directory, key, rng: SystemRandom::new(), kid: None, nonce: None })
+    }
+
+    /// Registers the account (or retrieves the existing account of the key), with the given contact email.
+    pub(crate) async fn register(&mut self, email: Option<&str>) -> Result<()> {
+        let contact = email.map(|email| vec![format!("mailto:{email}")]).unwrap_or_default();
+        let payload = json!({ "termsOfServiceAgreed": true, "contact": contact });
+        let url = self.directory.new_account.clone();
+        let response = self.post(&url, Some(&payload)).await?;
+        self.kid = Some(location(&response)?);
+        Ok(())
+    }
+
+    /// Issues a certificate for the given domain, serving the HTTP-01 challenges through the given map.
+    /// Returns the PEM certificate chain, and the PEM key of the certificate.
+    pub(crate) async fn issue(&mut self, domain: &str, challenges: &Challenges) -> Result<(String, String)> {
+        // Place the order.
+        let url = self.directory.new_order.clone();
+        let payload = json!({ "identifiers": [{ "type": "dns", "value": domain }] });
+        let response = self.post(&url, Some(&payload)).await?;
+        let order_url = location(&response)?;
+        let order: Order = response.json().await?;
+
+        // Complete the HTTP-01 challenge of each pending authorization.
+        for authorization_url in &order.authorizations {
+            let authorization: Authorization = self.post(authorization_url, None).await?.json().await?;
+            if authorization.status == "valid" {
+                continue;
+            }
+            let challenge = match authorization.challenges.iter().find(|challenge| challenge.kind == "http-01") {
+                Some(challenge) => challenge,
+                None => bail!("The ACME server offered no HTTP-01 challenge for '{domain}'"),
+            };
+            challenges.write().insert(challenge.token.clone(), self.key_authorization(&challenge.token));
+            self.post(&challenge.url, Some(&json!({}))).await?;
+            let result = self.poll(authorization_url, "authorization").await;
+            challenges.write().remove(&challenge.token);
+            result?;
+        }
+
+        // Finalize the order with a new certificate key.
+        let (csr, certificate_key) = certificate_request(domain)?;
+        self.post(&order.finalize, Some(&json!({ "csr": base64url(&csr) }))).await?;
+        let order: Order = serde_json::from_value(self.poll(&order_url, "order").await?)?;
+
+        // Download the certificate chain.
+        let certificate_url = match order.certificate {
+            Some(url) => url,
+            None => bail!("The ACME server returned no certificate for '{domain}'"),
+        };
+        let chain = self.post(&certificate_url, None).await?.text().await?;
+        Ok((chain, certificate_key))
+    }
+
+    /// Polls the given authorization or order, until it is valid.
+    async fn poll(&mut self, url: &str, name: &str) -> Result<Value> {
+        for _ in 0..MAX_POLLS {
+            let object: Value = self.post(url, None).await?.json().await?;
+            match object["status"].as_str() {
+                Some("valid") => return Ok(object),
+                Some("pending") | Some("processing") | Some("ready") => tokio::time::sleep(POLL_INTERVAL).await,
+                _ => bail!("The ACME {name} failed - {object}"),
+            }
+        }
+        bail!("The ACME {name} timed out")
+    }
+
+    /// Returns the key authorization of the given challenge token.
+    fn key_authorization(&self, token: &str) -> String {
+        let thumbprint = digest(&SHA256, self.jwk().to_string().as_bytes());
+        format!("{token}.{}", base64url(thumbprint.as_ref()))
+    }
+
+    /// Returns the JSON web key of the account key, with its members in lexicographic order.
+    fn jwk(&self) -> Value {
+        let point = self.key.public_key().as_ref();
+        let (x, y) = (base64url(&point[1..33]), base64url(&point[33..65]));
+        json!({ "crv": "P-256", "kty": "EC", "x": x, "y": y })
+    }
+
+    /// Sends the given payload (or a POST-as-GET, if `None`) to the given URL, as a signed JWS.
+    async fn post(&mut self, url: &str, payload: Option<&Value>) -> Result<Response> {
+        // Retry once with a fresh nonce, as the server may reject a nonce at any time.
+        for _ in 0..2 {
+            let nonce = match self.nonce.take() {
+                Some(nonce) => nonce,
+                None => replay_nonce(&self.client.head(&self.directory.new_nonce).send().await?)?,
+            };
+            let mut protected = json!({ "alg": "ES256", "nonce": nonce, "url": url });
+            match &self.kid {
+                Some(kid) => protected["kid"] = json!(kid),
+                None => protected["jwk"] = self.jwk(),
+            }
+            let protected = base64url(protected.to_string().as_bytes());
+            let payload = payload.map(|payload| base64url(payload.to_string().as_bytes())).unwrap_or_default();
+            let signature = self
+                .key
+                .sign(&self.rng, format!("{protected}.{payload}").as_bytes())
+                .map_err(|_| anyhow!("Failed to sign the ACME request"))?;
+            let signature = base64url(signature.as_ref());
+            let body = json!({ "protected": protected, "payload": payload, "signature": signature });
+
+            let response = self
+                .client
+                .post(url)
+                .header("content-type", "application/jose+json")
+                .body(body.to_string())
+                .send()
+                .await?;
+            self.nonce = replay_nonce(&response).ok();
+            if response.status().is_success() {
+                return Ok(response);
+            }
+            let problem: Value = response.json().await.unwrap_or_default();
+            if problem["type"] != "urn:ietf:params:acme:error:badNonce" {
+                bail!("The ACME request to '{url}' failed - {problem}");
+            }
+        }
+        bail!("The ACME server rejected the nonces of the request to '{url}'")
+    }
+}
+
+/// Returns the replay nonce of the given response.
+fn replay_nonce(response: &Response) -> Result<String> {
+    match response.headers().get("replay-nonce").and_then(|nonce| nonce.to_str().ok()) {
+        Some(nonce) => Ok(nonce.to_string()),
+        None => bail!("The ACME server returned no replay nonce"),
+    }
+}
+
+/// Returns the location header of the given response.
+fn location(response: &Response) -> Result<String> {
+    match response.headers().get(LOCATION).and_then(|location| location.to_str().ok()) {
+        Some(location) => Ok(location.to_string()),
+        None => bail!("The ACME server returned no location"),
+    }
+}
+
+/// Generates a new P-256 key, in PKCS#8 encoding.
+pub(crate) fn generate_key() -> Result<Vec<u8>> {
+    Ok(KeyPair::generate(&PKCS_ECDSA_P256_SHA256)?.serialize_der())
+}
+
+/// Returns the DER-encoded PKCS#10 certificate request for the given domain, with a new P-256 key,
+/// and the PEM encoding of the key.
+fn certificate_request(domain: &str) -> Result<(Vec<u8>, String)> {
+    let mut params = CertificateParams::new(vec![domain.to_string()]);
+    params.alg = &PKCS_ECDSA_P256_SHA256;
+    params.distinguished_name = DistinguishedName::new();
+    params.distinguished_name.push(DnType::CommonName, domain);
+    let certificate = Certificate::from_params(params)?;
+    Ok((certificate.serialize_request_der()?, certificate.serialize_private_key_pem()))
+}
+
+/// Returns the unpadded URL-safe base64 encoding of the given bytes, as used by the JWS of the ACME requests.
+fn base64url(bytes: &[u8]) -> String {
+    base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64url() {
+        assert_eq!(base64url(b""), "");
+        assert_eq!(base64url(b"f"), "Zg");
+        assert_eq!(base64url(&[0xfb, 0xff]), "-_8");
+    }
+
+    #[test]
+    fn test_certificate_request() {
+        let (request, key) = certificate_request("node.example.com").unwrap();
+
+        // Ensure the request names the domain, and carries the public key of the certificate key.
+        assert!(request.windows(16).any(|window| window == b"node.example.com"));
+        let key = KeyPair::from_pem(&key).unwrap();
+        assert!(request.windows(65).any(|window| window == key.public_key_raw()));
+
+        // Ensure the generated keys are accepted for the signatures of the ACME requests.
+        assert!(EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &key.serialize_der()).is_ok());
+        assert!(EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &generate_key().unwrap()).is_ok());
+    }
+}
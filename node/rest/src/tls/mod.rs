@@ -0,0 +1,225 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+mod acme;
+use acme::{generate_key, AcmeClient, Challenges};
+
+use anyhow::{bail, Result};
+use std::{
+    fs,
+    io,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+use tokio::sync::oneshot;
+use warp::Filter;
+
+/// The directory URL of the Let's Encrypt production ACME server.
+pub const LETS_ENCRYPT_DIRECTORY: &str = "https://acme-v02.api.letsencrypt.org/directory";
+
+/// The age at which an ACME certificate is renewed (the certificates of Let's Encrypt are valid for 90 days).
+const ACME_RENEWAL_AGE: Duration = Duration::from_secs(60 * 24 * 60 * 60);
+/// The interval at which the certificate is checked for changes, or for renewal.
+const RELOAD_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// The interval after which a failed renewal is retried.
+const RENEWAL_RETRY_INTERVAL: Duration = Duration::from_secs(600);
+
+/// The TLS configuration of the REST server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TlsConfig {
+    /// Serves the certificate chain and private key of the given PEM files, which are reloaded when they change.
+    Files { certificate: PathBuf, key: PathBuf },
+    /// Issues and renews the certificate of a domain with an ACME server.
+    Acme(AcmeConfig),
+}
+
+/// The configuration of the ACME certificate management.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AcmeConfig {
+    /// The domain of the certificate, which must resolve to this node.
+    pub domain: String,
+    /// The contact email of the ACME account, if any.
+    pub email: Option<String>,
+    /// The directory URL of the ACME server.
+    pub directory_url: String,
+    /// The IP address and port on which the HTTP-01 challenges are served, which must be reachable on port 80.
+    pub challenge_ip: SocketAddr,
+    /// The directory in which the account key, certificate, and certificate key are stored.
+    pub cache_dir: PathBuf,
+}
+
+impl TlsConfig {
+    /// Ensures the domain of the ACME certificate is a bare host name.
+    pub fn check(&self) -> Result<()> {
+        if let Self::Acme(acme) = self {
+            if acme.domain.is_empty() || acme.domain.contains(|c: char| c == '/' || c == ':' || c.is_whitespace()) {
+                bail!("Invalid TLS domain '{}' (expected a host name, e.g. 'node.example.com')", acme.domain);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the paths of the certificate chain and the private key.
+    fn paths(&self) -> (PathBuf, PathBuf) {
+        match self {
+            Self::Files { certificate, key } => (certificate.clone(), key.clone()),
+            Self::Acme(config) => config.paths(),
+        }
+    }
+
+    /// Returns the PEM certificate chain and private key, issuing or renewing the ACME certificate if it is due.
+    pub(crate) async fn load(&self) -> Result<(Vec<u8>, Vec<u8>)> {
+        if let Self::Acme(config) = self {
+            if config.is_due() {
+                config.issue().await?;
+            }
+        }
+        let (certificate, key) = self.paths();
+        Ok((fs::read(certificate)?, fs::read(key)?))
+    }
+
+    /// Resolves with the renewed PEM certificate chain and private key, once their files changed, or the ACME
+    /// certificate was renewed. The current certificate is served meanwhile, so a failed renewal is retried later.
+    pub(crate) async fn renewed(&self) -> (Vec<u8>, Vec<u8>) {
+        let (certificate, key) = self.paths();
+        let mut loaded = (modified(&certificate), modified(&key));
+        loop {
+            tokio::time::sleep(RELOAD_CHECK_INTERVAL).await;
+            match self {
+                Self::Files { .. } => {
+                    let current = (modified(&certificate), modified(&key));
+                    if current == loaded {
+                        continue;
+                    }
+                    loaded = current;
+                }
+                Self::Acme(config) => {
+                    if !config.is_due() {
+                        continue;
+                    }
+                }
+            }
+            match self.load().await {
+                Ok(renewed) => return renewed,
+                Err(error) => {
+                    warn!("Failed to renew the TLS certificate of the REST server - {error}");
+                    tokio::time::sleep(RENEWAL_RETRY_INTERVAL).await;
+                }
+            }
+        }
+    }
+}
+
+impl AcmeConfig {
+    /// Returns the paths of the cached certificate chain and private key.
+    fn paths(&self) -> (PathBuf, PathBuf) {
+        (self.cache_dir.join(format!("{}.crt", self.domain)), self.cache_dir.join(format!("{}.key", self.domain)))
+    }
+
+    /// Returns `true` if the certificate is missing, or due for renewal.
+    fn is_due(&self) -> bool {
+        match modified(&self.paths().0).map(|issued_at| issued_at.elapsed()) {
+            Some(Ok(age)) => age >= ACME_RENEWAL_AGE,
+            Some(Err(_)) => false,
+            None => true,
+        }
+    }
+
+    /// Issues a new certificate with the ACME server, and stores it in the cache directory.
+    async fn issue(&self) -> Result<()> {
+        fs::create_dir_all(&self.cache_dir)?;
+        // Load the account key, or generate one for a new account.
+        let account_path = self.cache_dir.join("account.key");
+        let account_key = match fs::read(&account_path) {
+            Ok(account_key) => account_key,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                let account_key = generate_key()?;
+                write_private(&account_path, &account_key)?;
+                account_key
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+        // Serve the HTTP-01 challenges for the duration of the order.
+        let challenges = Challenges::default();
+        let tokens = challenges.clone();
+        let route = warp::path!(".well-known" / "acme-challenge" / String).and_then(move |token: String| {
+            let key_authorization = tokens.read().get(&token).cloned();
+            async move { key_authorization.ok_or_else(warp::reject::not_found) }
+        });
+        let (shutdown, signal) = oneshot::channel::<()>();
+        let (_, server) = warp::serve(route).try_bind_with_graceful_shutdown(self.challenge_ip, async {
+            signal.await.ok();
+        })?;
+        let server = tokio::spawn(server);
+
+        info!("Requesting a TLS certificate for '{}' from '{}'", self.domain, self.directory_url);
+        let result = async {
+            let mut client = AcmeClient::new(&self.directory_url, &account_key).await?;
+            client.register(self.email.as_deref()).await?;
+            client.issue(&self.domain, &challenges).await
+        }
+        .await;
+        let _ = shutdown.send(());
+        let _ = server.await;
+        let (chain, key) = result?;
+
+        // Store the key first, as the modification time of the certificate marks the issuance.
+        let (certificate_path, key_path) = self.paths();
+        write_private(&key_path, key.as_bytes())?;
+        fs::write(certificate_path, chain)?;
+        info!("Issued a TLS certificate for '{}'", self.domain);
+        Ok(())
+    }
+}
+
+/// Returns the modification time of the given file, if it exists.
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Writes the given private key to the given path, readable by the owner of the node process only.
+fn write_private(path: &Path, bytes: &[u8]) -> Result<()> {
+    fs::write(path, bytes)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acme_renewal() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let config = AcmeConfig {
+            domain: "node.example.com".to_string(),
+            email: None,
+            directory_url: LETS_ENCRYPT_DIRECTORY.to_string(),
+            challenge_ip: "0.0.0.0:80".parse().unwrap(),
+            cache_dir: cache_dir.path().to_path_buf(),
+        };
+        // Ensure a missing certificate is due, and a fresh certificate is not.
+        assert!(config.is_due());
+        fs::write(config.paths().0, "certificate").unwrap();
+        assert!(!config.is_due());
+    }
+}
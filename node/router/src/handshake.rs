@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{Peer, Router};
+use crate::{ConnectionDirection, Peer, Router};
 use snarkos_node_messages::{
     ChallengeRequest,
     ChallengeResponse,
@@ -31,7 +31,10 @@ use snarkvm::prelude::{error, Address, Header, Network};
 use anyhow::{bail, Result};
 use futures::SinkExt;
 use rand::{rngs::OsRng, Rng};
-use std::{io, net::SocketAddr};
+use std::{
+    io,
+    net::{IpAddr, SocketAddr},
+};
 use tokio::net::TcpStream;
 use tokio_stream::StreamExt;
 use tokio_util::codec::Framed;
@@ -148,7 +151,9 @@ impl<N: Network> Router<N> {
         let our_nonce = rng.gen();
 
         // Send a challenge request to the peer.
-        let our_request = ChallengeRequest::new(self.local_ip().port(), self.node_type, self.address(), our_nonce);
+        let our_compression = self.compression();
+        let our_request =
+            ChallengeRequest::new(self.local_ip().port(), self.node_type, self.address(), our_nonce, our_compression);
         trace!("Sending '{}' to '{peer_addr}'", our_request.name());
         framed.send(Message::ChallengeRequest(our_request)).await?;
 
@@ -160,6 +165,9 @@ impl<N: Network> Router<N> {
         // Listen for the challenge request message.
         let peer_request = expect_message!(Message::ChallengeRequest, framed, peer_addr);
 
+        // Retrieve the IP address of this node, as observed by the peer.
+        let observed_ip = peer_response.observed_ip;
+
         // Verify the challenge response. If a disconnect reason was returned, send the disconnect message and abort.
         handle_verification!(
             self.verify_challenge_response(peer_addr, peer_request.address, peer_response, genesis_header, our_nonce)
@@ -171,6 +179,9 @@ impl<N: Network> Router<N> {
         // Verify the challenge request. If a disconnect reason was returned, send the disconnect message and abort.
         handle_verification!(self.verify_challenge_request(peer_addr, &peer_request), framed, peer_addr);
 
+        // Ensure the peer is the account bound to its address, if any.
+        handle_verification!(self.verify_bound_account(peer_ip, &peer_request), framed, peer_addr);
+
         /* Step 3: Send the challenge response. */
 
         // Sign the counterparty nonce.
@@ -180,12 +191,22 @@ impl<N: Network> Router<N> {
             .map_err(|_| error(format!("Failed to sign the challenge request nonce from '{peer_addr}'")))?;
 
         // Send the challenge response.
-        let our_response = ChallengeResponse { genesis_header, signature: Data::Object(our_signature) };
+        let our_response =
+            ChallengeResponse { genesis_header, observed_ip: peer_ip.ip(), signature: Data::Object(our_signature) };
         trace!("Sending '{}' to '{peer_addr}'", our_response.name());
         framed.send(Message::ChallengeResponse(our_response)).await?;
 
-        // Add the peer to the router.
-        self.insert_connected_peer(Peer::new(peer_ip, &peer_request), peer_addr);
+        // Add the peer to the router, with the compression negotiated on the connection.
+        let compression = our_compression.negotiate(peer_request.compression);
+        self.insert_connected_peer(
+            Peer::new(peer_ip, &peer_request, ConnectionDirection::Outbound, compression),
+            peer_addr,
+        );
+        // Bind the dialed address to the account that answered the challenge, as it was found listening there.
+        self.announcements().bind(peer_ip, peer_request.address);
+
+        // Announce the listening address of this node to the peer.
+        self.send_announcement(peer_ip, observed_ip, &mut framed).await?;
 
         Ok((peer_ip, framed))
     }
@@ -218,6 +239,13 @@ impl<N: Network> Router<N> {
         // Verify the challenge request. If a disconnect reason was returned, send the disconnect message and abort.
         handle_verification!(self.verify_challenge_request(peer_addr, &peer_request), framed, peer_addr);
 
+        // Ensure the peer does not take a connection slot reserved for committee members.
+        if !self.committee.is_member(&peer_request.address)
+            && self.number_of_connected_peers() >= self.max_connected_peers()
+        {
+            return Err(error(format!("Dropping connection request from '{peer_ip}' (maximum peers reached)")));
+        }
+
         /* Step 2: Send the challenge response followed by own challenge request. */
 
         // Initialize an RNG.
@@ -233,12 +261,15 @@ impl<N: Network> Router<N> {
         let our_nonce = rng.gen();
 
         // Send the challenge response.
-        let our_response = ChallengeResponse { genesis_header, signature: Data::Object(our_signature) };
+        let our_response =
+            ChallengeResponse { genesis_header, observed_ip: peer_addr.ip(), signature: Data::Object(our_signature) };
         trace!("Sending '{}' to '{peer_addr}'", our_response.name());
         framed.send(Message::ChallengeResponse(our_response)).await?;
 
         // Send the challenge request.
-        let our_request = ChallengeRequest::new(self.local_ip().port(), self.node_type, self.address(), our_nonce);
+        let our_compression = self.compression();
+        let our_request =
+            ChallengeRequest::new(self.local_ip().port(), self.node_type, self.address(), our_nonce, our_compression);
         trace!("Sending '{}' to '{peer_addr}'", our_request.name());
         framed.send(Message::ChallengeRequest(our_request)).await?;
 
@@ -247,6 +278,9 @@ impl<N: Network> Router<N> {
         // Listen for the challenge response message.
         let peer_response = expect_message!(Message::ChallengeResponse, framed, peer_addr);
 
+        // Retrieve the IP address of this node, as observed by the peer.
+        let observed_ip = peer_response.observed_ip;
+
         // Verify the challenge response. If a disconnect reason was returned, send the disconnect message and abort.
         handle_verification!(
             self.verify_challenge_response(peer_addr, peer_request.address, peer_response, genesis_header, our_nonce)
@@ -255,8 +289,15 @@ impl<N: Network> Router<N> {
             peer_addr
         );
 
-        // Add the peer to the router.
-        self.insert_connected_peer(Peer::new(peer_ip, &peer_request), peer_addr);
+        // Add the peer to the router, with the compression negotiated on the connection.
+        let compression = our_compression.negotiate(peer_request.compression);
+        self.insert_connected_peer(
+            Peer::new(peer_ip, &peer_request, ConnectionDirection::Inbound, compression),
+            peer_addr,
+        );
+
+        // Announce the listening address of this node to the peer.
+        self.send_announcement(peer_ip, observed_ip, &mut framed).await?;
 
         Ok((peer_ip, framed))
     }
@@ -300,7 +341,7 @@ impl<N: Network> Router<N> {
         message: &ChallengeRequest<N>,
     ) -> Option<DisconnectReason> {
         // Retrieve the components of the challenge request.
-        let &ChallengeRequest { version, listener_port: _, node_type, address, nonce: _ } = message;
+        let &ChallengeRequest { version, listener_port: _, node_type, address, nonce: _, compression: _ } = message;
 
         // Ensure the message protocol version is not outdated.
         if version < Message::<N>::VERSION {
@@ -320,6 +361,38 @@ impl<N: Network> Router<N> {
         None
     }
 
+    /// Verifies that the peer is the account bound to its listening address, if this node previously dialed
+    /// the address. Returns a disconnect reason if the peer is another account.
+    fn verify_bound_account(&self, peer_ip: SocketAddr, message: &ChallengeRequest<N>) -> Option<DisconnectReason> {
+        match self.announcements().bound_account(&peer_ip) {
+            Some(address) if address != message.address => {
+                warn!("Dropping '{peer_ip}' (the address is bound to another account)");
+                Some(DisconnectReason::ProtocolViolation)
+            }
+            _ => None,
+        }
+    }
+
+    /// Records the IP of this node as observed by the peer, and announces the listening address of this node
+    /// to the peer, if the observed IP is corroborated. Otherwise, the heartbeat announces it once it is.
+    async fn send_announcement(
+        &self,
+        peer_ip: SocketAddr,
+        observed_ip: IpAddr,
+        framed: &mut Framed<&mut TcpStream, MessageCodec<N>>,
+    ) -> io::Result<()> {
+        self.announcements().insert_observed_ip(peer_ip, observed_ip);
+        let our_announcement = match self.sign_announcement(peer_ip) {
+            Ok(Some(announcement)) => announcement,
+            Ok(None) => return Ok(()),
+            Err(e) => return Err(error(format!("Failed to sign the peer announcement - {e}"))),
+        };
+        trace!("Sending '{}' to '{peer_ip}'", our_announcement.name());
+        framed.send(Message::PeerAnnouncement(our_announcement)).await?;
+        self.announcements().mark_sent(peer_ip);
+        Ok(())
+    }
+
     /// Verifies the given challenge response. Returns a disconnect reason if the response is invalid.
     async fn verify_challenge_response(
         &self,
@@ -330,7 +403,7 @@ impl<N: Network> Router<N> {
         expected_nonce: u64,
     ) -> Option<DisconnectReason> {
         // Retrieve the components of the challenge response.
-        let ChallengeResponse { genesis_header, signature } = response;
+        let ChallengeResponse { genesis_header, observed_ip: _, signature } = response;
 
         // Verify the challenge response, by checking that the block header matches.
         if genesis_header != expected_genesis_header {
@@ -347,8 +420,9 @@ impl<N: Network> Router<N> {
             }
         };
 
-        // Verify the signature.
-        if !signature.verify_bytes(&peer_address, &expected_nonce.to_le_bytes()) {
+        // Verify the signature, on the signature verifier.
+        let verify = move || signature.verify_bytes(&peer_address, &expected_nonce.to_le_bytes());
+        if !self.signatures().verify(verify).await {
             warn!("Handshake with '{peer_addr}' failed (invalid signature)");
             return Some(DisconnectReason::InvalidChallengeResponse);
         }
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{Outbound, Router, REDUNDANCY_FACTOR};
+use crate::{Outbound, Router, REDUNDANCY_FACTOR, STALE_TIP_HEARTBEATS};
 use snarkos_node_messages::{DisconnectReason, Message, PeerRequest, PuzzleRequest};
 use snarkvm::prelude::Network;
 
@@ -39,6 +39,13 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
     const MEDIAN_NUMBER_OF_PEERS: usize = max(Self::MAXIMUM_NUMBER_OF_PEERS / 2, Self::MINIMUM_NUMBER_OF_PEERS);
     /// The maximum number of peers permitted to maintain connections with.
     const MAXIMUM_NUMBER_OF_PEERS: usize = 21;
+    /// The divisor of the number of connected peers to rotate when the tip is stale.
+    const STALE_TIP_ROTATION_DIVISOR: usize = 3;
+    /// The duration in seconds to sleep in between attempts to redial the disconnected committee members.
+    const COMMITTEE_REDIAL_IN_SECS: u64 = 3; // 3 seconds
+    /// The duration in seconds in between the announcements of this node to each connected peer,
+    /// which keeps the announcements that the peers relay recent.
+    const ANNOUNCEMENT_INTERVAL_IN_SECS: u64 = 300; // 5 minutes
 
     /// Handles the heartbeat request.
     fn heartbeat(&self) {
@@ -49,14 +56,38 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         self.remove_stale_connected_peers();
         // Remove the oldest connected peer.
         self.remove_oldest_connected_peer();
+        // Rotate the quietest connected peers, if the tip is stale.
+        self.handle_stale_tip();
         // Keep the number of connected peers within the allowed range.
         self.handle_connected_peers();
         // Keep the bootstrap peers within the allowed range.
         self.handle_bootstrap_peers();
         // Keep the trusted peers connected.
         self.handle_trusted_peers();
+        // Keep the committee members connected.
+        self.handle_committee_peers();
         // Keep the puzzle request up to date.
         self.handle_puzzle_request();
+        // Keep the announcements of this node to the connected peers recent.
+        self.handle_announcements();
+    }
+
+    /// This function announces the listening address of this node to each connected peer that is due an announcement,
+    /// once the IP that the peer observed for this node is corroborated.
+    fn handle_announcements(&self) {
+        for peer_ip in self.router().connected_peers() {
+            if !self.router().announcements().is_due(&peer_ip, Self::ANNOUNCEMENT_INTERVAL_IN_SECS) {
+                continue;
+            }
+            match self.router().sign_announcement(peer_ip) {
+                Ok(Some(announcement)) => {
+                    self.send(peer_ip, Message::PeerAnnouncement(announcement));
+                    self.router().announcements().mark_sent(peer_ip);
+                }
+                Ok(None) => (),
+                Err(error) => warn!("Failed to sign the peer announcement for '{peer_ip}' - {error}"),
+            }
+        }
     }
 
     /// TODO (howardwu): Consider checking minimum number of beacons and validators, to exclude clients and provers.
@@ -114,12 +145,13 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         // Retrieve the bootstrap peers.
         let bootstrap = self.router().bootstrap_peers();
 
-        // Find the oldest connected peer, that is neither trusted nor a bootstrap peer.
+        // Find the oldest connected peer, that is neither trusted, a bootstrap peer, nor a committee member.
         let oldest_peer = self
             .router()
             .get_connected_peers()
             .iter()
             .filter(|peer| !trusted.contains(&peer.ip()) && !bootstrap.contains(&peer.ip()))
+            .filter(|peer| !self.router().committee().is_member(&peer.address()))
             .min_by_key(|peer| peer.last_seen())
             .map(|peer| peer.ip());
 
@@ -132,13 +164,76 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         }
     }
 
+    /// This function rotates part of the connected peers, if the tip has not advanced in
+    /// `STALE_TIP_HEARTBEATS` heartbeats while peers report higher heights.
+    fn handle_stale_tip(&self) {
+        // Skip if the node does not maintain its own canon locators.
+        let node_type = self.router().node_type();
+        if !node_type.is_beacon() && !node_type.is_validator() {
+            return;
+        }
+
+        // Retrieve the local height and the highest peer height.
+        let local_height = self.router().sync().latest_canon_height();
+        let peer_height = self.router().sync().get_peers_by_height().into_iter().map(|(_, height)| height).max();
+        // Skip if the tip is not stale.
+        if !self.router().tip_monitor().update(local_height, peer_height) {
+            return;
+        }
+
+        // Retrieve the trusted peers.
+        let trusted = self.router().trusted_peers();
+        // Retrieve the bootstrap peers.
+        let bootstrap = self.router().bootstrap_peers();
+
+        // Sort the connected peers, that are neither trusted, bootstrap peers, nor committee members, by their message rate.
+        let mut peers = self
+            .router()
+            .get_connected_peers()
+            .into_iter()
+            .filter(|peer| !trusted.contains(&peer.ip()) && !bootstrap.contains(&peer.ip()))
+            .filter(|peer| !self.router().committee().is_member(&peer.address()))
+            .map(|peer| {
+                let num_messages = self.router().message_stats().num_messages(&peer.ip());
+                let rate = num_messages as f64 / peer.first_seen().elapsed().as_secs_f64().max(1.0);
+                (peer.ip(), rate)
+            })
+            .collect::<Vec<_>>();
+        peers.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+
+        // Rotate a third of the peers (rounded up), starting from the quietest.
+        let num_rotated = (peers.len() + Self::STALE_TIP_ROTATION_DIVISOR - 1) / Self::STALE_TIP_ROTATION_DIVISOR;
+        warn!(
+            "The tip has been stuck at block {local_height} for {STALE_TIP_HEARTBEATS} heartbeats, while peers are at block {} - rotating {num_rotated} peers",
+            peer_height.unwrap_or_default()
+        );
+        for (peer_ip, _) in peers.into_iter().take(num_rotated) {
+            info!("Disconnecting from '{peer_ip}' (stale tip)");
+            self.send(peer_ip, Message::Disconnect(DisconnectReason::PeerRefresh.into()));
+            // Disconnect from this peer.
+            self.router().disconnect(peer_ip);
+        }
+
+        // Initialize an RNG.
+        let rng = &mut OsRng::default();
+        // Attempt to connect to new candidate peers.
+        for peer_ip in self.router().select_candidate_peers(num_rotated, rng) {
+            self.router().connect(peer_ip);
+        }
+    }
+
     /// TODO (howardwu): If the node is a beacon, keep the beacons, and keep 0 clients and provers.
     ///  If the node is a validator, keep REDUNDANCY_FACTOR beacons.
     ///  If the node is a client or prover, prioritize validators, and keep 0 beacons.
     /// This function keeps the number of connected peers within the allowed range.
     fn handle_connected_peers(&self) {
-        // Obtain the number of connected peers.
-        let num_connected = self.router().number_of_connected_peers();
+        // Obtain the number of connected peers, excluding the committee members in the reserved slots.
+        let num_connected = self
+            .router()
+            .get_connected_peers()
+            .iter()
+            .filter(|peer| !self.router().committee().is_member(&peer.address()))
+            .count();
         // Compute the number of surplus peers.
         let num_surplus = num_connected.saturating_sub(Self::MAXIMUM_NUMBER_OF_PEERS);
         // Compute the number of deficit peers.
@@ -163,6 +258,7 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
                 .connected_peers()
                 .into_iter()
                 .filter(|peer_ip| !trusted.contains(peer_ip) && !bootstrap.contains(peer_ip))
+                .filter(|peer_ip| !self.router().is_connected_committee_member(peer_ip))
                 .choose_multiple(rng, num_surplus);
 
             // Proceed to send disconnect requests to these peers.
@@ -188,7 +284,7 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
             let rng = &mut OsRng::default();
 
             // Attempt to connect to more peers.
-            for peer_ip in self.router().candidate_peers().into_iter().choose_multiple(rng, num_deficient) {
+            for peer_ip in self.router().select_candidate_peers(num_deficient, rng) {
                 self.router().connect(peer_ip);
             }
             // Request more peers from the connected peers.
@@ -254,6 +350,46 @@ pub trait Heartbeat<N: Network>: Outbound<N> {
         }
     }
 
+    /// This function attempts to connect to any disconnected committee members, preempting
+    /// a connected peer that is not a committee member if every connection slot is taken.
+    /// Note: The preempted slot is freed asynchronously, so the member is connected on a later redial.
+    fn handle_committee_peers(&self) {
+        // Retrieve the trusted peers.
+        let trusted = self.router().trusted_peers();
+
+        for peer_ip in self.router().committee().member_ips() {
+            // Skip the committee member if it is connected, or is connecting.
+            if self.router().is_connected(&peer_ip) || self.router().is_connecting(&peer_ip) {
+                continue;
+            }
+
+            // If every connection slot is taken, preempt a peer that is neither trusted nor a committee member.
+            if self.router().number_of_connected_peers() >= self.router().max_connected_peers_with_reserved() {
+                // Prefer to preempt clients and provers, and then the peers that were last seen the longest ago.
+                let preempted = self
+                    .router()
+                    .get_connected_peers()
+                    .into_iter()
+                    .filter(|peer| !trusted.contains(&peer.ip()))
+                    .filter(|peer| !self.router().committee().is_member(&peer.address()))
+                    .min_by_key(|peer| (peer.is_beacon() || peer.is_validator(), peer.last_seen()));
+                match preempted {
+                    Some(peer) => {
+                        info!("Disconnecting from '{}' (preempted by a committee member)", peer.ip());
+                        self.send(peer.ip(), Message::Disconnect(DisconnectReason::TooManyPeers.into()));
+                        // Disconnect from this peer.
+                        self.router().disconnect(peer.ip());
+                    }
+                    None => break,
+                }
+                continue;
+            }
+
+            // Attempt to connect to the committee member.
+            self.router().connect(peer_ip);
+        }
+    }
+
     /// This function updates the coinbase puzzle if network has updated.
     fn handle_puzzle_request(&self) {
         // Retrieve the node type.
@@ -0,0 +1,193 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::prelude::{Address, Network};
+
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use std::{
+    collections::{HashMap, HashSet},
+    net::{IpAddr, SocketAddr},
+    time::Instant,
+};
+
+/// The state of the peer announcements: the IPs the connected peers observed for this node,
+/// the times this node last announced itself to them, and the accounts proven to listen at the dialed addresses.
+#[derive(Debug)]
+pub struct Announcements<N: Network> {
+    /// The IP of this node, as observed by each connected peer.
+    observed_ips: RwLock<HashMap<SocketAddr, IpAddr>>,
+    /// The time this node last sent its own announcement to each connected peer.
+    last_sent: RwLock<HashMap<SocketAddr, Instant>>,
+    /// The account that answered the challenge of this node at each listening address it dialed.
+    /// The first binding of an address is kept, so a later announcement cannot claim the address for another account.
+    bindings: RwLock<IndexMap<SocketAddr, Address<N>>>,
+}
+
+impl<N: Network> Default for Announcements<N> {
+    fn default() -> Self {
+        Self { observed_ips: Default::default(), last_sent: Default::default(), bindings: Default::default() }
+    }
+}
+
+impl<N: Network> Announcements<N> {
+    /// The minimum number of distinct peer IPs that must observe the same IP of this node, before it is announced.
+    pub const MINIMUM_OBSERVED_IP_REPORTS: usize = 2;
+    /// The maximum number of listening addresses bound to an account.
+    pub const MAXIMUM_BINDINGS: usize = 10_000;
+
+    /// Records the IP of this node, as observed by the given connected peer.
+    pub fn insert_observed_ip(&self, peer_ip: SocketAddr, observed_ip: IpAddr) {
+        self.observed_ips.write().insert(peer_ip, observed_ip);
+    }
+
+    /// Returns the IP of this node that the given peer observed, if it may be announced to the peer.
+    ///
+    /// The observed IP is chosen by the peer, so it is only announced if it is the configured IP of this node,
+    /// if it is a loopback IP observed by a peer on this host, or if enough peers at distinct IPs observed it.
+    pub fn corroborated_ip(&self, local_ip: SocketAddr, peer_ip: &SocketAddr) -> Option<IpAddr> {
+        let observed_ips = self.observed_ips.read();
+        let observed_ip = match observed_ips.get(peer_ip) {
+            Some(observed_ip) if !observed_ip.is_unspecified() => *observed_ip,
+            _ => return None,
+        };
+        if observed_ip == local_ip.ip() || (observed_ip.is_loopback() && peer_ip.ip().is_loopback()) {
+            return Some(observed_ip);
+        }
+        let reporters = observed_ips
+            .iter()
+            .filter(|(_, ip)| **ip == observed_ip)
+            .map(|(reporter, _)| reporter.ip())
+            .collect::<HashSet<_>>();
+        match reporters.len() >= Self::MINIMUM_OBSERVED_IP_REPORTS {
+            true => Some(observed_ip),
+            false => None,
+        }
+    }
+
+    /// Returns `true` if this node has not announced itself to the given peer within the given interval.
+    pub fn is_due(&self, peer_ip: &SocketAddr, interval_in_secs: u64) -> bool {
+        self.last_sent.read().get(peer_ip).map_or(true, |sent| sent.elapsed().as_secs() >= interval_in_secs)
+    }
+
+    /// Records that this node announced itself to the given peer.
+    pub fn mark_sent(&self, peer_ip: SocketAddr) {
+        self.last_sent.write().insert(peer_ip, Instant::now());
+    }
+
+    /// Binds the given listening address to the account that answered the challenge of this node when dialing it,
+    /// unless the address is already bound.
+    pub fn bind(&self, listener: SocketAddr, address: Address<N>) {
+        let mut bindings = self.bindings.write();
+        if bindings.contains_key(&listener) {
+            return;
+        }
+        // Forget the oldest binding, if the bindings are full.
+        if bindings.len() >= Self::MAXIMUM_BINDINGS {
+            bindings.shift_remove_index(0);
+        }
+        bindings.insert(listener, address);
+    }
+
+    /// Returns the account bound to the given listening address, if any.
+    pub fn bound_account(&self, listener: &SocketAddr) -> Option<Address<N>> {
+        self.bindings.read().get(listener).copied()
+    }
+
+    /// Removes the observed IP and the announcement time of the given disconnected peer. Its binding is kept.
+    pub fn remove_peer(&self, peer_ip: &SocketAddr) {
+        self.observed_ips.write().remove(peer_ip);
+        self.last_sent.write().remove(peer_ip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{PrivateKey, TestRng, Testnet3};
+
+    use std::str::FromStr;
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_corroborated_ip() {
+        let announcements = Announcements::<CurrentNetwork>::default();
+        let local_ip = SocketAddr::from_str("0.0.0.0:4133").unwrap();
+        let public_ip = IpAddr::from_str("1.2.3.4").unwrap();
+
+        // Ensure an IP observed by a single peer is not announced.
+        let peer_a = SocketAddr::from_str("5.6.7.8:4133").unwrap();
+        announcements.insert_observed_ip(peer_a, public_ip);
+        assert_eq!(announcements.corroborated_ip(local_ip, &peer_a), None);
+
+        // Ensure a second port of the same peer IP does not corroborate it.
+        let peer_b = SocketAddr::from_str("5.6.7.8:4134").unwrap();
+        announcements.insert_observed_ip(peer_b, public_ip);
+        assert_eq!(announcements.corroborated_ip(local_ip, &peer_a), None);
+
+        // Ensure the IP is announced once a peer at another IP observed it.
+        let peer_c = SocketAddr::from_str("9.10.11.12:4133").unwrap();
+        announcements.insert_observed_ip(peer_c, public_ip);
+        assert_eq!(announcements.corroborated_ip(local_ip, &peer_a), Some(public_ip));
+        assert_eq!(announcements.corroborated_ip(local_ip, &peer_c), Some(public_ip));
+
+        // Ensure a disconnected peer no longer corroborates the IP.
+        announcements.remove_peer(&peer_c);
+        assert_eq!(announcements.corroborated_ip(local_ip, &peer_a), None);
+
+        // Ensure the configured IP, and a loopback IP observed on this host, need no corroboration.
+        let configured_ip = SocketAddr::new(public_ip, 4133);
+        assert_eq!(announcements.corroborated_ip(configured_ip, &peer_a), Some(public_ip));
+        let peer_d = SocketAddr::from_str("127.0.0.1:4134").unwrap();
+        announcements.insert_observed_ip(peer_d, IpAddr::from_str("127.0.0.1").unwrap());
+        assert!(announcements.corroborated_ip(local_ip, &peer_d).is_some());
+        let peer_e = SocketAddr::from_str("13.14.15.16:4133").unwrap();
+        announcements.insert_observed_ip(peer_e, IpAddr::from_str("127.0.0.1").unwrap());
+        assert_eq!(announcements.corroborated_ip(local_ip, &peer_e), None);
+    }
+
+    #[test]
+    fn test_bindings() {
+        let rng = &mut TestRng::default();
+        let announcements = Announcements::<CurrentNetwork>::default();
+        let listener = SocketAddr::from_str("1.2.3.4:4133").unwrap();
+        let first = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+        let second = Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap();
+
+        // Ensure the first binding of an address is kept.
+        assert_eq!(announcements.bound_account(&listener), None);
+        announcements.bind(listener, first);
+        announcements.bind(listener, second);
+        assert_eq!(announcements.bound_account(&listener), Some(first));
+
+        // Ensure the binding outlives the connection to the peer.
+        announcements.remove_peer(&listener);
+        assert_eq!(announcements.bound_account(&listener), Some(first));
+    }
+
+    #[test]
+    fn test_is_due() {
+        let announcements = Announcements::<CurrentNetwork>::default();
+        let peer_ip = SocketAddr::from_str("1.2.3.4:4133").unwrap();
+
+        // Ensure an announcement is due until it is sent, and again after the interval.
+        assert!(announcements.is_due(&peer_ip, 300));
+        announcements.mark_sent(peer_ip);
+        assert!(!announcements.is_due(&peer_ip, 300));
+        assert!(announcements.is_due(&peer_ip, 0));
+    }
+}
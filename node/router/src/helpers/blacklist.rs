@@ -0,0 +1,281 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::prelude::{Network, PuzzleCommitment};
+
+use anyhow::Result;
+use core::hash::Hash;
+use linked_hash_map::LinkedHashMap;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use time::{Duration, OffsetDateTime};
+
+/// The maximum number of entries retained in each blacklist.
+const MAX_BLACKLIST_SIZE: usize = 1 << 16;
+/// The duration in seconds for which a transmission remains blacklisted.
+/// Some transmissions are only invalid against the current ledger state, so entries are not kept forever.
+pub const BLACKLIST_EXPIRY_IN_SECS: i64 = 3600; // 1 hour
+
+/// The reason a transmission was blacklisted, and the time at which the entry expires.
+type Entry = (String, OffsetDateTime);
+
+/// A blacklist entry, as persisted to the blacklist file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Record<N: Network> {
+    /// A blacklisted solution commitment.
+    Solution { commitment: PuzzleCommitment<N>, reason: String, expires_at: i64 },
+    /// A blacklisted transaction ID.
+    Transaction { transaction_id: N::TransactionID, reason: String, expires_at: i64 },
+}
+
+/// The transmissions (solutions and transactions) that recently failed verification.
+/// Blacklisted transmissions are neither requested nor verified again when other peers advertise them.
+#[derive(Debug)]
+pub struct Blacklist<N: Network> {
+    /// The map of blacklisted solution commitments to their entries.
+    solutions: RwLock<LinkedHashMap<PuzzleCommitment<N>, Entry>>,
+    /// The map of blacklisted transaction IDs to their entries.
+    transactions: RwLock<LinkedHashMap<N::TransactionID, Entry>>,
+    /// The path of the file the entries are persisted to, if any.
+    path: Mutex<Option<PathBuf>>,
+    /// The number of entries appended to the file since it was last compacted.
+    num_appended: AtomicUsize,
+}
+
+impl<N: Network> Default for Blacklist<N> {
+    /// Initializes a new instance of the blacklist.
+    fn default() -> Self {
+        Self {
+            solutions: Default::default(),
+            transactions: Default::default(),
+            path: Default::default(),
+            num_appended: Default::default(),
+        }
+    }
+}
+
+impl<N: Network> Blacklist<N> {
+    /// Loads the unexpired entries from the given file, and persists any new entries to it.
+    pub fn open(&self, path: PathBuf) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if path.exists() {
+            let now = OffsetDateTime::now_utc();
+            for line in fs::read_to_string(&path)?.lines() {
+                let record = match serde_json::from_str::<Record<N>>(line) {
+                    Ok(record) => record,
+                    Err(error) => {
+                        warn!("Skipping malformed blacklist entry in '{}' - {error}", path.display());
+                        continue;
+                    }
+                };
+                match record {
+                    Record::Solution { commitment, reason, expires_at } => {
+                        let expiry = OffsetDateTime::from_unix_timestamp(expires_at)?;
+                        if expiry > now {
+                            Self::insert(&self.solutions, commitment, reason, expiry);
+                        }
+                    }
+                    Record::Transaction { transaction_id, reason, expires_at } => {
+                        let expiry = OffsetDateTime::from_unix_timestamp(expires_at)?;
+                        if expiry > now {
+                            Self::insert(&self.transactions, transaction_id, reason, expiry);
+                        }
+                    }
+                }
+            }
+        }
+        let mut path_lock = self.path.lock();
+        *path_lock = Some(path);
+        // Rewrite the file with the unexpired entries only.
+        self.compact(&mut path_lock)
+    }
+
+    /// Blacklists the given solution commitment, for the given reason.
+    pub fn insert_solution(&self, commitment: PuzzleCommitment<N>, reason: String) {
+        let expiry = OffsetDateTime::now_utc() + Duration::seconds(BLACKLIST_EXPIRY_IN_SECS);
+        Self::insert(&self.solutions, commitment, reason.clone(), expiry);
+        self.persist(&Record::Solution { commitment, reason, expires_at: expiry.unix_timestamp() });
+    }
+
+    /// Blacklists the given transaction ID, for the given reason.
+    pub fn insert_transaction(&self, transaction_id: N::TransactionID, reason: String) {
+        let expiry = OffsetDateTime::now_utc() + Duration::seconds(BLACKLIST_EXPIRY_IN_SECS);
+        Self::insert(&self.transactions, transaction_id, reason.clone(), expiry);
+        self.persist(&Record::Transaction { transaction_id, reason, expires_at: expiry.unix_timestamp() });
+    }
+
+    /// Returns the reason the given solution commitment is blacklisted, if it is.
+    pub fn get_solution(&self, commitment: &PuzzleCommitment<N>) -> Option<String> {
+        Self::get(&self.solutions, commitment)
+    }
+
+    /// Returns the reason the given transaction ID is blacklisted, if it is.
+    pub fn get_transaction(&self, transaction_id: &N::TransactionID) -> Option<String> {
+        Self::get(&self.transactions, transaction_id)
+    }
+
+    /// Returns the number of blacklisted solutions and transactions, including expired entries not yet evicted.
+    pub fn len(&self) -> usize {
+        self.solutions.read().len() + self.transactions.read().len()
+    }
+
+    /// Returns `true` if there are no blacklisted solutions or transactions.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<N: Network> Blacklist<N> {
+    /// Inserts the given key, evicting expired entries and enforcing the maximum size.
+    fn insert<K: Eq + Hash>(map: &RwLock<LinkedHashMap<K, Entry>>, key: K, reason: String, expiry: OffsetDateTime) {
+        let now = OffsetDateTime::now_utc();
+        let mut map = map.write();
+        // Re-insert the key at the back, as the newest entry.
+        map.remove(&key);
+        map.insert(key, (reason, expiry));
+        // Evict the expired entries and the oldest entries beyond the maximum size.
+        while map.front().map_or(false, |(_, (_, expiry))| *expiry <= now) || map.len() > MAX_BLACKLIST_SIZE {
+            map.pop_front();
+        }
+    }
+
+    /// Returns the reason the given key is blacklisted, if it is and has not expired.
+    fn get<K: Eq + Hash>(map: &RwLock<LinkedHashMap<K, Entry>>, key: &K) -> Option<String> {
+        map.read().get(key).filter(|(_, expiry)| *expiry > OffsetDateTime::now_utc()).map(|(reason, _)| reason.clone())
+    }
+
+    /// Appends the given record to the blacklist file, if one is set, and compacts the file once it has
+    /// grown by the maximum number of entries.
+    fn persist(&self, record: &Record<N>) {
+        let mut path = self.path.lock();
+        let result = match &*path {
+            Some(path) => serde_json::to_vec(record).map_err(anyhow::Error::from).and_then(|mut line| {
+                line.push(b'\n');
+                OpenOptions::new().create(true).append(true).open(path)?.write_all(&line)?;
+                Ok(())
+            }),
+            None => return,
+        };
+        let result = match result {
+            Ok(()) if self.num_appended.fetch_add(1, Ordering::Relaxed) + 1 >= MAX_BLACKLIST_SIZE => {
+                self.compact(&mut path)
+            }
+            result => result,
+        };
+        if let Err(error) = result {
+            warn!("Failed to persist the blacklist - {error}");
+        }
+    }
+
+    /// Rewrites the blacklist file with the unexpired entries, atomically.
+    fn compact(&self, path: &mut Option<PathBuf>) -> Result<()> {
+        let path = match path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let now = OffsetDateTime::now_utc();
+        let mut contents = Vec::new();
+        for (commitment, (reason, expiry)) in self.solutions.read().iter().filter(|(_, (_, expiry))| *expiry > now) {
+            let record = Record::Solution {
+                commitment: *commitment,
+                reason: reason.clone(),
+                expires_at: expiry.unix_timestamp(),
+            };
+            serde_json::to_writer(&mut contents, &record)?;
+            contents.push(b'\n');
+        }
+        for (transaction_id, (reason, expiry)) in
+            self.transactions.read().iter().filter(|(_, (_, expiry))| *expiry > now)
+        {
+            let record = Record::Transaction {
+                transaction_id: *transaction_id,
+                reason: reason.clone(),
+                expires_at: expiry.unix_timestamp(),
+            };
+            serde_json::to_writer(&mut contents, &record)?;
+            contents.push(b'\n');
+        }
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, contents)?;
+        fs::rename(&temp_path, path)?;
+        self.num_appended.store(0, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{Field, TestRng, Testnet3, Uniform};
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_blacklist() {
+        let rng = &mut TestRng::default();
+        let blacklist = Blacklist::<CurrentNetwork>::default();
+
+        // Blacklist a transaction.
+        let transaction_id = Field::<CurrentNetwork>::rand(rng).into();
+        assert!(blacklist.get_transaction(&transaction_id).is_none());
+        blacklist.insert_transaction(transaction_id, "Invalid proof".to_string());
+        assert_eq!(blacklist.get_transaction(&transaction_id), Some("Invalid proof".to_string()));
+        assert_eq!(blacklist.len(), 1);
+
+        // Ensure re-inserting the transaction does not duplicate the entry.
+        blacklist.insert_transaction(transaction_id, "Invalid fee".to_string());
+        assert_eq!(blacklist.get_transaction(&transaction_id), Some("Invalid fee".to_string()));
+        assert_eq!(blacklist.len(), 1);
+    }
+
+    #[test]
+    fn test_blacklist_persistence() {
+        let rng = &mut TestRng::default();
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("blacklist.jsonl");
+
+        // Blacklist two transactions, one of which has already expired.
+        let blacklist = Blacklist::<CurrentNetwork>::default();
+        blacklist.open(path.clone()).unwrap();
+        let transaction_id = Field::<CurrentNetwork>::rand(rng).into();
+        let expired_transaction_id = Field::<CurrentNetwork>::rand(rng).into();
+        blacklist.insert_transaction(transaction_id, "Invalid proof".to_string());
+        blacklist.persist(&Record::Transaction {
+            transaction_id: expired_transaction_id,
+            reason: "Invalid fee".to_string(),
+            expires_at: OffsetDateTime::now_utc().unix_timestamp() - 1,
+        });
+
+        // Ensure the unexpired entry is reloaded from the file.
+        let blacklist = Blacklist::<CurrentNetwork>::default();
+        blacklist.open(path.clone()).unwrap();
+        assert_eq!(blacklist.get_transaction(&transaction_id), Some("Invalid proof".to_string()));
+        assert!(blacklist.get_transaction(&expired_transaction_id).is_none());
+        assert_eq!(blacklist.len(), 1);
+
+        // Ensure the file was compacted to the unexpired entry.
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkos_node_messages::BlockRequest;
+
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+use std::{
+    collections::{HashMap, VecDeque},
+    net::SocketAddr,
+};
+use tokio::sync::Notify;
+
+/// The maximum number of block requests queued for a single peer.
+pub const MAX_QUEUED_BLOCK_REQUESTS_PER_PEER: usize = 64;
+/// The maximum number of block response bytes in flight to a single peer, before its requests are deferred.
+pub const MAX_OUTSTANDING_BYTES_PER_PEER: usize = 32 * 1024 * 1024; // 32 MiB
+/// The maximum number of block requests served concurrently.
+pub const MAX_CONCURRENT_BLOCK_RESPONSES: usize = 4;
+
+/// A scheduler that serves the inbound block requests round-robin across peers,
+/// deferring the peers that have too many response bytes in flight.
+#[derive(Debug, Default)]
+pub struct BlockScheduler {
+    /// The queued block requests of each peer, in round-robin order.
+    queues: Mutex<IndexMap<SocketAddr, VecDeque<BlockRequest>>>,
+    /// The number of block response bytes sent to each peer, that are not yet written to its socket.
+    outstanding_bytes: Mutex<HashMap<SocketAddr, usize>>,
+    /// The notifier for when a block request is queued, or outstanding bytes are released.
+    notify: Notify,
+}
+
+impl BlockScheduler {
+    /// Queues the given block request from the peer, returning `false` if the peer has too many queued requests.
+    pub fn enqueue(&self, peer_ip: SocketAddr, request: BlockRequest) -> bool {
+        {
+            let mut queues = self.queues.lock();
+            let requests = queues.entry(peer_ip).or_default();
+            if requests.len() >= MAX_QUEUED_BLOCK_REQUESTS_PER_PEER {
+                return false;
+            }
+            requests.push_back(request);
+        }
+        self.notify.notify_waiters();
+        true
+    }
+
+    /// Waits for, and returns, the next block request to serve.
+    pub async fn next(&self) -> (SocketAddr, BlockRequest) {
+        loop {
+            // Note: The notification is registered before checking the queues, so that no wakeup is missed.
+            let notified = self.notify.notified();
+            if let Some(next) = self.try_next() {
+                return next;
+            }
+            notified.await;
+        }
+    }
+
+    /// Returns the next block request to serve, if any peer below its outstanding bytes limit has a queued request.
+    fn try_next(&self) -> Option<(SocketAddr, BlockRequest)> {
+        let mut queues = self.queues.lock();
+        let outstanding_bytes = self.outstanding_bytes.lock();
+
+        // Find the first peer in round-robin order that is below its outstanding bytes limit.
+        let index = queues.keys().position(|peer_ip| {
+            outstanding_bytes.get(peer_ip).copied().unwrap_or(0) < MAX_OUTSTANDING_BYTES_PER_PEER
+        })?;
+        let (peer_ip, mut requests) = queues.shift_remove_index(index)?;
+        let request = requests.pop_front()?;
+        // Move the peer to the back of the round-robin order, if it has more queued requests.
+        if !requests.is_empty() {
+            queues.insert(peer_ip, requests);
+        }
+        Some((peer_ip, request))
+    }
+
+    /// Returns the number of block response bytes in flight to the given peer.
+    pub fn outstanding_bytes(&self, peer_ip: &SocketAddr) -> usize {
+        self.outstanding_bytes.lock().get(peer_ip).copied().unwrap_or(0)
+    }
+
+    /// Records the given number of block response bytes as in flight to the given peer.
+    pub fn add_outstanding_bytes(&self, peer_ip: SocketAddr, num_bytes: usize) {
+        *self.outstanding_bytes.lock().entry(peer_ip).or_default() += num_bytes;
+    }
+
+    /// Releases the given number of block response bytes, once they are written to the given peer.
+    pub fn release_outstanding_bytes(&self, peer_ip: SocketAddr, num_bytes: usize) {
+        {
+            let mut outstanding_bytes = self.outstanding_bytes.lock();
+            if let Some(bytes) = outstanding_bytes.get_mut(&peer_ip) {
+                *bytes = bytes.saturating_sub(num_bytes);
+                if *bytes == 0 {
+                    outstanding_bytes.remove(&peer_ip);
+                }
+            }
+        }
+        self.notify.notify_waiters();
+    }
+
+    /// Removes the queued block requests and outstanding bytes of the given peer.
+    pub fn remove_peer(&self, peer_ip: &SocketAddr) {
+        self.queues.lock().shift_remove(peer_ip);
+        self.outstanding_bytes.lock().remove(peer_ip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(start_height: u32) -> BlockRequest {
+        BlockRequest { start_height, end_height: start_height + 1 }
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let scheduler = BlockScheduler::default();
+        let peer_a = SocketAddr::from(([1, 1, 1, 1], 4133));
+        let peer_b = SocketAddr::from(([2, 2, 2, 2], 4133));
+
+        // Queue three requests from the first peer, then one request from the second peer.
+        for height in 0..3 {
+            assert!(scheduler.enqueue(peer_a, request(height)));
+        }
+        assert!(scheduler.enqueue(peer_b, request(10)));
+
+        // Ensure the requests are served round-robin between the peers.
+        assert_eq!(scheduler.try_next(), Some((peer_a, request(0))));
+        assert_eq!(scheduler.try_next(), Some((peer_b, request(10))));
+        assert_eq!(scheduler.try_next(), Some((peer_a, request(1))));
+        assert_eq!(scheduler.try_next(), Some((peer_a, request(2))));
+        assert_eq!(scheduler.try_next(), None);
+    }
+
+    #[test]
+    fn test_outstanding_bytes() {
+        let scheduler = BlockScheduler::default();
+        let peer_a = SocketAddr::from(([1, 1, 1, 1], 4133));
+        let peer_b = SocketAddr::from(([2, 2, 2, 2], 4133));
+
+        assert!(scheduler.enqueue(peer_a, request(0)));
+        assert!(scheduler.enqueue(peer_b, request(10)));
+
+        // Ensure a peer at its outstanding bytes limit is deferred.
+        scheduler.add_outstanding_bytes(peer_a, MAX_OUTSTANDING_BYTES_PER_PEER);
+        assert_eq!(scheduler.try_next(), Some((peer_b, request(10))));
+        assert_eq!(scheduler.try_next(), None);
+
+        // Ensure the peer is served once its bytes are released.
+        scheduler.release_outstanding_bytes(peer_a, MAX_OUTSTANDING_BYTES_PER_PEER);
+        assert_eq!(scheduler.outstanding_bytes(&peer_a), 0);
+        assert_eq!(scheduler.try_next(), Some((peer_a, request(0))));
+    }
+
+    #[test]
+    fn test_max_queued_requests() {
+        let scheduler = BlockScheduler::default();
+        let peer_ip = SocketAddr::from(([1, 1, 1, 1], 4133));
+
+        for height in 0..MAX_QUEUED_BLOCK_REQUESTS_PER_PEER as u32 {
+            assert!(scheduler.enqueue(peer_ip, request(height)));
+        }
+        assert!(!scheduler.enqueue(peer_ip, request(u32::MAX - 1)));
+
+        // Ensure the queue of a removed peer is cleared.
+        scheduler.remove_peer(&peer_ip);
+        assert_eq!(scheduler.try_next(), None);
+    }
+}
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use snarkos_node_messages::BlockRequest;
+use snarkos_node_messages::{BlockRequest, CertificateRangeRequest};
 use snarkvm::prelude::{Network, PuzzleCommitment};
 
 use core::hash::Hash;
@@ -29,6 +29,8 @@ use time::{Duration, OffsetDateTime};
 
 /// The maximum number of items to store in a cache map.
 const MAX_CACHE_SIZE: usize = 1 << 17;
+/// The time after which an unanswered certificate range request expires.
+const CERTIFICATE_REQUEST_TIMEOUT_IN_SECS: i64 = 30;
 
 /// A helper containing the peer IP and solution commitment.
 type SolutionKey<N> = (SocketAddr, PuzzleCommitment<N>);
@@ -49,6 +51,8 @@ pub struct Cache<N: Network> {
     seen_inbound_transactions: RwLock<LinkedHashMap<TransactionKey<N>, OffsetDateTime>>,
     /// The map of peer IPs to their block requests.
     seen_outbound_block_requests: RwLock<IndexMap<SocketAddr, IndexSet<BlockRequest>>>,
+    /// The map of peer IPs to their certificate range requests, and the timestamps of the requests.
+    seen_outbound_certificate_requests: RwLock<IndexMap<SocketAddr, IndexMap<CertificateRangeRequest, OffsetDateTime>>>,
     /// The map of peer IPs to the number of puzzle requests.
     seen_outbound_puzzle_requests: RwLock<IndexMap<SocketAddr, u16>>,
     /// The map of solution commitments to their last seen timestamp.
@@ -74,6 +78,7 @@ impl<N: Network> Cache<N> {
             seen_inbound_solutions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
             seen_inbound_transactions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
             seen_outbound_block_requests: Default::default(),
+            seen_outbound_certificate_requests: Default::default(),
             seen_outbound_puzzle_requests: Default::default(),
             seen_outbound_solutions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
             seen_outbound_transactions: RwLock::new(LinkedHashMap::with_capacity(MAX_CACHE_SIZE)),
@@ -136,6 +141,38 @@ impl<N: Network> Cache<N> {
         if let Some(requests) = map_write.get_mut(&peer_ip) { requests.remove(request) } else { false }
     }
 
+    /// Returns `true` if the cache contains an unexpired certificate range request for the given peer.
+    pub fn contains_outbound_certificate_request(&self, peer_ip: &SocketAddr) -> bool {
+        let now = OffsetDateTime::now_utc();
+        self.seen_outbound_certificate_requests
+            .read()
+            .get(peer_ip)
+            .map(|r| r.values().any(|timestamp| !Self::is_certificate_request_expired(now, *timestamp)))
+            .unwrap_or(false)
+    }
+
+    /// Inserts the certificate range request for the given peer IP, returning the number of recent requests.
+    /// The expired requests of the peer are removed, so a peer that never responds is asked again.
+    pub fn insert_outbound_certificate_request(&self, peer_ip: SocketAddr, request: CertificateRangeRequest) -> usize {
+        let now = OffsetDateTime::now_utc();
+        let mut map_write = self.seen_outbound_certificate_requests.write();
+        let requests = map_write.entry(peer_ip).or_default();
+        requests.retain(|_, timestamp| !Self::is_certificate_request_expired(now, *timestamp));
+        requests.insert(request, now);
+        requests.len()
+    }
+
+    /// Removes the certificate range request for the given peer IP, returning `true` if the request was present,
+    /// and had not expired.
+    pub fn remove_outbound_certificate_request(&self, peer_ip: SocketAddr, request: &CertificateRangeRequest) -> bool {
+        let now = OffsetDateTime::now_utc();
+        let mut map_write = self.seen_outbound_certificate_requests.write();
+        match map_write.get_mut(&peer_ip).and_then(|requests| requests.swap_remove(request)) {
+            Some(timestamp) => !Self::is_certificate_request_expired(now, timestamp),
+            None => false,
+        }
+    }
+
     /// Returns `true` if the cache contains a puzzle request from the given peer.
     pub fn contains_outbound_puzzle_request(&self, peer_ip: &SocketAddr) -> bool {
         self.seen_outbound_puzzle_requests.read().contains_key(peer_ip)
@@ -171,6 +208,11 @@ impl<N: Network> Cache<N> {
 }
 
 impl<N: Network> Cache<N> {
+    /// Returns `true` if a certificate range request sent at the given timestamp has expired.
+    fn is_certificate_request_expired(now: OffsetDateTime, timestamp: OffsetDateTime) -> bool {
+        now - timestamp > Duration::seconds(CERTIFICATE_REQUEST_TIMEOUT_IN_SECS)
+    }
+
     /// Insert a new timestamp for the given key, returning the number of recent entries.
     fn retain_and_insert<K: Eq + Hash + Clone>(
         map: &RwLock<IndexMap<K, VecDeque<OffsetDateTime>>>,
@@ -328,4 +370,32 @@ mod tests {
         // Check that the cache still contains the transaction.
         assert_eq!(cache.seen_outbound_transactions.read().len(), 1);
     }
+
+    #[test]
+    fn test_outbound_certificate_request() {
+        let cache = Cache::<CurrentNetwork>::default();
+        let peer_ip = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 1234);
+        let request = CertificateRangeRequest { start_round: 1, end_round: 10 };
+
+        // Insert a request, and check that the cache is awaiting its response.
+        assert_eq!(cache.insert_outbound_certificate_request(peer_ip, request), 1);
+        assert!(cache.contains_outbound_certificate_request(&peer_ip));
+        assert!(cache.remove_outbound_certificate_request(peer_ip, &request));
+        assert!(!cache.contains_outbound_certificate_request(&peer_ip));
+
+        // Insert a request, and expire it.
+        cache.insert_outbound_certificate_request(peer_ip, request);
+        let expired = OffsetDateTime::now_utc() - Duration::seconds(CERTIFICATE_REQUEST_TIMEOUT_IN_SECS + 1);
+        cache.seen_outbound_certificate_requests.write().get_mut(&peer_ip).unwrap().insert(request, expired);
+
+        // Check that the expired request is ignored, and that a late response is rejected.
+        assert!(!cache.contains_outbound_certificate_request(&peer_ip));
+        assert!(!cache.remove_outbound_certificate_request(peer_ip, &request));
+
+        // Check that the expired requests are removed, when a new request is inserted.
+        cache.seen_outbound_certificate_requests.write().get_mut(&peer_ip).unwrap().insert(request, expired);
+        let other = CertificateRangeRequest { start_round: 11, end_round: 20 };
+        assert_eq!(cache.insert_outbound_certificate_request(peer_ip, other), 1);
+        assert!(cache.contains_outbound_certificate_request(&peer_ip));
+    }
 }
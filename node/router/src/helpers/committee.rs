@@ -0,0 +1,110 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::prelude::{Address, Network};
+
+use indexmap::{IndexMap, IndexSet};
+use parking_lot::RwLock;
+use std::net::SocketAddr;
+
+/// The members of the current committee, and the last known listening IPs of those members.
+#[derive(Debug)]
+pub struct CommitteePeers<N: Network> {
+    /// The addresses of the committee members.
+    members: RwLock<IndexSet<Address<N>>>,
+    /// The last known listening IP of each committee member.
+    ips: RwLock<IndexMap<Address<N>, SocketAddr>>,
+}
+
+impl<N: Network> Default for CommitteePeers<N> {
+    fn default() -> Self {
+        Self { members: Default::default(), ips: Default::default() }
+    }
+}
+
+impl<N: Network> CommitteePeers<N> {
+    /// Replaces the committee members with the given addresses.
+    /// The known IPs of the addresses that are no longer in the committee are forgotten.
+    pub fn set_members(&self, members: impl IntoIterator<Item = Address<N>>) {
+        let members = members.into_iter().collect::<IndexSet<_>>();
+        self.ips.write().retain(|address, _| members.contains(address));
+        *self.members.write() = members;
+    }
+
+    /// Returns the number of committee members.
+    pub fn num_members(&self) -> usize {
+        self.members.read().len()
+    }
+
+    /// Returns `true` if the given address is a committee member.
+    pub fn is_member(&self, address: &Address<N>) -> bool {
+        self.members.read().contains(address)
+    }
+
+    /// Returns `true` if the given IP is the last known listening IP of a committee member.
+    pub fn is_member_ip(&self, peer_ip: &SocketAddr) -> bool {
+        self.ips.read().values().any(|ip| ip == peer_ip)
+    }
+
+    /// Returns the last known listening IPs of the committee members.
+    pub fn member_ips(&self) -> Vec<SocketAddr> {
+        self.ips.read().values().copied().collect()
+    }
+
+    /// Records the listening IP of the given address, if it is a committee member.
+    pub fn insert_peer(&self, address: Address<N>, peer_ip: SocketAddr) {
+        if self.is_member(&address) {
+            self.ips.write().insert(address, peer_ip);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::{PrivateKey, TestRng, Testnet3};
+
+    type CurrentNetwork = Testnet3;
+
+    fn sample_address(rng: &mut TestRng) -> Address<CurrentNetwork> {
+        Address::try_from(PrivateKey::<CurrentNetwork>::new(rng).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_committee_peers() {
+        let rng = &mut TestRng::default();
+        let committee = CommitteePeers::<CurrentNetwork>::default();
+
+        let (member, other) = (sample_address(rng), sample_address(rng));
+        let (member_ip, other_ip) = ("1.2.3.4:4133".parse().unwrap(), "5.6.7.8:4133".parse().unwrap());
+
+        // Ensure only the IPs of committee members are recorded.
+        committee.set_members([member]);
+        committee.insert_peer(member, member_ip);
+        committee.insert_peer(other, other_ip);
+        assert_eq!(committee.num_members(), 1);
+        assert!(committee.is_member(&member));
+        assert!(!committee.is_member(&other));
+        assert!(committee.is_member_ip(&member_ip));
+        assert!(!committee.is_member_ip(&other_ip));
+        assert_eq!(committee.member_ips(), vec![member_ip]);
+
+        // Ensure the IP of a member is forgotten once it leaves the committee.
+        committee.set_members([other]);
+        assert!(!committee.is_member_ip(&member_ip));
+        assert!(committee.member_ips().is_empty());
+    }
+}
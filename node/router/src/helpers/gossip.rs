@@ -0,0 +1,353 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkos_node_messages::UnconfirmedTransaction;
+use snarkvm::prelude::Network;
+
+use indexmap::IndexSet;
+use linked_hash_map::LinkedHashMap;
+use parking_lot::RwLock;
+use rand::{seq::SliceRandom, Rng};
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+use tokio::sync::broadcast;
+
+/// The maximum number of transaction payloads retained to serve `TransactionRequest` messages.
+const MAX_PAYLOADS: usize = 4096;
+/// The maximum number of announced transaction IDs retained as pending requests.
+const MAX_REQUESTS: usize = 4096;
+/// The duration in seconds after which the first attempt of a pending transaction request expires.
+/// Each retry doubles the timeout of the previous attempt.
+const REQUEST_TIMEOUT_IN_SECS: u64 = 5;
+/// The maximum number of attempts of a pending transaction request, including the first request.
+pub const MAX_REQUEST_ATTEMPTS: u8 = 4;
+/// The capacity of the channel of pending request events.
+const PENDING_EVENTS_CAPACITY: usize = 1024;
+
+/// The configuration of the gossip protocol.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct GossipConfig {
+    /// The number of peers that receive the full payload of a gossiped message, or all peers if `None`.
+    pub fanout: Option<usize>,
+    /// If `true`, the peers beyond the fanout receive an announcement of the transaction ID,
+    /// and may request the full transaction. Otherwise, the peers beyond the fanout receive nothing.
+    pub lazy_push: bool,
+    /// The maximum delay in milliseconds before relaying a locally-submitted transaction to each peer.
+    /// Each peer receives the transaction after an independent random delay, or immediately if `0`.
+    pub diffusion_delay_in_ms: u64,
+}
+
+/// An event in the lifecycle of a pending transaction request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PendingEvent<N: Network> {
+    /// The transaction was requested from the given peer.
+    Inserted { transaction_id: N::TransactionID, peer_ip: SocketAddr },
+    /// The requested transaction was received.
+    Resolved { transaction_id: N::TransactionID },
+    /// The request passed its deadline after the given number of attempts,
+    /// with the other peers that announced the transaction.
+    Expired { transaction_id: N::TransactionID, peers: Vec<SocketAddr>, attempts: u8 },
+}
+
+/// A pending transaction request.
+#[derive(Clone, Debug)]
+struct PendingRequest {
+    /// The deadline of the request.
+    deadline: Instant,
+    /// The other peers that announced the transaction, which have not been requested yet.
+    peers: IndexSet<SocketAddr>,
+    /// The number of attempts of the request, including the current one.
+    attempts: u8,
+}
+
+/// The state of the gossip protocol, for unconfirmed solutions and transactions.
+#[derive(Debug)]
+pub struct Gossip<N: Network> {
+    /// The gossip configuration.
+    config: RwLock<GossipConfig>,
+    /// The recently seen transactions, by transaction ID.
+    payloads: RwLock<LinkedHashMap<N::TransactionID, UnconfirmedTransaction<N>>>,
+    /// The pending transaction requests, by transaction ID.
+    requests: RwLock<LinkedHashMap<N::TransactionID, PendingRequest>>,
+    /// The sender of the pending request events.
+    events: broadcast::Sender<PendingEvent<N>>,
+}
+
+impl<N: Network> Default for Gossip<N> {
+    /// Initializes a new instance of the gossip state.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Network> Gossip<N> {
+    /// Initializes a new instance of the gossip state.
+    pub fn new() -> Self {
+        let (events, _) = broadcast::channel(PENDING_EVENTS_CAPACITY);
+        Self { config: Default::default(), payloads: Default::default(), requests: Default::default(), events }
+    }
+
+    /// Returns a receiver of the pending request events.
+    pub fn subscribe_pending(&self) -> broadcast::Receiver<PendingEvent<N>> {
+        self.events.subscribe()
+    }
+
+    /// Returns the gossip configuration.
+    pub fn config(&self) -> GossipConfig {
+        *self.config.read()
+    }
+
+    /// Sets the gossip configuration.
+    pub fn set_config(&self, config: GossipConfig) {
+        *self.config.write() = config;
+    }
+
+    /// Splits the given peers into the peers that receive the full payload (eager),
+    /// and the peers that receive an announcement (lazy).
+    pub fn select_peers(&self, mut peers: Vec<SocketAddr>) -> (Vec<SocketAddr>, Vec<SocketAddr>) {
+        let config = self.config();
+        match config.fanout {
+            Some(fanout) if fanout < peers.len() => {
+                // Sample the eager peers uniformly at random.
+                peers.shuffle(&mut rand::thread_rng());
+                let lazy = peers.split_off(fanout);
+                match config.lazy_push {
+                    true => (peers, lazy),
+                    false => (peers, vec![]),
+                }
+            }
+            _ => (peers, vec![]),
+        }
+    }
+
+    /// Samples a random delay before relaying a locally-submitted transaction to a peer,
+    /// or returns `None` if the diffusion delay is disabled.
+    pub fn sample_diffusion_delay(&self) -> Option<Duration> {
+        match self.config().diffusion_delay_in_ms {
+            0 => None,
+            max_delay => Some(Duration::from_millis(rand::thread_rng().gen_range(0..=max_delay))),
+        }
+    }
+
+    /// Returns the transaction with the given ID, if it was recently seen.
+    pub fn get_payload(&self, transaction_id: &N::TransactionID) -> Option<UnconfirmedTransaction<N>> {
+        self.payloads.read().get(transaction_id).cloned()
+    }
+
+    /// Inserts the given transaction into the recently seen transactions, resolving its pending request.
+    pub fn insert_payload(&self, message: UnconfirmedTransaction<N>) {
+        let transaction_id = message.transaction_id;
+        let mut payloads = self.payloads.write();
+        payloads.insert(transaction_id, message);
+        while payloads.len() > MAX_PAYLOADS {
+            payloads.pop_front();
+        }
+        drop(payloads);
+
+        if self.requests.write().remove(&transaction_id).is_some() {
+            let _ = self.events.send(PendingEvent::Resolved { transaction_id });
+        }
+    }
+
+    /// Returns `true` if the transaction announced by the given peer should be requested from the peer,
+    /// i.e. it was not recently seen or requested, and records it as pending. If the transaction is
+    /// already pending, the peer is retained as an alternative peer to request it from.
+    pub fn should_request(&self, transaction_id: N::TransactionID, peer_ip: SocketAddr) -> bool {
+        if self.payloads.read().contains_key(&transaction_id) {
+            return false;
+        }
+        let mut requests = self.requests.write();
+        if let Some(request) = requests.get_mut(&transaction_id) {
+            request.peers.insert(peer_ip);
+            return false;
+        }
+        Self::insert_request(&mut requests, transaction_id, Default::default(), 1);
+        drop(requests);
+
+        let _ = self.events.send(PendingEvent::Inserted { transaction_id, peer_ip });
+        true
+    }
+
+    /// Records the transaction as requested again from the given peer, with the remaining alternative peers,
+    /// and returns `true` if the request may be sent. The request is abandoned once `attempts` exceeds the maximum.
+    pub fn retry_request(
+        &self,
+        transaction_id: N::TransactionID,
+        peer_ip: SocketAddr,
+        peers: Vec<SocketAddr>,
+        attempts: u8,
+    ) -> bool {
+        if attempts > MAX_REQUEST_ATTEMPTS {
+            return false;
+        }
+        Self::insert_request(&mut self.requests.write(), transaction_id, peers.into_iter().collect(), attempts);
+        let _ = self.events.send(PendingEvent::Inserted { transaction_id, peer_ip });
+        true
+    }
+
+    /// Removes the pending requests that passed their deadline, and returns the number of expired requests.
+    pub fn expire_requests(&self) -> usize {
+        let now = Instant::now();
+        let mut requests = self.requests.write();
+        // Note: The requests are not in the order of their deadlines, as the retries back off exponentially.
+        let expired_ids: Vec<_> =
+            requests.iter().filter(|(_, request)| request.deadline <= now).map(|(id, _)| *id).collect();
+        let expired: Vec<_> = expired_ids.into_iter().filter_map(|id| Some((id, requests.remove(&id)?))).collect();
+        drop(requests);
+
+        for (transaction_id, request) in &expired {
+            let (peers, attempts) = (request.peers.iter().copied().collect(), request.attempts);
+            let _ = self.events.send(PendingEvent::Expired { transaction_id: *transaction_id, peers, attempts });
+        }
+        expired.len()
+    }
+
+    /// Returns the timeout of the given attempt of a pending request, which doubles with each retry.
+    fn request_timeout(attempts: u8) -> Duration {
+        Duration::from_secs(REQUEST_TIMEOUT_IN_SECS << attempts.saturating_sub(1).min(MAX_REQUEST_ATTEMPTS))
+    }
+
+    /// Inserts a pending request with a fresh deadline, evicting the oldest requests if full.
+    fn insert_request(
+        requests: &mut LinkedHashMap<N::TransactionID, PendingRequest>,
+        transaction_id: N::TransactionID,
+        peers: IndexSet<SocketAddr>,
+        attempts: u8,
+    ) {
+        let deadline = Instant::now() + Self::request_timeout(attempts);
+        requests.insert(transaction_id, PendingRequest { deadline, peers, attempts });
+        while requests.len() > MAX_REQUESTS {
+            requests.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_messages::Data;
+    use snarkvm::prelude::{Field, TestRng, Testnet3, Uniform};
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_select_peers() {
+        let gossip = Gossip::<CurrentNetwork>::new();
+        let peers: Vec<_> = (0..10).map(|i| SocketAddr::from(([127, 0, 0, 1], 4130 + i))).collect();
+
+        // By default, all peers receive the full payload.
+        let (eager, lazy) = gossip.select_peers(peers.clone());
+        assert_eq!(eager.len(), 10);
+        assert!(lazy.is_empty());
+
+        // With a fanout, only a subset of the peers receive the full payload.
+        gossip.set_config(GossipConfig { fanout: Some(3), lazy_push: false, ..Default::default() });
+        let (eager, lazy) = gossip.select_peers(peers.clone());
+        assert_eq!(eager.len(), 3);
+        assert!(lazy.is_empty());
+
+        // With lazy push, the remaining peers receive an announcement.
+        gossip.set_config(GossipConfig { fanout: Some(3), lazy_push: true, ..Default::default() });
+        let (eager, lazy) = gossip.select_peers(peers.clone());
+        assert_eq!(eager.len(), 3);
+        assert_eq!(lazy.len(), 7);
+        assert!(eager.iter().chain(lazy.iter()).all(|peer_ip| peers.contains(peer_ip)));
+    }
+
+    #[test]
+    fn test_sample_diffusion_delay() {
+        let gossip = Gossip::<CurrentNetwork>::new();
+
+        // By default, locally-submitted transactions are relayed immediately.
+        assert_eq!(gossip.sample_diffusion_delay(), None);
+
+        // With a diffusion delay, each delay is sampled within the bound.
+        gossip.set_config(GossipConfig { diffusion_delay_in_ms: 500, ..Default::default() });
+        for _ in 0..100 {
+            let delay = gossip.sample_diffusion_delay().unwrap();
+            assert!(delay <= Duration::from_millis(500));
+        }
+    }
+
+    #[test]
+    fn test_should_request() {
+        let rng = &mut TestRng::default();
+        let gossip = Gossip::<CurrentNetwork>::new();
+        let peer_ip = SocketAddr::from(([127, 0, 0, 1], 4130));
+
+        // Ensure an announced transaction is requested once.
+        let transaction_id = Field::<CurrentNetwork>::rand(rng).into();
+        assert!(gossip.should_request(transaction_id, peer_ip));
+        assert!(!gossip.should_request(transaction_id, peer_ip));
+    }
+
+    #[test]
+    fn test_pending_events() {
+        let rng = &mut TestRng::default();
+        let gossip = Gossip::<CurrentNetwork>::new();
+        let mut events = gossip.subscribe_pending();
+        let peer_a = SocketAddr::from(([127, 0, 0, 1], 4130));
+        let peer_b = SocketAddr::from(([127, 0, 0, 1], 4131));
+
+        // Request a transaction announced by two peers.
+        let transaction_id = Field::<CurrentNetwork>::rand(rng).into();
+        assert!(gossip.should_request(transaction_id, peer_a));
+        assert!(!gossip.should_request(transaction_id, peer_b));
+        assert_eq!(events.try_recv().unwrap(), PendingEvent::Inserted { transaction_id, peer_ip: peer_a });
+
+        // Ensure the request does not expire before its deadline.
+        assert_eq!(gossip.expire_requests(), 0);
+        // Ensure the request expires with the alternative peer, after its deadline.
+        gossip.requests.write().get_mut(&transaction_id).unwrap().deadline = Instant::now();
+        assert_eq!(gossip.expire_requests(), 1);
+        let expired = PendingEvent::Expired { transaction_id, peers: vec![peer_b], attempts: 1 };
+        assert_eq!(events.try_recv().unwrap(), expired);
+
+        // Retry the request from the alternative peer, and resolve it.
+        assert!(gossip.retry_request(transaction_id, peer_b, vec![], 2));
+        assert_eq!(events.try_recv().unwrap(), PendingEvent::Inserted { transaction_id, peer_ip: peer_b });
+        gossip.insert_payload(UnconfirmedTransaction { transaction_id, transaction: Data::Buffer(Default::default()) });
+        assert_eq!(events.try_recv().unwrap(), PendingEvent::Resolved { transaction_id });
+        assert_eq!(gossip.expire_requests(), 0);
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        let rng = &mut TestRng::default();
+        let gossip = Gossip::<CurrentNetwork>::new();
+        let peer_ip = SocketAddr::from(([127, 0, 0, 1], 4130));
+
+        // Ensure the timeout doubles with each attempt.
+        assert_eq!(Gossip::<CurrentNetwork>::request_timeout(1), Duration::from_secs(REQUEST_TIMEOUT_IN_SECS));
+        assert_eq!(Gossip::<CurrentNetwork>::request_timeout(2), Duration::from_secs(2 * REQUEST_TIMEOUT_IN_SECS));
+        assert_eq!(Gossip::<CurrentNetwork>::request_timeout(3), Duration::from_secs(4 * REQUEST_TIMEOUT_IN_SECS));
+
+        // Ensure a retry is pending with the backed off deadline.
+        let transaction_id = Field::<CurrentNetwork>::rand(rng).into();
+        assert!(gossip.retry_request(transaction_id, peer_ip, vec![], 2));
+        let deadline = gossip.requests.read().get(&transaction_id).unwrap().deadline;
+        assert!(deadline > Instant::now() + Duration::from_secs(REQUEST_TIMEOUT_IN_SECS));
+
+        // Ensure the request is abandoned once the attempts are exhausted.
+        let transaction_id = Field::<CurrentNetwork>::rand(rng).into();
+        assert!(gossip.retry_request(transaction_id, peer_ip, vec![], MAX_REQUEST_ATTEMPTS));
+        let transaction_id = Field::<CurrentNetwork>::rand(rng).into();
+        assert!(!gossip.retry_request(transaction_id, peer_ip, vec![], MAX_REQUEST_ATTEMPTS + 1));
+        assert!(!gossip.requests.read().contains_key(&transaction_id));
+    }
+}
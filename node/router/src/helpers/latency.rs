@@ -0,0 +1,142 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::{net::SocketAddr, time::Duration};
+
+/// The minimum number of response times observed from a peer, before its request timeout adapts to them.
+pub const MIN_LATENCY_SAMPLES: u64 = 3;
+/// The lower bound of an adaptive request timeout, in milliseconds.
+pub const MIN_REQUEST_TIMEOUT_IN_MILLIS: u64 = 2_000; // 2 seconds
+/// The upper bound of an adaptive request timeout, in milliseconds.
+pub const MAX_REQUEST_TIMEOUT_IN_MILLIS: u64 = 60_000; // 60 seconds
+
+/// The estimate of the response time of a peer, as in the retransmission timer of TCP (RFC 6298).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct LatencyEstimate {
+    /// The moving average of the response times, in microseconds.
+    pub mean_micros: u64,
+    /// The moving average of the deviation of the response times from the mean, in microseconds.
+    pub deviation_micros: u64,
+    /// The number of response times observed.
+    pub num_samples: u64,
+}
+
+impl LatencyEstimate {
+    /// Updates the estimate with the given response time.
+    /// The mean moves by 1/8 of its error, and the deviation moves by 1/4 of its error.
+    fn update(&mut self, sample: Duration) {
+        let sample = sample.as_micros().min(u64::MAX as u128) as u64;
+        match self.num_samples {
+            0 => {
+                self.mean_micros = sample;
+                self.deviation_micros = sample / 2;
+            }
+            _ => {
+                let error = self.mean_micros.abs_diff(sample);
+                self.deviation_micros = self.deviation_micros - self.deviation_micros / 4 + error / 4;
+                self.mean_micros = self.mean_micros - self.mean_micros / 8 + sample / 8;
+            }
+        }
+        self.num_samples = self.num_samples.saturating_add(1);
+    }
+
+    /// Returns the request timeout, as the mean plus four times the deviation, within the timeout bounds.
+    pub fn timeout(&self) -> Duration {
+        let timeout_micros = self.mean_micros.saturating_add(self.deviation_micros.saturating_mul(4));
+        Duration::from_micros(timeout_micros).clamp(
+            Duration::from_millis(MIN_REQUEST_TIMEOUT_IN_MILLIS),
+            Duration::from_millis(MAX_REQUEST_TIMEOUT_IN_MILLIS),
+        )
+    }
+}
+
+/// The response times of the peers, from which their request timeouts are derived.
+#[derive(Debug, Default)]
+pub struct PeerLatencies {
+    /// The map of peer IPs to the estimate of their response time.
+    estimates: RwLock<IndexMap<SocketAddr, LatencyEstimate>>,
+}
+
+impl PeerLatencies {
+    /// Returns the estimate of the response time of the given peer, if any response was observed.
+    pub fn get(&self, peer_ip: &SocketAddr) -> Option<LatencyEstimate> {
+        self.estimates.read().get(peer_ip).copied()
+    }
+
+    /// Records the response time of a request to the given peer.
+    pub fn insert_sample(&self, peer_ip: SocketAddr, sample: Duration) {
+        self.estimates.write().entry(peer_ip).or_default().update(sample);
+    }
+
+    /// Returns the request timeout for the given peer, or the given default timeout
+    /// if too few of its responses were observed.
+    pub fn timeout(&self, peer_ip: &SocketAddr, default: Duration) -> Duration {
+        match self.estimates.read().get(peer_ip) {
+            Some(estimate) if estimate.num_samples >= MIN_LATENCY_SAMPLES => estimate.timeout(),
+            _ => default,
+        }
+    }
+
+    /// Removes the estimate of the response time of the given peer.
+    pub fn remove(&self, peer_ip: &SocketAddr) {
+        self.estimates.write().remove(peer_ip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{IpAddr, Ipv4Addr};
+
+    #[test]
+    fn test_peer_latencies() {
+        let latencies = PeerLatencies::default();
+        let peer_ip = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 1);
+        let default = Duration::from_secs(15);
+
+        // Ensure the default timeout is used, until enough responses are observed.
+        assert_eq!(latencies.timeout(&peer_ip, default), default);
+        for _ in 0..MIN_LATENCY_SAMPLES - 1 {
+            latencies.insert_sample(peer_ip, Duration::from_millis(800));
+            assert_eq!(latencies.timeout(&peer_ip, default), default);
+        }
+
+        // Ensure a consistently fast peer is timed out sooner than the default.
+        for _ in 0..32 {
+            latencies.insert_sample(peer_ip, Duration::from_millis(800));
+        }
+        let estimate = latencies.get(&peer_ip).unwrap();
+        assert_eq!(estimate.mean_micros, 800_000);
+        assert_eq!(estimate.timeout(), Duration::from_millis(MIN_REQUEST_TIMEOUT_IN_MILLIS));
+        assert_eq!(latencies.timeout(&peer_ip, default), Duration::from_millis(MIN_REQUEST_TIMEOUT_IN_MILLIS));
+
+        // Ensure a consistently slow peer is given more time than the default.
+        for _ in 0..64 {
+            latencies.insert_sample(peer_ip, Duration::from_secs(20));
+        }
+        let timeout = latencies.timeout(&peer_ip, default);
+        assert!(timeout > Duration::from_secs(20), "{timeout:?}");
+        assert!(timeout <= Duration::from_millis(MAX_REQUEST_TIMEOUT_IN_MILLIS));
+
+        // Ensure the estimate is removed with the peer.
+        latencies.remove(&peer_ip);
+        assert_eq!(latencies.get(&peer_ip), None);
+        assert_eq!(latencies.timeout(&peer_ip, default), default);
+    }
+}
@@ -0,0 +1,161 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkos_node_messages::Message;
+use snarkvm::prelude::Network;
+
+use anyhow::{bail, Result};
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use std::{collections::VecDeque, net::SocketAddr, time::Instant};
+
+/// The interval in seconds over which the requests of each light peer are rate limited.
+const RATE_LIMIT_INTERVAL_IN_SECS: u64 = 10;
+
+/// The configuration of the light gateway.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LightGatewayConfig {
+    /// If `true`, the client peers are served as untrusted light consumers, restricted to the read-only messages.
+    pub enabled: bool,
+    /// The maximum number of requests from each light peer within the rate limit interval.
+    pub max_requests_per_interval: usize,
+}
+
+impl Default for LightGatewayConfig {
+    /// Returns the default configuration, in which the light gateway is disabled.
+    fn default() -> Self {
+        Self { enabled: false, max_requests_per_interval: 50 }
+    }
+}
+
+/// The light gateway, which serves block headers, recent blocks, and memory pool summaries to the client peers
+/// over a read-only subset of the messages, with a rate limit independent of the other peers.
+#[derive(Debug, Default)]
+pub struct LightGateway {
+    /// The light gateway configuration.
+    config: RwLock<LightGatewayConfig>,
+    /// The recent request timestamps of each light peer.
+    requests: RwLock<IndexMap<SocketAddr, VecDeque<Instant>>>,
+}
+
+impl LightGateway {
+    /// Returns the light gateway configuration.
+    pub fn config(&self) -> LightGatewayConfig {
+        *self.config.read()
+    }
+
+    /// Sets the light gateway configuration.
+    pub fn set_config(&self, config: LightGatewayConfig) {
+        *self.config.write() = config;
+    }
+
+    /// Returns `true` if the light gateway is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.config.read().enabled
+    }
+
+    /// Returns `true` if the given message is in the read-only subset served to light peers.
+    pub fn is_permitted<N: Network>(message: &Message<N>) -> bool {
+        matches!(
+            message,
+            Message::BlockRequest(..)
+                | Message::Disconnect(..)
+                | Message::HeaderRequest(..)
+                | Message::MemoryPoolRequest(..)
+                | Message::PeerAnnouncement(..)
+                | Message::PeerResponse(..)
+                | Message::Ping(..)
+                | Message::Pong(..)
+                | Message::PuzzleRequest(..)
+        )
+    }
+
+    /// Returns `true` if the given message is a request, which counts towards the rate limit of light peers.
+    fn is_request<N: Network>(message: &Message<N>) -> bool {
+        matches!(
+            message,
+            Message::BlockRequest(..)
+                | Message::HeaderRequest(..)
+                | Message::MemoryPoolRequest(..)
+                | Message::PuzzleRequest(..)
+        )
+    }
+
+    /// Checks that the given message from a light peer is permitted, and within the rate limit of the peer.
+    pub fn check<N: Network>(&self, peer_ip: SocketAddr, message: &Message<N>) -> Result<()> {
+        // Ensure the message is in the read-only subset.
+        if !Self::is_permitted(message) {
+            bail!("Light peer '{peer_ip}' sent a restricted message ('{}')", message.name())
+        }
+        // Ensure the peer is within its rate limit.
+        if Self::is_request(message) {
+            let now = Instant::now();
+            let mut requests = self.requests.write();
+            let timestamps = requests.entry(peer_ip).or_default();
+            timestamps.push_back(now);
+            while timestamps.front().map_or(false, |t| now.duration_since(*t).as_secs() >= RATE_LIMIT_INTERVAL_IN_SECS)
+            {
+                timestamps.pop_front();
+            }
+            if timestamps.len() > self.config().max_requests_per_interval {
+                bail!("Light peer '{peer_ip}' exceeded the rate limit ({} requests)", timestamps.len())
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the request timestamps of the given peer.
+    pub fn remove_peer(&self, peer_ip: &SocketAddr) {
+        self.requests.write().remove(peer_ip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_messages::{BlockRequest, Data, MemoryPoolRequest, UnconfirmedTransaction};
+    use snarkvm::prelude::{Field, TestRng, Testnet3, Uniform};
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_light_gateway() {
+        let rng = &mut TestRng::default();
+        let peer_ip = SocketAddr::from(([127, 0, 0, 1], 4130));
+
+        let gateway = LightGateway::default();
+        gateway.set_config(LightGatewayConfig { enabled: true, max_requests_per_interval: 2 });
+        assert!(gateway.is_enabled());
+
+        // Ensure the consensus and gossip messages are restricted.
+        let transaction_id = Field::<CurrentNetwork>::rand(rng).into();
+        let message = Message::<CurrentNetwork>::UnconfirmedTransaction(UnconfirmedTransaction {
+            transaction_id,
+            transaction: Data::Buffer(Default::default()),
+        });
+        assert!(gateway.check(peer_ip, &message).is_err());
+
+        // Ensure the requests are rate limited.
+        let request = Message::<CurrentNetwork>::BlockRequest(BlockRequest { start_height: 0, end_height: 1 });
+        assert!(gateway.check(peer_ip, &request).is_ok());
+        assert!(gateway.check(peer_ip, &Message::<CurrentNetwork>::MemoryPoolRequest(MemoryPoolRequest)).is_ok());
+        assert!(gateway.check(peer_ip, &request).is_err());
+
+        // Ensure the rate limit is reset for a reconnecting peer.
+        gateway.remove_peer(&peer_ip);
+        assert!(gateway.check(peer_ip, &request).is_ok());
+    }
+}
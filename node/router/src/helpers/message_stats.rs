@@ -0,0 +1,164 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::net::SocketAddr;
+use time::OffsetDateTime;
+
+/// The number of completed minutes observed before a message type is checked for anomalies.
+const WARMUP_MINUTES: u64 = 5;
+/// The factor over the baseline rate at which a message rate is flagged as anomalous.
+const ANOMALY_FACTOR: f64 = 100.0;
+/// The minimum baseline rate (in messages per minute) used for the anomaly check.
+const MIN_BASELINE: f64 = 1.0;
+/// The smoothing factor of the exponentially-weighted moving average of the per-minute rate.
+const BASELINE_ALPHA: f64 = 0.2;
+
+/// The statistics for one message type from one peer.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct MessageCounter {
+    /// The number of messages received in the current minute.
+    pub current_minute: u64,
+    /// The number of messages received in the previous minute.
+    pub previous_minute: u64,
+    /// The moving average of the number of messages received per minute.
+    pub baseline: f64,
+    /// The total number of messages received.
+    pub total: u64,
+    /// The number of minutes in which the message rate was flagged as anomalous.
+    pub anomalies: u64,
+    /// The number of completed minutes observed.
+    #[serde(skip)]
+    minutes_observed: u64,
+    /// The current minute, as the number of minutes since the Unix epoch.
+    #[serde(skip)]
+    minute: i64,
+}
+
+impl MessageCounter {
+    /// Records a message at the given minute, returning `true` if the current rate just became anomalous.
+    fn record(&mut self, minute: i64) -> bool {
+        // If a new minute started, fold the completed minute(s) into the baseline.
+        if minute > self.minute {
+            if self.total > 0 {
+                let elapsed = (minute - self.minute) as u64;
+                self.baseline = BASELINE_ALPHA * self.current_minute as f64 + (1.0 - BASELINE_ALPHA) * self.baseline;
+                // Decay the baseline for each silent minute, up to the warmup period.
+                for _ in 1..elapsed.min(WARMUP_MINUTES) {
+                    self.baseline *= 1.0 - BASELINE_ALPHA;
+                }
+                self.previous_minute = if elapsed == 1 { self.current_minute } else { 0 };
+                self.minutes_observed += elapsed;
+            }
+            self.minute = minute;
+            self.current_minute = 0;
+        }
+
+        self.current_minute += 1;
+        self.total += 1;
+
+        // Flag the rate once per minute, when it first crosses the anomaly threshold.
+        let threshold = (ANOMALY_FACTOR * self.baseline.max(MIN_BASELINE)).ceil() as u64;
+        let is_anomalous = self.minutes_observed >= WARMUP_MINUTES && self.current_minute == threshold;
+        if is_anomalous {
+            self.anomalies += 1;
+        }
+        is_anomalous
+    }
+}
+
+/// The per-minute statistics of inbound messages, by peer and message ID.
+#[derive(Debug, Default)]
+pub struct MessageStats {
+    /// The map of peer IPs to their message counters, by message ID.
+    peers: RwLock<IndexMap<SocketAddr, IndexMap<u16, MessageCounter>>>,
+}
+
+impl MessageStats {
+    /// Records an inbound message with the given message ID from the given peer,
+    /// returning `true` if the message rate from the peer just became anomalous.
+    pub fn record(&self, peer_ip: SocketAddr, message_id: u16) -> bool {
+        self.record_at(peer_ip, message_id, OffsetDateTime::now_utc().unix_timestamp() / 60)
+    }
+
+    /// Records an inbound message with the given message ID from the given peer, at the given minute.
+    fn record_at(&self, peer_ip: SocketAddr, message_id: u16, minute: i64) -> bool {
+        self.peers.write().entry(peer_ip).or_default().entry(message_id).or_default().record(minute)
+    }
+
+    /// Returns the message statistics of all peers, by message ID.
+    pub fn get_all(&self) -> IndexMap<SocketAddr, IndexMap<u16, MessageCounter>> {
+        self.peers.read().clone()
+    }
+
+    /// Returns the total number of anomalies flagged for the given peer.
+    pub fn num_anomalies(&self, peer_ip: &SocketAddr) -> u64 {
+        self.peers.read().get(peer_ip).map_or(0, |counters| counters.values().map(|c| c.anomalies).sum())
+    }
+
+    /// Returns the total number of messages received from the given peer.
+    pub fn num_messages(&self, peer_ip: &SocketAddr) -> u64 {
+        self.peers.read().get(peer_ip).map_or(0, |counters| counters.values().map(|c| c.total).sum())
+    }
+
+    /// Removes the message statistics of the given peer.
+    pub fn remove_peer(&self, peer_ip: &SocketAddr) {
+        self.peers.write().remove(peer_ip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The message ID of a block request.
+    const BLOCK_REQUEST: u16 = 3;
+    /// The message ID of a ping.
+    const PING: u16 = 10;
+
+    #[test]
+    fn test_message_stats() {
+        let stats = MessageStats::default();
+        let peer_ip = SocketAddr::from(([127, 0, 0, 1], 4130));
+
+        // Send 2 block requests per minute during the warmup period.
+        for minute in 0..WARMUP_MINUTES as i64 {
+            assert!(!stats.record_at(peer_ip, BLOCK_REQUEST, minute));
+            assert!(!stats.record_at(peer_ip, BLOCK_REQUEST, minute));
+        }
+        assert_eq!(stats.get_all()[&peer_ip][&BLOCK_REQUEST].total, 2 * WARMUP_MINUTES);
+        assert_eq!(stats.get_all()[&peer_ip][&BLOCK_REQUEST].current_minute, 2);
+
+        // Burst the block requests in the next minute, and ensure the anomaly is flagged exactly once.
+        let minute = WARMUP_MINUTES as i64;
+        let num_flagged = (0..1000).filter(|_| stats.record_at(peer_ip, BLOCK_REQUEST, minute)).count();
+        assert_eq!(num_flagged, 1);
+        assert_eq!(stats.num_anomalies(&peer_ip), 1);
+        assert_eq!(stats.get_all()[&peer_ip][&BLOCK_REQUEST].previous_minute, 2);
+
+        // Ensure other message types are tracked independently.
+        assert!(!stats.record_at(peer_ip, PING, minute));
+        assert_eq!(stats.get_all()[&peer_ip][&PING].total, 1);
+        assert_eq!(stats.num_messages(&peer_ip), 2 * WARMUP_MINUTES + 1001);
+
+        // Remove the peer.
+        stats.remove_peer(&peer_ip);
+        assert_eq!(stats.num_anomalies(&peer_ip), 0);
+        assert!(stats.get_all().is_empty());
+    }
+}
@@ -0,0 +1,342 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::Router;
+use snarkos_node_messages::{Message, NodeType};
+use snarkvm::prelude::Network;
+
+use anyhow::{bail, ensure, Result};
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use std::{net::SocketAddr, sync::Arc};
+
+/// The verdict of a middleware layer on an inbound message.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// The message proceeds to the next layer, and then to its handler.
+    Continue,
+    /// The message is dropped, without disconnecting the peer.
+    Skip,
+}
+
+/// A middleware layer around the handlers of the inbound messages.
+/// A layer rejects a message by returning an error, which disconnects the peer.
+pub trait Middleware<N: Network>: Send + Sync {
+    /// Checks the inbound message from the given peer.
+    fn check(&self, router: &Router<N>, peer_ip: SocketAddr, message: &Message<N>) -> Result<Verdict>;
+}
+
+/// The declarative routes of the inbound messages, composing the middleware layers
+/// applied to every message, and to each message type, ahead of the message handlers.
+pub struct MessageRoutes<N: Network> {
+    /// The layers applied to every message, in order.
+    layers: RwLock<Vec<Arc<dyn Middleware<N>>>>,
+    /// The layers applied to each message type (by message ID), in order, after the global layers.
+    routes: RwLock<IndexMap<u16, Vec<Arc<dyn Middleware<N>>>>>,
+}
+
+impl<N: Network> Default for MessageRoutes<N> {
+    /// Returns the default routes, which enforce the protocol policies of the router.
+    fn default() -> Self {
+        let routes = Self { layers: Default::default(), routes: Default::default() };
+        // Rate limit, record, and restrict every message.
+        routes.layer(SpamFilter);
+        routes.layer(MessageMetrics);
+        routes.layer(LightGatewayFilter);
+        // Authenticate the beacon messages (BeaconPropose, BeaconTimeout, BeaconVote).
+        routes.route(0, ProposalFilter);
+        routes.route(1, BeaconOnly);
+        routes.route(2, BeaconOnly);
+        // Require the light gateway for the light peer requests (HeaderRequest, MemoryPoolRequest).
+        routes.route(18, LightGatewayOnly);
+        routes.route(20, LightGatewayOnly);
+        // Rate limit the puzzle requests (PuzzleRequest).
+        routes.route(12, PuzzleRateLimit);
+        // Deduplicate, and skip the blacklisted transmissions
+        // (TransactionAnnouncement, UnconfirmedSolution, UnconfirmedTransaction).
+        routes.route(16, BlacklistFilter);
+        routes.route(14, Dedup);
+        routes.route(14, BlacklistFilter);
+        routes.route(15, Dedup);
+        routes.route(15, BlacklistFilter);
+        routes
+    }
+}
+
+impl<N: Network> MessageRoutes<N> {
+    /// Appends the given layer to the layers applied to every message.
+    pub fn layer(&self, layer: impl Middleware<N> + 'static) {
+        self.layers.write().push(Arc::new(layer));
+    }
+
+    /// Appends the given layer to the layers applied to the message type with the given ID (see `Message::id`).
+    pub fn route(&self, message_id: u16, layer: impl Middleware<N> + 'static) {
+        self.routes.write().entry(message_id).or_default().push(Arc::new(layer));
+    }
+
+    /// Passes the inbound message from the given peer through the global layers, and then the layers
+    /// of its message type, stopping at the first layer that skips or rejects the message.
+    pub fn check(&self, router: &Router<N>, peer_ip: SocketAddr, message: &Message<N>) -> Result<Verdict> {
+        // Note: The layers are cloned out of the locks, so a layer may register further layers.
+        let layers = self.layers.read().clone();
+        let routes = self.routes.read().get(&message.id()).cloned().unwrap_or_default();
+        for layer in layers.iter().chain(routes.iter()) {
+            if layer.check(router, peer_ip, message)? == Verdict::Skip {
+                return Ok(Verdict::Skip);
+            }
+        }
+        Ok(Verdict::Continue)
+    }
+}
+
+/// Drops the peer, if they have sent more than 1000 messages in the last 5 seconds.
+pub struct SpamFilter;
+
+impl<N: Network> Middleware<N> for SpamFilter {
+    fn check(&self, router: &Router<N>, peer_ip: SocketAddr, _message: &Message<N>) -> Result<Verdict> {
+        let num_messages = router.cache.insert_inbound_message(peer_ip, 5);
+        if num_messages >= 1000 {
+            bail!("Dropping '{peer_ip}' for spamming messages (num_messages = {num_messages})")
+        }
+        Ok(Verdict::Continue)
+    }
+}
+
+/// Records the message, and flags the peer if its message rate is anomalous.
+pub struct MessageMetrics;
+
+impl<N: Network> Middleware<N> for MessageMetrics {
+    fn check(&self, router: &Router<N>, peer_ip: SocketAddr, message: &Message<N>) -> Result<Verdict> {
+        if router.message_stats().record(peer_ip, message.id()) {
+            let num_anomalies = router.message_stats().num_anomalies(&peer_ip);
+            warn!("Anomalous '{}' message rate from '{peer_ip}' (num_anomalies = {num_anomalies})", message.name());
+        }
+        Ok(Verdict::Continue)
+    }
+}
+
+/// If the light gateway is enabled, restricts the client peers to the read-only messages, within their rate limit.
+pub struct LightGatewayFilter;
+
+impl<N: Network> Middleware<N> for LightGatewayFilter {
+    fn check(&self, router: &Router<N>, peer_ip: SocketAddr, message: &Message<N>) -> Result<Verdict> {
+        if router.light_gateway().is_enabled() && router.is_connected_client(&peer_ip) {
+            router.light_gateway().check(peer_ip, message)?;
+        }
+        Ok(Verdict::Continue)
+    }
+}
+
+/// Ensures this node and the peer are both beacons.
+pub struct BeaconOnly;
+
+impl<N: Network> Middleware<N> for BeaconOnly {
+    fn check(&self, router: &Router<N>, peer_ip: SocketAddr, message: &Message<N>) -> Result<Verdict> {
+        ensure!(router.node_type().is_beacon(), "[{}] This node is not a beacon", message.name());
+        ensure!(router.is_connected_beacon(&peer_ip), "[{}] '{peer_ip}' is not a beacon", message.name());
+        Ok(Verdict::Continue)
+    }
+}
+
+/// Ensures a beacon proposal is sent between beacons, or to a validator by a beacon or a validator that relays it.
+pub struct ProposalFilter;
+
+impl<N: Network> Middleware<N> for ProposalFilter {
+    fn check(&self, router: &Router<N>, peer_ip: SocketAddr, message: &Message<N>) -> Result<Verdict> {
+        match router.node_type() {
+            NodeType::Beacon => {
+                ensure!(router.is_connected_beacon(&peer_ip), "[{}] '{peer_ip}' is not a beacon", message.name())
+            }
+            NodeType::Validator => ensure!(
+                router.is_connected_beacon(&peer_ip) || router.is_connected_validator(&peer_ip),
+                "[{}] '{peer_ip}' is not a beacon or validator",
+                message.name()
+            ),
+            _ => bail!("[{}] This node is not a beacon or validator", message.name()),
+        }
+        Ok(Verdict::Continue)
+    }
+}
+
+/// Ensures this node is a light gateway.
+pub struct LightGatewayOnly;
+
+impl<N: Network> Middleware<N> for LightGatewayOnly {
+    fn check(&self, router: &Router<N>, _peer_ip: SocketAddr, message: &Message<N>) -> Result<Verdict> {
+        ensure!(router.light_gateway().is_enabled(), "[{}] This node is not a light gateway", message.name());
+        Ok(Verdict::Continue)
+    }
+}
+
+/// Drops the peer, if they have sent more than 5 puzzle requests within the interval.
+pub struct PuzzleRateLimit;
+
+impl PuzzleRateLimit {
+    /// The maximum number of puzzle requests per interval.
+    pub const MAXIMUM_PUZZLE_REQUESTS_PER_INTERVAL: usize = 5;
+}
+
+impl<N: Network> Middleware<N> for PuzzleRateLimit {
+    fn check(&self, router: &Router<N>, peer_ip: SocketAddr, _message: &Message<N>) -> Result<Verdict> {
+        // Insert the puzzle request for the peer, and fetch the recent frequency.
+        let frequency = router.cache.insert_inbound_puzzle_request(peer_ip);
+        // Check if the number of puzzle requests is within the limit.
+        if frequency > Self::MAXIMUM_PUZZLE_REQUESTS_PER_INTERVAL {
+            bail!("Peer '{peer_ip}' is not following the protocol (excessive puzzle requests)")
+        }
+        Ok(Verdict::Continue)
+    }
+}
+
+/// Rejects the unconfirmed solutions and transactions that the peer has sent before.
+pub struct Dedup;
+
+impl<N: Network> Middleware<N> for Dedup {
+    fn check(&self, router: &Router<N>, peer_ip: SocketAddr, message: &Message<N>) -> Result<Verdict> {
+        // Update the timestamp for the unconfirmed solution or transaction.
+        let seen_before = match message {
+            Message::UnconfirmedSolution(message) => {
+                router.cache.insert_inbound_solution(peer_ip, message.puzzle_commitment).is_some()
+            }
+            Message::UnconfirmedTransaction(message) => {
+                router.cache.insert_inbound_transaction(peer_ip, message.transaction_id).is_some()
+            }
+            _ => false,
+        };
+        if seen_before {
+            bail!("Skipping '{}' from '{peer_ip}'", message.name())
+        }
+        Ok(Verdict::Continue)
+    }
+}
+
+/// Skips the solutions and transactions that recently failed verification.
+pub struct BlacklistFilter;
+
+impl<N: Network> Middleware<N> for BlacklistFilter {
+    fn check(&self, router: &Router<N>, peer_ip: SocketAddr, message: &Message<N>) -> Result<Verdict> {
+        let reason = match message {
+            Message::TransactionAnnouncement(message) => router.blacklist().get_transaction(&message.transaction_id),
+            Message::UnconfirmedSolution(message) => router.blacklist().get_solution(&message.puzzle_commitment),
+            Message::UnconfirmedTransaction(message) => router.blacklist().get_transaction(&message.transaction_id),
+            _ => None,
+        };
+        match reason {
+            Some(reason) => {
+                trace!("Skipping '{}' from '{peer_ip}' (blacklisted - {reason})", message.name());
+                Ok(Verdict::Skip)
+            }
+            None => Ok(Verdict::Continue),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_account::Account;
+    use snarkos_node_messages::{
+        BeaconPropose,
+        BeaconVote,
+        Data,
+        MemoryPoolRequest,
+        NodeType,
+        PeerRequest,
+        Pong,
+        PuzzleRequest,
+    };
+    use snarkvm::prelude::{TestRng, Testnet3};
+
+    type CurrentNetwork = Testnet3;
+
+    /// A layer that skips every message.
+    struct SkipAll;
+
+    impl<N: Network> Middleware<N> for SkipAll {
+        fn check(&self, _router: &Router<N>, _peer_ip: SocketAddr, _message: &Message<N>) -> Result<Verdict> {
+            Ok(Verdict::Skip)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_message_routes() {
+        let rng = &mut TestRng::default();
+        let account = Account::<CurrentNetwork>::new(rng).unwrap();
+        let node_ip = SocketAddr::from(([127, 0, 0, 1], 0));
+        let router = Router::new(node_ip, NodeType::Client, account, &[], 10, true).await.unwrap();
+        let routes = router.message_routes();
+        let peer_ip = SocketAddr::from(([127, 0, 0, 1], 4130));
+
+        // The default routes pass the ordinary messages.
+        let peer_request = Message::PeerRequest(PeerRequest);
+        assert_eq!(routes.check(&router, peer_ip, &peer_request).unwrap(), Verdict::Continue);
+        // The light peer requests require the light gateway.
+        assert!(routes.check(&router, peer_ip, &Message::MemoryPoolRequest(MemoryPoolRequest)).is_err());
+        // The puzzle requests are rate limited.
+        let puzzle_request = Message::PuzzleRequest(PuzzleRequest);
+        for _ in 0..PuzzleRateLimit::MAXIMUM_PUZZLE_REQUESTS_PER_INTERVAL {
+            assert_eq!(routes.check(&router, peer_ip, &puzzle_request).unwrap(), Verdict::Continue);
+        }
+        assert!(routes.check(&router, peer_ip, &puzzle_request).is_err());
+
+        // A layer on a route only applies to its message type.
+        routes.route(peer_request.id(), SkipAll);
+        assert_eq!(routes.check(&router, peer_ip, &peer_request).unwrap(), Verdict::Skip);
+        let pong = Message::Pong(Pong { is_fork: None });
+        assert_eq!(routes.check(&router, peer_ip, &pong).unwrap(), Verdict::Continue);
+    }
+
+    #[tokio::test]
+    async fn test_beacon_routes() {
+        let rng = &mut TestRng::default();
+        let node_ip = SocketAddr::from(([127, 0, 0, 1], 0));
+        let peer_ip = SocketAddr::from(([127, 0, 0, 1], 4130));
+
+        // Sample a beacon proposal.
+        let propose = Message::BeaconPropose(BeaconPropose::<CurrentNetwork>::new(
+            1,
+            1,
+            Default::default(),
+            Data::Buffer(Default::default()),
+        ));
+
+        // A client rejects the beacon proposal.
+        let account = Account::<CurrentNetwork>::new(rng).unwrap();
+        let client = Router::new(node_ip, NodeType::Client, account, &[], 10, true).await.unwrap();
+        let error = client.message_routes().check(&client, peer_ip, &propose).unwrap_err();
+        assert!(error.to_string().contains("This node is not a beacon"));
+
+        // A beacon rejects the beacon proposal from a peer that is not a connected beacon.
+        let account = Account::<CurrentNetwork>::new(rng).unwrap();
+        let beacon = Router::new(node_ip, NodeType::Beacon, account, &[], 10, true).await.unwrap();
+        let error = beacon.message_routes().check(&beacon, peer_ip, &propose).unwrap_err();
+        assert!(error.to_string().contains("is not a beacon"));
+        // A validator rejects the beacon proposal from a peer that is not a connected beacon or validator.
+        let account = Account::<CurrentNetwork>::new(rng).unwrap();
+        let validator = Router::new(node_ip, NodeType::Validator, account, &[], 10, true).await.unwrap();
+        let error = validator.message_routes().check(&validator, peer_ip, &propose).unwrap_err();
+        assert!(error.to_string().contains("is not a beacon or validator"));
+        // The beacon votes are guarded as well.
+        let vote = Message::BeaconVote(BeaconVote::<CurrentNetwork>::new(
+            1,
+            1,
+            Default::default(),
+            0,
+            Data::Buffer(Default::default()),
+        ));
+        assert!(beacon.message_routes().check(&beacon, peer_ip, &vote).is_err());
+    }
+}
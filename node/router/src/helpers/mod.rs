@@ -14,14 +14,56 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
+mod announcements;
+pub use announcements::*;
+
+mod blacklist;
+pub use blacklist::*;
+
+mod block_scheduler;
+pub use block_scheduler::*;
+
 mod cache;
 pub use cache::Cache;
 
+mod committee;
+pub use committee::*;
+
+mod gossip;
+pub use gossip::*;
+
+mod latency;
+pub use latency::*;
+
+mod light_gateway;
+pub use light_gateway::*;
+
+mod message_stats;
+pub use message_stats::*;
+
+mod middleware;
+pub use middleware::*;
+
 mod peer;
 pub use peer::*;
 
+mod peer_diversity;
+pub use peer_diversity::*;
+
+mod relay;
+pub use relay::*;
+
 mod resolver;
 pub(crate) use resolver::*;
 
+mod signatures;
+pub use signatures::*;
+
+mod spill;
+pub use spill::*;
+
 mod sync;
 pub use sync::*;
+
+mod tip_monitor;
+pub use tip_monitor::*;
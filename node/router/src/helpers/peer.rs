@@ -14,11 +14,48 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use snarkos_node_messages::{ChallengeRequest, NodeType};
+use snarkos_node_messages::{ChallengeRequest, Compression, NodeType, PeerAnnouncement};
 use snarkvm::prelude::{Address, Network};
 
+use serde::Serialize;
 use std::{net::SocketAddr, time::Instant};
 
+/// The direction of a connection, from the perspective of this node.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionDirection {
+    /// The peer initiated the connection.
+    Inbound,
+    /// This node initiated the connection.
+    Outbound,
+}
+
+/// A summary of a connected peer, as reported to operators.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PeerInfo {
+    /// The IP address of the peer, with the port set to the listener port.
+    pub ip: SocketAddr,
+    /// The direction of the connection.
+    pub direction: ConnectionDirection,
+    /// The node type of the peer.
+    pub node_type: NodeType,
+    /// The message version of the peer.
+    pub version: u32,
+    /// The number of seconds since the last message was received from the peer.
+    pub last_seen_secs: u64,
+}
+
+/// The number of connected peers, by connection direction.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ConnectionCount {
+    /// The total number of connected peers.
+    pub total: usize,
+    /// The number of peers that connected to this node.
+    pub inbound: usize,
+    /// The number of peers this node connected to.
+    pub outbound: usize,
+}
+
 /// The state for each connected peer.
 #[derive(Clone, Debug)]
 pub struct Peer<N: Network> {
@@ -30,22 +67,36 @@ pub struct Peer<N: Network> {
     node_type: NodeType,
     /// The message version of the peer.
     version: u32,
+    /// The direction of the connection.
+    direction: ConnectionDirection,
     /// The timestamp of the first message received from the peer.
     first_seen: Instant,
     /// The timestamp of the last message received from this peer.
     last_seen: Instant,
+    /// The signed announcement of the listening address of the peer, once it is received and verified.
+    announcement: Option<PeerAnnouncement<N>>,
+    /// The compression negotiated on the connection.
+    compression: Compression,
 }
 
 impl<N: Network> Peer<N> {
     /// Initializes a new instance of `Peer`.
-    pub fn new(listening_ip: SocketAddr, challenge_request: &ChallengeRequest<N>) -> Self {
+    pub fn new(
+        listening_ip: SocketAddr,
+        challenge_request: &ChallengeRequest<N>,
+        direction: ConnectionDirection,
+        compression: Compression,
+    ) -> Self {
         Self {
             peer_ip: listening_ip,
             address: challenge_request.address,
             node_type: challenge_request.node_type,
             version: challenge_request.version,
+            direction,
             first_seen: Instant::now(),
             last_seen: Instant::now(),
+            announcement: None,
+            compression,
         }
     }
 
@@ -89,6 +140,16 @@ impl<N: Network> Peer<N> {
         self.version
     }
 
+    /// Returns the direction of the connection.
+    pub const fn direction(&self) -> ConnectionDirection {
+        self.direction
+    }
+
+    /// Returns `true` if the peer initiated the connection.
+    pub fn is_inbound(&self) -> bool {
+        self.direction == ConnectionDirection::Inbound
+    }
+
     /// Returns the first seen timestamp of the peer.
     pub fn first_seen(&self) -> Instant {
         self.first_seen
@@ -98,6 +159,27 @@ impl<N: Network> Peer<N> {
     pub fn last_seen(&self) -> Instant {
         self.last_seen
     }
+
+    /// Returns the signed announcement of the listening address of the peer, if it was received.
+    pub const fn announcement(&self) -> Option<&PeerAnnouncement<N>> {
+        self.announcement.as_ref()
+    }
+
+    /// Returns the compression negotiated on the connection.
+    pub const fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    /// Returns a summary of the peer.
+    pub fn info(&self) -> PeerInfo {
+        PeerInfo {
+            ip: self.peer_ip,
+            direction: self.direction,
+            node_type: self.node_type,
+            version: self.version,
+            last_seen_secs: self.last_seen.elapsed().as_secs(),
+        }
+    }
 }
 
 impl<N: Network> Peer<N> {
@@ -115,4 +197,9 @@ impl<N: Network> Peer<N> {
     pub fn set_last_seen(&mut self, last_seen: Instant) {
         self.last_seen = last_seen;
     }
+
+    /// Updates the signed announcement of the listening address of the peer.
+    pub fn set_announcement(&mut self, announcement: PeerAnnouncement<N>) {
+        self.announcement = Some(announcement);
+    }
 }
@@ -0,0 +1,159 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use indexmap::IndexMap;
+use parking_lot::RwLock;
+use rand::{seq::SliceRandom, Rng};
+use std::net::{IpAddr, SocketAddr};
+
+/// The configuration of the diversity constraints on the peers this node dials.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PeerDiversityConfig {
+    /// The maximum number of peers in the same subnet, or unlimited if `None`.
+    /// A subnet is a /16 for IPv4 addresses, and a /32 for IPv6 addresses.
+    pub max_peers_per_subnet: Option<usize>,
+}
+
+/// The subnet of an IP address, over which the peer diversity is enforced.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Subnet {
+    /// The /16 prefix of an IPv4 address.
+    V4([u8; 2]),
+    /// The /32 prefix of an IPv6 address.
+    V6([u16; 2]),
+}
+
+impl From<IpAddr> for Subnet {
+    /// Returns the subnet of the given IP address. An IPv4-mapped IPv6 address is in the subnet of its IPv4 address.
+    fn from(ip: IpAddr) -> Self {
+        match ip {
+            IpAddr::V4(ip) => {
+                let [a, b, ..] = ip.octets();
+                Self::V4([a, b])
+            }
+            IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+                Some(ip) => Self::from(IpAddr::V4(ip)),
+                None => {
+                    let [a, b, ..] = ip.segments();
+                    Self::V6([a, b])
+                }
+            },
+        }
+    }
+}
+
+/// The diversity constraints on the peers this node dials, which bound the share of the peer set
+/// that a single network operator can fill, to make the node harder to eclipse.
+#[derive(Debug, Default)]
+pub struct PeerDiversity {
+    /// The peer diversity configuration.
+    config: RwLock<PeerDiversityConfig>,
+}
+
+impl PeerDiversity {
+    /// Returns the peer diversity configuration.
+    pub fn config(&self) -> PeerDiversityConfig {
+        *self.config.read()
+    }
+
+    /// Sets the peer diversity configuration.
+    pub fn set_config(&self, config: PeerDiversityConfig) {
+        *self.config.write() = config;
+    }
+
+    /// Returns up to `num` of the given candidate peers to dial, sampled uniformly at random.
+    /// A candidate is skipped if its subnet is already at the limit, counting the given peers
+    /// (connected or connecting) and the previously selected candidates.
+    pub fn select_candidates<R: Rng>(
+        &self,
+        candidates: impl IntoIterator<Item = SocketAddr>,
+        peers: impl IntoIterator<Item = SocketAddr>,
+        num: usize,
+        rng: &mut R,
+    ) -> Vec<SocketAddr> {
+        let mut candidates: Vec<_> = candidates.into_iter().collect();
+        candidates.shuffle(rng);
+
+        let max_peers_per_subnet = match self.config().max_peers_per_subnet {
+            Some(max_peers_per_subnet) => max_peers_per_subnet,
+            None => return candidates.into_iter().take(num).collect(),
+        };
+
+        // Count the peers in each subnet.
+        let mut num_peers = IndexMap::<Subnet, usize>::new();
+        for peer_ip in peers {
+            *num_peers.entry(Subnet::from(peer_ip.ip())).or_default() += 1;
+        }
+
+        let mut selected = Vec::with_capacity(num);
+        for candidate in candidates {
+            if selected.len() >= num {
+                break;
+            }
+            let count = num_peers.entry(Subnet::from(candidate.ip())).or_default();
+            if *count < max_peers_per_subnet {
+                *count += 1;
+                selected.push(candidate);
+            }
+        }
+        selected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::TestRng;
+
+    use std::net::Ipv6Addr;
+
+    #[test]
+    fn test_subnet() {
+        let ip = |a, b, c, d| IpAddr::from([a, b, c, d]);
+        assert_eq!(Subnet::from(ip(10, 1, 2, 3)), Subnet::from(ip(10, 1, 200, 4)));
+        assert_ne!(Subnet::from(ip(10, 1, 2, 3)), Subnet::from(ip(10, 2, 2, 3)));
+
+        // Ensure an IPv4-mapped IPv6 address is in the subnet of its IPv4 address.
+        let mapped = IpAddr::V6(Ipv6Addr::from([0, 0, 0, 0, 0, 0xffff, 0x0a01, 0x0203]));
+        assert_eq!(Subnet::from(mapped), Subnet::from(ip(10, 1, 2, 3)));
+    }
+
+    #[test]
+    fn test_select_candidates() {
+        let rng = &mut TestRng::default();
+        let diversity = PeerDiversity::default();
+
+        // Sample 10 candidates in the subnet of a connected peer, and 10 candidates in distinct subnets.
+        let peers = vec![SocketAddr::from(([10, 1, 0, 1], 4133))];
+        let candidates: Vec<_> = (0..10u8)
+            .map(|i| SocketAddr::from(([10, 1, 1, i], 4133)))
+            .chain((0..10u8).map(|i| SocketAddr::from(([20, i, 0, 1], 4133))))
+            .collect();
+
+        // By default, the candidates are unconstrained.
+        let selected = diversity.select_candidates(candidates.clone(), peers.clone(), 20, rng);
+        assert_eq!(selected.len(), 20);
+
+        // With a limit of 2 peers per subnet, only one more candidate is selected in the subnet of the connected peer.
+        diversity.set_config(PeerDiversityConfig { max_peers_per_subnet: Some(2) });
+        let selected = diversity.select_candidates(candidates.clone(), peers, 20, rng);
+        assert_eq!(selected.len(), 11);
+        assert_eq!(selected.iter().filter(|ip| Subnet::from(ip.ip()) == Subnet::V4([10, 1])).count(), 1);
+
+        // Ensure the number of selected candidates is bounded.
+        assert_eq!(diversity.select_candidates(candidates, vec![], 5, rng).len(), 5);
+    }
+}
@@ -0,0 +1,246 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkos_node_messages::BeaconPropose;
+use snarkvm::prelude::{Block, Network};
+
+use anyhow::{bail, Result};
+use indexmap::IndexSet;
+use parking_lot::{Mutex, RwLock};
+use std::{
+    collections::VecDeque,
+    net::SocketAddr,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use tokio::sync::Notify;
+
+/// The maximum number of proposals awaiting the full verification of their blocks, after which the oldest are dropped.
+pub const MAX_DEFERRED_BLOCKS: usize = 64; // 64 blocks
+/// The maximum number of recently seen proposals, by block hash.
+pub const MAX_SEEN_PROPOSALS: usize = 1024; // 1024 blocks
+
+/// The validation a validator performs on a beacon proposal, before relaying it to the other validators.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum RelayLevel {
+    /// The proposal is relayed once its block is fully verified.
+    #[default]
+    Full,
+    /// The proposal is relayed once its block header and signature are checked,
+    /// and its block is fully verified in the background.
+    Header,
+}
+
+impl RelayLevel {
+    /// Returns `true` if the proposal is relayed before the full verification of its block.
+    pub const fn is_deferred(&self) -> bool {
+        matches!(self, Self::Header)
+    }
+}
+
+impl core::str::FromStr for RelayLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "full" => Ok(Self::Full),
+            "header" => Ok(Self::Header),
+            _ => bail!("Unknown relay level '{s}' (expected 'full' or 'header')"),
+        }
+    }
+}
+
+impl core::fmt::Display for RelayLevel {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", match self {
+            Self::Full => "full",
+            Self::Header => "header",
+        })
+    }
+}
+
+/// A beacon proposal that passed the header checks, and awaits the full verification of its block.
+pub struct DeferredBlock<N: Network> {
+    /// The IP of the peer that sent the proposal.
+    pub peer_ip: SocketAddr,
+    /// The proposal as it was received, so it is relayed without serializing the block again.
+    pub proposal: BeaconPropose<N>,
+    /// The block of the proposal.
+    pub block: Block<N>,
+    /// Whether the proposal was relayed before the full verification of its block.
+    pub is_relayed: bool,
+}
+
+/// The relay of the beacon proposals between validators, which queues the proposals that passed the header checks
+/// for the full verification of their blocks in the background, so the peers that sent an invalid block are
+/// penalized, even if the proposal was already relayed.
+pub struct Relay<N: Network> {
+    /// The validation performed before a proposal is relayed.
+    level: RwLock<RelayLevel>,
+    /// The hashes of the recently seen proposals, in order of arrival, so each proposal is handled once.
+    seen: Mutex<IndexSet<N::BlockHash>>,
+    /// The proposals awaiting the full verification of their blocks, in order of arrival.
+    deferred: Mutex<VecDeque<DeferredBlock<N>>>,
+    /// The notification of a newly deferred proposal.
+    notify: Notify,
+    /// The number of deferred proposals that were dropped, as the queue was full.
+    num_dropped: AtomicU64,
+    /// The number of proposals whose blocks failed the full verification.
+    num_invalid: AtomicU64,
+}
+
+impl<N: Network> Default for Relay<N> {
+    fn default() -> Self {
+        Self {
+            level: Default::default(),
+            seen: Default::default(),
+            deferred: Default::default(),
+            notify: Default::default(),
+            num_dropped: Default::default(),
+            num_invalid: Default::default(),
+        }
+    }
+}
+
+impl<N: Network> Relay<N> {
+    /// Returns the validation performed before a proposal is relayed.
+    pub fn level(&self) -> RelayLevel {
+        *self.level.read()
+    }
+
+    /// Sets the validation performed before a proposal is relayed.
+    pub fn set_level(&self, level: RelayLevel) {
+        *self.level.write() = level;
+    }
+
+    /// Returns the number of proposals awaiting the full verification of their blocks.
+    pub fn num_deferred(&self) -> usize {
+        self.deferred.lock().len()
+    }
+
+    /// Returns the number of deferred proposals that were dropped, as the queue was full.
+    pub fn num_dropped(&self) -> u64 {
+        self.num_dropped.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of proposals whose blocks failed the full verification.
+    pub fn num_invalid(&self) -> u64 {
+        self.num_invalid.load(Ordering::Relaxed)
+    }
+
+    /// Inserts the given block hash into the recently seen proposals, and returns `true` if it was not yet seen.
+    pub fn insert_seen(&self, hash: N::BlockHash) -> bool {
+        let mut seen = self.seen.lock();
+        if !seen.insert(hash) {
+            return false;
+        }
+        if seen.len() > MAX_SEEN_PROPOSALS {
+            seen.shift_remove_index(0);
+        }
+        true
+    }
+
+    /// Queues the given proposal for the full verification of its block, and drops the oldest one if the queue is full.
+    pub fn defer(&self, deferred: DeferredBlock<N>) {
+        {
+            let mut queue = self.deferred.lock();
+            queue.push_back(deferred);
+            if queue.len() > MAX_DEFERRED_BLOCKS {
+                if let Some(dropped) = queue.pop_front() {
+                    self.num_dropped.fetch_add(1, Ordering::Relaxed);
+                    trace!("Dropped the deferred proposal for block {}, as the queue is full", dropped.block.height());
+                }
+            }
+        }
+        self.notify.notify_one();
+    }
+
+    /// Removes and returns the oldest proposal awaiting the full verification of its block, if any.
+    pub fn pop(&self) -> Option<DeferredBlock<N>> {
+        self.deferred.lock().pop_front()
+    }
+
+    /// Waits until a proposal is deferred.
+    pub async fn notified(&self) {
+        self.notify.notified().await
+    }
+
+    /// Registers a proposal whose block failed the full verification.
+    pub fn register_invalid(&self) {
+        self.num_invalid.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkos_node_messages::Data;
+    use snarkvm::prelude::{Field, FromBytes};
+
+    type CurrentNetwork = snarkvm::prelude::Testnet3;
+
+    #[test]
+    fn test_relay_level() {
+        assert_eq!("full".parse::<RelayLevel>().unwrap(), RelayLevel::Full);
+        assert_eq!("header".parse::<RelayLevel>().unwrap(), RelayLevel::Header);
+        assert!("none".parse::<RelayLevel>().is_err());
+        assert_eq!(RelayLevel::default().to_string(), "full");
+        assert!(!RelayLevel::Full.is_deferred());
+        assert!(RelayLevel::Header.is_deferred());
+    }
+
+    #[test]
+    fn test_seen_proposals() {
+        let relay = Relay::<CurrentNetwork>::default();
+        let hash = |i: u32| Field::<CurrentNetwork>::from_u32(i).into();
+
+        // Ensure each proposal is seen once.
+        assert!(relay.insert_seen(hash(0)));
+        assert!(!relay.insert_seen(hash(0)));
+
+        // Ensure the oldest proposals are forgotten beyond the maximum.
+        for i in 1..=MAX_SEEN_PROPOSALS as u32 {
+            assert!(relay.insert_seen(hash(i)));
+        }
+        assert!(relay.insert_seen(hash(0)));
+        assert!(!relay.insert_seen(hash(MAX_SEEN_PROPOSALS as u32)));
+    }
+
+    #[tokio::test]
+    async fn test_deferred_blocks() {
+        let relay = Relay::<CurrentNetwork>::default();
+        let block = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let deferred = |port: u16| DeferredBlock {
+            peer_ip: SocketAddr::from(([127, 0, 0, 1], port)),
+            proposal: BeaconPropose::new(block.round(), block.height(), block.hash(), Data::Object(block.clone())),
+            block: block.clone(),
+            is_relayed: false,
+        };
+
+        // Ensure a deferred proposal wakes the waiter.
+        relay.defer(deferred(1));
+        relay.notified().await;
+        assert_eq!(relay.pop().unwrap().peer_ip.port(), 1);
+        assert!(relay.pop().is_none());
+
+        // Ensure the oldest proposals are dropped beyond the maximum.
+        for port in 1..=MAX_DEFERRED_BLOCKS as u16 + 2 {
+            relay.defer(deferred(port));
+        }
+        assert_eq!(relay.num_deferred(), MAX_DEFERRED_BLOCKS);
+        assert_eq!(relay.num_dropped(), 2);
+        assert_eq!(relay.pop().unwrap().peer_ip.port(), 3);
+    }
+}
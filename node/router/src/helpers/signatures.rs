@@ -0,0 +1,153 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{ensure, Result};
+use parking_lot::RwLock;
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
+use std::sync::Arc;
+use tokio::sync::oneshot;
+
+/// The default number of threads that verify signatures.
+pub const DEFAULT_SIGNATURE_THREADS: usize = 2;
+/// The maximum number of threads that verify signatures.
+pub const MAX_SIGNATURE_THREADS: usize = 64;
+/// The minimum number of items of a batch that are checked in sequence by one thread.
+const MIN_BATCH_CHUNK_SIZE: usize = 8;
+
+/// The signature verifier runs the signature checks of the inbound messages on a dedicated thread pool,
+/// so that a large batch of signatures does not starve the async executor.
+#[derive(Debug, Default)]
+pub struct SignatureVerifier {
+    /// The number of threads, or the default if `None`.
+    num_threads: RwLock<Option<usize>>,
+    /// The thread pool, which is built on first use.
+    pool: RwLock<Option<Arc<ThreadPool>>>,
+}
+
+impl SignatureVerifier {
+    /// Returns the number of threads that verify signatures.
+    pub fn num_threads(&self) -> usize {
+        self.num_threads.read().unwrap_or(DEFAULT_SIGNATURE_THREADS)
+    }
+
+    /// Sets the number of threads that verify signatures. The thread pool is rebuilt on next use.
+    pub fn set_num_threads(&self, num_threads: usize) -> Result<()> {
+        ensure!(
+            (1..=MAX_SIGNATURE_THREADS).contains(&num_threads),
+            "The number of signature threads must be between 1 and {MAX_SIGNATURE_THREADS}"
+        );
+        *self.num_threads.write() = Some(num_threads);
+        *self.pool.write() = None;
+        Ok(())
+    }
+
+    /// Returns the thread pool, building it if necessary.
+    fn pool(&self) -> Option<Arc<ThreadPool>> {
+        if let Some(pool) = &*self.pool.read() {
+            return Some(pool.clone());
+        }
+        let mut pool = self.pool.write();
+        if pool.is_none() {
+            let builder = ThreadPoolBuilder::new()
+                .num_threads(self.num_threads())
+                .thread_name(|index| format!("signature-verifier-{index}"));
+            match builder.build() {
+                Ok(built) => *pool = Some(Arc::new(built)),
+                Err(error) => error!("Failed to build the signature verifier pool - {error}"),
+            }
+        }
+        pool.clone()
+    }
+
+    /// Runs the given signature check on the thread pool, and returns its result.
+    pub async fn verify(&self, verify: impl FnOnce() -> bool + Send + 'static) -> bool {
+        let pool = match self.pool() {
+            Some(pool) => pool,
+            // If the thread pool is unavailable, verify on the calling thread.
+            None => return verify(),
+        };
+        let (sender, receiver) = oneshot::channel();
+        pool.spawn(move || {
+            let _ = sender.send(verify());
+        });
+        receiver.await.unwrap_or(false)
+    }
+
+    /// Runs the given signature check on the batch as a single job on the thread pool, and returns the batch
+    /// if every item is valid. Otherwise, returns an invalid item, or `None` if the batch could not be verified.
+    ///
+    /// Note: The signatures of this network cannot be aggregated, so the items are checked in parallel chunks of
+    /// at least `MIN_BATCH_CHUNK_SIZE` items, and the batch is rejected as soon as an invalid item is found.
+    pub async fn verify_batch<T: Send + Sync + 'static>(
+        &self,
+        items: Vec<T>,
+        verify: impl Fn(&T) -> bool + Send + Sync + 'static,
+    ) -> Result<Vec<T>, Option<T>> {
+        let check = move |items: Vec<T>| {
+            let invalid = items.par_iter().with_min_len(MIN_BATCH_CHUNK_SIZE).position_any(|item| !verify(item));
+            match invalid {
+                Some(index) => Err(items.into_iter().nth(index)),
+                None => Ok(items),
+            }
+        };
+        let pool = match self.pool() {
+            Some(pool) => pool,
+            // If the thread pool is unavailable, verify on the calling thread.
+            None => return check(items),
+        };
+        let (sender, receiver) = oneshot::channel();
+        pool.spawn(move || {
+            let _ = sender.send(check(items));
+        });
+        receiver.await.unwrap_or(Err(None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_signature_verifier() {
+        let verifier = SignatureVerifier::default();
+        assert_eq!(verifier.num_threads(), DEFAULT_SIGNATURE_THREADS);
+
+        // Ensure an invalid number of threads is rejected.
+        assert!(verifier.set_num_threads(0).is_err());
+        assert!(verifier.set_num_threads(MAX_SIGNATURE_THREADS + 1).is_err());
+
+        // Ensure the checks run on the thread pool.
+        verifier.set_num_threads(4).unwrap();
+        let is_pool_thread = || thread_name().starts_with("signature-verifier-");
+        assert!(verifier.verify(is_pool_thread).await);
+
+        // Ensure a valid batch is returned in order.
+        let batch = verifier.verify_batch((0..100).collect(), |item: &u32| *item < 100).await;
+        assert_eq!(batch.unwrap(), (0..100).collect::<Vec<_>>());
+        assert!(verifier.verify_batch(Vec::<u32>::new(), |_| false).await.unwrap().is_empty());
+
+        // Ensure an invalid item rejects the batch.
+        let batch = verifier.verify_batch((0..100).collect(), |item: &u32| *item != 42).await;
+        assert_eq!(batch.unwrap_err(), Some(42));
+        // Ensure the checks run as a single job on the thread pool.
+        assert!(verifier.verify_batch(vec![(); 100], move |_| is_pool_thread()).await.is_ok());
+    }
+
+    /// Returns the name of the current thread.
+    fn thread_name() -> String {
+        std::thread::current().name().unwrap_or_default().to_string()
+    }
+}
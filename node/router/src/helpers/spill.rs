@@ -0,0 +1,324 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::prelude::{Block, FromBytes, Network, ToBytes};
+
+use anyhow::Result;
+use parking_lot::Mutex;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// The configuration of the spill buffer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpillConfig {
+    /// The maximum number of blocks held in memory, before the highest blocks are spilled to disk.
+    pub max_in_memory: usize,
+    /// The scratch directory for spilled blocks, or `None` to keep every block in memory.
+    pub dir: Option<PathBuf>,
+    /// The maximum number of bytes spilled to disk, before further blocks are kept in memory.
+    pub max_disk_bytes: u64,
+}
+
+impl Default for SpillConfig {
+    /// Returns the default configuration, which keeps every block in memory.
+    fn default() -> Self {
+        Self { max_in_memory: 256, dir: None, max_disk_bytes: 4 * 1024 * 1024 * 1024 }
+    }
+}
+
+/// A buffer of blocks by height, which keeps the lowest blocks in memory and spills the rest
+/// to a bounded scratch directory on disk. The lowest blocks are kept in memory, as they are
+/// the next to be advanced to; the spilled blocks are read back as the buffer is drained.
+///
+/// The buffer only locks its bookkeeping, and reads and writes the spilled blocks without holding the lock.
+/// Each spilled block is written to a file of its own, so that a file is only ever accessed by the caller
+/// that removed its entry from the bookkeeping.
+#[derive(Debug)]
+pub struct SpillBuffer<N: Network> {
+    /// The bookkeeping of the buffer.
+    state: Mutex<SpillState<N>>,
+}
+
+/// The bookkeeping of a spill buffer.
+#[derive(Debug)]
+struct SpillState<N: Network> {
+    /// The spill configuration.
+    config: SpillConfig,
+    /// The blocks held in memory, by height.
+    memory: BTreeMap<u32, Block<N>>,
+    /// The blocks being written to disk, by height.
+    spilling: BTreeMap<u32, Arc<Block<N>>>,
+    /// The path and size in bytes of the blocks spilled to disk, by height.
+    disk: BTreeMap<u32, (PathBuf, u64)>,
+    /// The total number of bytes spilled to disk, or reserved for the blocks being written.
+    disk_bytes: u64,
+    /// The number of files written, which makes the path of each spilled block unique.
+    num_files: u64,
+}
+
+impl<N: Network> Default for SpillBuffer<N> {
+    /// Initializes a new spill buffer, which keeps every block in memory.
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(SpillState {
+                config: Default::default(),
+                memory: Default::default(),
+                spilling: Default::default(),
+                disk: Default::default(),
+                disk_bytes: 0,
+                num_files: 0,
+            }),
+        }
+    }
+}
+
+impl<N: Network> SpillBuffer<N> {
+    /// Sets the spill configuration, and clears any blocks left in the scratch directory by a previous run.
+    /// The spilled blocks of this buffer are read back into memory first, so that none are lost.
+    pub fn set_config(&self, config: SpillConfig) -> Result<()> {
+        // Read the spilled blocks back into memory.
+        let heights: Vec<u32> = self.state.lock().disk.keys().copied().collect();
+        for height in heights {
+            if let Some(block) = self.remove(&height) {
+                self.state.lock().memory.insert(height, block);
+            }
+        }
+        // Clear the scratch directory.
+        if let Some(dir) = &config.dir {
+            if dir.exists() {
+                fs::remove_dir_all(dir)?;
+            }
+            fs::create_dir_all(dir)?;
+        }
+        self.state.lock().config = config;
+        Ok(())
+    }
+
+    /// Returns the number of blocks in the buffer.
+    pub fn len(&self) -> usize {
+        let state = self.state.lock();
+        state.memory.len() + state.spilling.len() + state.disk.len()
+    }
+
+    /// Returns `true` if the buffer is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of blocks spilled to disk.
+    pub fn num_spilled(&self) -> usize {
+        self.state.lock().disk.len()
+    }
+
+    /// Returns the number of bytes spilled to disk.
+    pub fn spilled_bytes(&self) -> u64 {
+        let state = self.state.lock();
+        state.disk.values().map(|(_, size)| size).sum()
+    }
+
+    /// Returns `true` if the buffer contains a block at the given height.
+    pub fn contains_key(&self, height: &u32) -> bool {
+        let state = self.state.lock();
+        state.memory.contains_key(height) || state.spilling.contains_key(height) || state.disk.contains_key(height)
+    }
+
+    /// Returns the block at the given height, if it exists.
+    pub fn get(&self, height: &u32) -> Option<Block<N>> {
+        let path = {
+            let state = self.state.lock();
+            if let Some(block) = state.memory.get(height) {
+                return Some(block.clone());
+            }
+            if let Some(block) = state.spilling.get(height) {
+                return Some(Block::clone(block));
+            }
+            state.disk.get(height)?.0.clone()
+        };
+        // Read the spilled block, which may have been removed in the meantime.
+        read_block(*height, &path)
+    }
+
+    /// Inserts the given block at the given height, and returns the previous block at the height, if any.
+    /// If the memory bound is exceeded, the highest block is spilled to disk before returning.
+    pub fn insert(&self, height: u32, block: Block<N>) -> Option<Block<N>> {
+        let previous = self.remove(&height);
+        // Insert the block, and select the highest block to spill, if the memory bound is exceeded.
+        let spill = {
+            let mut state = self.state.lock();
+            state.memory.insert(height, block);
+            match state.memory.len() > state.config.max_in_memory && state.config.dir.is_some() {
+                true => state.memory.pop_last().map(|(highest, block)| {
+                    let block = Arc::new(block);
+                    state.spilling.insert(highest, block.clone());
+                    (highest, block)
+                }),
+                false => None,
+            }
+        };
+        if let Some((highest, block)) = spill {
+            self.spill(highest, block);
+        }
+        previous
+    }
+
+    /// Removes and returns the block at the given height, if it exists.
+    pub fn remove(&self, height: &u32) -> Option<Block<N>> {
+        let path = {
+            let mut state = self.state.lock();
+            if let Some(block) = state.memory.remove(height) {
+                return Some(block);
+            }
+            // A block being written is removed from the bookkeeping, and its file is removed by the writer.
+            if let Some(block) = state.spilling.remove(height) {
+                return Some(Arc::try_unwrap(block).unwrap_or_else(|block| Block::clone(&block)));
+            }
+            let (path, size) = state.disk.remove(height)?;
+            state.disk_bytes -= size;
+            path
+        };
+        // Read and remove the spilled block, whose file is no longer referenced by the bookkeeping.
+        let block = read_block(*height, &path);
+        let _ = fs::remove_file(path);
+        block
+    }
+
+    /// Writes the given block to disk, or returns it to memory if it is not spilled.
+    fn spill(&self, height: u32, block: Arc<Block<N>>) {
+        // Serialize the block, and reserve its size on disk.
+        let bytes = block.to_bytes_le().ok();
+        let path = {
+            let mut state = self.state.lock();
+            match (&bytes, state.config.dir.clone()) {
+                (Some(bytes), Some(dir)) if state.disk_bytes + bytes.len() as u64 <= state.config.max_disk_bytes => {
+                    state.disk_bytes += bytes.len() as u64;
+                    state.num_files += 1;
+                    Some(dir.join(format!("{height}-{}.block", state.num_files)))
+                }
+                // Keep the block in memory, if it cannot be serialized, or the disk bound would be exceeded.
+                _ => None,
+            }
+        };
+
+        // Write the block to disk.
+        let size = bytes.as_ref().map_or(0, |bytes| bytes.len() as u64);
+        let is_written = match (&path, &bytes) {
+            (Some(path), Some(bytes)) => match fs::write(path, bytes) {
+                Ok(()) => true,
+                Err(error) => {
+                    warn!("Failed to spill block {height} to '{}' - {error}", path.display());
+                    false
+                }
+            },
+            _ => false,
+        };
+
+        // Record the spilled block, unless it was removed while it was being written.
+        let mut state = self.state.lock();
+        if path.is_some() && !is_written {
+            state.disk_bytes -= size;
+        }
+        let is_pending = state.spilling.remove(&height).is_some();
+        match (path, is_written, is_pending) {
+            (Some(path), true, true) => {
+                state.disk.insert(height, (path, size));
+            }
+            (Some(path), true, false) => {
+                state.disk_bytes -= size;
+                drop(state);
+                let _ = fs::remove_file(path);
+            }
+            (_, _, true) => {
+                state.memory.insert(height, Arc::try_unwrap(block).unwrap_or_else(|block| Block::clone(&block)));
+            }
+            (_, _, false) => (),
+        }
+    }
+}
+
+/// Reads the spilled block at the given height from the given path.
+fn read_block<N: Network>(height: u32, path: &Path) -> Option<Block<N>> {
+    match fs::read(path).map_err(anyhow::Error::from).and_then(|bytes| Block::from_bytes_le(&bytes)) {
+        Ok(block) => Some(block),
+        Err(error) => {
+            warn!("Failed to read the spilled block {height} from '{}' - {error}", path.display());
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use snarkvm::prelude::Testnet3;
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_spill_buffer() {
+        let block = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path().join("spill");
+
+        let num_files = || fs::read_dir(&dir).unwrap().count();
+
+        // Initialize a buffer that holds two blocks in memory.
+        let buffer = SpillBuffer::<CurrentNetwork>::default();
+        let config = SpillConfig { max_in_memory: 2, dir: Some(dir.clone()), max_disk_bytes: u64::MAX };
+        buffer.set_config(config).unwrap();
+
+        // Ensure the highest blocks are spilled to disk.
+        for height in 1..=4 {
+            assert!(buffer.insert(height, block.clone()).is_none());
+        }
+        assert_eq!(buffer.len(), 4);
+        assert_eq!(buffer.num_spilled(), 2);
+        assert_eq!(num_files(), 2);
+        assert!(buffer.contains_key(&1) && buffer.contains_key(&4));
+        assert_eq!(buffer.spilled_bytes(), 2 * block.to_bytes_le().unwrap().len() as u64);
+
+        // Ensure the spilled blocks are read back.
+        assert_eq!(buffer.get(&4), Some(block.clone()));
+        assert_eq!(buffer.remove(&4), Some(block.clone()));
+        assert!(!buffer.contains_key(&4));
+        assert_eq!(num_files(), 1);
+        assert_eq!(buffer.num_spilled(), 1);
+
+        // Ensure a block spilled again at the same height is written to a new file, and read back.
+        assert!(buffer.insert(4, block.clone()).is_none());
+        assert_eq!(buffer.num_spilled(), 2);
+        assert_eq!(buffer.insert(4, block.clone()), Some(block.clone()));
+        assert_eq!(buffer.num_spilled(), 2);
+        assert_eq!(num_files(), 2);
+        assert_eq!(buffer.remove(&4), Some(block.clone()));
+
+        // Ensure reconfiguring the buffer keeps its blocks, and clears the scratch directory.
+        fs::write(dir.join("99.block"), b"stale").unwrap();
+        let config = SpillConfig { max_in_memory: 2, dir: Some(dir.clone()), max_disk_bytes: 0 };
+        buffer.set_config(config).unwrap();
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.num_spilled(), 0);
+        assert!(!dir.join("99.block").exists());
+
+        // Ensure the blocks are kept in memory, once the disk bound is reached.
+        assert!(buffer.insert(5, block.clone()).is_none());
+        assert_eq!(buffer.num_spilled(), 0);
+        assert_eq!(buffer.remove(&5), Some(block));
+    }
+}
@@ -14,8 +14,9 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use snarkos_node_messages::BlockLocators;
-use snarkvm::prelude::{Block, Network};
+use crate::{LatencyEstimate, PeerLatencies, SpillBuffer, SpillConfig};
+use snarkos_node_messages::{AncestorRequest, AncestorResponse, BlockCertificate, BlockLocators};
+use snarkvm::prelude::{Block, FromBytes, Network, ToBytes};
 
 use anyhow::{bail, ensure, Result};
 use colored::Colorize;
@@ -23,21 +24,91 @@ use core::hash::Hash;
 use indexmap::{IndexMap, IndexSet};
 use itertools::Itertools;
 use once_cell::sync::OnceCell;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use rand::{prelude::IteratorRandom, CryptoRng, Rng};
-use std::{collections::BTreeMap, net::SocketAddr, time::Instant};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fs::{self, OpenOptions},
+    io::Write,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+use time::OffsetDateTime;
 
 pub const REDUNDANCY_FACTOR: usize = 3;
 pub const EXTRA_REDUNDANCY_FACTOR: usize = REDUNDANCY_FACTOR * 2;
 pub const NUM_SYNC_CANDIDATE_PEERS: usize = REDUNDANCY_FACTOR * 5;
 
+/// The block request timeout, until enough responses of the peer were observed to adapt it.
 pub const BLOCK_REQUEST_TIMEOUT_IN_SECS: u64 = 15; // 15 seconds
 pub const MAX_BLOCK_REQUESTS: usize = 50; // 50 requests
 pub const MAX_BLOCK_REQUEST_TIMEOUTS: usize = 5; // 5 timeouts
 
+pub const MAX_FORK_HISTORY: usize = 1024; // 1024 forks
+pub const MAX_FORK_REPLACED_HASHES: usize = 64; // 64 blocks
+/// The minimum interval between two new forks recorded for the same peer, so a peer cannot flood the fork history.
+pub const FORK_INTERVAL_IN_SECS: i64 = 60; // 60 seconds
+
+/// The ancestor request timeout, until enough responses of the peer were observed to adapt it.
+pub const ANCESTOR_REQUEST_TIMEOUT_IN_SECS: u64 = 10; // 10 seconds
+
+pub const MAX_CERTIFIED_HASHES: usize = 1024; // 1024 blocks
+
 /// A tuple of the block hash (optional), previous block hash (optional), and sync IPs.
 pub type SyncRequest<N> = (Option<<N as Network>::BlockHash>, Option<<N as Network>::BlockHash>, IndexSet<SocketAddr>);
 
+/// A fork observed between the canonical chain of this node and the block locators of a peer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fork<N: Network> {
+    /// The IP of the forked peer.
+    pub peer_ip: SocketAddr,
+    /// The height of the common ancestor.
+    pub ancestor: u32,
+    /// The height of the first diverging block.
+    pub height: u32,
+    /// The canonical block hash at the diverging height.
+    pub canon_hash: N::BlockHash,
+    /// The peer's block hash at the diverging height.
+    pub peer_hash: N::BlockHash,
+    /// The peer's latest block height.
+    pub peer_height: u32,
+    /// The number of blocks the peer would replace on the canonical chain.
+    pub depth: u32,
+    /// The canonical block hashes the peer would replace, from the block above the common ancestor upward,
+    /// bounded by `MAX_FORK_REPLACED_HASHES`.
+    pub replaced: Vec<N::BlockHash>,
+    /// The UTC timestamp at which the fork was observed.
+    pub timestamp: i64,
+}
+
+impl<N: Network> Fork<N> {
+    /// Returns `true` if the given fork is the same divergence of the same peer, regardless of its refinements.
+    fn is_same(&self, other: &Self) -> bool {
+        self.peer_ip == other.peer_ip && self.height == other.height && self.peer_hash == other.peer_hash
+    }
+}
+
+/// The state of a search for the common ancestor with a peer on a fork.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AncestorSearch {
+    /// The highest block height known to be common with the peer.
+    pub common_height: u32,
+    /// The lowest block height known to diverge from the peer.
+    pub fork_height: u32,
+    /// The timestamp of the in-flight ancestor request, if one exists.
+    requested_at: Option<Instant>,
+}
+
+impl AncestorSearch {
+    /// Returns `true` if the common ancestor has been found.
+    pub const fn is_complete(&self) -> bool {
+        self.fork_height <= self.common_height.saturating_add(1)
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct PeerPair(SocketAddr, SocketAddr);
 
@@ -65,6 +136,8 @@ impl Hash for PeerPair {
 /// - When a request is completed, the `requests` map still has the entry, but its `sync_ips` is empty;
 /// - the `request_timestamps` map remains unchanged.
 /// - When a response is removed/completed, the `requests` map and `request_timestamps` map also remove the entry for the request height.
+/// - When a sync IP times out, the `requests` map removes the sync IP from the entry for the request height;
+/// - When every pending sync IP of a request has timed out without a response, the request is timed out;
 /// - When a request is timed out, the `requests`, `request_timestamps`, and `responses` map remove the entry for the request height;
 #[derive(Debug)]
 pub struct Sync<N: Network> {
@@ -84,13 +157,43 @@ pub struct Sync<N: Network> {
     requests: RwLock<BTreeMap<u32, SyncRequest<N>>>,
     /// The map of block height to the received blocks.
     /// Removing an entry from this map must remove the corresponding entry from the requests map.
-    responses: RwLock<BTreeMap<u32, Block<N>>>,
+    /// The highest blocks are spilled to disk, if a scratch directory is configured.
+    /// The buffer locks only its bookkeeping, so the blocks are read and written without holding a lock.
+    responses: SpillBuffer<N>,
     /// The map of block height to the timestamp of the last time the block was requested.
     /// This map is used to determine which requests to remove if they have been pending for too long.
     request_timestamps: RwLock<BTreeMap<u32, Instant>>,
     /// The map of (timed out) peer IPs to their request timestamps.
     /// This map is used to determine which peers to remove if they have timed out too many times.
     request_timeouts: RwLock<IndexMap<SocketAddr, Vec<Instant>>>,
+    /// The response times of the peers, from which their block and ancestor request timeouts are derived.
+    latencies: PeerLatencies,
+    /// The most recently observed forks, in order of observation.
+    forks: RwLock<VecDeque<Fork<N>>>,
+    /// The path of the file the forks are persisted to, if any.
+    forks_path: Mutex<Option<PathBuf>>,
+    /// The number of forks appended to the file since it was last compacted.
+    num_forks_appended: AtomicUsize,
+    /// The total number of forks recorded since startup.
+    num_forks: AtomicU64,
+    /// The map of forked peer IPs to the search for their common ancestor with this node.
+    /// The block locators only bound the fork point, so the search narrows it down with ancestor requests.
+    ancestor_searches: RwLock<IndexMap<SocketAddr, AncestorSearch>>,
+    /// The map of block height to the trusted (weak-subjectivity) checkpoint hash.
+    /// The node never syncs a chain that conflicts with a checkpoint, however long that chain is.
+    trusted_checkpoints: RwLock<BTreeMap<u32, N::BlockHash>>,
+    /// The set of peer IPs whose chains conflict with a trusted checkpoint.
+    /// These peers are never selected as sync peers.
+    conflicting_peers: RwLock<IndexSet<SocketAddr>>,
+    /// The map of trusted checkpoint height to the lowest block response that is verified to lead to the checkpoint,
+    /// and the expected hash of the block below it. The block responses below a checkpoint that the ledger has not
+    /// reached are only advanced to once their chain of previous block hashes leads to the checkpoint hash.
+    checkpoint_anchors: RwLock<BTreeMap<u32, (u32, N::BlockHash)>>,
+    /// The map of block height to the block certificate, as signed by a beacon and received in certificate
+    /// range responses. The certified hashes extend beyond the block locators of the sync peers.
+    certified: RwLock<BTreeMap<u32, BlockCertificate<N>>>,
+    /// The path of the file the certificates are persisted to, if any.
+    certificates_path: Mutex<Option<PathBuf>>,
 }
 
 impl<N: Network> Default for Sync<N> {
@@ -104,6 +207,17 @@ impl<N: Network> Default for Sync<N> {
             responses: Default::default(),
             request_timestamps: Default::default(),
             request_timeouts: Default::default(),
+            latencies: Default::default(),
+            forks: Default::default(),
+            forks_path: Default::default(),
+            num_forks_appended: Default::default(),
+            num_forks: Default::default(),
+            ancestor_searches: Default::default(),
+            trusted_checkpoints: Default::default(),
+            conflicting_peers: Default::default(),
+            checkpoint_anchors: Default::default(),
+            certified: Default::default(),
+            certificates_path: Default::default(),
         }
     }
 }
@@ -119,6 +233,11 @@ impl<N: Network> Sync<N> {
         self.local_ip.set(local_ip).expect("The local IP was set more than once");
     }
 
+    /// Sets the spill configuration of the block responses, and clears the scratch directory.
+    pub fn set_spill_config(&self, config: SpillConfig) -> Result<()> {
+        self.responses.set_config(config)
+    }
+
     /// Returns the latest block height in the sync pool.
     pub fn latest_canon_height(&self) -> u32 {
         self.canon.read().keys().last().copied().unwrap_or(0)
@@ -151,6 +270,15 @@ impl<N: Network> Sync<N> {
         )
     }
 
+    /// Returns the median of the latest heights reported by the peers, if any.
+    /// For an even number of peers, the lower of the two middle heights is returned,
+    /// so that a minority of peers cannot raise the height by reporting blocks that do not exist.
+    pub fn median_peer_height(&self) -> Option<u32> {
+        let heights: Vec<u32> =
+            self.locators.read().values().map(|locators| locators.latest_locator_height()).sorted().collect();
+        heights.get(heights.len().checked_sub(1)? / 2).copied()
+    }
+
     /// Returns the list of peers with their heights, sorted by height (descending).
     pub fn get_peers_by_height(&self) -> Vec<(SocketAddr, u32)> {
         self.locators
@@ -166,6 +294,109 @@ impl<N: Network> Sync<N> {
         self.common_ancestors.read().get(&PeerPair(peer_a, peer_b)).copied()
     }
 
+    /// Returns up to `limit` of the most recently observed forks, from newest to oldest.
+    pub fn get_fork_history(&self, limit: usize) -> Vec<Fork<N>> {
+        self.forks.read().iter().rev().take(limit).cloned().collect()
+    }
+
+    /// Returns the total number of forks recorded since startup.
+    pub fn num_forks(&self) -> u64 {
+        self.num_forks.load(Ordering::Relaxed)
+    }
+
+    /// Loads the fork history from the given file, and persists any new or refined forks to it.
+    pub fn open_fork_history(&self, path: PathBuf) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if path.exists() {
+            let mut forks = self.forks.write();
+            for line in fs::read_to_string(&path)?.lines() {
+                let fork = match serde_json::from_str::<Fork<N>>(line) {
+                    Ok(fork) => fork,
+                    Err(error) => {
+                        warn!("Skipping malformed fork entry in '{}' - {error}", path.display());
+                        continue;
+                    }
+                };
+                // A refined fork supersedes its earlier entry.
+                forks.retain(|other| !other.is_same(&fork));
+                forks.push_back(fork);
+                if forks.len() > MAX_FORK_HISTORY {
+                    forks.pop_front();
+                }
+            }
+        }
+        let mut path_lock = self.forks_path.lock();
+        *path_lock = Some(path);
+        // Rewrite the file with the latest entry of each fork only.
+        self.compact_forks(&mut path_lock)
+    }
+
+    /// Loads the certificates from the given file, and persists the certificates in the sync pool to it.
+    /// The loaded certificates are returned to the caller, which must verify them before inserting them.
+    pub fn open_certificates(&self, path: PathBuf) -> Result<Vec<BlockCertificate<N>>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut certificates = Vec::new();
+        if path.exists() {
+            let bytes = fs::read(&path)?;
+            let mut reader = &bytes[..];
+            while !reader.is_empty() {
+                match BlockCertificate::read_le(&mut reader) {
+                    Ok(certificate) => certificates.push(certificate),
+                    Err(error) => {
+                        warn!("Skipping the malformed certificates in '{}' - {error}", path.display());
+                        break;
+                    }
+                }
+            }
+        }
+        *self.certificates_path.lock() = Some(path);
+        Ok(certificates)
+    }
+
+    /// Returns the ancestor search for the given peer IP, if it exists.
+    pub fn get_ancestor_search(&self, peer_ip: &SocketAddr) -> Option<AncestorSearch> {
+        self.ancestor_searches.read().get(peer_ip).copied()
+    }
+
+    /// Returns the trusted checkpoints, by block height.
+    pub fn get_trusted_checkpoints(&self) -> BTreeMap<u32, N::BlockHash> {
+        self.trusted_checkpoints.read().clone()
+    }
+
+    /// Returns `true` if the chain of the given peer IP conflicts with a trusted checkpoint.
+    pub fn is_conflicting_peer(&self, peer_ip: &SocketAddr) -> bool {
+        self.conflicting_peers.read().contains(peer_ip)
+    }
+
+    /// Returns the certified block hash and previous block hash for the given block height, if they exist.
+    pub fn get_certified_hash(&self, height: u32) -> Option<(N::BlockHash, N::BlockHash)> {
+        self.certified.read().get(&height).map(|certificate| (certificate.block_hash, certificate.previous_hash))
+    }
+
+    /// Returns the round of the highest certified block, if any.
+    pub fn latest_certified_round(&self) -> Option<u64> {
+        self.certified.read().values().last().map(|certificate| certificate.round)
+    }
+
+    /// Returns the estimate of the response time of the given peer, if any response was observed.
+    pub fn get_peer_latency(&self, peer_ip: &SocketAddr) -> Option<LatencyEstimate> {
+        self.latencies.get(peer_ip)
+    }
+
+    /// Returns the block request timeout for the given peer.
+    pub fn block_request_timeout(&self, peer_ip: &SocketAddr) -> Duration {
+        self.latencies.timeout(peer_ip, Duration::from_secs(BLOCK_REQUEST_TIMEOUT_IN_SECS))
+    }
+
+    /// Returns the ancestor request timeout for the given peer.
+    pub fn ancestor_request_timeout(&self, peer_ip: &SocketAddr) -> Duration {
+        self.latencies.timeout(peer_ip, Duration::from_secs(ANCESTOR_REQUEST_TIMEOUT_IN_SECS))
+    }
+
     /// Returns the block request for the given height, if it exists.
     pub fn get_block_request(&self, height: u32) -> Option<SyncRequest<N>> {
         self.requests.read().get(&height).cloned()
@@ -198,6 +429,79 @@ impl<N: Network> Sync<N> {
         Ok(())
     }
 
+    /// Inserts the block hashes of the given certificates, which must be verified by the caller.
+    /// The certified hashes are used as the expected hashes of the block requests at their heights.
+    pub fn insert_certified_hashes(&self, certificates: &[BlockCertificate<N>]) -> Result<()> {
+        // Ensure the certificates do not conflict with the canonical chain, the checkpoints, or each other.
+        for certificate in certificates {
+            let (height, hash) = (certificate.height, certificate.block_hash);
+            if let Some(canon_hash) = self.get_canon_hash(height) {
+                ensure!(canon_hash == hash, "The certificate for block {height} conflicts with canon");
+            }
+            if let Some(checkpoint) = self.trusted_checkpoints.read().get(&height) {
+                ensure!(*checkpoint == hash, "The certificate for block {height} conflicts with the checkpoint");
+            }
+            if let Some((certified_hash, _)) = self.get_certified_hash(height) {
+                ensure!(certified_hash == hash, "The certificate for block {height} conflicts with another");
+            }
+        }
+
+        // Ensure the first certificate beyond the latest canon height links to the canonical tip, or a certified hash.
+        let latest_canon_height = self.latest_canon_height();
+        if let Some(first) = certificates.iter().find(|certificate| certificate.height > latest_canon_height) {
+            let anchor = match first.height == latest_canon_height + 1 {
+                true => self.get_canon_hash(latest_canon_height),
+                false => self.get_certified_hash(first.height - 1).map(|(hash, _)| hash),
+            };
+            ensure!(anchor == Some(first.previous_hash), "The certificate for block {} is not anchored", first.height);
+        }
+
+        // Insert the certified hashes, beyond the latest canon height.
+        let mut certified = self.certified.write();
+        for certificate in certificates.iter().filter(|certificate| certificate.height > latest_canon_height) {
+            certified.insert(certificate.height, certificate.clone());
+        }
+        // Remove the certified hashes that are canon, and the highest hashes beyond the maximum.
+        certified.retain(|height, _| *height > latest_canon_height);
+        while certified.len() > MAX_CERTIFIED_HASHES {
+            certified.pop_last();
+        }
+        // Persist the certificates, so they are restored after a restart.
+        if let Err(error) = self.persist_certificates(&certified) {
+            warn!("Failed to persist the certificates - {error}");
+        }
+        Ok(())
+    }
+
+    /// Rewrites the certificates file with the given certificates, atomically, if a file is set.
+    fn persist_certificates(&self, certified: &BTreeMap<u32, BlockCertificate<N>>) -> Result<()> {
+        let path = self.certificates_path.lock();
+        let path = match &*path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let mut contents = Vec::new();
+        for certificate in certified.values() {
+            certificate.write_le(&mut contents)?;
+        }
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, contents)?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    /// Sets the trusted checkpoints, after checking that they do not conflict with the canonical chain.
+    /// The node refuses to sync a chain that conflicts with a checkpoint, and warns about the peers on such a chain.
+    pub fn set_trusted_checkpoints(&self, checkpoints: BTreeMap<u32, N::BlockHash>) -> Result<()> {
+        for (height, hash) in &checkpoints {
+            if let Some(canon_hash) = self.get_canon_hash(*height) {
+                ensure!(canon_hash == *hash, "The checkpoint at block {height} conflicts with the ledger");
+            }
+        }
+        *self.trusted_checkpoints.write() = checkpoints;
+        Ok(())
+    }
+
     /// Returns the sync peers with their latest heights, and their minimum common ancestor, if the node can sync.
     /// This function returns peers that are consistent with each other, and have a block height
     /// that is greater than the canon height of this node.
@@ -254,19 +558,21 @@ impl<N: Network> Sync<N> {
             return Err(error);
         }
 
-        // Remove the peer IP from the request entry.
+        // Remove the peer IP from the request entry, and record the response time of the peer.
         if let Some((_, _, sync_ips)) = self.requests.write().get_mut(&height) {
-            sync_ips.remove(&peer_ip);
+            if sync_ips.remove(&peer_ip) {
+                if let Some(timestamp) = self.request_timestamps.read().get(&height) {
+                    self.latencies.insert_sample(peer_ip, timestamp.elapsed());
+                }
+            }
         }
 
-        // Acquire the write lock on the responses map.
-        let mut responses = self.responses.write();
         // Insert the candidate block into the responses map.
-        if let Some(existing_block) = responses.insert(height, block.clone()) {
+        if let Some(existing_block) = self.responses.insert(height, block.clone()) {
             // If the candidate block was already present, ensure it is the same block.
             if block != existing_block {
                 // Remove the candidate block.
-                responses.remove(&height);
+                self.responses.remove(&height);
                 // Remove all block requests to the peer.
                 self.remove_block_requests_to_peer(&peer_ip);
                 bail!("Candidate block {height} from '{peer_ip}' is malformed");
@@ -292,14 +598,28 @@ impl<N: Network> Sync<N> {
 
         // Compute the common ancestor with this node.
         let mut ancestor = 0;
+        let mut fork_height = None;
         for (height, hash) in locators.clone().into_iter() {
             if let Some(canon_hash) = self.get_canon_hash(height) {
                 match canon_hash == hash {
                     true => ancestor = height,
-                    false => break, // fork
+                    false => {
+                        // Record the fork.
+                        self.insert_fork(peer_ip, ancestor, height, canon_hash, hash, locators.latest_locator_height());
+                        fork_height = Some(height);
+                        break;
+                    }
                 }
             }
         }
+        // Ensure the peer's chain does not conflict with the trusted checkpoints.
+        if let Some(height) = self.find_conflicting_checkpoint(&locators, fork_height) {
+            if self.conflicting_peers.write().insert(peer_ip) {
+                warn!("Peer '{peer_ip}' is on a chain that conflicts with the checkpoint at block {height}");
+            }
+        }
+        // Refine the common ancestor with the ancestor search for the peer.
+        let ancestor = self.update_ancestor_search(peer_ip, ancestor, fork_height);
         // Update the common ancestor entry for this node.
         self.common_ancestors.write().insert(PeerPair(self.local_ip(), peer_ip), ancestor);
 
@@ -326,6 +646,231 @@ impl<N: Network> Sync<N> {
         Ok(())
     }
 
+    /// Starts, continues, or clears the ancestor search for the given peer, and returns the best known common ancestor.
+    /// An existing search is kept as long as it lies within the bounds given by the peer's block locators.
+    fn update_ancestor_search(&self, peer_ip: SocketAddr, ancestor: u32, fork_height: Option<u32>) -> u32 {
+        let mut searches = self.ancestor_searches.write();
+        match fork_height {
+            Some(fork_height) => match searches.get(&peer_ip) {
+                Some(search) if ancestor <= search.common_height && search.fork_height <= fork_height => {
+                    search.common_height
+                }
+                _ => {
+                    let search = AncestorSearch { common_height: ancestor, fork_height, requested_at: None };
+                    searches.insert(peer_ip, search);
+                    ancestor
+                }
+            },
+            None => {
+                searches.remove(&peer_ip);
+                ancestor
+            }
+        }
+    }
+
+    /// Returns the next ancestor request for the given peer, if the search is incomplete
+    /// and there is no ancestor request in flight.
+    pub fn next_ancestor_request(&self, peer_ip: &SocketAddr) -> Option<AncestorRequest> {
+        let mut searches = self.ancestor_searches.write();
+        let search = searches.get_mut(peer_ip)?;
+        // Skip if the common ancestor has been found.
+        if search.is_complete() {
+            return None;
+        }
+        // Skip if an ancestor request is in flight.
+        if let Some(requested_at) = search.requested_at {
+            if requested_at.elapsed() < self.ancestor_request_timeout(peer_ip) {
+                return None;
+            }
+        }
+        search.requested_at = Some(Instant::now());
+        Some(AncestorRequest { start_height: search.common_height, end_height: search.fork_height })
+    }
+
+    /// Narrows down the ancestor search for the given peer with its ancestor response, and returns
+    /// the next ancestor request, if the common ancestor has not been found yet.
+    /// The given `canon_hash` function returns the canonical block hash at the given height.
+    pub fn process_ancestor_response(
+        &self,
+        peer_ip: SocketAddr,
+        response: AncestorResponse<N>,
+        canon_hash: impl Fn(u32) -> Option<N::BlockHash>,
+    ) -> Result<Option<AncestorRequest>> {
+        let AncestorResponse { request, hashes } = response;
+
+        let mut searches = self.ancestor_searches.write();
+        // Ensure the response matches the in-flight ancestor request.
+        let (search, requested_at) = match searches.get_mut(&peer_ip) {
+            Some(search) => match search.requested_at {
+                Some(requested_at) => (search, requested_at),
+                None => bail!("Received an unexpected ancestor response from '{peer_ip}'"),
+            },
+            None => bail!("Received an unexpected ancestor response from '{peer_ip}'"),
+        };
+        if request.start_height != search.common_height || request.end_height != search.fork_height {
+            let (start, end) = (request.start_height, request.end_height);
+            bail!("Received an ancestor response from '{peer_ip}' for a stale range ({start}..={end})")
+        }
+        // Ensure the hashes are for the requested heights.
+        if !hashes.iter().map(|(height, _)| *height).eq(request.heights()) {
+            bail!("Received an ancestor response from '{peer_ip}' with unexpected heights")
+        }
+        // Record the response time of the peer.
+        self.latencies.insert_sample(peer_ip, requested_at.elapsed());
+
+        // Find the highest common height, and the lowest diverging height above it.
+        let mut common_height = None;
+        let mut fork_height = search.fork_height;
+        for (height, hash) in hashes {
+            let expected_hash = match canon_hash(height) {
+                Some(expected_hash) => expected_hash,
+                None => bail!("Missing the canonical block hash at block {height}"),
+            };
+            match expected_hash == hash {
+                true => {
+                    common_height = Some(height);
+                    break;
+                }
+                false => fork_height = height,
+            }
+        }
+
+        // If the peer no longer agrees on the start of the range, restart from its next block locators.
+        let common_height = match common_height {
+            Some(common_height) => common_height,
+            None => {
+                searches.remove(&peer_ip);
+                let start = request.start_height;
+                debug!("Peer '{peer_ip}' reorganized below block {start}, restarting the ancestor search");
+                return Ok(None);
+            }
+        };
+
+        *search = AncestorSearch { common_height, fork_height, requested_at: None };
+        if !search.is_complete() {
+            // Mark the next ancestor request as in flight, as the caller sends it.
+            search.requested_at = Some(Instant::now());
+            return Ok(Some(AncestorRequest { start_height: common_height, end_height: fork_height }));
+        }
+        drop(searches);
+
+        // Update the common ancestor entry for this node.
+        self.common_ancestors.write().insert(PeerPair(self.local_ip(), peer_ip), common_height);
+        // Update the latest fork record for the peer.
+        let depth = self.latest_canon_height().saturating_sub(common_height);
+        let replaced = self.get_replaced_hashes(common_height);
+        let refined = match self.forks.write().iter_mut().rev().find(|fork| fork.peer_ip == peer_ip) {
+            Some(fork) => {
+                fork.ancestor = common_height;
+                fork.depth = depth;
+                fork.replaced = replaced;
+                Some(fork.clone())
+            }
+            None => None,
+        };
+        if let Some(fork) = refined {
+            self.persist_fork(&fork);
+        }
+        debug!("Found the common ancestor with '{peer_ip}' at block {common_height} (depth {depth})");
+        Ok(None)
+    }
+
+    /// Records a fork observed with the given peer, if it has not already been recorded,
+    /// and no other fork was recorded for the peer within the last `FORK_INTERVAL_IN_SECS`.
+    fn insert_fork(
+        &self,
+        peer_ip: SocketAddr,
+        ancestor: u32,
+        height: u32,
+        canon_hash: N::BlockHash,
+        peer_hash: N::BlockHash,
+        peer_height: u32,
+    ) {
+        // Compute the number of canonical blocks the peer would replace, and their hashes.
+        let depth = self.latest_canon_height().saturating_sub(ancestor);
+        let replaced = self.get_replaced_hashes(ancestor);
+        let fork = Fork {
+            peer_ip,
+            ancestor,
+            height,
+            canon_hash,
+            peer_hash,
+            peer_height,
+            depth,
+            replaced,
+            timestamp: OffsetDateTime::now_utc().unix_timestamp(),
+        };
+
+        let mut forks = self.forks.write();
+        // Skip if the fork was already recorded for this peer.
+        if forks.iter().any(|other| other.is_same(&fork)) {
+            return;
+        }
+        // Skip if another fork was recorded for this peer too recently.
+        let latest = forks.iter().rev().find(|other| other.peer_ip == peer_ip);
+        if latest.map_or(false, |other| fork.timestamp.saturating_sub(other.timestamp) < FORK_INTERVAL_IN_SECS) {
+            trace!("Skipping the fork of '{peer_ip}' at block {height}, as another fork was recorded recently");
+            return;
+        }
+        debug!("Peer '{peer_ip}' is on a fork at block {height} (common ancestor {ancestor}, depth {depth})");
+        // Bound the fork history.
+        if forks.len() >= MAX_FORK_HISTORY {
+            forks.pop_front();
+        }
+        forks.push_back(fork.clone());
+        drop(forks);
+
+        self.num_forks.fetch_add(1, Ordering::Relaxed);
+        self.persist_fork(&fork);
+    }
+
+    /// Returns the canonical block hashes above the given common ancestor, bounded by `MAX_FORK_REPLACED_HASHES`.
+    fn get_replaced_hashes(&self, ancestor: u32) -> Vec<N::BlockHash> {
+        let start = ancestor.saturating_add(1);
+        self.canon.read().range(start..).take(MAX_FORK_REPLACED_HASHES).map(|(_, hash)| *hash).collect()
+    }
+
+    /// Appends the given fork to the fork history file, if one is set, and compacts the file once it has
+    /// grown by the maximum number of forks.
+    fn persist_fork(&self, fork: &Fork<N>) {
+        let mut path = self.forks_path.lock();
+        let result = match &*path {
+            Some(path) => serde_json::to_vec(fork).map_err(anyhow::Error::from).and_then(|mut line| {
+                line.push(b'\n');
+                OpenOptions::new().create(true).append(true).open(path)?.write_all(&line)?;
+                Ok(())
+            }),
+            None => return,
+        };
+        let result = match result {
+            Ok(()) if self.num_forks_appended.fetch_add(1, Ordering::Relaxed) + 1 >= MAX_FORK_HISTORY => {
+                self.compact_forks(&mut path)
+            }
+            result => result,
+        };
+        if let Err(error) = result {
+            warn!("Failed to persist the fork history - {error}");
+        }
+    }
+
+    /// Rewrites the fork history file with the forks in memory, atomically.
+    fn compact_forks(&self, path: &mut Option<PathBuf>) -> Result<()> {
+        let path = match path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let mut contents = Vec::new();
+        for fork in self.forks.read().iter() {
+            serde_json::to_writer(&mut contents, fork)?;
+            contents.push(b'\n');
+        }
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, contents)?;
+        fs::rename(&temp_path, path)?;
+        self.num_forks_appended.store(0, Ordering::Relaxed);
+        Ok(())
+    }
+
     /// Removes the peer from the sync pool, if they exist.
     pub fn remove_peer(&self, peer_ip: &SocketAddr) {
         // Remove the locators entry for the given peer IP.
@@ -334,11 +879,17 @@ impl<N: Network> Sync<N> {
         self.remove_block_requests_to_peer(peer_ip);
         // Remove the timeouts for the peer.
         self.request_timeouts.write().remove(peer_ip);
+        // Remove the response times of the peer.
+        self.latencies.remove(peer_ip);
+        // Remove the ancestor search for the peer.
+        self.ancestor_searches.write().remove(peer_ip);
+        // Remove the checkpoint conflict of the peer.
+        self.conflicting_peers.write().remove(peer_ip);
     }
 
     /// Removes the block request for the given peer IP, if it exists.
     pub fn remove_block_request_to_peer(&self, peer_ip: &SocketAddr, height: u32) {
-        let mut can_revoke = self.responses.read().get(&height).is_none();
+        let mut can_revoke = !self.responses.contains_key(&height);
 
         // Remove the peer IP from the request entry. If the request entry is now empty,
         // and the response entry for this height is also empty, then remove the request entry altogether.
@@ -357,15 +908,13 @@ impl<N: Network> Sync<N> {
     pub fn remove_block_requests_to_peer(&self, peer_ip: &SocketAddr) {
         // Acquire the write lock on the requests map.
         let mut requests = self.requests.write();
-        // Acquire the read lock on the responses map.
-        let responses = self.responses.read();
 
         // Remove the peer IP from the requests map. If any request entry is now empty,
         // and its corresponding response entry is also empty, then remove that request entry altogether.
         requests.retain(|height, (_, _, peer_ips)| {
             peer_ips.remove(peer_ip);
 
-            let retain = !peer_ips.is_empty() || responses.get(height).is_some();
+            let retain = !peer_ips.is_empty() || self.responses.contains_key(height);
             if !retain {
                 self.request_timestamps.write().remove(height);
             }
@@ -378,12 +927,16 @@ impl<N: Network> Sync<N> {
         // Remove the request entry for the given height.
         self.requests.write().remove(&height);
         // Remove the response entry for the given height.
-        self.responses.write().remove(&height);
+        self.responses.remove(&height);
         // Remove the request timestamp entry for the given height.
         self.request_timestamps.write().remove(&height);
+        // Reset the checkpoint anchors, as the response may have been verified to lead to a checkpoint.
+        self.checkpoint_anchors.write().clear();
     }
 
     /// Removes and returns the block response for the given height, if the request is complete.
+    /// If the block is below a trusted checkpoint that the ledger has not reached, it is only returned once
+    /// the block responses up to the checkpoint lead to the checkpoint hash.
     pub fn remove_block_response(&self, height: u32) -> Option<Block<N>> {
         // Determine if the request is complete.
         let is_request_complete =
@@ -393,10 +946,20 @@ impl<N: Network> Sync<N> {
         if !is_request_complete {
             return None;
         }
+
+        // Ensure the block leads to the next trusted checkpoint, before it is advanced to.
+        match self.leads_to_checkpoint(height) {
+            Ok(true) => (),
+            Ok(false) => return None,
+            Err(error) => {
+                warn!("{error}");
+                return None;
+            }
+        }
         // Remove the request entry for the given height.
         self.requests.write().remove(&height);
         // Remove the response entry for the given height.
-        self.responses.write().remove(&height)
+        self.responses.remove(&height)
     }
 }
 
@@ -412,7 +975,7 @@ impl<N: Network> Sync<N> {
             bail!("Failed to add block request, as block {height} exists in the requests map");
         }
         // Ensure the block height is not already responded.
-        if self.responses.read().contains_key(&height) {
+        if self.responses.contains_key(&height) {
             bail!("Failed to add block request, as block {height} exists in the responses map");
         }
         // Ensure the block height is not already requested.
@@ -422,11 +985,86 @@ impl<N: Network> Sync<N> {
         Ok(())
     }
 
+    /// Returns the height of the trusted checkpoint that the given peer's chain conflicts with, if one exists.
+    /// The chain conflicts if one of its block locators differs from a checkpoint, or if it forks from
+    /// the canonical chain at or below a checkpoint that the canonical chain has already passed.
+    fn find_conflicting_checkpoint(&self, locators: &BlockLocators<N>, fork_height: Option<u32>) -> Option<u32> {
+        let checkpoints = self.trusted_checkpoints.read();
+        // Check the block locators against the checkpoints.
+        for (height, hash) in locators.clone().into_iter() {
+            if checkpoints.get(&height).map_or(false, |checkpoint| *checkpoint != hash) {
+                return Some(height);
+            }
+        }
+        // Check that syncing the fork would not reorganize the canonical chain across a checkpoint.
+        match fork_height {
+            Some(fork_height) if fork_height <= self.latest_canon_height() => {
+                checkpoints.range(fork_height..=self.latest_canon_height()).next().map(|(height, _)| *height)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the block response at the given height leads to the next trusted checkpoint that the ledger
+    /// has not reached, following the previous block hashes of the block responses down from the checkpoint.
+    /// Returns `false` if a block response on the way is still missing, and `true` if there is no such checkpoint.
+    /// On a block response that does not lead to the checkpoint, the block requests up to the checkpoint are removed.
+    fn leads_to_checkpoint(&self, height: u32) -> Result<bool> {
+        // Retrieve the next checkpoint at or above the block, that the ledger has not reached.
+        let (checkpoint_height, checkpoint_hash) = match self.next_pending_checkpoint(height) {
+            Some(checkpoint) => checkpoint,
+            None => return Ok(true),
+        };
+
+        // Resume from the lowest block response that was already verified to lead to the checkpoint.
+        let (mut lowest, mut expected_hash) = match self.checkpoint_anchors.read().get(&checkpoint_height) {
+            Some((lowest, expected_hash)) => (*lowest, *expected_hash),
+            None => (checkpoint_height + 1, checkpoint_hash),
+        };
+        while lowest > height {
+            let block = match self.responses.get(&(lowest - 1)) {
+                Some(block) => block,
+                None => break,
+            };
+            if block.hash() != expected_hash {
+                // Remove the block requests up to the checkpoint, so that they are requested again.
+                for height in height..=checkpoint_height {
+                    self.remove_block_request(height);
+                }
+                bail!("Block {} does not lead to the checkpoint at block {checkpoint_height}", block.height())
+            }
+            lowest -= 1;
+            expected_hash = block.previous_hash();
+        }
+        self.checkpoint_anchors.write().insert(checkpoint_height, (lowest, expected_hash));
+        Ok(lowest <= height)
+    }
+
+    /// Returns the lowest trusted checkpoint at or above the given height, if the ledger has not reached it.
+    fn next_pending_checkpoint(&self, height: u32) -> Option<(u32, N::BlockHash)> {
+        let latest_canon_height = self.latest_canon_height();
+        self.trusted_checkpoints
+            .read()
+            .range(height.max(latest_canon_height.saturating_add(1))..)
+            .next()
+            .map(|(height, hash)| (*height, *hash))
+    }
+
     /// Checks the given block (response) from a peer against the expected block hash and previous block hash.
     fn check_block_response(&self, peer_ip: &SocketAddr, block: &Block<N>) -> Result<()> {
         // Retrieve the block height.
         let height = block.height();
 
+        // Ensure the candidate block does not conflict with the trusted checkpoint at its height.
+        if let Some(checkpoint) = self.trusted_checkpoints.read().get(&height) {
+            if block.hash() != *checkpoint {
+                if self.conflicting_peers.write().insert(*peer_ip) {
+                    warn!("Peer '{peer_ip}' is on a chain that conflicts with the checkpoint at block {height}");
+                }
+                bail!("Candidate block {height} from '{peer_ip}' conflicts with the checkpoint")
+            }
+        }
+
         // Retrieve the request entry for the candidate block.
         if let Some((expected_hash, expected_previous_hash, sync_ips)) = self.requests.read().get(&height) {
             // Ensure the candidate block hash matches the expected hash.
@@ -451,13 +1089,12 @@ impl<N: Network> Sync<N> {
         }
     }
 
-    /// Removes block requests that have timed out. This also removes the corresponding block responses,
-    /// and adds the timed out sync IPs to a map for tracking. Returns the number of timed out block requests.
+    /// Removes the sync IPs that have timed out from the block requests, and adds them to a map for tracking.
+    /// A block request expires once every pending sync IP has timed out without a response, which also removes
+    /// the corresponding block response. Returns the number of timed out block requests.
     fn remove_timed_out_block_requests(&self) -> usize {
         // Acquire the write lock on the requests map.
         let mut requests = self.requests.write();
-        // Acquire the write lock on the responses map.
-        let mut responses = self.responses.write();
         // Acquire the write lock on the request timestamps map.
         let mut request_timestamps = self.request_timestamps.write();
 
@@ -466,35 +1103,57 @@ impl<N: Network> Sync<N> {
 
         // Track each unique peer IP that has timed out.
         let mut timeout_ips = IndexSet::new();
-        // Track the number of timed out block requests.
-        let mut num_timed_out_block_requests = 0;
+        // Track the heights of the timed out block requests.
+        let mut timed_out_heights = Vec::new();
 
         // Remove timed out block requests.
         request_timestamps.retain(|height, timestamp| {
-            // Determine if the duration since the request timestamp has exceeded the request timeout.
-            let is_time_passed = now.duration_since(*timestamp).as_secs() > BLOCK_REQUEST_TIMEOUT_IN_SECS;
-            // Determine if the request is incomplete.
-            let is_request_incomplete =
-                !requests.get(height).map(|(_, _, peer_ips)| peer_ips.is_empty()).unwrap_or(false);
-            // Determine if the request has timed out.
-            let is_timeout = is_time_passed && is_request_incomplete;
+            // Retrieve the duration since the request timestamp.
+            let elapsed = now.duration_since(*timestamp);
+            // Determine if the request has timed out. A request without an entry times out after the default timeout.
+            let is_timeout = match requests.get_mut(height) {
+                Some((_, _, sync_ips)) => {
+                    // Remove the pending sync IPs that have exceeded their own request timeout.
+                    let timed_out_ips = sync_ips
+                        .iter()
+                        .filter(|ip| elapsed > self.block_request_timeout(ip))
+                        .copied()
+                        .collect::<Vec<_>>();
+                    sync_ips.retain(|ip| !timed_out_ips.contains(ip));
+                    // The request times out once every pending sync IP has timed out, and no response arrived.
+                    let is_timeout =
+                        !timed_out_ips.is_empty() && sync_ips.is_empty() && !self.responses.contains_key(height);
+                    // Add each sync IP that exceeded its request timeout to the timeout IPs.
+                    timeout_ips.extend(timed_out_ips);
+                    is_timeout
+                }
+                None => elapsed.as_secs() > BLOCK_REQUEST_TIMEOUT_IN_SECS,
+            };
 
             // If the request has timed out, then remove it.
             if is_timeout {
                 // Remove the request entry for the given height.
-                if let Some((_, _, sync_ips)) = requests.remove(height) {
-                    // Add each sync IP to the timeout IPs.
-                    timeout_ips.extend(sync_ips);
-                }
-                // Remove the response entry for the given height.
-                responses.remove(height);
-                // Increment the number of timed out block requests.
-                num_timed_out_block_requests += 1;
+                requests.remove(height);
+                // Track the height, to remove its response entry once the locks are released.
+                timed_out_heights.push(*height);
             }
             // Retain if this is not a timeout.
             !is_timeout
         });
 
+        // Release the locks, and remove the response entries of the timed out block requests,
+        // which may read and remove the spilled blocks from disk.
+        drop(requests);
+        drop(request_timestamps);
+        let mut is_response_removed = false;
+        for height in &timed_out_heights {
+            is_response_removed |= self.responses.remove(height).is_some();
+        }
+        // Reset the checkpoint anchors, as the removed responses may have been verified to lead to a checkpoint.
+        if is_response_removed {
+            self.checkpoint_anchors.write().clear();
+        }
+
         // If there are timeout IPs, then add them to the request timeouts map.
         if !timeout_ips.is_empty() {
             // Acquire the write lock on the request timeouts map.
@@ -505,7 +1164,7 @@ impl<N: Network> Sync<N> {
             }
         }
 
-        num_timed_out_block_requests
+        timed_out_heights.len()
     }
 
     /// Returns the sync peers and their minimum common ancestor, if the node needs to sync.
@@ -528,6 +1187,7 @@ impl<N: Network> Sync<N> {
             .iter()
             .filter(|(_, locators)| locators.latest_locator_height() > latest_canon_height)
             .filter(|(ip, _)| timeouts.get(*ip).map(|count| *count < MAX_BLOCK_REQUEST_TIMEOUTS).unwrap_or(true))
+            .filter(|(ip, _)| !self.is_conflicting_peer(ip))
             .sorted_by(|(_, a), (_, b)| b.latest_locator_height().cmp(&a.latest_locator_height()))
             .take(NUM_SYNC_CANDIDATE_PEERS)
             .map(|(peer_ip, locators)| (*peer_ip, locators.clone()))
@@ -608,8 +1268,13 @@ impl<N: Network> Sync<N> {
 
         // Compute the start height for the block request.
         let start_height = latest_canon_height + 1;
-        // Compute the end height for the block request.
-        let end_height = (min_common_ancestor + 1).min(start_height + MAX_BLOCK_REQUESTS as u32);
+        // Compute the end height for the block request. The block responses that are held until they lead
+        // to a pending checkpoint extend the window, so that the requests reach the checkpoint.
+        let num_held = match self.next_pending_checkpoint(start_height) {
+            Some(_) => self.responses.len() as u32,
+            None => 0,
+        };
+        let end_height = (min_common_ancestor + 1).min(start_height + MAX_BLOCK_REQUESTS as u32 + num_held);
 
         let mut requests = Vec::with_capacity((start_height..end_height).len());
 
@@ -621,6 +1286,11 @@ impl<N: Network> Sync<N> {
 
             // Construct the block request.
             let (hash, previous_hash, num_sync_ips, is_honest) = construct_request(height, &sync_peers);
+            // Expect the certified hashes, if they exist, as they are signed by a beacon.
+            let (hash, previous_hash, is_honest) = match self.get_certified_hash(height) {
+                Some((hash, previous_hash)) => (Some(hash), Some(previous_hash), true),
+                None => (hash, previous_hash, is_honest),
+            };
 
             // Handle the dishonest case.
             if !is_honest {
@@ -718,6 +1388,7 @@ fn construct_request<N: Network>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{MIN_LATENCY_SAMPLES, MIN_REQUEST_TIMEOUT_IN_MILLIS};
     use snarkos_node_messages::helpers::block_locators::test_helpers::{
         sample_block_locators,
         sample_block_locators_with_fork,
@@ -819,6 +1490,259 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_fork_history() {
+        let sync = sample_sync_at_height(20);
+
+        // Add a peer on the canonical chain, and ensure no fork is recorded.
+        sync.update_peer_locators(sample_peer_ip(1), sample_block_locators(20)).unwrap();
+        assert!(sync.get_fork_history(10).is_empty());
+
+        // Add a peer on a fork at 11, and ensure the fork is recorded.
+        let peer_ip = sample_peer_ip(2);
+        sync.update_peer_locators(peer_ip, sample_block_locators_with_fork(25, 11)).unwrap();
+        let forks = sync.get_fork_history(10);
+        assert_eq!(forks.len(), 1);
+        assert_eq!(forks[0].peer_ip, peer_ip);
+        assert_eq!(forks[0].ancestor, 10);
+        assert_eq!(forks[0].height, 11);
+        assert_eq!(forks[0].peer_height, 25);
+        assert_eq!(forks[0].depth, 10);
+        assert_eq!(forks[0].replaced, (11..=20).map(|height| sync.get_canon_hash(height).unwrap()).collect::<Vec<_>>());
+        assert_eq!(sync.num_forks(), 1);
+
+        // Update the peer on the same fork, and ensure the fork is not recorded twice.
+        sync.update_peer_locators(peer_ip, sample_block_locators_with_fork(26, 11)).unwrap();
+        assert_eq!(sync.get_fork_history(10).len(), 1);
+        assert!(sync.get_fork_history(0).is_empty());
+
+        // Move the peer to another fork right away, and ensure the new fork is rate-limited.
+        sync.update_peer_locators(peer_ip, sample_block_locators_with_fork(26, 15)).unwrap();
+        assert_eq!(sync.get_fork_history(10).len(), 1);
+
+        // Ensure the fork of another peer is still recorded.
+        sync.update_peer_locators(sample_peer_ip(3), sample_block_locators_with_fork(26, 15)).unwrap();
+        assert_eq!(sync.get_fork_history(10).len(), 2);
+        assert_eq!(sync.num_forks(), 2);
+    }
+
+    #[test]
+    fn test_fork_history_persistence() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("forks.jsonl");
+
+        // Record a fork, and refine its common ancestor.
+        let sync = sample_sync_at_height(20);
+        sync.open_fork_history(path.clone()).unwrap();
+        sync.update_peer_locators(sample_peer_ip(2), sample_block_locators_with_fork(25, 11)).unwrap();
+        let mut fork = sync.get_fork_history(1).pop().unwrap();
+        fork.ancestor = 9;
+        fork.depth = 11;
+        sync.persist_fork(&fork);
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2);
+
+        // Ensure the refined fork is reloaded from the file, in place of its earlier entry.
+        let sync = sample_sync_at_height(20);
+        sync.open_fork_history(path.clone()).unwrap();
+        assert_eq!(sync.get_fork_history(10), vec![fork]);
+        assert_eq!(sync.num_forks(), 0);
+
+        // Ensure the file was compacted to the latest entry.
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+    }
+
+    #[test]
+    fn test_ancestor_search() {
+        let sync = sample_sync_at_height(200);
+        let peer_ip = sample_peer_ip(1);
+
+        // The peer is on a fork at 137, while the block locators only show a divergence at 200.
+        type BlockHash = <CurrentNetwork as Network>::BlockHash;
+        let canon_hash = |height: u32| -> Option<BlockHash> { Some(Field::<CurrentNetwork>::from_u32(height).into()) };
+        let peer_hash = |height: u32| -> BlockHash {
+            match height < 137 {
+                true => Field::<CurrentNetwork>::from_u32(height).into(),
+                false => (-Field::<CurrentNetwork>::from_u32(height)).into(),
+            }
+        };
+        sync.ancestor_searches
+            .write()
+            .insert(peer_ip, AncestorSearch { common_height: 0, fork_height: 200, requested_at: None });
+
+        // Narrow down the search, until the common ancestor is found.
+        let mut num_requests = 0;
+        let mut request = sync.next_ancestor_request(&peer_ip);
+        while let Some(ancestor_request) = request {
+            // Ensure a request is not sent while another one is in flight.
+            assert!(sync.next_ancestor_request(&peer_ip).is_none());
+
+            let hashes = ancestor_request.heights().into_iter().map(|height| (height, peer_hash(height))).collect();
+            let response = AncestorResponse { request: ancestor_request, hashes };
+            request = sync.process_ancestor_response(peer_ip, response, canon_hash).unwrap();
+            num_requests += 1;
+        }
+        assert_eq!(num_requests, 2, "The ancestor search took {num_requests} requests");
+
+        // Ensure the common ancestor was found.
+        let search = sync.get_ancestor_search(&peer_ip).unwrap();
+        assert_eq!((search.common_height, search.fork_height), (136, 137));
+        assert_eq!(sync.get_common_ancestor(sample_local_ip(), peer_ip), Some(136));
+        assert!(sync.next_ancestor_request(&peer_ip).is_none());
+
+        // Ensure an unsolicited ancestor response is rejected.
+        let request = AncestorRequest { start_height: 136, end_height: 137 };
+        let response = AncestorResponse { request, hashes: vec![(137, peer_hash(137)), (136, peer_hash(136))] };
+        assert!(sync.process_ancestor_response(peer_ip, response, canon_hash).is_err());
+
+        // Ensure the ancestor search is removed with the peer.
+        sync.remove_peer(&peer_ip);
+        assert!(sync.get_ancestor_search(&peer_ip).is_none());
+    }
+
+    #[test]
+    fn test_trusted_checkpoints() {
+        let sync = sample_sync_at_height(0);
+        let (peer1_ip, peer2_ip) = (sample_peer_ip(1), sample_peer_ip(2));
+        type BlockHash = <CurrentNetwork as Network>::BlockHash;
+        let hash = |height: u32| -> BlockHash { Field::<CurrentNetwork>::from_u32(height).into() };
+
+        // Ensure a checkpoint that conflicts with the canonical chain is rejected.
+        sync.set_trusted_checkpoints(BTreeMap::from([(0, hash(1))])).unwrap_err();
+        assert!(sync.get_trusted_checkpoints().is_empty());
+
+        // Set a checkpoint at block 5.
+        sync.set_trusted_checkpoints(BTreeMap::from([(5, hash(5))])).unwrap();
+
+        // Add a peer on the checkpointed chain, and a higher peer on a chain that forks below the checkpoint.
+        sync.update_peer_locators(peer1_ip, sample_block_locators(10)).unwrap();
+        sync.update_peer_locators(peer2_ip, sample_block_locators_with_fork(20, 3)).unwrap();
+        assert!(!sync.is_conflicting_peer(&peer1_ip));
+        assert!(sync.is_conflicting_peer(&peer2_ip));
+
+        // Ensure the blocks are only requested from the peer on the checkpointed chain.
+        let requests = sync.prepare_block_requests();
+        assert_eq!(requests.len(), 10);
+        assert!(requests.iter().all(|(_, (_, _, sync_ips))| sync_ips == &indexset![peer1_ip]));
+
+        // Ensure the conflict is cleared with the peer.
+        sync.remove_peer(&peer2_ip);
+        assert!(!sync.is_conflicting_peer(&peer2_ip));
+    }
+
+    #[test]
+    fn test_median_peer_height() {
+        let sync = sample_sync_at_height(0);
+        assert_eq!(sync.median_peer_height(), None);
+
+        // Ensure the median of a single peer is its height.
+        sync.update_peer_locators(sample_peer_ip(1), sample_block_locators(10)).unwrap();
+        assert_eq!(sync.median_peer_height(), Some(10));
+
+        // Ensure a single outlier does not raise the median, for an even number of peers.
+        sync.update_peer_locators(sample_peer_ip(2), sample_block_locators(1000)).unwrap();
+        assert_eq!(sync.median_peer_height(), Some(10));
+
+        // Ensure the median is the middle height, for an odd number of peers.
+        sync.update_peer_locators(sample_peer_ip(3), sample_block_locators(12)).unwrap();
+        assert_eq!(sync.median_peer_height(), Some(12));
+
+        // Ensure the median follows the removal of a peer.
+        sync.remove_peer(&sample_peer_ip(3));
+        assert_eq!(sync.median_peer_height(), Some(10));
+    }
+
+    #[test]
+    fn test_certified_hashes() {
+        let sync = sample_sync_at_height(0);
+        let peer_ip = sample_peer_ip(1);
+        type BlockHash = <CurrentNetwork as Network>::BlockHash;
+        let hash = |height: u32| -> BlockHash { Field::<CurrentNetwork>::from_u32(height).into() };
+
+        // Sample the certificates of the blocks on the certified chain.
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let genesis = BlockCertificate::from_block(&genesis);
+        let certificate = |height: u32| BlockCertificate {
+            round: height as u64,
+            height,
+            previous_hash: hash(height.saturating_sub(1)),
+            block_hash: hash(height),
+            ..genesis.clone()
+        };
+
+        // Ensure a certificate that conflicts with the canonical chain is rejected.
+        let conflicting = BlockCertificate { block_hash: hash(9), ..certificate(0) };
+        sync.insert_certified_hashes(&[conflicting]).unwrap_err();
+
+        // Ensure certificates that do not link to the canonical tip are rejected.
+        let unanchored = BlockCertificate { previous_hash: hash(7), ..certificate(1) };
+        sync.insert_certified_hashes(&[unanchored]).unwrap_err();
+        sync.insert_certified_hashes(&[certificate(3)]).unwrap_err();
+        assert_eq!(sync.get_certified_hash(3), None);
+
+        // Insert the certified hashes, and ensure the canonical heights are skipped.
+        sync.insert_certified_hashes(&(0..=10).map(certificate).collect::<Vec<_>>()).unwrap();
+        assert_eq!(sync.get_certified_hash(0), None);
+        assert_eq!(sync.get_certified_hash(5), Some((hash(5), hash(4))));
+
+        // Ensure a certificate that conflicts with a certified hash is rejected.
+        let conflicting = BlockCertificate { block_hash: hash(9), ..certificate(5) };
+        sync.insert_certified_hashes(&[conflicting]).unwrap_err();
+        assert_eq!(sync.get_certified_hash(5), Some((hash(5), hash(4))));
+
+        // Ensure certificates that extend the certified hashes are accepted, and those beyond a gap are rejected.
+        sync.insert_certified_hashes(&[certificate(11)]).unwrap();
+        sync.insert_certified_hashes(&[certificate(13)]).unwrap_err();
+        assert_eq!(sync.get_certified_hash(13), None);
+
+        // Add a peer on a chain that forks from the certified chain at block 3.
+        sync.update_peer_locators(peer_ip, sample_block_locators_with_fork(10, 3)).unwrap();
+
+        // Ensure the block requests expect the certified hashes, instead of the hashes in the locators of the peer.
+        let requests = sync.prepare_block_requests();
+        assert_eq!(requests.len(), 10);
+        for (height, (expected_hash, expected_previous_hash, _)) in requests {
+            assert_eq!(expected_hash, Some(hash(height)));
+            assert_eq!(expected_previous_hash, Some(hash(height - 1)));
+        }
+    }
+
+    #[test]
+    fn test_certificates_persistence() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("certificates.bin");
+        type BlockHash = <CurrentNetwork as Network>::BlockHash;
+        let hash = |height: u32| -> BlockHash { Field::<CurrentNetwork>::from_u32(height).into() };
+
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let genesis = BlockCertificate::from_block(&genesis);
+        let certificate = |height: u32| BlockCertificate {
+            round: height as u64,
+            height,
+            previous_hash: hash(height.saturating_sub(1)),
+            block_hash: hash(height),
+            ..genesis.clone()
+        };
+
+        // Insert the certified hashes, and ensure they are persisted.
+        let sync = sample_sync_at_height(0);
+        assert!(sync.open_certificates(path.clone()).unwrap().is_empty());
+        sync.insert_certified_hashes(&(1..=10).map(certificate).collect::<Vec<_>>()).unwrap();
+        assert_eq!(sync.latest_certified_round(), Some(10));
+
+        // Ensure the certificates are loaded from the file, and can be restored after a restart.
+        let sync = sample_sync_at_height(0);
+        let certificates = sync.open_certificates(path.clone()).unwrap();
+        assert_eq!(certificates, (1..=10).map(certificate).collect::<Vec<_>>());
+        sync.insert_certified_hashes(&certificates).unwrap();
+        assert_eq!(sync.get_certified_hash(10), Some((hash(10), hash(9))));
+
+        // Ensure a truncated file yields the certificates before the malformed one.
+        let bytes = fs::read(&path).unwrap();
+        fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+        let sync = sample_sync_at_height(0);
+        assert_eq!(sync.open_certificates(path).unwrap().len(), 9);
+    }
+
     #[test]
     fn test_prepare_block_requests() {
         for num_peers in 0..111 {
@@ -1009,6 +1933,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_adaptive_block_request_timeouts() {
+        use snarkvm::prelude::FromBytes;
+
+        // Initialize a sync pool without a canonical chain, so the genesis block can be requested.
+        let sync = Sync::<CurrentNetwork>::default();
+        sync.set_local_ip(sample_local_ip());
+        let (fast_peer, new_peer) = (sample_peer_ip(1), sample_peer_ip(2));
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+
+        // Record the response times of the fast peer.
+        for _ in 0..MIN_LATENCY_SAMPLES {
+            sync.latencies.insert_sample(fast_peer, Duration::from_millis(100));
+        }
+        assert_eq!(sync.block_request_timeout(&fast_peer), Duration::from_millis(MIN_REQUEST_TIMEOUT_IN_MILLIS));
+        assert_eq!(sync.block_request_timeout(&new_peer), Duration::from_secs(BLOCK_REQUEST_TIMEOUT_IN_SECS));
+
+        // Insert a block request to both peers, that has been pending for longer than the timeout of the fast peer.
+        sync.insert_block_request(0, (None, None, indexset![fast_peer, new_peer])).unwrap();
+        sync.request_timestamps.write().insert(0, Instant::now() - Duration::from_secs(5));
+
+        // Ensure only the fast peer times out, and the request remains pending on the other peer.
+        assert_eq!(sync.remove_timed_out_block_requests(), 0);
+        assert_eq!(sync.get_block_request(0), Some((None, None, indexset![new_peer])));
+        assert!(sync.get_block_request_timestamp(0).is_some());
+        assert!(sync.request_timeouts.read().contains_key(&fast_peer));
+        assert!(!sync.request_timeouts.read().contains_key(&new_peer));
+
+        // Ensure the response of the other peer is still accepted, and completes the request.
+        sync.insert_block_response(new_peer, genesis).unwrap();
+        assert_eq!(sync.get_block_request(0), Some((None, None, indexset![])));
+        assert!(sync.responses.contains_key(&0));
+        assert_eq!(sync.remove_timed_out_block_requests(), 0);
+        assert!(sync.responses.contains_key(&0));
+
+        // Insert a block request to both peers, that has been pending for longer than the timeout of both peers.
+        sync.insert_block_request(1, (None, None, indexset![fast_peer, new_peer])).unwrap();
+        let expired = Instant::now() - Duration::from_secs(BLOCK_REQUEST_TIMEOUT_IN_SECS + 1);
+        sync.request_timestamps.write().insert(1, expired);
+
+        // Ensure the request expires, once every pending peer has timed out.
+        assert_eq!(sync.remove_timed_out_block_requests(), 1);
+        assert!(sync.get_block_request(1).is_none());
+        assert!(sync.get_block_request_timestamp(1).is_none());
+        assert!(sync.request_timeouts.read().contains_key(&new_peer));
+
+        // Ensure the response times are removed with the peer.
+        sync.remove_peer(&fast_peer);
+        assert!(sync.get_peer_latency(&fast_peer).is_none());
+    }
+
     #[test]
     fn test_insert_block_requests_fails() {
         let sync = sample_sync_at_height(9);
@@ -0,0 +1,93 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use parking_lot::RwLock;
+
+/// The number of consecutive heartbeats without tip progress, while peers report higher heights,
+/// after which the tip is considered stale.
+pub const STALE_TIP_HEARTBEATS: usize = 8; // 8 heartbeats
+
+/// A monitor that detects when the local tip stops advancing while peers are ahead.
+#[derive(Debug, Default)]
+pub struct TipMonitor {
+    /// The last observed local height, and the number of consecutive heartbeats it has been stalled for.
+    state: RwLock<(u32, usize)>,
+}
+
+impl TipMonitor {
+    /// Returns the number of consecutive heartbeats the local tip has been stalled for.
+    pub fn num_stalled_heartbeats(&self) -> usize {
+        self.state.read().1
+    }
+
+    /// Updates the monitor with the local height and the highest peer height,
+    /// returning `true` if the tip is stale. The stalled count is reset once the tip is reported stale.
+    pub fn update(&self, local_height: u32, peer_height: Option<u32>) -> bool {
+        let mut state = self.state.write();
+        let (last_height, num_stalled) = &mut *state;
+
+        // If the tip advanced, or no peer is ahead, the tip is not stale.
+        if local_height > *last_height || peer_height.map_or(true, |height| height <= local_height) {
+            *last_height = local_height;
+            *num_stalled = 0;
+            return false;
+        }
+
+        // Otherwise, increment the stalled count.
+        *num_stalled += 1;
+        if *num_stalled >= STALE_TIP_HEARTBEATS {
+            *num_stalled = 0;
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tip_monitor() {
+        let monitor = TipMonitor::default();
+
+        // Ensure the tip is not stale while it advances.
+        for height in 1..=STALE_TIP_HEARTBEATS as u32 * 2 {
+            assert!(!monitor.update(height, Some(100)));
+        }
+        assert_eq!(monitor.num_stalled_heartbeats(), 0);
+
+        // Ensure the tip is not stale while no peer is ahead.
+        let height = STALE_TIP_HEARTBEATS as u32 * 2;
+        for _ in 0..STALE_TIP_HEARTBEATS * 2 {
+            assert!(!monitor.update(height, Some(height)));
+            assert!(!monitor.update(height, None));
+        }
+
+        // Ensure the tip becomes stale after it stalls while peers are ahead.
+        for i in 1..STALE_TIP_HEARTBEATS {
+            assert!(!monitor.update(height, Some(100)));
+            assert_eq!(monitor.num_stalled_heartbeats(), i);
+        }
+        assert!(monitor.update(height, Some(100)));
+        assert_eq!(monitor.num_stalled_heartbeats(), 0);
+
+        // Ensure progress resets the stalled count.
+        assert!(!monitor.update(height, Some(100)));
+        assert!(!monitor.update(height + 1, Some(100)));
+        assert_eq!(monitor.num_stalled_heartbeats(), 0);
+    }
+}
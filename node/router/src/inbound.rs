@@ -14,30 +14,45 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{Outbound, Peer};
+use crate::{Outbound, Peer, Router, Verdict};
 use snarkos_node_messages::{
+    AncestorRequest,
+    AncestorResponse,
     BeaconPropose,
+    BlockCertificate,
     BlockRequest,
+    CertificateRangeRequest,
+    CertificateRangeResponse,
+    Data,
     DataBlocks,
+    HeaderRequest,
+    HeaderResponse,
     Message,
+    PeerAnnouncement,
     PeerResponse,
     Ping,
     Pong,
+    TransactionRequest,
     UnconfirmedSolution,
     UnconfirmedTransaction,
+    UnconfirmedTransmission,
+    SOLUTION_TRANSMISSION_KIND,
+    TRANSACTION_TRANSMISSION_KIND,
 };
 use snarkos_node_tcp::protocols::Reading;
 use snarkvm::prelude::{Block, EpochChallenge, Header, Network, ProverSolution, Transaction};
 
 use anyhow::{bail, ensure, Result};
 use std::{net::SocketAddr, time::Instant};
+use time::OffsetDateTime;
 
 #[async_trait]
 pub trait Inbound<N: Network>: Reading + Outbound<N> {
-    /// The maximum number of puzzle requests per interval.
-    const MAXIMUM_PUZZLE_REQUESTS_PER_INTERVAL: usize = 5;
     /// The duration in seconds to sleep in between ping requests with a connected peer.
     const PING_SLEEP_IN_SECS: u64 = 9; // 9 seconds
+    /// The maximum difference in seconds between the timestamp of a peer announcement and the local clock,
+    /// for an announcement sent by the peer itself, or into the future, for a relayed announcement.
+    const MAXIMUM_ANNOUNCEMENT_SKEW_IN_SECS: i64 = 60; // 1 minute
 
     /// Handles the inbound message from the peer.
     async fn inbound(&self, peer_addr: SocketAddr, message: Message<N>) -> Result<()> {
@@ -47,22 +62,35 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
             None => bail!("Unable to resolve the (ambiguous) peer address '{peer_addr}'"),
         };
 
-        // Drop the peer, if they have sent more than 1000 messages in the last 5 seconds.
-        let num_messages = self.router().cache.insert_inbound_message(peer_ip, 5);
-        if num_messages >= 1000 {
-            bail!("Dropping '{peer_ip}' for spamming messages (num_messages = {num_messages})")
-        }
-
         trace!("Received '{}' from '{peer_ip}'", message.name());
 
+        // Pass the message through the middleware layers of its route.
+        if self.router().message_routes().check(self.router(), peer_ip, &message)? == Verdict::Skip {
+            return Ok(());
+        }
+
         // This match statement handles the inbound message by deserializing the message,
         // checking the message is valid, and then calling the appropriate (trait) handler.
         match message {
+            Message::AncestorRequest(message) => {
+                let AncestorRequest { start_height, end_height } = &message;
+
+                // Ensure the ancestor request is well-formed.
+                if start_height >= end_height {
+                    bail!("Ancestor request from '{peer_ip}' has an invalid range ({start_height}..={end_height})")
+                }
+                // Process the ancestor request.
+                match self.ancestor_request(peer_ip, message) {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid ancestor request"),
+                }
+            }
+            Message::AncestorResponse(message) => match self.ancestor_response(peer_ip, message) {
+                true => Ok(()),
+                false => bail!("Peer '{peer_ip}' sent an invalid ancestor response"),
+            },
             Message::BeaconPropose(message) => {
-                // Ensure this node is a beacon.
-                ensure!(self.router().node_type().is_beacon(), "[BeaconPropose] This node is not a beacon");
-                // Ensure the peer is a beacon.
-                ensure!(self.router().is_connected_beacon(&peer_ip), "[BeaconPropose] '{peer_ip}' is not a beacon");
+                // Note: The `ProposalFilter` route ensures the proposal is sent between beacons, or to a validator.
 
                 // Clone the serialized message.
                 let serialized = message.clone();
@@ -78,9 +106,8 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                 {
                     bail!("Peer '{peer_ip}' is not following the 'BeaconPropose' protocol")
                 }
-                // TODO (howardwu): Preemptively check the block signature is valid against the peer's account address.
-                //  Only the block proposer should be able to send a valid block signature. This message type should not
-                //  be propagated by any other peers.
+                // Note: The validators relay the proposals, so the block signature is checked against the beacons
+                //  by the handler, instead of against the account of the peer.
                 // Handle the block proposal.
                 match self.beacon_propose(peer_ip, serialized, block) {
                     true => Ok(()),
@@ -117,9 +144,10 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                     bail!("Block request from '{peer_ip}' has an excessive range ({start_height}..{end_height})")
                 }
 
-                match self.block_request(peer_ip, message) {
+                // Queue the block request, to be served fairly between the peers.
+                match self.router().block_scheduler().enqueue(peer_ip, message) {
                     true => Ok(()),
-                    false => bail!("Peer '{peer_ip}' sent an invalid block request"),
+                    false => bail!("Peer '{peer_ip}' sent too many block requests"),
                 }
             }
             Message::BlockResponse(message) => {
@@ -157,6 +185,38 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                     false => bail!("Peer '{peer_ip}' sent an invalid block response"),
                 }
             }
+            Message::CertificateRangeRequest(message) => {
+                let CertificateRangeRequest { start_round, end_round } = &message;
+
+                // Ensure the certificate range request is well-formed.
+                if start_round > end_round {
+                    bail!("Certificate request from '{peer_ip}' has an invalid range ({start_round}..={end_round})")
+                }
+                // Ensure that the certificate range request is within the allowed bounds.
+                if end_round - start_round >= CertificateRangeResponse::<N>::MAXIMUM_NUMBER_OF_CERTIFICATES as u64 {
+                    bail!("Certificate request from '{peer_ip}' has an excessive range ({start_round}..={end_round})")
+                }
+                // Process the certificate range request.
+                match self.certificate_range_request(peer_ip, message) {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid certificate range request"),
+                }
+            }
+            Message::CertificateRangeResponse(message) => {
+                // Remove the certificate range request, checking if this node previously sent it to this peer.
+                if !self.router().cache.remove_outbound_certificate_request(peer_ip, &message.request) {
+                    bail!("Peer '{peer_ip}' is not following the protocol (unexpected certificate range response)")
+                }
+                // Ensure the certificates are within the requested rounds, and linked.
+                if let Err(error) = message.check() {
+                    bail!("Peer '{peer_ip}' sent an invalid certificate range response - {error}")
+                }
+                // Process the certificate range response.
+                match self.certificate_range_response(peer_ip, message.request, message.certificates).await {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid certificate range response"),
+                }
+            }
             Message::ChallengeRequest(..) | Message::ChallengeResponse(..) => {
                 // Disconnect as the peer is not following the protocol.
                 bail!("Peer '{peer_ip}' is not following the protocol")
@@ -164,11 +224,40 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
             Message::Disconnect(message) => {
                 bail!("Disconnecting peer '{peer_ip}' for the following reason: {:?}", message.reason)
             }
+            Message::HeaderRequest(message) => {
+                let HeaderRequest { start_height, end_height } = &message;
+
+                // Ensure the header request is well-formed.
+                if start_height >= end_height {
+                    bail!("Header request from '{peer_ip}' has an invalid range ({start_height}..{end_height})")
+                }
+                // Ensure that the header request is within the allowed bounds.
+                if end_height - start_height > HeaderResponse::<N>::MAXIMUM_NUMBER_OF_HEADERS as u32 {
+                    bail!("Header request from '{peer_ip}' has an excessive range ({start_height}..{end_height})")
+                }
+                // Process the header request.
+                match self.header_request(peer_ip, message) {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid header request"),
+                }
+            }
+            Message::MemoryPoolRequest(..) => match self.memory_pool_request(peer_ip) {
+                true => Ok(()),
+                false => bail!("Peer '{peer_ip}' sent an invalid memory pool request"),
+            },
+            Message::HeaderResponse(..) | Message::MemoryPoolResponse(..) => {
+                // Disconnect as this node does not send light gateway requests.
+                bail!("Peer '{peer_ip}' is not following the protocol (unexpected light gateway response)")
+            }
+            Message::PeerAnnouncement(message) => match self.peer_announcement(peer_ip, message).await {
+                true => Ok(()),
+                false => bail!("Peer '{peer_ip}' sent an invalid peer announcement"),
+            },
             Message::PeerRequest(..) => match self.peer_request(peer_ip) {
                 true => Ok(()),
                 false => bail!("Peer '{peer_ip}' sent an invalid peer request"),
             },
-            Message::PeerResponse(message) => match self.peer_response(peer_ip, &message.peers) {
+            Message::PeerResponse(message) => match self.peer_response(peer_ip, &message.peers).await {
                 true => Ok(()),
                 false => bail!("Peer '{peer_ip}' sent an invalid peer response"),
             },
@@ -180,19 +269,10 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                 true => Ok(()),
                 false => bail!("Peer '{peer_ip}' sent an invalid pong"),
             },
-            Message::PuzzleRequest(..) => {
-                // Insert the puzzle request for the peer, and fetch the recent frequency.
-                let frequency = self.router().cache.insert_inbound_puzzle_request(peer_ip);
-                // Check if the number of puzzle requests is within the limit.
-                if frequency > Self::MAXIMUM_PUZZLE_REQUESTS_PER_INTERVAL {
-                    bail!("Peer '{peer_ip}' is not following the protocol (excessive puzzle requests)")
-                }
-                // Process the puzzle request.
-                match self.puzzle_request(peer_ip) {
-                    true => Ok(()),
-                    false => bail!("Peer '{peer_ip}' sent an invalid puzzle request"),
-                }
-            }
+            Message::PuzzleRequest(..) => match self.puzzle_request(peer_ip) {
+                true => Ok(()),
+                false => bail!("Peer '{peer_ip}' sent an invalid puzzle request"),
+            },
             Message::PuzzleResponse(message) => {
                 // Check that this node previously sent a puzzle request to this peer.
                 if !self.router().cache.contains_outbound_puzzle_request(&peer_ip) {
@@ -212,16 +292,30 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                     false => bail!("Peer '{peer_ip}' sent an invalid puzzle response"),
                 }
             }
+            Message::TransactionAnnouncement(message) => {
+                // Request the transaction, if it was not recently seen or requested.
+                if self.router().gossip().should_request(message.transaction_id, peer_ip) {
+                    let transaction_id = message.transaction_id;
+                    self.send(peer_ip, Message::TransactionRequest(TransactionRequest { transaction_id }));
+                }
+                Ok(())
+            }
+            Message::TransactionRequest(message) => {
+                // Send the transaction, if it was recently seen.
+                match self.router().gossip().get_payload(&message.transaction_id) {
+                    Some(transaction) => {
+                        self.send(peer_ip, Message::UnconfirmedTransaction(transaction));
+                        Ok(())
+                    }
+                    None => {
+                        trace!("Skipping 'TransactionRequest' from '{peer_ip}' (unknown transaction)");
+                        Ok(())
+                    }
+                }
+            }
             Message::UnconfirmedSolution(message) => {
                 // Clone the serialized message.
                 let serialized = message.clone();
-                // Update the timestamp for the unconfirmed solution.
-                let seen_before =
-                    self.router().cache.insert_inbound_solution(peer_ip, message.puzzle_commitment).is_some();
-                // Determine whether to propagate the solution.
-                if seen_before {
-                    bail!("Skipping 'UnconfirmedSolution' from '{peer_ip}'")
-                }
                 // Perform the deferred non-blocking deserialization of the solution.
                 let solution = match message.solution.deserialize().await {
                     Ok(solution) => solution,
@@ -240,13 +334,6 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
             Message::UnconfirmedTransaction(message) => {
                 // Clone the serialized message.
                 let serialized = message.clone();
-                // Update the timestamp for the unconfirmed transaction.
-                let seen_before =
-                    self.router().cache.insert_inbound_transaction(peer_ip, message.transaction_id).is_some();
-                // Determine whether to propagate the transaction.
-                if seen_before {
-                    bail!("Skipping 'UnconfirmedTransaction' from '{peer_ip}'")
-                }
                 // Perform the deferred non-blocking deserialization of the transaction.
                 let transaction = match message.transaction.deserialize().await {
                     Ok(transaction) => transaction,
@@ -257,14 +344,64 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                     bail!("Peer '{peer_ip}' is not following the 'UnconfirmedTransaction' protocol")
                 }
                 // Handle the unconfirmed transaction.
+                // Note: The transaction is retained to serve `TransactionRequest` messages once the node relays it.
                 match self.unconfirmed_transaction(peer_ip, serialized, transaction) {
                     true => Ok(()),
                     false => bail!("Peer '{peer_ip}' sent an invalid unconfirmed transaction"),
                 }
             }
+            Message::UnconfirmedTransmission(message) => match message.kind.as_str() {
+                // Handle the native transmissions as their messages.
+                SOLUTION_TRANSMISSION_KIND => {
+                    // Perform the deferred non-blocking deserialization of the solution.
+                    let buffer = Data::<ProverSolution<N>>::Buffer(message.payload.clone());
+                    let solution = match buffer.deserialize().await {
+                        Ok(solution) => solution,
+                        Err(error) => bail!("[UnconfirmedTransmission] {error}"),
+                    };
+                    let serialized = UnconfirmedSolution {
+                        puzzle_commitment: solution.commitment(),
+                        solution: Data::Buffer(message.payload),
+                    };
+                    match self.unconfirmed_solution(peer_ip, serialized, solution).await {
+                        true => Ok(()),
+                        false => bail!("Peer '{peer_ip}' sent an invalid unconfirmed solution"),
+                    }
+                }
+                TRANSACTION_TRANSMISSION_KIND => {
+                    // Perform the deferred non-blocking deserialization of the transaction.
+                    let buffer = Data::<Transaction<N>>::Buffer(message.payload.clone());
+                    let transaction = match buffer.deserialize().await {
+                        Ok(transaction) => transaction,
+                        Err(error) => bail!("[UnconfirmedTransmission] {error}"),
+                    };
+                    let serialized = UnconfirmedTransaction {
+                        transaction_id: transaction.id(),
+                        transaction: Data::Buffer(message.payload),
+                    };
+                    match self.unconfirmed_transaction(peer_ip, serialized, transaction) {
+                        true => Ok(()),
+                        false => bail!("Peer '{peer_ip}' sent an invalid unconfirmed transaction"),
+                    }
+                }
+                _ => match self.unconfirmed_transmission(peer_ip, message) {
+                    true => Ok(()),
+                    false => bail!("Peer '{peer_ip}' sent an invalid unconfirmed transmission"),
+                },
+            },
         }
     }
 
+    /// Handles an `AncestorRequest` message.
+    fn ancestor_request(&self, _peer_ip: SocketAddr, _message: AncestorRequest) -> bool {
+        false
+    }
+
+    /// Handles an `AncestorResponse` message.
+    fn ancestor_response(&self, _peer_ip: SocketAddr, _message: AncestorResponse<N>) -> bool {
+        false
+    }
+
     /// Handles a `BeaconPropose` message.
     fn beacon_propose(&self, _peer_ip: SocketAddr, _serialized: BeaconPropose<N>, _block: Block<N>) -> bool {
         // pub const ALEO_MAXIMUM_FORK_DEPTH: u32 = (NUM_RECENTS as u32).saturating_sub(1);
@@ -289,19 +426,100 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
     /// Handles a `BlockResponse` message.
     fn block_response(&self, peer_ip: SocketAddr, _blocks: Vec<Block<N>>) -> bool;
 
+    /// Handles a `CertificateRangeRequest` message.
+    fn certificate_range_request(&self, _peer_ip: SocketAddr, _message: CertificateRangeRequest) -> bool {
+        false
+    }
+
+    /// Handles a `CertificateRangeResponse` message.
+    async fn certificate_range_response(
+        &self,
+        _peer_ip: SocketAddr,
+        _request: CertificateRangeRequest,
+        _certificates: Vec<BlockCertificate<N>>,
+    ) -> bool {
+        false
+    }
+
+    /// Handles a `HeaderRequest` message from a light peer.
+    fn header_request(&self, _peer_ip: SocketAddr, _message: HeaderRequest) -> bool {
+        false
+    }
+
+    /// Handles a `MemoryPoolRequest` message from a light peer.
+    fn memory_pool_request(&self, _peer_ip: SocketAddr) -> bool {
+        false
+    }
+
+    /// Handles a `PeerAnnouncement` message, in which the peer announces its own listening address.
+    async fn peer_announcement(&self, peer_ip: SocketAddr, message: PeerAnnouncement<N>) -> bool {
+        // Retrieve the connected peer.
+        let peer = match self.router().get_connected_peer(&peer_ip) {
+            Some(peer) => peer,
+            None => return false,
+        };
+        // Ensure the announcement matches the listening address, account, and node type of the peer.
+        if message.listener != peer_ip || message.address != peer.address() || message.node_type != peer.node_type() {
+            warn!("Peer '{peer_ip}' announced a listening address, account, or node type that is not its own");
+            return false;
+        }
+        // Ensure the announcement was signed recently.
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        if (now - message.timestamp).abs() > Self::MAXIMUM_ANNOUNCEMENT_SKEW_IN_SECS {
+            warn!("Peer '{peer_ip}' sent a stale peer announcement");
+            return false;
+        }
+        // Ensure the announcement is signed by the account of the peer, on the signature verifier.
+        let announcement = message.clone();
+        if !self.router().signatures().verify(move || announcement.verify_signature()).await {
+            warn!("Peer '{peer_ip}' sent a peer announcement with an invalid signature");
+            return false;
+        }
+        // Store the announcement, to relay it to the other peers.
+        self.router()
+            .update_connected_peer(peer_ip, peer.node_type(), |peer: &mut Peer<N>| {
+                peer.set_announcement(message.clone())
+            })
+            .is_ok()
+    }
+
     /// Handles a `PeerRequest` message.
     fn peer_request(&self, peer_ip: SocketAddr) -> bool {
-        // Retrieve the connected peers.
-        let peers = self.router().connected_peers();
+        // Retrieve the signed announcements of the connected peers.
+        let peers = self.router().connected_announcements();
         // Send a `PeerResponse` message to the peer.
         self.send(peer_ip, Message::PeerResponse(PeerResponse { peers }));
         true
     }
 
     /// Handles a `PeerResponse` message.
-    fn peer_response(&self, _peer_ip: SocketAddr, peers: &[SocketAddr]) -> bool {
-        // Adds the given peer IPs to the list of candidate peers.
-        self.router().insert_candidate_peers(peers);
+    async fn peer_response(&self, peer_ip: SocketAddr, peers: &[PeerAnnouncement<N>]) -> bool {
+        // Ensure no announcement is from the future.
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        if let Some(announcement) = peers.iter().find(|a| a.timestamp > now + Self::MAXIMUM_ANNOUNCEMENT_SKEW_IN_SECS) {
+            warn!("Peer '{peer_ip}' relayed an invalid announcement of '{}'", announcement.listener);
+            return false;
+        }
+        // Skip the stale announcements, as their nodes may have since left their addresses.
+        let peers = peers
+            .iter()
+            .filter(|announcement| now - announcement.timestamp <= Router::<N>::MAXIMUM_ANNOUNCEMENT_AGE_IN_SECS)
+            .cloned()
+            .collect::<Vec<_>>();
+        // Ensure every announcement is signed by the account it announces.
+        let peers = match self.router().signatures().verify_batch(peers, PeerAnnouncement::verify_signature).await {
+            Ok(peers) => peers,
+            Err(Some(announcement)) => {
+                warn!("Peer '{peer_ip}' relayed an invalid announcement of '{}'", announcement.listener);
+                return false;
+            }
+            Err(None) => {
+                warn!("Failed to verify the announcements relayed by '{peer_ip}'");
+                return false;
+            }
+        };
+        // Adds the announced listening addresses to the list of candidate peers.
+        self.router().insert_candidate_announcements(&peers);
         true
     }
 
@@ -329,6 +547,12 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
                 warn!("Peer '{peer_ip}' sent invalid block locators: {error}");
                 return false;
             }
+            // If this node is on a fork with the peer, narrow down the common ancestor.
+            if self.router().node_type().is_beacon() || self.router().node_type().is_validator() {
+                if let Some(request) = self.router().sync().next_ancestor_request(&peer_ip) {
+                    self.send(peer_ip, Message::AncestorRequest(request));
+                }
+            }
         }
 
         // Update the connected peer.
@@ -397,4 +621,11 @@ pub trait Inbound<N: Network>: Reading + Outbound<N> {
         serialized: UnconfirmedTransaction<N>,
         _transaction: Transaction<N>,
     ) -> bool;
+
+    /// Handles an `UnconfirmedTransmission` message of a kind beyond the solutions and transactions.
+    /// Note: Nodes without the registry of the transmission kinds neither check nor relay the transmissions.
+    fn unconfirmed_transmission(&self, peer_ip: SocketAddr, message: UnconfirmedTransmission) -> bool {
+        trace!("Skipping the '{}' transmission from '{peer_ip}'", message.kind);
+        true
+    }
 }
@@ -40,7 +40,7 @@ mod routing;
 pub use routing::*;
 
 use snarkos_account::Account;
-use snarkos_node_messages::NodeType;
+use snarkos_node_messages::{Compression, MessageCodec, NodeType, PeerAnnouncement, PeerResponse};
 use snarkos_node_tcp::{Config, Tcp};
 use snarkvm::prelude::{Address, Network, PrivateKey, ViewKey};
 
@@ -48,7 +48,19 @@ use anyhow::{bail, Result};
 use core::str::FromStr;
 use indexmap::{IndexMap, IndexSet};
 use parking_lot::{Mutex, RwLock};
-use std::{collections::HashSet, future::Future, net::SocketAddr, ops::Deref, sync::Arc, time::Instant};
+use rand::{rngs::OsRng, Rng};
+use std::{
+    collections::HashSet,
+    future::Future,
+    net::SocketAddr,
+    ops::Deref,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+use time::OffsetDateTime;
 use tokio::task::JoinHandle;
 
 #[derive(Clone)]
@@ -69,12 +81,36 @@ pub struct InnerRouter<N: Network> {
     node_type: NodeType,
     /// The account of the node.
     account: Account<N>,
+    /// The state of the peer announcements, and the accounts bound to the dialed listening addresses.
+    announcements: Announcements<N>,
     /// The cache.
     cache: Cache<N>,
+    /// The transmissions that recently failed verification.
+    blacklist: Blacklist<N>,
+    /// The committee members, and their last known IPs.
+    committee: CommitteePeers<N>,
+    /// The scheduler of inbound block requests.
+    block_scheduler: BlockScheduler,
+    /// The light gateway, which serves the client peers as untrusted light consumers.
+    light_gateway: LightGateway,
+    /// The inbound message statistics.
+    message_stats: MessageStats,
+    /// The middleware routes of the inbound messages.
+    message_routes: MessageRoutes<N>,
+    /// The gossip state.
+    gossip: Gossip<N>,
+    /// The diversity constraints on the peers this node dials.
+    peer_diversity: PeerDiversity,
+    /// The relay of the beacon proposals between validators.
+    relay: Relay<N>,
     /// The resolver.
     resolver: Resolver,
+    /// The verifier of the signatures of the inbound messages.
+    signatures: SignatureVerifier,
     /// The sync pool.
     sync: Sync<N>,
+    /// The tip monitor.
+    tip_monitor: TipMonitor,
     /// The set of trusted peers.
     trusted_peers: IndexSet<SocketAddr>,
     /// The map of connected peer IPs to their peer handlers.
@@ -90,6 +126,10 @@ pub struct InnerRouter<N: Network> {
     restricted_peers: RwLock<IndexMap<SocketAddr, Instant>>,
     /// The spawned handles.
     handles: Mutex<Vec<JoinHandle<()>>>,
+    /// The boolean flag for the standby mode, in which the node stays in sync without producing blocks or solutions.
+    standby: AtomicBool,
+    /// The compression this node offers for the block and certificate messages of its connections.
+    compression: RwLock<Compression>,
     /// The boolean flag for the development mode.
     is_dev: bool,
 }
@@ -102,6 +142,10 @@ impl<N: Network> Router<N> {
     /// The duration in seconds after which a connected peer is considered inactive or
     /// disconnected if no message has been received in the meantime.
     const RADIO_SILENCE_IN_SECS: u64 = 150; // 2.5 minutes
+    /// The number of connection slots reserved for committee members, on top of the maximum number of peers.
+    pub const COMMITTEE_RESERVED_SLOTS: u16 = 8;
+    /// The maximum age in seconds of a peer announcement that is relayed, after which the peer must announce again.
+    pub const MAXIMUM_ANNOUNCEMENT_AGE_IN_SECS: i64 = 900; // 15 minutes
 }
 
 impl<N: Network> Router<N> {
@@ -115,21 +159,35 @@ impl<N: Network> Router<N> {
         is_dev: bool,
     ) -> Result<Self> {
         // Initialize the TCP stack.
-        let tcp = Tcp::new(Config::new(node_ip, max_peers));
+        let tcp = Tcp::new(Config::new(node_ip, max_peers.saturating_add(Self::COMMITTEE_RESERVED_SLOTS)));
         // Initialize the router.
         Ok(Self(Arc::new(InnerRouter {
             tcp,
             node_type,
             account,
+            announcements: Default::default(),
             cache: Default::default(),
+            blacklist: Default::default(),
+            committee: Default::default(),
+            block_scheduler: Default::default(),
+            light_gateway: Default::default(),
+            message_stats: Default::default(),
+            message_routes: Default::default(),
+            gossip: Default::default(),
+            peer_diversity: Default::default(),
+            relay: Default::default(),
             resolver: Default::default(),
+            signatures: Default::default(),
             sync: Default::default(),
+            tip_monitor: Default::default(),
             trusted_peers: trusted_peers.iter().copied().collect(),
             connected_peers: Default::default(),
             connecting_peers: Default::default(),
             candidate_peers: Default::default(),
             restricted_peers: Default::default(),
             handles: Default::default(),
+            standby: Default::default(),
+            compression: Default::default(),
             is_dev,
         })))
     }
@@ -164,7 +222,12 @@ impl<N: Network> Router<N> {
             bail!("Dropping connection attempt to '{peer_ip}' (attempted to self-connect)")
         }
         // Ensure the node does not surpass the maximum number of peer connections.
-        if self.number_of_connected_peers() >= self.max_connected_peers() {
+        // Note: Committee members may connect into the reserved slots.
+        let max_peers = match self.committee.is_member_ip(&peer_ip) {
+            true => self.max_connected_peers_with_reserved(),
+            false => self.max_connected_peers(),
+        };
+        if self.number_of_connected_peers() >= max_peers {
             bail!("Dropping connection attempt to '{peer_ip}' (maximum peers reached)")
         }
         // Ensure the node is not already connected to this peer.
@@ -230,11 +293,107 @@ impl<N: Network> Router<N> {
         &self.sync
     }
 
+    /// Returns the committee members, and their last known IPs.
+    pub fn committee(&self) -> &CommitteePeers<N> {
+        &self.committee
+    }
+
+    /// Replaces the committee members with the given addresses, and records the IPs of the connected members.
+    pub fn set_committee(&self, members: impl IntoIterator<Item = Address<N>>) {
+        self.committee.set_members(members);
+        for peer in self.connected_peers.read().values() {
+            self.committee.insert_peer(peer.address(), peer.ip());
+        }
+    }
+
+    /// Returns the tip monitor.
+    pub fn tip_monitor(&self) -> &TipMonitor {
+        &self.tip_monitor
+    }
+
+    /// Returns the scheduler of inbound block requests.
+    pub fn block_scheduler(&self) -> &BlockScheduler {
+        &self.block_scheduler
+    }
+
+    /// Returns the light gateway.
+    pub fn light_gateway(&self) -> &LightGateway {
+        &self.light_gateway
+    }
+
+    /// Returns the inbound message statistics.
+    pub fn message_stats(&self) -> &MessageStats {
+        &self.message_stats
+    }
+
+    /// Returns the middleware routes of the inbound messages.
+    pub fn message_routes(&self) -> &MessageRoutes<N> {
+        &self.message_routes
+    }
+
+    /// Returns the gossip state.
+    pub fn gossip(&self) -> &Gossip<N> {
+        &self.gossip
+    }
+
+    /// Returns the diversity constraints on the peers this node dials.
+    pub fn peer_diversity(&self) -> &PeerDiversity {
+        &self.peer_diversity
+    }
+
+    /// Returns the verifier of the signatures of the inbound messages.
+    pub fn signatures(&self) -> &SignatureVerifier {
+        &self.signatures
+    }
+
+    /// Returns the transmissions that recently failed verification.
+    pub fn blacklist(&self) -> &Blacklist<N> {
+        &self.blacklist
+    }
+
+    /// Returns the relay of the beacon proposals between validators.
+    pub fn relay(&self) -> &Relay<N> {
+        &self.relay
+    }
+
+    /// Returns `true` if the node is in standby mode, in which it stays in sync
+    /// without producing blocks (beacons) or solving the coinbase puzzle (provers).
+    pub fn is_standby(&self) -> bool {
+        self.standby.load(Ordering::Relaxed)
+    }
+
+    /// Enters or leaves the standby mode.
+    pub fn set_standby(&self, standby: bool) {
+        if self.standby.swap(standby, Ordering::Relaxed) != standby {
+            info!("{} standby mode", if standby { "Entering" } else { "Leaving" });
+        }
+    }
+
     /// Returns `true` if the node is in development mode.
     pub fn is_dev(&self) -> bool {
         self.is_dev
     }
 
+    /// Returns the compression this node offers for the block and certificate messages of its connections.
+    pub fn compression(&self) -> Compression {
+        *self.compression.read()
+    }
+
+    /// Sets the compression this node offers for the block and certificate messages of its new connections.
+    pub fn set_compression(&self, compression: Compression) {
+        *self.compression.write() = compression;
+    }
+
+    /// Returns the codec for the messages of the given (ambiguous) peer address,
+    /// with the compression negotiated on its connection.
+    pub fn codec(&self, peer_addr: SocketAddr) -> MessageCodec<N> {
+        let compression = self
+            .resolve_to_listener(&peer_addr)
+            .and_then(|peer_ip| self.connected_peers.read().get(&peer_ip).map(Peer::compression))
+            .unwrap_or_default();
+        MessageCodec::with_compression(compression)
+    }
+
     /// Returns the listener IP address from the (ambiguous) peer address.
     pub fn resolve_to_listener(&self, peer_addr: &SocketAddr) -> Option<SocketAddr> {
         self.resolver.get_listener(peer_addr)
@@ -255,6 +414,11 @@ impl<N: Network> Router<N> {
         self.connected_peers.read().get(peer_ip).map_or(false, |peer| peer.is_beacon())
     }
 
+    /// Returns `true` if the given peer IP is a connected committee member.
+    pub fn is_connected_committee_member(&self, peer_ip: &SocketAddr) -> bool {
+        self.connected_peers.read().get(peer_ip).map_or(false, |peer| self.committee.is_member(&peer.address()))
+    }
+
     /// Returns `true` if the given peer IP is a connected validator.
     pub fn is_connected_validator(&self, peer_ip: &SocketAddr) -> bool {
         self.connected_peers.read().get(peer_ip).map_or(false, |peer| peer.is_validator())
@@ -275,6 +439,11 @@ impl<N: Network> Router<N> {
         self.connecting_peers.lock().contains(ip)
     }
 
+    /// Returns `true` if a certificate range request to the given peer IP is awaiting its response.
+    pub fn is_awaiting_certificates(&self, peer_ip: &SocketAddr) -> bool {
+        self.cache.contains_outbound_certificate_request(peer_ip)
+    }
+
     /// Returns `true` if the given IP is restricted.
     pub fn is_restricted(&self, ip: &SocketAddr) -> bool {
         self.restricted_peers
@@ -284,8 +453,13 @@ impl<N: Network> Router<N> {
             .unwrap_or(false)
     }
 
-    /// Returns the maximum number of connected peers.
+    /// Returns the maximum number of connected peers, excluding the slots reserved for committee members.
     pub fn max_connected_peers(&self) -> usize {
+        self.max_connected_peers_with_reserved().saturating_sub(Self::COMMITTEE_RESERVED_SLOTS as usize)
+    }
+
+    /// Returns the maximum number of connected peers, including the slots reserved for committee members.
+    pub fn max_connected_peers_with_reserved(&self) -> usize {
         self.tcp.config().max_connections as usize
     }
 
@@ -339,6 +513,18 @@ impl<N: Network> Router<N> {
         self.connected_peers.read().keys().copied().collect()
     }
 
+    /// Returns a summary of each connected peer.
+    pub fn connected_peer_info(&self) -> Vec<PeerInfo> {
+        self.connected_peers.read().values().map(|peer| peer.info()).collect()
+    }
+
+    /// Returns the number of connected peers, by connection direction.
+    pub fn connection_count(&self) -> ConnectionCount {
+        let connected_peers = self.connected_peers.read();
+        let inbound = connected_peers.values().filter(|peer| peer.is_inbound()).count();
+        ConnectionCount { total: connected_peers.len(), inbound, outbound: connected_peers.len() - inbound }
+    }
+
     /// Returns the list of connected beacons.
     pub fn connected_beacons(&self) -> Vec<SocketAddr> {
         self.connected_peers.read().iter().filter(|(_, peer)| peer.is_beacon()).map(|(ip, _)| *ip).collect()
@@ -364,6 +550,50 @@ impl<N: Network> Router<N> {
         self.candidate_peers.read().clone()
     }
 
+    /// Returns the state of the peer announcements.
+    pub fn announcements(&self) -> &Announcements<N> {
+        &self.announcements
+    }
+
+    /// Returns the recent signed announcements of the connected peers, for the listening addresses this node
+    /// dialed and found the announcing account at, up to the maximum number of peers in a `PeerResponse`.
+    pub fn connected_announcements(&self) -> Vec<PeerAnnouncement<N>> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        self.connected_peers
+            .read()
+            .values()
+            .filter_map(|peer| peer.announcement())
+            .filter(|announcement| {
+                self.announcements.bound_account(&announcement.listener) == Some(announcement.address)
+            })
+            .filter(|announcement| now - announcement.timestamp <= Self::MAXIMUM_ANNOUNCEMENT_AGE_IN_SECS)
+            .take(PeerResponse::<N>::MAXIMUM_NUMBER_OF_PEERS as usize)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns an announcement of the listening address of this node to the given connected peer,
+    /// at the IP the peer observed for this node, if that IP is corroborated.
+    pub fn sign_announcement(&self, peer_ip: SocketAddr) -> Result<Option<PeerAnnouncement<N>>> {
+        let observed_ip = match self.announcements.corroborated_ip(self.local_ip(), &peer_ip) {
+            Some(observed_ip) => observed_ip,
+            None => return Ok(None),
+        };
+        let listener = SocketAddr::new(observed_ip, self.local_ip().port());
+        let timestamp = OffsetDateTime::now_utc().unix_timestamp();
+        let bytes = PeerAnnouncement::signed_bytes(listener, self.node_type, self.address(), timestamp)?;
+        let signature = self.account.sign_bytes(&bytes, &mut OsRng)?;
+        let node_type = self.node_type;
+        Ok(Some(PeerAnnouncement { listener, node_type, address: self.address(), timestamp, signature }))
+    }
+
+    /// Returns up to `num` candidate peers to connect to, sampled at random within the peer diversity constraints.
+    pub fn select_candidate_peers<R: Rng>(&self, num: usize, rng: &mut R) -> Vec<SocketAddr> {
+        let mut peers = self.connected_peers();
+        peers.extend(self.connecting_peers.lock().iter().copied());
+        self.peer_diversity.select_candidates(self.candidate_peers(), peers, num, rng)
+    }
+
     /// Returns the list of restricted peers.
     pub fn restricted_peers(&self) -> Vec<SocketAddr> {
         self.restricted_peers.read().keys().copied().collect()
@@ -409,6 +639,8 @@ impl<N: Network> Router<N> {
         let peer_ip = peer.ip();
         // Adds a bidirectional map between the listener address and (ambiguous) peer address.
         self.resolver.insert_peer(peer_ip, peer_addr);
+        // Record the IP of the peer, if it is a committee member.
+        self.committee.insert_peer(peer.address(), peer_ip);
         // Add an entry for this `Peer` in the connected peers.
         self.connected_peers.write().insert(peer_ip, peer);
         // Remove this peer from the candidate peers, if it exists.
@@ -437,6 +669,20 @@ impl<N: Network> Router<N> {
         self.candidate_peers.write().extend(eligible_peers);
     }
 
+    /// Inserts the listening addresses of the given verified announcements to the set of candidate peers,
+    /// skipping the addresses that are bound to another account.
+    pub fn insert_candidate_announcements(&self, announcements: &[PeerAnnouncement<N>]) {
+        let peers = announcements
+            .iter()
+            .filter(|announcement| {
+                let bound_account = self.announcements.bound_account(&announcement.listener);
+                bound_account.map_or(true, |address| address == announcement.address)
+            })
+            .map(|announcement| announcement.listener)
+            .collect::<Vec<_>>();
+        self.insert_candidate_peers(&peers);
+    }
+
     /// Inserts the given peer into the restricted peers.
     pub fn insert_restricted_peer(&self, peer_ip: SocketAddr) {
         // Remove this peer from the candidate peers, if it exists.
@@ -471,6 +717,14 @@ impl<N: Network> Router<N> {
         self.resolver.remove_peer(&peer_ip);
         // Removes the peer from the sync pool.
         self.sync.remove_peer(&peer_ip);
+        // Removes the queued block requests of the peer.
+        self.block_scheduler.remove_peer(&peer_ip);
+        // Removes the message statistics of the peer.
+        self.message_stats.remove_peer(&peer_ip);
+        // Removes the light gateway requests of the peer.
+        self.light_gateway.remove_peer(&peer_ip);
+        // Removes the observed IP and the announcement time of the peer.
+        self.announcements.remove_peer(&peer_ip);
         // Remove this peer from the connected peers, if it exists.
         self.connected_peers.write().remove(&peer_ip);
         // Add the peer to the candidate peers.
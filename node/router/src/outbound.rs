@@ -15,7 +15,7 @@
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::Router;
-use snarkos_node_messages::{BlockLocators, Message, Ping};
+use snarkos_node_messages::{BlockLocators, BlockResponse, Data, Message, Ping, TransactionAnnouncement};
 use snarkos_node_tcp::protocols::Writing;
 use snarkvm::prelude::Network;
 use std::io;
@@ -54,10 +54,19 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         if let Message::BlockRequest(request) = message {
             self.router().cache.insert_outbound_block_request(peer_ip, request);
         }
+        // If the message type is a certificate range request, add it to the cache.
+        if let Message::CertificateRangeRequest(request) = message {
+            self.router().cache.insert_outbound_certificate_request(peer_ip, request);
+        }
         // If the message type is a puzzle request, increment the cache.
         if matches!(message, Message::PuzzleRequest(_)) {
             self.router().cache.increment_outbound_puzzle_requests(peer_ip);
         }
+        // If the message type is a serialized block response, retrieve its size.
+        let num_response_bytes = match &message {
+            Message::BlockResponse(BlockResponse { blocks: Data::Buffer(bytes), .. }) => bytes.len(),
+            _ => 0,
+        };
         // Retrieve the message name.
         let name = message.name();
         // Send the message to the peer.
@@ -69,7 +78,35 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
             debug!("Disconnecting from '{peer_ip}' (unable to send)");
             self.router().disconnect(peer_ip);
         }
-        result.ok()
+        match result {
+            Ok(receiver) if num_response_bytes > 0 => {
+                Some(self.track_block_response(peer_ip, num_response_bytes, receiver))
+            }
+            result => result.ok(),
+        }
+    }
+
+    /// Records the given block response bytes as in flight to the peer, until the message is written,
+    /// and returns a receiver for the delivery of the message.
+    fn track_block_response(
+        &self,
+        peer_ip: SocketAddr,
+        num_bytes: usize,
+        receiver: oneshot::Receiver<io::Result<()>>,
+    ) -> oneshot::Receiver<io::Result<()>> {
+        let router = self.router().clone();
+        router.block_scheduler().add_outstanding_bytes(peer_ip, num_bytes);
+
+        let (sender, delivery) = oneshot::channel();
+        tokio::spawn(async move {
+            let result = receiver.await;
+            // Release the bytes, as the message is either written or dropped.
+            router.block_scheduler().release_outstanding_bytes(peer_ip, num_bytes);
+            if let Ok(result) = result {
+                let _ = sender.send(result);
+            }
+        });
+        delivery
     }
 
     /// Sends the given message to every connected peer, excluding the sender and any specified peer IPs.
@@ -97,12 +134,49 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         // }
 
         // Prepare the peers to send to.
-        let connected_peers = self.router().connected_peers();
-        let peers = connected_peers.iter().filter(|peer_ip| !excluded_peers.contains(peer_ip));
+        let mut peers = self.router().connected_peers();
+        peers.retain(|peer_ip| !excluded_peers.contains(peer_ip));
+
+        // Send the message to all peers that are not the sender and excluded peers.
+        self.gossip(message, peers);
+    }
 
-        // Iterate through all peers that are not the sender and excluded peers.
-        for peer_ip in peers {
-            self.send(*peer_ip, message.clone());
+    /// Sends the given locally-submitted message to every connected peer, excluding any specified peer IPs.
+    /// If a diffusion delay is configured, each peer receives a transaction after an independent random delay,
+    /// so that observers are unable to identify the node that originated the transaction from first-seen timing.
+    fn propagate_local(&self, message: Message<N>, excluded_peers: &[SocketAddr]) {
+        // If the message is not a transaction, or the diffusion delay is disabled, propagate it immediately.
+        let is_delayed = self.router().gossip().config().diffusion_delay_in_ms > 0;
+        let transaction = match message {
+            Message::UnconfirmedTransaction(ref transaction) if is_delayed => transaction.clone(),
+            _ => return self.propagate(message, excluded_peers),
+        };
+        let transaction_id = transaction.transaction_id;
+
+        // Prepare the peers to send to.
+        let mut peers = self.router().connected_peers();
+        peers.retain(|peer_ip| !excluded_peers.contains(peer_ip));
+
+        // Select the peers to send the full message to.
+        let (eager, lazy) = self.router().gossip().select_peers(peers);
+        // Retain the transaction to serve `TransactionRequest` messages.
+        self.router().gossip().insert_payload(transaction);
+
+        // Prepare the message for each peer.
+        let announcement = Message::TransactionAnnouncement(TransactionAnnouncement { transaction_id });
+        let messages = eager
+            .into_iter()
+            .map(|peer_ip| (peer_ip, message.clone()))
+            .chain(lazy.into_iter().map(|peer_ip| (peer_ip, announcement.clone())));
+
+        // Send the message to each peer, after an independent random delay.
+        for (peer_ip, message) in messages {
+            let delay = self.router().gossip().sample_diffusion_delay().unwrap_or_default();
+            let self_ = self.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                self_.send(peer_ip, message);
+            });
         }
     }
 
@@ -131,13 +205,11 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         // }
 
         // Prepare the peers to send to.
-        let connected_beacons = self.router().connected_beacons();
-        let peers = connected_beacons.iter().filter(|peer_ip| !excluded_peers.contains(peer_ip));
+        let mut peers = self.router().connected_beacons();
+        peers.retain(|peer_ip| !excluded_peers.contains(peer_ip));
 
-        // Iterate through all beacons that are not the sender and excluded beacons.
-        for peer_ip in peers {
-            self.send(*peer_ip, message.clone());
-        }
+        // Send the message to all beacons that are not the sender and excluded beacons.
+        self.gossip(message, peers);
     }
 
     /// Sends the given message to every connected validator, excluding the sender and any specified IPs.
@@ -165,12 +237,37 @@ pub trait Outbound<N: Network>: Writing<Message = Message<N>> {
         // }
 
         // Prepare the peers to send to.
-        let connected_validators = self.router().connected_validators();
-        let peers = connected_validators.iter().filter(|peer_ip| !excluded_peers.contains(peer_ip));
+        let mut peers = self.router().connected_validators();
+        peers.retain(|peer_ip| !excluded_peers.contains(peer_ip));
+
+        // Send the message to all validators that are not the sender and excluded validators.
+        self.gossip(message, peers);
+    }
+
+    /// Sends the given message to the given peers. Unconfirmed solutions and transactions are only sent
+    /// to the configured fanout of peers, and if lazy push is enabled, transactions are announced to the rest.
+    fn gossip(&self, message: Message<N>, peers: Vec<SocketAddr>) {
+        // Select the peers to send the full message to.
+        let (eager, lazy) = match message {
+            Message::UnconfirmedSolution(_) | Message::UnconfirmedTransaction(_) => {
+                self.router().gossip().select_peers(peers)
+            }
+            _ => (peers, vec![]),
+        };
+
+        // If the message is a transaction, retain it to serve `TransactionRequest` messages.
+        if let Message::UnconfirmedTransaction(ref message) = message {
+            self.router().gossip().insert_payload(message.clone());
+            // Announce the transaction to the lazy peers.
+            for peer_ip in lazy {
+                let transaction_id = message.transaction_id;
+                self.send(peer_ip, Message::TransactionAnnouncement(TransactionAnnouncement { transaction_id }));
+            }
+        }
 
-        // Iterate through all beacons that are not the sender and excluded beacons.
-        for peer_ip in peers {
-            self.send(*peer_ip, message.clone());
+        // Send the full message to the eager peers.
+        for peer_ip in eager {
+            self.send(peer_ip, message.clone());
         }
     }
 
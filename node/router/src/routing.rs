@@ -14,8 +14,8 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{Heartbeat, Inbound, Outbound};
-use snarkos_node_messages::Message;
+use crate::{Heartbeat, Inbound, Outbound, PendingEvent, MAX_CONCURRENT_BLOCK_RESPONSES};
+use snarkos_node_messages::{Message, TransactionRequest};
 use snarkos_node_tcp::{
     protocols::{Disconnect, Handshake},
     P2P,
@@ -23,6 +23,7 @@ use snarkos_node_tcp::{
 use snarkvm::prelude::Network;
 
 use core::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
 
 #[async_trait]
 pub trait Routing<N: Network>: P2P + Disconnect + Handshake + Inbound<N> + Outbound<N> + Heartbeat<N> {
@@ -37,6 +38,12 @@ pub trait Routing<N: Network>: P2P + Disconnect + Handshake + Inbound<N> + Outbo
         self.enable_listener().await;
         // Initialize the heartbeat.
         self.initialize_heartbeat();
+        // Initialize the committee redial.
+        self.initialize_committee_redial();
+        // Initialize the block scheduler.
+        self.initialize_block_scheduler();
+        // Initialize the pending transaction requests.
+        self.initialize_pending_requests();
         // Initialize the report.
         self.initialize_report();
     }
@@ -60,6 +67,91 @@ pub trait Routing<N: Network>: P2P + Disconnect + Handshake + Inbound<N> + Outbo
         });
     }
 
+    /// Initialize a new instance of the committee redial, which reconnects to the committee members
+    /// more frequently than the heartbeat, as losing a committee connection directly costs rounds.
+    fn initialize_committee_redial(&self) {
+        let self_clone = self.clone();
+        self.router().spawn(async move {
+            loop {
+                // Sleep for `COMMITTEE_REDIAL_IN_SECS` seconds.
+                tokio::time::sleep(Duration::from_secs(Self::COMMITTEE_REDIAL_IN_SECS)).await;
+                // Reconnect to the disconnected committee members.
+                self_clone.handle_committee_peers();
+            }
+        });
+    }
+
+    /// Initialize the workers that serve the queued block requests, in the order of the block scheduler.
+    fn initialize_block_scheduler(&self) {
+        for _ in 0..MAX_CONCURRENT_BLOCK_RESPONSES {
+            let self_clone = self.clone();
+            self.router().spawn(async move {
+                loop {
+                    // Wait for the next block request to serve.
+                    let (peer_ip, request) = self_clone.router().block_scheduler().next().await;
+                    // Serve the block request, as retrieving and serializing the blocks is blocking.
+                    let node = self_clone.clone();
+                    match tokio::task::spawn_blocking(move || node.block_request(peer_ip, request)).await {
+                        Ok(true) => (),
+                        Ok(false) => {
+                            warn!("Disconnecting from '{peer_ip}' - Peer sent an invalid block request");
+                            self_clone.router().disconnect(peer_ip);
+                        }
+                        Err(error) => error!("Failed to serve the block request from '{peer_ip}' - {error}"),
+                    }
+                }
+            });
+        }
+    }
+
+    /// Initialize the workers that expire the pending transaction requests, and re-request
+    /// the expired transactions from the other peers that announced them.
+    fn initialize_pending_requests(&self) {
+        let self_clone = self.clone();
+        self.router().spawn(async move {
+            loop {
+                // Sleep for a second.
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                // Expire the pending requests that passed their deadline.
+                self_clone.router().gossip().expire_requests();
+            }
+        });
+
+        let self_clone = self.clone();
+        let mut events = self.router().gossip().subscribe_pending();
+        self.router().spawn(async move {
+            loop {
+                let (transaction_id, peers, attempts) = match events.recv().await {
+                    Ok(PendingEvent::Expired { transaction_id, peers, attempts }) => (transaction_id, peers, attempts),
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(num_skipped)) => {
+                        warn!("Skipped {num_skipped} pending request events");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                // Re-request the transaction from the next connected peer that announced it.
+                let mut peers = peers.into_iter().filter(|peer_ip| self_clone.router().is_connected(peer_ip));
+                let peer_ip = match peers.next() {
+                    Some(peer_ip) => peer_ip,
+                    None => {
+                        trace!("Dropping the request for transaction '{transaction_id}' (no other peers)");
+                        continue;
+                    }
+                };
+                // Retry with a backed off deadline, unless the attempts are exhausted.
+                let attempts = attempts + 1;
+                match self_clone.router().gossip().retry_request(transaction_id, peer_ip, peers.collect(), attempts) {
+                    true => {
+                        trace!("Re-requesting transaction '{transaction_id}' from '{peer_ip}' (attempt {attempts})");
+                        self_clone.send(peer_ip, Message::TransactionRequest(TransactionRequest { transaction_id }));
+                    }
+                    false => trace!("Dropping the request for transaction '{transaction_id}' (attempts exhausted)"),
+                }
+            }
+        });
+    }
+
     /// Initialize a new instance of the report.
     fn initialize_report(&self) {
         let self_clone = self.clone();
@@ -0,0 +1,115 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+mod common;
+use common::*;
+
+use snarkos_account::Account;
+use snarkos_node_messages::{NodeType, PeerAnnouncement};
+use snarkos_node_router::{Inbound, Router};
+use snarkos_node_tcp::{protocols::Handshake, P2P};
+use snarkvm::prelude::Testnet3 as CurrentNetwork;
+use snarkvm_utilities::TestRng;
+
+use core::time::Duration;
+use deadline::deadline;
+use std::{net::SocketAddr, str::FromStr};
+use time::OffsetDateTime;
+
+/// Returns an announcement of the given listening address by the given account, signed at the given timestamp.
+fn sample_announcement(
+    account: &Account<CurrentNetwork>,
+    listener: SocketAddr,
+    timestamp: i64,
+    rng: &mut TestRng,
+) -> PeerAnnouncement<CurrentNetwork> {
+    let bytes = PeerAnnouncement::signed_bytes(listener, NodeType::Client, account.address(), timestamp).unwrap();
+    let signature = account.sign_bytes(&bytes, rng).unwrap();
+    PeerAnnouncement { listener, node_type: NodeType::Client, address: account.address(), timestamp, signature }
+}
+
+#[tokio::test]
+async fn test_announcement_binding() {
+    // Create 2 routers.
+    let node0 = validator(0, 2).await;
+    let node1 = client(0, 2).await;
+
+    // Enable handshake protocol.
+    node0.enable_handshake().await;
+    node1.enable_handshake().await;
+
+    // Start listening.
+    node0.tcp().enable_listener().await.unwrap();
+    node1.tcp().enable_listener().await.unwrap();
+
+    // Connect node0 to node1.
+    node0.connect(node1.local_ip());
+
+    // Ensure node0 relays the announcement of node1, once it is received.
+    let node0_ = node0.clone();
+    deadline!(Duration::from_secs(5), move || node0_.connected_announcements().len() == 1);
+    let announcement = node0.connected_announcements().remove(0);
+    assert_eq!(announcement.listener, node1.local_ip());
+    assert_eq!(announcement.address, node1.address());
+
+    // Ensure node0 bound the address it dialed to the account of node1.
+    assert_eq!(node0.announcements().bound_account(&node1.local_ip()), Some(node1.address()));
+
+    // Ensure node1 neither binds nor relays the address of node0, which it did not dial.
+    let (node1_, node0_ip) = (node1.clone(), node0.local_ip());
+    deadline!(Duration::from_secs(5), move || {
+        node1_.get_connected_peer(&node0_ip).map_or(false, |peer| peer.announcement().is_some())
+    });
+    assert_eq!(node1.announcements().bound_account(&node0.local_ip()), None);
+    assert!(node1.connected_announcements().is_empty());
+}
+
+#[tokio::test]
+async fn test_relayed_announcements() {
+    let rng = &mut TestRng::default();
+
+    // Create a router.
+    let node = client(0, 2).await;
+    node.tcp().enable_listener().await.unwrap();
+    let relayer = SocketAddr::from_str("1.1.1.1:4133").unwrap();
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+
+    // Ensure a recent announcement adds a candidate peer.
+    let account = Account::new(rng).unwrap();
+    let listener = SocketAddr::from_str("1.2.3.4:4133").unwrap();
+    assert!(node.peer_response(relayer, &[sample_announcement(&account, listener, now, rng)]).await);
+    assert!(node.candidate_peers().contains(&listener));
+
+    // Ensure a stale announcement is skipped, without rejecting the message.
+    let listener = SocketAddr::from_str("1.2.3.5:4133").unwrap();
+    let stale = now - Router::<CurrentNetwork>::MAXIMUM_ANNOUNCEMENT_AGE_IN_SECS - 1;
+    assert!(node.peer_response(relayer, &[sample_announcement(&account, listener, stale, rng)]).await);
+    assert!(!node.candidate_peers().contains(&listener));
+
+    // Ensure an announcement from the future, or with an invalid signature, rejects the message.
+    let listener = SocketAddr::from_str("1.2.3.6:4133").unwrap();
+    assert!(!node.peer_response(relayer, &[sample_announcement(&account, listener, now + 120, rng)]).await);
+    let mut forged = sample_announcement(&account, listener, now, rng);
+    forged.timestamp -= 1;
+    assert!(!node.peer_response(relayer, &[forged]).await);
+    assert!(!node.candidate_peers().contains(&listener));
+
+    // Ensure an address bound to another account cannot be claimed by a relayed announcement.
+    let listener = SocketAddr::from_str("1.2.3.7:4133").unwrap();
+    node.announcements().bind(listener, Account::<CurrentNetwork>::new(rng).unwrap().address());
+    assert!(node.peer_response(relayer, &[sample_announcement(&account, listener, now, rng)]).await);
+    assert!(!node.candidate_peers().contains(&listener));
+}
@@ -111,6 +111,10 @@ async fn test_connect_with_handshake() {
         // Check the router level.
         assert_eq!(node0.number_of_connected_peers(), 1);
         assert_eq!(node1.number_of_connected_peers(), 1);
+
+        // Check the connection directions.
+        assert_eq!(node0.connection_count().outbound, 1);
+        assert_eq!(node1.connection_count().inbound, 1);
     }
     {
         // Connect node0 to node1 again.
@@ -0,0 +1,39 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+mod common;
+use common::*;
+
+#[tokio::test]
+async fn test_standby() {
+    // Create a prover and a beacon, which stop their work in standby mode.
+    let prover = prover(0, 1).await;
+    let beacon = beacon(0, 1).await;
+    assert!(!prover.is_standby());
+    assert!(!beacon.is_standby());
+
+    // Ensure the standby mode is entered and left, and is repeatable.
+    prover.set_standby(true);
+    assert!(prover.is_standby());
+    prover.set_standby(true);
+    assert!(prover.is_standby());
+
+    // Ensure the standby mode is local to each node.
+    assert!(!beacon.is_standby());
+
+    prover.set_standby(false);
+    assert!(!prover.is_standby());
+}
@@ -18,10 +18,12 @@ mod router;
 
 use crate::traits::NodeInterface;
 use snarkos_account::Account;
-use snarkos_node_consensus::Consensus;
+use snarkos_node_cdn::EraReader;
+use snarkos_node_consensus::{BlockPipeline, Consensus, PipelineInput, RoundTimings, TransmissionClass, WorkerConfig};
 use snarkos_node_ledger::{Ledger, RecordMap};
 use snarkos_node_messages::{
     BeaconPropose,
+    Compression,
     Data,
     Message,
     NodeType,
@@ -29,8 +31,9 @@ use snarkos_node_messages::{
     UnconfirmedSolution,
     UnconfirmedTransaction,
 };
-use snarkos_node_rest::Rest;
+use snarkos_node_rest::{Rest, RestConfig};
 use snarkos_node_router::{Heartbeat, Inbound, Outbound, Router, Routing};
+use snarkos_node_store::Indexes;
 use snarkos_node_tcp::{
     protocols::{Disconnect, Handshake, Reading, Writing},
     P2P,
@@ -53,15 +56,37 @@ use ::time::OffsetDateTime;
 use aleo_std::prelude::{finish, lap, timer};
 use anyhow::{bail, Result};
 use core::{str::FromStr, time::Duration};
+use futures_util::stream::{FuturesUnordered, StreamExt};
 use parking_lot::{Mutex, RwLock};
 use std::{
+    io,
     net::SocketAddr,
+    path::PathBuf,
     sync::{
         atomic::{AtomicBool, AtomicU64, Ordering},
         Arc,
     },
 };
-use tokio::{task::JoinHandle, time::timeout};
+use tokio::{sync::oneshot, task::JoinHandle, time::timeout};
+
+/// The maximum duration in milliseconds to wait for a produced block to be written to the connected beacons.
+const PROPAGATION_TIMEOUT_IN_MS: u64 = 5_000;
+
+/// Waits until each of the given writes completes, or until the timeout elapses,
+/// and returns the number of writes that succeeded.
+async fn await_delivery(writes: Vec<oneshot::Receiver<io::Result<()>>>, duration: Duration) -> usize {
+    let mut writes = writes.into_iter().collect::<FuturesUnordered<_>>();
+    let mut num_delivered = 0;
+    let _ = timeout(duration, async {
+        while let Some(result) = writes.next().await {
+            if let Ok(Ok(())) = result {
+                num_delivered += 1;
+            }
+        }
+    })
+    .await;
+    num_delivered
+}
 
 /// A beacon is a full node, capable of producing blocks.
 #[derive(Clone)]
@@ -74,8 +99,12 @@ pub struct Beacon<N: Network, C: ConsensusStorage<N>> {
     consensus: Consensus<N, C>,
     /// The router of the node.
     router: Router<N>,
+    /// The block pipeline of the node.
+    pipeline: Arc<BlockPipeline>,
     /// The REST server of the node.
     rest: Option<Rest<N, C, Self>>,
+    /// The era files from which the blocks requested by peers are served, if set.
+    era_reader: Arc<RwLock<Option<EraReader>>>,
     /// The time it to generate a block.
     block_generation_time: Arc<AtomicU64>,
     /// The unspent records.
@@ -88,13 +117,18 @@ pub struct Beacon<N: Network, C: ConsensusStorage<N>> {
 
 impl<N: Network, C: ConsensusStorage<N>> Beacon<N, C> {
     /// Initializes a new beacon node.
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         node_ip: SocketAddr,
         rest_ip: Option<SocketAddr>,
+        rest_config: RestConfig,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
         genesis: Block<N>,
         cdn: Option<String>,
+        assume_valid: Option<(u32, N::BlockHash)>,
+        compression: Compression,
+        state_dir: PathBuf,
         dev: Option<u16>,
     ) -> Result<Self> {
         let timer = timer!("Beacon::new");
@@ -115,6 +149,19 @@ impl<N: Network, C: ConsensusStorage<N>> Beacon<N, C> {
 
         // Initialize the consensus.
         let consensus = Consensus::new(ledger.clone(), dev.is_some())?;
+        // Set the assume-valid checkpoint, if one is provided.
+        if let Some((height, hash)) = assume_valid {
+            consensus.set_assume_valid(height, hash)?;
+        }
+        // Load the equivocation evidence, and persist any new evidence to the state directory.
+        let evidence_path = state_dir.join("equivocations.jsonl");
+        consensus.equivocations().open(evidence_path)?;
+        // Load the history of changes to the beacon set, and persist any new changes to the state directory.
+        consensus.committee_history().open(state_dir.join("committee.jsonl"))?;
+        // Load the blocks proposed in recent rounds, and persist any new proposals to the state directory.
+        consensus.proposals().open(state_dir.join("proposals"))?;
+        // Start the workers that process the unconfirmed transmissions.
+        consensus.set_worker_config(WorkerConfig::default())?;
         lap!(timer, "Initialize consensus");
 
         // Initialize the block generation time.
@@ -133,15 +180,38 @@ impl<N: Network, C: ConsensusStorage<N>> Beacon<N, C> {
             dev.is_some(),
         )
         .await?;
+        // Offer the compression for the block and certificate messages to the peers.
+        router.set_compression(compression);
+        // Load the recently blacklisted transmissions, and persist any new entries to the state directory.
+        router.blacklist().open(state_dir.join("blacklist.jsonl"))?;
+        // Load the fork history, and persist any new or refined forks to the state directory.
+        router.sync().open_fork_history(state_dir.join("forks.jsonl"))?;
         lap!(timer, "Initialize the router");
 
+        // Set the committee, to keep the committee members connected.
+        router.set_committee(consensus.beacons().into_keys());
+
+        // Initialize the block pipeline, which advances the ledger with the blocks from the sync pool.
+        let (source, canon, committee) = (router.clone(), router.clone(), consensus.clone());
+        let pipeline = BlockPipeline::new(
+            consensus.clone(),
+            move |height| source.sync().remove_block_response(height).map(PipelineInput::Decoded),
+            move |block: &Block<N>| {
+                canon.sync().insert_canon_locator(block.height(), block.hash());
+                canon.set_committee(committee.beacons().into_keys());
+            },
+        )?;
+        lap!(timer, "Initialize the block pipeline");
+
         // Initialize the node.
         let mut node = Self {
             account,
             ledger: ledger.clone(),
             consensus: consensus.clone(),
             router,
+            pipeline: Arc::new(pipeline),
             rest: None,
+            era_reader: Default::default(),
             block_generation_time,
             unspent_records: Arc::new(RwLock::new(unspent_records)),
             handles: Default::default(),
@@ -150,7 +220,7 @@ impl<N: Network, C: ConsensusStorage<N>> Beacon<N, C> {
 
         // Initialize the REST server.
         if let Some(rest_ip) = rest_ip {
-            node.rest = Some(Rest::start(rest_ip, Some(consensus), ledger, Arc::new(node.clone()))?);
+            node.rest = Some(Rest::start(rest_ip, rest_config, Some(consensus), ledger, Arc::new(node.clone()), dev)?);
             lap!(timer, "Initialize REST server");
         }
         // Initialize the routing.
@@ -171,14 +241,60 @@ impl<N: Network, C: ConsensusStorage<N>> Beacon<N, C> {
         &self.ledger
     }
 
+    /// Returns the consensus module.
+    pub fn consensus(&self) -> &Consensus<N, C> {
+        &self.consensus
+    }
+
     /// Returns the REST server.
     pub fn rest(&self) -> &Option<Rest<N, C, Self>> {
         &self.rest
     }
+
+    /// Sets the era files from which the blocks requested by peers are served, wherever they cover the request.
+    pub fn set_era_reader(&self, era_reader: EraReader) {
+        *self.era_reader.write() = Some(era_reader);
+    }
+
+    /// Initializes the secondary indexes, and keeps them up to date with the ledger.
+    pub fn initialize_indexes(&self, dev: Option<u16>) -> Result<()> {
+        let indexes = Arc::new(Indexes::open(dev)?);
+        // Serve the indexes from the REST server.
+        if let Some(rest) = &self.rest {
+            rest.set_indexes(indexes.clone());
+        }
+        let subscriber = self.consensus.subscriptions().subscribe_blocks();
+        let ledger = self.ledger.clone();
+        self.handles.lock().push(tokio::spawn(crate::helpers::run_indexer(ledger, indexes, subscriber)));
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Beacon<N, C> {
+    /// Returns the number of unconfirmed transactions and solutions in the memory pool.
+    fn memory_pool_size(&self) -> Option<(usize, usize)> {
+        let memory_pool = self.consensus.memory_pool();
+        Some((memory_pool.num_unconfirmed_transactions(), memory_pool.num_unconfirmed_solutions()))
+    }
+
+    /// Returns the number of watched addresses, and the total number of changes observed.
+    fn watch_list_size(&self) -> Option<(usize, u64)> {
+        let watch_list = self.consensus.watch_list();
+        Some((watch_list.num_addresses(), watch_list.num_changes()))
+    }
+
+    /// Returns the number of REST requests rejected for exceeding the rate limit and the maximum body size.
+    fn rest_rejections(&self) -> Option<(u64, u64)> {
+        self.rest.as_ref().map(|rest| (rest.limiter().num_rate_limited(), rest.limiter().num_oversized()))
+    }
+
+    /// Returns the saturation of the fullest transmission worker queue, and the number of dropped transmissions.
+    fn transmission_backpressure(&self) -> Option<(f64, u64)> {
+        let workers = self.consensus.workers();
+        Some((workers.saturation(), workers.num_dropped()))
+    }
+
     /// Shuts down the node.
     async fn shut_down(&self) {
         info!("Shutting down...");
@@ -191,6 +307,10 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Beacon<N, C> {
         trace!("Shutting down the beacon...");
         self.handles.lock().iter().for_each(|handle| handle.abort());
 
+        // Shut down the block pipeline.
+        trace!("Shutting down the block pipeline...");
+        self.pipeline.shut_down();
+
         // Shut down the router.
         self.router.shut_down().await;
 
@@ -221,19 +341,27 @@ impl<N: Network, C: ConsensusStorage<N>> Beacon<N, C> {
     async fn initialize_block_production(&self) {
         let beacon = self.clone();
         self.handles.lock().push(tokio::spawn(async move {
-            // Expected time per block.
-            const ROUND_TIME: u64 = 15; // 15 seconds per block
-
             // Produce blocks.
             loop {
+                // If the node is in standby, stay in sync without producing blocks.
+                if beacon.router.is_standby() {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    if beacon.shutdown.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    continue;
+                }
+
                 // Fetch the current timestamp.
                 let current_timestamp = OffsetDateTime::now_utc().unix_timestamp();
                 // Compute the elapsed time.
                 let elapsed_time = current_timestamp.saturating_sub(beacon.ledger.latest_timestamp()) as u64;
 
-                // Do not produce a block if the elapsed time has not exceeded `ROUND_TIME - block_generation_time`.
-                // This will ensure a block is produced at intervals of approximately `ROUND_TIME`.
-                let time_to_wait = ROUND_TIME.saturating_sub(beacon.block_generation_time.load(Ordering::Acquire));
+                // Retrieve the expected time per block, which adapts to the latency of the recent rounds.
+                let round_time = beacon.consensus.batch().timeout_in_secs();
+                // Do not produce a block if the elapsed time has not exceeded `round_time - block_generation_time`.
+                // This will ensure a block is produced at intervals of approximately `round_time`.
+                let time_to_wait = round_time.saturating_sub(beacon.block_generation_time.load(Ordering::Acquire));
                 trace!("Waiting for {time_to_wait} seconds before producing a block...");
                 if elapsed_time < time_to_wait {
                     if let Err(error) = timeout(
@@ -266,6 +394,8 @@ impl<N: Network, C: ConsensusStorage<N>> Beacon<N, C> {
 
     /// Produces the next block and propagates it to all peers.
     async fn produce_next_block(&self) -> Result<()> {
+        // Start a timer for the preparation of the memory pool.
+        let timer = std::time::Instant::now();
         let mut beacon_transaction: Option<Transaction<N>> = None;
 
         // Produce a transaction if the mempool is empty.
@@ -346,22 +476,36 @@ impl<N: Network, C: ConsensusStorage<N>> Beacon<N, C> {
             }
         }
 
+        let preparation_ms = timer.elapsed().as_millis() as u64;
+
         // Propose the next block.
         let beacon = self.clone();
-        let next_block = match tokio::task::spawn_blocking(move || {
+        let (next_block, proposal_ms, verification_ms, commit_ms) = match tokio::task::spawn_blocking(move || {
+            let timer = std::time::Instant::now();
             let next_block = beacon.consensus.propose_next_block(beacon.private_key(), &mut rand::thread_rng())?;
+            let proposal_ms = timer.elapsed().as_millis() as u64;
 
             // Ensure the block is a valid next block.
-            if let Err(error) = beacon.consensus.check_next_block(&next_block) {
+            let timer = std::time::Instant::now();
+            let result = beacon.consensus.check_next_block(&next_block);
+            let verification_ms = timer.elapsed().as_millis() as u64;
+            if let Err(error) = result {
+                // Drop the proposal, if it was re-proposed from the cache, so the next attempt proposes a new block.
+                beacon.consensus.proposals().remove(&next_block);
                 // Clear the memory pool of all solutions and transactions.
                 trace!("Clearing the memory pool...");
                 beacon.consensus.clear_memory_pool()?;
                 trace!("Cleared the memory pool");
                 bail!("Proposed an invalid block: {error}")
             }
+            // Persist the proposal before it leaves the beacon, so a restart never signs another block in the round.
+            beacon.consensus.proposals().insert(&next_block)?;
 
             // Advance to the next block.
-            match beacon.consensus.advance_to_next_block(&next_block) {
+            let timer = std::time::Instant::now();
+            let result = beacon.consensus.advance_to_next_block(&next_block);
+            let commit_ms = timer.elapsed().as_millis() as u64;
+            match result {
                 Ok(()) => {
                     // If the beacon produced a transaction, save its output records.
                     if let Some(transaction) = beacon_transaction {
@@ -393,6 +537,8 @@ impl<N: Network, C: ConsensusStorage<N>> Beacon<N, C> {
                     }
                 }
                 Err(error) => {
+                    // Drop the proposal, as it never left the beacon, so the next attempt proposes a new block.
+                    beacon.consensus.proposals().remove(&next_block);
                     // Clear the memory pool of all solutions and transactions.
                     trace!("Clearing the memory pool...");
                     beacon.consensus.clear_memory_pool()?;
@@ -401,11 +547,11 @@ impl<N: Network, C: ConsensusStorage<N>> Beacon<N, C> {
                 }
             }
 
-            Ok(next_block)
+            Ok((next_block, proposal_ms, verification_ms, commit_ms))
         })
         .await
         {
-            Ok(Ok(next_block)) => next_block,
+            Ok(Ok(result)) => result,
             Ok(Err(error)) => {
                 // Sleep for one second.
                 tokio::time::sleep(Duration::from_secs(1)).await;
@@ -447,9 +593,11 @@ impl<N: Network, C: ConsensusStorage<N>> Beacon<N, C> {
         // }
 
         // Prepare the message.
+        let timer = std::time::Instant::now();
         let next_block_round = next_block.round();
         let next_block_height = next_block.height();
         let next_block_hash = next_block.hash();
+        let num_transactions = next_block.transactions().len();
 
         // Serialize the block ahead of time to not do it for each peer.
         let serialized_block = match Data::Object(next_block).serialize().await {
@@ -465,8 +613,46 @@ impl<N: Network, C: ConsensusStorage<N>> Beacon<N, C> {
             serialized_block,
         ));
 
-        // Propagate the block to all beacons.
-        self.propagate_to_beacons(message, &[]);
+        // Write the block to the connected beacons, and wait for the writes to complete, to measure its delivery.
+        let writes = self
+            .router
+            .connected_beacons()
+            .into_iter()
+            .filter_map(|peer_ip| self.send(peer_ip, message.clone()))
+            .collect::<Vec<_>>();
+        let num_beacons = writes.len();
+        let num_delivered = await_delivery(writes, Duration::from_millis(PROPAGATION_TIMEOUT_IN_MS)).await;
+        // Send the block to the connected validators, which relay it to the other validators.
+        for peer_ip in self.router.connected_validators() {
+            self.send(peer_ip, message.clone());
+        }
+
+        // Report the timings of the round.
+        let timings = RoundTimings {
+            round: next_block_round,
+            height: next_block_height,
+            num_transactions,
+            preparation_ms,
+            proposal_ms,
+            verification_ms,
+            commit_ms,
+            propagation_ms: timer.elapsed().as_millis() as u64,
+        };
+        debug!(
+            "Round {} (block {}): prepared in {}ms, proposed in {}ms, checked in {}ms, committed in {}ms, delivered to {num_delivered}/{num_beacons} beacons in {}ms",
+            timings.round,
+            timings.height,
+            timings.preparation_ms,
+            timings.proposal_ms,
+            timings.verification_ms,
+            timings.commit_ms,
+            timings.propagation_ms
+        );
+        // Adapt the batch size and batch timeout to the latency of the round.
+        if let Some(limits) = self.consensus.batch().observe(&timings) {
+            debug!("Adjusted the batch size to {} transactions, every {} seconds", limits.size, limits.timeout_in_secs);
+        }
+        self.consensus.subscriptions().notify_round(timings);
 
         Ok(())
     }
@@ -475,6 +661,7 @@ impl<N: Network, C: ConsensusStorage<N>> Beacon<N, C> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use snarkos_node_store::StorageLayout;
     use snarkvm::prelude::{ConsensusMemory, ConsensusStore, Testnet3, VM};
 
     use rand::SeedableRng;
@@ -482,6 +669,35 @@ mod tests {
 
     type CurrentNetwork = Testnet3;
 
+    #[tokio::test]
+    async fn test_await_delivery() {
+        // Ensure the completed writes are counted, and the failed writes are not.
+        let (sender0, receiver0) = oneshot::channel();
+        let (sender1, receiver1) = oneshot::channel();
+        let (sender2, receiver2) = oneshot::channel::<io::Result<()>>();
+        sender0.send(Ok(())).unwrap();
+        sender1.send(Err(io::ErrorKind::BrokenPipe.into())).unwrap();
+        drop(sender2);
+        let writes = vec![receiver0, receiver1, receiver2];
+        assert_eq!(await_delivery(writes, Duration::from_secs(1)).await, 1);
+
+        // Ensure a pending write is waited for, until it completes.
+        let (sender, receiver) = oneshot::channel();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            sender.send(Ok(())).unwrap();
+        });
+        let start = std::time::Instant::now();
+        assert_eq!(await_delivery(vec![receiver], Duration::from_secs(5)).await, 1);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        // Ensure a stalled write is abandoned at the timeout.
+        let (_sender, receiver) = oneshot::channel();
+        let start = std::time::Instant::now();
+        assert_eq!(await_delivery(vec![receiver], Duration::from_millis(50)).await, 0);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
     /// Use `RUST_MIN_STACK=67108864 cargo test --release profiler --features timer` to run this test.
     #[ignore]
     #[tokio::test]
@@ -505,10 +721,14 @@ mod tests {
         let beacon = Beacon::<CurrentNetwork, ConsensusMemory<CurrentNetwork>>::new(
             node,
             Some(rest),
+            Default::default(),
             beacon_account,
             &[],
             genesis,
             None,
+            None,
+            Default::default(),
+            StorageLayout::default().state_dir(CurrentNetwork::ID, dev),
             dev,
         )
         .await
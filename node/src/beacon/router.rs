@@ -17,18 +17,25 @@
 use super::*;
 
 use snarkos_node_messages::{
+    AncestorRequest,
+    AncestorResponse,
+    BeaconPropose,
     BlockRequest,
     BlockResponse,
     DataBlocks,
     DisconnectReason,
+    HeaderRequest,
+    HeaderResponse,
+    MemoryPoolResponse,
     Message,
     MessageCodec,
     Ping,
     Pong,
+    UnconfirmedTransmission,
 };
 use snarkos_node_router::Routing;
 use snarkos_node_tcp::{Connection, ConnectionSide, Tcp};
-use snarkvm::prelude::{error, EpochChallenge, Header};
+use snarkvm::prelude::{error, EpochChallenge, Header, ToBytes};
 
 use futures_util::sink::SinkExt;
 use std::{io, net::SocketAddr};
@@ -86,8 +93,8 @@ impl<N: Network, C: ConsensusStorage<N>> Writing for Beacon<N, C> {
 
     /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
     /// The `side` parameter indicates the connection side **from the node's perspective**.
-    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router.codec(peer_addr)
     }
 }
 
@@ -98,8 +105,14 @@ impl<N: Network, C: ConsensusStorage<N>> Reading for Beacon<N, C> {
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router.codec(peer_addr)
+    }
+
+    /// Returns `true` if the message is an unconfirmed transmission, which is dropped when the queue of
+    /// the connection is full. The reading of the other messages waits for room in the queue instead.
+    fn is_droppable(&self, message: &Self::Message) -> bool {
+        message.is_transmission()
     }
 
     /// Processes a message received from the network.
@@ -132,20 +145,93 @@ impl<N: Network, C: ConsensusStorage<N>> Outbound<N> for Beacon<N, C> {
     }
 }
 
+impl<N: Network, C: ConsensusStorage<N>> Beacon<N, C> {
+    /// Adds the unconfirmed solution to the memory pool, and propagates the solution to all connected beacons.
+    /// Returns `true` if the solution was added to the memory pool.
+    fn process_unconfirmed_solution(
+        &self,
+        peer_ip: SocketAddr,
+        serialized: UnconfirmedSolution<N>,
+        solution: ProverSolution<N>,
+    ) -> bool {
+        // Add the unconfirmed solution to the memory pool.
+        if let Err(error) = self.consensus.add_unconfirmed_solution(&solution) {
+            trace!("[UnconfirmedSolution] {error}");
+            // Blacklist the solution, unless it was rejected as a duplicate.
+            let commitment = solution.commitment();
+            let is_duplicate = self.consensus.memory_pool().contains_unconfirmed_solution(commitment)
+                || self.ledger.contains_puzzle_commitment(&commitment).unwrap_or(true);
+            if !is_duplicate {
+                self.router().blacklist().insert_solution(commitment, error.to_string());
+            }
+            return false;
+        }
+        let message = Message::UnconfirmedSolution(serialized);
+        // Propagate the "UnconfirmedSolution" to the connected beacons.
+        self.propagate_to_beacons(message, &[peer_ip]);
+        true
+    }
+
+    /// Adds the unconfirmed transaction to the memory pool, and propagates the transaction to all connected beacons.
+    /// Returns `true` if the transaction was added to the memory pool.
+    fn process_unconfirmed_transaction(
+        &self,
+        peer_ip: SocketAddr,
+        serialized: UnconfirmedTransaction<N>,
+        transaction: Transaction<N>,
+    ) -> bool {
+        // Add the unconfirmed transaction to the memory pool.
+        let transaction_id = transaction.id();
+        if let Err(error) = self.consensus.add_unconfirmed_transaction(transaction) {
+            trace!("[UnconfirmedTransaction] {error}");
+            // Blacklist the transaction, unless it was rejected as a duplicate.
+            let is_duplicate = self.consensus.memory_pool().contains_unconfirmed_transaction(transaction_id)
+                || self.ledger.contains_transaction_id(&transaction_id).unwrap_or(true);
+            if !is_duplicate {
+                self.router().blacklist().insert_transaction(transaction_id, error.to_string());
+            }
+            return false;
+        }
+        let message = Message::UnconfirmedTransaction(serialized);
+        // Propagate the "UnconfirmedTransaction" to the connected beacons.
+        self.propagate_to_beacons(message, &[peer_ip]);
+        true
+    }
+}
+
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Beacon<N, C> {
+    /// Handles a `BeaconPropose` message.
+    fn beacon_propose(&self, peer_ip: SocketAddr, _serialized: BeaconPropose<N>, block: Block<N>) -> bool {
+        // Check the proposal for an equivocation by its signer.
+        if let Some(evidence) = self.consensus.check_equivocation(&block) {
+            warn!("Peer '{peer_ip}' sent a proposal that equivocates in round {}", evidence.round);
+        }
+        // TODO (howardwu): Handle the block proposal.
+        false
+    }
+
     /// Retrieves the blocks within the block request range, and returns the block response to the peer.
     fn block_request(&self, peer_ip: SocketAddr, message: BlockRequest) -> bool {
         let BlockRequest { start_height, end_height } = &message;
 
-        // Retrieve the blocks within the requested range.
-        let blocks = match self.ledger.get_blocks(*start_height..*end_height) {
-            Ok(blocks) => Data::Object(DataBlocks(blocks)),
+        // Retrieve the blocks within the requested range, from the era files if they cover it.
+        let era_reader = self.era_reader.read().clone();
+        let blocks = match crate::helpers::get_blocks(&self.ledger, era_reader.as_ref(), *start_height..*end_height) {
+            Ok(blocks) => blocks,
             Err(error) => {
                 error!("Failed to retrieve blocks {start_height} to {end_height} from the ledger - {error}");
                 return false;
             }
         };
+        // Serialize the blocks, so that the block scheduler can account for the size of the response.
+        let blocks = match DataBlocks(blocks).to_bytes_le() {
+            Ok(bytes) => Data::Buffer(bytes.into()),
+            Err(error) => {
+                error!("Failed to serialize blocks {start_height} to {end_height} - {error}");
+                return false;
+            }
+        };
         // Send the `BlockResponse` message to the peer.
         self.send(peer_ip, Message::BlockResponse(BlockResponse { request: message, blocks }));
         true
@@ -155,31 +241,16 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Beacon<N, C> {
     fn block_response(&self, peer_ip: SocketAddr, blocks: Vec<Block<N>>) -> bool {
         // Insert the candidate blocks into the sync pool.
         for block in blocks {
+            // Check the block for an equivocation by its signer.
+            self.consensus.check_equivocation(&block);
             if let Err(error) = self.router().sync().insert_block_response(peer_ip, block) {
                 warn!("{error}");
                 return false;
             }
         }
 
-        // Retrieve the latest block height.
-        let mut latest_height = self.ledger.latest_height();
-        // Try to advance the ledger with the sync pool.
-        while let Some(block) = self.router().sync().remove_block_response(latest_height + 1) {
-            // Check the next block.
-            if let Err(error) = self.consensus.check_next_block(&block) {
-                warn!("The next block ({}) is invalid - {error}", block.height());
-                break;
-            }
-            // Attempt to advance to the next block.
-            if let Err(error) = self.consensus.advance_to_next_block(&block) {
-                warn!("{error}");
-                break;
-            }
-            // Insert the height and hash as canon in the sync pool.
-            self.router().sync().insert_canon_locator(block.height(), block.hash());
-            // Increment the latest height.
-            latest_height += 1;
-        }
+        // Wake the block pipeline to advance with blocks from the sync pool.
+        self.pipeline.notify();
         true
     }
 
@@ -203,6 +274,100 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Beacon<N, C> {
         true
     }
 
+    /// Retrieves the block hashes at the heights of the ancestor request, and returns the ancestor response to the peer.
+    fn ancestor_request(&self, peer_ip: SocketAddr, message: AncestorRequest) -> bool {
+        // Retrieve the block hashes at the requested heights.
+        let hashes = match message
+            .heights()
+            .into_iter()
+            .map(|height| Ok((height, self.ledger.get_hash(height)?)))
+            .collect::<Result<Vec<_>>>()
+        {
+            Ok(hashes) => hashes,
+            Err(error) => {
+                let (start, end) = (message.start_height, message.end_height);
+                error!("Failed to retrieve the block hashes from {start} to {end} from the ledger - {error}");
+                return false;
+            }
+        };
+        // Send the `AncestorResponse` message to the peer.
+        self.send(peer_ip, Message::AncestorResponse(AncestorResponse { request: message, hashes }));
+        true
+    }
+
+    /// Narrows down the common ancestor with the peer, and sends the next ancestor request, if needed.
+    fn ancestor_response(&self, peer_ip: SocketAddr, message: AncestorResponse<N>) -> bool {
+        let canon_hash = |height| self.ledger.get_hash(height).ok();
+        match self.router().sync().process_ancestor_response(peer_ip, message, canon_hash) {
+            Ok(Some(request)) => {
+                self.send(peer_ip, Message::AncestorRequest(request));
+                true
+            }
+            Ok(None) => true,
+            Err(error) => {
+                warn!("{error}");
+                false
+            }
+        }
+    }
+
+    /// Retrieves the block certificates within the requested rounds, and returns the certificate range response to the peer.
+    fn certificate_range_request(&self, peer_ip: SocketAddr, message: CertificateRangeRequest) -> bool {
+        let certificates = match crate::helpers::get_block_certificates(&self.ledger, &message) {
+            Ok(certificates) => certificates,
+            Err(error) => {
+                let (start, end) = (message.start_round, message.end_round);
+                error!("Failed to retrieve the certificates for rounds {start} to {end} from the ledger - {error}");
+                return false;
+            }
+        };
+        // Send the `CertificateRangeResponse` message to the peer.
+        self.send(
+            peer_ip,
+            Message::CertificateRangeResponse(CertificateRangeResponse { request: message, certificates }),
+        );
+        true
+    }
+
+    /// Retrieves the block headers within the header request range, and returns the header response to the peer.
+    fn header_request(&self, peer_ip: SocketAddr, message: HeaderRequest) -> bool {
+        let HeaderRequest { start_height, end_height } = message;
+
+        // Retrieve the block headers within the requested range, up to the latest block.
+        let end_height = end_height.min(self.ledger.latest_height() + 1);
+        let headers = match (start_height..end_height)
+            .map(|height| self.ledger.get_header(height))
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(headers) => headers,
+            Err(error) => {
+                error!("Failed to retrieve headers {start_height} to {end_height} from the ledger - {error}");
+                return false;
+            }
+        };
+        // Send the `HeaderResponse` message to the peer.
+        self.send(peer_ip, Message::HeaderResponse(HeaderResponse { request: message, headers }));
+        true
+    }
+
+    /// Summarizes the memory pool, and returns the memory pool response to the peer.
+    fn memory_pool_request(&self, peer_ip: SocketAddr) -> bool {
+        let memory_pool = self.consensus.memory_pool();
+        // Retrieve the oldest unconfirmed transaction IDs.
+        let limit = MemoryPoolResponse::<N>::MAXIMUM_NUMBER_OF_TRANSACTION_IDS as usize;
+        let transaction_ids = memory_pool.unconfirmed_transaction_ids(0, limit);
+        // Send the `MemoryPoolResponse` message to the peer.
+        self.send(
+            peer_ip,
+            Message::MemoryPoolResponse(MemoryPoolResponse {
+                num_transactions: memory_pool.num_unconfirmed_transactions() as u32,
+                num_solutions: memory_pool.num_unconfirmed_solutions() as u32,
+                transaction_ids,
+            }),
+        );
+        true
+    }
+
     /// Retrieves the latest epoch challenge and latest block header, and returns the puzzle response to the peer.
     fn puzzle_request(&self, peer_ip: SocketAddr) -> bool {
         // Retrieve the latest epoch challenge.
@@ -226,39 +391,62 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Beacon<N, C> {
         false
     }
 
-    /// Adds the unconfirmed solution to the memory pool, and propagates the solution to all connected beacons.
+    /// Queues the unconfirmed solution on the solution workers, which add it to the memory pool and propagate it.
     async fn unconfirmed_solution(
         &self,
         peer_ip: SocketAddr,
         serialized: UnconfirmedSolution<N>,
         solution: ProverSolution<N>,
     ) -> bool {
-        // Add the unconfirmed solution to the memory pool.
-        if let Err(error) = self.consensus.add_unconfirmed_solution(&solution) {
-            trace!("[UnconfirmedSolution] {error}");
-            return true; // Maintain the connection.
-        }
-        let message = Message::UnconfirmedSolution(serialized);
-        // Propagate the "UnconfirmedSolution" to the connected beacons.
-        self.propagate_to_beacons(message, &[peer_ip]);
+        let id = solution.commitment().to_bytes_le().unwrap_or_default();
+        let node = self.clone();
+        self.consensus.workers().dispatch(TransmissionClass::Solution, &id, move || {
+            node.process_unconfirmed_solution(peer_ip, serialized, solution)
+        });
         true
     }
 
-    /// Adds the unconfirmed transaction to the memory pool, and propagates the transaction to all connected beacons.
+    /// Queues the unconfirmed transaction on the workers of its class, which add it to the memory pool and propagate it.
     fn unconfirmed_transaction(
         &self,
         peer_ip: SocketAddr,
         serialized: UnconfirmedTransaction<N>,
         transaction: Transaction<N>,
     ) -> bool {
-        // Add the unconfirmed transaction to the memory pool.
-        if let Err(error) = self.consensus.add_unconfirmed_transaction(transaction) {
-            trace!("[UnconfirmedTransaction] {error}");
-            return true; // Maintain the connection.
+        let class = TransmissionClass::of_transaction(&transaction);
+        let id = transaction.id().to_bytes_le().unwrap_or_default();
+        let node = self.clone();
+        self.consensus
+            .workers()
+            .dispatch(class, &id, move || node.process_unconfirmed_transaction(peer_ip, serialized, transaction));
+        true
+    }
+
+    /// Queues the unconfirmed transmission of a registered kind on the transaction workers, which add it to
+    /// the memory pool and propagate it.
+    fn unconfirmed_transmission(&self, peer_ip: SocketAddr, message: UnconfirmedTransmission) -> bool {
+        // Skip the transmissions of the kinds that are not registered.
+        if !self.consensus.transmissions().is_registered(&message.kind) {
+            trace!("Skipping the '{}' transmission from '{peer_ip}' (unregistered kind)", message.kind);
+            return true;
         }
-        let message = Message::UnconfirmedTransaction(serialized);
-        // Propagate the "UnconfirmedTransaction" to the connected beacons.
-        self.propagate_to_beacons(message, &[peer_ip]);
+        let id = message.payload.clone();
+        let node = self.clone();
+        self.consensus.workers().dispatch(TransmissionClass::Transaction, &id, move || {
+            // Add the unconfirmed transmission to the memory pool, after checking it against the rules of its kind.
+            match node.consensus.add_unconfirmed_transmission(&message.kind, message.payload.to_vec()) {
+                Ok(true) => (),
+                Ok(false) => return true,
+                Err(error) => {
+                    trace!("[UnconfirmedTransmission] {error}");
+                    return false;
+                }
+            }
+            let message = Message::UnconfirmedTransmission(message);
+            // Propagate the "UnconfirmedTransmission" to the connected beacons.
+            node.propagate_to_beacons(message, &[peer_ip]);
+            true
+        });
         true
     }
 }
@@ -29,7 +29,7 @@ use snarkvm::prelude::{Block, CoinbasePuzzle, ConsensusStorage, EpochChallenge,
 use anyhow::Result;
 use core::marker::PhantomData;
 use parking_lot::RwLock;
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
 
 /// A client node is a full node, capable of querying with the network.
 #[derive(Clone)]
@@ -55,6 +55,7 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
         account: Account<N>,
         trusted_peers: &[SocketAddr],
         genesis: Block<N>,
+        state_dir: PathBuf,
         dev: Option<u16>,
     ) -> Result<Self> {
         // Initialize the node router.
@@ -67,6 +68,10 @@ impl<N: Network, C: ConsensusStorage<N>> Client<N, C> {
             dev.is_some(),
         )
         .await?;
+        // Load the recently blacklisted transmissions, and persist any new entries to the state directory.
+        router.blacklist().open(state_dir.join("blacklist.jsonl"))?;
+        // Load the fork history, and persist any new or refined forks to the state directory.
+        router.sync().open_fork_history(state_dir.join("forks.jsonl"))?;
         // Load the coinbase puzzle.
         let coinbase_puzzle = CoinbasePuzzle::<N>::load()?;
         // Initialize the node.
@@ -68,8 +68,8 @@ impl<N: Network, C: ConsensusStorage<N>> Writing for Client<N, C> {
 
     /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
     /// The `side` parameter indicates the connection side **from the node's perspective**.
-    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router.codec(peer_addr)
     }
 }
 
@@ -80,8 +80,8 @@ impl<N: Network, C: ConsensusStorage<N>> Reading for Client<N, C> {
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router.codec(peer_addr)
     }
 
     /// Processes a message received from the network.
@@ -197,7 +197,10 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Client<N, C> {
                     self.propagate_to_validators(message, &[peer_ip]);
                 }
                 Ok(Ok(false)) | Ok(Err(_)) => {
-                    trace!("Invalid prover solution '{}' for the proof target.", solution.commitment())
+                    trace!("Invalid prover solution '{}' for the proof target.", solution.commitment());
+                    // Blacklist the solution.
+                    let reason = "Invalid prover solution for the proof target".to_string();
+                    self.router().blacklist().insert_solution(solution.commitment(), reason);
                 }
                 Err(error) => warn!("Failed to verify the prover solution: {error}"),
             }
@@ -14,12 +14,25 @@
 // You should have received a copy of the GNU General Public License
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
+use snarkos_node_cdn::EraReader;
+use snarkos_node_consensus::{BlockNotification, Consensus};
 use snarkos_node_ledger::Ledger;
-use snarkos_node_messages::{BlockLocators, CHECKPOINT_INTERVAL, NUM_RECENTS};
-use snarkvm::prelude::{ConsensusStorage, Network};
+use snarkos_node_messages::{
+    BlockCertificate,
+    BlockLocators,
+    CertificateRangeRequest,
+    CertificateRangeResponse,
+    CHECKPOINT_INTERVAL,
+    NUM_RECENTS,
+};
+use snarkos_node_store::Indexes;
+use snarkvm::prelude::{Block, ConsensusStorage, Network};
 
-use anyhow::Result;
+use anyhow::{ensure, Result};
+use core::ops::Range;
 use indexmap::IndexMap;
+use std::{collections::BTreeMap, sync::Arc};
+use tokio::sync::broadcast::{self, error::RecvError};
 
 /// Returns the block locators for the given ledger.
 pub fn get_block_locators<N: Network, C: ConsensusStorage<N>>(ledger: &Ledger<N, C>) -> Result<BlockLocators<N>> {
@@ -46,6 +59,88 @@ pub fn get_block_locators<N: Network, C: ConsensusStorage<N>>(ledger: &Ledger<N,
     Ok(BlockLocators::new(recents, checkpoints))
 }
 
+/// Returns the certificates of the blocks in the given ledger, within the rounds of the given request.
+pub fn get_block_certificates<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    request: &CertificateRangeRequest,
+) -> Result<Vec<BlockCertificate<N>>> {
+    ledger
+        .get_heights_for_rounds(request.start_round, request.end_round)?
+        .map(|height| {
+            let header = ledger.get_header(height)?;
+            Ok(BlockCertificate {
+                round: header.round(),
+                height,
+                previous_hash: ledger.get_previous_hash(height)?,
+                header,
+                block_hash: ledger.get_hash(height)?,
+                signature: ledger.get_signature(height)?,
+            })
+        })
+        .collect()
+}
+
+/// Returns the certificate range request for the rounds after the latest block in the given ledger,
+/// or after the given latest certified round, if it is higher.
+pub fn next_certificate_request<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    latest_certified_round: Option<u64>,
+) -> CertificateRangeRequest {
+    let start_round = ledger.latest_round().max(latest_certified_round.unwrap_or(0)).saturating_add(1);
+    let num_rounds = CertificateRangeResponse::<N>::MAXIMUM_NUMBER_OF_CERTIFICATES as u64;
+    CertificateRangeRequest { start_round, end_round: start_round.saturating_add(num_rounds - 1) }
+}
+
+/// Returns the certificate range request for the rounds up to the lowest block that is linked back from the
+/// assume-valid checkpoint, if the blocks between it and the latest block in the given ledger are not yet linked,
+/// and the peer at the given height has the lowest linked block.
+pub fn next_assume_valid_request<N: Network, C: ConsensusStorage<N>>(
+    consensus: &Consensus<N, C>,
+    ledger: &Ledger<N, C>,
+    peer_height: u32,
+) -> Option<CertificateRangeRequest> {
+    let (height, _) = consensus.lowest_assume_valid_link()?;
+    let (latest_height, latest_round) = (ledger.latest_height(), ledger.latest_round());
+    // Note: The rounds of the blocks are consecutive after the first block, so the round of the lowest linked block
+    // follows from the latest block, once the latest round is non-zero.
+    if height <= latest_height + 1 || height > peer_height || latest_round == 0 {
+        return None;
+    }
+    let end_round = latest_round + (height - latest_height) as u64;
+    let num_rounds = CertificateRangeResponse::<N>::MAXIMUM_NUMBER_OF_CERTIFICATES as u64;
+    let start_round = end_round.saturating_sub(num_rounds - 1).max(latest_round + 1);
+    Some(CertificateRangeRequest { start_round, end_round })
+}
+
+/// Ensures the given trusted checkpoints do not conflict with the blocks in the given ledger.
+pub fn check_trusted_checkpoints<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    checkpoints: &BTreeMap<u32, N::BlockHash>,
+) -> Result<()> {
+    for (height, hash) in checkpoints.range(..=ledger.latest_height()) {
+        ensure!(ledger.get_hash(*height)? == *hash, "The checkpoint at block {height} conflicts with the ledger");
+    }
+    Ok(())
+}
+
+/// Returns the blocks in the given range, as requested by a peer during sync. The blocks are read from the given
+/// era files if they cover the range, and otherwise, or if the era files cannot be read, from the ledger.
+pub fn get_blocks<N: Network, C: ConsensusStorage<N>>(
+    ledger: &Ledger<N, C>,
+    era_reader: Option<&EraReader>,
+    heights: Range<u32>,
+) -> Result<Vec<Block<N>>> {
+    if let Some(era_reader) = era_reader.filter(|era_reader| heights.end <= era_reader.end_height()) {
+        match era_reader.get_checked_blocks(ledger, heights.clone()) {
+            Ok(blocks) => return Ok(blocks),
+            Err(error) => {
+                warn!("Failed to read blocks {} to {} from the era files - {error}", heights.start, heights.end)
+            }
+        }
+    }
+    ledger.get_blocks(heights)
+}
+
 /// A helper to log instructions to recover.
 pub fn log_clean_error(dev: Option<u16>) {
     match dev {
@@ -53,3 +148,32 @@ pub fn log_clean_error(dev: Option<u16>) {
         None => error!("Storage corruption detected! Run `snarkos clean` to reset storage"),
     }
 }
+
+/// Keeps the secondary indexes up to date with the ledger, backfilling the blocks they have not indexed yet
+/// on startup and each time a new block is accepted.
+pub async fn run_indexer<N: Network, C: ConsensusStorage<N>>(
+    ledger: Ledger<N, C>,
+    indexes: Arc<Indexes<N>>,
+    mut subscriber: broadcast::Receiver<BlockNotification<N>>,
+) {
+    loop {
+        // Index the blocks up to the latest block.
+        let (ledger_clone, indexes_clone) = (ledger.clone(), indexes.clone());
+        let result = tokio::task::spawn_blocking(move || {
+            indexes_clone.backfill(ledger_clone.latest_height(), |height| ledger_clone.get_block(height))
+        })
+        .await;
+        match result {
+            Ok(Ok(num_blocks)) if num_blocks > 1 => info!("Indexed {num_blocks} blocks"),
+            Ok(Ok(_)) => (),
+            Ok(Err(error)) => warn!("Failed to update the secondary indexes - {error}"),
+            Err(error) => warn!("Failed to update the secondary indexes - {error}"),
+        }
+
+        // Wait for the next block. If any blocks were skipped, they are backfilled as well.
+        match subscriber.recv().await {
+            Ok(_) | Err(RecvError::Lagged(_)) => (),
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
@@ -39,14 +39,30 @@ mod helpers;
 mod traits;
 pub use traits::*;
 
-pub use snarkos_node_messages::NodeType;
+pub use snarkos_node_consensus::{
+    BatchConfig,
+    BuilderPolicy,
+    JournalConfig,
+    TransmissionKind,
+    VerifierConfig,
+    VerifierEndpoint,
+    VerifierKey,
+    WatchListConfig,
+    WorkerConfig,
+};
+pub use snarkos_node_messages::{Compression, NodeType};
+pub use snarkos_node_router::{GossipConfig, LightGatewayConfig, PeerDiversityConfig, RelayLevel, SpillConfig};
 
 use snarkos_account::Account;
-use snarkos_node_store::ConsensusDB;
+use snarkos_node_cdn::{EraReader, RemoteEraArchive};
+use snarkos_node_metrics::{AlertRules, PushConfig};
+use snarkos_node_rest::RestConfig;
+use snarkos_node_router::{Outbound, Router};
+use snarkos_node_store::{rocksdb::RocksDB, ConsensusDB, StorageLayout};
 use snarkvm::prelude::{Address, Block, ConsensusMemory, Network, PrivateKey, ViewKey};
 
-use anyhow::Result;
-use std::{net::SocketAddr, sync::Arc};
+use anyhow::{bail, Result};
+use std::{collections::BTreeMap, net::SocketAddr, sync::Arc};
 
 pub enum Node<N: Network> {
     /// A beacon is a full node, capable of producing blocks.
@@ -61,31 +77,71 @@ pub enum Node<N: Network> {
 
 impl<N: Network> Node<N> {
     /// Initializes a new beacon node.
+    #[allow(clippy::too_many_arguments)]
     pub async fn new_beacon(
         node_ip: SocketAddr,
         rest_ip: Option<SocketAddr>,
+        rest_config: RestConfig,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
         genesis: Block<N>,
         cdn: Option<String>,
+        assume_valid: Option<(u32, N::BlockHash)>,
+        compression: Compression,
+        layout: StorageLayout,
         dev: Option<u16>,
     ) -> Result<Self> {
-        Ok(Self::Beacon(Arc::new(Beacon::new(node_ip, rest_ip, account, trusted_peers, genesis, cdn, dev).await?)))
+        // Open the database in the storage layout, before the ledger is loaded.
+        RocksDB::open_with_layout(N::ID, dev, &layout)?;
+        let state_dir = layout.state_dir(N::ID, dev);
+        let node = Beacon::new(
+            node_ip,
+            rest_ip,
+            rest_config,
+            account,
+            trusted_peers,
+            genesis,
+            cdn,
+            assume_valid,
+            compression,
+            state_dir,
+            dev,
+        );
+        Ok(Self::Beacon(Arc::new(node.await?)))
     }
 
     /// Initializes a new validator node.
+    #[allow(clippy::too_many_arguments)]
     pub async fn new_validator(
         node_ip: SocketAddr,
         rest_ip: Option<SocketAddr>,
+        rest_config: RestConfig,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
         genesis: Block<N>,
         cdn: Option<String>,
+        assume_valid: Option<(u32, N::BlockHash)>,
+        compression: Compression,
+        layout: StorageLayout,
         dev: Option<u16>,
     ) -> Result<Self> {
-        Ok(Self::Validator(Arc::new(
-            Validator::new(node_ip, rest_ip, account, trusted_peers, genesis, cdn, dev).await?,
-        )))
+        // Open the database in the storage layout, before the ledger is loaded.
+        RocksDB::open_with_layout(N::ID, dev, &layout)?;
+        let state_dir = layout.state_dir(N::ID, dev);
+        let node = Validator::new(
+            node_ip,
+            rest_ip,
+            rest_config,
+            account,
+            trusted_peers,
+            genesis,
+            cdn,
+            assume_valid,
+            compression,
+            state_dir,
+            dev,
+        );
+        Ok(Self::Validator(Arc::new(node.await?)))
     }
 
     /// Initializes a new prover node.
@@ -94,9 +150,11 @@ impl<N: Network> Node<N> {
         account: Account<N>,
         trusted_peers: &[SocketAddr],
         genesis: Block<N>,
+        layout: StorageLayout,
         dev: Option<u16>,
     ) -> Result<Self> {
-        Ok(Self::Prover(Arc::new(Prover::new(node_ip, account, trusted_peers, genesis, dev).await?)))
+        let state_dir = layout.state_dir(N::ID, dev);
+        Ok(Self::Prover(Arc::new(Prover::new(node_ip, account, trusted_peers, genesis, state_dir, dev).await?)))
     }
 
     /// Initializes a new client node.
@@ -105,9 +163,11 @@ impl<N: Network> Node<N> {
         account: Account<N>,
         trusted_peers: &[SocketAddr],
         genesis: Block<N>,
+        layout: StorageLayout,
         dev: Option<u16>,
     ) -> Result<Self> {
-        Ok(Self::Client(Arc::new(Client::new(node_ip, account, trusted_peers, genesis, dev).await?)))
+        let state_dir = layout.state_dir(N::ID, dev);
+        Ok(Self::Client(Arc::new(Client::new(node_ip, account, trusted_peers, genesis, state_dir, dev).await?)))
     }
 
     /// Returns the node type.
@@ -159,4 +219,221 @@ impl<N: Network> Node<N> {
             Self::Client(node) => node.is_dev(),
         }
     }
+
+    /// Returns the router of the node.
+    pub fn router(&self) -> &Router<N> {
+        match self {
+            Self::Beacon(node) => node.router(),
+            Self::Validator(node) => node.router(),
+            Self::Prover(node) => node.router(),
+            Self::Client(node) => node.router(),
+        }
+    }
+
+    /// Sets the gossip configuration of the node.
+    pub fn set_gossip_config(&self, config: GossipConfig) {
+        match self {
+            Self::Beacon(node) => node.router().gossip().set_config(config),
+            Self::Validator(node) => node.router().gossip().set_config(config),
+            Self::Prover(node) => node.router().gossip().set_config(config),
+            Self::Client(node) => node.router().gossip().set_config(config),
+        }
+    }
+
+    /// Sets the diversity constraints on the peers the node dials.
+    pub fn set_peer_diversity_config(&self, config: PeerDiversityConfig) {
+        match self {
+            Self::Beacon(node) => node.router().peer_diversity().set_config(config),
+            Self::Validator(node) => node.router().peer_diversity().set_config(config),
+            Self::Prover(node) => node.router().peer_diversity().set_config(config),
+            Self::Client(node) => node.router().peer_diversity().set_config(config),
+        }
+    }
+
+    /// Sets the number of threads that verify the signatures of the inbound messages.
+    pub fn set_signature_threads(&self, num_threads: usize) -> Result<()> {
+        match self {
+            Self::Beacon(node) => node.router().signatures().set_num_threads(num_threads),
+            Self::Validator(node) => node.router().signatures().set_num_threads(num_threads),
+            Self::Prover(node) => node.router().signatures().set_num_threads(num_threads),
+            Self::Client(node) => node.router().signatures().set_num_threads(num_threads),
+        }
+    }
+
+    /// Sets the light gateway configuration of the node, which serves the client peers as untrusted light consumers.
+    pub fn set_light_gateway_config(&self, config: LightGatewayConfig) -> Result<()> {
+        match self {
+            Self::Beacon(node) => node.router().light_gateway().set_config(config),
+            Self::Validator(node) => node.router().light_gateway().set_config(config),
+            Self::Prover(..) | Self::Client(..) => bail!("The light gateway requires a node with a ledger"),
+        }
+        Ok(())
+    }
+
+    /// Sets the validation a validator performs on a beacon proposal, before relaying it to the other validators.
+    pub fn set_relay_level(&self, level: RelayLevel) -> Result<()> {
+        match self {
+            Self::Validator(node) => node.router().relay().set_level(level),
+            Self::Beacon(..) | Self::Prover(..) | Self::Client(..) => {
+                bail!("The beacon proposals are only relayed by validators")
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets the spill configuration of the sync pool, and clears its scratch directory.
+    pub fn set_spill_config(&self, config: SpillConfig) -> Result<()> {
+        match self {
+            Self::Beacon(node) => node.router().sync().set_spill_config(config),
+            Self::Validator(node) => node.router().sync().set_spill_config(config),
+            Self::Prover(node) => node.router().sync().set_spill_config(config),
+            Self::Client(node) => node.router().sync().set_spill_config(config),
+        }
+    }
+
+    /// Sets the trusted (weak-subjectivity) checkpoints of the sync pool, after checking them against the ledger.
+    pub fn set_trusted_checkpoints(&self, checkpoints: BTreeMap<u32, N::BlockHash>) -> Result<()> {
+        match self {
+            Self::Beacon(node) => {
+                helpers::check_trusted_checkpoints(node.ledger(), &checkpoints)?;
+                node.router().sync().set_trusted_checkpoints(checkpoints)
+            }
+            Self::Validator(node) => {
+                helpers::check_trusted_checkpoints(node.ledger(), &checkpoints)?;
+                node.router().sync().set_trusted_checkpoints(checkpoints)
+            }
+            Self::Prover(node) => node.router().sync().set_trusted_checkpoints(checkpoints),
+            Self::Client(node) => node.router().sync().set_trusted_checkpoints(checkpoints),
+        }
+    }
+
+    /// Sets the policy under which the node accepts bundles of transactions from external builders,
+    /// and adopts them in the blocks it proposes.
+    pub fn set_builder_policy(&self, policy: BuilderPolicy) -> Result<()> {
+        match self {
+            Self::Beacon(node) => node.consensus().bundles().set_policy(policy),
+            Self::Validator(..) | Self::Prover(..) | Self::Client(..) => {
+                bail!("The external builders require a node that proposes blocks")
+            }
+        }
+        Ok(())
+    }
+
+    /// Registers the given kind of transmission, beyond the solutions and transactions,
+    /// for the node to validate, hold in its memory pool, and gossip to its peers.
+    pub fn register_transmission_kind(&self, kind: impl TransmissionKind<N, ConsensusDB<N>> + 'static) -> Result<()> {
+        match self {
+            Self::Beacon(node) => node.consensus().register_transmission_kind(kind),
+            Self::Validator(node) => node.consensus().register_transmission_kind(kind),
+            Self::Prover(..) | Self::Client(..) => bail!("The transmission kinds require a node with a ledger"),
+        }
+    }
+
+    /// Enables the journal of consensus events for the node, with the given configuration.
+    pub fn set_journal_config(&self, config: JournalConfig) -> Result<()> {
+        match self {
+            Self::Beacon(node) => node.consensus().set_journal(config),
+            Self::Validator(node) => node.consensus().set_journal(config),
+            Self::Prover(..) | Self::Client(..) => bail!("The consensus journal requires a node with a ledger"),
+        }
+    }
+
+    /// Sets the configuration of the watch list, watching its addresses.
+    pub fn set_watch_list_config(&self, config: WatchListConfig<N>) -> Result<()> {
+        match self {
+            Self::Beacon(node) => node.consensus().set_watch_list_config(config),
+            Self::Validator(node) => node.consensus().set_watch_list_config(config),
+            Self::Prover(..) | Self::Client(..) => bail!("The watch list requires a node with a ledger"),
+        }
+    }
+
+    /// Offloads the proof verification of blocks to the verification service with the given configuration.
+    pub fn set_verifier_config(&self, config: VerifierConfig) -> Result<()> {
+        match self {
+            Self::Beacon(node) => node.consensus().set_verifier_config(config),
+            Self::Validator(node) => node.consensus().set_verifier_config(config),
+            Self::Prover(..) | Self::Client(..) => bail!("The verification service requires a node with a ledger"),
+        }
+    }
+
+    /// Starts the workers that process the unconfirmed transmissions, with the given configuration.
+    pub fn set_worker_config(&self, config: WorkerConfig) -> Result<()> {
+        match self {
+            Self::Beacon(node) => node.consensus().set_worker_config(config),
+            Self::Validator(node) => node.consensus().set_worker_config(config),
+            Self::Prover(..) | Self::Client(..) => bail!("The transmission workers require a beacon or validator node"),
+        }
+    }
+
+    /// Sets the bounds of the adaptive batch size and batch timeout of the produced blocks.
+    pub fn set_batch_config(&self, config: BatchConfig) -> Result<()> {
+        match self {
+            Self::Beacon(node) => node.consensus().set_batch_config(config),
+            Self::Validator(..) | Self::Prover(..) | Self::Client(..) => {
+                bail!("The adaptive batch requires a beacon node")
+            }
+        }
+    }
+
+    /// Initializes the given alert rules for the node.
+    pub fn initialize_alerts(&self, rules: AlertRules) {
+        match self {
+            Self::Beacon(node) => node.initialize_alerts(rules),
+            Self::Validator(node) => node.initialize_alerts(rules),
+            Self::Prover(node) => node.initialize_alerts(rules),
+            Self::Client(node) => node.initialize_alerts(rules),
+        }
+    }
+
+    /// Initializes pushing the metrics of the node to the configured endpoint.
+    pub fn initialize_metrics_push(&self, config: PushConfig) {
+        match self {
+            Self::Beacon(node) => node.initialize_metrics_push(config),
+            Self::Validator(node) => node.initialize_metrics_push(config),
+            Self::Prover(node) => node.initialize_metrics_push(config),
+            Self::Client(node) => node.initialize_metrics_push(config),
+        }
+    }
+
+    /// Initializes updating the gauges served by the Prometheus exporter.
+    pub fn initialize_metrics_export(&self) {
+        match self {
+            Self::Beacon(node) => node.initialize_metrics_export(),
+            Self::Validator(node) => node.initialize_metrics_export(),
+            Self::Prover(node) => node.initialize_metrics_export(),
+            Self::Client(node) => node.initialize_metrics_export(),
+        }
+    }
+
+    /// Initializes the secondary indexes of the ledger, and keeps them up to date.
+    pub fn initialize_indexes(&self, dev: Option<u16>) -> Result<()> {
+        match self {
+            Self::Beacon(node) => node.initialize_indexes(dev),
+            Self::Validator(node) => node.initialize_indexes(dev),
+            Self::Prover(..) | Self::Client(..) => bail!("The secondary indexes require a node with a ledger"),
+        }
+    }
+
+    /// Sets the era files from which the blocks requested by peers during sync are served.
+    pub fn set_era_reader(&self, era_reader: EraReader) -> Result<()> {
+        match self {
+            Self::Beacon(node) => node.set_era_reader(era_reader),
+            Self::Validator(node) => node.set_era_reader(era_reader),
+            Self::Prover(..) | Self::Client(..) => bail!("Serving blocks from era files requires a node with a ledger"),
+        }
+        Ok(())
+    }
+
+    /// Sets the remote archive of era files, from which the REST server serves the historical blocks (experimental).
+    pub fn set_remote_archive(&self, archive: RemoteEraArchive) -> Result<()> {
+        let rest = match self {
+            Self::Beacon(node) => node.rest().as_ref().map(|rest| rest.set_archive(archive)),
+            Self::Validator(node) => node.rest().as_ref().map(|rest| rest.set_archive(archive)),
+            Self::Prover(..) | Self::Client(..) => None,
+        };
+        match rest {
+            Some(()) => Ok(()),
+            None => bail!("The remote archive requires a node with a REST server"),
+        }
+    }
 }
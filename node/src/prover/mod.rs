@@ -33,6 +33,7 @@ use parking_lot::{Mutex, RwLock};
 use rand::{rngs::OsRng, CryptoRng, Rng};
 use std::{
     net::SocketAddr,
+    path::PathBuf,
     sync::{
         atomic::{AtomicBool, AtomicU8, Ordering},
         Arc,
@@ -72,6 +73,7 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
         account: Account<N>,
         trusted_peers: &[SocketAddr],
         genesis: Block<N>,
+        state_dir: PathBuf,
         dev: Option<u16>,
     ) -> Result<Self> {
         // Initialize the node router.
@@ -84,6 +86,8 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
             dev.is_some(),
         )
         .await?;
+        // Load the recently blacklisted transmissions, and persist any new entries to the state directory.
+        router.blacklist().open(state_dir.join("blacklist.jsonl"))?;
         // Load the coinbase puzzle.
         let coinbase_puzzle = CoinbasePuzzle::<N>::load()?;
         // Compute the maximum number of puzzle instances.
@@ -154,6 +158,13 @@ impl<N: Network, C: ConsensusStorage<N>> Prover<N, C> {
                 continue;
             }
 
+            // If the node is in standby, then skip this iteration.
+            if self.router.is_standby() {
+                trace!("Skipping an iteration of the coinbase puzzle (standby)");
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                continue;
+            }
+
             // If the number of instances of the coinbase puzzle exceeds the maximum, then skip this iteration.
             if self.num_puzzle_instances() > self.max_puzzle_instances {
                 // Sleep for a brief period of time.
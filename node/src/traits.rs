@@ -15,9 +15,30 @@
 // along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
 
 use snarkos_node_messages::NodeType;
+use snarkos_node_metrics::{
+    blocks,
+    memory_pool,
+    node,
+    peers,
+    rest,
+    storage,
+    transmissions,
+    watch_list,
+    AlertAction,
+    AlertEngine,
+    AlertRules,
+    PushConfig,
+};
 use snarkos_node_router::Routing;
+use snarkos_node_store::rocksdb::RocksDB;
+use snarkos_node_tcp::P2P;
 use snarkvm::prelude::{Address, Network, PrivateKey, ViewKey};
 
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
 #[async_trait]
 pub trait NodeInterface<N: Network>: Routing<N> {
     /// Returns the node type.
@@ -45,6 +66,133 @@ pub trait NodeInterface<N: Network>: Routing<N> {
         self.router().is_dev()
     }
 
+    /// Returns the number of unconfirmed transactions and solutions in the memory pool, if the node has one.
+    fn memory_pool_size(&self) -> Option<(usize, usize)> {
+        None
+    }
+
+    /// Returns the number of watched addresses, and the total number of changes observed, if the node has a watch list.
+    fn watch_list_size(&self) -> Option<(usize, u64)> {
+        None
+    }
+
+    /// Returns the number of REST requests rejected for exceeding the rate limit and the maximum body size,
+    /// if the node serves a REST API.
+    fn rest_rejections(&self) -> Option<(u64, u64)> {
+        None
+    }
+
+    /// Returns the saturation of the fullest transmission worker queue, and the number of transmissions dropped
+    /// as their queue was full, if the node has transmission workers.
+    fn transmission_backpressure(&self) -> Option<(f64, u64)> {
+        None
+    }
+
+    /// Returns a snapshot of the exported metrics of the node.
+    fn metrics_snapshot(&self) -> HashMap<&'static str, f64> {
+        let router = self.router();
+        // Retrieve the latest height of the node, and the number of blocks it is behind the median peer.
+        let latest_height = router.sync().latest_canon_height();
+        let peer_height = router.sync().median_peer_height().unwrap_or_default();
+
+        let mut snapshot = HashMap::from([
+            (blocks::HEIGHT, latest_height as f64),
+            (blocks::SYNC_LAG, peer_height.saturating_sub(latest_height) as f64),
+            (blocks::FORKS, router.sync().num_forks() as f64),
+            (peers::CONNECTED, router.number_of_connected_peers() as f64),
+            (peers::CANDIDATE, router.number_of_candidate_peers() as f64),
+            (peers::RESTRICTED, router.number_of_restricted_peers() as f64),
+            (peers::THROTTLED, router.tcp().stats().throttled() as f64),
+            (node::STANDBY, router.is_standby() as u8 as f64),
+        ]);
+        // Include the size of the memory pool, if the node has one.
+        if let Some((num_transactions, num_solutions)) = self.memory_pool_size() {
+            snapshot.insert(memory_pool::TRANSACTIONS, num_transactions as f64);
+            snapshot.insert(memory_pool::SOLUTIONS, num_solutions as f64);
+        }
+        // Include the size of the watch list, if the node has one.
+        if let Some((num_addresses, num_changes)) = self.watch_list_size() {
+            snapshot.insert(watch_list::ADDRESSES, num_addresses as f64);
+            snapshot.insert(watch_list::CHANGES, num_changes as f64);
+        }
+        // Include the number of rejected REST requests, if the node serves a REST API.
+        if let Some((num_rate_limited, num_oversized)) = self.rest_rejections() {
+            snapshot.insert(rest::RATE_LIMITED, num_rate_limited as f64);
+            snapshot.insert(rest::OVERSIZED, num_oversized as f64);
+        }
+        // Include the backpressure on the transmission workers, if the node has them.
+        if let Some((saturation, num_dropped)) = self.transmission_backpressure() {
+            snapshot.insert(transmissions::QUEUE_SATURATION, saturation);
+            snapshot.insert(transmissions::DROPPED, num_dropped as f64);
+        }
+        // Include the statistics of the database, if it is open.
+        if let Some(stats) = RocksDB::stats() {
+            snapshot.insert(storage::LIVE_DATA_BYTES, stats.live_data_bytes as f64);
+            snapshot.insert(storage::SST_FILES_BYTES, stats.sst_files_bytes as f64);
+            snapshot.insert(storage::MEMTABLE_BYTES, stats.memtable_bytes as f64);
+            snapshot.insert(storage::NUM_KEYS, stats.num_keys as f64);
+        }
+        snapshot
+    }
+
+    /// Evaluates the given alert rules against the metrics of the node, and performs the actions of the rules that fire.
+    fn initialize_alerts(&self, rules: AlertRules) {
+        // The interval (in seconds) at which the alert rules are evaluated.
+        const ALERT_INTERVAL_IN_SECS: u64 = 10;
+
+        let node = self.clone();
+        let mut engine = AlertEngine::new(rules);
+        tokio::task::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(ALERT_INTERVAL_IN_SECS)).await;
+
+                for alert in engine.evaluate(&node.metrics_snapshot(), Instant::now()) {
+                    warn!("Alert '{}' fired ({} = {})", alert.rule, alert.metric, alert.value);
+                    match &alert.action {
+                        AlertAction::Log => (),
+                        AlertAction::Webhook { url } => {
+                            if let Err(error) = snarkos_node_metrics::post_alert(url, &alert).await {
+                                warn!("Failed to post alert '{}' to '{url}' - {error}", alert.rule);
+                            }
+                        }
+                        AlertAction::Shutdown => {
+                            node.shut_down().await;
+                            std::process::exit(1);
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    /// Periodically pushes the metrics of the node to the configured endpoint.
+    fn initialize_metrics_push(&self, config: PushConfig) {
+        let node = self.clone();
+        tokio::task::spawn(async move {
+            loop {
+                tokio::time::sleep(config.interval).await;
+
+                if let Err(error) = snarkos_node_metrics::push_metrics(&config, &node.metrics_snapshot()).await {
+                    warn!("Failed to push the metrics to '{}' - {error}", config.url);
+                }
+            }
+        });
+    }
+
+    /// Periodically updates the gauges served by the Prometheus exporter with the metrics of the node.
+    fn initialize_metrics_export(&self) {
+        // The interval (in seconds) at which the gauges are updated.
+        const EXPORT_INTERVAL_IN_SECS: u64 = 5;
+
+        let node = self.clone();
+        tokio::task::spawn(async move {
+            loop {
+                snarkos_node_metrics::update_gauges(&node.metrics_snapshot());
+                tokio::time::sleep(Duration::from_secs(EXPORT_INTERVAL_IN_SECS)).await;
+            }
+        });
+    }
+
     /// Handles OS signals for the node to intercept and perform a clean shutdown.
     /// Note: Only Ctrl-C is supported; it should work on both Unix-family systems and Windows.
     fn handle_signals(&self) {
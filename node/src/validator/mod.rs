@@ -18,11 +18,22 @@ mod router;
 
 use crate::traits::NodeInterface;
 use snarkos_account::Account;
-use snarkos_node_consensus::Consensus;
+use snarkos_node_cdn::EraReader;
+use snarkos_node_consensus::{BlockPipeline, Consensus, PipelineInput, TransmissionClass, WorkerConfig};
 use snarkos_node_ledger::Ledger;
-use snarkos_node_messages::{BlockRequest, Message, NodeType, PuzzleResponse, UnconfirmedSolution};
-use snarkos_node_rest::Rest;
+use snarkos_node_messages::{
+    BlockCertificate,
+    BlockRequest,
+    Compression,
+    DisconnectReason,
+    Message,
+    NodeType,
+    PuzzleResponse,
+    UnconfirmedSolution,
+};
+use snarkos_node_rest::{Rest, RestConfig};
 use snarkos_node_router::{Heartbeat, Inbound, Outbound, Router, Routing};
+use snarkos_node_store::Indexes;
 use snarkos_node_tcp::{
     protocols::{Disconnect, Handshake, Reading, Writing},
     P2P,
@@ -30,9 +41,10 @@ use snarkos_node_tcp::{
 use snarkvm::prelude::{Block, ConsensusStorage, Header, Network, ProverSolution};
 
 use anyhow::Result;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use std::{
     net::SocketAddr,
+    path::PathBuf,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -50,8 +62,12 @@ pub struct Validator<N: Network, C: ConsensusStorage<N>> {
     consensus: Consensus<N, C>,
     /// The router of the node.
     router: Router<N>,
+    /// The block pipeline of the node.
+    pipeline: Arc<BlockPipeline>,
     /// The REST server of the node.
     rest: Option<Rest<N, C, Self>>,
+    /// The era files from which the blocks requested by peers are served, if set.
+    era_reader: Arc<RwLock<Option<EraReader>>>,
     /// The spawned handles.
     handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     /// The shutdown signal.
@@ -60,13 +76,18 @@ pub struct Validator<N: Network, C: ConsensusStorage<N>> {
 
 impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
     /// Initializes a new validator node.
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         node_ip: SocketAddr,
         rest_ip: Option<SocketAddr>,
+        rest_config: RestConfig,
         account: Account<N>,
         trusted_peers: &[SocketAddr],
         genesis: Block<N>,
         cdn: Option<String>,
+        assume_valid: Option<(u32, N::BlockHash)>,
+        compression: Compression,
+        state_dir: PathBuf,
         dev: Option<u16>,
     ) -> Result<Self> {
         // Initialize the ledger.
@@ -81,6 +102,17 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         }
         // Initialize the consensus.
         let consensus = Consensus::new(ledger.clone(), dev.is_some())?;
+        // Set the assume-valid checkpoint, if one is provided.
+        if let Some((height, hash)) = assume_valid {
+            consensus.set_assume_valid(height, hash)?;
+        }
+        // Load the equivocation evidence, and persist any new evidence to the state directory.
+        let evidence_path = state_dir.join("equivocations.jsonl");
+        consensus.equivocations().open(evidence_path)?;
+        // Load the history of changes to the beacon set, and persist any new changes to the state directory.
+        consensus.committee_history().open(state_dir.join("committee.jsonl"))?;
+        // Start the workers that process the unconfirmed transmissions.
+        consensus.set_worker_config(WorkerConfig::default())?;
 
         // Initialize the node router.
         let router = Router::new(
@@ -92,23 +124,49 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
             dev.is_some(),
         )
         .await?;
+        // Offer the compression for the block and certificate messages to the peers.
+        router.set_compression(compression);
+        // Load the recently blacklisted transmissions, and persist any new entries to the state directory.
+        router.blacklist().open(state_dir.join("blacklist.jsonl"))?;
+        // Load the fork history, and persist any new or refined forks to the state directory.
+        router.sync().open_fork_history(state_dir.join("forks.jsonl"))?;
+        // Load the certificates of the blocks ahead of the ledger, and persist any new ones to the state directory.
+        let certificates = router.sync().open_certificates(state_dir.join("certificates.bin"))?;
+
+        // Set the committee, to keep the committee members connected.
+        router.set_committee(consensus.beacons().into_keys());
+
+        // Initialize the block pipeline, which advances the ledger with the blocks from the sync pool.
+        let (source, canon, committee) = (router.clone(), router.clone(), consensus.clone());
+        let pipeline = BlockPipeline::new(
+            consensus.clone(),
+            move |height| source.sync().remove_block_response(height).map(PipelineInput::Decoded),
+            move |block: &Block<N>| {
+                canon.sync().insert_canon_locator(block.height(), block.hash());
+                canon.set_committee(committee.beacons().into_keys());
+            },
+        )?;
 
         // Initialize the node.
         let mut node = Self {
             ledger: ledger.clone(),
             consensus: consensus.clone(),
             router,
+            pipeline: Arc::new(pipeline),
             rest: None,
+            era_reader: Default::default(),
             handles: Default::default(),
             shutdown: Default::default(),
         };
 
         // Initialize the REST server.
         if let Some(rest_ip) = rest_ip {
-            node.rest = Some(Rest::start(rest_ip, Some(consensus), ledger, Arc::new(node.clone()))?);
+            node.rest = Some(Rest::start(rest_ip, rest_config, Some(consensus), ledger, Arc::new(node.clone()), dev)?);
         }
-        // Initialize the sync pool.
-        node.initialize_sync()?;
+        // Initialize the sync pool, and restore the certificates that are still valid.
+        node.initialize_sync(certificates)?;
+        // Initialize the relay of the beacon proposals.
+        node.initialize_relay();
         // Initialize the routing.
         node.initialize_routing().await;
         // Initialize the signal handler.
@@ -122,14 +180,60 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         &self.ledger
     }
 
+    /// Returns the consensus module.
+    pub fn consensus(&self) -> &Consensus<N, C> {
+        &self.consensus
+    }
+
     /// Returns the REST server.
     pub fn rest(&self) -> &Option<Rest<N, C, Self>> {
         &self.rest
     }
+
+    /// Sets the era files from which the blocks requested by peers are served, wherever they cover the request.
+    pub fn set_era_reader(&self, era_reader: EraReader) {
+        *self.era_reader.write() = Some(era_reader);
+    }
+
+    /// Initializes the secondary indexes, and keeps them up to date with the ledger.
+    pub fn initialize_indexes(&self, dev: Option<u16>) -> Result<()> {
+        let indexes = Arc::new(Indexes::open(dev)?);
+        // Serve the indexes from the REST server.
+        if let Some(rest) = &self.rest {
+            rest.set_indexes(indexes.clone());
+        }
+        let subscriber = self.consensus.subscriptions().subscribe_blocks();
+        let ledger = self.ledger.clone();
+        self.handles.lock().push(tokio::spawn(crate::helpers::run_indexer(ledger, indexes, subscriber)));
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Validator<N, C> {
+    /// Returns the number of unconfirmed transactions and solutions in the memory pool.
+    fn memory_pool_size(&self) -> Option<(usize, usize)> {
+        let memory_pool = self.consensus.memory_pool();
+        Some((memory_pool.num_unconfirmed_transactions(), memory_pool.num_unconfirmed_solutions()))
+    }
+
+    /// Returns the number of watched addresses, and the total number of changes observed.
+    fn watch_list_size(&self) -> Option<(usize, u64)> {
+        let watch_list = self.consensus.watch_list();
+        Some((watch_list.num_addresses(), watch_list.num_changes()))
+    }
+
+    /// Returns the number of REST requests rejected for exceeding the rate limit and the maximum body size.
+    fn rest_rejections(&self) -> Option<(u64, u64)> {
+        self.rest.as_ref().map(|rest| (rest.limiter().num_rate_limited(), rest.limiter().num_oversized()))
+    }
+
+    /// Returns the saturation of the fullest transmission worker queue, and the number of dropped transmissions.
+    fn transmission_backpressure(&self) -> Option<(f64, u64)> {
+        let workers = self.consensus.workers();
+        Some((workers.saturation(), workers.num_dropped()))
+    }
+
     /// Shuts down the node.
     async fn shut_down(&self) {
         info!("Shutting down...");
@@ -142,6 +246,10 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Validator<N, C> {
         trace!("Shutting down the validator...");
         self.handles.lock().iter().for_each(|handle| handle.abort());
 
+        // Shut down the block pipeline.
+        trace!("Shutting down the block pipeline...");
+        self.pipeline.shut_down();
+
         // Shut down the router.
         self.router.shut_down().await;
 
@@ -154,12 +262,14 @@ impl<N: Network, C: ConsensusStorage<N>> NodeInterface<N> for Validator<N, C> {
 }
 
 impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
-    /// Initializes the sync pool.
-    fn initialize_sync(&self) -> Result<()> {
+    /// Initializes the sync pool, with the given certificates that were persisted before a restart.
+    fn initialize_sync(&self, certificates: Vec<BlockCertificate<N>>) -> Result<()> {
         // Retrieve the canon locators.
         let canon_locators = crate::helpers::get_block_locators(&self.ledger)?;
         // Insert the canon locators into the sync pool.
         self.router.sync().insert_canon_locators(canon_locators).unwrap();
+        // Restore the certificates, so the node resumes syncing without requesting them again.
+        self.restore_certificates(certificates);
 
         // Start the sync loop.
         let validator = self.clone();
@@ -174,6 +284,23 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
                 // Sleep briefly to avoid triggering spam detection.
                 tokio::time::sleep(Duration::from_secs(1)).await;
 
+                // If the highest peer is ahead, request the block certificates that link the assume-valid checkpoint
+                // back to the canonical tip (headers first), if the peer has them, or for the upcoming rounds.
+                if let Some((peer_ip, height)) = validator.router.sync().get_peers_by_height().first().copied() {
+                    let is_ahead = height > validator.ledger.latest_height();
+                    if is_ahead && !validator.router.is_awaiting_certificates(&peer_ip) {
+                        let (consensus, ledger) = (&validator.consensus, &validator.ledger);
+                        let request = match crate::helpers::next_assume_valid_request(consensus, ledger, height) {
+                            Some(request) => request,
+                            None => {
+                                let latest_certified_round = validator.router.sync().latest_certified_round();
+                                crate::helpers::next_certificate_request(ledger, latest_certified_round)
+                            }
+                        };
+                        validator.send(peer_ip, Message::CertificateRangeRequest(request));
+                    }
+                }
+
                 // Prepare the block requests, if any.
                 let block_requests = validator.router.sync().prepare_block_requests();
                 trace!("Prepared {} block requests", block_requests.len());
@@ -208,31 +335,84 @@ impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
         Ok(())
     }
 
-    /// Attempts to advance with blocks from the sync pool.
-    fn advance_with_sync_blocks(&self) {
-        // Retrieve the latest block height.
-        let mut current_height = self.ledger.latest_height();
-        // Try to advance the ledger with the sync pool.
-        while let Some(block) = self.router.sync().remove_block_response(current_height + 1) {
-            // Ensure the block height matches.
-            if block.height() != current_height + 1 {
-                warn!("Block height mismatch: expected {}, found {}", current_height + 1, block.height());
-                break;
-            }
-            // Check the next block.
-            if let Err(error) = self.consensus.check_next_block(&block) {
-                warn!("The next block ({}) is invalid - {error}", block.height());
-                break;
+    /// Fully verifies the blocks of the deferred proposals in the background, relays the valid proposals that were
+    /// not yet relayed, and restricts the peers that sent an invalid block.
+    fn initialize_relay(&self) {
+        let validator = self.clone();
+        self.handles.lock().push(tokio::spawn(async move {
+            loop {
+                let deferred = match validator.router.relay().pop() {
+                    Some(deferred) => deferred,
+                    None => {
+                        validator.router.relay().notified().await;
+                        continue;
+                    }
+                };
+                // Verify the block on a blocking thread, as it verifies the proofs of its transactions.
+                let node = validator.clone();
+                let (deferred, result) = match tokio::task::spawn_blocking(move || {
+                    let result = node.verify_deferred_block(&deferred);
+                    (deferred, result)
+                })
+                .await
+                {
+                    Ok(output) => output,
+                    Err(error) => {
+                        error!("Failed to verify a deferred proposal - {error}");
+                        continue;
+                    }
+                };
+                let (peer_ip, height) = (deferred.peer_ip, deferred.block.height());
+                match result {
+                    Ok(true) if !deferred.is_relayed => validator.relay_proposal(peer_ip, deferred.proposal),
+                    Ok(_) => (),
+                    Err(error) => {
+                        warn!("Restricting '{peer_ip}' for proposing an invalid block {height} - {error}");
+                        validator.router.relay().register_invalid();
+                        validator.router.insert_restricted_peer(peer_ip);
+                        validator.send(peer_ip, Message::Disconnect(DisconnectReason::ProtocolViolation.into()));
+                        validator.router.disconnect(peer_ip);
+                    }
+                }
             }
-            // Attempt to advance to the next block.
-            if let Err(error) = self.consensus.advance_to_next_block(&block) {
-                warn!("{error}");
+        }));
+    }
+
+    /// Restores the given certificates into the sync pool, after validating them against the current committee.
+    /// The certificates are restored up to the first one that is not signed by a beacon, has an invalid signature,
+    /// or does not link to the one before it, as the certificates beyond it cannot be anchored to the ledger.
+    fn restore_certificates(&self, certificates: Vec<BlockCertificate<N>>) {
+        let num_persisted = certificates.len();
+        let latest_height = self.ledger.latest_height();
+        let beacons = self.consensus.beacons();
+        let mut restored: Vec<BlockCertificate<N>> = Vec::with_capacity(num_persisted);
+        for certificate in certificates.into_iter().filter(|certificate| certificate.height > latest_height) {
+            let is_linked = match restored.last() {
+                Some(previous) => {
+                    previous.height + 1 == certificate.height && previous.block_hash == certificate.previous_hash
+                }
+                None => true,
+            };
+            if !is_linked || !beacons.contains_key(&certificate.signer()) || !certificate.verify() {
+                warn!("Discarding the persisted certificates from block {}", certificate.height);
                 break;
             }
-            // Insert the height and hash as canon in the sync pool.
-            self.router.sync().insert_canon_locator(block.height(), block.hash());
-            // Increment the latest height.
-            current_height += 1;
+            restored.push(certificate);
         }
+        if restored.is_empty() {
+            return;
+        }
+        // Link the certificates back from the assume-valid checkpoint, if it is not yet reached.
+        let blocks = restored.iter().map(|c| (c.height, c.block_hash, c.previous_hash)).collect::<Vec<_>>();
+        self.consensus.link_assume_valid(&blocks);
+        match self.router.sync().insert_certified_hashes(&restored) {
+            Ok(()) => info!("Restored {} of {num_persisted} persisted certificates", restored.len()),
+            Err(error) => warn!("Discarding the persisted certificates - {error}"),
+        }
+    }
+
+    /// Wakes the block pipeline to advance with blocks from the sync pool.
+    fn advance_with_sync_blocks(&self) {
+        self.pipeline.notify();
     }
 }
@@ -17,20 +17,32 @@
 use super::*;
 
 use snarkos_node_messages::{
+    AncestorRequest,
+    AncestorResponse,
+    BeaconPropose,
+    BlockCertificate,
     BlockRequest,
     BlockResponse,
+    CertificateRangeRequest,
+    CertificateRangeResponse,
     Data,
     DataBlocks,
     DisconnectReason,
+    HeaderRequest,
+    HeaderResponse,
+    MemoryPoolResponse,
     Message,
     MessageCodec,
     Ping,
     Pong,
     UnconfirmedTransaction,
+    UnconfirmedTransmission,
 };
+use snarkos_node_router::DeferredBlock;
 use snarkos_node_tcp::{Connection, ConnectionSide, Tcp};
-use snarkvm::prelude::{error, EpochChallenge, Network, Transaction};
+use snarkvm::prelude::{error, EpochChallenge, Network, ToBytes, Transaction};
 
+use anyhow::bail;
 use futures_util::sink::SinkExt;
 use std::{io, net::SocketAddr, time::Duration};
 
@@ -87,8 +99,8 @@ impl<N: Network, C: ConsensusStorage<N>> Writing for Validator<N, C> {
 
     /// Creates an [`Encoder`] used to write the outbound messages to the target stream.
     /// The `side` parameter indicates the connection side **from the node's perspective**.
-    fn codec(&self, _addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router.codec(peer_addr)
     }
 }
 
@@ -99,8 +111,14 @@ impl<N: Network, C: ConsensusStorage<N>> Reading for Validator<N, C> {
 
     /// Creates a [`Decoder`] used to interpret messages from the network.
     /// The `side` param indicates the connection side **from the node's perspective**.
-    fn codec(&self, _peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
-        Default::default()
+    fn codec(&self, peer_addr: SocketAddr, _side: ConnectionSide) -> Self::Codec {
+        self.router.codec(peer_addr)
+    }
+
+    /// Returns `true` if the message is an unconfirmed transmission, which is dropped when the queue of
+    /// the connection is full. The reading of the other messages waits for room in the queue instead.
+    fn is_droppable(&self, message: &Self::Message) -> bool {
+        message.is_transmission()
     }
 
     /// Processes a message received from the network.
@@ -133,20 +151,95 @@ impl<N: Network, C: ConsensusStorage<N>> Outbound<N> for Validator<N, C> {
     }
 }
 
+impl<N: Network, C: ConsensusStorage<N>> Validator<N, C> {
+    /// Checks the given proposed block extends the latest block, and is signed by a beacon on its block hash,
+    /// which is recomputed from its header. The transactions of the block are not verified.
+    fn check_proposal_header(&self, block: &Block<N>) -> Result<()> {
+        if block.previous_hash() != self.ledger.latest_hash() {
+            bail!("The block does not extend the latest block")
+        }
+        let certificate = BlockCertificate::from_block(block);
+        if !self.consensus.beacons().contains_key(&certificate.signer()) {
+            bail!("The block is not signed by a beacon")
+        }
+        if !certificate.verify() {
+            bail!("The block has an invalid hash or signature")
+        }
+        Ok(())
+    }
+
+    /// Fully verifies the block of the given deferred proposal, and returns `Ok(true)` if it is valid,
+    /// or `Ok(false)` if it is stale, as the ledger advanced past it in the meantime.
+    pub(super) fn verify_deferred_block(&self, deferred: &DeferredBlock<N>) -> Result<bool> {
+        let block = &deferred.block;
+        // If the ledger advanced past the block, it is valid if it was added to the ledger.
+        if block.height() <= self.ledger.latest_height() {
+            return Ok(self.ledger.get_hash(block.height())? == block.hash());
+        }
+        match self.consensus.check_next_block(block) {
+            Ok(()) => Ok(true),
+            // Note: The ledger may have advanced during the verification.
+            Err(_) if block.height() <= self.ledger.latest_height() => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Relays the given proposal to the connected validators, except for the given peer.
+    pub(super) fn relay_proposal(&self, peer_ip: SocketAddr, proposal: BeaconPropose<N>) {
+        self.propagate_to_validators(Message::BeaconPropose(proposal), &[peer_ip]);
+    }
+}
+
 #[async_trait]
 impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
+    /// Checks the header and signature of the proposed block, and queues the block for its full verification
+    /// in the background. The proposal is relayed to the other validators before or after its full verification,
+    /// depending on the relay level.
+    fn beacon_propose(&self, peer_ip: SocketAddr, serialized: BeaconPropose<N>, block: Block<N>) -> bool {
+        // Skip the proposals that do not extend the latest block.
+        if block.height() != self.ledger.latest_height() + 1 {
+            return true;
+        }
+        // Check the header and signature of the block, which is cheap compared to its full verification.
+        if let Err(error) = self.check_proposal_header(&block) {
+            warn!("Peer '{peer_ip}' sent an invalid proposal for block {} - {error}", block.height());
+            return false;
+        }
+        // Skip the proposals that were already seen.
+        // Note: This is checked after the signature, so a forged proposal cannot shadow the proposal of the beacon.
+        if !self.router().relay().insert_seen(block.hash()) {
+            return true;
+        }
+        // Relay the proposal right away, if its full verification is deferred.
+        let is_relayed = self.router().relay().level().is_deferred();
+        if is_relayed {
+            self.relay_proposal(peer_ip, serialized.clone());
+        }
+        self.router().relay().defer(DeferredBlock { peer_ip, proposal: serialized, block, is_relayed });
+        true
+    }
+
     /// Retrieves the blocks within the block request range, and returns the block response to the peer.
     fn block_request(&self, peer_ip: SocketAddr, message: BlockRequest) -> bool {
         let BlockRequest { start_height, end_height } = &message;
 
-        // Retrieve the blocks within the requested range.
-        let blocks = match self.ledger.get_blocks(*start_height..*end_height) {
-            Ok(blocks) => Data::Object(DataBlocks(blocks)),
+        // Retrieve the blocks within the requested range, from the era files if they cover it.
+        let era_reader = self.era_reader.read().clone();
+        let blocks = match crate::helpers::get_blocks(&self.ledger, era_reader.as_ref(), *start_height..*end_height) {
+            Ok(blocks) => blocks,
             Err(error) => {
                 error!("Failed to retrieve blocks {start_height} to {end_height} from the ledger - {error}");
                 return false;
             }
         };
+        // Serialize the blocks, so that the block scheduler can account for the size of the response.
+        let blocks = match DataBlocks(blocks).to_bytes_le() {
+            Ok(bytes) => Data::Buffer(bytes.into()),
+            Err(error) => {
+                error!("Failed to serialize blocks {start_height} to {end_height} - {error}");
+                return false;
+            }
+        };
         // Send the `BlockResponse` message to the peer.
         self.send(peer_ip, Message::BlockResponse(BlockResponse { request: message, blocks }));
         true
@@ -156,6 +249,8 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
     fn block_response(&self, peer_ip: SocketAddr, blocks: Vec<Block<N>>) -> bool {
         // Insert the candidate blocks into the sync pool.
         for block in blocks {
+            // Check the block for an equivocation by its signer.
+            self.consensus.check_equivocation(&block);
             if let Err(error) = self.router().sync().insert_block_response(peer_ip, block) {
                 warn!("{error}");
                 return false;
@@ -187,6 +282,149 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
         true
     }
 
+    /// Retrieves the block hashes at the heights of the ancestor request, and returns the ancestor response to the peer.
+    fn ancestor_request(&self, peer_ip: SocketAddr, message: AncestorRequest) -> bool {
+        // Retrieve the block hashes at the requested heights.
+        let hashes = match message
+            .heights()
+            .into_iter()
+            .map(|height| Ok((height, self.ledger.get_hash(height)?)))
+            .collect::<Result<Vec<_>>>()
+        {
+            Ok(hashes) => hashes,
+            Err(error) => {
+                let (start, end) = (message.start_height, message.end_height);
+                error!("Failed to retrieve the block hashes from {start} to {end} from the ledger - {error}");
+                return false;
+            }
+        };
+        // Send the `AncestorResponse` message to the peer.
+        self.send(peer_ip, Message::AncestorResponse(AncestorResponse { request: message, hashes }));
+        true
+    }
+
+    /// Narrows down the common ancestor with the peer, and sends the next ancestor request, if needed.
+    fn ancestor_response(&self, peer_ip: SocketAddr, message: AncestorResponse<N>) -> bool {
+        let canon_hash = |height| self.ledger.get_hash(height).ok();
+        match self.router().sync().process_ancestor_response(peer_ip, message, canon_hash) {
+            Ok(Some(request)) => {
+                self.send(peer_ip, Message::AncestorRequest(request));
+                true
+            }
+            Ok(None) => true,
+            Err(error) => {
+                warn!("{error}");
+                false
+            }
+        }
+    }
+
+    /// Retrieves the block certificates within the requested rounds, and returns the certificate range response to the peer.
+    fn certificate_range_request(&self, peer_ip: SocketAddr, message: CertificateRangeRequest) -> bool {
+        let certificates = match crate::helpers::get_block_certificates(&self.ledger, &message) {
+            Ok(certificates) => certificates,
+            Err(error) => {
+                let (start, end) = (message.start_round, message.end_round);
+                error!("Failed to retrieve the certificates for rounds {start} to {end} from the ledger - {error}");
+                return false;
+            }
+        };
+        // Send the `CertificateRangeResponse` message to the peer.
+        self.send(
+            peer_ip,
+            Message::CertificateRangeResponse(CertificateRangeResponse { request: message, certificates }),
+        );
+        true
+    }
+
+    /// Verifies the block certificates are signed by beacons, links them back from the assume-valid checkpoint,
+    /// and inserts their block hashes into the sync pool.
+    async fn certificate_range_response(
+        &self,
+        peer_ip: SocketAddr,
+        request: CertificateRangeRequest,
+        certificates: Vec<BlockCertificate<N>>,
+    ) -> bool {
+        // Ensure each certificate is signed by a beacon.
+        let beacons = self.consensus.beacons();
+        if let Some(certificate) = certificates.iter().find(|c| !beacons.contains_key(&c.signer())) {
+            warn!(
+                "Peer '{peer_ip}' sent a certificate for block {} that is not signed by a beacon",
+                certificate.height
+            );
+            return false;
+        }
+        // Verify the signatures of the certificates, on the signature verifier.
+        let certificates = match self.router().signatures().verify_batch(certificates, BlockCertificate::verify).await {
+            Ok(certificates) => certificates,
+            Err(Some(certificate)) => {
+                warn!("Peer '{peer_ip}' sent a certificate for block {} with an invalid signature", certificate.height);
+                return false;
+            }
+            Err(None) => {
+                warn!("Failed to verify the certificates sent by '{peer_ip}'");
+                return false;
+            }
+        };
+        // Link the certificates back from the assume-valid checkpoint, if it is not yet reached.
+        let blocks = certificates.iter().map(|c| (c.height, c.block_hash, c.previous_hash)).collect::<Vec<_>>();
+        let num_linked = self.consensus.link_assume_valid(&blocks);
+        if num_linked > 0 {
+            debug!("Linked {num_linked} blocks back from the assume-valid checkpoint");
+        }
+        // If the certificates were requested beyond the upcoming rounds to link the assume-valid checkpoint,
+        // they are not anchored to the canonical tip, and are not inserted into the sync pool.
+        if request.start_round > self.ledger.latest_round().saturating_add(1) {
+            return true;
+        }
+        match self.router().sync().insert_certified_hashes(&certificates) {
+            Ok(()) => true,
+            Err(error) => {
+                warn!("Peer '{peer_ip}' sent conflicting certificates - {error}");
+                false
+            }
+        }
+    }
+
+    /// Retrieves the block headers within the header request range, and returns the header response to the peer.
+    fn header_request(&self, peer_ip: SocketAddr, message: HeaderRequest) -> bool {
+        let HeaderRequest { start_height, end_height } = message;
+
+        // Retrieve the block headers within the requested range, up to the latest block.
+        let end_height = end_height.min(self.ledger.latest_height() + 1);
+        let headers = match (start_height..end_height)
+            .map(|height| self.ledger.get_header(height))
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(headers) => headers,
+            Err(error) => {
+                error!("Failed to retrieve headers {start_height} to {end_height} from the ledger - {error}");
+                return false;
+            }
+        };
+        // Send the `HeaderResponse` message to the peer.
+        self.send(peer_ip, Message::HeaderResponse(HeaderResponse { request: message, headers }));
+        true
+    }
+
+    /// Summarizes the memory pool, and returns the memory pool response to the peer.
+    fn memory_pool_request(&self, peer_ip: SocketAddr) -> bool {
+        let memory_pool = self.consensus.memory_pool();
+        // Retrieve the oldest unconfirmed transaction IDs.
+        let limit = MemoryPoolResponse::<N>::MAXIMUM_NUMBER_OF_TRANSACTION_IDS as usize;
+        let transaction_ids = memory_pool.unconfirmed_transaction_ids(0, limit);
+        // Send the `MemoryPoolResponse` message to the peer.
+        self.send(
+            peer_ip,
+            Message::MemoryPoolResponse(MemoryPoolResponse {
+                num_transactions: memory_pool.num_unconfirmed_transactions() as u32,
+                num_solutions: memory_pool.num_unconfirmed_solutions() as u32,
+                transaction_ids,
+            }),
+        );
+        true
+    }
+
     /// Retrieves the latest epoch challenge and latest block header, and returns the puzzle response to the peer.
     fn puzzle_request(&self, peer_ip: SocketAddr) -> bool {
         // Retrieve the latest epoch challenge.
@@ -220,6 +458,13 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
         // Add the unconfirmed solution to the memory pool.
         if let Err(error) = self.consensus.add_unconfirmed_solution(&solution) {
             trace!("[UnconfirmedSolution] {error}");
+            // Blacklist the solution, unless it was rejected as a duplicate.
+            let commitment = solution.commitment();
+            let is_duplicate = self.consensus.memory_pool().contains_unconfirmed_solution(commitment)
+                || self.ledger.contains_puzzle_commitment(&commitment).unwrap_or(true);
+            if !is_duplicate {
+                self.router().blacklist().insert_solution(commitment, error.to_string());
+            }
             return true; // Maintain the connection.
         }
         let message = Message::UnconfirmedSolution(serialized);
@@ -230,18 +475,59 @@ impl<N: Network, C: ConsensusStorage<N>> Inbound<N> for Validator<N, C> {
         true
     }
 
-    /// Handles an `UnconfirmedTransaction` message.
+    /// Queues the unconfirmed transaction on the workers of its class, which check it and propagate it.
     fn unconfirmed_transaction(
         &self,
         peer_ip: SocketAddr,
         serialized: UnconfirmedTransaction<N>,
-        _transaction: Transaction<N>,
+        transaction: Transaction<N>,
     ) -> bool {
-        let message = Message::UnconfirmedTransaction(serialized);
-        // Propagate the "UnconfirmedTransaction" to the connected beacons.
-        self.propagate_to_beacons(message.clone(), &[peer_ip]);
-        // Propagate the "UnconfirmedTransaction" to the connected validators.
-        self.propagate_to_validators(message, &[peer_ip]);
+        let class = TransmissionClass::of_transaction(&transaction);
+        let id = transaction.id().to_bytes_le().unwrap_or_default();
+        let node = self.clone();
+        self.consensus.workers().dispatch(class, &id, move || {
+            // Ensure the transaction is valid, before relaying it and serving it to the peers.
+            if let Err(error) = node.consensus.check_transaction_basic(&transaction) {
+                trace!("[UnconfirmedTransaction] {error}");
+                return false;
+            }
+            let message = Message::UnconfirmedTransaction(serialized);
+            // Propagate the "UnconfirmedTransaction" to the connected beacons.
+            node.propagate_to_beacons(message.clone(), &[peer_ip]);
+            // Propagate the "UnconfirmedTransaction" to the connected validators.
+            node.propagate_to_validators(message, &[peer_ip]);
+            true
+        });
+        true
+    }
+
+    /// Queues the unconfirmed transmission of a registered kind on the transaction workers, which add it to
+    /// the memory pool and propagate it.
+    fn unconfirmed_transmission(&self, peer_ip: SocketAddr, message: UnconfirmedTransmission) -> bool {
+        // Skip the transmissions of the kinds that are not registered.
+        if !self.consensus.transmissions().is_registered(&message.kind) {
+            trace!("Skipping the '{}' transmission from '{peer_ip}' (unregistered kind)", message.kind);
+            return true;
+        }
+        let id = message.payload.clone();
+        let node = self.clone();
+        self.consensus.workers().dispatch(TransmissionClass::Transaction, &id, move || {
+            // Add the unconfirmed transmission to the memory pool, after checking it against the rules of its kind.
+            match node.consensus.add_unconfirmed_transmission(&message.kind, message.payload.to_vec()) {
+                Ok(true) => (),
+                Ok(false) => return true,
+                Err(error) => {
+                    trace!("[UnconfirmedTransmission] {error}");
+                    return false;
+                }
+            }
+            let message = Message::UnconfirmedTransmission(message);
+            // Propagate the "UnconfirmedTransmission" to the connected beacons.
+            node.propagate_to_beacons(message.clone(), &[peer_ip]);
+            // Propagate the "UnconfirmedTransmission" to the connected validators.
+            node.propagate_to_validators(message, &[peer_ip]);
+            true
+        });
         true
     }
 }
@@ -0,0 +1,431 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+    rocksdb::{self, DataMap, Database},
+    MapID,
+    SecondaryIndexMap,
+};
+use snarkvm::{prelude::*, synthesizer::store::helpers::MapRead};
+
+use ::rocksdb::WriteBatch;
+use core::{fmt::Debug, hash::Hash, marker::PhantomData};
+use indexmap::{IndexMap, IndexSet};
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// The definition of a secondary index, which declares the entries it derives from each block.
+pub trait IndexDefinition<N: Network>: 'static + Send + Sync {
+    /// The name of the index, which identifies its progress cursor.
+    const NAME: &'static str;
+    /// The map of the index.
+    const MAP: SecondaryIndexMap;
+    /// The map of the number of values under each key of the index.
+    const COUNT_MAP: SecondaryIndexMap;
+
+    /// The key of the index.
+    type Key: Copy + Debug + PartialEq + Eq + Hash + Serialize + DeserializeOwned + Send + Sync;
+    /// The value of the index.
+    type Value: Copy + Debug + PartialEq + Eq + Hash + Serialize + DeserializeOwned + Send + Sync;
+
+    /// Returns the entries of the index derived from the given block.
+    fn entries(block: &Block<N>) -> Vec<(Self::Key, Self::Value)>;
+}
+
+/// The interface of a secondary index, through which every index is updated and rebuilt alike,
+/// regardless of its keys and values. The writes are queued in a write batch, so that they are written
+/// atomically with the progress cursor of the index.
+pub trait LedgerIndex<N: Network>: Send + Sync {
+    /// Returns the name of the index.
+    fn name(&self) -> &'static str;
+
+    /// Queues the insertion of the entries derived from the given block into the index.
+    fn index_block(&self, block: &Block<N>, batch: &mut WriteBatch) -> Result<()>;
+
+    /// Queues the removal of every entry from the index.
+    fn clear(&self, batch: &mut WriteBatch) -> Result<()>;
+}
+
+/// The position of an entry in a secondary index, as the big-endian height of its block and its index in the block,
+/// so that the entries under each key are stored in the order they were indexed.
+type Position = ([u8; 4], [u8; 4]);
+
+/// A secondary index, which stores the values derived under each key, in the order they were indexed.
+/// Each value is stored under its own composite key, so indexing a block only writes its own entries.
+pub struct SecondaryIndex<N: Network, D: IndexDefinition<N>> {
+    /// The mapping of `(key, height, index)` to `value`.
+    map: DataMap<(D::Key, Position), D::Value>,
+    /// The mapping of `key` to the number of values under it.
+    counts: DataMap<D::Key, u64>,
+    /// PhantomData.
+    _phantom: PhantomData<N>,
+}
+
+impl<N: Network, D: IndexDefinition<N>> SecondaryIndex<N, D> {
+    /// Opens the index.
+    pub fn open(dev: Option<u16>) -> Result<Self> {
+        Ok(Self {
+            map: rocksdb::RocksDB::open_map(N::ID, dev, MapID::SecondaryIndex(D::MAP))?,
+            counts: rocksdb::RocksDB::open_map(N::ID, dev, MapID::SecondaryIndex(D::COUNT_MAP))?,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the number of values indexed under the given key.
+    pub fn count(&self, key: &D::Key) -> Result<u64> {
+        Ok(self.counts.get(key)?.map_or(0, |count| *count))
+    }
+
+    /// Returns the values indexed under the given key, from the given offset and up to the given limit,
+    /// in the order they were indexed. Only the values up to the end of the page are read from storage.
+    pub fn get_page(&self, key: &D::Key, offset: usize, limit: usize) -> Result<Vec<D::Value>> {
+        Ok(self.iter(key)?.skip(offset).take(limit).collect())
+    }
+
+    /// Returns the values indexed under the given key, in the order they were indexed.
+    pub fn get(&self, key: &D::Key) -> Result<Vec<D::Value>> {
+        Ok(self.iter(key)?.collect())
+    }
+
+    /// Returns an iterator over the values indexed under the given key, in the order they were indexed,
+    /// which reads the values from storage as it advances.
+    pub fn iter(&self, key: &D::Key) -> Result<impl Iterator<Item = D::Value> + '_> {
+        Ok(self.map.iter_prefix(key)?.map(|(_, value)| value))
+    }
+
+    /// Returns an iterator over the values indexed under the given key in the blocks from the given height,
+    /// along with the height of the block of each value, in the order they were indexed.
+    /// The iterator seeks to the height in storage, so the values below it are never read.
+    pub fn iter_from_height(&self, key: &D::Key, height: u32) -> Result<impl Iterator<Item = (u32, D::Value)> + '_> {
+        let start = (key, (height.to_be_bytes(), [0u8; 4]));
+        Ok(self.map.iter_prefix_from(key, &start)?.map(|((_, (height, _)), value)| (u32::from_be_bytes(height), value)))
+    }
+}
+
+impl<N: Network, D: IndexDefinition<N>> LedgerIndex<N> for SecondaryIndex<N, D> {
+    /// Returns the name of the index.
+    fn name(&self) -> &'static str {
+        D::NAME
+    }
+
+    /// Queues the insertion of the entries derived from the given block into the index,
+    /// skipping the entries that are repeated within the block.
+    fn index_block(&self, block: &Block<N>, batch: &mut WriteBatch) -> Result<()> {
+        let height = block.height().to_be_bytes();
+        let entries: IndexSet<_> = D::entries(block).into_iter().collect();
+        let mut num_new_values = IndexMap::<D::Key, u64>::new();
+        for (index, (key, value)) in entries.into_iter().enumerate() {
+            let index = u32::try_from(index)?.to_be_bytes();
+            self.map.insert_in_batch(batch, &(key, (height, index)), &value)?;
+            *num_new_values.entry(key).or_default() += 1;
+        }
+        // Update the number of values under each key.
+        for (key, num_new_values) in num_new_values {
+            self.counts.insert_in_batch(batch, &key, &(self.count(&key)? + num_new_values))?;
+        }
+        Ok(())
+    }
+
+    /// Queues the removal of every entry from the index.
+    fn clear(&self, batch: &mut WriteBatch) -> Result<()> {
+        self.map.clear_in_batch(batch)?;
+        self.counts.clear_in_batch(batch)
+    }
+}
+
+/// The index of `address` to the IDs of the transactions with the address in a public input or output.
+pub struct AddressTransactions;
+
+impl<N: Network> IndexDefinition<N> for AddressTransactions {
+    type Key = Address<N>;
+    type Value = N::TransactionID;
+
+    const COUNT_MAP: SecondaryIndexMap = SecondaryIndexMap::AddressTransactionsCount;
+    const MAP: SecondaryIndexMap = SecondaryIndexMap::AddressTransactions;
+    const NAME: &'static str = "address-transactions";
+
+    /// Returns the addresses in the public inputs and outputs of each transaction in the block.
+    fn entries(block: &Block<N>) -> Vec<(Self::Key, Self::Value)> {
+        let mut entries = Vec::new();
+        for transaction in block.transactions().iter() {
+            for transition in transaction.transitions() {
+                let inputs = transition.inputs().iter().filter_map(|input| match input {
+                    Input::Public(_, Some(plaintext)) => Some(plaintext),
+                    _ => None,
+                });
+                let outputs = transition.outputs().iter().filter_map(|output| match output {
+                    Output::Public(_, Some(plaintext)) => Some(plaintext),
+                    _ => None,
+                });
+                for plaintext in inputs.chain(outputs) {
+                    if let Plaintext::Literal(Literal::Address(address), _) = plaintext {
+                        entries.push((*address, transaction.id()));
+                    }
+                }
+            }
+        }
+        entries
+    }
+}
+
+/// The index of `program ID` to the IDs of the transactions that deployed the program.
+pub struct ProgramDeployments;
+
+impl<N: Network> IndexDefinition<N> for ProgramDeployments {
+    type Key = ProgramID<N>;
+    type Value = N::TransactionID;
+
+    const COUNT_MAP: SecondaryIndexMap = SecondaryIndexMap::ProgramDeploymentsCount;
+    const MAP: SecondaryIndexMap = SecondaryIndexMap::ProgramDeployments;
+    const NAME: &'static str = "program-deployments";
+
+    /// Returns the program ID of each deployment in the block.
+    fn entries(block: &Block<N>) -> Vec<(Self::Key, Self::Value)> {
+        block
+            .transactions()
+            .iter()
+            .filter_map(|transaction| match transaction {
+                Transaction::Deploy(transaction_id, _, deployment, _) => {
+                    Some((*deployment.program_id(), *transaction_id))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// The index of `puzzle commitment` to the address of the prover of the solution.
+pub struct SolutionProvers;
+
+impl<N: Network> IndexDefinition<N> for SolutionProvers {
+    type Key = PuzzleCommitment<N>;
+    type Value = Address<N>;
+
+    const COUNT_MAP: SecondaryIndexMap = SecondaryIndexMap::SolutionProversCount;
+    const MAP: SecondaryIndexMap = SecondaryIndexMap::SolutionProvers;
+    const NAME: &'static str = "solution-provers";
+
+    /// Returns the prover of each solution in the coinbase of the block.
+    fn entries(block: &Block<N>) -> Vec<(Self::Key, Self::Value)> {
+        match block.coinbase() {
+            Some(coinbase) => coinbase
+                .partial_solutions()
+                .iter()
+                .map(|solution| (solution.commitment(), solution.address()))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// The secondary indexes of the ledger, each with a progress cursor, so that an index can be added
+/// or rebuilt by backfilling it from the blocks it has not indexed yet.
+pub struct Indexes<N: Network> {
+    /// The mapping of `index name` to the height of the next block to index.
+    cursor_map: DataMap<String, u32>,
+    /// The index of `address` to the IDs of the transactions with the address in a public input or output.
+    transactions_by_address: SecondaryIndex<N, AddressTransactions>,
+    /// The index of `program ID` to the IDs of the transactions that deployed the program.
+    deployments_by_program: SecondaryIndex<N, ProgramDeployments>,
+    /// The index of `puzzle commitment` to the address of the prover of the solution.
+    provers_by_solution: SecondaryIndex<N, SolutionProvers>,
+    /// The lock that serializes the updates to the indexes and their cursors.
+    lock: Mutex<()>,
+}
+
+impl<N: Network> Indexes<N> {
+    /// Opens the secondary indexes.
+    pub fn open(dev: Option<u16>) -> Result<Self> {
+        Ok(Self {
+            cursor_map: rocksdb::RocksDB::open_map(N::ID, dev, MapID::SecondaryIndex(SecondaryIndexMap::Cursor))?,
+            transactions_by_address: SecondaryIndex::open(dev)?,
+            deployments_by_program: SecondaryIndex::open(dev)?,
+            provers_by_solution: SecondaryIndex::open(dev)?,
+            lock: Default::default(),
+        })
+    }
+
+    /// Returns the secondary indexes.
+    pub fn all(&self) -> [&dyn LedgerIndex<N>; 3] {
+        [&self.transactions_by_address, &self.deployments_by_program, &self.provers_by_solution]
+    }
+
+    /// Returns the index of `address` to the IDs of the transactions with the address in a public input or output.
+    pub const fn transactions_by_address(&self) -> &SecondaryIndex<N, AddressTransactions> {
+        &self.transactions_by_address
+    }
+
+    /// Returns the index of `program ID` to the IDs of the transactions that deployed the program.
+    pub const fn deployments_by_program(&self) -> &SecondaryIndex<N, ProgramDeployments> {
+        &self.deployments_by_program
+    }
+
+    /// Returns the index of `puzzle commitment` to the address of the prover of the solution.
+    pub const fn provers_by_solution(&self) -> &SecondaryIndex<N, SolutionProvers> {
+        &self.provers_by_solution
+    }
+
+    /// Returns the height of the next block to index, for the index with the given name.
+    pub fn cursor(&self, name: &str) -> Result<u32> {
+        Ok(self.cursor_map.get(name)?.map_or(0, |height| *height))
+    }
+
+    /// Indexes the given block, in each index whose cursor is at the height of the block.
+    /// The entries and the advanced cursors are written atomically, so an index never skips or repeats a block.
+    pub fn index_block(&self, block: &Block<N>) -> Result<()> {
+        let _lock = self.lock.lock();
+        let mut batch = WriteBatch::default();
+        for index in self.all() {
+            if self.cursor(index.name())? == block.height() {
+                index.index_block(block, &mut batch)?;
+                self.cursor_map.insert_in_batch(&mut batch, &index.name().to_string(), &(block.height() + 1))?;
+            }
+        }
+        self.cursor_map.write_batch(batch)
+    }
+
+    /// Indexes the blocks up to the given latest height that any index has not indexed yet, retrieving each block
+    /// with the given function, and returns the number of blocks indexed.
+    pub fn backfill(&self, latest_height: u32, get_block: impl Fn(u32) -> Result<Block<N>>) -> Result<u32> {
+        // Determine the lowest cursor of the indexes.
+        let mut start_height = latest_height + 1;
+        for index in self.all() {
+            start_height = start_height.min(self.cursor(index.name())?);
+        }
+        // Index the blocks from the lowest cursor.
+        for height in start_height..=latest_height {
+            self.index_block(&get_block(height)?)?;
+        }
+        Ok(latest_height + 1 - start_height)
+    }
+
+    /// Clears the index with the given name, or every index if `None`, and resets its cursor to the genesis block.
+    pub fn reset(&self, name: Option<&str>) -> Result<()> {
+        // Ensure the index exists.
+        if let Some(name) = name {
+            ensure!(self.all().iter().any(|index| index.name() == name), "Unknown index '{name}'");
+        }
+
+        let _lock = self.lock.lock();
+        let mut batch = WriteBatch::default();
+        for index in self.all().into_iter().filter(|index| name.map_or(true, |name| index.name() == name)) {
+            index.clear(&mut batch)?;
+            self.cursor_map.remove_in_batch(&mut batch, &index.name().to_string())?;
+        }
+        self.cursor_map.write_batch(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type CurrentNetwork = Testnet3;
+
+    /// Opens the secondary indexes in a temporary test database.
+    fn sample_indexes() -> Indexes<CurrentNetwork> {
+        let database = rocksdb::RocksDB::open_testing(tempfile::tempdir().unwrap().into_path(), None).unwrap();
+        let open_index = |map, counts| SecondaryIndex {
+            map: database.test_map(MapID::SecondaryIndex(map)),
+            counts: database.test_map(MapID::SecondaryIndex(counts)),
+            _phantom: PhantomData,
+        };
+        Indexes {
+            cursor_map: database.test_map(MapID::SecondaryIndex(SecondaryIndexMap::Cursor)),
+            transactions_by_address: open_index(
+                SecondaryIndexMap::AddressTransactions,
+                SecondaryIndexMap::AddressTransactionsCount,
+            ),
+            deployments_by_program: open_index(
+                SecondaryIndexMap::ProgramDeployments,
+                SecondaryIndexMap::ProgramDeploymentsCount,
+            ),
+            provers_by_solution: open_index(
+                SecondaryIndexMap::SolutionProvers,
+                SecondaryIndexMap::SolutionProversCount,
+            ),
+            lock: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_genesis_entries() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+
+        // Ensure the genesis transactions are indexed under the addresses they mint to.
+        let entries = <AddressTransactions as IndexDefinition<CurrentNetwork>>::entries(&genesis);
+        assert!(!entries.is_empty());
+        for (_, transaction_id) in &entries {
+            assert!(genesis.transactions().get(transaction_id).is_some());
+        }
+
+        // Ensure the genesis block has no deployments or coinbase solutions.
+        assert!(<ProgramDeployments as IndexDefinition<CurrentNetwork>>::entries(&genesis).is_empty());
+        assert!(<SolutionProvers as IndexDefinition<CurrentNetwork>>::entries(&genesis).is_empty());
+    }
+
+    #[test]
+    fn test_index_block() {
+        let genesis = Block::<CurrentNetwork>::from_bytes_le(CurrentNetwork::genesis_bytes()).unwrap();
+        let indexes = sample_indexes();
+        let name = <AddressTransactions as IndexDefinition<CurrentNetwork>>::NAME;
+        assert_eq!(indexes.cursor(name).unwrap(), 0);
+
+        // Ensure the block is indexed, along with the cursor of each index.
+        indexes.index_block(&genesis).unwrap();
+        for index in indexes.all() {
+            assert_eq!(indexes.cursor(index.name()).unwrap(), 1);
+        }
+
+        // Ensure the values under each key are read in the order they were indexed, without repetitions.
+        let entries = <AddressTransactions as IndexDefinition<CurrentNetwork>>::entries(&genesis);
+        let (address, _) = entries[0];
+        let expected: Vec<_> = entries
+            .iter()
+            .filter(|(key, _)| *key == address)
+            .map(|(_, transaction_id)| *transaction_id)
+            .collect::<IndexSet<_>>()
+            .into_iter()
+            .collect();
+        assert_eq!(indexes.transactions_by_address().get(&address).unwrap(), expected);
+        assert_eq!(indexes.transactions_by_address().count(&address).unwrap(), expected.len() as u64);
+
+        // Ensure the values are paged by offset and limit.
+        let index = indexes.transactions_by_address();
+        assert_eq!(index.get_page(&address, 0, 1).unwrap(), expected[..1]);
+        assert_eq!(index.get_page(&address, 1, expected.len()).unwrap(), expected[1..]);
+        assert!(index.get_page(&address, expected.len(), 10).unwrap().is_empty());
+        assert!(index.get_page(&address, 0, 0).unwrap().is_empty());
+
+        // Ensure the values are read from a height, along with the height of their block.
+        let from_genesis: Vec<_> = index.iter_from_height(&address, 0).unwrap().collect();
+        assert_eq!(from_genesis, expected.iter().map(|transaction_id| (0, *transaction_id)).collect::<Vec<_>>());
+        assert_eq!(index.iter_from_height(&address, 1).unwrap().count(), 0);
+
+        // Ensure a block below the cursor is not indexed again.
+        indexes.index_block(&genesis).unwrap();
+        assert_eq!(indexes.transactions_by_address().get(&address).unwrap(), expected);
+        assert_eq!(indexes.transactions_by_address().count(&address).unwrap(), expected.len() as u64);
+
+        // Ensure the index is cleared along with its cursor.
+        indexes.reset(Some(name)).unwrap();
+        assert!(indexes.transactions_by_address().get(&address).unwrap().is_empty());
+        assert_eq!(indexes.transactions_by_address().count(&address).unwrap(), 0);
+        assert_eq!(indexes.cursor(name).unwrap(), 0);
+        assert_eq!(indexes.cursor(<ProgramDeployments as IndexDefinition<CurrentNetwork>>::NAME).unwrap(), 1);
+        assert!(indexes.reset(Some("unknown")).is_err());
+    }
+}
@@ -0,0 +1,71 @@
+// Copyright (C) 2019-2023 Aleo Systems Inc.
+// This file is part of the snarkOS library.
+
+// The snarkOS library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkOS library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkOS library. If not, see <https://www.gnu.org/licenses/>.
+
+use std::path::PathBuf;
+
+/// The placement of the storage components of the node, which allows each component to live on a different disk.
+/// The database is opened in its directories with `RocksDB::open_with_layout`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StorageLayout {
+    /// The directory of the ledger database, or the default ledger directory if `None`.
+    pub ledger_dir: Option<PathBuf>,
+    /// The directory of the database write-ahead log, or the ledger directory if `None`.
+    pub wal_dir: Option<PathBuf>,
+    /// The directory of the node state files (e.g. the proving jobs), or the ledger directory if `None`.
+    pub state_dir: Option<PathBuf>,
+}
+
+impl StorageLayout {
+    /// Returns the directory of the ledger database.
+    pub fn ledger_dir(&self, network_id: u16, dev: Option<u16>) -> PathBuf {
+        self.ledger_dir.clone().unwrap_or_else(|| aleo_std::aleo_ledger_dir(network_id, dev))
+    }
+
+    /// Returns the directory of the database write-ahead log.
+    pub fn wal_dir(&self, network_id: u16, dev: Option<u16>) -> PathBuf {
+        self.wal_dir.clone().unwrap_or_else(|| self.ledger_dir(network_id, dev))
+    }
+
+    /// Returns the directory of the node state files.
+    pub fn state_dir(&self, network_id: u16, dev: Option<u16>) -> PathBuf {
+        self.state_dir.clone().unwrap_or_else(|| self.ledger_dir(network_id, dev))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_layout() {
+        // Ensure the default layout places every component in the ledger directory.
+        let layout = StorageLayout::default();
+        let ledger_dir = aleo_std::aleo_ledger_dir(3, Some(0));
+        assert_eq!(layout.ledger_dir(3, Some(0)), ledger_dir);
+        assert_eq!(layout.wal_dir(3, Some(0)), ledger_dir);
+        assert_eq!(layout.state_dir(3, Some(0)), ledger_dir);
+
+        // Ensure the components follow the ledger directory, unless placed elsewhere.
+        let layout = StorageLayout {
+            ledger_dir: Some(PathBuf::from("/mnt/hdd/ledger")),
+            wal_dir: Some(PathBuf::from("/mnt/nvme/wal")),
+            state_dir: None,
+        };
+        assert_eq!(layout.ledger_dir(3, None), PathBuf::from("/mnt/hdd/ledger"));
+        assert_eq!(layout.wal_dir(3, None), PathBuf::from("/mnt/nvme/wal"));
+        assert_eq!(layout.state_dir(3, None), PathBuf::from("/mnt/hdd/ledger"));
+    }
+}
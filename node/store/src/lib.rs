@@ -27,6 +27,12 @@ pub use block::*;
 mod consensus;
 pub use consensus::*;
 
+mod indexes;
+pub use indexes::*;
+
+mod layout;
+pub use layout::*;
+
 mod program;
 pub use program::*;
 
@@ -47,6 +53,7 @@ pub enum MapID {
     TransitionInput(TransitionInputMap),
     TransitionOutput(TransitionOutputMap),
     Program(ProgramMap),
+    SecondaryIndex(SecondaryIndexMap),
     #[cfg(test)]
     Test(TestMap),
 }
@@ -62,6 +69,7 @@ impl From<MapID> for u16 {
             MapID::TransitionInput(id) => id as u16,
             MapID::TransitionOutput(id) => id as u16,
             MapID::Program(id) => id as u16,
+            MapID::SecondaryIndex(id) => id as u16,
             #[cfg(test)]
             MapID::Test(id) => id as u16,
         }
@@ -161,6 +169,18 @@ pub enum ProgramMap {
     Value = DataID::ValueMap as u16,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum SecondaryIndexMap {
+    Cursor = DataID::IndexCursorMap as u16,
+    AddressTransactions = DataID::IndexAddressTransactionsMap as u16,
+    ProgramDeployments = DataID::IndexProgramDeploymentsMap as u16,
+    SolutionProvers = DataID::IndexSolutionProversMap as u16,
+    AddressTransactionsCount = DataID::IndexAddressTransactionsCountMap as u16,
+    ProgramDeploymentsCount = DataID::IndexProgramDeploymentsCountMap as u16,
+    SolutionProversCount = DataID::IndexSolutionProversCountMap as u16,
+}
+
 #[cfg(test)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(u16)]
@@ -233,6 +253,14 @@ enum DataID {
     KeyValueIDMap,
     KeyMap,
     ValueMap,
+    // Secondary index
+    IndexCursorMap,
+    IndexAddressTransactionsMap,
+    IndexProgramDeploymentsMap,
+    IndexSolutionProversMap,
+    IndexAddressTransactionsCountMap,
+    IndexProgramDeploymentsCountMap,
+    IndexSolutionProversCountMap,
 
     // Testing
     #[cfg(test)]
@@ -227,6 +227,79 @@ impl<
 }
 
 impl<K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> DataMap<K, V> {
+    ///
+    /// Returns an iterator visiting each key-value pair whose serialized key starts with the serialized `prefix`,
+    /// in the byte order of the serialized keys, which reads the pairs from storage as it advances.
+    ///
+    pub(crate) fn iter_prefix<Q: Serialize + ?Sized>(&self, prefix: &Q) -> Result<impl Iterator<Item = (K, V)> + '_> {
+        self.iter_prefix_from(prefix, prefix)
+    }
+
+    ///
+    /// Returns an iterator visiting each key-value pair whose serialized key starts with the serialized `prefix`,
+    /// from the first serialized key at or after the serialized `start`, in the byte order of the serialized keys.
+    /// The iterator seeks to `start` in storage, so the pairs before it are never read.
+    ///
+    pub(crate) fn iter_prefix_from<Q: Serialize + ?Sized, S: Serialize + ?Sized>(
+        &self,
+        prefix: &Q,
+        start: &S,
+    ) -> Result<impl Iterator<Item = (K, V)> + '_> {
+        let mut raw_prefix = self.context.clone();
+        bincode::serialize_into(&mut raw_prefix, prefix)?;
+        let mut raw_start = self.context.clone();
+        bincode::serialize_into(&mut raw_start, start)?;
+
+        // The iterator is bounded by the context of the map, so stop at the first key beyond the prefix.
+        let mode = rocksdb::IteratorMode::From(&raw_start, rocksdb::Direction::Forward);
+        Ok(self.database.iterator(mode).map_while(move |entry| {
+            let (key, value) = entry
+                .map_err(|e| {
+                    error!("RocksDB iterator error: {e}");
+                })
+                .ok()?;
+            if !key.starts_with(&raw_prefix) {
+                return None;
+            }
+            Some((bincode::deserialize(&key[PREFIX_LEN..]).ok()?, bincode::deserialize(&value).ok()?))
+        }))
+    }
+
+    ///
+    /// Queues the insertion of the given key-value pair in the given write batch,
+    /// which may hold the writes of several maps, to be written atomically with `write_batch`.
+    ///
+    pub(crate) fn insert_in_batch(&self, batch: &mut WriteBatch, key: &K, value: &V) -> Result<()> {
+        batch.put(self.create_prefixed_key(key)?, bincode::serialize(value)?);
+        Ok(())
+    }
+
+    ///
+    /// Queues the removal of the given key in the given write batch.
+    ///
+    pub(crate) fn remove_in_batch(&self, batch: &mut WriteBatch, key: &K) -> Result<()> {
+        batch.delete(self.create_prefixed_key(key)?);
+        Ok(())
+    }
+
+    ///
+    /// Queues the removal of every key-value pair of the map in the given write batch.
+    ///
+    pub(crate) fn clear_in_batch(&self, batch: &mut WriteBatch) -> Result<()> {
+        for entry in self.database.prefix_iterator(&self.context) {
+            batch.delete(entry?.0);
+        }
+        Ok(())
+    }
+
+    ///
+    /// Performs the writes queued in the given write batch atomically.
+    ///
+    pub(crate) fn write_batch(&self, batch: WriteBatch) -> Result<()> {
+        self.database.rocksdb.write(batch)?;
+        Ok(())
+    }
+
     #[inline]
     fn create_prefixed_key<Q>(&self, key: &Q) -> Result<Vec<u8>>
     where
@@ -23,7 +23,7 @@ use iterator::*;
 #[cfg(test)]
 mod tests;
 
-use crate::MapID;
+use crate::{MapID, StorageLayout};
 
 use anyhow::{bail, Result};
 use core::{fmt::Debug, hash::Hash};
@@ -39,6 +39,9 @@ use std::{
 
 pub const PREFIX_LEN: usize = 4; // N::ID (u16) + DataID (u16)
 
+/// The RocksDB instance, which is opened at most once per process.
+static DB: OnceCell<RocksDB> = OnceCell::new();
+
 pub trait Database {
     /// Opens the database.
     fn open(network_id: u16, dev: Option<u16>) -> Result<Self>
@@ -62,6 +65,8 @@ pub struct RocksDB {
     network_id: u16,
     /// The optional development ID.
     dev: Option<u16>,
+    /// The storage layout the database was opened in.
+    layout: StorageLayout,
 }
 
 impl Deref for RocksDB {
@@ -72,14 +77,45 @@ impl Deref for RocksDB {
     }
 }
 
-impl Database for RocksDB {
-    /// Opens the database.
-    ///
-    /// In production mode, the database opens directory `~/.aleo/storage/ledger-{network}`.
-    /// In development mode, the database opens directory `/path/to/repo/.ledger-{network}-{id}`.
-    fn open(network_id: u16, dev: Option<u16>) -> Result<Self> {
-        static DB: OnceCell<RocksDB> = OnceCell::new();
+/// The statistics of the RocksDB instance, as reported by its properties.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DatabaseStats {
+    /// The estimated size of the live data, in bytes.
+    pub live_data_bytes: u64,
+    /// The total size of the SST files, in bytes.
+    pub sst_files_bytes: u64,
+    /// The size of the memtables, in bytes.
+    pub memtable_bytes: u64,
+    /// The estimated number of keys.
+    pub num_keys: u64,
+}
+
+impl RocksDB {
+    /// Returns the statistics of the database, if it is open.
+    pub fn stats() -> Option<DatabaseStats> {
+        let database = DB.get()?;
+        let property = |name: &str| database.property_int_value(name).ok().flatten().unwrap_or_default();
+        Some(DatabaseStats {
+            live_data_bytes: property("rocksdb.estimate-live-data-size"),
+            sst_files_bytes: property("rocksdb.total-sst-files-size"),
+            memtable_bytes: property("rocksdb.cur-size-all-mem-tables"),
+            num_keys: property("rocksdb.estimate-num-keys"),
+        })
+    }
+
+    /// Opens the database in the directories of the given storage layout. As the database is opened
+    /// at most once per process, this must be called before the ledger is loaded, which otherwise
+    /// opens the database in the default storage layout.
+    pub fn open_with_layout(network_id: u16, dev: Option<u16>, layout: &StorageLayout) -> Result<Self> {
+        let database = Self::initialize(network_id, dev, layout)?;
+        match database.layout == *layout {
+            true => Ok(database),
+            false => bail!("The database is already open in a different storage layout"),
+        }
+    }
 
+    /// Returns the database, opening it in the directories of the given storage layout if it is not open yet.
+    fn initialize(network_id: u16, dev: Option<u16>, layout: &StorageLayout) -> Result<Self> {
         // Retrieve the database.
         let database = DB
             .get_or_try_init(|| {
@@ -91,7 +127,12 @@ impl Database for RocksDB {
                 let prefix_extractor = rocksdb::SliceTransform::create_fixed_prefix(PREFIX_LEN);
                 options.set_prefix_extractor(prefix_extractor);
 
-                let primary = aleo_std::aleo_ledger_dir(network_id, dev);
+                // Retrieve the directories of the storage layout.
+                let primary = layout.ledger_dir(network_id, dev);
+                // Place the write-ahead log, if it is configured to live elsewhere.
+                if layout.wal_dir.is_some() {
+                    options.set_wal_dir(layout.wal_dir(network_id, dev));
+                }
                 let rocksdb = {
                     options.increase_parallelism(2);
                     options.create_if_missing(true);
@@ -99,7 +140,7 @@ impl Database for RocksDB {
                     Arc::new(rocksdb::DB::open(&options, primary)?)
                 };
 
-                Ok::<_, anyhow::Error>(RocksDB { rocksdb, network_id, dev })
+                Ok::<_, anyhow::Error>(RocksDB { rocksdb, network_id, dev, layout: layout.clone() })
             })?
             .clone();
 
@@ -109,6 +150,17 @@ impl Database for RocksDB {
             false => bail!("Mismatching network ID or development ID in the database"),
         }
     }
+}
+
+impl Database for RocksDB {
+    /// Opens the database.
+    ///
+    /// In production mode, the database opens directory `~/.aleo/storage/ledger-{network}`.
+    /// In development mode, the database opens directory `/path/to/repo/.ledger-{network}-{id}`.
+    /// If the database was opened with `open_with_layout`, it stays in the directories of that layout instead.
+    fn open(network_id: u16, dev: Option<u16>) -> Result<Self> {
+        Self::initialize(network_id, dev, &StorageLayout::default())
+    }
 
     /// Opens the map with the given `network_id`, `(optional) development ID`, and `map_id` from storage.
     fn open_map<K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned>(
@@ -131,7 +183,7 @@ impl Database for RocksDB {
 impl RocksDB {
     /// Opens the test database.
     #[cfg(test)]
-    fn open_testing(temp_dir: std::path::PathBuf, dev: Option<u16>) -> Result<Self> {
+    pub(crate) fn open_testing(temp_dir: std::path::PathBuf, dev: Option<u16>) -> Result<Self> {
         let database = {
             // Customize database options.
             let mut options = rocksdb::Options::default();
@@ -153,7 +205,7 @@ impl RocksDB {
                 Arc::new(rocksdb::DB::open(&options, primary)?)
             };
 
-            Ok::<_, anyhow::Error>(RocksDB { rocksdb, network_id: u16::MAX, dev })
+            Ok::<_, anyhow::Error>(RocksDB { rocksdb, network_id: u16::MAX, dev, layout: Default::default() })
         }?;
 
         // Ensure the database development ID match.
@@ -171,14 +223,26 @@ impl RocksDB {
         map_id: MapID,
     ) -> Result<DataMap<K, V>> {
         // Open the RocksDB test database.
-        let database = Self::open_testing(temp_dir, dev)?;
+        Ok(Self::open_testing(temp_dir, dev)?.test_map(map_id))
+    }
 
+    /// Opens the test map with the given `map_id` in the test database.
+    #[cfg(test)]
+    pub(crate) fn test_map<K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned>(
+        &self,
+        map_id: MapID,
+    ) -> DataMap<K, V> {
         // Combine contexts to create a new scope.
-        let mut context = database.network_id.to_le_bytes().to_vec();
+        let mut context = self.network_id.to_le_bytes().to_vec();
         context.extend_from_slice(&(u16::from(map_id)).to_le_bytes());
 
         // Return the DataMap.
-        Ok(DataMap { database, context, batch_in_progress: Default::default(), atomic_batch: Default::default() })
+        DataMap {
+            database: self.clone(),
+            context,
+            batch_in_progress: Default::default(),
+            atomic_batch: Default::default(),
+        }
     }
 }
 
@@ -29,6 +29,8 @@ pub struct Stats {
     bytes_received: AtomicU64,
     /// The number of failures.
     failures: AtomicU64,
+    /// The number of inbound messages that waited for room in the queue of their connection.
+    throttled: AtomicU64,
 }
 
 impl Stats {
@@ -53,6 +55,11 @@ impl Stats {
         self.failures.load(Relaxed)
     }
 
+    /// Returns the number of inbound messages that waited for room in the queue of their connection.
+    pub fn throttled(&self) -> u64 {
+        self.throttled.load(Relaxed)
+    }
+
     /// Registers a sent message of the provided `size` in bytes.
     pub fn register_sent_message(&self, size: usize) {
         self.msgs_sent.fetch_add(1, Relaxed);
@@ -69,4 +76,9 @@ impl Stats {
     pub fn register_failure(&self) {
         self.failures.fetch_add(1, Relaxed);
     }
+
+    /// Registers an inbound message that waited for room in the queue of its connection.
+    pub fn register_throttled(&self) {
+        self.throttled.fetch_add(1, Relaxed);
+    }
 }
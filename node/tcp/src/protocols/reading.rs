@@ -21,7 +21,10 @@ use bytes::BytesMut;
 use futures_util::StreamExt;
 use tokio::{
     io::AsyncRead,
-    sync::{mpsc, oneshot},
+    sync::{
+        mpsc::{self, error::TrySendError},
+        oneshot,
+    },
 };
 use tokio_util::codec::{Decoder, FramedRead};
 use tracing::*;
@@ -40,7 +43,9 @@ use crate::{
 ///
 /// Each inbound message is isolated by the user-supplied [`Reading::Codec`], creating a [`Reading::Message`],
 /// which is immediately queued (with a [`Reading::MESSAGE_QUEUE_DEPTH`] limit) to be processed by
-/// [`Reading::process_message`]. The configured fatal IO errors result in an immediate disconnect
+/// [`Reading::process_message`]. Once the queue is full, the messages that [`Reading::is_droppable`] are dropped,
+/// and the reading of the other messages waits for room in the queue, which slows the peer down.
+/// The configured fatal IO errors result in an immediate disconnect
 /// (in order to e.g. avoid accidentally reading "borked" messages).
 #[async_trait]
 pub trait Reading: P2P
@@ -96,6 +101,14 @@ where
     /// The `side` param indicates the connection side **from the node's perspective**.
     fn codec(&self, addr: SocketAddr, side: ConnectionSide) -> Self::Codec;
 
+    /// Returns `true` if the inbound message can be dropped when the queue of its connection is full.
+    /// Otherwise, the reading from the connection waits until the queue has room for the message.
+    ///
+    /// By default, every message can be dropped.
+    fn is_droppable(&self, _message: &Self::Message) -> bool {
+        true
+    }
+
     /// Processes an inbound message. Can be used to update state, send replies etc.
     async fn process_message(&self, source: SocketAddr, message: Self::Message) -> io::Result<()>;
 }
@@ -157,6 +170,7 @@ impl<R: Reading> ReadingInternal for R {
         let (tx_reader, rx_reader) = oneshot::channel::<()>();
 
         // the task for reading messages from a stream
+        let self_clone = self.clone();
         let node = self.tcp().clone();
         let reader_task = tokio::spawn(async move {
             trace!(parent: node.span(), "spawned a task for reading messages from {}", addr);
@@ -170,9 +184,19 @@ impl<R: Reading> ReadingInternal for R {
                 match bytes {
                     Ok(msg) => {
                         // send the message for further processing
-                        if let Err(e) = inbound_message_sender.try_send(msg) {
-                            error!(parent: node.span(), "can't process a message from {}: {}", addr, e);
-                            node.stats().register_failure();
+                        match inbound_message_sender.try_send(msg) {
+                            Ok(()) => (),
+                            // wait for room in the queue, which stops reading from the peer in the meantime
+                            Err(TrySendError::Full(msg)) if !self_clone.is_droppable(&msg) => {
+                                node.stats().register_throttled();
+                                if inbound_message_sender.send(msg).await.is_err() {
+                                    break;
+                                }
+                            }
+                            Err(e) => {
+                                error!(parent: node.span(), "can't process a message from {}: {}", addr, e);
+                                node.stats().register_failure();
+                            }
                         }
                     }
                     Err(e) => {
@@ -17,18 +17,28 @@
 use crate::common::test_peer::sample_genesis_block;
 use snarkos_account::Account;
 use snarkos_node::{Beacon, Client, Prover, Validator};
-use snarkvm::prelude::{ConsensusMemory, Testnet3 as CurrentNetwork};
+use snarkos_node_store::StorageLayout;
+use snarkvm::prelude::{ConsensusMemory, Network, Testnet3 as CurrentNetwork};
 
-use std::str::FromStr;
+use std::{path::PathBuf, str::FromStr};
+
+/// Returns the state directory of the default storage layout.
+fn state_dir() -> PathBuf {
+    StorageLayout::default().state_dir(CurrentNetwork::ID, None)
+}
 
 pub async fn beacon() -> Beacon<CurrentNetwork, ConsensusMemory<CurrentNetwork>> {
     Beacon::new(
         "127.0.0.1:0".parse().unwrap(),
         None,
+        Default::default(),
         Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
         &[],
         sample_genesis_block(), // Should load the current network's genesis block.
         None,                   // No CDN.
+        None,                   // No assume-valid checkpoint.
+        Default::default(),     // No compression.
+        state_dir(),
         None,
     )
     .await
@@ -41,6 +51,7 @@ pub async fn client() -> Client<CurrentNetwork, ConsensusMemory<CurrentNetwork>>
         Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
         &[],
         sample_genesis_block(),
+        state_dir(),
         None,
     )
     .await
@@ -53,6 +64,7 @@ pub async fn prover() -> Prover<CurrentNetwork, ConsensusMemory<CurrentNetwork>>
         Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
         &[],
         sample_genesis_block(),
+        state_dir(),
         None,
     )
     .await
@@ -63,10 +75,14 @@ pub async fn validator() -> Validator<CurrentNetwork, ConsensusMemory<CurrentNet
     Validator::new(
         "127.0.0.1:0".parse().unwrap(),
         None,
+        Default::default(),
         Account::<CurrentNetwork>::from_str("APrivateKey1zkp2oVPTci9kKcUprnbzMwq95Di1MQERpYBhEeqvkrDirK1").unwrap(),
         &[],
         sample_genesis_block(), // Should load the current network's genesis block.
         None,                   // No CDN.
+        None,                   // No assume-valid checkpoint.
+        Default::default(),     // No compression.
+        state_dir(),
         None,
     )
     .await
@@ -133,7 +133,13 @@ impl Handshake for TestPeer {
         match node_side {
             ConnectionSide::Initiator => {
                 // Send a challenge request to the peer.
-                let our_request = ChallengeRequest::new(local_ip.port(), self.node_type(), self.address(), rng.gen());
+                let our_request = ChallengeRequest::new(
+                    local_ip.port(),
+                    self.node_type(),
+                    self.address(),
+                    rng.gen(),
+                    Default::default(),
+                );
                 framed.send(Message::ChallengeRequest(our_request)).await?;
 
                 // Receive the peer's challenge bundle.
@@ -144,7 +150,11 @@ impl Handshake for TestPeer {
                 let signature = self.account().sign_bytes(&peer_request.nonce.to_le_bytes(), rng).unwrap();
 
                 // Send the challenge response.
-                let our_response = ChallengeResponse { genesis_header, signature: Data::Object(signature) };
+                let our_response = ChallengeResponse {
+                    genesis_header,
+                    observed_ip: peer_addr.ip(),
+                    signature: Data::Object(signature),
+                };
                 framed.send(Message::ChallengeResponse(our_response)).await?;
             }
             ConnectionSide::Responder => {
@@ -155,9 +165,19 @@ impl Handshake for TestPeer {
                 let signature = self.account().sign_bytes(&peer_request.nonce.to_le_bytes(), rng).unwrap();
 
                 // Send our challenge bundle.
-                let our_response = ChallengeResponse { genesis_header, signature: Data::Object(signature) };
+                let our_response = ChallengeResponse {
+                    genesis_header,
+                    observed_ip: peer_addr.ip(),
+                    signature: Data::Object(signature),
+                };
                 framed.send(Message::ChallengeResponse(our_response)).await?;
-                let our_request = ChallengeRequest::new(local_ip.port(), self.node_type(), self.address(), rng.gen());
+                let our_request = ChallengeRequest::new(
+                    local_ip.port(),
+                    self.node_type(),
+                    self.address(),
+                    rng.gen(),
+                    Default::default(),
+                );
                 framed.send(Message::ChallengeRequest(our_request)).await?;
 
                 // Listen for the challenge response.